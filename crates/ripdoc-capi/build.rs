@@ -0,0 +1,36 @@
+//! Generates the C header for the functions exported from `src/lib.rs` into `OUT_DIR`.
+//!
+//! The committed copy, `include/ripdoc.h`, is only replaced when `RIPDOC_UPDATE_HEADER` is set,
+//! so ordinary builds never write into the source tree.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn main() {
+	println!("cargo:rerun-if-changed=src/lib.rs");
+	println!("cargo:rerun-if-changed=cbindgen.toml");
+	println!("cargo:rerun-if-env-changed=RIPDOC_UPDATE_HEADER");
+
+	let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+	let header = PathBuf::from(env::var("OUT_DIR").unwrap()).join("ripdoc.h");
+	match cbindgen::generate(&crate_dir) {
+		Ok(bindings) => {
+			bindings.write_to_file(&header);
+		}
+		Err(error) => {
+			println!("cargo:warning=could not generate the C header: {error}");
+			return;
+		}
+	}
+
+	if env::var_os("RIPDOC_UPDATE_HEADER").is_some() {
+		let committed = crate_dir.join("include/ripdoc.h");
+		if let Err(error) = fs::copy(&header, &committed) {
+			println!(
+				"cargo:warning=could not update {}: {error}",
+				committed.display()
+			);
+		}
+	}
+}
@@ -0,0 +1,49 @@
+#ifndef RIPDOC_H
+#define RIPDOC_H
+
+/* Generated from crates/ripdoc-capi/src/lib.rs by cbindgen; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Describe the last failure on the calling thread, or return null when nothing has failed.
+//
+// The string belongs to ripdoc: it must not be freed, and stays valid until the next call on
+// this thread that fails.
+const char *ripdoc_last_error(void);
+
+// Render `target` as a skeleton, in the format the options select.
+//
+// Returns null on failure. The returned string must be released with [`ripdoc_string_free`].
+//
+// # Safety
+//
+// `target` must be a valid NUL-terminated string. `options_json` must be null or a valid
+// NUL-terminated string.
+char *ripdoc_render(const char *target, const char *options_json);
+
+// Search `target` for `query`, returning a JSON object with the matched items under `results`
+// and the skeleton of those items under `rendered`.
+//
+// Each result has its `crate`, `kind`, `path`, `definition_path`, and `source` location, which
+// is null when unknown. Search settings come from the `search` key of the options.
+//
+// Returns null on failure. The returned string must be released with [`ripdoc_string_free`].
+//
+// # Safety
+//
+// `target` and `query` must be valid NUL-terminated strings. `options_json` must be null or a
+// valid NUL-terminated string.
+char *ripdoc_search(const char *target, const char *query, const char *options_json);
+
+// Release a string returned by [`ripdoc_render`] or [`ripdoc_search`]. Null is ignored.
+//
+// # Safety
+//
+// `string` must be null or a string returned by ripdoc that has not been released yet.
+void ripdoc_string_free(char *string);
+
+#endif  /* RIPDOC_H */
@@ -0,0 +1,232 @@
+//! C interface to ripdoc, for embedding it in programs not written in Rust.
+//!
+//! Strings passed in are NUL-terminated UTF-8. Options are a JSON object taking the keys of a
+//! `.ripdoc.toml` file, such as `{"private": true, "features": ["serde"], "search": {"fuzzy":
+//! true}}`, laid over the config files found from the target as on the command line. A null or
+//! empty options string sets nothing.
+//!
+//! Strings returned are owned by the caller and released with [`ripdoc_string_free`]. Every
+//! function returns null on failure, after which [`ripdoc_last_error`] describes the failure.
+//! The header declaring these functions is kept in `include/ripdoc.h`; building with
+//! `RIPDOC_UPDATE_HEADER=1` regenerates it.
+
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+
+use ripdoc_core::error::RipdocError;
+use ripdoc_core::search::SearchResult;
+use ripdoc_core::troubleshoot::remedy;
+use ripdoc_core::{Config, MatchMode, Ripdoc, SearchConfig, SearchOptions, SourceLocation};
+use serde_json::{Value, json};
+
+thread_local! {
+	/// Description of the last failure on this thread, kept until the next failure.
+	static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Render `target` as a skeleton, in the format the options select.
+///
+/// Returns null on failure. The returned string must be released with [`ripdoc_string_free`].
+///
+/// # Safety
+///
+/// `target` must be a valid NUL-terminated string. `options_json` must be null or a valid
+/// NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ripdoc_render(
+	target: *const c_char,
+	options_json: *const c_char,
+) -> *mut c_char {
+	respond(|| {
+		// SAFETY: the caller guarantees both pointers are null or valid strings.
+		let (target, options) =
+			unsafe { (read_str(target, "target")?, read_options(options_json)?) };
+		let config = layered(target, options)?;
+		ripdoc(&config)
+			.render(
+				target,
+				config.no_default_features.unwrap_or(false),
+				config.all_features.unwrap_or(false),
+				config.features.as_deref().unwrap_or_default(),
+				config.private.unwrap_or(false),
+			)
+			.map_err(|err| describe(&err))
+	})
+}
+
+/// Search `target` for `query`, returning a JSON object with the matched items under `results`
+/// and the skeleton of those items under `rendered`.
+///
+/// Each result has its `crate`, `kind`, `path`, `definition_path`, and `source` location, which
+/// is null when unknown. Search settings come from the `search` key of the options.
+///
+/// Returns null on failure. The returned string must be released with [`ripdoc_string_free`].
+///
+/// # Safety
+///
+/// `target` and `query` must be valid NUL-terminated strings. `options_json` must be null or a
+/// valid NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ripdoc_search(
+	target: *const c_char,
+	query: *const c_char,
+	options_json: *const c_char,
+) -> *mut c_char {
+	respond(|| {
+		// SAFETY: the caller guarantees the pointers are null or valid strings.
+		let (target, query, options) = unsafe {
+			(
+				read_str(target, "target")?,
+				read_str(query, "query")?,
+				read_options(options_json)?,
+			)
+		};
+		let config = layered(target, options)?;
+		let mut options = search_options(&config.search, query);
+		options.include_private = config.private.unwrap_or(false);
+		let response = ripdoc(&config)
+			.search(
+				target,
+				config.no_default_features.unwrap_or(false),
+				config.all_features.unwrap_or(false),
+				config.features.as_deref().unwrap_or_default(),
+				&options,
+			)
+			.map_err(|err| describe(&err))?;
+		let results: Vec<Value> = response.results.iter().map(search_result_json).collect();
+		Ok(json!({ "results": results, "rendered": response.rendered }).to_string())
+	})
+}
+
+/// Describe the last failure on the calling thread, or return null when nothing has failed.
+///
+/// The string belongs to ripdoc: it must not be freed, and stays valid until the next call on
+/// this thread that fails.
+#[unsafe(no_mangle)]
+pub extern "C" fn ripdoc_last_error() -> *const c_char {
+	LAST_ERROR.with_borrow(|error| error.as_ref().map_or(ptr::null(), |error| error.as_ptr()))
+}
+
+/// Release a string returned by [`ripdoc_render`] or [`ripdoc_search`]. Null is ignored.
+///
+/// # Safety
+///
+/// `string` must be null or a string returned by ripdoc that has not been released yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn ripdoc_string_free(string: *mut c_char) {
+	if !string.is_null() {
+		// SAFETY: the caller guarantees the string came from `CString::into_raw` below.
+		drop(unsafe { CString::from_raw(string) });
+	}
+}
+
+/// Run `call`, returning its output as a C string, or null after recording why it failed.
+fn respond(call: impl FnOnce() -> Result<String, String>) -> *mut c_char {
+	// Unwinding into C is undefined behaviour, so a panic is reported like any other failure
+	let outcome = panic::catch_unwind(AssertUnwindSafe(call)).unwrap_or_else(|panic| {
+		let message = panic
+			.downcast_ref::<&str>()
+			.map(|message| message.to_string())
+			.or_else(|| panic.downcast_ref::<String>().cloned())
+			.unwrap_or_else(|| "unknown panic".into());
+		Err(format!("ripdoc panicked: {message}"))
+	});
+	match outcome.and_then(|output| {
+		CString::new(output).map_err(|_| "output contains a NUL byte".to_string())
+	}) {
+		Ok(output) => output.into_raw(),
+		Err(message) => {
+			set_last_error(message);
+			ptr::null_mut()
+		}
+	}
+}
+
+/// Record `message` as the last failure on this thread.
+fn set_last_error(message: String) {
+	// Messages come from Rust strings, so the only NULs to drop are ones embedded in them
+	let message = CString::new(message.replace('\0', "")).unwrap_or_default();
+	LAST_ERROR.set(Some(message));
+}
+
+/// Describe `err` along with what to do about it, when that is known.
+fn describe(err: &RipdocError) -> String {
+	match remedy(err) {
+		Some(remedy) => format!("{err}\n{remedy}"),
+		None => err.to_string(),
+	}
+}
+
+/// Borrow the string argument `name` from C.
+///
+/// # Safety
+///
+/// `value` must be null or a valid NUL-terminated string that outlives the returned borrow.
+unsafe fn read_str<'a>(value: *const c_char, name: &str) -> Result<&'a str, String> {
+	if value.is_null() {
+		return Err(format!("{name} is null"));
+	}
+	// SAFETY: checked for null above; the caller guarantees the rest.
+	let value = unsafe { CStr::from_ptr(value) };
+	value
+		.to_str()
+		.map_err(|err| format!("{name} is not valid UTF-8: {err}"))
+}
+
+/// Parse the options argument, where null and blank strings set nothing.
+///
+/// # Safety
+///
+/// `options_json` must be null or a valid NUL-terminated string.
+unsafe fn read_options(options_json: *const c_char) -> Result<Config, String> {
+	if options_json.is_null() {
+		return Ok(Config::default());
+	}
+	// SAFETY: checked for null above; the caller guarantees the rest.
+	let options = unsafe { read_str(options_json, "options") }?;
+	if options.trim().is_empty() {
+		return Ok(Config::default());
+	}
+	serde_json::from_str(options).map_err(|err| format!("invalid options: {err}"))
+}
+
+/// Options for `target` laid over the config files found from it.
+fn layered(target: &str, options: Config) -> Result<Config, String> {
+	let files = Config::load(target).map_err(|err| describe(&err))?;
+	Ok(options.or(files))
+}
+
+/// Ripdoc instance for `config`, with progress output silenced since there is no terminal.
+fn ripdoc(config: &Config) -> Ripdoc {
+	Ripdoc::new().with_silent(true).with_config(config)
+}
+
+/// Search options for `query` from the `search` settings.
+fn search_options(search: &SearchConfig, query: &str) -> SearchOptions {
+	let mut options = SearchOptions::new(query);
+	options.case_sensitive = search.case_sensitive.unwrap_or(false);
+	if search.fuzzy.unwrap_or(false) {
+		options.match_mode = MatchMode::Fuzzy;
+	}
+	options.expand_containers = !search.direct_match_only.unwrap_or(false);
+	options.domains = search.domains.unwrap_or_default();
+	options
+}
+
+/// JSON form of a search result, matching the one `ripdoc serve` answers with.
+fn search_result_json(result: &SearchResult) -> Value {
+	json!({
+		"crate": result.crate_name,
+		"kind": result.kind.label(),
+		"path": result.path_string,
+		"definition_path": result.definition_path,
+		"source": result.source.as_ref().map(source_json),
+	})
+}
+
+/// JSON form of a source location.
+fn source_json(location: &SourceLocation) -> Value {
+	json!({ "path": location.path, "line": location.line, "column": location.column })
+}
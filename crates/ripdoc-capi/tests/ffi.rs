@@ -0,0 +1,133 @@
+//! Tests calling the exported C functions as a C program would.
+#![allow(clippy::tests_outside_test_module)]
+
+use std::ffi::{CStr, CString, c_char};
+use std::fs;
+use std::ptr;
+
+use ripdoc::{ripdoc_last_error, ripdoc_render, ripdoc_search, ripdoc_string_free};
+use serde_json::Value;
+use tempfile::TempDir;
+
+/// Options every test passes, so nothing is downloaded and the output is plain Rust.
+const OPTIONS: &str = r#"{"offline": true, "format": "rust", "cache": false}"#;
+
+/// Write a minimal library crate and return its directory.
+fn create_crate() -> TempDir {
+	let temp_dir = TempDir::new().unwrap();
+	fs::create_dir(temp_dir.path().join("src")).unwrap();
+	fs::write(
+		temp_dir.path().join("src/lib.rs"),
+		"/// Says hello.\npub fn greet() {}\n\n/// Left alone.\npub struct Quiet;\n",
+	)
+	.unwrap();
+	fs::write(
+		temp_dir.path().join("Cargo.toml"),
+		"[package]\nname = \"dummy_crate\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+	)
+	.unwrap();
+	temp_dir
+}
+
+/// Take ownership of a string returned by ripdoc, or panic with the last error when it is null.
+fn take(output: *mut c_char) -> String {
+	assert!(!output.is_null(), "call failed: {}", last_error());
+	// SAFETY: non-null outputs are valid strings owned by the caller.
+	let string = unsafe { CStr::from_ptr(output) }
+		.to_str()
+		.unwrap()
+		.to_owned();
+	// SAFETY: the string came from ripdoc and is released once.
+	unsafe { ripdoc_string_free(output) };
+	string
+}
+
+/// The last error on this thread.
+fn last_error() -> String {
+	let error = ripdoc_last_error();
+	assert!(!error.is_null(), "no error was recorded");
+	// SAFETY: non-null errors are valid strings owned by ripdoc.
+	unsafe { CStr::from_ptr(error) }
+		.to_str()
+		.unwrap()
+		.to_owned()
+}
+
+#[test]
+fn render_returns_the_skeleton() {
+	let temp_dir = create_crate();
+	let target = CString::new(temp_dir.path().to_str().unwrap()).unwrap();
+	let options = CString::new(OPTIONS).unwrap();
+
+	// SAFETY: both arguments are valid strings.
+	let rendered = take(unsafe { ripdoc_render(target.as_ptr(), options.as_ptr()) });
+	assert!(rendered.contains("pub fn greet()"), "{rendered}");
+	assert!(rendered.contains("pub struct Quiet;"), "{rendered}");
+}
+
+#[test]
+fn search_returns_matches_as_json() {
+	let temp_dir = create_crate();
+	let target = CString::new(temp_dir.path().to_str().unwrap()).unwrap();
+	let query = CString::new("greet").unwrap();
+	let options = CString::new(OPTIONS).unwrap();
+
+	// SAFETY: all arguments are valid strings.
+	let response =
+		take(unsafe { ripdoc_search(target.as_ptr(), query.as_ptr(), options.as_ptr()) });
+	let response: Value = serde_json::from_str(&response).unwrap();
+	let results = response["results"].as_array().unwrap();
+	assert_eq!(results.len(), 1, "{response}");
+	assert_eq!(results[0]["path"], "dummy_crate::greet");
+	assert_eq!(results[0]["kind"], "function");
+	let rendered = response["rendered"].as_str().unwrap();
+	assert!(rendered.contains("pub fn greet()"), "{rendered}");
+	assert!(!rendered.contains("Quiet"), "{rendered}");
+}
+
+#[test]
+fn failures_return_null_and_record_why() {
+	let options = CString::new(OPTIONS).unwrap();
+
+	// SAFETY: a null target is reported, not dereferenced.
+	let output = unsafe { ripdoc_render(ptr::null(), options.as_ptr()) };
+	assert!(output.is_null());
+	assert_eq!(last_error(), "target is null");
+
+	let temp_dir = create_crate();
+	let target = CString::new(temp_dir.path().to_str().unwrap()).unwrap();
+	let invalid = CString::new(r#"{"private": "yes"}"#).unwrap();
+	// SAFETY: both arguments are valid strings.
+	let output = unsafe { ripdoc_render(target.as_ptr(), invalid.as_ptr()) };
+	assert!(output.is_null());
+	assert!(
+		last_error().starts_with("invalid options:"),
+		"{}",
+		last_error()
+	);
+
+	let missing = CString::new(temp_dir.path().join("missing").to_str().unwrap()).unwrap();
+	let query = CString::new("greet").unwrap();
+	// SAFETY: all arguments are valid strings.
+	let output = unsafe { ripdoc_search(missing.as_ptr(), query.as_ptr(), options.as_ptr()) };
+	assert!(output.is_null());
+	assert!(!last_error().is_empty());
+
+	// SAFETY: null is ignored.
+	unsafe { ripdoc_string_free(ptr::null_mut()) };
+}
+
+#[test]
+fn null_options_set_nothing() {
+	let temp_dir = create_crate();
+	let target = CString::new(temp_dir.path().to_str().unwrap()).unwrap();
+	fs::write(
+		temp_dir.path().join(".ripdoc.toml"),
+		"offline = true\nformat = \"rust\"\ncache = false\n",
+	)
+	.unwrap();
+
+	// SAFETY: the target is a valid string and the options may be null.
+	let rendered = take(unsafe { ripdoc_render(target.as_ptr(), ptr::null()) });
+	assert!(rendered.contains("pub fn greet()"), "{rendered}");
+}
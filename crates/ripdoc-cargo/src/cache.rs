@@ -11,6 +11,7 @@ use std::{env, fs};
 use rustdoc_types::Crate;
 
 use crate::error::{Result, RipdocError};
+use crate::options::BuildOptions;
 
 /// Configuration for the documentation cache.
 #[derive(Debug, Clone)]
@@ -52,7 +53,7 @@ impl CacheConfig {
 	}
 
 	/// Get the cache directory, using the default if not specified.
-	fn get_cache_dir(&self) -> Result<PathBuf> {
+	pub(crate) fn get_cache_dir(&self) -> Result<PathBuf> {
 		if let Some(ref dir) = self.cache_dir {
 			return Ok(dir.clone());
 		}
@@ -87,30 +88,34 @@ pub struct CacheKey {
 	pub private_items: bool,
 	/// Rust toolchain version (to handle rustdoc JSON format changes).
 	pub toolchain_version: Option<String>,
+	/// Target triple documented instead of the host, if any.
+	pub target_triple: Option<String>,
 }
 
 impl CacheKey {
 	/// Generate a cache key from build parameters.
+	///
+	/// The toolchain is identified by `toolchain_version` rather than by the name in `options`,
+	/// since a name such as `nightly` changes meaning on update.
 	pub fn new(
 		manifest_path: PathBuf,
 		package_info: String,
-		no_default_features: bool,
-		all_features: bool,
-		mut features: Vec<String>,
-		private_items: bool,
+		options: &BuildOptions,
 		toolchain_version: Option<String>,
 	) -> Self {
 		// Sort features for consistent cache keys
+		let mut features = options.features.to_vec();
 		features.sort();
 
 		Self {
 			package_info,
 			manifest_path,
-			no_default_features,
-			all_features,
+			no_default_features: options.no_default_features,
+			all_features: options.all_features,
 			features,
-			private_items,
+			private_items: options.private_items,
 			toolchain_version,
+			target_triple: options.target_triple.map(str::to_string),
 		}
 	}
 
@@ -135,6 +140,9 @@ impl CacheKey {
 		// Hash toolchain version
 		self.toolchain_version.hash(&mut hasher);
 
+		// Hash target triple
+		self.target_triple.hash(&mut hasher);
+
 		format!("{:x}", hasher.finish())
 	}
 
@@ -145,6 +153,14 @@ impl CacheKey {
 	}
 }
 
+/// Check whether cached documentation exists for the given parameters without loading it.
+pub fn is_cached(config: &CacheConfig, key: &CacheKey) -> bool {
+	config.enabled
+		&& config
+			.get_cache_dir()
+			.is_ok_and(|cache_dir| key.cache_path(&cache_dir).exists())
+}
+
 /// Try to load cached documentation for the given parameters.
 pub fn load_cached(config: &CacheConfig, key: &CacheKey) -> Result<Option<Crate>> {
 	if !config.enabled {
@@ -228,12 +244,15 @@ pub fn save_cached(config: &CacheConfig, key: &CacheKey, crate_data: &Crate) ->
 }
 
 /// Get the current Rust toolchain version for cache invalidation.
-pub fn get_toolchain_version() -> Option<String> {
+///
+/// `toolchain` names the rustup toolchain used for rustdoc JSON generation, defaulting to
+/// `nightly`.
+pub fn get_toolchain_version(toolchain: Option<&str>) -> Option<String> {
 	use std::process::Command;
 
 	let output = if crate::is_rustup_available() {
 		Command::new("rustup")
-			.args(["run", "nightly", "rustc", "--version"])
+			.args(["run", toolchain.unwrap_or("nightly"), "rustc", "--version"])
 			.output()
 			.ok()?
 	} else {
@@ -250,6 +269,9 @@ pub fn get_toolchain_version() -> Option<String> {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::env::CargoEnv;
+	use crate::interrupt::Interrupt;
+	use crate::verbosity::Verbosity;
 
 	#[test]
 	fn test_cache_key_hash_consistency() {
@@ -257,20 +279,34 @@ mod tests {
 		let key1 = CacheKey::new(
 			manifest.clone(),
 			"test-crate-0.1.0".to_string(),
-			false,
-			false,
-			vec!["feature1".to_string(), "feature2".to_string()],
-			false,
+			&BuildOptions {
+				no_default_features: false,
+				all_features: false,
+				features: &["feature1".to_string(), "feature2".to_string()],
+				private_items: false,
+				toolchain: None,
+				target_triple: None,
+				verbosity: Verbosity::Normal,
+				interrupt: &Interrupt::default(),
+				env: &CargoEnv::default(),
+			},
 			Some("rustc 1.70.0".to_string()),
 		);
 
 		let key2 = CacheKey::new(
 			manifest,
 			"test-crate-0.1.0".to_string(),
-			false,
-			false,
-			vec!["feature2".to_string(), "feature1".to_string()], // Different order
-			false,
+			&BuildOptions {
+				no_default_features: false,
+				all_features: false,
+				features: &["feature2".to_string(), "feature1".to_string()], // Different order
+				private_items: false,
+				toolchain: None,
+				target_triple: None,
+				verbosity: Verbosity::Normal,
+				interrupt: &Interrupt::default(),
+				env: &CargoEnv::default(),
+			},
 			Some("rustc 1.70.0".to_string()),
 		);
 
@@ -284,23 +320,61 @@ mod tests {
 		let key1 = CacheKey::new(
 			manifest.clone(),
 			"test-crate-0.1.0".to_string(),
-			false,
-			false,
-			vec![],
-			false,
+			&BuildOptions {
+				no_default_features: false,
+				all_features: false,
+				features: &[],
+				private_items: false,
+				toolchain: None,
+				target_triple: None,
+				verbosity: Verbosity::Normal,
+				interrupt: &Interrupt::default(),
+				env: &CargoEnv::default(),
+			},
 			Some("rustc 1.70.0".to_string()),
 		);
 
 		let key2 = CacheKey::new(
 			manifest,
 			"test-crate-0.1.0".to_string(),
-			true, // Different flag
-			false,
-			vec![],
-			false,
+			&BuildOptions {
+				no_default_features: true, // Different flag
+				all_features: false,
+				features: &[],
+				private_items: false,
+				toolchain: None,
+				target_triple: None,
+				verbosity: Verbosity::Normal,
+				interrupt: &Interrupt::default(),
+				env: &CargoEnv::default(),
+			},
 			Some("rustc 1.70.0".to_string()),
 		);
 
 		assert_ne!(key1.hash(), key2.hash());
 	}
+
+	#[test]
+	fn test_cache_key_hash_includes_target_triple() {
+		let key = |target_triple: Option<&str>| {
+			CacheKey::new(
+				PathBuf::from("/path/to/Cargo.toml"),
+				"test-crate-0.1.0".to_string(),
+				&BuildOptions {
+					no_default_features: false,
+					all_features: false,
+					features: &[],
+					private_items: false,
+					toolchain: None,
+					target_triple,
+					verbosity: Verbosity::Normal,
+					interrupt: &Interrupt::default(),
+					env: &CargoEnv::default(),
+				},
+				Some("rustc 1.70.0".to_string()),
+			)
+		};
+
+		assert_ne!(key(None).hash(), key(Some("x86_64-pc-windows-msvc")).hash());
+	}
 }
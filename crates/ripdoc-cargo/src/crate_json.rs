@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use rustdoc_types::{Crate, FORMAT_VERSION};
+use serde::Deserialize;
+
+use crate::error::{Result, RipdocError};
+
+/// Parsed rustdoc JSON along with the file it was read from, so other tools can reuse it.
+#[derive(Debug, Clone)]
+pub struct CrateJson {
+	/// The parsed crate.
+	pub crate_data: Crate,
+	/// Rustdoc JSON file the crate was read from. For a crate served from ripdoc's cache, which
+	/// holds a binary encoding rather than JSON, this is where rustdoc wrote the JSON when it
+	/// is still there, and `None` otherwise.
+	pub path: Option<PathBuf>,
+}
+
+/// Minimal view of a rustdoc JSON document, read before committing to the full schema.
+#[derive(Deserialize)]
+struct FormatProbe {
+	/// Format version written by rustdoc; absent in very old or malformed output.
+	format_version: Option<u32>,
+}
+
+/// Deserialize rustdoc JSON, checking its format version against the one `rustdoc_types`
+/// understands first so mismatches surface as [`RipdocError::FormatVersionMismatch`] instead of
+/// an opaque serde error about missing fields.
+pub fn parse_crate(content: &str) -> Result<Crate> {
+	if let Ok(FormatProbe {
+		format_version: Some(found),
+	}) = serde_json::from_str(content)
+		&& found != FORMAT_VERSION
+	{
+		return Err(RipdocError::FormatVersionMismatch {
+			found,
+			supported: FORMAT_VERSION,
+		});
+	}
+
+	serde_json::from_str(content)
+		.map_err(|e| RipdocError::Generate(format!("Failed to parse rustdoc JSON: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bumped_format_version_is_reported() {
+		let content = format!(r#"{{"format_version": {}, "root": 0}}"#, FORMAT_VERSION + 1);
+		match parse_crate(&content) {
+			Err(RipdocError::FormatVersionMismatch { found, supported }) => {
+				assert_eq!(found, FORMAT_VERSION + 1);
+				assert_eq!(supported, FORMAT_VERSION);
+			}
+			other => panic!("expected a format version mismatch, got {other:?}"),
+		}
+	}
+
+	#[test]
+	fn matching_format_version_reports_schema_errors() {
+		let content = format!(r#"{{"format_version": {FORMAT_VERSION}}}"#);
+		let err = parse_crate(&content).unwrap_err();
+		assert!(
+			matches!(err, RipdocError::Generate(ref message) if message.contains("Failed to parse")),
+			"{err}"
+		);
+	}
+}
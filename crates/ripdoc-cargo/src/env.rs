@@ -0,0 +1,144 @@
+use std::env;
+#[cfg(test)]
+use std::ffi::OsStr;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::cache::CacheConfig;
+
+/// Variable naming the directory cargo builds into.
+const TARGET_DIR: &str = "CARGO_TARGET_DIR";
+
+/// Variable naming a program cargo runs rustc through, such as `sccache`.
+const RUSTC_WRAPPER: &str = "RUSTC_WRAPPER";
+
+/// Environment of the cargo commands behind fetches and rustdoc JSON builds.
+///
+/// By default the commands inherit ripdoc's environment, except that builds go to a scratch
+/// target directory owned by ripdoc, so they do not contend with or trample the project's own
+/// builds, and `RUSTC_WRAPPER` is cleared, since wrappers such as `sccache` often break rustdoc
+/// JSON builds. [`Self::overrides`] are applied last and take precedence over both.
+#[derive(Debug, Clone, Default)]
+pub struct CargoEnv {
+	/// Variables to set, or to remove when the value is `None`, in the order given.
+	pub overrides: Vec<(String, Option<String>)>,
+	/// Whether builds use the project's target directory rather than ripdoc's scratch one.
+	pub project_target_dir: bool,
+}
+
+impl CargoEnv {
+	/// Variables to set or remove for a command, defaults first and overrides after them.
+	pub fn vars(&self) -> Vec<(String, Option<String>)> {
+		let mut vars = vec![(RUSTC_WRAPPER.to_string(), None)];
+		if !self.project_target_dir {
+			let scratch = scratch_target_dir().to_string_lossy().into_owned();
+			vars.push((TARGET_DIR.to_string(), Some(scratch)));
+		}
+		vars.extend(self.overrides.iter().cloned());
+		vars
+	}
+
+	/// Set and remove the variables of [`Self::vars`] on `command`.
+	pub fn apply(&self, command: &mut Command) {
+		for (key, value) in self.vars() {
+			match value {
+				Some(value) => command.env(key, value),
+				None => command.env_remove(key),
+			};
+		}
+	}
+
+	/// Set and remove the variables of [`Self::vars`] on a `cargo metadata` invocation.
+	pub fn apply_metadata(&self, command: &mut cargo_metadata::MetadataCommand) {
+		for (key, value) in self.vars() {
+			match value {
+				Some(value) => command.env(key, value),
+				None => command.env_remove(key),
+			};
+		}
+	}
+
+	/// Target directory builds are given through `CARGO_TARGET_DIR`, or `None` when they use
+	/// whatever cargo picks for the project.
+	pub fn target_dir(&self) -> Option<PathBuf> {
+		let vars = self.vars();
+		let target_dir = vars.iter().rev().find(|(key, _)| key == TARGET_DIR);
+		target_dir.and_then(|(_, value)| value.as_deref().map(PathBuf::from))
+	}
+}
+
+/// Directory under ripdoc's cache that builds go to unless the project's is requested, or
+/// under the system's temporary directory when there is no cache directory.
+pub fn scratch_target_dir() -> PathBuf {
+	let base = CacheConfig::default()
+		.get_cache_dir()
+		.unwrap_or_else(|_| env::temp_dir().join("ripdoc"));
+	base.join("target")
+}
+
+/// Value `command` was given for `key`: `Some(None)` when it is removed, `None` when it is
+/// inherited.
+#[cfg(test)]
+pub(crate) fn command_env<'a>(command: &'a Command, key: &str) -> Option<Option<&'a OsStr>> {
+	command
+		.get_envs()
+		.find(|(name, _)| *name == key)
+		.map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn applied(env: &CargoEnv) -> Command {
+		let mut command = Command::new("cargo");
+		env.apply(&mut command);
+		command
+	}
+
+	#[test]
+	fn builds_use_a_scratch_target_dir_without_a_wrapper() {
+		let command = applied(&CargoEnv::default());
+		let scratch = scratch_target_dir();
+		assert_eq!(
+			command_env(&command, TARGET_DIR),
+			Some(Some(scratch.as_os_str()))
+		);
+		assert_eq!(command_env(&command, RUSTC_WRAPPER), Some(None));
+		assert_eq!(command_env(&command, "RUSTFLAGS"), None);
+		assert_eq!(CargoEnv::default().target_dir(), Some(scratch));
+	}
+
+	#[test]
+	fn project_target_dirs_are_inherited() {
+		let env = CargoEnv {
+			project_target_dir: true,
+			..CargoEnv::default()
+		};
+		assert_eq!(command_env(&applied(&env), TARGET_DIR), None);
+		assert_eq!(env.target_dir(), None);
+	}
+
+	#[test]
+	fn overrides_win_over_the_defaults() {
+		let env = CargoEnv {
+			overrides: vec![
+				(RUSTC_WRAPPER.into(), Some("sccache".into())),
+				(TARGET_DIR.into(), Some("/tmp/docs".into())),
+				("RUSTDOCFLAGS".into(), None),
+			],
+			project_target_dir: false,
+		};
+		let command = applied(&env);
+		assert_eq!(
+			command_env(&command, RUSTC_WRAPPER),
+			Some(Some(OsStr::new("sccache")))
+		);
+		assert_eq!(
+			command_env(&command, TARGET_DIR),
+			Some(Some(OsStr::new("/tmp/docs")))
+		);
+		assert_eq!(command_env(&command, "RUSTDOCFLAGS"), Some(None));
+		assert_eq!(env.target_dir(), Some(PathBuf::from("/tmp/docs")));
+	}
+}
@@ -1,10 +1,36 @@
 use std::fmt;
+use std::time::Duration;
+
+use crate::interrupt::Stage;
 
 /// Errors produced while resolving targets or interacting with Cargo/rustdoc.
 #[derive(Debug)]
 pub enum RipdocError {
 	/// Generic error with a message.
 	Generate(String),
+	/// Rustdoc could not build the crate, usually because it does not compile.
+	BuildFailed(String),
+	/// The toolchain is too old, or otherwise unusable.
+	Toolchain(String),
+	/// The toolchain rustdoc should run with is not installed.
+	ToolchainMissing {
+		/// Toolchain name, such as `nightly`.
+		toolchain: String,
+	},
+	/// The standard library for the requested target triple is not installed.
+	TargetMissing {
+		/// Target triple, such as `thumbv7em-none-eabihf`.
+		target: String,
+	},
+	/// A registry crate was needed offline, but no matching release is in cargo's cache.
+	NotCached {
+		/// Crate name as given.
+		name: String,
+		/// Version requirement given with the crate, if any.
+		version: Option<String>,
+		/// Releases of the crate that are cached, oldest first.
+		cached: Vec<String>,
+	},
 	/// Failed to parse a manifest file.
 	ManifestParse(String),
 	/// The requested target path does not point to a Cargo package.
@@ -13,16 +39,107 @@ pub enum RipdocError {
 	ModuleNotFound(String),
 	/// The requested target specification was malformed.
 	InvalidTarget(String),
+	/// Every release matching the requested version has been yanked from its registry.
+	Yanked {
+		/// Crate name as published.
+		name: String,
+		/// Newest yanked release matching the request.
+		version: String,
+		/// Unyanked release closest to `version`, if there is one.
+		nearest: Option<String>,
+	},
+	/// Rustdoc JSON was written in a format version `rustdoc_types` cannot read.
+	FormatVersionMismatch {
+		/// Format version found in the JSON document.
+		found: u32,
+		/// Format version supported by this build of ripdoc.
+		supported: u32,
+	},
+	/// A stage ran past its timeout and was stopped.
+	TimedOut {
+		/// Stage that was stopped.
+		stage: Stage,
+		/// Longest the stage was allowed to run.
+		timeout: Duration,
+	},
+	/// A stage was stopped through its cancellation token.
+	Cancelled {
+		/// Stage that was stopped.
+		stage: Stage,
+	},
 }
 
 impl fmt::Display for RipdocError {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
-			Self::Generate(message) => write!(f, "{message}"),
+			Self::Generate(message) | Self::BuildFailed(message) | Self::Toolchain(message) => {
+				write!(f, "{message}")
+			}
+			Self::ToolchainMissing { toolchain } => {
+				write!(
+					f,
+					"ripdoc requires the {toolchain} toolchain to be installed"
+				)
+			}
+			Self::TargetMissing { target } => write!(
+				f,
+				"the standard library for target '{target}' is not installed"
+			),
+			Self::NotCached {
+				name,
+				version,
+				cached,
+			} => {
+				write!(f, "crate '{name}'")?;
+				if let Some(version) = version {
+					write!(f, "@{version}")?;
+				}
+				write!(f, " is not cached locally for offline use")?;
+				if !cached.is_empty() {
+					write!(f, " (cached versions: {})", cached.join(", "))?;
+				}
+				Ok(())
+			}
 			Self::ManifestParse(message) => write!(f, "failed to parse manifest: {message}"),
 			Self::ManifestNotFound => write!(f, "failed to locate Cargo.toml"),
 			Self::ModuleNotFound(name) => write!(f, "module or crate not found: {name}"),
 			Self::InvalidTarget(message) => write!(f, "{message}"),
+			Self::Yanked {
+				name,
+				version,
+				nearest: Some(nearest),
+			} => write!(
+				f,
+				"'{name}'@{version} has been yanked; the nearest release still available is \
+				 {nearest} (`{name}@{nearest}`)"
+			),
+			Self::Yanked {
+				name,
+				version,
+				nearest: None,
+			} => write!(
+				f,
+				"'{name}'@{version} has been yanked and no other release is available"
+			),
+			Self::FormatVersionMismatch { found, supported } if found > supported => write!(
+				f,
+				"rustdoc JSON format version {found} is newer than the supported version \
+				 {supported}; update ripdoc, or pin an older nightly with \
+				 `rustup toolchain install nightly-YYYY-MM-DD`"
+			),
+			Self::FormatVersionMismatch { found, supported } => write!(
+				f,
+				"rustdoc JSON format version {found} is older than the supported version \
+				 {supported}; update your toolchain with `rustup update nightly`, or use an older \
+				 ripdoc release"
+			),
+			Self::TimedOut { stage, timeout } => {
+				write!(
+					f,
+					"{stage} did not finish within {timeout:?} and was stopped"
+				)
+			}
+			Self::Cancelled { stage } => write!(f, "{stage} was cancelled"),
 		}
 	}
 }
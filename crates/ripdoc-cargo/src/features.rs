@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::env::CargoEnv;
+use crate::error::{Result, RipdocError};
+
+/// Features cargo enables for a package, compared with the ones that were asked for.
+///
+/// Cargo unifies features across everything built together, so another workspace member that
+/// depends on the package with extra features turns those on for it as well, and the documented
+/// API can include items the requested features alone would leave out.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ResolvedFeatures {
+	/// Every feature cargo enables for the package, sorted.
+	pub enabled: Vec<String>,
+	/// Enabled features that the requested ones do not turn on by themselves, sorted; empty
+	/// when the build matches the request.
+	pub unified: Vec<String>,
+}
+
+/// Ask `cargo metadata` which features the package at `manifest_path` ends up with when built
+/// with the given feature flags.
+///
+/// Runs with `--offline` when `offline` is set, and in `env`.
+pub fn resolved_features(
+	manifest_path: &Path,
+	no_default_features: bool,
+	all_features: bool,
+	features: &[String],
+	offline: bool,
+	env: &CargoEnv,
+) -> Result<ResolvedFeatures> {
+	let mut options = Vec::new();
+	if no_default_features {
+		options.push("--no-default-features".to_string());
+	}
+	if all_features {
+		options.push("--all-features".to_string());
+	}
+	if !features.is_empty() {
+		options.push("--features".to_string());
+		options.push(features.join(","));
+	}
+	if offline {
+		options.push("--offline".to_string());
+	}
+	let mut command = cargo_metadata::MetadataCommand::new();
+	command.manifest_path(manifest_path).other_options(options);
+	env.apply_metadata(&mut command);
+	let metadata = command
+		.exec()
+		.map_err(|err| RipdocError::Generate(format!("Failed to get cargo metadata: {err}")))?;
+
+	let not_a_package = || {
+		RipdocError::Generate(format!(
+			"'{}' does not declare a package to resolve features for",
+			manifest_path.display()
+		))
+	};
+	let resolve = metadata.resolve.as_ref().ok_or_else(not_a_package)?;
+	let root = resolve.root.as_ref().ok_or_else(not_a_package)?;
+	let node = resolve
+		.nodes
+		.iter()
+		.find(|node| &node.id == root)
+		.ok_or_else(not_a_package)?;
+	let declared: BTreeMap<String, Vec<String>> = metadata[root]
+		.features
+		.iter()
+		.map(|(name, enables)| {
+			let enables = enables.iter().map(|entry| entry.to_string()).collect();
+			(name.to_string(), enables)
+		})
+		.collect();
+
+	let mut enabled: Vec<String> = node.features.iter().map(|name| name.to_string()).collect();
+	enabled.sort();
+	let requested = requested_closure(&declared, no_default_features, all_features, features);
+	let unified = enabled
+		.iter()
+		.filter(|name| !requested.contains(*name))
+		.cloned()
+		.collect();
+	Ok(ResolvedFeatures { enabled, unified })
+}
+
+/// Features of a package with the feature table `declared` that the feature flags turn on by
+/// themselves, following what each feature enables.
+fn requested_closure(
+	declared: &BTreeMap<String, Vec<String>>,
+	no_default_features: bool,
+	all_features: bool,
+	features: &[String],
+) -> BTreeSet<String> {
+	if all_features {
+		return declared.keys().cloned().collect();
+	}
+	let mut pending: Vec<&str> = features.iter().map(String::as_str).collect();
+	if !no_default_features {
+		pending.push("default");
+	}
+	let mut enabled = BTreeSet::new();
+	while let Some(entry) = pending.pop() {
+		// `dep:name` and `name?/feature` leave the dependency's implicit feature off, while
+		// `name/feature` turns it on
+		let name = match entry.split_once('/') {
+			Some((dependency, _)) if dependency.ends_with('?') => continue,
+			Some((dependency, _)) => dependency,
+			None if entry.starts_with("dep:") => continue,
+			None => entry,
+		};
+		if let Some(enables) = declared.get(name)
+			&& enabled.insert(name.to_string())
+		{
+			pending.extend(enables.iter().map(String::as_str));
+		}
+	}
+	enabled
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+
+	use tempfile::TempDir;
+
+	use super::*;
+
+	/// Workspace where `app` depends on `engine` with only its `extra` feature.
+	fn setup_unified_workspace() -> TempDir {
+		let temp_dir = TempDir::new().unwrap();
+		let root = temp_dir.path();
+		fs::create_dir_all(root.join("engine/src")).unwrap();
+		fs::create_dir_all(root.join("app/src")).unwrap();
+		fs::write(
+			root.join("Cargo.toml"),
+			r#"
+            [workspace]
+            members = ["engine", "app"]
+            resolver = "2"
+            "#,
+		)
+		.unwrap();
+		fs::write(
+			root.join("engine/Cargo.toml"),
+			r#"
+            [package]
+            name = "engine"
+            version = "0.1.0"
+            edition = "2021"
+
+            [features]
+            default = ["std"]
+            std = []
+            extra = []
+            fast = []
+            "#,
+		)
+		.unwrap();
+		fs::write(root.join("engine/src/lib.rs"), "pub struct Engine;").unwrap();
+		fs::write(
+			root.join("app/Cargo.toml"),
+			r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+            edition = "2021"
+
+            [dependencies]
+            engine = { path = "../engine", default-features = false, features = ["extra"] }
+            "#,
+		)
+		.unwrap();
+		fs::write(root.join("app/src/lib.rs"), "pub use engine::Engine;").unwrap();
+		temp_dir
+	}
+
+	fn resolve(
+		workspace: &TempDir,
+		no_default_features: bool,
+		features: &[&str],
+	) -> ResolvedFeatures {
+		let features: Vec<String> = features.iter().map(|name| name.to_string()).collect();
+		resolved_features(
+			&workspace.path().join("engine/Cargo.toml"),
+			no_default_features,
+			false,
+			&features,
+			true,
+			&CargoEnv::default(),
+		)
+		.unwrap()
+	}
+
+	#[test]
+	fn features_enabled_by_other_members_are_reported_as_unified() {
+		let workspace = setup_unified_workspace();
+		let resolved = resolve(&workspace, false, &[]);
+		assert_eq!(resolved.enabled, ["default", "extra", "std"]);
+		assert_eq!(resolved.unified, ["extra"]);
+
+		let resolved = resolve(&workspace, true, &["fast"]);
+		assert_eq!(resolved.enabled, ["extra", "fast"]);
+		assert_eq!(resolved.unified, ["extra"]);
+	}
+
+	#[test]
+	fn requested_features_are_not_reported_as_unified() {
+		let workspace = setup_unified_workspace();
+		let resolved = resolve(&workspace, true, &["extra", "fast"]);
+		assert_eq!(resolved.enabled, ["extra", "fast"]);
+		assert!(resolved.unified.is_empty(), "{resolved:?}");
+	}
+
+	#[test]
+	fn requested_closure_follows_what_features_enable() {
+		let declared = BTreeMap::from([
+			("default".to_string(), vec!["std".to_string()]),
+			("std".to_string(), vec!["serde?/std".to_string()]),
+			("serde".to_string(), vec!["dep:serde".to_string()]),
+			("json".to_string(), vec!["serde/alloc".to_string()]),
+			("fast".to_string(), Vec::new()),
+		]);
+		let closure = |no_default_features, features: &[&str]| {
+			let features: Vec<String> = features.iter().map(|name| name.to_string()).collect();
+			requested_closure(&declared, no_default_features, false, &features)
+				.into_iter()
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(closure(false, &[]), ["default", "std"]);
+		assert_eq!(closure(true, &["json"]), ["json", "serde"]);
+		assert_eq!(closure(true, &["dep:serde"]), Vec::<String>::new());
+		assert_eq!(
+			requested_closure(&declared, true, true, &[]).len(),
+			declared.len()
+		);
+	}
+}
@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{Result, RipdocError};
+
+/// Files that differ between the git revision `rev` and the working tree of the repository
+/// containing `dir`, as absolute paths.
+///
+/// Paths come from `git diff --name-only <rev>`, so untracked files are not included and
+/// deleted files are listed even though they no longer exist.
+pub fn changed_files(dir: &Path, rev: &str) -> Result<Vec<PathBuf>> {
+	let toplevel = git(dir, &["rev-parse", "--show-toplevel"]).map_err(|_| {
+		RipdocError::InvalidTarget(format!(
+			"'{}' is not inside a git worktree, so changes since '{rev}' cannot be found",
+			dir.display()
+		))
+	})?;
+	let toplevel = PathBuf::from(toplevel.trim());
+	// Unquoted so non-ASCII file names come through as they are
+	let args = [
+		"-c",
+		"core.quotePath=false",
+		"diff",
+		"--name-only",
+		rev,
+		"--",
+	];
+	let names = git(dir, &args).map_err(|stderr| {
+		RipdocError::Generate(format!(
+			"git diff against '{rev}' failed: {}",
+			stderr.trim()
+		))
+	})?;
+	Ok(names
+		.lines()
+		.filter(|line| !line.is_empty())
+		.map(|name| toplevel.join(name))
+		.collect())
+}
+
+/// Run git with `args` in `dir`, returning its stdout, or its stderr when it fails.
+fn git(dir: &Path, args: &[&str]) -> std::result::Result<String, String> {
+	let output = Command::new("git")
+		.args(args)
+		.current_dir(dir)
+		.output()
+		.map_err(|err| format!("failed to run git: {err}"))?;
+	if !output.status.success() {
+		return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+	}
+	Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
@@ -0,0 +1,327 @@
+use std::fmt;
+use std::io::Read;
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::error::{Result, RipdocError};
+
+/// How often a running command is checked for its exit, its timeout, and cancellation.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Handle that stops long-running steps when cancelled, shared by cloning.
+///
+/// Cancelling is sticky: every step started with the token afterwards stops at once.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+	/// Set once the token is cancelled.
+	cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+	/// Create a token that has not been cancelled.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Stop the steps running with this token, and any started with it later.
+	pub fn cancel(&self) {
+		self.cancelled.store(true, Ordering::Relaxed);
+	}
+
+	/// Whether [`Self::cancel`] has been called on this token or a clone of it.
+	pub fn is_cancelled(&self) -> bool {
+		self.cancelled.load(Ordering::Relaxed)
+	}
+}
+
+/// A step that runs an external command and can be interrupted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+	/// `cargo fetch` downloading a registry crate.
+	Fetch,
+	/// `cargo rustdoc` generating rustdoc JSON.
+	Build,
+}
+
+impl fmt::Display for Stage {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::Fetch => "cargo fetch",
+			Self::Build => "the rustdoc JSON build",
+		})
+	}
+}
+
+/// When the commands behind each [`Stage`] are stopped early: after running for `timeout`, or
+/// once `cancel` is cancelled. Without either, commands run to completion.
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt {
+	/// Longest each stage may run.
+	pub timeout: Option<Duration>,
+	/// Token that stops the running stage when cancelled.
+	pub cancel: Option<CancellationToken>,
+}
+
+impl Interrupt {
+	/// Run `command` for `stage` to completion, capturing its output.
+	///
+	/// When the timeout passes or the token is cancelled first, the command is killed along
+	/// with the processes it started, and [`RipdocError::TimedOut`] or
+	/// [`RipdocError::Cancelled`] is returned. On Unix, SIGINT or SIGTERM arriving while the
+	/// command runs kills it the same way before the signal is handled as usual.
+	pub fn output(&self, command: &mut Command, stage: Stage) -> Result<Output> {
+		if self.timeout.is_none() && self.cancel.is_none() {
+			return Ok(command.output()?);
+		}
+		self.check(stage)?;
+
+		// Cargo runs rustc, rustdoc and build scripts as children; a process group of their
+		// own lets them be killed together. The terminal no longer sends Ctrl-C to that group,
+		// so it is passed on while the command runs
+		#[cfg(unix)]
+		std::os::unix::process::CommandExt::process_group(command, 0);
+		#[cfg(unix)]
+		let _forwarding = forward::Guard::install();
+		let mut child = command
+			.stdin(Stdio::null())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::piped())
+			.spawn()?;
+		// Pipes are drained as the command runs, so it never blocks on a full one
+		let stdout = drain(child.stdout.take());
+		let stderr = drain(child.stderr.take());
+
+		let started = Instant::now();
+		let status = loop {
+			if let Some(status) = child.try_wait()? {
+				break status;
+			}
+			#[cfg(unix)]
+			if forward::pending() {
+				kill_tree(&mut child);
+				return Err(RipdocError::Cancelled { stage });
+			}
+			let stopped = self.check(stage).and_then(|()| match self.timeout {
+				Some(timeout) if started.elapsed() >= timeout => {
+					Err(RipdocError::TimedOut { stage, timeout })
+				}
+				_ => Ok(()),
+			});
+			if let Err(err) = stopped {
+				kill_tree(&mut child);
+				return Err(err);
+			}
+			thread::sleep(POLL_INTERVAL);
+		};
+
+		Ok(Output {
+			status,
+			stdout: stdout.join().unwrap_or_default(),
+			stderr: stderr.join().unwrap_or_default(),
+		})
+	}
+
+	/// Fail with [`RipdocError::Cancelled`] when the token has been cancelled.
+	pub fn check(&self, stage: Stage) -> Result<()> {
+		match &self.cancel {
+			Some(cancel) if cancel.is_cancelled() => Err(RipdocError::Cancelled { stage }),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Read `pipe` to its end on another thread.
+fn drain(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+	thread::spawn(move || {
+		let mut buffer = Vec::new();
+		if let Some(mut pipe) = pipe {
+			let _ = pipe.read_to_end(&mut buffer);
+		}
+		buffer
+	})
+}
+
+/// Kill `child` and the processes in its process group, then reap it.
+fn kill_tree(child: &mut Child) {
+	#[cfg(unix)]
+	if let Ok(pid) = libc::pid_t::try_from(child.id()) {
+		// SAFETY: `kill` has no memory-safety preconditions; a negative pid names the group
+		// `child` leads, which was created for it when it was spawned.
+		unsafe {
+			libc::kill(-pid, libc::SIGKILL);
+		}
+	}
+	let _ = child.kill();
+	let _ = child.wait();
+}
+
+/// Catching Ctrl-C and termination requests while commands run in a process group of their own.
+#[cfg(unix)]
+mod forward {
+	use std::sync::atomic::{AtomicI32, Ordering};
+	use std::sync::{Mutex, PoisonError};
+	use std::{mem, ptr};
+
+	/// Signals the terminal or a supervisor sends to stop ripdoc, and so the commands it runs.
+	const SIGNALS: [libc::c_int; 2] = [libc::SIGINT, libc::SIGTERM];
+
+	/// The first of [`SIGNALS`] received while a command runs, or 0.
+	static PENDING: AtomicI32 = AtomicI32::new(0);
+
+	/// Number of running commands, and the handlers in place before the first of them started.
+	static ACTIVE: Mutex<(usize, Vec<libc::sigaction>)> = Mutex::new((0, Vec::new()));
+
+	/// Signal handler noting which signal arrived; storing to an atomic is async-signal-safe.
+	extern "C" fn note(signal: libc::c_int) {
+		let _ = PENDING.compare_exchange(0, signal, Ordering::SeqCst, Ordering::SeqCst);
+	}
+
+	/// Whether one of [`SIGNALS`] arrived, so running commands should be killed.
+	pub fn pending() -> bool {
+		PENDING.load(Ordering::SeqCst) != 0
+	}
+
+	/// Catches [`SIGNALS`] while any guard is alive.
+	///
+	/// Dropping the last guard restores the previous handlers and raises a caught signal again,
+	/// so ripdoc then stops, or not, just as it would have without the guard.
+	pub struct Guard(());
+
+	impl Guard {
+		/// Start catching [`SIGNALS`], unless they are ignored.
+		pub fn install() -> Self {
+			let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+			if active.0 == 0 {
+				PENDING.store(0, Ordering::SeqCst);
+				active.1 = SIGNALS.iter().map(|&signal| catch(signal)).collect();
+			}
+			active.0 += 1;
+			Self(())
+		}
+	}
+
+	impl Drop for Guard {
+		fn drop(&mut self) {
+			let mut active = ACTIVE.lock().unwrap_or_else(PoisonError::into_inner);
+			active.0 -= 1;
+			if active.0 > 0 {
+				return;
+			}
+			for (&signal, previous) in SIGNALS.iter().zip(&active.1) {
+				// SAFETY: `previous` was filled in by `sigaction` for this signal in `catch`.
+				unsafe {
+					libc::sigaction(signal, previous, ptr::null_mut());
+				}
+			}
+			active.1.clear();
+			let signal = PENDING.swap(0, Ordering::SeqCst);
+			if signal != 0 {
+				// SAFETY: `raise` has no memory-safety preconditions.
+				unsafe {
+					libc::raise(signal);
+				}
+			}
+		}
+	}
+
+	/// Install [`note`] as the handler for `signal` unless it is ignored, returning the previous
+	/// handler.
+	fn catch(signal: libc::c_int) -> libc::sigaction {
+		// SAFETY: `sigaction` is all integers and function pointers stored as integers, for
+		// which zero is valid; the pointers passed to `sigaction` outlive the calls.
+		unsafe {
+			let mut previous: libc::sigaction = mem::zeroed();
+			libc::sigaction(signal, ptr::null(), &mut previous);
+			// A background job has SIGINT ignored; catching it would stop it on Ctrl-C
+			if previous.sa_sigaction != libc::SIG_IGN {
+				let mut action: libc::sigaction = mem::zeroed();
+				action.sa_sigaction = note as extern "C" fn(libc::c_int) as libc::sighandler_t;
+				action.sa_flags = libc::SA_RESTART;
+				libc::sigemptyset(&mut action.sa_mask);
+				libc::sigaction(signal, &action, ptr::null_mut());
+			}
+			previous
+		}
+	}
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+	use super::*;
+
+	/// A command that runs for far longer than any test waits.
+	fn sleeper() -> Command {
+		let mut command = Command::new("sleep");
+		command.arg("30");
+		command
+	}
+
+	#[test]
+	fn commands_past_their_timeout_are_killed() {
+		let interrupt = Interrupt {
+			timeout: Some(Duration::from_millis(200)),
+			cancel: None,
+		};
+		let started = Instant::now();
+		let err = interrupt.output(&mut sleeper(), Stage::Build).unwrap_err();
+		assert!(
+			matches!(
+				err,
+				RipdocError::TimedOut {
+					stage: Stage::Build,
+					..
+				}
+			),
+			"{err:?}"
+		);
+		assert!(started.elapsed() < Duration::from_secs(10));
+		assert_eq!(
+			err.to_string(),
+			"the rustdoc JSON build did not finish within 200ms and was stopped"
+		);
+	}
+
+	#[test]
+	fn cancelled_tokens_stop_running_and_later_commands() {
+		let cancel = CancellationToken::new();
+		let interrupt = Interrupt {
+			timeout: None,
+			cancel: Some(cancel.clone()),
+		};
+		let canceller = thread::spawn(move || {
+			thread::sleep(Duration::from_millis(200));
+			cancel.cancel();
+		});
+		let err = interrupt.output(&mut sleeper(), Stage::Fetch).unwrap_err();
+		canceller.join().unwrap();
+		assert!(
+			matches!(
+				err,
+				RipdocError::Cancelled {
+					stage: Stage::Fetch
+				}
+			),
+			"{err:?}"
+		);
+
+		let mut echo = Command::new("echo");
+		let err = interrupt.output(&mut echo, Stage::Fetch).unwrap_err();
+		assert!(matches!(err, RipdocError::Cancelled { .. }), "{err:?}");
+	}
+
+	#[test]
+	fn commands_finishing_in_time_keep_their_output() {
+		let interrupt = Interrupt {
+			timeout: Some(Duration::from_secs(30)),
+			cancel: Some(CancellationToken::new()),
+		};
+		let mut echo = Command::new("echo");
+		echo.arg("done");
+		let output = interrupt.output(&mut echo, Stage::Build).unwrap();
+		assert!(output.status.success());
+		assert_eq!(output.stdout, b"done\n");
+	}
+}
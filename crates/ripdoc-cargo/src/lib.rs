@@ -1,25 +1,57 @@
 //! Utilities for querying Cargo metadata and managing crate sources.
 
-pub use self::cache::{CacheConfig, CacheKey, get_toolchain_version, load_cached, save_cached};
+pub use self::cache::{
+	CacheConfig, CacheKey, get_toolchain_version, is_cached, load_cached, save_cached,
+};
+pub use self::crate_json::{CrateJson, parse_crate};
+pub use self::env::CargoEnv;
 pub use self::error::{Result, RipdocError};
+pub use self::features::{ResolvedFeatures, resolved_features};
+pub use self::git::changed_files;
+pub use self::interrupt::{CancellationToken, Interrupt, Stage};
+pub use self::metrics::BuildMetrics;
+pub use self::options::BuildOptions;
 pub use self::path::CargoPath;
-pub use self::registry::fetch_registry_crate;
-pub use self::resolved_target::{ResolvedTarget, resolve_target};
+pub use self::plan::{BuildPlan, PlannedTarget};
+pub use self::registry::{Registry, fetch_registry_crate, list_cached_versions};
+pub use self::resolved_target::{ResolvedTarget, check_locked, resolve_target};
 pub use self::rustdoc_error::map_rustdoc_build_error;
+pub use self::source::{ResolvedInfo, SourceKind};
+pub use self::verbosity::Verbosity;
 /// Caching layer for rustdoc JSON output.
 pub mod cache;
+/// Rustdoc JSON parsing with format version checks.
+pub mod crate_json;
+/// Environment of the cargo commands behind fetches and builds.
+pub mod env;
 /// Error helpers for interacting with Cargo and rustdoc.
 pub mod error;
+/// Features cargo enables for a package once they are unified across the build.
+pub mod features;
+/// Files changed in a git worktree since a revision.
+pub mod git;
+/// Timeouts and cancellation for the external commands behind fetches and builds.
+pub mod interrupt;
+/// Time and size measurements of rustdoc JSON builds.
+pub mod metrics;
+/// Settings of a rustdoc JSON build.
+pub mod options;
 /// CargoPath type and cargo crate path resolution.
 pub mod path;
-/// Downloading crates from crates.io into a local cache.
+/// Dry-run summaries of rustdoc JSON builds.
+pub mod plan;
+/// Downloading crates from crates.io or another registry into a local cache.
 pub mod registry;
 /// Target resolution to ResolvedTarget type.
 pub mod resolved_target;
 /// Rustdoc error handling and diagnostics extraction.
 pub mod rustdoc_error;
+/// Where resolved targets come from.
+pub mod source;
 /// Target parsing utilities.
 pub mod target;
+/// Diagnostic verbosity levels.
+pub mod verbosity;
 
 /// Check if rustup is available on the system
 pub fn is_rustup_available() -> bool {
@@ -0,0 +1,14 @@
+use std::time::Duration;
+
+/// Time and size measurements from obtaining one crate's rustdoc JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BuildMetrics {
+	/// Time spent running rustdoc; zero when the JSON came from the cache or a file.
+	pub build: Duration,
+	/// Size of the rustdoc JSON read; zero when the crate came from the cache.
+	pub json_bytes: u64,
+	/// Time spent deserializing the rustdoc JSON or the cache entry.
+	pub parse: Duration,
+	/// Whether the crate was loaded from the rustdoc JSON cache.
+	pub cached: bool,
+}
@@ -0,0 +1,28 @@
+use crate::env::CargoEnv;
+use crate::interrupt::Interrupt;
+use crate::verbosity::Verbosity;
+
+/// What a rustdoc JSON build documents: the package's features, whether private items are
+/// included, and the target platform, along with the toolchain that builds it and how the
+/// build is run.
+#[derive(Debug, Clone, Copy)]
+pub struct BuildOptions<'a> {
+	/// Whether default features are disabled.
+	pub no_default_features: bool,
+	/// Whether all features are enabled.
+	pub all_features: bool,
+	/// Features to enable explicitly.
+	pub features: &'a [String],
+	/// Whether private items are documented.
+	pub private_items: bool,
+	/// Toolchain passed to cargo, overriding the nightly picked by default.
+	pub toolchain: Option<&'a str>,
+	/// Target triple documented instead of the host, if any.
+	pub target_triple: Option<&'a str>,
+	/// How much of cargo's output is shown.
+	pub verbosity: Verbosity,
+	/// When the build is stopped early.
+	pub interrupt: &'a Interrupt,
+	/// Environment cargo runs in.
+	pub env: &'a CargoEnv,
+}
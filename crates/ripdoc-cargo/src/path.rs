@@ -1,12 +1,21 @@
+use std::collections::BTreeMap;
 use std::fs;
-use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Instant;
 
-use rustdoc_json::PackageTarget;
 use rustdoc_types::Crate;
 use tempfile::TempDir;
 
+use crate::crate_json::CrateJson;
+use crate::env::CargoEnv;
 use crate::error::{Result, RipdocError};
+use crate::interrupt::Stage;
+use crate::metrics::BuildMetrics;
+use crate::options::BuildOptions;
+use crate::plan::PlannedTarget;
+use crate::source::SourceKind;
+use crate::to_import_name;
 
 /// A path to a crate. This can be a directory on the filesystem or a temporary directory.
 #[derive(Debug)]
@@ -30,15 +39,133 @@ impl CargoPath {
 	/// Read the crate data for this resolved target using rustdoc JSON generation.
 	pub fn read_crate(
 		&self,
-		no_default_features: bool,
-		all_features: bool,
-		features: Vec<String>,
-		private_items: bool,
-		silent: bool,
+		options: &BuildOptions,
 		cache_config: &crate::cache::CacheConfig,
 	) -> Result<Crate> {
-		use std::io;
+		self.read_crate_measured(options, cache_config)
+			.map(|(crate_data, _)| crate_data)
+	}
+
+	/// Read the crate data like [`Self::read_crate`], also measuring the rustdoc build and the
+	/// JSON it produced.
+	pub fn read_crate_measured(
+		&self,
+		options: &BuildOptions,
+		cache_config: &crate::cache::CacheConfig,
+	) -> Result<(Crate, BuildMetrics)> {
+		self.read_crate_json(options, cache_config, false)
+			.map(|(json, metrics)| (json.crate_data, metrics))
+	}
+
+	/// Read the crate data like [`Self::read_crate`], along with the rustdoc JSON file it came
+	/// from; see [`CrateJson::path`].
+	pub fn read_crate_with_path(
+		&self,
+		options: &BuildOptions,
+		cache_config: &crate::cache::CacheConfig,
+	) -> Result<CrateJson> {
+		self.read_crate_json(options, cache_config, true)
+			.map(|(json, _)| json)
+	}
 
+	/// Load the crate from the cache or build its rustdoc JSON, measuring both.
+	///
+	/// A crate served from the cache only looks for rustdoc's earlier output when
+	/// `locate_cached` is set, since finding the target directory can take a `cargo metadata`
+	/// run.
+	fn read_crate_json(
+		&self,
+		options: &BuildOptions,
+		cache_config: &crate::cache::CacheConfig,
+		locate_cached: bool,
+	) -> Result<(CrateJson, BuildMetrics)> {
+		let mut metrics = BuildMetrics::default();
+		let silent = options.verbosity.is_silent();
+		let BuildInputs {
+			manifest_path,
+			package_info,
+			target,
+			cache_key,
+			..
+		} = self.build_inputs(options)?;
+
+		let started = Instant::now();
+		if let Ok(Some(cached_crate)) = crate::cache::load_cached(cache_config, &cache_key) {
+			log::debug!("using cached rustdoc JSON for {package_info}");
+			metrics.parse = started.elapsed();
+			metrics.cached = true;
+			let path = locate_cached
+				.then(|| {
+					self.rustdoc_json_path(
+						&manifest_path,
+						&target,
+						options.target_triple,
+						options.env,
+					)
+				})
+				.and_then(Result::ok)
+				.filter(|path| path.is_file());
+			let json = CrateJson {
+				crate_data: cached_crate,
+				path,
+			};
+			return Ok((json, metrics));
+		}
+		log::debug!("generating rustdoc JSON for {package_info}");
+
+		let mut command = rustdoc_command(&manifest_path, &target, options);
+
+		let started = Instant::now();
+		let output = options.interrupt.output(&mut command, Stage::Build)?;
+		metrics.build = started.elapsed();
+
+		if !silent {
+			for captured in [&output.stdout, &output.stderr] {
+				for line in String::from_utf8_lossy(captured).lines() {
+					log::info!(target: "cargo", "{line}");
+				}
+			}
+		}
+
+		if !output.status.success() {
+			return Err(super::rustdoc_error::map_rustdoc_build_error(
+				&output.stderr,
+				silent,
+			));
+		}
+		let json_path =
+			self.rustdoc_json_path(&manifest_path, &target, options.target_triple, options.env)?;
+		let json_content = fs::read_to_string(&json_path)?;
+		metrics.json_bytes = json_content.len() as u64;
+		let started = Instant::now();
+		let crate_data =
+			super::crate_json::parse_crate(&json_content).map_err(|err| match err {
+				RipdocError::Generate(message) => {
+					let update_msg = if super::is_rustup_available() {
+						"try running 'rustup update nightly'"
+					} else {
+						"try updating your nightly Rust toolchain"
+					};
+					RipdocError::Toolchain(format!(
+						"{message}\nThis may indicate an outdated nightly toolchain - {update_msg}"
+					))
+				}
+				other => other,
+			})?;
+		metrics.parse = started.elapsed();
+
+		// Save to cache (ignore errors - cache is best-effort)
+		let _ = crate::cache::save_cached(cache_config, &cache_key, &crate_data);
+
+		let json = CrateJson {
+			crate_data,
+			path: Some(json_path),
+		};
+		Ok((json, metrics))
+	}
+
+	/// Gather everything that determines a rustdoc JSON build, without running it.
+	pub(crate) fn build_inputs(&self, options: &BuildOptions) -> Result<BuildInputs> {
 		let manifest_path = self.manifest_path()?;
 
 		// Determine which target to document (lib or bin)
@@ -46,107 +173,133 @@ impl CargoPath {
 		let manifest: cargo_toml::Manifest = cargo_toml::Manifest::from_str(&manifest_content)
 			.map_err(|e| RipdocError::ManifestParse(e.to_string()))?;
 
+		let name = manifest.package.as_ref().map(|p| p.name.clone());
+		let version = manifest.package.as_ref().map(|p| p.version().to_string());
+
 		// Build package info for cache key
-		let package_info = if let Some(ref package) = manifest.package {
-			format!("{}-{}", package.name, package.version())
-		} else {
+		let package_info = match (&name, &version) {
+			(Some(name), Some(version)) => format!("{name}-{version}"),
 			// For virtual manifests or when package info is missing, use a default
-			"unknown-package".to_string()
+			_ => "unknown-package".to_string(),
 		};
 
-		// Try to load from cache
-		let toolchain_version = crate::cache::get_toolchain_version();
-		let cache_key = crate::cache::CacheKey::new(
-			manifest_path.clone(),
-			package_info.clone(),
-			no_default_features,
-			all_features,
-			features.clone(),
-			private_items,
-			toolchain_version,
-		);
-
-		if let Ok(Some(cached_crate)) = crate::cache::load_cached(cache_config, &cache_key) {
-			return Ok(cached_crate);
-		}
-
-		let package_target = if manifest.lib.is_some() || self.as_path().join("src/lib.rs").exists()
-		{
+		let target = if lib_root(&manifest, self.as_path()).is_some() {
 			// Package has a library target
-			PackageTarget::Lib
+			PlannedTarget::Lib
 		} else if !manifest.bin.is_empty() {
 			// Package has explicit binary targets, use the first one
 			let first_bin = &manifest.bin[0];
-			PackageTarget::Bin(first_bin.name.clone().unwrap_or_else(|| {
-				manifest
-					.package
-					.as_ref()
-					.map(|p| p.name.clone())
-					.unwrap_or_else(|| "main".to_string())
-			}))
-		} else if self.as_path().join("src/main.rs").exists() {
-			// Package has default binary structure (src/main.rs)
-			PackageTarget::Bin(
-				manifest
-					.package
-					.as_ref()
-					.map(|p| p.name.clone())
+			PlannedTarget::Bin(
+				first_bin
+					.name
+					.clone()
+					.or_else(|| name.clone())
 					.unwrap_or_else(|| "main".to_string()),
 			)
+		} else if self.as_path().join("src/main.rs").exists() {
+			// Package has default binary structure (src/main.rs)
+			PlannedTarget::Bin(name.clone().unwrap_or_else(|| "main".to_string()))
 		} else {
 			// Fallback to Lib (will fail if there's truly no target)
-			PackageTarget::Lib
+			PlannedTarget::Lib
 		};
 
-		let mut captured_stdout = Vec::new();
-		let mut captured_stderr = Vec::new();
+		let toolchain_version = crate::cache::get_toolchain_version(options.toolchain);
+		let cache_key = crate::cache::CacheKey::new(
+			manifest_path.clone(),
+			package_info.clone(),
+			options,
+			toolchain_version,
+		);
 
-		let mut builder = rustdoc_json::Builder::default();
+		Ok(BuildInputs {
+			manifest_path,
+			name,
+			version,
+			package_info,
+			target,
+			cache_key,
+		})
+	}
 
-		// Only set toolchain if rustup is available
-		if super::is_rustup_available() {
-			builder = builder.toolchain("nightly");
+	/// Where `cargo rustdoc` writes the JSON for `target`: `<target dir>[/<triple>]/doc/`, named
+	/// after the target's crate name. The target directory is the one `env` sets, or else the
+	/// one cargo picks for the package.
+	fn rustdoc_json_path(
+		&self,
+		manifest_path: &Path,
+		target: &PlannedTarget,
+		target_triple: Option<&str>,
+		env: &CargoEnv,
+	) -> Result<PathBuf> {
+		let target_dir = match env.target_dir() {
+			Some(target_dir) => target_dir,
+			None => {
+				let mut command = cargo_metadata::MetadataCommand::new();
+				command.manifest_path(manifest_path).no_deps();
+				env.apply_metadata(&mut command);
+				let metadata = command.exec().map_err(|err| {
+					RipdocError::Generate(format!("Failed to get cargo metadata: {err}"))
+				})?;
+				metadata.target_directory.into_std_path_buf()
+			}
+		};
+		let crate_name = match target {
+			PlannedTarget::Bin(name) => to_import_name(name),
+			PlannedTarget::Lib | PlannedTarget::Json => self
+				.lib_name()?
+				.ok_or_else(|| RipdocError::Generate("package has no library target".into()))?,
+		};
+
+		let mut path = target_dir;
+		if let Some(target_triple) = target_triple {
+			path.push(target_triple);
 		}
+		path.push("doc");
+		path.push(format!("{crate_name}.json"));
+		Ok(path)
+	}
 
-		let build_result = builder
-			.manifest_path(manifest_path)
-			.package_target(package_target)
-			.document_private_items(private_items)
-			.no_default_features(no_default_features)
-			.all_features(all_features)
-			.features(features)
-			.quiet(silent)
-			.silent(false)
-			.build_with_captured_output(&mut captured_stdout, &mut captured_stderr);
+	/// Path of the library target's root file relative to the package, from `[lib] path` or
+	/// the default `src/lib.rs`; `None` when the package has no library.
+	pub fn lib_root(&self) -> Result<Option<PathBuf>> {
+		Ok(lib_root(&self.manifest()?, self.as_path()))
+	}
 
-		if !silent {
-			if !captured_stdout.is_empty() && io::stdout().write_all(&captured_stdout).is_err() {
-				// Best-effort output mirroring; ignore write failures.
-			}
-			if !captured_stderr.is_empty() && io::stderr().write_all(&captured_stderr).is_err() {
-				// Best-effort output mirroring; ignore write failures.
-			}
+	/// Crate name of the library target: `[lib] name`, or else the package name with `-` read
+	/// as `_`; `None` when the package has no library.
+	pub fn lib_name(&self) -> Result<Option<String>> {
+		let manifest = self.manifest()?;
+		if lib_root(&manifest, self.as_path()).is_none() {
+			return Ok(None);
 		}
+		let declared = manifest.lib.as_ref().and_then(|lib| lib.name.clone());
+		Ok(declared.or_else(|| {
+			manifest
+				.package
+				.as_ref()
+				.map(|package| to_import_name(&package.name))
+		}))
+	}
 
-		let json_path = build_result.map_err(|err| {
-			super::rustdoc_error::map_rustdoc_build_error(&err, &captured_stderr, silent)
-		})?;
-		let json_content = fs::read_to_string(&json_path)?;
-		let crate_data: Crate = serde_json::from_str(&json_content).map_err(|e| {
-            let update_msg = if super::is_rustup_available() {
-                "try running 'rustup update nightly'"
-            } else {
-                "try updating your nightly Rust toolchain"
-            };
-            RipdocError::Generate(format!(
-                "Failed to parse rustdoc JSON, which may indicate an outdated nightly toolchain - {update_msg}:\nError: {e}"
-            ))
-        })?;
-
-		// Save to cache (ignore errors - cache is best-effort)
-		let _ = crate::cache::save_cached(cache_config, &cache_key, &crate_data);
+	/// Whether `name` names this package, by its package name or its library's crate name, with
+	/// `-` and `_` treated alike. Packages without a readable manifest name nothing.
+	pub fn is_named(&self, name: &str) -> bool {
+		let name = to_import_name(name);
+		let package = self
+			.manifest()
+			.ok()
+			.and_then(|manifest| manifest.package)
+			.map(|package| to_import_name(&package.name));
+		let lib = self.lib_name().ok().flatten();
+		package.as_deref() == Some(name.as_str()) || lib.as_deref() == Some(name.as_str())
+	}
 
-		Ok(crate_data)
+	/// Parse this package's `Cargo.toml`.
+	fn manifest(&self) -> Result<cargo_toml::Manifest> {
+		let manifest_content = fs::read_to_string(self.manifest_path()?)?;
+		cargo_toml::Manifest::from_str(&manifest_content)
+			.map_err(|e| RipdocError::ManifestParse(e.to_string()))
 	}
 
 	/// Compute the absolute `Cargo.toml` path for this source.
@@ -182,15 +335,27 @@ impl CargoPath {
 		Ok(manifest.workspace.is_some() && manifest.package.is_none())
 	}
 
-	/// Find a dependency within the current workspace or registry cache.
-	pub fn find_dependency(&self, dependency: &str, _offline: bool) -> Result<Option<Self>> {
+	/// Identify if the path is a package manifest that also declares a `[workspace]`.
+	pub fn is_hybrid_workspace(&self) -> Result<bool> {
+		if !self.has_manifest()? {
+			return Ok(false);
+		}
 		let manifest_path = self.manifest_path()?;
+		let manifest = cargo_toml::Manifest::from_path(&manifest_path)
+			.map_err(|err| RipdocError::ManifestParse(err.to_string()))?;
+		Ok(manifest.workspace.is_some() && manifest.package.is_some())
+	}
 
-		let metadata = cargo_metadata::MetadataCommand::new()
-			.manifest_path(&manifest_path)
-			.exec()
-			.map_err(|err| RipdocError::Generate(format!("Failed to get cargo metadata: {err}")))?;
-
+	/// Find a dependency within the current workspace or registry cache, along with where it
+	/// comes from.
+	///
+	/// Path dependencies declared in this crate's manifest are found on disk directly. Anything
+	/// else needs `cargo metadata`, which is run with `--offline` when `offline` is set.
+	pub fn find_dependency(
+		&self,
+		dependency: &str,
+		offline: bool,
+	) -> Result<Option<(Self, SourceKind)>> {
 		// Try both the provided name and its hyphenated/underscored version
 		let alt_dependency = if dependency.contains('_') {
 			dependency.replace('_', "-")
@@ -198,11 +363,26 @@ impl CargoPath {
 			dependency.replace('-', "_")
 		};
 
+		if let Some(path) = self.find_path_dependency(&[dependency, alt_dependency.as_str()])? {
+			return Ok(Some((path, SourceKind::Path)));
+		}
+
+		let manifest_path = self.manifest_path()?;
+		let mut command = cargo_metadata::MetadataCommand::new();
+		command.manifest_path(&manifest_path);
+		if offline {
+			command.other_options(vec!["--offline".to_string()]);
+		}
+		let metadata = command
+			.exec()
+			.map_err(|err| RipdocError::Generate(format!("Failed to get cargo metadata: {err}")))?;
+
 		// First check workspace members
 		for package in &metadata.workspace_packages() {
 			if package.name == dependency || package.name == alt_dependency {
-				return Ok(Some(Self::Path(
-					package.manifest_path.parent().unwrap().to_path_buf().into(),
+				return Ok(Some((
+					Self::Path(package.manifest_path.parent().unwrap().to_path_buf().into()),
+					SourceKind::WorkspaceMember,
 				)));
 			}
 		}
@@ -210,8 +390,14 @@ impl CargoPath {
 		// Then check all resolved dependencies
 		for package in &metadata.packages {
 			if package.name == dependency || package.name == alt_dependency {
-				return Ok(Some(Self::Path(
-					package.manifest_path.parent().unwrap().to_path_buf().into(),
+				let source = match &package.source {
+					None => SourceKind::Path,
+					Some(source) if source.repr.starts_with("git+") => SourceKind::Git,
+					Some(_) => SourceKind::Registry,
+				};
+				return Ok(Some((
+					Self::Path(package.manifest_path.parent().unwrap().to_path_buf().into()),
+					source,
 				)));
 			}
 		}
@@ -219,6 +405,78 @@ impl CargoPath {
 		Ok(None)
 	}
 
+	/// Locate a `path` dependency on one of the `names` packages, declared in this crate's
+	/// manifest and present on disk, without asking cargo.
+	fn find_path_dependency(&self, names: &[&str]) -> Result<Option<Self>> {
+		let manifest_content = fs::read_to_string(self.manifest_path()?)?;
+		let manifest: cargo_toml::Manifest = cargo_toml::Manifest::from_str(&manifest_content)
+			.map_err(|e| RipdocError::ManifestParse(e.to_string()))?;
+
+		let declared = manifest
+			.dependencies
+			.iter()
+			.chain(&manifest.dev_dependencies)
+			.chain(&manifest.build_dependencies);
+		for (name, dependency) in declared {
+			if !names.contains(&dependency.package().unwrap_or(name)) {
+				continue;
+			}
+			let Some(path) = dependency
+				.detail()
+				.and_then(|detail| detail.path.as_deref())
+			else {
+				continue;
+			};
+			let dir = self.as_path().join(path);
+			if dir.join("Cargo.toml").is_file() {
+				return Ok(Some(Self::Path(dir)));
+			}
+		}
+		Ok(None)
+	}
+
+	/// List the package names of the direct `[dependencies]` declared in this crate's manifest.
+	///
+	/// Renamed dependencies report the package they point at rather than their local alias.
+	pub fn direct_dependencies(&self) -> Result<Vec<String>> {
+		let manifest_content = fs::read_to_string(self.manifest_path()?)?;
+		let manifest: cargo_toml::Manifest = cargo_toml::Manifest::from_str(&manifest_content)
+			.map_err(|e| RipdocError::ManifestParse(e.to_string()))?;
+
+		Ok(manifest
+			.dependencies
+			.iter()
+			.map(|(name, dependency)| dependency.package().unwrap_or(name).to_string())
+			.collect())
+	}
+
+	/// List the features declared in this crate's manifest, each with the features and
+	/// dependencies it enables.
+	pub fn features(&self) -> Result<BTreeMap<String, Vec<String>>> {
+		let manifest_content = fs::read_to_string(self.manifest_path()?)?;
+		let manifest: cargo_toml::Manifest = cargo_toml::Manifest::from_str(&manifest_content)
+			.map_err(|e| RipdocError::ManifestParse(e.to_string()))?;
+
+		Ok(manifest.features)
+	}
+
+	/// Fail with cargo's own error if resolving this package would have to rewrite `Cargo.lock`.
+	///
+	/// Runs `cargo metadata --locked` (plus `--offline` when requested), which resolves the full
+	/// dependency graph against the existing lockfile without writing it.
+	pub fn check_lockfile(&self, offline: bool) -> Result<()> {
+		let mut options = vec!["--locked".to_string()];
+		if offline {
+			options.push("--offline".to_string());
+		}
+		cargo_metadata::MetadataCommand::new()
+			.manifest_path(self.manifest_path()?)
+			.other_options(options)
+			.exec()
+			.map_err(|err| RipdocError::Generate(format!("Cargo.lock check failed: {err}")))?;
+		Ok(())
+	}
+
 	/// Walk upwards from `start_dir` to locate the closest `Cargo.toml`.
 	pub fn nearest_manifest(start_dir: &Path) -> Option<Self> {
 		let mut current_dir = start_dir.to_path_buf();
@@ -235,7 +493,7 @@ impl CargoPath {
 		None
 	}
 
-	/// Find a package in the current workspace by name.
+	/// Find a package in the current workspace by its package name or its library's name.
 	pub(super) fn find_workspace_package(
 		&self,
 		module_name: &str,
@@ -255,12 +513,15 @@ impl CargoPath {
 			.map_err(|err| RipdocError::Generate(format!("Failed to get cargo metadata: {err}")))?;
 
 		for package in metadata.workspace_packages() {
-			if package.name == module_name || package.name == alt_name {
+			let names_lib = package.targets.iter().any(|target| {
+				target.is_lib() && (target.name == module_name || target.name == alt_name)
+			});
+			if package.name == module_name || package.name == alt_name || names_lib {
 				let package_path = package.manifest_path.parent().unwrap().to_path_buf().into();
-				return Ok(Some(super::resolved_target::ResolvedTarget::new(
-					Self::Path(package_path),
-					&[],
-				)));
+				return Ok(Some(
+					super::resolved_target::ResolvedTarget::new(Self::Path(package_path), &[])
+						.with_source(SourceKind::WorkspaceMember),
+				));
 			}
 		}
 		Ok(None)
@@ -286,11 +547,101 @@ impl CargoPath {
 	}
 }
 
+/// Inputs that determine a rustdoc JSON build for a [`CargoPath`].
+pub(crate) struct BuildInputs {
+	/// Absolute manifest path handed to cargo.
+	pub(crate) manifest_path: PathBuf,
+	/// Package name from the manifest, if it has a package section.
+	pub(crate) name: Option<String>,
+	/// Package version from the manifest, if it has a package section.
+	pub(crate) version: Option<String>,
+	/// `name-version` label used in the cache key and diagnostics.
+	pub(crate) package_info: String,
+	/// Target rustdoc documents.
+	pub(crate) target: PlannedTarget,
+	/// Cache key for the resulting JSON.
+	pub(crate) cache_key: crate::cache::CacheKey,
+}
+
+/// The `cargo rustdoc` invocation generating rustdoc JSON for `target`, run in the environment
+/// `options` names.
+fn rustdoc_command(
+	manifest_path: &Path,
+	target: &PlannedTarget,
+	options: &BuildOptions,
+) -> Command {
+	let mut command = Command::new("cargo");
+	if let Some(toolchain) = rustdoc_toolchain(options.toolchain, super::is_rustup_available()) {
+		command.arg(format!("+{toolchain}"));
+	}
+	command
+		.arg("rustdoc")
+		.arg("--manifest-path")
+		.arg(manifest_path);
+	match target {
+		PlannedTarget::Bin(name) => command.args(["--bin", name]),
+		PlannedTarget::Lib | PlannedTarget::Json => command.arg("--lib"),
+	};
+	if let Some(target_triple) = options.target_triple {
+		command.args(["--target", target_triple]);
+	}
+	if options.no_default_features {
+		command.arg("--no-default-features");
+	}
+	if options.all_features {
+		command.arg("--all-features");
+	}
+	if !options.features.is_empty() {
+		command.arg("--features").arg(options.features.join(","));
+	}
+	if options.verbosity.is_silent() {
+		command.arg("--quiet");
+	}
+	command.args([
+		"--",
+		"-Z",
+		"unstable-options",
+		"--output-format",
+		"json",
+		"--cap-lints",
+		"warn",
+	]);
+	if options.private_items {
+		command.arg("--document-private-items");
+	}
+	options.env.apply(&mut command);
+	command
+}
+
+/// Pick the toolchain passed to cargo for rustdoc JSON generation.
+///
+/// An explicit override always wins. Otherwise `nightly` is selected when rustup is available, and
+/// the default `cargo` on `PATH` is used as-is when it is not.
+pub fn rustdoc_toolchain(toolchain: Option<&str>, rustup_available: bool) -> Option<&str> {
+	match toolchain {
+		Some(toolchain) => Some(toolchain),
+		None if rustup_available => Some("nightly"),
+		None => None,
+	}
+}
+
+/// Root file of the library target of the package at `root` described by `manifest`.
+///
+/// A declared `[lib]` counts even when its file is missing, so cargo reports the problem.
+fn lib_root(manifest: &cargo_toml::Manifest, root: &Path) -> Option<PathBuf> {
+	let declared = manifest.lib.as_ref().and_then(|lib| lib.path.as_deref());
+	let path = PathBuf::from(declared.unwrap_or("src/lib.rs"));
+	(manifest.lib.is_some() || root.join(&path).is_file()).then_some(path)
+}
+
 #[cfg(test)]
 mod tests {
 	use tempfile::tempdir;
 
 	use super::*;
+	use crate::env::command_env;
+	use crate::interrupt::Interrupt;
+	use crate::verbosity::Verbosity;
 
 	#[test]
 	fn test_is_workspace() -> Result<()> {
@@ -319,4 +670,49 @@ version = "0.1.0"
 
 		Ok(())
 	}
+
+	#[test]
+	fn rustdoc_toolchain_prefers_override() {
+		assert_eq!(
+			rustdoc_toolchain(Some("nightly-2024-10-01"), true),
+			Some("nightly-2024-10-01")
+		);
+		assert_eq!(rustdoc_toolchain(Some("custom"), false), Some("custom"));
+		assert_eq!(rustdoc_toolchain(None, true), Some("nightly"));
+		assert_eq!(rustdoc_toolchain(None, false), None);
+	}
+
+	#[test]
+	fn rustdoc_builds_run_in_the_cargo_env() {
+		let env = CargoEnv {
+			overrides: vec![("RUSTDOCFLAGS".into(), Some("--cfg docsrs".into()))],
+			project_target_dir: false,
+		};
+		let command = rustdoc_command(
+			Path::new("/work/demo/Cargo.toml"),
+			&PlannedTarget::Lib,
+			&BuildOptions {
+				no_default_features: false,
+				all_features: false,
+				features: &[],
+				private_items: false,
+				toolchain: Some("nightly"),
+				target_triple: None,
+				verbosity: Verbosity::Quiet,
+				interrupt: &Interrupt::default(),
+				env: &env,
+			},
+		);
+
+		let scratch = crate::env::scratch_target_dir();
+		assert_eq!(
+			command_env(&command, "CARGO_TARGET_DIR"),
+			Some(Some(scratch.as_os_str()))
+		);
+		assert_eq!(command_env(&command, "RUSTC_WRAPPER"), Some(None));
+		assert_eq!(
+			command_env(&command, "RUSTDOCFLAGS"),
+			Some(Some("--cfg docsrs".as_ref()))
+		);
+	}
 }
@@ -0,0 +1,55 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// Target within a package that rustdoc documents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase", tag = "kind", content = "name")]
+pub enum PlannedTarget {
+	/// The package's library target.
+	Lib,
+	/// A binary target, identified by name.
+	Bin(String),
+	/// A pre-generated rustdoc JSON file, read without invoking cargo.
+	Json,
+}
+
+impl fmt::Display for PlannedTarget {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match self {
+			Self::Lib => write!(f, "lib"),
+			Self::Bin(name) => write!(f, "bin ({name})"),
+			Self::Json => write!(f, "rustdoc JSON file"),
+		}
+	}
+}
+
+/// Summary of how rustdoc JSON would be produced for a resolved target, without building it.
+#[derive(Debug, Clone, Serialize)]
+pub struct BuildPlan {
+	/// Package name from the manifest, or the file stem for pre-generated JSON.
+	pub package: String,
+	/// Package version from the manifest, if known.
+	pub version: Option<String>,
+	/// Package directory, or the JSON file read directly.
+	pub path: PathBuf,
+	/// Module path filter applied within the package.
+	pub filter: String,
+	/// Target rustdoc documents.
+	pub target: PlannedTarget,
+	/// Whether default features are disabled.
+	pub no_default_features: bool,
+	/// Whether all features are enabled.
+	pub all_features: bool,
+	/// Features enabled explicitly, sorted.
+	pub features: Vec<String>,
+	/// Whether private items are documented.
+	pub private_items: bool,
+	/// Toolchain passed to cargo, if any.
+	pub toolchain: Option<String>,
+	/// Target triple passed to cargo, if any.
+	pub target_triple: Option<String>,
+	/// Whether a cached rustdoc JSON build would be reused.
+	pub cache_hit: bool,
+}
@@ -1,50 +1,109 @@
-use std::io::Read;
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::process::Command;
-use std::{env, fs};
+use std::{env, fmt, fs};
 
-use semver::Version;
-use ureq::http;
+use semver::{Version, VersionReq};
+use serde::Deserialize;
 
 use super::path::CargoPath;
+use crate::env::CargoEnv;
 use crate::error::{Result, RipdocError};
-
-const CRATES_IO_API: &str = "https://crates.io/api/v1/crates";
-
-/// Download (or reuse a cached) crate from crates.io and expose it as a [`CargoPath`].
+use crate::interrupt::{Interrupt, Stage};
+use crate::target::exact_version;
+
+/// Sparse index of crates.io, queried unless cargo's config replaces it.
+const CRATES_IO_INDEX: &str = "sparse+https://index.crates.io/";
+
+/// Git index URL cargo uses for crates.io; version lookups go to [`CRATES_IO_INDEX`] instead.
+const CRATES_IO_GIT_INDEX: &str = "https://github.com/rust-lang/crates.io-index";
+
+/// Registry name given to an index URL passed directly, defined for cargo with `--config`.
+const URL_REGISTRY_NAME: &str = "ripdoc-registry";
+
+/// Longest chain of `replace-with` entries followed before giving up.
+const MAX_REPLACEMENTS: usize = 8;
+
+/// Download (or reuse a cached) crate from a registry and expose it as a [`CargoPath`].
+///
+/// `registry` names a registry from cargo's config or gives an index URL; without it crates.io
+/// is used, through any source replacement cargo is configured with.
+///
+/// `version` may be any requirement, as parsed by
+/// [`parse_version_req`](crate::target::parse_version_req); the newest unyanked
+/// release matching it is fetched, or the newest already cached release when offline. Offline
+/// and without a version, the newest cached release of any version is used. Names are matched
+/// with `-` and `_` interchangeable, as crates.io does.
+///
+/// `interrupt` stops the download once it runs too long or is cancelled, and `env` sets the
+/// environment `cargo fetch` runs in.
 pub fn fetch_registry_crate(
 	name: &str,
-	version: Option<&Version>,
+	version: Option<&VersionReq>,
 	offline: bool,
+	registry: Option<&str>,
+	interrupt: &Interrupt,
+	env: &CargoEnv,
 ) -> Result<CargoPath> {
-	let resolved_version = if let Some(version) = version {
-		version.to_string()
-	} else {
-		if offline {
-			return Err(RipdocError::Generate(format!(
-				"crate '{name}' requires an explicit version when running offline"
-			)));
-		}
-		fetch_latest_version(name)?
-	};
-
-	// Check if crate exists in cargo's cache
-	if let Some(cached_path) = find_in_cargo_cache(name, &resolved_version)? {
+	let registry = Registry::from_config(registry, &env::current_dir()?)?;
+	let exact = version.and_then(exact_version);
+
+	// A cached exact release is used as is, even if it has since been yanked
+	if let Some(version) = &exact
+		&& let Some(cached_path) = spellings(name)
+			.iter()
+			.find_map(|name| find_in_cargo_cache(name, &version.to_string()).transpose())
+			.transpose()?
+	{
 		return Ok(CargoPath::Path(cached_path));
 	}
 
 	if offline {
-		return Err(RipdocError::Generate(format!(
-			"crate '{name}'@{resolved_version} is not cached locally for offline use. \
-             Run without --offline or use `cargo fetch` first."
-		)));
+		let Some(version) = version else {
+			let Some((newest, path)) = cached_releases(name)?
+				.into_iter()
+				.max_by(|(a, _), (b, _)| a.cmp(b))
+			else {
+				return Err(RipdocError::NotCached {
+					name: name.to_string(),
+					version: None,
+					cached: Vec::new(),
+				});
+			};
+			log::warn!("using '{name}'@{newest}, the newest release cached for offline use");
+			return Ok(CargoPath::Path(path));
+		};
+		if let Some(path) = find_cached_match(name, version)? {
+			return Ok(CargoPath::Path(path));
+		}
+		return Err(RipdocError::NotCached {
+			name: name.to_string(),
+			version: Some(version.to_string()),
+			cached: list_cached_versions(name)?
+				.iter()
+				.map(Version::to_string)
+				.collect(),
+		});
+	}
+
+	interrupt.check(Stage::Fetch)?;
+	let (name, resolved_version) = match exact {
+		// Git indexes cannot be queried, so exact releases are fetched without a yank check
+		Some(version) if registry.sparse_base().is_err() => (name.to_string(), version),
+		_ => IndexVersions::fetch(name, &registry)?.select(version, &registry)?,
+	};
+	let resolved_version = resolved_version.to_string();
+
+	// Check if crate exists in cargo's cache
+	if let Some(cached_path) = find_in_cargo_cache(&name, &resolved_version)? {
+		return Ok(CargoPath::Path(cached_path));
 	}
 
 	// Use cargo fetch to download the crate
-	fetch_with_cargo(name, &resolved_version)?;
+	fetch_with_cargo(&name, &resolved_version, &registry, interrupt, env)?;
 
 	// Find it in the cache (it should be there now)
-	find_in_cargo_cache(name, &resolved_version)?
+	find_in_cargo_cache(&name, &resolved_version)?
 		.map(CargoPath::Path)
 		.ok_or_else(|| {
 			RipdocError::Generate(format!(
@@ -53,10 +112,348 @@ pub fn fetch_registry_crate(
 		})
 }
 
-fn fetch_latest_version(name: &str) -> Result<String> {
-	let url = format!("{CRATES_IO_API}/{name}");
-	let mut response = request(&url, name)?;
+/// A registry crates are looked up in and downloaded from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Registry {
+	/// Name shown in messages: the registry or source name from cargo's config, or the index URL.
+	pub label: String,
+	/// Registry name written as `registry = "<name>"` in generated manifests, or `None` for
+	/// crates.io, which cargo redirects through any configured source replacement by itself.
+	pub manifest_name: Option<String>,
+	/// Index URL as cargo spells it, including any `sparse+` prefix.
+	pub index: String,
+}
+
+impl Registry {
+	/// crates.io, without any source replacement.
+	pub fn crates_io() -> Self {
+		Self {
+			label: "crates-io".to_string(),
+			manifest_name: None,
+			index: CRATES_IO_INDEX.to_string(),
+		}
+	}
+
+	/// Pick the registry for `spec` using the cargo config that applies in `dir`.
+	///
+	/// `spec` is the name of a registry in cargo's config (`[registries.<name>]` or a
+	/// `CARGO_REGISTRIES_<NAME>_INDEX` variable) or an index URL. Without a spec, crates.io is
+	/// used, following `[source.crates-io] replace-with` to a configured mirror.
+	pub fn from_config(spec: Option<&str>, dir: &Path) -> Result<Self> {
+		let config = CargoConfig::load(dir)?;
+		match spec {
+			Some(url) if url.contains("://") => Ok(Self {
+				label: url.to_string(),
+				manifest_name: Some(URL_REGISTRY_NAME.to_string()),
+				index: url.to_string(),
+			}),
+			Some(name) => {
+				let index = config.registry_index(name).ok_or_else(|| {
+					RipdocError::Generate(format!(
+						"registry '{name}' is not defined in cargo's config; add \
+						 `[registries.{name}] index = \"...\"` or pass an index URL"
+					))
+				})?;
+				Ok(Self {
+					label: name.to_string(),
+					manifest_name: Some(name.to_string()),
+					index,
+				})
+			}
+			None => config.crates_io_replacement(),
+		}
+	}
+
+	/// Base URL of the sparse index, ending in `/`.
+	fn sparse_base(&self) -> Result<String> {
+		let index = self.index.strip_prefix("registry+").unwrap_or(&self.index);
+		let base = match index.strip_prefix("sparse+") {
+			Some(base) => base,
+			None if index.trim_end_matches('/') == CRATES_IO_GIT_INDEX => {
+				&CRATES_IO_INDEX["sparse+".len()..]
+			}
+			None => {
+				return Err(RipdocError::Generate(format!(
+					"{self} uses a git index, which ripdoc cannot query for the latest version; \
+					 name an explicit version such as `crate@1.0`"
+				)));
+			}
+		};
+		Ok(format!("{}/", base.trim_end_matches('/')))
+	}
+}
+
+impl fmt::Display for Registry {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		if self.label == self.index {
+			write!(f, "registry {}", self.index)
+		} else {
+			write!(f, "registry '{}' ({})", self.label, self.index)
+		}
+	}
+}
+
+/// The cargo config files that apply in a directory, highest precedence first.
+struct CargoConfig {
+	tables: Vec<toml::Table>,
+}
+
+impl CargoConfig {
+	/// Read `.cargo/config.toml` (or `.cargo/config`) from `dir` and its ancestors, then from
+	/// `CARGO_HOME`.
+	fn load(dir: &Path) -> Result<Self> {
+		let mut dirs: Vec<PathBuf> = dir.ancestors().map(|dir| dir.join(".cargo")).collect();
+		if let Ok(cargo_home) = get_cargo_home()
+			&& !dirs.contains(&cargo_home)
+		{
+			dirs.push(cargo_home);
+		}
+
+		let mut tables = Vec::new();
+		for dir in dirs {
+			let Some(file) = ["config.toml", "config"]
+				.into_iter()
+				.map(|name| dir.join(name))
+				.find(|file| file.is_file())
+			else {
+				continue;
+			};
+			let content = fs::read_to_string(&file)?;
+			let table = content.parse::<toml::Table>().map_err(|err| {
+				RipdocError::Generate(format!(
+					"Failed to parse cargo config {}: {err}",
+					file.display()
+				))
+			})?;
+			tables.push(table);
+		}
+		Ok(Self { tables })
+	}
+
+	/// The string at `path`, from the highest-precedence file that sets it.
+	fn get(&self, path: &[&str]) -> Option<String> {
+		self.tables.iter().find_map(|table| {
+			let (last, parents) = path.split_last()?;
+			let mut table = table;
+			for key in parents {
+				table = table.get(*key)?.as_table()?;
+			}
+			table.get(*last)?.as_str().map(str::to_string)
+		})
+	}
+
+	/// Index URL of the registry `name`, with cargo's environment override taking precedence.
+	fn registry_index(&self, name: &str) -> Option<String> {
+		let var = format!(
+			"CARGO_REGISTRIES_{}_INDEX",
+			name.to_uppercase().replace('-', "_")
+		);
+		env::var(var)
+			.ok()
+			.or_else(|| self.get(&["registries", name, "index"]))
+	}
+
+	/// crates.io, or the registry that `[source.crates-io] replace-with` redirects it to.
+	fn crates_io_replacement(&self) -> Result<Registry> {
+		let mut source = "crates-io".to_string();
+		for _ in 0..MAX_REPLACEMENTS {
+			let Some(next) = self.get(&["source", &source, "replace-with"]) else {
+				if source == "crates-io" {
+					return Ok(Registry::crates_io());
+				}
+				let index = self
+					.get(&["source", &source, "registry"])
+					.or_else(|| self.registry_index(&source))
+					.ok_or_else(|| {
+						RipdocError::Generate(format!(
+							"cargo source '{source}' replacing crates.io is not a registry; \
+							 ripdoc can only download crates from a registry index"
+						))
+					})?;
+				return Ok(Registry {
+					label: source,
+					manifest_name: None,
+					index,
+				});
+			};
+			source = next;
+		}
+		Err(RipdocError::Generate(
+			"cargo's `replace-with` entries for crates.io form a cycle".to_string(),
+		))
+	}
+}
+
+/// The releases of a crate listed in a registry index.
+#[derive(Debug)]
+struct IndexVersions {
+	/// Name the crate was published under, which may spell `-` and `_` differently from the
+	/// name that was asked for.
+	name: String,
+	/// Every parseable release, with whether it has been yanked.
+	releases: Vec<(Version, bool)>,
+}
+
+impl IndexVersions {
+	/// Read the releases of `name` from `registry`'s sparse index, trying the other spellings
+	/// of `-` and `_` when the name as given is not found.
+	fn fetch(name: &str, registry: &Registry) -> Result<Self> {
+		let base = registry.sparse_base()?;
+		let mut not_found = None;
+		for spelling in spellings(name) {
+			let url = format!("{base}{}", index_path(&spelling));
+			match read_index_file(&url, name, registry) {
+				Ok(body) => return Self::parse(&spelling, &body, registry),
+				Err(err @ RipdocError::ModuleNotFound(_)) => not_found = not_found.or(Some(err)),
+				Err(err) => return Err(err),
+			}
+		}
+		Err(not_found
+			.unwrap_or_else(|| RipdocError::ModuleNotFound(format!("'{name}' in {registry}"))))
+	}
+
+	/// Parse the index file `body` of the crate `name`.
+	fn parse(name: &str, body: &str, registry: &Registry) -> Result<Self> {
+		let mut published = name.to_string();
+		let mut releases = Vec::new();
+		for line in body.lines().filter(|line| !line.trim().is_empty()) {
+			let entry: IndexEntry = serde_json::from_str(line).map_err(|err| {
+				RipdocError::Generate(format!(
+					"Failed to parse index entry for '{name}' from {registry}: {err}"
+				))
+			})?;
+			if let Some(entry_name) = entry.name {
+				published = entry_name;
+			}
+			if let Ok(version) = Version::parse(&entry.vers) {
+				releases.push((version, entry.yanked));
+			}
+		}
+		Ok(Self {
+			name: published,
+			releases,
+		})
+	}
+
+	/// Unyanked releases, in no particular order.
+	fn available(&self) -> impl Iterator<Item = &Version> {
+		self.releases
+			.iter()
+			.filter(|(_, yanked)| !yanked)
+			.map(|(version, _)| version)
+	}
+
+	/// Pick the release to fetch for `req`, returning it with the published crate name.
+	///
+	/// Without a requirement this is the newest stable release, or the newest pre-release when
+	/// there is none. With one it is the newest unyanked release that matches; when only
+	/// yanked releases match, the error names the nearest release that can still be fetched.
+	fn select(self, req: Option<&VersionReq>, registry: &Registry) -> Result<(String, Version)> {
+		let Some(req) = req else {
+			let latest_stable = self
+				.available()
+				.filter(|version| version.pre.is_empty())
+				.max();
+			return match latest_stable.or_else(|| self.available().max()) {
+				Some(version) => Ok((self.name.clone(), version.clone())),
+				None => Err(RipdocError::Generate(format!(
+					"No unyanked releases of '{}' found in {registry}",
+					self.name
+				))),
+			};
+		};
+
+		if let Some(version) = self
+			.available()
+			.filter(|version| req.matches(version))
+			.max()
+		{
+			return Ok((self.name.clone(), version.clone()));
+		}
+		let yanked = self
+			.releases
+			.iter()
+			.filter(|(version, yanked)| *yanked && req.matches(version))
+			.map(|(version, _)| version)
+			.max();
+		match yanked {
+			Some(version) => Err(RipdocError::Yanked {
+				name: self.name.clone(),
+				version: version.to_string(),
+				nearest: self.nearest_available(version).map(Version::to_string),
+			}),
+			None => Err(RipdocError::ModuleNotFound(format!(
+				"'{}'@{req} in {registry}",
+				self.name
+			))),
+		}
+	}
+
+	/// The unyanked release closest to `version`: the newest one below it, or failing that the
+	/// oldest one above it.
+	fn nearest_available(&self, version: &Version) -> Option<&Version> {
+		self.available()
+			.filter(|candidate| *candidate < version)
+			.max()
+			.or_else(|| {
+				self.available()
+					.filter(|candidate| *candidate > version)
+					.min()
+			})
+	}
+}
+
+/// One line of a crate's file in a registry index.
+#[derive(Deserialize)]
+struct IndexEntry {
+	#[serde(default)]
+	name: Option<String>,
+	vers: String,
+	#[serde(default)]
+	yanked: bool,
+}
+
+/// `name` followed by its other spellings, with every `-` read as `_` and every `_` as `-`.
+fn spellings(name: &str) -> Vec<String> {
+	let mut spellings = vec![name.to_string()];
+	for spelling in [name.replace('-', "_"), name.replace('_', "-")] {
+		if !spellings.contains(&spelling) {
+			spellings.push(spelling);
+		}
+	}
+	spellings
+}
 
+/// Path of a crate's file within a registry index, such as `se/rd/serde`.
+fn index_path(name: &str) -> String {
+	let name = name.to_lowercase();
+	match name.len() {
+		1 => format!("1/{name}"),
+		2 => format!("2/{name}"),
+		3 => format!("3/{}/{name}", &name[..1]),
+		_ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+	}
+}
+
+/// Read a crate's index file over HTTP or, for `file://` indexes, from disk.
+fn read_index_file(url: &str, crate_name: &str, registry: &Registry) -> Result<String> {
+	let not_found = || RipdocError::ModuleNotFound(format!("'{crate_name}' in {registry}"));
+
+	if let Some(path) = url.strip_prefix("file://") {
+		return fs::read_to_string(path).map_err(|err| match err.kind() {
+			io::ErrorKind::NotFound => not_found(),
+			_ => RipdocError::Generate(format!(
+				"Failed to read {registry} for '{crate_name}': {err}"
+			)),
+		});
+	}
+
+	let mut response = ureq::get(url).call().map_err(|err| match err {
+		ureq::Error::StatusCode(404 | 410) => not_found(),
+		err => RipdocError::Generate(format!(
+			"Failed to reach {registry} for '{crate_name}': {err}"
+		)),
+	})?;
 	let mut body = String::new();
 	response
 		.body_mut()
@@ -64,37 +461,10 @@ fn fetch_latest_version(name: &str) -> Result<String> {
 		.read_to_string(&mut body)
 		.map_err(|err| {
 			RipdocError::Generate(format!(
-				"Failed to read crates.io response for '{name}': {err}"
+				"Failed to read {registry} response for '{crate_name}': {err}"
 			))
 		})?;
-
-	let value: serde_json::Value = serde_json::from_str(&body).map_err(|err| {
-		RipdocError::Generate(format!(
-			"Failed to parse crates.io metadata for '{name}': {err}"
-		))
-	})?;
-
-	let crate_info = value
-		.get("crate")
-		.and_then(|v| v.as_object())
-		.ok_or_else(|| {
-			RipdocError::Generate(format!("Malformed crates.io response for '{name}'"))
-		})?;
-
-	let max_stable = crate_info
-		.get("max_stable_version")
-		.and_then(|v| v.as_str())
-		.filter(|version| !version.is_empty());
-	let max_version = crate_info
-		.get("max_version")
-		.and_then(|v| v.as_str())
-		.ok_or_else(|| {
-			RipdocError::Generate(format!("Missing max_version for '{name}' on crates.io"))
-		})?;
-
-	let chosen = max_stable.unwrap_or(max_version).to_string();
-
-	Ok(chosen)
+	Ok(body)
 }
 
 /// Find a crate in cargo's registry cache
@@ -124,13 +494,79 @@ fn find_in_cargo_cache(name: &str, version: &str) -> Result<Option<PathBuf>> {
 	Ok(None)
 }
 
+/// The newest release of `name`, in any spelling, in cargo's registry cache that matches
+/// `req`.
+fn find_cached_match(name: &str, req: &VersionReq) -> Result<Option<PathBuf>> {
+	Ok(cached_releases(name)?
+		.into_iter()
+		.filter(|(version, _)| req.matches(version))
+		.max_by(|(a, _), (b, _)| a.cmp(b))
+		.map(|(_, path)| path))
+}
+
+/// Versions of `name`, in any spelling, unpacked in cargo's registry cache, oldest first and
+/// each listed once even when several registries hold it.
+pub fn list_cached_versions(name: &str) -> Result<Vec<Version>> {
+	let mut versions: Vec<Version> = cached_releases(name)?
+		.into_iter()
+		.map(|(version, _)| version)
+		.collect();
+	versions.sort();
+	versions.dedup();
+	Ok(versions)
+}
+
+/// Every release of `name`, in any spelling, unpacked in any registry's directory of cargo's
+/// cache, with the directory holding it.
+fn cached_releases(name: &str) -> Result<Vec<(Version, PathBuf)>> {
+	let registry_src = get_cargo_home()?.join("registry").join("src");
+	if !registry_src.exists() {
+		return Ok(Vec::new());
+	}
+
+	let names = spellings(name);
+	let mut releases = Vec::new();
+	for index_dir in fs::read_dir(&registry_src)? {
+		let index_dir = index_dir?.path();
+		if !index_dir.is_dir() {
+			continue;
+		}
+		for entry in fs::read_dir(&index_dir)? {
+			let crate_dir = entry?.path();
+			let Some(dir_name) = crate_dir.file_name().and_then(|name| name.to_str()) else {
+				continue;
+			};
+			let Some(version) = names.iter().find_map(|name| {
+				let version = dir_name.strip_prefix(name.as_str())?.strip_prefix('-')?;
+				Version::parse(version).ok()
+			}) else {
+				continue;
+			};
+			if crate_dir.join("Cargo.toml").exists() {
+				releases.push((version, crate_dir));
+			}
+		}
+	}
+	Ok(releases)
+}
+
 /// Use `cargo fetch` to download a crate into cargo's cache
-fn fetch_with_cargo(name: &str, version: &str) -> Result<()> {
+fn fetch_with_cargo(
+	name: &str,
+	version: &str,
+	registry: &Registry,
+	interrupt: &Interrupt,
+	env: &CargoEnv,
+) -> Result<()> {
 	// Create a temporary directory with a minimal Cargo.toml
 	let temp_dir = tempfile::tempdir()
 		.map_err(|err| RipdocError::Generate(format!("Failed to create temp directory: {err}")))?;
 
 	let manifest_path = temp_dir.path().join("Cargo.toml");
+	let dependency = match &registry.manifest_name {
+		Some(registry) => format!(r#"{{ version = "={version}", registry = "{registry}" }}"#),
+		None => format!(r#""={version}""#),
+	};
 	let manifest_content = format!(
 		r#"[package]
 name = "temp-fetch"
@@ -138,7 +574,7 @@ version = "0.0.0"
 edition = "2021"
 
 [dependencies]
-{name} = "={version}"
+{name} = {dependency}
 "#
 	);
 
@@ -153,24 +589,45 @@ edition = "2021"
 	fs::write(&lib_path, "")
 		.map_err(|err| RipdocError::Generate(format!("Failed to write src/lib.rs: {err}")))?;
 
-	// Run cargo fetch
-	let output = Command::new("cargo")
-		.arg("fetch")
-		.arg("--manifest-path")
-		.arg(&manifest_path)
-		.output()
-		.map_err(|err| RipdocError::Generate(format!("Failed to run cargo fetch: {err}")))?;
+	let mut command = fetch_command(&manifest_path, registry, env);
+	let output = interrupt
+		.output(&mut command, Stage::Fetch)
+		.map_err(|err| match err {
+			RipdocError::Generate(message) => {
+				RipdocError::Generate(format!("Failed to run cargo fetch: {message}"))
+			}
+			other => other,
+		})?;
 
 	if !output.status.success() {
 		let stderr = String::from_utf8_lossy(&output.stderr);
 		return Err(RipdocError::Generate(format!(
-			"cargo fetch failed for '{name}'@{version}: {stderr}"
+			"cargo fetch failed for '{name}'@{version} from {registry}: {stderr}"
 		)));
 	}
 
 	Ok(())
 }
 
+/// The `cargo fetch` invocation downloading the dependencies of `manifest_path`, run in `env`.
+///
+/// The registry is defined on the command line, so index URLs work without a config entry.
+fn fetch_command(manifest_path: &Path, registry: &Registry, env: &CargoEnv) -> Command {
+	let mut command = Command::new("cargo");
+	command
+		.arg("fetch")
+		.arg("--manifest-path")
+		.arg(manifest_path);
+	if let Some(manifest_name) = &registry.manifest_name {
+		let index = toml::Value::String(registry.index.clone());
+		command
+			.arg("--config")
+			.arg(format!("registries.{manifest_name}.index={index}"));
+	}
+	env.apply(&mut command);
+	command
+}
+
 fn get_cargo_home() -> Result<PathBuf> {
 	if let Some(cargo_home) = env::var_os("CARGO_HOME") {
 		return Ok(PathBuf::from(cargo_home));
@@ -184,40 +641,27 @@ fn get_cargo_home() -> Result<PathBuf> {
 	))
 }
 
-fn request(url: &str, crate_name: &str) -> Result<http::Response<ureq::Body>> {
-	ureq::get(url).call().map_err(|err| match err {
-		ureq::Error::StatusCode(404) => RipdocError::ModuleNotFound(crate_name.to_string()),
-		err => RipdocError::Generate(format!(
-			"Failed to reach crates.io for '{crate_name}': {err}"
-		)),
-	})
-}
-
 #[cfg(test)]
 mod tests {
+	use std::sync::Mutex;
+
 	use super::*;
+	use crate::env::command_env;
+	use crate::target::parse_version_req;
 
-	#[test]
-	fn offline_requires_version() {
-		let err = fetch_registry_crate("serde", None, true).unwrap_err();
-		assert!(
-			err.to_string().contains("requires an explicit version"),
-			"unexpected error {err}"
-		);
-	}
+	/// Serializes the tests that point `CARGO_HOME` somewhere else.
+	static CARGO_HOME_LOCK: Mutex<()> = Mutex::new(());
 
-	#[test]
-	fn get_cargo_home_respects_env() {
+	/// Run `f` with `CARGO_HOME` set to `cargo_home`, restoring it afterwards.
+	fn with_cargo_home<T>(cargo_home: &Path, f: impl FnOnce() -> T) -> T {
+		let _guard = CARGO_HOME_LOCK
+			.lock()
+			.unwrap_or_else(|poisoned| poisoned.into_inner());
 		let original = env::var_os("CARGO_HOME");
-		let tmp = tempfile::tempdir().unwrap();
-
 		unsafe {
-			env::set_var("CARGO_HOME", tmp.path());
+			env::set_var("CARGO_HOME", cargo_home);
 		}
-
-		let cargo_home = get_cargo_home().unwrap();
-		assert_eq!(cargo_home, tmp.path());
-
+		let result = f();
 		unsafe {
 			if let Some(original) = original {
 				env::set_var("CARGO_HOME", original);
@@ -225,6 +669,113 @@ mod tests {
 				env::remove_var("CARGO_HOME");
 			}
 		}
+		result
+	}
+
+	/// A cargo home whose registry cache holds the given `(registry, directory)` pairs, each
+	/// directory an unpacked crate with a manifest.
+	fn cargo_cache_fixture(crates: &[(&str, &str)]) -> tempfile::TempDir {
+		let cargo_home = tempfile::tempdir().unwrap();
+		for (registry, dir) in crates {
+			let crate_dir = cargo_home
+				.path()
+				.join("registry")
+				.join("src")
+				.join(registry)
+				.join(dir);
+			fs::create_dir_all(&crate_dir).unwrap();
+			fs::write(crate_dir.join("Cargo.toml"), "[package]\n").unwrap();
+		}
+		cargo_home
+	}
+
+	#[test]
+	fn offline_without_a_version_fails_when_nothing_is_cached() {
+		let cargo_home = cargo_cache_fixture(&[]);
+		let err = with_cargo_home(cargo_home.path(), || {
+			fetch_registry_crate(
+				"serde",
+				None,
+				true,
+				None,
+				&Interrupt::default(),
+				&CargoEnv::default(),
+			)
+			.unwrap_err()
+		});
+		assert!(
+			err.to_string()
+				.contains("crate 'serde' is not cached locally for offline use"),
+			"unexpected error {err}"
+		);
+	}
+
+	#[test]
+	fn offline_without_a_version_uses_the_newest_cached_release() {
+		let cargo_home = cargo_cache_fixture(&[
+			("index.crates.io-1949cf8c6b5b557f", "fixture_crate-1.2.0"),
+			("index.crates.io-1949cf8c6b5b557f", "fixture_crate-1.10.0"),
+			(
+				"index.crates.io-1949cf8c6b5b557f",
+				"fixture_crate_extra-9.0.0",
+			),
+			("corp-0123456789abcdef", "fixture-crate-1.9.0"),
+			("corp-0123456789abcdef", "fixture_crate-1.2.0"),
+		]);
+		with_cargo_home(cargo_home.path(), || {
+			let versions = list_cached_versions("fixture-crate").unwrap();
+			let versions: Vec<String> = versions.iter().map(Version::to_string).collect();
+			assert_eq!(versions, ["1.2.0", "1.9.0", "1.10.0"]);
+
+			let CargoPath::Path(path) = fetch_registry_crate(
+				"fixture_crate",
+				None,
+				true,
+				None,
+				&Interrupt::default(),
+				&CargoEnv::default(),
+			)
+			.unwrap() else {
+				panic!("expected a cached path");
+			};
+			assert!(path.ends_with("fixture_crate-1.10.0"), "{}", path.display());
+		});
+	}
+
+	#[test]
+	fn offline_misses_list_the_cached_versions() {
+		let cargo_home = cargo_cache_fixture(&[
+			("index.crates.io-1949cf8c6b5b557f", "fixture_crate-1.2.0"),
+			("index.crates.io-1949cf8c6b5b557f", "fixture_crate-2.0.0"),
+		]);
+		let err = with_cargo_home(cargo_home.path(), || {
+			let req = parse_version_req("3").unwrap();
+			fetch_registry_crate(
+				"fixture_crate",
+				Some(&req),
+				true,
+				None,
+				&Interrupt::default(),
+				&CargoEnv::default(),
+			)
+			.unwrap_err()
+		});
+		assert!(
+			err.to_string()
+				.contains("for offline use (cached versions: 1.2.0, 2.0.0)"),
+			"unexpected error {err}"
+		);
+
+		let empty = cargo_cache_fixture(&[]);
+		let versions = with_cargo_home(empty.path(), || list_cached_versions("fixture_crate"));
+		assert!(versions.unwrap().is_empty());
+	}
+
+	#[test]
+	fn get_cargo_home_respects_env() {
+		let tmp = tempfile::tempdir().unwrap();
+		let cargo_home = with_cargo_home(tmp.path(), || get_cargo_home().unwrap());
+		assert_eq!(cargo_home, tmp.path());
 	}
 
 	#[test]
@@ -232,4 +783,227 @@ mod tests {
 		let result = find_in_cargo_cache("nonexistent-crate-xyz", "99.99.99").unwrap();
 		assert!(result.is_none());
 	}
+
+	#[test]
+	fn index_paths_follow_cargo_layout() {
+		assert_eq!(index_path("a"), "1/a");
+		assert_eq!(index_path("io"), "2/io");
+		assert_eq!(index_path("syn"), "3/s/syn");
+		assert_eq!(index_path("Serde_JSON"), "se/rd/serde_json");
+	}
+
+	#[test]
+	fn sparse_index_fixture_resolves_latest_stable_release() {
+		let index = tempfile::tempdir().unwrap();
+		let crate_dir = index.path().join("se").join("rd");
+		fs::create_dir_all(&crate_dir).unwrap();
+		fs::write(
+			crate_dir.join("serde"),
+			[
+				r#"{"name":"serde","vers":"1.0.0","deps":[],"cksum":"","features":{},"yanked":false}"#,
+				r#"{"name":"serde","vers":"1.2.0","deps":[],"cksum":"","features":{},"yanked":false}"#,
+				r#"{"name":"serde","vers":"1.3.0","deps":[],"cksum":"","features":{},"yanked":true}"#,
+				r#"{"name":"serde","vers":"2.0.0-alpha.1","deps":[],"cksum":"","features":{},"yanked":false}"#,
+			]
+			.join("\n"),
+		)
+		.unwrap();
+
+		let url = format!("sparse+file://{}", index.path().display());
+		let registry = Registry::from_config(Some(&url), index.path()).unwrap();
+		assert_eq!(registry.manifest_name.as_deref(), Some(URL_REGISTRY_NAME));
+		let versions = IndexVersions::fetch("serde", &registry).unwrap();
+		let (name, latest) = versions.select(None, &registry).unwrap();
+		assert_eq!(
+			(name.as_str(), latest.to_string().as_str()),
+			("serde", "1.2.0")
+		);
+
+		let err = IndexVersions::fetch("missing-crate", &registry).unwrap_err();
+		assert!(matches!(err, RipdocError::ModuleNotFound(_)), "{err}");
+		assert!(err.to_string().contains(&url), "{err}");
+	}
+
+	#[test]
+	fn cargo_config_selects_replacement_and_named_registries() {
+		let project = tempfile::tempdir().unwrap();
+		let cargo_dir = project.path().join(".cargo");
+		fs::create_dir(&cargo_dir).unwrap();
+		fs::write(
+			cargo_dir.join("config.toml"),
+			r#"
+			[source.crates-io]
+			replace-with = "mirror"
+
+			[source.mirror]
+			registry = "sparse+https://mirror.example/index/"
+
+			[registries.corp]
+			index = "sparse+https://corp.example/index/"
+			"#,
+		)
+		.unwrap();
+		let nested = project.path().join("member");
+		fs::create_dir(&nested).unwrap();
+
+		let mirror = Registry::from_config(None, &nested).unwrap();
+		assert_eq!(mirror.label, "mirror");
+		assert_eq!(mirror.manifest_name, None);
+		assert_eq!(mirror.index, "sparse+https://mirror.example/index/");
+		assert_eq!(
+			mirror.sparse_base().unwrap(),
+			"https://mirror.example/index/"
+		);
+
+		let corp = Registry::from_config(Some("corp"), &nested).unwrap();
+		assert_eq!(corp.manifest_name.as_deref(), Some("corp"));
+		assert_eq!(corp.index, "sparse+https://corp.example/index/");
+
+		let err = Registry::from_config(Some("unconfigured-registry"), &nested).unwrap_err();
+		assert!(err.to_string().contains("unconfigured-registry"), "{err}");
+	}
+
+	#[test]
+	fn git_indexes_are_named_in_errors() {
+		assert_eq!(
+			Registry::crates_io().sparse_base().unwrap(),
+			"https://index.crates.io/"
+		);
+
+		let registry = Registry {
+			label: "corp".to_string(),
+			manifest_name: Some("corp".to_string()),
+			index: "https://git.example/index".to_string(),
+		};
+		let err = registry.sparse_base().unwrap_err();
+		assert!(err.to_string().contains("registry 'corp'"), "{err}");
+	}
+
+	#[test]
+	fn fetches_run_in_the_cargo_env() {
+		let registry = Registry {
+			label: "corp".to_string(),
+			manifest_name: Some("corp".to_string()),
+			index: "sparse+https://corp.example/index/".to_string(),
+		};
+		let env = CargoEnv {
+			overrides: vec![("CARGO_NET_GIT_FETCH_WITH_CLI".into(), Some("true".into()))],
+			project_target_dir: true,
+		};
+		let command = fetch_command(Path::new("/tmp/fetch/Cargo.toml"), &registry, &env);
+
+		let args: Vec<_> = command.get_args().collect();
+		assert_eq!(
+			args.last().copied(),
+			Some(r#"registries.corp.index="sparse+https://corp.example/index/""#.as_ref())
+		);
+		assert_eq!(command_env(&command, "RUSTC_WRAPPER"), Some(None));
+		assert_eq!(command_env(&command, "CARGO_TARGET_DIR"), None);
+		assert_eq!(
+			command_env(&command, "CARGO_NET_GIT_FETCH_WITH_CLI"),
+			Some(Some("true".as_ref()))
+		);
+	}
+
+	/// An index whose `fixture-crate` file lists `releases`, as `(version, yanked)` pairs.
+	fn index_fixture(releases: &[(&str, bool)]) -> (tempfile::TempDir, Registry) {
+		let index = tempfile::tempdir().unwrap();
+		let crate_dir = index.path().join("fi").join("xt");
+		fs::create_dir_all(&crate_dir).unwrap();
+		let lines: Vec<String> = releases
+			.iter()
+			.map(|(version, yanked)| {
+				format!(
+					r#"{{"name":"fixture_crate","vers":"{version}","deps":[],"cksum":"","features":{{}},"yanked":{yanked}}}"#
+				)
+			})
+			.collect();
+		fs::write(crate_dir.join("fixture_crate"), lines.join("\n")).unwrap();
+		let url = format!("sparse+file://{}", index.path().display());
+		let registry = Registry::from_config(Some(&url), index.path()).unwrap();
+		(index, registry)
+	}
+
+	#[test]
+	fn requirements_resolve_to_the_newest_matching_release() {
+		let (_index, registry) = index_fixture(&[
+			("1.37.2", false),
+			("1.38.0", false),
+			("1.38.1", false),
+			("1.38.2", true),
+			("1.39.0", false),
+			("2.0.0-rc.1", false),
+		]);
+		let select = |spec: &str| {
+			let req = parse_version_req(spec).unwrap();
+			// The name is looked up with `-` read as `_`, and reported as published
+			let versions = IndexVersions::fetch("fixture-crate", &registry).unwrap();
+			let (name, version) = versions.select(Some(&req), &registry).unwrap();
+			assert_eq!(name, "fixture_crate");
+			version.to_string()
+		};
+
+		assert_eq!(select("1"), "1.39.0");
+		assert_eq!(select("1.38"), "1.38.1");
+		assert_eq!(select("1.37"), "1.37.2");
+		assert_eq!(select("1.38.0"), "1.38.0");
+		assert_eq!(select("<1.38"), "1.37.2");
+		assert_eq!(select("2.0.0-rc.1"), "2.0.0-rc.1");
+		assert_eq!(select("^1"), "1.39.0");
+		assert_eq!(select("1.*"), "1.39.0");
+		assert_eq!(select("1.38.*"), "1.38.1");
+		assert_eq!(select(">=1.37, <1.39"), "1.38.1");
+
+		let versions = IndexVersions::fetch("fixture_crate", &registry).unwrap();
+		let req = parse_version_req("3").unwrap();
+		let err = versions.select(Some(&req), &registry).unwrap_err();
+		assert!(matches!(err, RipdocError::ModuleNotFound(_)), "{err}");
+	}
+
+	#[test]
+	fn yanked_versions_name_the_nearest_available_release() {
+		let (_index, registry) = index_fixture(&[
+			("0.9.0", false),
+			("1.0.0", true),
+			("1.0.1", true),
+			("1.1.0", false),
+		]);
+		let select = |spec: &str| {
+			let req = parse_version_req(spec).unwrap();
+			let versions = IndexVersions::fetch("fixture_crate", &registry).unwrap();
+			versions.select(Some(&req), &registry).unwrap_err()
+		};
+
+		let err = select("1.0.1");
+		assert!(
+			matches!(
+				&err,
+				RipdocError::Yanked { version, nearest: Some(nearest), .. }
+					if version == "1.0.1" && nearest == "0.9.0"
+			),
+			"{err:?}"
+		);
+		assert_eq!(
+			err.to_string(),
+			"'fixture_crate'@1.0.1 has been yanked; the nearest release still available is \
+			 0.9.0 (`fixture_crate@0.9.0`)"
+		);
+
+		// Only yanked releases match `1.0`, so the newest of them is reported
+		let err = select("1.0");
+		assert!(
+			err.to_string()
+				.starts_with("'fixture_crate'@1.0.1 has been yanked"),
+			"{err}"
+		);
+
+		let (_index, registry) = index_fixture(&[("1.0.0", true)]);
+		let req = parse_version_req("1.0.0").unwrap();
+		let versions = IndexVersions::fetch("fixture_crate", &registry).unwrap();
+		let err = versions.select(Some(&req), &registry).unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"'fixture_crate'@1.0.0 has been yanked and no other release is available"
+		);
+	}
 }
@@ -1,14 +1,25 @@
+use std::collections::BTreeMap;
 use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, Instant};
 use std::{env, fs};
 
 use rustdoc_types::Crate;
-use semver::Version;
+use semver::VersionReq;
 
-use super::path::CargoPath;
+use super::cache::is_cached;
+use super::crate_json::{CrateJson, parse_crate};
+use super::path::{CargoPath, rustdoc_toolchain};
+use super::plan::{BuildPlan, PlannedTarget};
 use super::registry::fetch_registry_crate;
+use super::source::{ResolvedInfo, SourceKind};
 use super::to_import_name;
+use crate::env::CargoEnv;
 use crate::error::{Result, RipdocError};
-use crate::target::{Entrypoint, Target};
+use crate::features::{ResolvedFeatures, resolved_features};
+use crate::interrupt::Interrupt;
+use crate::metrics::BuildMetrics;
+use crate::options::BuildOptions;
+use crate::target::{Entrypoint, Target, exact_version};
 
 /// A resolved Rust package or module target.
 #[derive(Debug)]
@@ -16,6 +27,18 @@ pub struct ResolvedTarget {
 	/// Package directory path (filesystem or temporary).
 	pub(super) package_path: CargoPath,
 
+	/// Pre-generated rustdoc JSON file read instead of invoking cargo, if any.
+	json_file: Option<PathBuf>,
+
+	/// Where the package comes from.
+	source: SourceKind,
+
+	/// Time spent downloading the package from a registry; zero for local packages.
+	fetch_time: Duration,
+
+	/// Version requirement a registry package was resolved from, if one was given.
+	requested: Option<VersionReq>,
+
 	/// Module path within the package, excluding the package name. E.g.,
 	/// "module::submodule::item". Empty string for package root. This might not necessarily match
 	/// the user's input.
@@ -23,6 +46,10 @@ pub struct ResolvedTarget {
 }
 
 enum TargetResolution {
+	JsonFile {
+		file: PathBuf,
+		extra_path: Vec<String>,
+	},
 	FileModule {
 		file: PathBuf,
 		extra_path: Vec<String>,
@@ -35,9 +62,13 @@ enum TargetResolution {
 		workspace: CargoPath,
 		extra_path: Vec<String>,
 	},
+	HybridRoot {
+		root: CargoPath,
+		extra_path: Vec<String>,
+	},
 	NamedCrate {
 		name: String,
-		version: Option<Version>,
+		version: Option<VersionReq>,
 		extra_path: Vec<String>,
 	},
 }
@@ -46,6 +77,19 @@ impl TargetResolution {
 	fn plan(target: Target) -> Result<Self> {
 		match target.entrypoint {
 			Entrypoint::Path(path) => {
+				if path.extension().is_some_and(|ext| ext == "json") {
+					if !path.is_file() {
+						return Err(RipdocError::InvalidTarget(format!(
+							"Rustdoc JSON file '{}' does not exist",
+							path.display()
+						)));
+					}
+					return Ok(Self::JsonFile {
+						file: path,
+						extra_path: target.path,
+					});
+				}
+
 				if path.is_file() && path.extension().is_some_and(|ext| ext == "rs") {
 					return Ok(Self::FileModule {
 						file: path,
@@ -53,8 +97,29 @@ impl TargetResolution {
 					});
 				}
 
+				// A manifest stands for the directory holding it, as with `--manifest-path`
+				let path = if path.file_name().is_some_and(|name| name == "Cargo.toml") {
+					if !path.is_file() {
+						return Err(RipdocError::InvalidTarget(format!(
+							"Manifest '{}' does not exist",
+							path.display()
+						)));
+					}
+					match path.parent() {
+						Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+						_ => PathBuf::from("."),
+					}
+				} else {
+					path
+				};
+
 				let cargo_path = CargoPath::Path(path.clone());
-				if cargo_path.is_package()? {
+				if cargo_path.is_hybrid_workspace()? {
+					Ok(Self::HybridRoot {
+						root: cargo_path,
+						extra_path: target.path,
+					})
+				} else if cargo_path.is_package()? {
 					Ok(Self::PackageDir {
 						package: cargo_path,
 						extra_path: target.path,
@@ -79,8 +144,17 @@ impl TargetResolution {
 		}
 	}
 
-	fn resolve(self, offline: bool) -> Result<ResolvedTarget> {
+	fn resolve(
+		self,
+		offline: bool,
+		registry: Option<&str>,
+		interrupt: &Interrupt,
+		env: &CargoEnv,
+	) -> Result<ResolvedTarget> {
 		match self {
+			Self::JsonFile { file, extra_path } => {
+				ResolvedTarget::from_json_file(file, &extra_path)
+			}
 			Self::FileModule { file, extra_path } => {
 				ResolvedTarget::from_rust_file(file, &extra_path)
 			}
@@ -104,25 +178,59 @@ impl TargetResolution {
 				}
 				let package_name = extra_path.remove(0);
 				if let Some(package) = workspace.find_workspace_package(&package_name)? {
-					Ok(ResolvedTarget::new(package.package_path, &extra_path))
+					Ok(ResolvedTarget::new(package.package_path, &extra_path)
+						.with_source(SourceKind::WorkspaceMember))
 				} else {
 					Err(RipdocError::ModuleNotFound(format!(
 						"Package '{package_name}' not found in workspace"
 					)))
 				}
 			}
+			Self::HybridRoot {
+				root,
+				mut extra_path,
+			} => {
+				if let Some(first) = extra_path.first()
+					&& let Some(member) = root.find_workspace_package(first)?
+				{
+					extra_path.remove(0);
+					return Ok(ResolvedTarget::new(member.package_path, &extra_path)
+						.with_source(SourceKind::WorkspaceMember));
+				}
+				Ok(ResolvedTarget::new(root, &extra_path))
+			}
 			Self::NamedCrate {
 				name,
 				version,
 				extra_path,
-			} => ResolvedTarget::resolve_named_target(&name, version.as_ref(), &extra_path, offline),
+			} => ResolvedTarget::resolve_named_target(
+				&name,
+				version.as_ref(),
+				&extra_path,
+				offline,
+				registry,
+				interrupt,
+				env,
+			),
 		}
 	}
 }
 
 impl ResolvedTarget {
 	/// Build a `ResolvedTarget` with a normalised module filter path.
+	///
+	/// The package is already selected, so a first component naming it, by package or library
+	/// name, is dropped.
 	pub(super) fn new(path: CargoPath, components: &[String]) -> Self {
+		let components = match components {
+			[first, rest @ ..] if path.is_named(first) => rest,
+			_ => components,
+		};
+		Self::with_filter_components(path, components)
+	}
+
+	/// Build a `ResolvedTarget` filtering on `components` below the crate root.
+	fn with_filter_components(path: CargoPath, components: &[String]) -> Self {
 		let filter = if components.is_empty() {
 			String::new()
 		} else {
@@ -131,30 +239,212 @@ impl ResolvedTarget {
 			normalized_components.join("::")
 		};
 
+		let source = match path {
+			CargoPath::Path(_) => SourceKind::Path,
+			CargoPath::TempDir(_) => SourceKind::Temp,
+		};
 		Self {
 			package_path: path,
+			json_file: None,
+			source,
+			fetch_time: Duration::ZERO,
+			requested: None,
 			filter,
 		}
 	}
 
+	/// Record where the package comes from, when it is not apparent from the path alone.
+	pub(super) fn with_source(mut self, source: SourceKind) -> Self {
+		self.source = source;
+		self
+	}
+
+	/// Describe what the target resolved to: the package name and version from its manifest,
+	/// the version requirement it was picked for, where it comes from, its directory, and the
+	/// module filter.
+	pub fn info(&self) -> Result<ResolvedInfo> {
+		let (package, version) = match &self.json_file {
+			Some(file) => (
+				file.file_stem()
+					.map(|stem| stem.to_string_lossy().into_owned())
+					.unwrap_or_default(),
+				None,
+			),
+			None => {
+				let manifest = cargo_toml::Manifest::from_path(self.package_path.manifest_path()?)
+					.map_err(|err| RipdocError::ManifestParse(err.to_string()))?;
+				let package = manifest.package.ok_or_else(|| {
+					RipdocError::InvalidTarget(format!(
+						"'{}' has no [package] section",
+						self.package_root().display()
+					))
+				})?;
+				(package.name, package.version.get().ok().cloned())
+			}
+		};
+		Ok(ResolvedInfo {
+			package,
+			version,
+			requested: self
+				.requested
+				.as_ref()
+				.filter(|req| exact_version(req).is_none())
+				.map(VersionReq::to_string),
+			source: self.source,
+			path: self.package_root().to_path_buf(),
+			filter: self.filter.clone(),
+		})
+	}
+
 	/// Read the crate data for this resolved target using rustdoc JSON generation.
+	///
+	/// Targets naming a pre-generated JSON file are deserialized directly; the build options are
+	/// ignored because the file already fixes them.
 	pub fn read_crate(
 		&self,
-		no_default_features: bool,
-		all_features: bool,
-		features: Vec<String>,
-		private_items: bool,
-		silent: bool,
+		options: &BuildOptions,
 		cache_config: &crate::cache::CacheConfig,
 	) -> Result<Crate> {
-		self.package_path.read_crate(
+		self.read_crate_measured(options, cache_config)
+			.map(|(crate_data, _)| crate_data)
+	}
+
+	/// Read the crate data like [`Self::read_crate`], also measuring the rustdoc build and the
+	/// JSON it produced.
+	pub fn read_crate_measured(
+		&self,
+		options: &BuildOptions,
+		cache_config: &crate::cache::CacheConfig,
+	) -> Result<(Crate, BuildMetrics)> {
+		if let Some(file) = &self.json_file {
+			return read_json_file(file);
+		}
+		self.package_path.read_crate_measured(options, cache_config)
+	}
+
+	/// Read the crate data like [`Self::read_crate`], along with the rustdoc JSON file it came
+	/// from, for reuse by other tools; see [`CrateJson::path`].
+	///
+	/// Targets naming a pre-generated JSON file report that file.
+	pub fn read_crate_with_path(
+		&self,
+		options: &BuildOptions,
+		cache_config: &crate::cache::CacheConfig,
+	) -> Result<CrateJson> {
+		if let Some(file) = &self.json_file {
+			let (crate_data, _) = read_json_file(file)?;
+			return Ok(CrateJson {
+				crate_data,
+				path: Some(file.clone()),
+			});
+		}
+		self.package_path
+			.read_crate_with_path(options, cache_config)
+	}
+
+	/// Time spent downloading the package from a registry while resolving the target.
+	pub fn fetch_time(&self) -> Duration {
+		self.fetch_time
+	}
+
+	/// Describe how [`Self::read_crate`] would obtain rustdoc JSON, without building anything.
+	pub fn plan(
+		&self,
+		options: &BuildOptions,
+		cache_config: &crate::cache::CacheConfig,
+	) -> Result<BuildPlan> {
+		if let Some(file) = &self.json_file {
+			let package = file
+				.file_stem()
+				.map(|stem| stem.to_string_lossy().into_owned())
+				.unwrap_or_default();
+			return Ok(BuildPlan {
+				package,
+				version: None,
+				path: file.clone(),
+				filter: self.filter.clone(),
+				target: PlannedTarget::Json,
+				no_default_features: false,
+				all_features: false,
+				features: Vec::new(),
+				private_items: false,
+				toolchain: None,
+				target_triple: None,
+				cache_hit: false,
+			});
+		}
+
+		let inputs = self.package_path.build_inputs(options)?;
+		let toolchain = rustdoc_toolchain(options.toolchain, super::is_rustup_available());
+		Ok(BuildPlan {
+			package: inputs.name.unwrap_or(inputs.package_info),
+			version: inputs.version,
+			path: self.package_root().to_path_buf(),
+			filter: self.filter.clone(),
+			target: inputs.target,
+			no_default_features: options.no_default_features,
+			all_features: options.all_features,
+			features: inputs.cache_key.features.clone(),
+			private_items: options.private_items,
+			toolchain: toolchain.map(str::to_string),
+			target_triple: inputs.cache_key.target_triple.clone(),
+			cache_hit: is_cached(cache_config, &inputs.cache_key),
+		})
+	}
+
+	/// Resolve the direct dependencies of this target's package into targets of their own.
+	///
+	/// Dependencies are located with [`CargoPath::find_dependency`]; any that cannot be found are
+	/// skipped. Targets backed by a rustdoc JSON file have no manifest and yield no dependencies.
+	pub fn dependencies(&self, offline: bool) -> Result<Vec<Self>> {
+		if self.json_file.is_some() {
+			return Ok(Vec::new());
+		}
+
+		let mut dependencies = Vec::new();
+		for name in self.package_path.direct_dependencies()? {
+			match self.package_path.find_dependency(&name, offline)? {
+				Some((path, source)) => dependencies.push(Self::new(path, &[]).with_source(source)),
+				None => log::debug!("skipping dependency {name}: not found in cargo metadata"),
+			}
+		}
+		Ok(dependencies)
+	}
+
+	/// List the features declared by this target's package, each with what it enables.
+	///
+	/// Targets backed by a rustdoc JSON file have no manifest and yield no features.
+	pub fn features(&self) -> Result<BTreeMap<String, Vec<String>>> {
+		if self.json_file.is_some() {
+			return Ok(BTreeMap::new());
+		}
+		self.package_path.features()
+	}
+
+	/// Ask cargo which features this target's package is built with given the feature flags;
+	/// see [`resolved_features`].
+	///
+	/// Targets backed by a rustdoc JSON file have no manifest and yield `None`.
+	pub fn resolved_features(
+		&self,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+		offline: bool,
+		env: &CargoEnv,
+	) -> Result<Option<ResolvedFeatures>> {
+		if self.json_file.is_some() {
+			return Ok(None);
+		}
+		resolved_features(
+			&self.package_path.manifest_path()?,
 			no_default_features,
 			all_features,
 			features,
-			private_items,
-			silent,
-			cache_config,
+			offline,
+			env,
 		)
+		.map(Some)
 	}
 
 	/// Return the crate root on disk backing this target.
@@ -163,9 +453,31 @@ impl ResolvedTarget {
 	}
 
 	/// Resolve a `Target` into a fully-qualified location and filter path.
-	pub fn from_target(target: Target, offline: bool) -> Result<Self> {
+	///
+	/// `registry` selects the registry named targets are downloaded from; see
+	/// [`fetch_registry_crate`], which `interrupt` can stop and which runs cargo in `env`.
+	pub fn from_target(
+		target: Target,
+		offline: bool,
+		registry: Option<&str>,
+		interrupt: &Interrupt,
+		env: &CargoEnv,
+	) -> Result<Self> {
 		let resolution = TargetResolution::plan(target)?;
-		resolution.resolve(offline)
+		resolution.resolve(offline, registry, interrupt, env)
+	}
+
+	/// Resolve a target backed by a pre-generated rustdoc JSON file.
+	fn from_json_file(file: PathBuf, additional_path: &[String]) -> Result<Self> {
+		let file = fs::canonicalize(file)?;
+		let directory = file
+			.parent()
+			.ok_or_else(|| RipdocError::InvalidTarget("Invalid file path".to_string()))?
+			.to_path_buf();
+		let mut resolved =
+			Self::new(CargoPath::Path(directory), additional_path).with_source(SourceKind::Json);
+		resolved.json_file = Some(file);
+		Ok(resolved)
 	}
 
 	/// Resolve a module path starting from a specific Rust source file.
@@ -200,9 +512,16 @@ impl ResolvedTarget {
 			})
 			.collect();
 
-		// Remove "src" if it's the first component
-		if components.first().is_some_and(|c| c == "src") {
-			components.remove(0);
+		// The library's root file is the crate itself, and its directory holds the modules
+		let lib_root = cargo_path
+			.lib_root()?
+			.unwrap_or_else(|| PathBuf::from("src/lib.rs"));
+		if relative_path == lib_root {
+			components.clear();
+		} else if let Some(lib_dir) = lib_root.parent()
+			&& let Ok(inner) = relative_path.strip_prefix(lib_dir)
+		{
+			components.drain(..components.len() - inner.components().count());
 		}
 
 		// Remove the last component (file name) and add it back without the extension
@@ -215,65 +534,140 @@ impl ResolvedTarget {
 		// Combine the module path with the additional path
 		components.extend_from_slice(additional_path);
 
-		Ok(Self::new(cargo_path, &components))
+		// A module sharing the crate's name is still a module here
+		Ok(Self::with_filter_components(cargo_path, &components))
 	}
 
-	/// Create a resolved target backed by a cached download from crates.io.
+	/// Create a resolved target backed by a cached download from a registry.
 	fn from_registry_crate(
 		name: &str,
-		version: Option<&Version>,
+		version: Option<&VersionReq>,
 		path: &[String],
 		offline: bool,
+		registry: Option<&str>,
+		interrupt: &Interrupt,
+		env: &CargoEnv,
 	) -> Result<Self> {
-		let cargo_path = fetch_registry_crate(name, version, offline)?;
-		Ok(Self::new(cargo_path, path))
+		let started = Instant::now();
+		let cargo_path = fetch_registry_crate(name, version, offline, registry, interrupt, env)?;
+		let mut resolved = Self::new(cargo_path, path).with_source(SourceKind::Registry);
+		resolved.fetch_time = started.elapsed();
+		resolved.requested = version.cloned();
+		Ok(resolved)
 	}
 
 	fn resolve_named_target(
 		name: &str,
-		version: Option<&Version>,
+		version: Option<&VersionReq>,
 		path: &[String],
 		offline: bool,
+		registry: Option<&str>,
+		interrupt: &Interrupt,
+		env: &CargoEnv,
 	) -> Result<Self> {
 		if let Some(version) = version {
-			return Self::from_registry_crate(name, Some(version), path, offline);
+			return Self::from_registry_crate(
+				name,
+				Some(version),
+				path,
+				offline,
+				registry,
+				interrupt,
+				env,
+			);
 		}
 
 		let current_dir = env::current_dir()?;
 		if let Some(root) = CargoPath::nearest_manifest(&current_dir) {
 			if let Some(workspace_member) = root.find_workspace_package(name)? {
-				return Ok(Self::new(workspace_member.package_path, path));
+				return Ok(Self::new(workspace_member.package_path, path)
+					.with_source(SourceKind::WorkspaceMember));
 			}
 
-			if let Some(dependency) = root.find_dependency(name, offline)? {
-				return Ok(Self::new(dependency, path));
+			if let Some((dependency, source)) = root.find_dependency(name, offline)? {
+				return Ok(Self::new(dependency, path).with_source(source));
 			}
 		}
 
-		Self::from_registry_crate(name, None, path, offline)
+		Self::from_registry_crate(name, None, path, offline, registry, interrupt, env)
+	}
+}
+
+/// Deserialize a pre-generated rustdoc JSON file.
+fn read_json_file(file: &Path) -> Result<(Crate, BuildMetrics)> {
+	let content = fs::read_to_string(file)?;
+	let started = Instant::now();
+	let crate_data = parse_crate(&content).map_err(|err| match err {
+		RipdocError::Generate(message) => {
+			RipdocError::Generate(format!("{message} (in '{}')", file.display()))
+		}
+		other => other,
+	})?;
+	let metrics = BuildMetrics {
+		json_bytes: content.len() as u64,
+		parse: started.elapsed(),
+		..BuildMetrics::default()
+	};
+	Ok((crate_data, metrics))
+}
+
+/// Check that the lockfile governing `target_str` is up to date, as cargo's `--locked` would.
+///
+/// Path targets are checked from their nearest manifest and named targets from the current
+/// directory's. Rustdoc JSON files and registry downloads have no lockfile of the user's to
+/// protect, so they always pass.
+pub fn check_locked(target_str: &str, offline: bool) -> Result<()> {
+	let target = Target::parse(target_str)?;
+	let start = match &target.entrypoint {
+		Entrypoint::Path(path) if path.extension().is_some_and(|ext| ext == "json") => {
+			return Ok(());
+		}
+		Entrypoint::Path(path) if path.is_file() => {
+			path.parent().map(Path::to_path_buf).unwrap_or_default()
+		}
+		Entrypoint::Path(path) => path.clone(),
+		Entrypoint::Name {
+			version: Some(_), ..
+		} => return Ok(()),
+		Entrypoint::Name { version: None, .. } => env::current_dir()?,
+	};
+	let start = fs::canonicalize(&start).unwrap_or(start);
+
+	match CargoPath::nearest_manifest(&start) {
+		Some(package) => package.check_lockfile(offline),
+		None => Ok(()),
 	}
 }
 
 /// Resovles a target specification and returns a ResolvedTarget, pointing to the package
 /// directory. If necessary, construct temporary dummy crate to download packages from cargo.io.
 /// Parse a textual target specification into a `ResolvedTarget`.
-pub fn resolve_target(target_str: &str, offline: bool) -> Result<ResolvedTarget> {
+pub fn resolve_target(
+	target_str: &str,
+	offline: bool,
+	registry: Option<&str>,
+	interrupt: &Interrupt,
+	env: &CargoEnv,
+) -> Result<ResolvedTarget> {
 	let target = Target::parse(target_str)?;
 
 	match &target.entrypoint {
-		Entrypoint::Path(_) => ResolvedTarget::from_target(target, offline),
+		Entrypoint::Path(_) => {
+			ResolvedTarget::from_target(target, offline, registry, interrupt, env)
+		}
 		Entrypoint::Name {
 			name: _,
 			version: _,
 		} => {
-			let resolved = ResolvedTarget::from_target(target.clone(), offline)?;
+			let resolved =
+				ResolvedTarget::from_target(target.clone(), offline, registry, interrupt, env)?;
 			if !resolved.filter.is_empty() {
 				let first_component = resolved.filter.split("::").next().unwrap().to_string();
-				if let Some(cp) = resolved
+				if let Some((cp, source)) = resolved
 					.package_path
 					.find_dependency(&first_component, offline)?
 				{
-					Ok(ResolvedTarget::new(cp, &target.path))
+					Ok(ResolvedTarget::new(cp, &target.path).with_source(source))
 				} else {
 					Ok(resolved)
 				}
@@ -294,6 +688,7 @@ mod tests {
 	use tempfile::TempDir;
 
 	use super::*;
+	use crate::verbosity::Verbosity;
 
 	enum ExpectedResult {
 		Path(PathBuf),
@@ -361,6 +756,33 @@ mod tests {
 		.unwrap();
 		fs::create_dir_all(root.join("external")).unwrap();
 
+		// Create a package root that is also a workspace root
+		fs::create_dir_all(root.join("hybrid/src")).unwrap();
+		fs::create_dir_all(root.join("hybrid/member/src")).unwrap();
+		fs::write(
+			root.join("hybrid/Cargo.toml"),
+			r#"
+            [package]
+            name = "hybrid"
+            version = "0.1.0"
+            [workspace]
+            members = ["member"]
+            "#,
+		)
+		.unwrap();
+		fs::write(root.join("hybrid/src/lib.rs"), "// hybrid lib").unwrap();
+		fs::write(root.join("hybrid/src/module.rs"), "// hybrid module").unwrap();
+		fs::write(
+			root.join("hybrid/member/Cargo.toml"),
+			r#"
+            [package]
+            name = "member"
+            version = "0.1.0"
+            "#,
+		)
+		.unwrap();
+		fs::write(root.join("hybrid/member/src/lib.rs"), "// member lib").unwrap();
+
 		temp_dir
 	}
 
@@ -394,6 +816,22 @@ mod tests {
 				ExpectedResult::Path(root.join("workspace/pkg2")),
 				vec![],
 			),
+			(
+				Target {
+					entrypoint: Entrypoint::Path(root.join("workspace/pkg1/Cargo.toml")),
+					path: vec!["module".to_string()],
+				},
+				ExpectedResult::Path(root.join("workspace/pkg1")),
+				vec!["module".to_string()],
+			),
+			(
+				Target {
+					entrypoint: Entrypoint::Path(root.join("workspace/Cargo.toml")),
+					path: vec!["pkg2".to_string()],
+				},
+				ExpectedResult::Path(root.join("workspace/pkg2")),
+				vec![],
+			),
 			(
 				Target {
 					entrypoint: Entrypoint::Path(root.join("workspace/pkg1/src/module.rs")),
@@ -410,10 +848,40 @@ mod tests {
 				ExpectedResult::Path(root.join("standalone")),
 				vec!["module".to_string()],
 			),
+			(
+				Target {
+					entrypoint: Entrypoint::Path(root.join("hybrid")),
+					path: vec![],
+				},
+				ExpectedResult::Path(root.join("hybrid")),
+				vec![],
+			),
+			(
+				Target {
+					entrypoint: Entrypoint::Path(root.join("hybrid")),
+					path: vec!["member".to_string(), "module".to_string()],
+				},
+				ExpectedResult::Path(root.join("hybrid/member")),
+				vec!["module".to_string()],
+			),
+			(
+				Target {
+					entrypoint: Entrypoint::Path(root.join("hybrid")),
+					path: vec!["module".to_string()],
+				},
+				ExpectedResult::Path(root.join("hybrid")),
+				vec!["module".to_string()],
+			),
 		];
 
 		for (i, (target, expected_result, expected_filter)) in test_cases.into_iter().enumerate() {
-			let result = ResolvedTarget::from_target(target, true);
+			let result = ResolvedTarget::from_target(
+				target,
+				true,
+				None,
+				&Interrupt::default(),
+				&CargoEnv::default(),
+			);
 
 			match (result, expected_result) {
 				(Ok(resolved), ExpectedResult::Path(expected)) => {
@@ -485,7 +953,14 @@ mod tests {
 			path: vec![],
 		};
 
-		let resolved = ResolvedTarget::from_target(target, true).expect("workspace member");
+		let resolved = ResolvedTarget::from_target(
+			target,
+			true,
+			None,
+			&Interrupt::default(),
+			&CargoEnv::default(),
+		)
+		.expect("workspace member");
 		match resolved.package_path {
 			CargoPath::Path(path) => {
 				assert_eq!(
@@ -511,7 +986,14 @@ mod tests {
 			path: vec![],
 		};
 
-		let resolved = ResolvedTarget::from_target(target, true).expect("dependency");
+		let resolved = ResolvedTarget::from_target(
+			target,
+			true,
+			None,
+			&Interrupt::default(),
+			&CargoEnv::default(),
+		)
+		.expect("dependency");
 		match resolved.package_path {
 			CargoPath::Path(path) => {
 				assert_eq!(
@@ -524,7 +1006,354 @@ mod tests {
 	}
 
 	#[test]
-	fn registry_target_requires_version_offline() {
+	fn info_reports_source_kinds() {
+		let temp_dir = setup_test_structure();
+		let root = temp_dir.path();
+		let info = |target: Target| {
+			ResolvedTarget::from_target(
+				target,
+				true,
+				None,
+				&Interrupt::default(),
+				&CargoEnv::default(),
+			)
+			.unwrap()
+			.info()
+		};
+
+		let package = info(Target {
+			entrypoint: Entrypoint::Path(root.join("workspace/pkg1")),
+			path: vec!["module".to_string()],
+		})
+		.unwrap();
+		assert_eq!(package.package, "pkg1");
+		assert_eq!(package.version.as_deref(), Some("0.1.0"));
+		assert_eq!(package.source, SourceKind::Path);
+		assert_eq!(package.filter, "module");
+
+		let member = info(Target {
+			entrypoint: Entrypoint::Path(root.join("workspace")),
+			path: vec!["pkg2".to_string()],
+		})
+		.unwrap();
+		assert_eq!(member.package, "pkg2");
+		assert_eq!(member.source, SourceKind::WorkspaceMember);
+		assert_eq!(member.filter, "");
+
+		let _guard = DirGuard::change_to(&root.join("workspace/pkg2"));
+		let named = |name: &str| {
+			info(Target {
+				entrypoint: Entrypoint::Name {
+					name: name.to_string(),
+					version: None,
+				},
+				path: vec![],
+			})
+			.unwrap()
+		};
+		assert_eq!(named("pkg1").source, SourceKind::WorkspaceMember);
+		let dependency = named("standalone");
+		assert_eq!(dependency.package, "standalone");
+		assert_eq!(dependency.source, SourceKind::Path);
+		assert_eq!(
+			fs::canonicalize(&dependency.path).unwrap(),
+			fs::canonicalize(root.join("standalone")).unwrap()
+		);
+	}
+
+	#[test]
+	fn lib_name_and_path_come_from_the_manifest() {
+		let temp_dir = TempDir::new().unwrap();
+		let root = temp_dir.path().join("renamed");
+		fs::create_dir_all(root.join("lib")).unwrap();
+		fs::write(
+			root.join("Cargo.toml"),
+			r#"
+            [package]
+            name = "my-pkg"
+            version = "0.1.0"
+            [lib]
+            name = "totally_different"
+            path = "lib/mod.rs"
+            "#,
+		)
+		.unwrap();
+		fs::write(root.join("lib/mod.rs"), "pub mod inner;\npub struct Foo;").unwrap();
+		fs::write(root.join("lib/inner.rs"), "// inner module").unwrap();
+
+		let cargo_path = CargoPath::Path(root.clone());
+		assert_eq!(
+			cargo_path.lib_name().unwrap().as_deref(),
+			Some("totally_different")
+		);
+		assert_eq!(
+			cargo_path.lib_root().unwrap(),
+			Some(PathBuf::from("lib/mod.rs"))
+		);
+
+		let filter = |entrypoint: Entrypoint, path: &[&str]| {
+			let target = Target {
+				entrypoint,
+				path: path.iter().map(|c| c.to_string()).collect(),
+			};
+			ResolvedTarget::from_target(
+				target,
+				true,
+				None,
+				&Interrupt::default(),
+				&CargoEnv::default(),
+			)
+			.unwrap()
+			.filter
+		};
+		let dir = || Entrypoint::Path(root.clone());
+		assert_eq!(filter(dir(), &["Foo"]), "Foo");
+		assert_eq!(filter(dir(), &["my-pkg", "Foo"]), "Foo");
+		assert_eq!(filter(dir(), &["totally_different", "Foo"]), "Foo");
+		assert_eq!(filter(Entrypoint::Path(root.join("lib/mod.rs")), &[]), "");
+		assert_eq!(
+			filter(Entrypoint::Path(root.join("lib/inner.rs")), &[]),
+			"inner"
+		);
+
+		let _guard = DirGuard::change_to(&root);
+		let named = |name: &str| Entrypoint::Name {
+			name: name.to_string(),
+			version: None,
+		};
+		assert_eq!(filter(named("my-pkg"), &["Foo"]), "Foo");
+		assert_eq!(filter(named("totally_different"), &["Foo"]), "Foo");
+
+		let plan = ResolvedTarget::from_target(
+			Target {
+				entrypoint: dir(),
+				path: Vec::new(),
+			},
+			true,
+			None,
+			&Interrupt::default(),
+			&CargoEnv::default(),
+		)
+		.unwrap()
+		.plan(
+			&BuildOptions {
+				no_default_features: false,
+				all_features: false,
+				features: &[],
+				private_items: false,
+				toolchain: None,
+				target_triple: None,
+				verbosity: Verbosity::Normal,
+				interrupt: &Interrupt::default(),
+				env: &CargoEnv::default(),
+			},
+			&crate::cache::CacheConfig::disabled(),
+		)
+		.unwrap();
+		assert_eq!(plan.target, PlannedTarget::Lib);
+	}
+
+	#[test]
+	fn plan_describes_build_without_running_it() {
+		let temp_dir = setup_test_structure();
+		let root = temp_dir.path();
+		let target = Target {
+			entrypoint: Entrypoint::Path(root.join("workspace/pkg1")),
+			path: vec!["module".to_string()],
+		};
+
+		let resolved = ResolvedTarget::from_target(
+			target,
+			true,
+			None,
+			&Interrupt::default(),
+			&CargoEnv::default(),
+		)
+		.unwrap();
+		let features = vec!["b".to_string(), "a".to_string()];
+		let plan = resolved
+			.plan(
+				&BuildOptions {
+					no_default_features: true,
+					all_features: false,
+					features: &features,
+					private_items: false,
+					toolchain: Some("nightly-2024-10-01"),
+					target_triple: Some("thumbv7em-none-eabihf"),
+					verbosity: Verbosity::Normal,
+					interrupt: &Interrupt::default(),
+					env: &CargoEnv::default(),
+				},
+				&crate::cache::CacheConfig::disabled(),
+			)
+			.unwrap();
+
+		assert_eq!(plan.package, "pkg1");
+		assert_eq!(plan.version.as_deref(), Some("0.1.0"));
+		assert_eq!(plan.filter, "module");
+		assert_eq!(plan.target, PlannedTarget::Lib);
+		assert!(plan.no_default_features);
+		assert_eq!(plan.features, ["a", "b"]);
+		assert_eq!(plan.toolchain.as_deref(), Some("nightly-2024-10-01"));
+		assert_eq!(plan.target_triple.as_deref(), Some("thumbv7em-none-eabihf"));
+		assert!(!plan.cache_hit);
+	}
+
+	#[test]
+	fn dependencies_resolve_direct_path_dependencies() {
+		let temp_dir = setup_test_structure();
+		let root = temp_dir.path();
+		let target = Target {
+			entrypoint: Entrypoint::Path(root.join("workspace/pkg2")),
+			path: vec![],
+		};
+
+		let resolved = ResolvedTarget::from_target(
+			target,
+			true,
+			None,
+			&Interrupt::default(),
+			&CargoEnv::default(),
+		)
+		.unwrap();
+		let dependencies = resolved.dependencies(true).unwrap();
+		let roots: Vec<PathBuf> = dependencies
+			.iter()
+			.map(|dependency| fs::canonicalize(dependency.package_root()).unwrap())
+			.collect();
+		assert_eq!(roots, [fs::canonicalize(root.join("standalone")).unwrap()]);
+
+		let target = Target {
+			entrypoint: Entrypoint::Path(root.join("standalone")),
+			path: vec![],
+		};
+		let resolved = ResolvedTarget::from_target(
+			target,
+			true,
+			None,
+			&Interrupt::default(),
+			&CargoEnv::default(),
+		)
+		.unwrap();
+		assert!(resolved.dependencies(true).unwrap().is_empty());
+	}
+
+	#[test]
+	fn features_are_read_from_the_manifest() {
+		let temp_dir = setup_test_structure();
+		let root = temp_dir.path();
+		fs::write(
+			root.join("standalone/Cargo.toml"),
+			r#"
+            [package]
+            name = "standalone"
+            version = "0.1.0"
+
+            [features]
+            default = ["fast"]
+            fast = []
+            "#,
+		)
+		.unwrap();
+
+		let target = Target {
+			entrypoint: Entrypoint::Path(root.join("standalone")),
+			path: vec![],
+		};
+		let features = ResolvedTarget::from_target(
+			target,
+			true,
+			None,
+			&Interrupt::default(),
+			&CargoEnv::default(),
+		)
+		.unwrap()
+		.features()
+		.unwrap();
+		assert_eq!(features["default"], ["fast"]);
+		assert!(features["fast"].is_empty());
+		assert_eq!(features.len(), 2);
+	}
+
+	#[test]
+	fn check_locked_rejects_stale_lockfile() {
+		let temp_dir = setup_test_structure();
+		let root = temp_dir.path();
+		let pkg2 = root.join("workspace/pkg2");
+		let target = pkg2.to_str().unwrap();
+
+		// The lockfile predates pkg2's dependency on `standalone`.
+		fs::write(
+			root.join("workspace/Cargo.lock"),
+			r#"
+version = 4
+
+[[package]]
+name = "pkg1"
+version = "0.1.0"
+
+[[package]]
+name = "pkg2"
+version = "0.1.0"
+"#,
+		)
+		.unwrap();
+		let err = check_locked(target, true).unwrap_err();
+		assert!(
+			err.to_string().contains("--locked"),
+			"unexpected error: {err}"
+		);
+
+		// Resolving without `--locked` is free to rewrite the lockfile, after which it passes.
+		cargo_metadata::MetadataCommand::new()
+			.manifest_path(pkg2.join("Cargo.toml"))
+			.other_options(vec!["--offline".to_string()])
+			.exec()
+			.unwrap();
+		check_locked(target, true).unwrap();
+	}
+
+	#[test]
+	fn path_dependencies_resolve_offline_without_cargo_metadata() {
+		let temp_dir = setup_test_structure();
+		let root = temp_dir.path();
+		let app = root.join("app");
+		fs::create_dir_all(app.join("src")).unwrap();
+		fs::write(app.join("src/lib.rs"), "// app lib").unwrap();
+		// The registry dependency can't be resolved offline without a lockfile or a cached
+		// index, so any `cargo metadata` run would fail.
+		fs::write(
+			app.join("Cargo.toml"),
+			r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+            [dependencies]
+            standalone = { path = "../standalone" }
+            ripdoc-test-registry-only = "1"
+            "#,
+		)
+		.unwrap();
+
+		let package = CargoPath::Path(app);
+		let (dependency, source) = package
+			.find_dependency("standalone", true)
+			.expect("path dependency without cargo metadata")
+			.expect("standalone dependency");
+		assert_eq!(source, SourceKind::Path);
+		assert_eq!(
+			fs::canonicalize(dependency.as_path()).unwrap(),
+			fs::canonicalize(root.join("standalone")).unwrap()
+		);
+		assert!(
+			package
+				.find_dependency("ripdoc-test-registry-only", true)
+				.is_err()
+		);
+	}
+
+	#[test]
+	fn registry_target_offline_requires_a_cached_release() {
 		let temp_dir = setup_test_structure();
 		let root = temp_dir.path();
 		let _guard = DirGuard::change_to(&root.join("external"));
@@ -537,9 +1366,17 @@ mod tests {
 			path: vec![],
 		};
 
-		let err = ResolvedTarget::from_target(target, true).unwrap_err();
+		let err = ResolvedTarget::from_target(
+			target,
+			true,
+			None,
+			&Interrupt::default(),
+			&CargoEnv::default(),
+		)
+		.unwrap_err();
 		assert!(
-			err.to_string().contains("requires an explicit version"),
+			err.to_string()
+				.contains("is not cached locally for offline use"),
 			"unexpected error: {err}"
 		);
 	}
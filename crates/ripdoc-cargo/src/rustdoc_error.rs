@@ -1,49 +1,36 @@
-use super::is_rustup_available;
 use crate::error::RipdocError;
 
 /// Maximum number of characters from rustdoc stderr included in failure reports.
 const MAX_STDERR_CHARS: usize = 8_192;
 
-/// Translate a `rustdoc_json` build failure into a user-facing [`RipdocError`].
-pub fn map_rustdoc_build_error(
-	err: &rustdoc_json::BuildError,
-	captured_stderr: &[u8],
-	silent: bool,
-) -> RipdocError {
-	match err {
-		rustdoc_json::BuildError::BuildRustdocJsonError => {
-			format_rustdoc_failure(captured_stderr, silent)
-		}
-		other => {
-			let err_msg = other.to_string();
-			let stderr_str = String::from_utf8_lossy(captured_stderr);
-
-			if err_msg.contains("toolchain") && err_msg.contains("is not installed") {
-				let install_msg = if is_rustup_available() {
-					"run 'rustup toolchain install nightly'"
-				} else {
-					"ensure nightly Rust is installed and available in PATH"
-				};
-				return RipdocError::Generate(format!(
-					"ripdoc requires the nightly toolchain to be installed - {install_msg}"
-				));
-			}
-
-			// Check for nightly feature compatibility issues
-			if stderr_str.contains("unknown feature") || stderr_str.contains("E0635") {
-				return RipdocError::Generate(format!(
-					"Failed to build rustdoc JSON: This crate or its dependencies use unstable features that are not compatible with your current nightly toolchain.\n\
-                    \nOriginal error: {err_msg}"
-				));
-			}
-
-			if err_msg.contains("Failed to build rustdoc JSON") {
-				return format_rustdoc_failure(captured_stderr, silent);
-			}
-
-			RipdocError::Generate(format!("Failed to build rustdoc JSON: {err_msg}"))
-		}
+/// Maximum number of lines kept from cargo's report of a failed build script.
+const MAX_BUILD_SCRIPT_LINES: usize = 20;
+
+/// Phrases in a build script's output that mean it tried to reach the network and failed.
+const NETWORK_ERRORS: &[&str] = &[
+	"failed to lookup address",
+	"temporary failure in name resolution",
+	"could not resolve host",
+	"dns error",
+	"error sending request",
+	"connection refused",
+	"connection timed out",
+	"network is unreachable",
+];
+
+/// Translate a failed `cargo rustdoc` run into a user-facing [`RipdocError`], from what it wrote
+/// to stderr.
+pub fn map_rustdoc_build_error(captured_stderr: &[u8], silent: bool) -> RipdocError {
+	let stderr = String::from_utf8_lossy(captured_stderr);
+
+	// rustup fails before cargo starts when the toolchain is missing
+	if let Some(toolchain) = stderr.lines().find_map(missing_toolchain) {
+		return RipdocError::ToolchainMissing {
+			toolchain: toolchain.to_string(),
+		};
 	}
+
+	format_rustdoc_failure(captured_stderr, silent)
 }
 
 /// Format a detailed error for rustdoc build failures, optionally embedding diagnostics.
@@ -51,9 +38,38 @@ fn format_rustdoc_failure(captured_stderr: &[u8], silent: bool) -> RipdocError {
 	let stderr_raw = String::from_utf8_lossy(captured_stderr).into_owned();
 	let stderr_trimmed = stderr_raw.trim();
 
+	if let Some(message) = internal_compiler_error(stderr_trimmed) {
+		return RipdocError::BuildFailed(message);
+	}
+
+	if let Some((signal, name)) = killing_signal(stderr_trimmed) {
+		let mut message =
+			format!("Failed to build rustdoc JSON: rustdoc was killed by signal {signal} ({name})");
+		if signal == 9 {
+			message.push_str(
+				", which usually means it ran out of memory. Raise the memory limit, or document \
+				 less at once: drop `--all-features` and `--private`, build with \
+				 `--no-default-features`, or target a single workspace member.",
+			);
+		} else {
+			message.push('.');
+		}
+		return RipdocError::BuildFailed(message);
+	}
+
+	if let Some(target) = missing_target(stderr_trimmed) {
+		return RipdocError::TargetMissing {
+			target: target.to_string(),
+		};
+	}
+
+	if let Some(package) = failed_build_script(stderr_trimmed) {
+		return RipdocError::BuildFailed(build_script_failure(stderr_trimmed, package));
+	}
+
 	// Check for nightly feature compatibility issues
 	if stderr_trimmed.contains("unknown feature") || stderr_trimmed.contains("E0635") {
-		return RipdocError::Generate(
+		return RipdocError::BuildFailed(
             "Failed to build rustdoc JSON: This crate or its dependencies use unstable features that are not compatible with your current nightly toolchain.\n".to_string()
         );
 	}
@@ -65,7 +81,7 @@ fn format_rustdoc_failure(captured_stderr: &[u8], silent: bool) -> RipdocError {
 
 	if silent {
 		if stderr_trimmed.is_empty() {
-			return RipdocError::Generate(
+			return RipdocError::BuildFailed(
                 "Failed to build rustdoc JSON: rustdoc exited with an error but emitted no diagnostics. \
                  Re-run with --verbose or `cargo rustdoc` to inspect the failure.".to_string(),
             );
@@ -78,13 +94,128 @@ fn format_rustdoc_failure(captured_stderr: &[u8], silent: bool) -> RipdocError {
 		if truncated {
 			message.push_str("\n… output truncated …");
 		}
-		return RipdocError::Generate(message);
+		return RipdocError::BuildFailed(message);
+	}
+
+	RipdocError::BuildFailed(format!("Failed to build rustdoc JSON: {summary}"))
+}
+
+/// Describe an internal compiler error in rustdoc's stderr, naming the compiler version from
+/// the `note: rustc ... running on ...` line that accompanies the backtrace.
+fn internal_compiler_error(stderr: &str) -> Option<String> {
+	let ice_line = stderr.lines().map(str::trim).find(|line| {
+		line.contains("internal compiler error")
+			|| line.contains("the compiler unexpectedly panicked")
+	})?;
+	let version = stderr
+		.lines()
+		.find_map(|line| {
+			let rest = line.trim().strip_prefix("note: rustc ")?;
+			Some(rest.split(" running on").next().unwrap_or(rest).trim())
+		})
+		.unwrap_or("unknown version");
+	Some(format!(
+		"Failed to build rustdoc JSON: rustdoc crashed with an internal compiler error \
+		 (rustc {version}).\n{ice_line}\nThis is a toolchain bug rather than a problem in the \
+		 crate - try a different nightly, e.g. with `--toolchain nightly-YYYY-MM-DD`."
+	))
+}
+
+/// Signal number and name from cargo's `process didn't exit successfully: ... (signal: 9,
+/// SIGKILL: kill)` line, when rustdoc was killed rather than exiting on its own.
+fn killing_signal(stderr: &str) -> Option<(u32, &str)> {
+	stderr.lines().find_map(|line| {
+		let rest = line.split_once("(signal: ")?.1;
+		let (number, rest) = rest.split_once(',')?;
+		let name = rest.split(':').next()?.trim();
+		Some((number.trim().parse().ok()?, name))
+	})
+}
+
+/// Toolchain rustup reports as not installed, from `toolchain 'nightly-...' is not installed`.
+fn missing_toolchain(message: &str) -> Option<&str> {
+	let rest = message.split_once("toolchain '")?.1;
+	let (toolchain, tail) = rest.split_once('\'')?;
+	(!toolchain.is_empty() && tail.trim_start().starts_with("is not installed"))
+		.then_some(toolchain)
+}
+
+/// Target triple rustc reports as possibly not installed, e.g. after `can't find crate for core`.
+fn missing_target(stderr: &str) -> Option<&str> {
+	stderr.lines().find_map(|line| {
+		let rest = line.trim().strip_prefix("= note: the `")?;
+		let (target, tail) = rest.split_once('`')?;
+		tail.trim_start()
+			.starts_with("target may not be installed")
+			.then_some(target)
+	})
+}
+
+/// Package, with its version, whose build script failed, from cargo's `failed to run custom
+/// build command for `name v1.0.0`` line.
+fn failed_build_script(stderr: &str) -> Option<&str> {
+	stderr.lines().find_map(|line| {
+		let rest = line
+			.trim()
+			.strip_prefix("error: failed to run custom build command for `")?;
+		rest.split_once('`').map(|(package, _)| package)
+	})
+}
+
+/// Why a build script failed, when its output shows one of the common reasons.
+#[derive(Debug, PartialEq, Eq)]
+enum BuildScriptCause<'a> {
+	/// `pkg-config` could not find the named system library.
+	SystemLibrary(&'a str),
+	/// The script tried to download something and could not reach the network.
+	Network,
+	/// Anything else, such as a missing code generator.
+	Unknown,
+}
+
+/// Recognize why a build script failed from cargo's report of it.
+fn build_script_cause(stderr: &str) -> BuildScriptCause<'_> {
+	let library = stderr.lines().find_map(|line| {
+		// Older releases of the `pkg-config` crate quote the name, newer ones use backticks
+		if let Some(rest) = line.split_once("could not find system library '") {
+			return rest.1.split_once('\'').map(|(library, _)| library);
+		}
+		let rest = line.split_once("The system library `")?.1;
+		rest.split_once('`').map(|(library, _)| library)
+	});
+	if let Some(library) = library {
+		return BuildScriptCause::SystemLibrary(library);
 	}
+	let lowercase = stderr.to_lowercase();
+	if NETWORK_ERRORS.iter().any(|error| lowercase.contains(error)) {
+		return BuildScriptCause::Network;
+	}
+	BuildScriptCause::Unknown
+}
 
-	RipdocError::Generate(format!("Failed to build rustdoc JSON: {summary}"))
+/// Describe the failed build script of `package`: why it failed, when that is recognizable,
+/// followed by cargo's report of it.
+fn build_script_failure(stderr: &str, package: &str) -> String {
+	let reason = match build_script_cause(stderr) {
+		BuildScriptCause::SystemLibrary(library) => {
+			format!(" because the system library `{library}` was not found")
+		}
+		BuildScriptCause::Network => " because it could not reach the network".to_string(),
+		BuildScriptCause::Unknown => String::new(),
+	};
+	let mut message =
+		format!("Failed to build rustdoc JSON: the build script of `{package}` failed{reason}.");
+	if let Some(report) = extract_primary_diagnostic(stderr) {
+		message.push_str("\n\n");
+		message.push_str(&report);
+	}
+	message
 }
 
 /// Extract the first meaningful rustdoc diagnostic from the captured stderr stream.
+///
+/// A failed build script yields cargo's whole report of it, as gathered by
+/// [`build_script_block`].
 fn extract_primary_diagnostic(stderr: &str) -> Option<String> {
 	let mut lines = stderr.lines().peekable();
 
@@ -93,6 +224,13 @@ fn extract_primary_diagnostic(stderr: &str) -> Option<String> {
 			continue;
 		}
 
+		if line
+			.trim_start()
+			.starts_with("error: failed to run custom build command")
+		{
+			return Some(build_script_block(line, &mut lines));
+		}
+
 		let mut snippet = vec![line.trim_end().to_string()];
 
 		while let Some(peek) = lines.peek() {
@@ -134,6 +272,44 @@ fn extract_primary_diagnostic(stderr: &str) -> Option<String> {
 	None
 }
 
+/// Cargo's report of a failed build script, from its `error:` line `first` through the
+/// indented `Caused by:` block: the command that failed and what the script wrote to stderr.
+///
+/// The `cargo:` instructions the script printed to stdout are left out, and the rest is cut
+/// short after [`MAX_BUILD_SCRIPT_LINES`] lines, not counting the `--- stderr` marker.
+fn build_script_block<'a>(first: &str, lines: impl Iterator<Item = &'a str>) -> String {
+	let mut block = vec![first.trim_end().to_string()];
+	let mut in_stdout = false;
+	let mut kept = 0;
+	for line in lines {
+		let trimmed = line.trim();
+		if !line.starts_with([' ', '\t']) && !trimmed.is_empty() && trimmed != "Caused by:" {
+			break;
+		}
+		match trimmed {
+			"" => continue,
+			"--- stdout" => {
+				in_stdout = true;
+				continue;
+			}
+			"--- stderr" => {
+				in_stdout = false;
+				block.push(line.trim_end().to_string());
+				continue;
+			}
+			_ if in_stdout => continue,
+			_ => {}
+		}
+		if kept == MAX_BUILD_SCRIPT_LINES {
+			block.push("  …".to_string());
+			break;
+		}
+		block.push(line.trim_end().to_string());
+		kept += 1;
+	}
+	block.join("\n")
+}
+
 /// Determine whether a line introduces a new primary rustdoc error diagnostic.
 fn is_primary_error_line(line: &str) -> bool {
 	let trimmed = line.trim();
@@ -201,4 +377,229 @@ error: Compilation failed, aborting rustdoc
 		assert!(message.contains("src/lib.rs:3:9"));
 		assert!(message.contains("rustdoc stderr"));
 	}
+
+	#[test]
+	fn format_rustdoc_failure_reports_internal_compiler_errors() {
+		let stderr = br#"
+thread 'rustc' panicked at compiler/rustc_middle/src/ty/mod.rs:1042:9:
+index out of bounds: the len is 0 but the index is 0
+stack backtrace:
+   0:     0x7f3c1a2b4c5d - std::backtrace_rs::backtrace::libunwind::trace
+   1:     0x7f3c1a2b4c5d - rustdoc::core::run_global_ctxt
+
+error: internal compiler error: unexpected panic
+
+note: we would appreciate a bug report
+
+note: rustc 1.84.0-nightly (a1b2c3d4e 2024-10-20) running on x86_64-unknown-linux-gnu
+
+query stack during panic:
+end of query stack
+"#;
+		let message = format_rustdoc_failure(stderr, true).to_string();
+
+		assert!(message.contains("internal compiler error"), "{message}");
+		assert!(
+			message.contains("rustc 1.84.0-nightly (a1b2c3d4e 2024-10-20)"),
+			"{message}"
+		);
+		assert!(message.contains("different nightly"), "{message}");
+		assert!(!message.contains("stack backtrace"), "{message}");
+	}
+
+	#[test]
+	fn format_rustdoc_failure_suggests_memory_limits_when_killed() {
+		let stderr = b"error: could not document `huge`\n\nCaused by:\n  process didn't exit successfully: `rustdoc --crate-name huge src/lib.rs` (signal: 9, SIGKILL: kill)\n";
+		let message = format_rustdoc_failure(stderr, true).to_string();
+
+		assert!(message.contains("signal 9 (SIGKILL)"), "{message}");
+		assert!(message.contains("ran out of memory"), "{message}");
+		assert!(message.contains("--no-default-features"), "{message}");
+
+		let stderr = b"  process didn't exit successfully: `rustdoc` (signal: 11, SIGSEGV: invalid memory reference)\n";
+		let message = format_rustdoc_failure(stderr, false).to_string();
+		assert!(message.contains("signal 11 (SIGSEGV)"), "{message}");
+		assert!(!message.contains("out of memory"), "{message}");
+	}
+
+	#[test]
+	fn format_rustdoc_failure_suggests_installing_missing_target() {
+		let stderr = b"error[E0463]: can't find crate for `core`\n  |\n  = note: the `thumbv7em-none-eabihf` target may not be installed\n  = help: consider downloading the target with `rustup target add thumbv7em-none-eabihf`\n";
+		let err = format_rustdoc_failure(stderr, false);
+
+		assert!(
+			matches!(
+				&err,
+				RipdocError::TargetMissing { target } if target == "thumbv7em-none-eabihf"
+			),
+			"{err:?}"
+		);
+		assert!(
+			err.to_string()
+				.contains("'thumbv7em-none-eabihf' is not installed"),
+			"{err}"
+		);
+	}
+
+	#[test]
+	fn build_errors_name_a_missing_toolchain() {
+		let stderr = b"error: toolchain 'nightly-2024-10-01-x86_64-unknown-linux-gnu' is not \
+			installed\nhelp: run `rustup toolchain install nightly-2024-10-01` to install it\n";
+		let err = map_rustdoc_build_error(stderr, true);
+		assert!(
+			matches!(
+				&err,
+				RipdocError::ToolchainMissing { toolchain }
+					if toolchain == "nightly-2024-10-01-x86_64-unknown-linux-gnu"
+			),
+			"{err:?}"
+		);
+
+		let err = map_rustdoc_build_error(b"error: expected pattern, found `=`\n", true);
+		assert!(matches!(err, RipdocError::BuildFailed(_)), "{err:?}");
+	}
+
+	/// Cargo's report of `prost-build` failing to find `protoc`.
+	const PROTOC_FAILURE: &str = r#"
+   Compiling prost-build v0.12.3
+error: failed to run custom build command for `demo-proto v0.1.0 (/work/demo-proto)`
+
+Caused by:
+  process didn't exit successfully: `/tmp/target/debug/build/demo-proto-1f2e/build-script-build` (exit status: 1)
+  --- stdout
+  cargo:rerun-if-changed=proto/demo.proto
+  cargo:rerun-if-env-changed=PROTOC
+
+  --- stderr
+  Error: Custom { kind: NotFound, error: "Could not find `protoc`. If `protoc` is installed, try setting the `PROTOC` environment variable to the path of the `protoc` binary." }
+warning: build failed, waiting for other jobs to finish...
+"#;
+
+	#[test]
+	fn primary_diagnostic_keeps_build_script_stderr_without_stdout() {
+		let diagnostic = extract_primary_diagnostic(PROTOC_FAILURE).expect("a diagnostic");
+
+		assert!(
+			diagnostic.starts_with("error: failed to run custom build command for `demo-proto"),
+			"{diagnostic}"
+		);
+		assert!(diagnostic.contains("Caused by:"), "{diagnostic}");
+		assert!(diagnostic.contains("build-script-build"), "{diagnostic}");
+		assert!(
+			diagnostic.contains("Could not find `protoc`"),
+			"{diagnostic}"
+		);
+		assert!(
+			!diagnostic.contains("cargo:rerun-if-changed"),
+			"{diagnostic}"
+		);
+		assert!(
+			!diagnostic.contains("warning: build failed"),
+			"{diagnostic}"
+		);
+	}
+
+	#[test]
+	fn build_script_failures_name_the_package() {
+		let message = format_rustdoc_failure(PROTOC_FAILURE.as_bytes(), true).to_string();
+
+		assert!(
+			message.starts_with(
+				"Failed to build rustdoc JSON: the build script of `demo-proto v0.1.0 \
+				 (/work/demo-proto)` failed.\n\nerror: failed to run custom build command"
+			),
+			"{message}"
+		);
+		assert!(message.contains("Could not find `protoc`"), "{message}");
+		assert!(!message.contains("rustdoc stderr"), "{message}");
+	}
+
+	#[test]
+	fn build_script_failures_recognize_missing_system_libraries() {
+		let stderr = r#"
+error: failed to run custom build command for `openssl-sys v0.9.102`
+
+Caused by:
+  process didn't exit successfully: `/tmp/target/debug/build/openssl-sys-9a8b/build-script-main` (exit status: 101)
+  --- stdout
+  cargo:rerun-if-env-changed=OPENSSL_NO_PKG_CONFIG
+  run pkg_config fail: Could not run `PKG_CONFIG_ALLOW_SYSTEM_CFLAGS=1 pkg-config --libs --cflags openssl`
+  The pkg-config command could not be found.
+
+  --- stderr
+  thread 'main' panicked at build/find_normal.rs:190:5:
+  Could not find directory of OpenSSL installation, and this `-sys` crate cannot proceed without this knowledge.
+"#;
+		assert_eq!(build_script_cause(stderr), BuildScriptCause::Unknown);
+
+		let stderr = r#"
+error: failed to run custom build command for `libgit2-sys v0.16.2`
+
+Caused by:
+  --- stderr
+  error: could not find system library 'libgit2' required by the 'libgit2-sys' crate
+"#;
+		let message = format_rustdoc_failure(stderr.as_bytes(), false).to_string();
+		assert!(
+			message.contains(
+				"the build script of `libgit2-sys v0.16.2` failed because the system library \
+				 `libgit2` was not found."
+			),
+			"{message}"
+		);
+
+		let stderr = "  The system library `alsa` required by crate `alsa-sys` was not found.";
+		assert_eq!(
+			build_script_cause(stderr),
+			BuildScriptCause::SystemLibrary("alsa")
+		);
+	}
+
+	#[test]
+	fn build_script_failures_recognize_network_errors() {
+		let stderr = r#"
+error: failed to run custom build command for `onnxruntime-sys v0.0.14`
+
+Caused by:
+  process didn't exit successfully: `/tmp/target/debug/build/onnxruntime-sys-77c1/build-script-build` (exit status: 101)
+  --- stderr
+  thread 'main' panicked at build.rs:372:40:
+  Failed to download: error sending request for url (https://github.com/microsoft/onnxruntime/releases/download/v1.8.1/onnxruntime-linux-x64-1.8.1.tgz): error trying to connect: dns error: failed to lookup address information: Temporary failure in name resolution
+"#;
+		assert_eq!(build_script_cause(stderr), BuildScriptCause::Network);
+		let message = format_rustdoc_failure(stderr.as_bytes(), false).to_string();
+		assert!(
+			message.contains(
+				"the build script of `onnxruntime-sys v0.0.14` failed because it could not \
+				 reach the network."
+			),
+			"{message}"
+		);
+	}
+
+	#[test]
+	fn long_build_script_output_is_cut_short() {
+		let mut stderr = String::from(
+			"error: failed to run custom build command for `noisy v1.0.0`\n\nCaused by:\n  \
+			 --- stderr\n",
+		);
+		for line in 0..100 {
+			stderr.push_str(&format!("  line {line}\n"));
+		}
+		let diagnostic = extract_primary_diagnostic(&stderr).expect("a diagnostic");
+
+		assert!(diagnostic.contains("line 18"), "{diagnostic}");
+		assert!(!diagnostic.contains("line 19"), "{diagnostic}");
+		assert!(diagnostic.ends_with("  …"), "{diagnostic}");
+	}
+
+	#[test]
+	fn missing_toolchain_is_read_from_rustup_errors() {
+		assert_eq!(
+			missing_toolchain("error: toolchain 'nightly-2024-10-01' is not installed"),
+			Some("nightly-2024-10-01")
+		);
+		assert_eq!(missing_toolchain("toolchain '' is not installed"), None);
+		assert_eq!(missing_toolchain("could not compile"), None);
+	}
 }
@@ -0,0 +1,53 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+/// Where the package behind a resolved target comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SourceKind {
+	/// A member of the workspace the target was resolved from.
+	WorkspaceMember,
+	/// A local package, named by path or reached through a path dependency.
+	Path,
+	/// A package downloaded from a registry such as crates.io.
+	Registry,
+	/// A dependency checked out from a git repository.
+	Git,
+	/// A package generated in a temporary directory.
+	Temp,
+	/// A pre-generated rustdoc JSON file.
+	Json,
+}
+
+impl fmt::Display for SourceKind {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Self::WorkspaceMember => "workspace member",
+			Self::Path => "path",
+			Self::Registry => "registry",
+			Self::Git => "git",
+			Self::Temp => "temp",
+			Self::Json => "rustdoc JSON file",
+		})
+	}
+}
+
+/// What a target specification resolved to, without building anything.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResolvedInfo {
+	/// Package name from the manifest, or the file stem for pre-generated JSON.
+	pub package: String,
+	/// Package version from the resolved package's manifest, if known.
+	pub version: Option<String>,
+	/// Version requirement given with a registry target, such as `^1`, that `version` was
+	/// picked for; `None` when no version or an exact one was given.
+	pub requested: Option<String>,
+	/// Where the package comes from.
+	pub source: SourceKind,
+	/// Package directory; for pre-generated JSON, the directory holding the file.
+	pub path: PathBuf,
+	/// Normalized module path filter applied within the package, empty for the crate root.
+	pub filter: String,
+}
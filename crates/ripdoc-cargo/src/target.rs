@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use semver::Version;
+use semver::{Comparator, Op, Version, VersionReq};
 
 use crate::error::{Result, RipdocError};
 
@@ -13,8 +13,8 @@ pub enum Entrypoint {
 	Name {
 		/// Package or module name provided by the user.
 		name: String,
-		/// Optional package version requested with the target.
-		version: Option<Version>,
+		/// Optional package version requested with the target; see [`parse_version_req`].
+		version: Option<VersionReq>,
 	},
 }
 
@@ -37,9 +37,13 @@ pub enum Entrypoint {
 /// # Entrypoint Types
 ///
 /// - **File Path**: A path to a Rust file
+/// - **JSON File**: A path ending in `.json`, read as pre-generated rustdoc JSON
 /// - **Directory Path**: A path to a directory containing a Cargo.toml file
+/// - **Manifest Path**: A path to a `Cargo.toml`, naming the package in its directory as
+///   cargo's `--manifest-path` does
 /// - **Module**: A module name, typically starting with an uppercase letter
-/// - **Package**: A package name, optionally followed by '@' and a version number
+/// - **Package**: A package name, optionally followed by '@' and a version, which may be
+///   partial (`serde@1`) or a requirement (`serde@>=1.0.100, <1.0.150`)
 ///
 /// # Examples of valid target specifications:
 ///
@@ -47,10 +51,18 @@ pub enum Entrypoint {
 ///   - `src/lib.rs`
 ///   - `src/main.rs::my_module::MyStruct`
 ///
+/// - JSON files:
+///   - `target/doc/my_crate.json`
+///   - `my_crate.json::some_module::Item`
+///
 /// - Directory paths:
 ///   - `/path/to/my_project`
 ///   - `/path/to/my_project::some_module::function`
 ///
+/// - Manifest paths:
+///   - `Cargo.toml`
+///   - `workspace/pkg1/Cargo.toml::module`
+///
 /// - Modules:
 ///   - `MyModule`
 ///   - `MyModule::SubModule::function`
@@ -60,6 +72,7 @@ pub enum Entrypoint {
 ///   - `serde::Deserialize`
 ///   - `serde@1.0.104`
 ///   - `serde@1.0.104::Serialize`
+///   - `tokio@1.38`
 ///
 /// - Other examples:
 ///   - `tokio::sync::Mutex`
@@ -104,6 +117,8 @@ impl Target {
 
 		let entrypoint = if entrypoint.contains('/')
 			|| entrypoint.contains('\\')
+			|| entrypoint.ends_with(".json")
+			|| *entrypoint == "Cargo.toml"
 			|| *entrypoint == "."
 			|| *entrypoint == ".."
 		{
@@ -118,8 +133,7 @@ impl Target {
 				)));
 			}
 			let name = name_parts[0].to_string();
-			let version = Version::parse(name_parts[1])
-				.map_err(|e| RipdocError::InvalidTarget(format!("Invalid version: {e}")))?;
+			let version = parse_version_req(name_parts[1])?;
 			Entrypoint::Name {
 				name,
 				version: Some(version),
@@ -139,6 +153,50 @@ impl Target {
 	}
 }
 
+/// Parse the version given after `@` in a target.
+///
+/// A full version such as `1.0.104` means exactly that release. A partial one means the newest
+/// release it is a prefix of, so `1` matches any `1.x.y` and `1.38` any `1.38.y`. Anything else
+/// is read as a cargo version requirement, such as `^1.2`, `1.*` or `>=1.0, <1.5`.
+pub fn parse_version_req(spec: &str) -> Result<VersionReq> {
+	let invalid =
+		|err: semver::Error| RipdocError::InvalidTarget(format!("Invalid version: {err}"));
+	if let Ok(version) = Version::parse(spec) {
+		return Ok(VersionReq {
+			comparators: vec![Comparator {
+				op: Op::Exact,
+				major: version.major,
+				minor: Some(version.minor),
+				patch: Some(version.patch),
+				pre: version.pre,
+			}],
+		});
+	}
+	if !spec.is_empty() && spec.chars().all(|c| c.is_ascii_digit() || c == '.') {
+		return VersionReq::parse(&format!("~{spec}")).map_err(invalid);
+	}
+	VersionReq::parse(spec).map_err(invalid)
+}
+
+/// The single release `req` names, when it requires one exact version.
+pub fn exact_version(req: &VersionReq) -> Option<Version> {
+	match req.comparators.as_slice() {
+		[
+			Comparator {
+				op: Op::Exact,
+				major,
+				minor: Some(minor),
+				patch: Some(patch),
+				pre,
+			},
+		] => Some(Version {
+			pre: pre.clone(),
+			..Version::new(*major, *minor, *patch)
+		}),
+		_ => None,
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -189,6 +247,27 @@ mod tests {
 					path: vec!["some_module".to_string(), "function".to_string()],
 				}),
 			),
+			(
+				"my_crate.json::some_module",
+				Ok(Target {
+					entrypoint: Entrypoint::Path(PathBuf::from("my_crate.json")),
+					path: vec!["some_module".to_string()],
+				}),
+			),
+			(
+				"Cargo.toml",
+				Ok(Target {
+					entrypoint: Entrypoint::Path(PathBuf::from("Cargo.toml")),
+					path: vec![],
+				}),
+			),
+			(
+				"workspace/pkg1/Cargo.toml::module",
+				Ok(Target {
+					entrypoint: Entrypoint::Path(PathBuf::from("workspace/pkg1/Cargo.toml")),
+					path: vec!["module".to_string()],
+				}),
+			),
 			// Names (Modules or Packages)
 			(
 				"MyModule",
@@ -235,7 +314,7 @@ mod tests {
 				Ok(Target {
 					entrypoint: Entrypoint::Name {
 						name: "serde".to_string(),
-						version: Some(Version::parse("1.0.104").unwrap()),
+						version: Some(VersionReq::parse("=1.0.104").unwrap()),
 					},
 					path: vec![],
 				}),
@@ -245,11 +324,72 @@ mod tests {
 				Ok(Target {
 					entrypoint: Entrypoint::Name {
 						name: "serde".to_string(),
-						version: Some(Version::parse("1.0.104").unwrap()),
+						version: Some(VersionReq::parse("=1.0.104").unwrap()),
 					},
 					path: vec!["Serialize".to_string()],
 				}),
 			),
+			// Partial versions and requirements
+			(
+				"serde@1",
+				Ok(Target {
+					entrypoint: Entrypoint::Name {
+						name: "serde".to_string(),
+						version: Some(VersionReq::parse("~1").unwrap()),
+					},
+					path: vec![],
+				}),
+			),
+			(
+				"tokio@1.38::sync",
+				Ok(Target {
+					entrypoint: Entrypoint::Name {
+						name: "tokio".to_string(),
+						version: Some(VersionReq::parse("~1.38").unwrap()),
+					},
+					path: vec!["sync".to_string()],
+				}),
+			),
+			(
+				"serde@^1.0.100",
+				Ok(Target {
+					entrypoint: Entrypoint::Name {
+						name: "serde".to_string(),
+						version: Some(VersionReq::parse("^1.0.100").unwrap()),
+					},
+					path: vec![],
+				}),
+			),
+			(
+				"serde@^1",
+				Ok(Target {
+					entrypoint: Entrypoint::Name {
+						name: "serde".to_string(),
+						version: Some(VersionReq::parse("^1").unwrap()),
+					},
+					path: vec![],
+				}),
+			),
+			(
+				"serde@1.*::de",
+				Ok(Target {
+					entrypoint: Entrypoint::Name {
+						name: "serde".to_string(),
+						version: Some(VersionReq::parse("1.*").unwrap()),
+					},
+					path: vec!["de".to_string()],
+				}),
+			),
+			(
+				"tokio@>=1.30, <1.39",
+				Ok(Target {
+					entrypoint: Entrypoint::Name {
+						name: "tokio".to_string(),
+						version: Some(VersionReq::parse(">=1.30, <1.39").unwrap()),
+					},
+					path: vec![],
+				}),
+			),
 			// Complex paths
 			(
 				"tokio::sync::Mutex",
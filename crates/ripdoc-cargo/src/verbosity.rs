@@ -0,0 +1,32 @@
+use log::LevelFilter;
+
+/// Amount of diagnostic output emitted while generating and rendering documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Verbosity {
+	/// Report errors only.
+	Quiet,
+	/// Report warnings and errors, suppressing cargo output.
+	#[default]
+	Normal,
+	/// Forward cargo and rustdoc output as informational log records.
+	Verbose,
+	/// Additionally emit debug diagnostics such as cache activity.
+	Debug,
+}
+
+impl Verbosity {
+	/// Whether cargo and rustdoc output should be suppressed.
+	pub fn is_silent(self) -> bool {
+		self < Self::Verbose
+	}
+
+	/// The most detailed log level that should be displayed at this verbosity.
+	pub fn level_filter(self) -> LevelFilter {
+		match self {
+			Self::Quiet => LevelFilter::Error,
+			Self::Normal => LevelFilter::Warn,
+			Self::Verbose => LevelFilter::Info,
+			Self::Debug => LevelFilter::Debug,
+		}
+	}
+}
@@ -0,0 +1,278 @@
+//! ANSI highlighting of Rust skeletons and search matches for terminal output.
+//!
+//! The tokenizer is deliberately shallow: it only needs to tell comments, literals, keywords,
+//! types, and macros apart in the formatted skeletons ripdoc produces.
+
+use std::collections::BTreeSet;
+use std::ops::Range;
+
+use owo_colors::{OwoColorize, Style};
+
+/// Rust keywords, including the reserved ones that may appear as raw identifiers.
+const KEYWORDS: &[&str] = &[
+	"as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+	"false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+	"ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type", "union",
+	"unsafe", "use", "where", "while",
+];
+
+/// Style of search matches, drawn over any syntax color.
+fn match_style() -> Style {
+	Style::new().bright_red().bold()
+}
+
+/// Color `text` for a terminal, with Rust syntax colors when `syntax` is set and every
+/// occurrence of `query` in the match style on top of them.
+///
+/// Removing the escape sequences from the result gives back `text` unchanged.
+pub fn highlight(text: &str, syntax: bool, query: &str, case_sensitive: bool) -> String {
+	let spans = if syntax { tokenize(text) } else { Vec::new() };
+	let matches = find_matches(text, query, case_sensitive);
+
+	let mut bounds = BTreeSet::from([0, text.len()]);
+	for range in spans.iter().map(|(range, _)| range).chain(&matches) {
+		bounds.insert(range.start);
+		bounds.insert(range.end);
+	}
+	let bounds: Vec<usize> = bounds.into_iter().collect();
+
+	let mut output = String::with_capacity(text.len() * 2);
+	let (mut span_idx, mut match_idx) = (0, 0);
+	for window in bounds.windows(2) {
+		let (start, end) = (window[0], window[1]);
+		while spans
+			.get(span_idx)
+			.is_some_and(|(range, _)| range.end <= start)
+		{
+			span_idx += 1;
+		}
+		while matches
+			.get(match_idx)
+			.is_some_and(|range| range.end <= start)
+		{
+			match_idx += 1;
+		}
+		let style = if matches
+			.get(match_idx)
+			.is_some_and(|range| range.start <= start)
+		{
+			Some(match_style())
+		} else {
+			spans
+				.get(span_idx)
+				.filter(|(range, _)| range.start <= start)
+				.map(|(_, style)| *style)
+		};
+		let segment = &text[start..end];
+		match style {
+			Some(style) => output.push_str(&segment.style(style).to_string()),
+			None => output.push_str(segment),
+		}
+	}
+	output
+}
+
+/// Byte ranges of the non-overlapping occurrences of `query` in `text`.
+fn find_matches(text: &str, query: &str, case_sensitive: bool) -> Vec<Range<usize>> {
+	if query.is_empty() {
+		return Vec::new();
+	}
+	let (haystack, needle) = if case_sensitive {
+		(text.to_string(), query.to_string())
+	} else {
+		(text.to_lowercase(), query.to_lowercase())
+	};
+	haystack
+		.match_indices(&needle)
+		.map(|(start, _)| start..start + needle.len())
+		// Lowercasing can change byte lengths, which would misalign the ranges with `text`.
+		.filter(|range| text.is_char_boundary(range.start) && text.is_char_boundary(range.end))
+		.collect()
+}
+
+/// Split Rust source into colored ranges, in order; text between them stays uncolored.
+fn tokenize(source: &str) -> Vec<(Range<usize>, Style)> {
+	let mut spans = Vec::new();
+	let mut start = 0;
+	while let Some(first) = source[start..].chars().next() {
+		let rest = &source[start..];
+		let (len, style) = if rest.starts_with("//") {
+			(
+				rest.find('\n').unwrap_or(rest.len()),
+				Some(Style::new().bright_black()),
+			)
+		} else if rest.starts_with("/*") {
+			let len = rest.find("*/").map_or(rest.len(), |end| end + 2);
+			(len, Some(Style::new().bright_black()))
+		} else if let Some(len) = string_len(rest) {
+			(len, Some(Style::new().green()))
+		} else if first == '\'' {
+			quote_len(rest)
+		} else if first.is_alphabetic() || first == '_' {
+			word(rest)
+		} else if first.is_ascii_digit() {
+			(ident_len(rest), Some(Style::new().cyan()))
+		} else {
+			(first.len_utf8(), None)
+		};
+		if let Some(style) = style {
+			spans.push((start..start + len, style));
+		}
+		start += len;
+	}
+	spans
+}
+
+/// Length of the identifier-like run at the start of `text`.
+fn ident_len(text: &str) -> usize {
+	text.find(|c: char| !(c.is_alphanumeric() || c == '_'))
+		.unwrap_or(text.len())
+}
+
+/// Length and style of the keyword, type, macro, or plain identifier starting `text`.
+fn word(text: &str) -> (usize, Option<Style>) {
+	let len = ident_len(text);
+	let word = &text[..len];
+	if KEYWORDS.contains(&word) {
+		(len, Some(Style::new().magenta()))
+	} else if text[len..].starts_with('!') {
+		(len + 1, Some(Style::new().blue()))
+	} else if word.starts_with(char::is_uppercase) {
+		(len, Some(Style::new().yellow()))
+	} else {
+		(len, None)
+	}
+}
+
+/// Length and style of the character literal or lifetime starting `text` with `'`.
+fn quote_len(text: &str) -> (usize, Option<Style>) {
+	let body = &text[1..];
+	if let Some(escaped) = body.strip_prefix('\\') {
+		let len = escaped.find('\'').map_or(text.len(), |end| end + 3);
+		return (len, Some(Style::new().green()));
+	}
+	match body.chars().next() {
+		Some(c) if body[c.len_utf8()..].starts_with('\'') => {
+			(c.len_utf8() + 2, Some(Style::new().green()))
+		}
+		Some(_) => (1 + ident_len(body), Some(Style::new().cyan())),
+		None => (1, None),
+	}
+}
+
+/// Length of the string literal starting `text`, including byte and raw strings.
+fn string_len(text: &str) -> Option<usize> {
+	let prefix = text.find(|c: char| c != 'b' && c != 'r' && c != 'c')?;
+	let (prefix, body) = text.split_at(prefix);
+	if prefix.len() > 2 {
+		return None;
+	}
+	if prefix.contains('r') {
+		let hashes = body.len() - body.trim_start_matches('#').len();
+		let quoted = body[hashes..].strip_prefix('"')?;
+		let closing = format!("\"{}", "#".repeat(hashes));
+		let len = quoted
+			.find(&closing)
+			.map_or(quoted.len(), |end| end + closing.len());
+		return Some(prefix.len() + hashes + 1 + len);
+	}
+	let quoted = body.strip_prefix('"')?;
+	let mut escaped = false;
+	for (idx, c) in quoted.char_indices() {
+		match c {
+			'\\' if !escaped => escaped = true,
+			'"' if !escaped => return Some(prefix.len() + idx + 2),
+			_ => escaped = false,
+		}
+	}
+	Some(text.len())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Remove ANSI escape sequences from `text`.
+	fn strip_ansi(text: &str) -> String {
+		let mut plain = String::new();
+		let mut chars = text.chars();
+		while let Some(c) = chars.next() {
+			if c == '\x1b' {
+				chars.by_ref().find(|c| *c == 'm');
+			} else {
+				plain.push(c);
+			}
+		}
+		plain
+	}
+
+	const SOURCE: &str = "/// Docs with \"quotes\".\n\
+		pub fn parse<'a>(input: &'a str, sep: char) -> Result<Vec<&'a str>, Error> {}\n\
+		const SEP: char = ',';\n\
+		const RAW: &str = r#\"a \"raw\" string\"#;\n\
+		format_args!(\"{}\", 42);\n";
+
+	#[test]
+	fn highlighting_only_adds_escape_sequences() {
+		let highlighted = highlight(SOURCE, true, "parse", false);
+		assert_ne!(highlighted, SOURCE);
+		assert_eq!(strip_ansi(&highlighted), SOURCE);
+	}
+
+	#[test]
+	fn tokens_are_classified() {
+		// Compare styles by the escape sequences they produce.
+		let paint = |style: Style| "x".style(style).to_string();
+		let styled = |needle: &str| {
+			let start = SOURCE.find(needle).unwrap();
+			tokenize(SOURCE)
+				.into_iter()
+				.find(|(range, _)| range.start == start)
+				.map(|(range, style)| (&SOURCE[range], paint(style)))
+		};
+		let comment = paint(Style::new().bright_black());
+		let literal = paint(Style::new().green());
+		assert_eq!(
+			styled("/// Docs"),
+			Some(("/// Docs with \"quotes\".", comment))
+		);
+		assert_eq!(styled("pub"), Some(("pub", paint(Style::new().magenta()))));
+		assert_eq!(
+			styled("Result"),
+			Some(("Result", paint(Style::new().yellow())))
+		);
+		assert_eq!(styled("'a>"), Some(("'a", paint(Style::new().cyan()))));
+		assert_eq!(styled("','"), Some(("','", literal.clone())));
+		assert_eq!(styled("r#\""), Some(("r#\"a \"raw\" string\"#", literal)));
+		assert_eq!(
+			styled("format_args!"),
+			Some(("format_args!", paint(Style::new().blue())))
+		);
+		assert_eq!(styled("input"), None);
+	}
+
+	#[test]
+	fn matches_are_drawn_over_syntax_colors() {
+		let highlighted = highlight("pub fn parse() {}", true, "PARSE", false);
+		let painted = "parse".style(match_style()).to_string();
+		assert!(highlighted.contains(&painted), "{highlighted:?}");
+
+		// A match inside a keyword splits it, keeping the keyword color around the match.
+		let highlighted = highlight("struct", true, "ru", true);
+		assert_eq!(strip_ansi(&highlighted), "struct");
+		assert!(
+			highlighted.contains(&"ru".style(match_style()).to_string()),
+			"{highlighted:?}"
+		);
+		assert!(highlighted.contains(&"st".style(Style::new().magenta()).to_string()));
+	}
+
+	#[test]
+	fn matches_alone_leave_the_rest_uncolored() {
+		let highlighted = highlight("fn parse()", false, "parse", true);
+		assert_eq!(
+			highlighted,
+			format!("fn {}()", "parse".style(match_style()))
+		);
+	}
+}
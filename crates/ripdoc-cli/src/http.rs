@@ -0,0 +1,287 @@
+//! `ripdoc serve --http`: a blocking HTTP server answering `GET /search`, `GET /item/<path>`,
+//! and `GET /list` for one crate loaded at startup.
+//!
+//! Connections are handled one at a time on the calling thread. Every response is JSON; errors
+//! carry their message as `{"error": "..."}`.
+
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+
+use ripdoc_core::error::RipdocError;
+use ripdoc_core::{LoadedCrate, SearchConfig, SearchDomain, SearchOptions};
+use serde_json::{Value, json};
+
+use crate::serve::{list_item_json, listed_items, search_options, search_result_json, source_json};
+
+/// A request that could not be answered: the status to reply with and why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HttpError {
+	/// HTTP status code.
+	status: u16,
+	/// Human-readable description, sent as the body.
+	message: String,
+}
+
+impl HttpError {
+	fn bad_request(message: impl Into<String>) -> Self {
+		Self {
+			status: 400,
+			message: message.into(),
+		}
+	}
+
+	fn not_found(message: impl Into<String>) -> Self {
+		Self {
+			status: 404,
+			message: message.into(),
+		}
+	}
+}
+
+impl From<RipdocError> for HttpError {
+	fn from(err: RipdocError) -> Self {
+		Self {
+			status: 500,
+			message: err.to_string(),
+		}
+	}
+}
+
+/// Decoded parameters of a request's query string.
+struct Query(Vec<(String, String)>);
+
+impl Query {
+	/// Split `query` into `name=value` pairs, percent-decoding both and reading `+` as a space.
+	fn parse(query: &str) -> Self {
+		let pairs = query
+			.split('&')
+			.filter(|pair| !pair.is_empty())
+			.map(|pair| {
+				let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+				(percent_decode(name, true), percent_decode(value, true))
+			})
+			.collect();
+		Self(pairs)
+	}
+
+	fn get(&self, name: &str) -> Option<&str> {
+		self.0
+			.iter()
+			.find(|(key, _)| key == name)
+			.map(|(_, value)| value.as_str())
+	}
+
+	fn bool(&self, name: &str) -> Result<Option<bool>, HttpError> {
+		self.get(name)
+			.map(|value| match value {
+				"" | "true" | "1" => Ok(true),
+				"false" | "0" => Ok(false),
+				_ => Err(HttpError::bad_request(format!(
+					"`{name}` must be true or false"
+				))),
+			})
+			.transpose()
+	}
+
+	fn domains(&self) -> Result<Option<SearchDomain>, HttpError> {
+		let Some(list) = self.get("domains") else {
+			return Ok(None);
+		};
+		let names: Vec<&str> = list.split(',').map(str::trim).collect();
+		SearchDomain::from_str_list(&names)
+			.map(Some)
+			.map_err(|err| HttpError::bad_request(err.to_string()))
+	}
+}
+
+/// HTTP server answering queries about one loaded crate.
+pub struct HttpServer {
+	/// The crate every request is answered from.
+	loaded: LoadedCrate,
+	/// Search settings used for parameters a request leaves out.
+	search_defaults: SearchConfig,
+}
+
+impl HttpServer {
+	/// Create a server for `loaded` whose searches default to `search_defaults`.
+	pub fn new(loaded: LoadedCrate, search_defaults: SearchConfig) -> Self {
+		Self {
+			loaded,
+			search_defaults,
+		}
+	}
+
+	/// Answer connections on `listener` for as long as it accepts them.
+	///
+	/// A connection that fails is logged and dropped without stopping the server.
+	pub fn run(&self, listener: &TcpListener) -> io::Result<()> {
+		for stream in listener.incoming() {
+			let result = stream.and_then(|stream| self.handle_connection(&stream));
+			if let Err(err) = result {
+				log::debug!("connection failed: {err}");
+			}
+		}
+		Ok(())
+	}
+
+	/// Read one request from `stream` and write its response, then close the connection.
+	fn handle_connection(&self, stream: &TcpStream) -> io::Result<()> {
+		let mut reader = BufReader::new(stream);
+		let mut request_line = String::new();
+		reader.read_line(&mut request_line)?;
+		// Headers are not needed, but are read so the client is not reset mid-request.
+		let mut header = String::new();
+		while reader.read_line(&mut header)? > 0 && !header.trim().is_empty() {
+			header.clear();
+		}
+
+		let (status, body) = match self.respond(&request_line) {
+			Ok(body) => (200, body),
+			Err(err) => (err.status, json!({ "error": err.message })),
+		};
+		write_response(stream, status, &body)
+	}
+
+	/// Route a request line such as `GET /list HTTP/1.1` to its endpoint.
+	fn respond(&self, request_line: &str) -> Result<Value, HttpError> {
+		let mut parts = request_line.split_whitespace();
+		let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+			return Err(HttpError::bad_request("malformed request line"));
+		};
+		if method != "GET" {
+			return Err(HttpError::bad_request(format!(
+				"unsupported method `{method}`; only GET is served"
+			)));
+		}
+
+		let (path, query) = target.split_once('?').unwrap_or((target, ""));
+		let query = Query::parse(query);
+		match path {
+			"/search" => self.search(&query),
+			"/list" => self.list(&query),
+			_ => match path.strip_prefix("/item/") {
+				Some(item) => self.item(&percent_decode(item, false)),
+				None => Err(HttpError::not_found(format!("no endpoint at `{path}`"))),
+			},
+		}
+	}
+
+	/// `GET /search?q=...`: matching items and the skeleton rendered around them.
+	fn search(&self, query: &Query) -> Result<Value, HttpError> {
+		let options = self
+			.search_options(query)?
+			.ok_or_else(|| HttpError::bad_request("missing `q`"))?;
+		let response = self.loaded.search(&options)?;
+		let results: Vec<Value> = response.results.iter().map(search_result_json).collect();
+		Ok(json!({ "results": results, "rendered": response.rendered }))
+	}
+
+	/// `GET /list`: every item, or those matching `q` when given.
+	fn list(&self, query: &Query) -> Result<Value, HttpError> {
+		let options = self.search_options(query)?;
+		let items: Vec<Value> = listed_items(&self.loaded, options.as_ref())
+			.iter()
+			.map(list_item_json)
+			.collect();
+		Ok(json!({ "items": items }))
+	}
+
+	/// `GET /item/<path>`: the item at `path` below the crate root, rendered with what
+	/// encloses it.
+	fn item(&self, path: &str) -> Result<Value, HttpError> {
+		let entry = self
+			.loaded
+			.index()
+			.find_relative(path)
+			.ok_or_else(|| HttpError::not_found(format!("no item at `{path}`")))?;
+		let rendered = self.loaded.render_items(&[entry.item_id])?;
+		Ok(json!({
+			"kind": entry.kind.label(),
+			"path": entry.path_string,
+			"source": entry.source.as_ref().map(source_json),
+			"rendered": rendered,
+		}))
+	}
+
+	/// Search options for the `q` parameter and its tuning parameters, or `None` without `q`.
+	fn search_options(&self, query: &Query) -> Result<Option<SearchOptions>, HttpError> {
+		let Some(q) = query.get("q").map(str::trim).filter(|q| !q.is_empty()) else {
+			return Ok(None);
+		};
+		let requested = SearchConfig {
+			case_sensitive: query.bool("case_sensitive")?,
+			fuzzy: query.bool("fuzzy")?,
+			domains: query.domains()?,
+			direct_match_only: query.bool("direct_match_only")?,
+		};
+		Ok(Some(search_options(requested, &self.search_defaults, q)))
+	}
+}
+
+/// Write a complete JSON response and close the connection.
+fn write_response(mut stream: &TcpStream, status: u16, body: &Value) -> io::Result<()> {
+	let body = body.to_string();
+	let reason = match status {
+		200 => "OK",
+		400 => "Bad Request",
+		404 => "Not Found",
+		_ => "Internal Server Error",
+	};
+	write!(
+		stream,
+		"HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\n\
+		 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+		body.len()
+	)?;
+	stream.flush()
+}
+
+/// Decode `%XX` escapes in `text`, and `+` as a space when `plus_as_space` is set.
+///
+/// Malformed escapes are kept as written, and invalid UTF-8 is replaced.
+fn percent_decode(text: &str, plus_as_space: bool) -> String {
+	let bytes = text.as_bytes();
+	let mut decoded = Vec::with_capacity(bytes.len());
+	let mut i = 0;
+	while i < bytes.len() {
+		let escaped = bytes
+			.get(i + 1..i + 3)
+			.filter(|_| bytes[i] == b'%')
+			.and_then(|hex| std::str::from_utf8(hex).ok())
+			.and_then(|hex| u8::from_str_radix(hex, 16).ok());
+		match (escaped, bytes[i]) {
+			(Some(byte), _) => {
+				decoded.push(byte);
+				i += 3;
+				continue;
+			}
+			(None, b'+') if plus_as_space => decoded.push(b' '),
+			(None, byte) => decoded.push(byte),
+		}
+		i += 1;
+	}
+	String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn query_strings_are_percent_decoded() {
+		let query = Query::parse("q=de+ser%3A%3AFoo&domains=name,doc&fuzzy&bad=%zz");
+		assert_eq!(query.get("q"), Some("de ser::Foo"));
+		assert_eq!(query.get("domains"), Some("name,doc"));
+		assert_eq!(query.bool("fuzzy"), Ok(Some(true)));
+		assert_eq!(query.get("bad"), Some("%zz"));
+		assert_eq!(query.get("missing"), None);
+		assert_eq!(percent_decode("a+b%20c", false), "a+b c");
+	}
+
+	#[test]
+	fn invalid_parameters_are_bad_requests() {
+		let query = Query::parse("fuzzy=maybe&domains=name,colour");
+		assert_eq!(query.bool("fuzzy").unwrap_err().status, 400);
+		assert_eq!(query.domains().unwrap_err().status, 400);
+	}
+}
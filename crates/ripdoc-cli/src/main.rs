@@ -1,102 +1,336 @@
 //! CLI entrypoint.
 
+/// Syntax and search-match highlighting.
+mod highlight;
+/// HTTP server for `serve --http`.
+mod http;
+/// Terminal color and pager handling.
+mod output;
+/// JSON-RPC server for `serve --stdio`.
+mod serve;
+/// Source watching for `--watch`.
+mod watch;
+
 use std::error::Error;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
 use std::process::{self, Command as ProcessCommand, Stdio};
 
-use clap::{Args, Parser, Subcommand, ValueEnum};
-use owo_colors::OwoColorize;
-use ripdoc_core::{RenderFormat, Ripdoc, SearchDomain, SearchOptions, SourceLocation};
-
-#[derive(Debug, Clone, Copy, ValueEnum)]
-/// Available search domains accepted by `--search-spec`.
-enum SearchSpec {
-	/// Match against item names.
-	Name,
-	/// Match against documentation comments.
-	Doc,
-	/// Match against canonical module paths.
-	Path,
-	/// Match against rendered signatures.
-	Signature,
-}
+use clap::{ArgAction, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use ripdoc_core::error::{CargoError, EXIT_FAILURE, EXIT_NO_MATCHES, EXIT_TOOLCHAIN, RipdocError};
+use ripdoc_core::tree::render_tree;
+use ripdoc_core::troubleshoot::remedy;
+use ripdoc_core::{
+	BodyStyle, Config, CrateSummary, ListItem, LoadOptions, LoadedCrate, MatchMode, ModuleTree,
+	RenderBudget, RenderFormat, Ripdoc, SearchConfig, SearchDomain, SearchOptions, SearchResponse,
+	SourceLocation, TreeNode, UnknownDomain, Verbosity,
+};
 
-impl From<SearchSpec> for SearchDomain {
-	fn from(spec: SearchSpec) -> Self {
-		match spec {
-			SearchSpec::Name => Self::NAMES,
-			SearchSpec::Doc => Self::DOCS,
-			SearchSpec::Path => Self::PATHS,
-			SearchSpec::Signature => Self::SIGNATURES,
-		}
-	}
-}
+use crate::output::{ColorChoice, Output};
 
 #[derive(Args, Clone)]
 struct CommonArgs {
 	/// Render auto-implemented traits
-	#[arg(short = 'i', long, default_value_t = false)]
+	#[arg(
+		short = 'i',
+		long,
+		default_value_t = false,
+		overrides_with = "no_auto_impls"
+	)]
 	auto_impls: bool,
 
+	/// Leave out auto-implemented traits, overriding config files
+	#[arg(long, default_value_t = false, overrides_with = "auto_impls")]
+	no_auto_impls: bool,
+
+	/// Leave out impl blocks, keeping only declarations and their `#[derive(...)]` lists
+	#[arg(long, default_value_t = false)]
+	no_impls: bool,
+
 	/// Render private items
-	#[arg(short = 'p', long, default_value_t = false)]
+	#[arg(
+		short = 'p',
+		long,
+		default_value_t = false,
+		overrides_with = "no_private"
+	)]
 	private: bool,
 
+	/// Leave out private items, overriding config files
+	#[arg(long, default_value_t = false, overrides_with = "private")]
+	no_private: bool,
+
+	/// Render items marked `#[doc(hidden)]`
+	#[arg(long, default_value_t = false, overrides_with = "no_include_hidden")]
+	include_hidden: bool,
+
+	/// Leave out items marked `#[doc(hidden)]`, overriding config files
+	#[arg(long, default_value_t = false, overrides_with = "include_hidden")]
+	no_include_hidden: bool,
+
+	/// Render `#[cfg(test)] mod tests` modules and items from build scripts
+	#[arg(long, default_value_t = false, overrides_with = "no_include_tests")]
+	include_tests: bool,
+
+	/// Leave out test modules and build script items, overriding config files
+	#[arg(long, default_value_t = false, overrides_with = "include_tests")]
+	no_include_tests: bool,
+
 	/// Disable default features
-	#[arg(short = 'n', long, default_value_t = false)]
+	#[arg(
+		short = 'n',
+		long,
+		default_value_t = false,
+		overrides_with = "default_features"
+	)]
 	no_default_features: bool,
 
+	/// Enable default features, overriding config files
+	#[arg(long, default_value_t = false, overrides_with = "no_default_features")]
+	default_features: bool,
+
 	/// Enable all features
-	#[arg(short = 'a', long, default_value_t = false)]
+	#[arg(
+		short = 'a',
+		long,
+		default_value_t = false,
+		overrides_with = "no_all_features"
+	)]
 	all_features: bool,
 
+	/// Enable only the requested features, overriding config files
+	#[arg(long, default_value_t = false, overrides_with = "all_features")]
+	no_all_features: bool,
+
 	/// Specify features to enable
 	#[arg(short = 'F', long, value_delimiter = ',')]
 	features: Vec<String>,
 
 	/// Enable offline mode, ensuring Cargo will not use the network
-	#[arg(short = 'o', long, default_value_t = false)]
+	#[arg(
+		short = 'o',
+		long,
+		default_value_t = false,
+		overrides_with = "no_offline"
+	)]
 	offline: bool,
 
-	/// Enable verbose mode, showing cargo output while rendering docs
-	#[arg(short = 'v', long, default_value_t = false)]
-	verbose: bool,
+	/// Let Cargo use the network, overriding config files
+	#[arg(long, default_value_t = false, overrides_with = "offline")]
+	no_offline: bool,
 
-	/// Select the render format (`rust` or `markdown`)
-	#[arg(short = 'f', long, value_enum, default_value = "markdown")]
-	format: OutputFormat,
+	/// Registry to download named crates from: a registry in cargo's config or an index URL
+	#[arg(long, value_name = "NAME|URL")]
+	registry: Option<String>,
+
+	/// Fail instead of updating an out-of-date `Cargo.lock`
+	#[arg(long, default_value_t = false)]
+	locked: bool,
+
+	/// Equivalent to `--locked --offline`
+	#[arg(long, default_value_t = false)]
+	frozen: bool,
+
+	/// Rustup toolchain used to generate rustdoc JSON (defaults to `nightly`)
+	#[arg(long)]
+	toolchain: Option<String>,
+
+	/// Document the crate for this target triple instead of the host (e.g. `thumbv7em-none-eabihf`)
+	#[arg(long = "target", value_name = "TRIPLE")]
+	target_triple: Option<String>,
+
+	/// Document the package with this `Cargo.toml`, as cargo's `--manifest-path` does; targets
+	/// written as `::path` then select a module within it
+	#[arg(long, value_name = "PATH", conflicts_with = "stdin_json")]
+	manifest_path: Option<PathBuf>,
+
+	/// Build in the project's target directory (or `CARGO_TARGET_DIR`) instead of ripdoc's
+	/// scratch directory
+	#[arg(long, default_value_t = false)]
+	use_project_target_dir: bool,
+
+	/// Print how the target would be built (use `--format json` for JSON) and exit without building
+	#[arg(long, default_value_t = false)]
+	dry_run: bool,
+
+	/// Print the package, version and source the target resolves to (use `--format json` for
+	/// JSON) and exit without building
+	#[arg(long, default_value_t = false, conflicts_with = "dry_run")]
+	resolve_only: bool,
+
+	/// Print counts of the crate's items by kind, its largest modules, and how many items are
+	/// documented or deprecated instead of rendering it (use `--format json` for JSON)
+	#[arg(long, default_value_t = false, conflicts_with_all = ["dry_run", "resolve_only"])]
+	summary: bool,
+
+	/// Render only the public surface: items reachable from the crate root through public
+	/// modules and `pub use` re-exports, shown where they are re-exported
+	#[arg(
+		long,
+		default_value_t = false,
+		conflicts_with_all = ["private", "changed_since", "timings", "split_modules"]
+	)]
+	facade: bool,
+
+	/// List the items declared `pub` that no public path from the crate root reaches, such as
+	/// those in private modules nothing re-exports (use `--format json` for JSON)
+	#[arg(
+		long,
+		default_value_t = false,
+		conflicts_with_all = ["dry_run", "resolve_only", "summary", "facade"]
+	)]
+	unreachable_pub: bool,
+
+	/// Increase diagnostic output (`-v` shows cargo output, `-vv` adds debug details)
+	#[arg(short = 'v', long, action = ArgAction::Count)]
+	verbose: u8,
+
+	/// Suppress everything on stderr except errors
+	#[arg(short = 'q', long, default_value_t = false, conflicts_with = "verbose")]
+	quiet: bool,
+
+	/// Leave out the header naming the crate, version, features and toolchain above rendered output
+	#[arg(long, default_value_t = false)]
+	no_header: bool,
+
+	/// When rustdoc cannot build the crate, render its public items parsed from source instead
+	#[arg(long, default_value_t = false)]
+	source_fallback: bool,
+
+	/// Print how long each stage of rendering took, and the sizes it produced, to stderr
+	#[arg(long, default_value_t = false)]
+	timings: bool,
+
+	/// Print where the rustdoc JSON for each target was written to stderr, so other tools can
+	/// read it without building it again
+	#[arg(
+		long,
+		default_value_t = false,
+		conflicts_with_all = ["dry_run", "resolve_only", "stdin_json", "watch"]
+	)]
+	emit_json_path: bool,
+
+	/// Render only the items defined in files changed since this git revision (path targets
+	/// inside a git worktree only)
+	#[arg(long, value_name = "REV", conflicts_with = "timings")]
+	changed_since: Option<String>,
+
+	/// Drop doc comments, then large structs' fields, then deep modules' items until rendered
+	/// output is at most this many bytes
+	#[arg(long, value_name = "BYTES", conflicts_with = "max_tokens")]
+	max_bytes: Option<usize>,
+
+	/// Like `--max-bytes`, counting whitespace-separated words as tokens
+	#[arg(long, value_name = "TOKENS")]
+	max_tokens: Option<usize>,
+
+	/// Body given to rendered functions that return a value: `todo` or `unimplemented` makes
+	/// Rust output type-check as a stub crate
+	#[arg(long, value_name = "STYLE", value_enum, default_value_t = BodyStyleArg::Empty)]
+	body_style: BodyStyleArg,
+
+	/// When to color Rust syntax and search matches; `auto` colors only when stdout is a terminal
+	#[arg(long, value_name = "WHEN", value_enum, default_value_t = ColorChoice::Auto)]
+	color: ColorChoice,
+
+	/// Never pipe output through a pager (`RIPDOC_PAGER`, `PAGER`, or `less -RFX`)
+	#[arg(long, default_value_t = false)]
+	no_pager: bool,
+
+	/// Write output to this file, replacing it atomically, instead of stdout. With several
+	/// targets, a directory (existing, or given with a trailing `/`) gets one file per target
+	#[arg(long, value_name = "PATH")]
+	output: Option<PathBuf>,
+
+	/// Write the skeleton as a crate in this directory instead: `src/lib.rs`, a `mod.rs` for each
+	/// module split out, and a `Cargo.toml` for `<crate>-skeleton` (requires `--split-modules`)
+	#[arg(
+		long,
+		value_name = "DIR",
+		requires = "split_modules",
+		conflicts_with = "output"
+	)]
+	output_dir: Option<PathBuf>,
+
+	/// Split Rust output into one file per top-level module, and per nested module larger than
+	/// 16 KiB, written to `--output-dir`
+	#[arg(long, default_value_t = false, requires = "output_dir")]
+	split_modules: bool,
+
+	/// Line printed before each target's output when several are rendered; `{target}` is
+	/// replaced with the target [default: `// ===== {target} =====` for Rust, `# {target}` for
+	/// Markdown, none for JSON]
+	#[arg(long, value_name = "TEMPLATE")]
+	banner: Option<String>,
+
+	/// Re-run whenever the target package's `src/` or `Cargo.toml` changes (path targets only)
+	#[arg(long, default_value_t = false)]
+	watch: bool,
+
+	/// Read rustdoc JSON for the crate from stdin instead of building a target; needs neither
+	/// cargo nor a nightly toolchain. `search` then takes only the query
+	#[arg(
+		long,
+		default_value_t = false,
+		conflicts_with_all = [
+			"targets",
+			"dry_run",
+			"resolve_only",
+			"watch",
+			"output_dir",
+			"changed_since",
+		]
+	)]
+	stdin_json: bool,
+
+	/// Render only the module or item at this path below the crate root, as a target's `::path`
+	/// would (requires `--stdin-json`)
+	#[arg(long, value_name = "PATH", requires = "stdin_json")]
+	filter: Option<String>,
+
+	/// Ignore `.ripdoc.toml` and `$XDG_CONFIG_HOME/ripdoc/config.toml`
+	#[arg(long, default_value_t = false)]
+	no_config: bool,
+
+	/// Select the render format (`rust`, `markdown`, or `json`) [default: markdown]
+	#[arg(short = 'f', long, value_enum)]
+	format: Option<OutputFormat>,
+
+	/// Settings merged from the command line and config files by [`apply_config`].
+	#[arg(skip)]
+	config: Config,
 }
 
+/// Search tuning flags; each needs a query to apply to.
 #[derive(Args, Clone)]
 struct SearchFilterArgs {
-	/// Comma-separated list of search domains (name, doc, signature, path). Defaults to name, doc, signature.
+	/// Comma-separated list of search domains (name, doc, signature, path, attrs, or all). Defaults to name, doc, signature.
 	#[arg(
 		long = "search-spec",
 		value_delimiter = ',',
 		value_name = "DOMAIN[,DOMAIN...]",
-		default_value = "name,doc,signature"
+		value_parser = parse_search_domain,
+		requires = "query"
 	)]
 	#[arg(short = 'S')]
-	search_spec: Vec<SearchSpec>,
+	search_spec: Vec<SearchDomain>,
 
 	/// Execute the search in a case sensitive manner.
-	#[arg(short = 'c', long, default_value_t = false)]
+	#[arg(short = 'c', long, default_value_t = false, requires = "query")]
 	search_case_sensitive: bool,
 
+	/// Match names fuzzily (e.g. `dsrlz` finds `Deserializer`) and rank results by score.
+	#[arg(long, default_value_t = false, requires = "query")]
+	fuzzy: bool,
+
 	/// Suppress automatic expansion of matched containers when searching.
-	#[arg(short = 'd', long, default_value_t = false)]
+	#[arg(short = 'd', long, default_value_t = false, requires = "query")]
 	direct_match_only: bool,
 }
 
-impl Default for SearchFilterArgs {
-	fn default() -> Self {
-		Self {
-			search_spec: vec![SearchSpec::Name, SearchSpec::Doc, SearchSpec::Signature],
-			search_case_sensitive: false,
-			direct_match_only: false,
-		}
-	}
-}
-
 #[derive(Args, Clone)]
 struct ListArgs {
 	/// Target to generate - a directory, file path, or a module name
@@ -107,6 +341,10 @@ struct ListArgs {
 	#[arg(short = 's', long)]
 	query: Option<String>,
 
+	/// Print items as an indented tree under the modules and types containing them.
+	#[arg(long, default_value_t = false)]
+	tree: bool,
+
 	#[command(flatten)]
 	filters: SearchFilterArgs,
 }
@@ -120,15 +358,33 @@ struct SearchArgs {
 	#[arg(required = false)]
 	query: Option<String>,
 
+	/// Also search the target's direct dependencies, building each one's documentation.
+	#[arg(long, default_value_t = false)]
+	with_deps: bool,
+
 	#[command(flatten)]
 	filters: SearchFilterArgs,
 }
 
 #[derive(Args, Clone)]
 struct RenderArgs {
-	/// Target to generate - a directory, file path, or a module name
+	/// Targets to generate - directories, file paths, or module names
 	#[arg(default_value = "./")]
-	target: String,
+	targets: Vec<String>,
+}
+
+#[derive(Args, Clone)]
+struct RawArgs {
+	/// Targets to generate - directories, file paths, or module names
+	#[arg(default_value = "./")]
+	targets: Vec<String>,
+
+	/// Only emit the items matching this search query, along with their ancestors.
+	#[arg(short = 's', long)]
+	query: Option<String>,
+
+	#[command(flatten)]
+	filters: SearchFilterArgs,
 }
 
 #[derive(Subcommand, Clone)]
@@ -139,29 +395,135 @@ enum Command {
 	List(ListArgs),
 	/// Search for matching items and render the filtered skeleton.
 	Search(SearchArgs),
-	/// Emit raw rustdoc JSON.
-	Raw(RenderArgs),
+	/// Emit raw rustdoc JSON, reduced to the items a `::path` filter or `--query` selects.
+	Raw(RawArgs),
+	/// Print a shell completion script.
+	#[command(hide = true)]
+	Completions(CompletionsArgs),
+	/// Print a roff man page.
+	#[command(hide = true)]
+	Manpage,
+	/// Answer JSON-RPC requests (`render`, `search`, `list`, `features`) from a long-running
+	/// process, keeping loaded crates in memory.
+	Serve(ServeArgs),
+}
+
+#[derive(Args, Clone)]
+struct ServeArgs {
+	/// Read one request per line from stdin and write one response per line to stdout.
+	#[arg(long, default_value_t = false)]
+	stdio: bool,
+
+	/// Load the target once and answer `GET /search`, `/item/<path>` and `/list` over HTTP on
+	/// this address (port 0 picks a free one)
+	#[arg(long, value_name = "ADDR", conflicts_with = "stdio")]
+	http: Option<String>,
+
+	/// Target served over HTTP - a directory, file path, or a module name [default: ./]
+	#[arg(requires = "http")]
+	target: Option<String>,
+}
+
+#[derive(Args, Clone)]
+struct CompletionsArgs {
+	/// Shell to generate completions for.
+	#[arg(value_enum)]
+	shell: Shell,
 }
 
 #[derive(Parser)]
-#[command(author, version, about, long_about = None)]
+#[command(name = "ripdoc", author, version, about, long_about = None)]
 /// Parsed command-line options for the ripdoc CLI.
 struct Cli {
 	#[command(flatten)]
 	common: CommonArgs,
 
-	#[arg()]
-	legacy_target: Option<String>,
-
-	#[arg(trailing_var_arg = true, hide = true)]
-	legacy_extra: Vec<String>,
+	/// Targets to render when no subcommand is given
+	#[arg(default_value = "./")]
+	targets: Vec<String>,
 
 	#[command(subcommand)]
 	command: Option<Command>,
 }
 
-/// Ensure the nightly toolchain and rust-docs JSON component are present.
-fn check_nightly_toolchain() -> Result<(), String> {
+/// One line for each flag given on the command line that the selected command ignores.
+///
+/// Flags that can never apply, such as search tuning without a query, are rejected by clap
+/// instead. These are flags that are valid in general but have no effect on this command.
+fn ignored_flag_warnings(cli: &Cli) -> Vec<String> {
+	let common = &cli.common;
+	let mut warnings = Vec::new();
+	match &cli.command {
+		Some(Command::List(args)) => {
+			for (flag, set) in [
+				("--auto-impls", common.auto_impls),
+				("--no-impls", common.no_impls),
+			] {
+				if set {
+					warnings.push(format!(
+						"{flag} is ignored by `list`, which does not render impl blocks"
+					));
+				}
+			}
+			if args.filters.direct_match_only {
+				warnings.push(
+					"--direct-match-only is ignored by `list`, which never expands matches"
+						.to_string(),
+				);
+			}
+		}
+		Some(Command::Raw(_)) => {
+			if let Some(format @ (OutputFormat::Rust | OutputFormat::Markdown)) = common.format
+				&& let Some(value) = format.to_possible_value()
+			{
+				warnings.push(format!(
+					"--format {} is ignored by `raw`, which always emits rustdoc JSON",
+					value.get_name()
+				));
+			}
+		}
+		_ => {}
+	}
+	if common.stdin_json {
+		for (flag, set) in [
+			("--no-default-features", common.no_default_features),
+			("--all-features", common.all_features),
+			("--features", !common.features.is_empty()),
+			("--offline", common.offline),
+			("--registry", common.registry.is_some()),
+			("--locked", common.locked),
+			("--frozen", common.frozen),
+			("--toolchain", common.toolchain.is_some()),
+			("--target", common.target_triple.is_some()),
+			("--use-project-target-dir", common.use_project_target_dir),
+			("--private", common.private),
+			("--source-fallback", common.source_fallback),
+			("--timings", common.timings),
+		] {
+			if set {
+				warnings.push(format!(
+					"{flag} is ignored by --stdin-json, which reads a crate rustdoc already built"
+				));
+			}
+		}
+	}
+	if common.split_modules {
+		if !matches!(cli.command, None | Some(Command::Render(_))) {
+			warnings.push("--split-modules is ignored by commands other than `render`".to_string());
+		} else if let Some(format @ (OutputFormat::Markdown | OutputFormat::Json)) = common.format
+			&& let Some(value) = format.to_possible_value()
+		{
+			warnings.push(format!(
+				"--format {} is ignored by --split-modules, which always writes Rust",
+				value.get_name()
+			));
+		}
+	}
+	warnings
+}
+
+/// Ensure the nightly toolchain (or the `--toolchain` override) is present.
+fn check_nightly_toolchain(toolchain: Option<&str>) -> Result<(), Box<dyn Error>> {
 	// First, check if rustup is available
 	let rustup_available = ProcessCommand::new("rustup")
 		.arg("--version")
@@ -171,17 +533,32 @@ fn check_nightly_toolchain() -> Result<(), String> {
 		.map(|status| status.success())
 		.unwrap_or(false);
 
+	if let Some(toolchain) = toolchain
+		&& !toolchain.contains("nightly")
+	{
+		log::warn!(
+			"toolchain '{toolchain}' does not look like a nightly; rustdoc JSON output may be unavailable"
+		);
+	}
+
 	if rustup_available {
-		// Check if nightly toolchain is installed via rustup
+		// Check if the requested toolchain is installed via rustup
+		let toolchain = toolchain.unwrap_or("nightly");
 		let output = ProcessCommand::new("rustup")
-			.args(["run", "nightly", "rustc", "--version"])
+			.args(["run", toolchain, "rustc", "--version"])
 			.stderr(Stdio::null())
 			.output()
 			.map_err(|e| format!("Failed to run rustup: {e}"))?;
 
 		if !output.status.success() {
-			return Err("ripdoc requires the nightly toolchain to be installed.\nRun: rustup toolchain install nightly".to_string());
+			let toolchain = toolchain.to_string();
+			return Err(RipdocError::from(CargoError::ToolchainMissing { toolchain }).into());
 		}
+	} else if let Some(toolchain) = toolchain {
+		return Err(format!(
+			"Selecting the '{toolchain}' toolchain requires rustup, which was not found in PATH."
+		)
+		.into());
 	} else {
 		// rustup is not available - check for nightly rustc directly
 		let output = ProcessCommand::new("rustc")
@@ -194,7 +571,7 @@ fn check_nightly_toolchain() -> Result<(), String> {
 			})?;
 
 		if !output.status.success() {
-			return Err("ripdoc requires a nightly Rust toolchain.\nEnsure nightly Rust is installed and available in PATH.".to_string());
+			return Err("ripdoc requires a nightly Rust toolchain.\nEnsure nightly Rust is installed and available in PATH.".into());
 		}
 
 		let version_str = String::from_utf8_lossy(&output.stdout);
@@ -202,7 +579,8 @@ fn check_nightly_toolchain() -> Result<(), String> {
 			return Err(format!(
 				"ripdoc requires a nightly Rust toolchain, but found: {}\nEnsure nightly Rust is installed and available in PATH.",
 				version_str.trim()
-			));
+			)
+			.into());
 		}
 	}
 
@@ -212,10 +590,180 @@ fn check_nightly_toolchain() -> Result<(), String> {
 /// Build a Ripdoc instance configured with common CLI knobs.
 fn build_ripdoc(common: &CommonArgs) -> Ripdoc {
 	Ripdoc::new()
-		.with_offline(common.offline)
-		.with_auto_impls(common.auto_impls)
-		.with_render_format(common.format.into())
-		.with_silent(!common.verbose)
+		.with_config(&common.config)
+		.with_locked(common.locked || common.frozen)
+		.with_project_target_dir(common.use_project_target_dir)
+		.with_impls(!common.no_impls)
+		.with_header(!common.no_header)
+		.with_source_fallback(common.source_fallback)
+		.with_budget(budget(common))
+		.with_body_style(common.body_style.into())
+		.with_verbosity(verbosity(common))
+}
+
+/// Settings given on the command line, as the highest-precedence config layer.
+///
+/// Switches only count when they or their negations are passed, so an absent flag defers to
+/// config files.
+fn cli_config(common: &CommonArgs) -> Config {
+	Config {
+		format: common.format.map(RenderFormat::from),
+		auto_impls: switch(common.auto_impls, common.no_auto_impls),
+		private: switch(common.private, common.no_private),
+		include_hidden: switch(common.include_hidden, common.no_include_hidden),
+		include_tests: switch(common.include_tests, common.no_include_tests),
+		no_default_features: switch(common.no_default_features, common.default_features),
+		all_features: switch(common.all_features, common.no_all_features),
+		features: (!common.features.is_empty()).then(|| common.features.clone()),
+		offline: switch(common.offline || common.frozen, common.no_offline),
+		registry: common.registry.clone(),
+		toolchain: common.toolchain.clone(),
+		target: common.target_triple.clone(),
+		..Config::default()
+	}
+}
+
+/// A switch and its negation as a config layer, `None` when neither was passed.
+fn switch(on: bool, off: bool) -> Option<bool> {
+	match (on, off) {
+		(true, _) => Some(true),
+		(false, true) => Some(false),
+		(false, false) => None,
+	}
+}
+
+/// Layer `files` under the command-line flags and write the merged settings back into `common`.
+fn apply_config(common: &mut CommonArgs, files: Config) {
+	let config = cli_config(common).or(files);
+	common.format = Some(config.format.unwrap_or(RenderFormat::Markdown).into());
+	common.auto_impls = config.auto_impls.unwrap_or(false);
+	common.private = config.private.unwrap_or(false);
+	common.include_hidden = config.include_hidden.unwrap_or(false);
+	common.include_tests = config.include_tests.unwrap_or(false);
+	common.no_default_features = config.no_default_features.unwrap_or(false);
+	common.all_features = config.all_features.unwrap_or(false);
+	common.features = config.features.clone().unwrap_or_default();
+	common.offline = config.offline.unwrap_or(false);
+	common.registry = config.registry.clone();
+	common.toolchain = config.toolchain.clone();
+	common.target_triple = config.target.clone();
+	common.config = config;
+}
+
+/// Load config files for the invoked command unless `--no-config` was given, then merge them
+/// under the command-line flags.
+fn configure(cli: &mut Cli) -> Result<(), Box<dyn Error>> {
+	apply_manifest_path(cli)?;
+	let files = if cli.common.no_config {
+		Config::default()
+	} else {
+		Config::load(command_targets(cli).first().map_or("./", String::as_str))?
+	};
+	apply_config(&mut cli.common, files);
+	Ok(())
+}
+
+/// Targets named by the invoked command, empty if it does not build any.
+fn command_targets(cli: &Cli) -> &[String] {
+	match &cli.command {
+		Some(Command::Render(args)) => &args.targets,
+		Some(Command::Raw(args)) => &args.targets,
+		Some(Command::List(args)) => std::slice::from_ref(&args.target),
+		Some(Command::Search(args)) => std::slice::from_ref(&args.target),
+		Some(Command::Completions(_) | Command::Manpage | Command::Serve(_)) => &[],
+		None => &cli.targets,
+	}
+}
+
+/// Mutable access to the targets named by the invoked command, as [`command_targets`].
+fn command_targets_mut(cli: &mut Cli) -> &mut [String] {
+	match &mut cli.command {
+		Some(Command::Render(args)) => &mut args.targets,
+		Some(Command::Raw(args)) => &mut args.targets,
+		Some(Command::List(args)) => std::slice::from_mut(&mut args.target),
+		Some(Command::Search(args)) => std::slice::from_mut(&mut args.target),
+		Some(Command::Completions(_) | Command::Manpage | Command::Serve(_)) => &mut [],
+		None => &mut cli.targets,
+	}
+}
+
+/// Point the command at `--manifest-path`: the default target becomes the manifest, and
+/// targets written as `::path` select a module below it.
+fn apply_manifest_path(cli: &mut Cli) -> Result<(), Box<dyn Error>> {
+	let Some(manifest) = cli.common.manifest_path.clone() else {
+		return Ok(());
+	};
+	if manifest.file_name().is_none_or(|name| name != "Cargo.toml") {
+		return Err(format!(
+			"--manifest-path must point at a Cargo.toml, not '{}'",
+			manifest.display()
+		)
+		.into());
+	}
+	let manifest = manifest.to_string_lossy();
+	for target in command_targets_mut(cli) {
+		*target = if target == "./" {
+			manifest.to_string()
+		} else if let Some(path) = target.strip_prefix("::") {
+			format!("{manifest}::{path}")
+		} else {
+			return Err(format!(
+				"--manifest-path already names the package to document, so '{target}' cannot \
+				 be a target; select a module within it with `::path`"
+			)
+			.into());
+		};
+	}
+	Ok(())
+}
+
+/// Size budget requested with `--max-bytes` or `--max-tokens`.
+fn budget(common: &CommonArgs) -> Option<RenderBudget> {
+	common
+		.max_bytes
+		.map(RenderBudget::Bytes)
+		.or(common.max_tokens.map(RenderBudget::Tokens))
+}
+
+/// Map the `-v`/`--quiet` flags onto a verbosity level.
+fn verbosity(common: &CommonArgs) -> Verbosity {
+	match (common.quiet, common.verbose) {
+		(true, _) => Verbosity::Quiet,
+		(false, 0) => Verbosity::Normal,
+		(false, 1) => Verbosity::Verbose,
+		(false, _) => Verbosity::Debug,
+	}
+}
+
+/// Logger that writes diagnostics to stderr so stdout only carries rendered output.
+struct StderrLogger;
+
+impl log::Log for StderrLogger {
+	fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+		metadata.level() <= log::max_level()
+	}
+
+	fn log(&self, record: &log::Record<'_>) {
+		if !self.enabled(record.metadata()) {
+			return;
+		}
+		match record.level() {
+			log::Level::Error => eprintln!("error: {}", record.args()),
+			log::Level::Warn => eprintln!("warning: {}", record.args()),
+			_ => eprintln!("{}", record.args()),
+		}
+	}
+
+	fn flush(&self) {}
+}
+
+static LOGGER: StderrLogger = StderrLogger;
+
+/// Install the stderr logger at the requested verbosity.
+fn init_logging(verbosity: Verbosity) {
+	if log::set_logger(&LOGGER).is_ok() {
+		log::set_max_level(verbosity.level_filter());
+	}
 }
 
 /// Resolve the active search domains specified by the CLI flags.
@@ -226,88 +774,495 @@ fn search_domains_from_filters(filters: &SearchFilterArgs) -> SearchDomain {
 		filters
 			.search_spec
 			.iter()
-			.fold(SearchDomain::empty(), |mut acc, spec| {
-				acc |= SearchDomain::from(*spec);
-				acc
-			})
+			.fold(SearchDomain::empty(), |acc, domain| acc | *domain)
 	}
 }
 
+/// Parse one `--search-spec` value into its search domains.
+fn parse_search_domain(name: &str) -> Result<SearchDomain, UnknownDomain> {
+	SearchDomain::from_str_list(&[name])
+}
+
 /// Build a `SearchOptions` value using the provided CLI configuration and query.
 fn build_search_options(
 	common: &CommonArgs,
 	filters: &SearchFilterArgs,
 	query: &str,
 ) -> SearchOptions {
+	let flags = SearchConfig {
+		case_sensitive: filters.search_case_sensitive.then_some(true),
+		fuzzy: filters.fuzzy.then_some(true),
+		domains: (!filters.search_spec.is_empty()).then(|| search_domains_from_filters(filters)),
+		direct_match_only: filters.direct_match_only.then_some(true),
+	};
+	let search = flags.or(common.config.search.clone());
+
 	let mut options = SearchOptions::new(query);
 	options.include_private = common.private;
-	options.case_sensitive = filters.search_case_sensitive;
-	options.expand_containers = !filters.direct_match_only;
-	options.domains = search_domains_from_filters(filters);
+	options.case_sensitive = search.case_sensitive.unwrap_or(false);
+	if search.fuzzy.unwrap_or(false) {
+		options.match_mode = MatchMode::Fuzzy;
+	}
+	options.expand_containers = !search.direct_match_only.unwrap_or(false);
+	options.domains = search.domains.unwrap_or_default();
 	options
 }
 
-/// Render a skeleton locally and stream it to stdout or a pager.
-fn run_render(common: &CommonArgs, target: &str, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
-	let output = rs.render(
-		target,
-		common.no_default_features,
-		common.all_features,
-		common.features.clone(),
-		common.private,
-	)?;
+/// Render skeletons locally and send them to stdout or a pager.
+fn run_render(
+	common: &CommonArgs,
+	targets: &[String],
+	rs: &Ripdoc,
+	stdout: &Output,
+) -> Result<(), Box<dyn Error>> {
+	if let Some(dir) = &common.output_dir {
+		return run_split_modules(common, targets, rs, dir);
+	}
+	let format = common.format.unwrap_or(OutputFormat::Markdown);
+	run_targets(common, targets, format, stdout, |target| {
+		if let Some(rev) = &common.changed_since {
+			let options = LoadOptions {
+				no_default_features: common.no_default_features,
+				all_features: common.all_features,
+				features: common.features.clone(),
+				include_private: common.private,
+			};
+			return Ok(rs.render_changed_since(target, &options, rev)?);
+		}
+		if common.facade {
+			let options = LoadOptions {
+				no_default_features: common.no_default_features,
+				all_features: common.all_features,
+				features: common.features.clone(),
+				include_private: false,
+			};
+			return Ok(rs.render_facade(target, &options)?);
+		}
+		if !common.timings {
+			return Ok(rs.render(
+				target,
+				common.no_default_features,
+				common.all_features,
+				&common.features,
+				common.private,
+			)?);
+		}
+		let (rendered, metrics) = rs.render_with_metrics(
+			target,
+			common.no_default_features,
+			common.all_features,
+			&common.features,
+			common.private,
+		)?;
+		eprintln!("timings for {target}:\n{metrics}");
+		Ok(rendered)
+	})
+}
+
+/// Render a single target as a crate laid out in `dir`, one file per split module.
+fn run_split_modules(
+	common: &CommonArgs,
+	targets: &[String],
+	rs: &Ripdoc,
+	dir: &Path,
+) -> Result<(), Box<dyn Error>> {
+	let [target] = targets else {
+		return Err("--split-modules takes a single target".into());
+	};
+	let options = LoadOptions {
+		no_default_features: common.no_default_features,
+		all_features: common.all_features,
+		features: common.features.clone(),
+		include_private: common.private,
+	};
+	let Some(tree) = rs.render_tree(target, &options)? else {
+		return Err(format!("{target}: nothing to render").into());
+	};
+	write_module_tree(dir, &tree)?;
+	log::info!("wrote {} as a crate to {}", tree.name, dir.display());
+	Ok(())
+}
+
+/// Write `tree` as a crate in `dir`: `src/lib.rs` for the root, `src/<path>/mod.rs` for each
+/// module split out, and a `Cargo.toml` for the package `<crate>-skeleton`.
+///
+/// The library keeps the crate's name so paths naming it still resolve, and the manifest
+/// declares its own workspace so it builds even inside another one.
+fn write_module_tree(dir: &Path, tree: &ModuleTree) -> io::Result<()> {
+	let src = dir.join("src");
+	let mut files = Vec::new();
+	tree.walk(&mut |path, module| {
+		let file = match path {
+			[] => src.join("lib.rs"),
+			_ => path
+				.iter()
+				.fold(src.clone(), |dir, name| {
+					dir.join(name.trim_start_matches("r#"))
+				})
+				.join("mod.rs"),
+		};
+		files.push((file, &module.source));
+	});
+	for (file, source) in files {
+		if let Some(parent) = file.parent() {
+			fs::create_dir_all(parent)?;
+		}
+		Output::to_file(file).print(source)?;
+	}
+
+	let name = tree.name.trim_start_matches("r#");
+	let manifest = format!(
+		"[package]\nname = \"{name}-skeleton\"\nversion = \"0.0.0\"\nedition = \"2021\"\n\n\
+		 [lib]\nname = \"{name}\"\npath = \"src/lib.rs\"\n\n[workspace]\n"
+	);
+	Output::to_file(dir.join("Cargo.toml")).print(&manifest)
+}
 
-	println!("{output}");
+/// Output raw rustdoc JSON, pruned to the selected items when a filter or query is given.
+fn run_raw(
+	common: &CommonArgs,
+	args: &RawArgs,
+	rs: &Ripdoc,
+	stdout: &Output,
+) -> Result<(), Box<dyn Error>> {
+	let search = args
+		.query
+		.as_deref()
+		.map(str::trim)
+		.filter(|query| !query.is_empty())
+		.map(|query| build_search_options(common, &args.filters, query));
+	let options = LoadOptions {
+		no_default_features: common.no_default_features,
+		all_features: common.all_features,
+		features: common.features.clone(),
+		include_private: common.private,
+	};
+	run_targets(
+		common,
+		&args.targets,
+		OutputFormat::Json,
+		stdout,
+		|target| Ok(rs.raw_json_selected(target, &options, search.as_ref())?),
+	)
+}
+
+/// Syntax-highlight Rust output bound for a color terminal; anything else is left as is.
+fn colorize(stdout: &Output, format: OutputFormat, text: String) -> String {
+	if stdout.color && matches!(format, OutputFormat::Rust) {
+		highlight::highlight(&text, true, "", false)
+	} else {
+		text
+	}
+}
 
+/// Produce `format` output for each of `targets` and print it in one go.
+///
+/// Several targets are separated by [`banner`] lines, or written one file per target when
+/// `--output` names a directory. A target that fails is reported on stderr without stopping
+/// the rest, and the run fails once they are done.
+fn run_targets(
+	common: &CommonArgs,
+	targets: &[String],
+	format: OutputFormat,
+	stdout: &Output,
+	produce: impl Fn(&str) -> Result<String, Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+	if let [target] = targets {
+		let output = produce(target)?;
+		stdout.print(&colorize(stdout, format, format!("{output}\n")))?;
+		return Ok(());
+	}
+
+	let dir = common.output.as_deref().filter(|path| is_output_dir(path));
+	let mut combined = String::new();
+	let mut failed = 0;
+	for target in targets {
+		let output = match produce(target) {
+			Ok(output) => output,
+			Err(e) => {
+				log::error!("{target}: {e}");
+				failed += 1;
+				continue;
+			}
+		};
+		if let Some(dir) = dir {
+			fs::create_dir_all(dir)?;
+			let file = format!("{}.{}", sanitize_target(target), format.extension());
+			Output::to_file(dir.join(file)).print(&format!("{output}\n"))?;
+			continue;
+		}
+		if !combined.is_empty() {
+			combined.push('\n');
+		}
+		if let Some(banner) = banner(common, format, target) {
+			combined.push_str(&banner);
+			combined.push_str("\n\n");
+		}
+		combined.push_str(&output);
+		combined.push('\n');
+	}
+
+	if !combined.is_empty() {
+		stdout.print(&colorize(stdout, format, combined))?;
+	}
+	if failed > 0 {
+		return Err(format!("{failed} of {} targets failed", targets.len()).into());
+	}
 	Ok(())
 }
 
-/// Output raw rustdoc JSON.
-fn run_raw(common: &CommonArgs, target: &str, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
-	let output = rs.raw_json(
+/// Whether `--output` names a directory: one that exists, or a path ending in `/`.
+fn is_output_dir(path: &Path) -> bool {
+	path.is_dir() || path.as_os_str().to_string_lossy().ends_with(['/', '\\'])
+}
+
+/// Line introducing `target`'s output when several targets are printed together.
+///
+/// JSON gets no default banner, so each document stays parseable on its own.
+fn banner(common: &CommonArgs, format: OutputFormat, target: &str) -> Option<String> {
+	if let Some(template) = &common.banner {
+		return Some(template.replace("{target}", target)).filter(|banner| !banner.is_empty());
+	}
+	match format {
+		OutputFormat::Rust => Some(format!("// ===== {target} =====")),
+		OutputFormat::Markdown => Some(format!("# {target}")),
+		OutputFormat::Json => None,
+	}
+}
+
+/// File stem for `target`'s output: anything other than ASCII letters, digits, `-`, `_` and
+/// `.` becomes `_`, so `tokio::sync` is written to `tokio__sync`.
+fn sanitize_target(target: &str) -> String {
+	let sanitized: String = target
+		.chars()
+		.map(|c| {
+			if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+				c
+			} else {
+				'_'
+			}
+		})
+		.collect();
+	let trimmed = sanitized.trim_matches(['_', '.']);
+	if trimmed.is_empty() {
+		"crate".to_string()
+	} else {
+		trimmed.to_string()
+	}
+}
+
+/// Print the resolved build plan for a target without generating rustdoc JSON.
+fn run_dry_run(common: &CommonArgs, target: &str, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
+	let plan = rs.plan(
 		target,
 		common.no_default_features,
 		common.all_features,
-		common.features.clone(),
+		&common.features,
 		common.private,
 	)?;
 
-	println!("{output}");
+	let mut out = io::stdout().lock();
+	if matches!(common.format, Some(OutputFormat::Json)) {
+		writeln!(out, "{}", serde_json::to_string_pretty(&plan)?)?;
+		return Ok(());
+	}
+
+	let version = plan.version.as_deref().unwrap_or("unknown");
+	let filter = if plan.filter.is_empty() {
+		"(crate root)"
+	} else {
+		plan.filter.as_str()
+	};
+	let mut features = if plan.features.is_empty() {
+		"(none)".to_string()
+	} else {
+		plan.features.join(", ")
+	};
+	if plan.all_features {
+		features.push_str(" [all features]");
+	}
+	if plan.no_default_features {
+		features.push_str(" [no default features]");
+	}
+	let rows = [
+		("package", format!("{} {version}", plan.package)),
+		("path", plan.path.display().to_string()),
+		("target", plan.target.to_string()),
+		("filter", filter.to_string()),
+		("features", features),
+		("private", plan.private_items.to_string()),
+		(
+			"toolchain",
+			plan.toolchain.unwrap_or_else(|| "(default)".to_string()),
+		),
+		(
+			"triple",
+			plan.target_triple.unwrap_or_else(|| "(host)".to_string()),
+		),
+		(
+			"cache",
+			if plan.cache_hit { "hit" } else { "miss" }.to_string(),
+		),
+	];
+	for (label, value) in rows {
+		writeln!(out, "{label:<10} {value}")?;
+	}
+	Ok(())
+}
+
+/// Print what a target resolves to without generating rustdoc JSON.
+fn run_resolve_only(common: &CommonArgs, target: &str, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
+	let info = rs.resolve(target)?;
+
+	let mut out = io::stdout().lock();
+	if matches!(common.format, Some(OutputFormat::Json)) {
+		writeln!(out, "{}", serde_json::to_string_pretty(&info)?)?;
+		return Ok(());
+	}
+
+	let filter = if info.filter.is_empty() {
+		"(crate root)"
+	} else {
+		info.filter.as_str()
+	};
+	let mut version = info.version.unwrap_or_else(|| "unknown".to_string());
+	if let Some(requested) = &info.requested {
+		version.push_str(&format!(" (requested {requested})"));
+	}
+	let rows = [
+		("package", info.package),
+		("version", version),
+		("source", info.source.to_string()),
+		("path", info.path.display().to_string()),
+		("filter", filter.to_string()),
+	];
+	for (label, value) in rows {
+		writeln!(out, "{label:<10} {value}")?;
+	}
+	Ok(())
+}
+
+/// Print how many items of each kind a target has, its largest modules, and how many items
+/// are documented or deprecated.
+fn run_summary(common: &CommonArgs, target: &str, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
+	let options = LoadOptions {
+		no_default_features: common.no_default_features,
+		all_features: common.all_features,
+		features: common.features.clone(),
+		include_private: common.private,
+	};
+	print_summary(common, &rs.summarize(target, &options)?)
+}
 
+/// Print `summary` as a table, or as JSON with `--format json`.
+fn print_summary(common: &CommonArgs, summary: &CrateSummary) -> Result<(), Box<dyn Error>> {
+	let mut out = io::stdout().lock();
+	if matches!(common.format, Some(OutputFormat::Json)) {
+		writeln!(out, "{}", serde_json::to_string_pretty(summary)?)?;
+	} else {
+		writeln!(out, "{summary}")?;
+	}
+	Ok(())
+}
+
+/// Print the items a target declares `pub` that no public path reaches.
+fn run_unreachable_pub(
+	common: &CommonArgs,
+	target: &str,
+	rs: &Ripdoc,
+) -> Result<(), Box<dyn Error>> {
+	let options = LoadOptions {
+		no_default_features: common.no_default_features,
+		all_features: common.all_features,
+		features: common.features.clone(),
+		include_private: true,
+	};
+	print_unreachable_pub(common, &rs.unreachable_pub(target, &options)?)
+}
+
+/// Print `items` as rows of kind, path and source location, or as JSON with `--format json`.
+fn print_unreachable_pub(common: &CommonArgs, items: &[ListItem]) -> Result<(), Box<dyn Error>> {
+	let mut out = io::stdout().lock();
+	if matches!(common.format, Some(OutputFormat::Json)) {
+		let items: Vec<_> = items.iter().map(serve::list_item_json).collect();
+		writeln!(out, "{}", serde_json::to_string_pretty(&items)?)?;
+		return Ok(());
+	}
+	if items.is_empty() {
+		log::info!("every public item is reachable from the crate root");
+		return Ok(());
+	}
+	let label_width = items
+		.iter()
+		.map(|item| item.kind.label().len())
+		.max()
+		.unwrap_or(0);
+	for item in items {
+		let location = format_source_location(item.source.as_ref());
+		writeln!(
+			out,
+			"{:<label_width$} {} {location}",
+			item.kind.label(),
+			item.path
+		)?;
+	}
 	Ok(())
 }
 
 /// Execute the list flow and print a structured item summary.
-fn run_list(common: &CommonArgs, args: &ListArgs, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
+fn run_list(
+	common: &CommonArgs,
+	args: &ListArgs,
+	rs: &Ripdoc,
+	stdout: &Output,
+) -> Result<Outcome, Box<dyn Error>> {
 	let mut search_options: Option<SearchOptions> = None;
 	let mut trimmed_query: Option<String> = None;
 
 	if let Some(query) = args.query.as_deref() {
 		let trimmed = query.trim();
 		if trimmed.is_empty() {
-			println!("Search query is empty; nothing to do.");
-			return Ok(());
+			log::warn!("search query is empty; nothing to do");
+			return Ok(Outcome::Success);
 		}
 		trimmed_query = Some(trimmed.to_string());
 		search_options = Some(build_search_options(common, &args.filters, trimmed));
 	}
 
+	if args.tree {
+		let tree = rs.list_tree(
+			&args.target,
+			common.no_default_features,
+			common.all_features,
+			&common.features,
+			common.private,
+			search_options.as_ref(),
+		)?;
+		return print_listing_tree(stdout, &tree, trimmed_query.as_deref());
+	}
+
 	let listings = rs.list(
 		&args.target,
 		common.no_default_features,
 		common.all_features,
-		common.features.clone(),
+		&common.features,
 		common.private,
 		search_options.as_ref(),
 	)?;
+	print_listings(stdout, &listings, trimmed_query.as_deref())
+}
 
+/// Print `listings` as aligned rows of kind, path and source location.
+///
+/// Nothing to print is explained on stderr and reported as [`Outcome::NoMatches`].
+fn print_listings(
+	stdout: &Output,
+	listings: &[ListItem],
+	query: Option<&str>,
+) -> Result<Outcome, Box<dyn Error>> {
 	if listings.is_empty() {
-		if let Some(query) = trimmed_query {
-			println!("No matches found for \"{query}\".");
-		} else {
-			println!("No items found.");
-		}
-		return Ok(());
+		report_empty_listing(query);
+		return Ok(Outcome::NoMatches);
 	}
 
 	let label_width = listings
@@ -331,9 +1286,32 @@ fn run_list(common: &CommonArgs, args: &ListArgs, rs: &Ripdoc) -> Result<(), Box
 		));
 	}
 
-	print!("{}", buffer);
+	stdout.print(&buffer)?;
 
-	Ok(())
+	Ok(Outcome::Success)
+}
+
+/// Print `tree` as an indented hierarchy, or explain on stderr why it is empty.
+fn print_listing_tree(
+	stdout: &Output,
+	tree: &[TreeNode],
+	query: Option<&str>,
+) -> Result<Outcome, Box<dyn Error>> {
+	if tree.is_empty() {
+		report_empty_listing(query);
+		return Ok(Outcome::NoMatches);
+	}
+	stdout.print(&render_tree(tree, stdout.terminal))?;
+	Ok(Outcome::Success)
+}
+
+/// Explain on stderr why a listing printed nothing.
+fn report_empty_listing(query: Option<&str>) {
+	if let Some(query) = query {
+		log::warn!("no matches found for \"{query}\"");
+	} else {
+		log::warn!("no items found");
+	}
 }
 
 /// Format a source location for display.
@@ -350,79 +1328,65 @@ fn format_source_location(source: Option<&SourceLocation>) -> String {
 	}
 }
 
-/// Highlight all occurrences of the search query.
-fn highlight_matches(text: &str, query: &str, case_sensitive: bool) -> String {
-	if query.is_empty() {
-		return text.to_string();
-	}
-
-	let mut result = String::with_capacity(text.len() * 2);
-	let search_text = if case_sensitive {
-		text.to_string()
-	} else {
-		text.to_lowercase()
-	};
-	let search_query = if case_sensitive {
-		query.to_string()
-	} else {
-		query.to_lowercase()
-	};
-
-	let mut last_end = 0;
-	let mut search_start = 0;
-
-	while let Some(pos) = search_text[search_start..].find(&search_query) {
-		let absolute_pos = search_start + pos;
-		// Add text before the match
-		result.push_str(&text[last_end..absolute_pos]);
-		// Add the highlighted match
-		let match_end = absolute_pos + query.len();
-		let matched_text = &text[absolute_pos..match_end];
-		result.push_str(&matched_text.to_string().bright_green().bold().to_string());
-		last_end = match_end;
-		search_start = match_end;
-	}
-
-	// Add remaining text
-	result.push_str(&text[last_end..]);
-	result
-}
-
 /// Execute the search flow and print the filtered skeleton to stdout.
-fn run_search(common: &CommonArgs, args: &SearchArgs, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
+fn run_search(
+	common: &CommonArgs,
+	args: &SearchArgs,
+	rs: &Ripdoc,
+	stdout: &Output,
+) -> Result<Outcome, Box<dyn Error>> {
 	if args.query.is_none() {
-		return run_cargo_search_fallback(&args.target, common.offline);
+		run_cargo_search_fallback(&args.target, common.offline)?;
+		return Ok(Outcome::Success);
 	}
 	let trimmed = args.query.as_deref().unwrap().trim();
 	if trimmed.is_empty() {
-		println!("Search query is empty; nothing to do.");
-		return Ok(());
+		log::warn!("search query is empty; nothing to do");
+		return Ok(Outcome::Success);
 	}
 
-	let options = build_search_options(common, &args.filters, trimmed);
+	let mut options = build_search_options(common, &args.filters, trimmed);
+	options.include_dependencies = args.with_deps;
 
 	let response = rs.search(
 		&args.target,
 		common.no_default_features,
 		common.all_features,
-		common.features.clone(),
+		&common.features,
 		&options,
 	)?;
+	print_search_response(common, &args.filters, stdout, trimmed, response)
+}
 
+/// Print the skeleton rendered for a search, highlighting matches of `query` in color output.
+///
+/// No matches are explained on stderr and reported as [`Outcome::NoMatches`].
+fn print_search_response(
+	common: &CommonArgs,
+	filters: &SearchFilterArgs,
+	stdout: &Output,
+	query: &str,
+	response: SearchResponse,
+) -> Result<Outcome, Box<dyn Error>> {
 	if response.results.is_empty() {
-		println!("No matches found for \"{}\".", trimmed);
-		return Ok(());
+		log::warn!("no matches found for \"{query}\"");
+		return Ok(Outcome::NoMatches);
 	}
 
-	let output = highlight_matches(
-		&response.rendered,
-		trimmed,
-		args.filters.search_case_sensitive,
-	);
+	let output = if stdout.color {
+		highlight::highlight(
+			&response.rendered,
+			matches!(common.format, Some(OutputFormat::Rust)),
+			query,
+			filters.search_case_sensitive,
+		)
+	} else {
+		response.rendered
+	};
 
-	print!("{}", output);
+	stdout.print(&output)?;
 
-	Ok(())
+	Ok(Outcome::Success)
 }
 
 /// Fallback to `cargo search` when a query is missing.
@@ -448,52 +1412,352 @@ fn run_cargo_search_fallback(term: &str, offline: bool) -> Result<(), Box<dyn Er
 	Ok(())
 }
 
+/// Run the selected command on rustdoc JSON read from stdin instead of on built targets.
+///
+/// Nothing is resolved or built, so commands take no targets and `--filter` stands in for a
+/// target's `::path`; `search` takes its query alone.
+fn run_stdin_json(cli: &Cli, stdout: &Output) -> Result<Outcome, Box<dyn Error>> {
+	let common = &cli.common;
+	let mut json = String::new();
+	io::stdin().read_to_string(&mut json)?;
+	let format = common.format.unwrap_or(OutputFormat::Markdown);
+	let loaded = LoadedCrate::from_json(&json)?
+		.with_filter(common.filter.as_deref().unwrap_or_default())
+		.with_auto_impls(common.auto_impls)
+		.with_impls(!common.no_impls)
+		.with_doc_hidden(common.include_hidden)
+		.with_tests(common.include_tests)
+		.with_render_format(format.into())
+		.with_budget(budget(common))
+		.with_body_style(common.body_style.into());
+
+	match &cli.command {
+		None | Some(Command::Render(_)) => {
+			if let Some(Command::Render(args)) = &cli.command {
+				reject_stdin_targets(&args.targets)?;
+			}
+			if common.summary {
+				print_summary(common, &loaded.summary())?;
+				return Ok(Outcome::Success);
+			}
+			if common.unreachable_pub {
+				print_unreachable_pub(common, &loaded.unreachable_pub())?;
+				return Ok(Outcome::Success);
+			}
+			let rendered = if common.facade {
+				loaded.render_facade()?
+			} else {
+				loaded.render()?
+			};
+			stdout.print(&colorize(stdout, format, format!("{rendered}\n")))?;
+		}
+		Some(Command::Raw(args)) => {
+			reject_stdin_targets(&args.targets)?;
+			let search = args
+				.query
+				.as_deref()
+				.map(str::trim)
+				.filter(|query| !query.is_empty())
+				.map(|query| build_search_options(common, &args.filters, query));
+			stdout.print(&format!("{}\n", loaded.selected_json(search.as_ref())?))?;
+		}
+		Some(Command::List(args)) => {
+			reject_stdin_targets(std::slice::from_ref(&args.target))?;
+			let query = args.query.as_deref().map(str::trim);
+			if query == Some("") {
+				log::warn!("search query is empty; nothing to do");
+				return Ok(Outcome::Success);
+			}
+			let search = query.map(|query| build_search_options(common, &args.filters, query));
+			if args.tree {
+				return print_listing_tree(stdout, &loaded.list_tree(search.as_ref()), query);
+			}
+			let listings = serve::listed_items(&loaded, search.as_ref());
+			return print_listings(stdout, &listings, query);
+		}
+		Some(Command::Search(args)) => {
+			let query = match &args.query {
+				Some(query) => {
+					reject_stdin_targets(std::slice::from_ref(&args.target))?;
+					query
+				}
+				None => &args.target,
+			};
+			let trimmed = query.trim();
+			if trimmed.is_empty() {
+				log::warn!("search query is empty; nothing to do");
+				return Ok(Outcome::Success);
+			}
+			let options = build_search_options(common, &args.filters, trimmed);
+			let response = loaded.search(&options)?;
+			return print_search_response(common, &args.filters, stdout, trimmed, response);
+		}
+		Some(Command::Serve(_) | Command::Completions(_) | Command::Manpage) => {
+			return Err("--stdin-json only applies to `render`, `list`, `search` and `raw`".into());
+		}
+	}
+	Ok(Outcome::Success)
+}
+
+/// Fail when a command run with `--stdin-json` names targets of its own.
+fn reject_stdin_targets(targets: &[String]) -> Result<(), Box<dyn Error>> {
+	if targets.iter().all(|target| target == "./") {
+		return Ok(());
+	}
+	Err(
+		"--stdin-json reads the crate from stdin and takes no targets; select a module with \
+	     --filter instead"
+			.into(),
+	)
+}
+
+/// Serve JSON-RPC requests until stdin is closed.
+///
+/// Requests default to the build and search settings given on the command line.
+fn run_serve(common: &CommonArgs, args: &ServeArgs, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
+	let defaults = LoadOptions {
+		no_default_features: common.no_default_features,
+		all_features: common.all_features,
+		features: common.features.clone(),
+		include_private: common.private,
+	};
+	if let Some(addr) = &args.http {
+		let loaded = rs.load(args.target.as_deref().unwrap_or("./"), &defaults)?;
+		let listener = TcpListener::bind(addr)?;
+		eprintln!("listening on http://{}", listener.local_addr()?);
+		http::HttpServer::new(loaded, common.config.search.clone()).run(&listener)?;
+		return Ok(());
+	}
+	if !args.stdio {
+		return Err("serve needs a transport: pass --stdio or --http <ADDR>".into());
+	}
+	let mut server = serve::Server::new(rs.clone(), defaults, common.config.search.clone());
+	server.run(io::stdin().lock(), io::stdout().lock())?;
+	Ok(())
+}
+
+/// Write the completion script for `shell` to stdout.
+///
+/// The script is generated into a buffer first: `clap_complete` panics when a write fails.
+fn run_completions(args: &CompletionsArgs) -> Result<(), Box<dyn Error>> {
+	let mut command = Cli::command();
+	let name = command.get_name().to_string();
+	let mut script = Vec::new();
+	clap_complete::generate(args.shell, &mut command, name, &mut script);
+	let mut out = io::stdout().lock();
+	out.write_all(&script)?;
+	out.flush()?;
+	Ok(())
+}
+
+/// Write the roff man page to stdout.
+fn run_manpage() -> Result<(), Box<dyn Error>> {
+	let mut out = io::stdout().lock();
+	clap_mangen::Man::new(Cli::command()).render(&mut out)?;
+	out.flush()?;
+	Ok(())
+}
+
 fn main() {
 	let cli = Cli::parse();
-	if let Err(e) = check_nightly_toolchain() {
-		eprintln!("{e}");
-		process::exit(1);
+	init_logging(verbosity(&cli.common));
+	for warning in ignored_flag_warnings(&cli) {
+		log::warn!("{warning}");
 	}
 
-	let result = run(cli);
+	// Generated artifacts only describe the CLI, so they must work without a nightly toolchain.
+	let generated = match &cli.command {
+		Some(Command::Completions(args)) => Some(run_completions(args)),
+		Some(Command::Manpage) => Some(run_manpage()),
+		_ => None,
+	};
+	if let Some(result) = generated {
+		if let Err(e) = result
+			&& !is_broken_pipe(e.as_ref())
+		{
+			print_error(e.as_ref());
+			process::exit(EXIT_FAILURE);
+		}
+		return;
+	}
 
-	if let Err(e) = result {
-		eprintln!("{e}");
-		process::exit(1);
+	let mut cli = cli;
+	if let Err(e) = configure(&mut cli) {
+		print_error(e.as_ref());
+		process::exit(exit_code(e.as_ref()));
+	}
+
+	// Rustdoc JSON from stdin is already built, so it needs no toolchain.
+	if !cli.common.stdin_json
+		&& let Err(e) = check_nightly_toolchain(cli.common.toolchain.as_deref())
+	{
+		print_error(e.as_ref());
+		process::exit(EXIT_TOOLCHAIN);
+	}
+
+	match run(&cli) {
+		Ok(Outcome::Success) => {}
+		Ok(Outcome::NoMatches) => process::exit(EXIT_NO_MATCHES),
+		// The reader went away, as with `ripdoc ... | head`; it already has what it wanted.
+		Err(e) if is_broken_pipe(e.as_ref()) => {}
+		Err(e) => {
+			print_error(e.as_ref());
+			process::exit(exit_code(e.as_ref()));
+		}
 	}
 }
 
-fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
-	let common = cli.common;
-	let rs = build_ripdoc(&common);
+/// Print `err` to stderr, followed by a remedy when it is a common failure with a known fix.
+fn print_error(err: &(dyn Error + 'static)) {
+	eprintln!("{err}");
+	if let Some(remedy) = err.downcast_ref::<RipdocError>().and_then(remedy) {
+		eprintln!("\n{remedy}");
+	}
+}
 
-	match cli.command {
-		Some(Command::Render(args)) => run_render(&common, &args.target, &rs),
-		Some(Command::Raw(args)) => run_raw(&common, &args.target, &rs),
-		Some(Command::List(args)) => run_list(&common, &args, &rs),
-		Some(Command::Search(args)) => run_search(&common, &args, &rs),
-		None => {
-			let default_target = cli.legacy_target.unwrap_or_else(|| "./".to_string());
-			if !cli.legacy_extra.is_empty() {
-				let mut extras = cli.legacy_extra;
-				if extras.first().is_some_and(|s| s == "search") {
-					extras.remove(0);
-				}
-				let query = extras.join(" ").trim().to_string();
-				if query.is_empty() {
-					return Err("A search query is required when trailing arguments are provided without a subcommand.".into());
-				}
-				let search_args = SearchArgs {
-					target: default_target,
-					query: Some(query),
-					filters: SearchFilterArgs::default(),
-				};
-				run_search(&common, &search_args, &rs)
-			} else {
-				run_render(&common, &default_target, &rs)
-			}
+/// How a command that ran without errors ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+	/// The command wrote its output.
+	Success,
+	/// A search or listing matched nothing, which exits with [`EXIT_NO_MATCHES`].
+	NoMatches,
+}
+
+/// Exit status for `err`: the code of a [`RipdocError`], or [`EXIT_FAILURE`] for anything else.
+fn exit_code(err: &(dyn Error + 'static)) -> i32 {
+	err.downcast_ref::<RipdocError>()
+		.map_or(EXIT_FAILURE, RipdocError::exit_code)
+}
+
+/// Whether `err` is a write to a stdout whose reader has closed it.
+fn is_broken_pipe(err: &(dyn Error + 'static)) -> bool {
+	err.downcast_ref::<io::Error>()
+		.is_some_and(|err| err.kind() == io::ErrorKind::BrokenPipe)
+}
+
+fn run(cli: &Cli) -> Result<Outcome, Box<dyn Error>> {
+	let common = &cli.common;
+	if common.dry_run {
+		let targets = command_targets(cli);
+		if targets.is_empty() {
+			return Err("--dry-run only applies to commands that build a target".into());
+		}
+		let rs = build_ripdoc(common);
+		for target in targets {
+			run_dry_run(common, target, &rs)?;
+		}
+		return Ok(Outcome::Success);
+	}
+	if common.resolve_only {
+		let targets = command_targets(cli);
+		if targets.is_empty() {
+			return Err("--resolve-only only applies to commands that build a target".into());
+		}
+		let rs = build_ripdoc(common);
+		for target in targets {
+			run_resolve_only(common, target, &rs)?;
+		}
+		return Ok(Outcome::Success);
+	}
+
+	let mut rs = build_ripdoc(common);
+	let stdout = match &common.output {
+		Some(path) => Output::to_file(path.clone()),
+		None => Output::detect(common.color, common.no_pager || common.watch),
+	};
+
+	if common.stdin_json {
+		return run_stdin_json(cli, &stdout);
+	}
+	if common.summary {
+		for target in command_targets(cli) {
+			run_summary(common, target, &rs)?;
+		}
+		return Ok(Outcome::Success);
+	}
+	if common.unreachable_pub {
+		for target in command_targets(cli) {
+			run_unreachable_pub(common, target, &rs)?;
+		}
+		return Ok(Outcome::Success);
+	}
+	if common.watch {
+		// Cache entries are keyed on the manifest rather than the sources, so they would keep
+		// serving the first render.
+		rs = rs.with_cache(false);
+		run_watch(cli, &rs, &stdout)?;
+		return Ok(Outcome::Success);
+	}
+	let outcome = dispatch(cli, &rs, &stdout)?;
+	if common.emit_json_path {
+		for target in command_targets(cli) {
+			emit_json_path(common, target, &rs)?;
+		}
+	}
+	Ok(outcome)
+}
+
+/// Print where the rustdoc JSON for `target` is on stderr.
+///
+/// The crate was just built, so this reads it back from the cache or rustdoc's output.
+fn emit_json_path(common: &CommonArgs, target: &str, rs: &Ripdoc) -> Result<(), Box<dyn Error>> {
+	let json = rs.inspect_with_path(
+		target,
+		common.no_default_features,
+		common.all_features,
+		&common.features,
+		common.private,
+	)?;
+	match json.path {
+		Some(path) => eprintln!("rustdoc JSON for {target}: {}", path.display()),
+		None => eprintln!(
+			"rustdoc JSON for {target} was served from the ripdoc cache; rustdoc's output is no \
+			 longer on disk"
+		),
+	}
+	Ok(())
+}
+
+/// Render once, then again every time the target package's sources change.
+fn run_watch(cli: &Cli, rs: &Ripdoc, stdout: &Output) -> Result<(), Box<dyn Error>> {
+	let target = match command_targets(cli) {
+		[] => return Err("--watch only applies to commands that build a target".into()),
+		[target] => target,
+		_ => return Err("--watch takes a single target".into()),
+	};
+	let root = rs.source_root(target)?;
+	let report = |result: Result<Outcome, Box<dyn Error>>| match result {
+		Err(e) if is_broken_pipe(e.as_ref()) => process::exit(0),
+		Err(e) => print_error(e.as_ref()),
+		Ok(_) => {}
+	};
+
+	report(dispatch(cli, rs, stdout));
+	log::info!("watching {} for changes...", root.display());
+	watch::watch(&root, || {
+		eprintln!("{}", watch::separator());
+		report(dispatch(cli, rs, stdout));
+		true
+	})?;
+	Ok(())
+}
+
+/// Run the selected command once.
+fn dispatch(cli: &Cli, rs: &Ripdoc, stdout: &Output) -> Result<Outcome, Box<dyn Error>> {
+	let common = &cli.common;
+	match &cli.command {
+		Some(Command::Render(args)) => run_render(common, &args.targets, rs, stdout)?,
+		Some(Command::Raw(args)) => run_raw(common, args, rs, stdout)?,
+		Some(Command::List(args)) => return run_list(common, args, rs, stdout),
+		Some(Command::Search(args)) => return run_search(common, args, rs, stdout),
+		Some(Command::Serve(args)) => run_serve(common, args, rs)?,
+		Some(Command::Completions(_) | Command::Manpage) => {
+			unreachable!("`main` writes generated artifacts before dispatching")
 		}
+		None => run_render(common, &cli.targets, rs, stdout)?,
 	}
+	Ok(Outcome::Success)
 }
 #[derive(Debug, Clone, Copy, ValueEnum)]
 /// Output formats the CLI can emit.
@@ -504,6 +1768,29 @@ enum OutputFormat {
 	/// Emit Markdown with stripped documentation markers.
 	#[value(alias = "md")]
 	Markdown,
+	/// Emit a structured JSON tree describing the skeleton.
+	Json,
+}
+
+impl OutputFormat {
+	/// File extension for output in this format.
+	fn extension(self) -> &'static str {
+		match self {
+			Self::Rust => "rs",
+			Self::Markdown => "md",
+			Self::Json => "json",
+		}
+	}
+}
+
+impl From<RenderFormat> for OutputFormat {
+	fn from(format: RenderFormat) -> Self {
+		match format {
+			RenderFormat::Rust => Self::Rust,
+			RenderFormat::Markdown => Self::Markdown,
+			RenderFormat::Json => Self::Json,
+		}
+	}
 }
 
 impl From<OutputFormat> for RenderFormat {
@@ -511,6 +1798,175 @@ impl From<OutputFormat> for RenderFormat {
 		match format {
 			OutputFormat::Rust => RenderFormat::Rust,
 			OutputFormat::Markdown => RenderFormat::Markdown,
+			OutputFormat::Json => RenderFormat::Json,
+		}
+	}
+}
+
+/// Function bodies the CLI can render, mirroring [`BodyStyle`].
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum BodyStyleArg {
+	/// `{}` for every function (default).
+	Empty,
+	/// `{ todo!() }` for functions that return a value.
+	Todo,
+	/// `{ unimplemented!() }` for functions that return a value.
+	Unimplemented,
+}
+
+impl From<BodyStyleArg> for BodyStyle {
+	fn from(style: BodyStyleArg) -> Self {
+		match style {
+			BodyStyleArg::Empty => Self::Empty,
+			BodyStyleArg::Todo => Self::Todo,
+			BodyStyleArg::Unimplemented => Self::Unimplemented,
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use clap::error::ErrorKind;
+
+	use super::*;
+
+	fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+		Cli::try_parse_from(std::iter::once("ripdoc").chain(args.iter().copied()))
+	}
+
+	#[test]
+	fn manifest_path_replaces_the_default_target() {
+		let mut cli = parse(&["--manifest-path", "workspace/pkg1/Cargo.toml"]).unwrap();
+		apply_manifest_path(&mut cli).unwrap();
+		assert_eq!(cli.targets, ["workspace/pkg1/Cargo.toml"]);
+
+		let mut cli = parse(&[
+			"--manifest-path",
+			"workspace/pkg1/Cargo.toml",
+			"list",
+			"::module",
+		])
+		.unwrap();
+		apply_manifest_path(&mut cli).unwrap();
+		assert_eq!(command_targets(&cli), ["workspace/pkg1/Cargo.toml::module"]);
+
+		let mut cli = parse(&["--manifest-path", "Cargo.toml", "serde"]).unwrap();
+		assert!(apply_manifest_path(&mut cli).is_err());
+		let mut cli = parse(&["--manifest-path", "workspace/pkg1"]).unwrap();
+		assert!(apply_manifest_path(&mut cli).is_err());
+	}
+
+	#[test]
+	fn dispatch_runs_the_selected_command() {
+		let dir = tempfile::tempdir().unwrap();
+		let stdout = Output::to_file(dir.path().join("out.rs"));
+		let rs = Ripdoc::new().with_offline(true).with_silent(true);
+
+		// An empty query is answered without building anything.
+		let cli = parse(&["search", "./", "  "]).unwrap();
+		assert_eq!(dispatch(&cli, &rs, &stdout).unwrap(), Outcome::Success);
+
+		let missing = dir.path().join("missing");
+		let cli = parse(&["render", missing.to_str().unwrap()]).unwrap();
+		assert!(dispatch(&cli, &rs, &stdout).is_err());
+		assert!(!dir.path().join("out.rs").exists());
+	}
+
+	#[test]
+	fn search_tuning_flags_require_a_query() {
+		for flag in [
+			"--search-spec=doc",
+			"--search-case-sensitive",
+			"--fuzzy",
+			"--direct-match-only",
+		] {
+			for command in [&["list", "."][..], &["raw", "."], &["search", "serde"]] {
+				let args: Vec<&str> = command.iter().copied().chain([flag]).collect();
+				let err = parse(&args)
+					.err()
+					.unwrap_or_else(|| panic!("{args:?} parsed"));
+				assert_eq!(err.kind(), ErrorKind::MissingRequiredArgument, "{args:?}");
+			}
+		}
+
+		assert!(parse(&["list", ".", "--query", "de", "--search-spec=doc"]).is_ok());
+		assert!(parse(&["raw", ".", "--query", "de", "--fuzzy"]).is_ok());
+		assert!(parse(&["search", "serde", "de", "--direct-match-only"]).is_ok());
+	}
+
+	#[test]
+	fn negated_flags_override_config_files() {
+		let files = Config {
+			private: Some(true),
+			no_default_features: Some(true),
+			offline: Some(true),
+			..Config::default()
+		};
+		let mut cli = parse(&[".", "--no-private", "--default-features"]).unwrap();
+		apply_config(&mut cli.common, files.clone());
+		assert!(!cli.common.private);
+		assert!(!cli.common.no_default_features);
+		assert!(cli.common.offline);
+
+		let mut cli = parse(&["--private", "--no-private", "--no-offline", "-p", "."]).unwrap();
+		apply_config(&mut cli.common, files);
+		assert!(cli.common.private);
+		assert!(!cli.common.offline);
+	}
+
+	#[test]
+	fn ignored_flags_are_reported() {
+		let warnings = |args: &[&str]| ignored_flag_warnings(&parse(args).unwrap());
+
+		let listed = warnings(&[
+			"--auto-impls",
+			"list",
+			".",
+			"-s",
+			"x",
+			"--direct-match-only",
+		]);
+		assert_eq!(listed.len(), 2, "{listed:?}");
+		assert!(listed[0].starts_with("--auto-impls is ignored by `list`"));
+		assert!(listed[1].starts_with("--direct-match-only is ignored by `list`"));
+		assert_eq!(
+			warnings(&["--format", "markdown", "raw", "."]),
+			["--format markdown is ignored by `raw`, which always emits rustdoc JSON"]
+		);
+
+		assert!(warnings(&["--format", "json", "raw", "."]).is_empty());
+		assert!(warnings(&["--auto-impls", "render", "."]).is_empty());
+		assert!(warnings(&["--auto-impls", "search", "serde", "de"]).is_empty());
+		assert!(warnings(&["list", "."]).is_empty());
+	}
+
+	#[test]
+	fn split_modules_needs_an_output_dir() {
+		assert!(parse(&["--split-modules", "."]).is_err());
+		assert!(parse(&["--output-dir", "out", "."]).is_err());
+		assert!(parse(&["--output-dir", "out", "--output", "x.rs", "--split-modules"]).is_err());
+		assert!(parse(&["--output-dir", "out", "--split-modules", "render", "."]).is_ok());
+
+		let warnings = |args: &[&str]| ignored_flag_warnings(&parse(args).unwrap());
+		assert_eq!(
+			warnings(&["--output-dir", "out", "--split-modules", "--format", "json"]),
+			["--format json is ignored by --split-modules, which always writes Rust"]
+		);
+		assert!(warnings(&["--output-dir", "out", "--split-modules", "-f", "rust"]).is_empty());
+	}
+
+	#[test]
+	fn stdin_json_takes_no_target() {
+		assert!(parse(&["--stdin-json", "serde"]).is_err());
+		assert!(parse(&["--stdin-json", "--dry-run"]).is_err());
+		assert!(parse(&["--filter", "de::value"]).is_err());
+		assert!(parse(&["--stdin-json", "--filter", "de::value"]).is_ok());
+		assert!(parse(&["--stdin-json", "search", "Connection"]).is_ok());
+
+		let warnings = ignored_flag_warnings(&parse(&["--stdin-json", "-F", "std"]).unwrap());
+		assert_eq!(
+			warnings,
+			["--features is ignored by --stdin-json, which reads a crate rustdoc already built"]
+		);
+	}
+}
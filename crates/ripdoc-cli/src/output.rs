@@ -0,0 +1,248 @@
+//! Terminal output: ANSI color decisions, paging of long output, and `--output` files.
+
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::{env, fs};
+
+use clap::ValueEnum;
+
+/// Pager used when neither `RIPDOC_PAGER` nor `PAGER` is set.
+const DEFAULT_PAGER: &str = "less -RFX";
+
+/// When to color terminal output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+	/// Color when stdout is a terminal.
+	#[default]
+	Auto,
+	/// Always color, even when output is piped.
+	Always,
+	/// Never color.
+	Never,
+}
+
+impl ColorChoice {
+	/// Whether output to stdout should be colored.
+	fn enabled(self, stdout_is_tty: bool) -> bool {
+		match self {
+			Self::Auto => stdout_is_tty,
+			Self::Always => true,
+			Self::Never => false,
+		}
+	}
+}
+
+/// Destination for command output, deciding on colors and paging up front.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output {
+	/// Whether highlighted output may include ANSI color codes.
+	pub color: bool,
+	/// Whether stdout is a terminal, which can show box-drawing characters.
+	pub terminal: bool,
+	/// Pager command line used when output overflows the terminal, if paging is enabled.
+	pager: Option<String>,
+	/// Terminal height in rows, if known.
+	height: Option<usize>,
+	/// File replaced with each printed output instead of writing to stdout.
+	file: Option<PathBuf>,
+}
+
+impl Output {
+	/// Inspect stdout and the environment to decide on colors and paging.
+	pub fn detect(color: ColorChoice, no_pager: bool) -> Self {
+		let height = terminal_size::terminal_size().map(|(_, height)| usize::from(height.0));
+		Self::new(
+			io::stdout().is_terminal(),
+			color,
+			no_pager,
+			env::var("RIPDOC_PAGER").ok(),
+			env::var("PAGER").ok(),
+			height,
+		)
+	}
+
+	/// Decide on colors and paging from explicit inputs.
+	///
+	/// Paging requires a terminal on stdout, and so do colors unless `color` forces them:
+	/// redirected output stays free of escape sequences. `RIPDOC_PAGER` takes precedence over
+	/// `PAGER`, and an empty value disables paging.
+	fn new(
+		stdout_is_tty: bool,
+		color: ColorChoice,
+		no_pager: bool,
+		ripdoc_pager: Option<String>,
+		pager: Option<String>,
+		height: Option<usize>,
+	) -> Self {
+		let pager = if stdout_is_tty && !no_pager {
+			let command = ripdoc_pager
+				.or(pager)
+				.unwrap_or_else(|| DEFAULT_PAGER.to_string());
+			Some(command.trim().to_string()).filter(|command| !command.is_empty())
+		} else {
+			None
+		};
+		Self {
+			color: color.enabled(stdout_is_tty),
+			terminal: stdout_is_tty,
+			pager,
+			height,
+			file: None,
+		}
+	}
+
+	/// Send output to `path` instead of stdout, without colors or paging.
+	pub fn to_file(path: PathBuf) -> Self {
+		Self {
+			color: false,
+			terminal: false,
+			pager: None,
+			height: None,
+			file: Some(path),
+		}
+	}
+
+	/// Pager to run for `text`, or `None` when it fits on screen or paging is disabled.
+	///
+	/// Output is paged when the terminal height is unknown; `less -F` still exits immediately
+	/// if the text fits.
+	fn pager_for(&self, text: &str) -> Option<&str> {
+		let pager = self.pager.as_deref()?;
+		let fits = self
+			.height
+			.is_some_and(|height| text.lines().count() < height);
+		(!fits).then_some(pager)
+	}
+
+	/// Write `text` to stdout, through the pager when it is taller than the terminal.
+	///
+	/// Falls back to writing directly if the pager cannot be started. With an output file, the
+	/// file is replaced atomically so readers never observe a partial write.
+	pub fn print(&self, text: &str) -> io::Result<()> {
+		if let Some(path) = &self.file {
+			let mut temp_path = path.clone().into_os_string();
+			temp_path.push(".tmp");
+			fs::write(&temp_path, text)?;
+			return fs::rename(&temp_path, path);
+		}
+		if let Some(pager) = self.pager_for(text) {
+			match spawn_pager(pager) {
+				Ok(child) => return feed_pager(child, text),
+				Err(err) => log::debug!("failed to start pager `{pager}`: {err}"),
+			}
+		}
+		let mut stdout = io::stdout().lock();
+		stdout.write_all(text.as_bytes())?;
+		stdout.flush()
+	}
+}
+
+/// Start the pager command line with a piped stdin.
+fn spawn_pager(command: &str) -> io::Result<Child> {
+	let mut parts = command.split_whitespace();
+	let program = parts
+		.next()
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "empty pager command"))?;
+	Command::new(program)
+		.args(parts)
+		.stdin(Stdio::piped())
+		.spawn()
+}
+
+/// Send `text` to the pager and wait for the user to close it.
+fn feed_pager(mut child: Child, text: &str) -> io::Result<()> {
+	if let Some(mut stdin) = child.stdin.take() {
+		// Quitting the pager before reading everything closes the pipe; that is not an error.
+		match stdin.write_all(text.as_bytes()) {
+			Err(err) if err.kind() == io::ErrorKind::BrokenPipe => {}
+			result => result?,
+		}
+	}
+	child.wait()?;
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn output(stdout_is_tty: bool, no_pager: bool) -> Output {
+		Output::new(
+			stdout_is_tty,
+			ColorChoice::Auto,
+			no_pager,
+			None,
+			None,
+			Some(3),
+		)
+	}
+
+	#[test]
+	fn colors_require_a_terminal() {
+		assert!(output(true, false).color);
+		assert!(output(true, true).color);
+		assert!(!output(false, false).color);
+	}
+
+	#[test]
+	fn color_choice_overrides_terminal_detection() {
+		let color = |stdout_is_tty, choice| {
+			Output::new(stdout_is_tty, choice, false, None, None, None).color
+		};
+		assert!(color(false, ColorChoice::Always));
+		assert!(color(true, ColorChoice::Always));
+		assert!(!color(true, ColorChoice::Never));
+		assert!(!color(false, ColorChoice::Never));
+		assert!(!color(false, ColorChoice::Auto));
+	}
+
+	#[test]
+	fn forced_colors_do_not_enable_paging() {
+		let out = Output::new(false, ColorChoice::Always, false, None, None, None);
+		assert_eq!(out.pager, None);
+	}
+
+	#[test]
+	fn paging_requires_a_terminal_and_no_opt_out() {
+		assert_eq!(output(true, false).pager.as_deref(), Some(DEFAULT_PAGER));
+		assert_eq!(output(true, true).pager, None);
+		assert_eq!(output(false, false).pager, None);
+	}
+
+	#[test]
+	fn ripdoc_pager_overrides_pager() {
+		let env = |ripdoc: Option<&str>, pager: Option<&str>| {
+			Output::new(
+				true,
+				ColorChoice::Auto,
+				false,
+				ripdoc.map(str::to_string),
+				pager.map(str::to_string),
+				None,
+			)
+			.pager
+		};
+		assert_eq!(env(Some("most"), Some("more")).as_deref(), Some("most"));
+		assert_eq!(env(None, Some("more")).as_deref(), Some("more"));
+		assert_eq!(env(Some(""), Some("more")), None);
+		assert_eq!(env(None, Some("  ")), None);
+	}
+
+	#[test]
+	fn output_file_is_never_colored_or_paged() {
+		let out = Output::to_file(PathBuf::from("skeleton.md"));
+		assert!(!out.color);
+		assert_eq!(out.pager_for("a\nb\nc\nd\n"), None);
+	}
+
+	#[test]
+	fn pages_only_output_taller_than_the_terminal() {
+		let out = output(true, false);
+		assert_eq!(out.pager_for("a\nb\n"), None);
+		assert_eq!(out.pager_for("a\nb\nc\n"), Some(DEFAULT_PAGER));
+
+		let unknown_height = Output::new(true, ColorChoice::Auto, false, None, None, None);
+		assert_eq!(unknown_height.pager_for("a\n"), Some(DEFAULT_PAGER));
+	}
+}
@@ -0,0 +1,451 @@
+//! `ripdoc serve --stdio`: a JSON-RPC 2.0 server reading one request per line from stdin and
+//! writing one response per line to stdout.
+//!
+//! Loaded crates are cached in memory, keyed by target and build settings, so repeated requests
+//! for the same crate skip cargo and rustdoc entirely.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use std::io::{self, BufRead, Write};
+
+use ripdoc_core::error::{RenderError, RipdocError};
+use ripdoc_core::search::SearchResult;
+use ripdoc_core::{
+	ListItem, LoadOptions, LoadedCrate, MatchMode, Ripdoc, SearchConfig, SearchDomain,
+	SearchOptions, SourceLocation,
+};
+use serde_json::{Map, Value, json};
+
+/// The request line is not valid JSON.
+const PARSE_ERROR: i64 = -32700;
+/// The request is not a JSON-RPC request object.
+const INVALID_REQUEST: i64 = -32600;
+/// The requested method does not exist.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// The request parameters are missing or have the wrong type.
+const INVALID_PARAMS: i64 = -32602;
+
+/// [`RipdocError::Cargo`]: resolving or documenting the target failed.
+const CARGO_ERROR: i64 = 1;
+/// [`RipdocError::Render`]: rendering the skeleton failed.
+const RENDER_ERROR: i64 = 2;
+/// The filter path matched nothing; `data` carries the filter and any suggestions.
+const FILTER_NOT_MATCHED: i64 = 3;
+/// [`RipdocError::PrivateFilter`]: the filter path names a private item.
+const PRIVATE_FILTER: i64 = 4;
+/// [`RipdocError::InvalidTarget`]: the target specification is invalid.
+const INVALID_TARGET: i64 = 5;
+/// [`RipdocError::Config`]: a config file could not be read or parsed.
+const CONFIG_ERROR: i64 = 6;
+/// [`RipdocError::Serialization`]: JSON encoding or decoding failed.
+const SERIALIZATION_ERROR: i64 = 7;
+
+/// Error object returned in a JSON-RPC error response.
+#[derive(Debug, Clone, PartialEq)]
+struct RpcError {
+	/// Numeric error code.
+	code: i64,
+	/// Human-readable description.
+	message: String,
+	/// Structured details, if any.
+	data: Option<Value>,
+}
+
+impl RpcError {
+	fn new(code: i64, message: impl Into<String>) -> Self {
+		Self {
+			code,
+			message: message.into(),
+			data: None,
+		}
+	}
+
+	fn to_json(&self) -> Value {
+		let mut error = json!({ "code": self.code, "message": self.message });
+		if let Some(data) = &self.data {
+			error["data"] = data.clone();
+		}
+		error
+	}
+}
+
+impl From<RipdocError> for RpcError {
+	fn from(err: RipdocError) -> Self {
+		let (code, data) = match &err {
+			RipdocError::Cargo(_) => (CARGO_ERROR, None),
+			RipdocError::Render(RenderError::FilterNotMatched(filter)) => (
+				FILTER_NOT_MATCHED,
+				Some(json!({ "filter": filter, "suggestions": [] })),
+			),
+			RipdocError::Render(_) => (RENDER_ERROR, None),
+			RipdocError::Serialization(_) => (SERIALIZATION_ERROR, None),
+			RipdocError::InvalidTarget(_) => (INVALID_TARGET, None),
+			RipdocError::Config(_) => (CONFIG_ERROR, None),
+			RipdocError::PrivateFilter(path) => (PRIVATE_FILTER, Some(json!({ "path": path }))),
+			RipdocError::FilterSuggestions {
+				filter,
+				suggestions,
+			} => (
+				FILTER_NOT_MATCHED,
+				Some(json!({ "filter": filter, "suggestions": suggestions })),
+			),
+		};
+		Self {
+			code,
+			message: err.to_string(),
+			data,
+		}
+	}
+}
+
+/// Typed access to a request's named parameters.
+struct Params<'a>(Option<&'a Map<String, Value>>);
+
+impl<'a> Params<'a> {
+	fn new(params: Option<&'a Value>) -> Result<Self, RpcError> {
+		match params {
+			None | Some(Value::Null) => Ok(Self(None)),
+			Some(Value::Object(map)) => Ok(Self(Some(map))),
+			Some(_) => Err(RpcError::new(
+				INVALID_PARAMS,
+				"params must be an object of named parameters",
+			)),
+		}
+	}
+
+	fn get(&self, name: &str) -> Option<&'a Value> {
+		self.0
+			.and_then(|map| map.get(name))
+			.filter(|value| !value.is_null())
+	}
+
+	fn invalid(name: &str, expected: &str) -> RpcError {
+		RpcError::new(INVALID_PARAMS, format!("`{name}` must be {expected}"))
+	}
+
+	fn string(&self, name: &str) -> Result<Option<&'a str>, RpcError> {
+		self.get(name)
+			.map(|value| {
+				value
+					.as_str()
+					.ok_or_else(|| Self::invalid(name, "a string"))
+			})
+			.transpose()
+	}
+
+	fn required_string(&self, name: &str) -> Result<&'a str, RpcError> {
+		self.string(name)?
+			.ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("missing `{name}`")))
+	}
+
+	fn bool(&self, name: &str) -> Result<Option<bool>, RpcError> {
+		self.get(name)
+			.map(|value| {
+				value
+					.as_bool()
+					.ok_or_else(|| Self::invalid(name, "a boolean"))
+			})
+			.transpose()
+	}
+
+	fn strings(&self, name: &str) -> Result<Option<Vec<String>>, RpcError> {
+		self.get(name)
+			.map(|value| {
+				value
+					.as_array()
+					.and_then(|items| {
+						items
+							.iter()
+							.map(|item| item.as_str().map(str::to_string))
+							.collect()
+					})
+					.ok_or_else(|| Self::invalid(name, "an array of strings"))
+			})
+			.transpose()
+	}
+
+	fn domains(&self, name: &str) -> Result<Option<SearchDomain>, RpcError> {
+		let Some(names) = self.strings(name)? else {
+			return Ok(None);
+		};
+		let names: Vec<&str> = names.iter().map(String::as_str).collect();
+		SearchDomain::from_str_list(&names)
+			.map(Some)
+			.map_err(|err| RpcError::new(INVALID_PARAMS, err.to_string()))
+	}
+}
+
+/// JSON-RPC server answering `render`, `search`, `list`, and `features` requests.
+pub struct Server {
+	/// Configured API entry point used to load crates.
+	ripdoc: Ripdoc,
+	/// Build settings used for parameters a request leaves out.
+	defaults: LoadOptions,
+	/// Search settings used for parameters a request leaves out.
+	search_defaults: SearchConfig,
+	/// Loaded crates keyed by target and normalized build settings.
+	cache: HashMap<(String, LoadOptions), LoadedCrate>,
+}
+
+impl Server {
+	/// Create a server whose requests default to the given build and search settings.
+	pub fn new(ripdoc: Ripdoc, defaults: LoadOptions, search_defaults: SearchConfig) -> Self {
+		Self {
+			ripdoc,
+			defaults,
+			search_defaults,
+			cache: HashMap::new(),
+		}
+	}
+
+	/// Answer requests from `input` until it is closed, writing responses to `output`.
+	pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+		for line in input.lines() {
+			let line = line?;
+			if line.trim().is_empty() {
+				continue;
+			}
+			if let Some(response) = self.handle_line(&line) {
+				serde_json::to_writer(&mut output, &response)?;
+				output.write_all(b"\n")?;
+				output.flush()?;
+			}
+		}
+		Ok(())
+	}
+
+	/// Answer one request line, or return `None` for a notification, which gets no reply.
+	fn handle_line(&mut self, line: &str) -> Option<Value> {
+		let request: Value = match serde_json::from_str(line) {
+			Ok(request) => request,
+			Err(err) => {
+				let error = RpcError::new(PARSE_ERROR, format!("invalid JSON: {err}"));
+				return Some(error_response(Value::Null, &error));
+			}
+		};
+		let result = self.handle(&request);
+		let id = request.get("id")?.clone();
+		Some(match result {
+			Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+			Err(error) => error_response(id, &error),
+		})
+	}
+
+	fn handle(&mut self, request: &Value) -> Result<Value, RpcError> {
+		let method = request
+			.get("method")
+			.and_then(Value::as_str)
+			.ok_or_else(|| RpcError::new(INVALID_REQUEST, "request has no `method`"))?;
+		let params = Params::new(request.get("params"))?;
+		match method {
+			"render" => self.render(&params),
+			"search" => self.search(&params),
+			"list" => self.list(&params),
+			"features" => self.features(&params),
+			other => Err(RpcError::new(
+				METHOD_NOT_FOUND,
+				format!("unknown method `{other}`"),
+			)),
+		}
+	}
+
+	/// Fetch the crate a request names from the cache, loading it on a miss.
+	///
+	/// Also returns whether the crate was already cached.
+	fn load(&mut self, params: &Params<'_>) -> Result<(&LoadedCrate, bool), RpcError> {
+		let target = params.required_string("target")?.to_string();
+		let mut options = LoadOptions {
+			no_default_features: params
+				.bool("no_default_features")?
+				.unwrap_or(self.defaults.no_default_features),
+			all_features: params
+				.bool("all_features")?
+				.unwrap_or(self.defaults.all_features),
+			features: params
+				.strings("features")?
+				.unwrap_or_else(|| self.defaults.features.clone()),
+			include_private: params
+				.bool("private")?
+				.unwrap_or(self.defaults.include_private),
+		};
+		// Feature order does not change the build, so it must not split the cache.
+		options.features.sort();
+		options.features.dedup();
+
+		match self.cache.entry((target, options)) {
+			Entry::Occupied(entry) => Ok((entry.into_mut(), true)),
+			Entry::Vacant(entry) => {
+				let (target, options) = entry.key();
+				let loaded = self.ripdoc.load(target, options)?;
+				Ok((entry.insert(loaded), false))
+			}
+		}
+	}
+
+	fn render(&mut self, params: &Params<'_>) -> Result<Value, RpcError> {
+		let (loaded, cached) = self.load(params)?;
+		let rendered = loaded.render()?;
+		Ok(json!({ "rendered": rendered, "cached": cached }))
+	}
+
+	fn search(&mut self, params: &Params<'_>) -> Result<Value, RpcError> {
+		let query = params.required_string("query")?;
+		let options = self.search_options(params, query)?;
+		let (loaded, cached) = self.load(params)?;
+		let response = loaded.search(&options)?;
+		let results: Vec<Value> = response.results.iter().map(search_result_json).collect();
+		Ok(json!({ "results": results, "rendered": response.rendered, "cached": cached }))
+	}
+
+	fn list(&mut self, params: &Params<'_>) -> Result<Value, RpcError> {
+		let options = match params.string("query")? {
+			Some(query) => Some(self.search_options(params, query)?),
+			None => None,
+		};
+		let (loaded, cached) = self.load(params)?;
+		let items: Vec<Value> = listed_items(loaded, options.as_ref())
+			.iter()
+			.map(list_item_json)
+			.collect();
+		Ok(json!({ "items": items, "cached": cached }))
+	}
+
+	fn features(&mut self, params: &Params<'_>) -> Result<Value, RpcError> {
+		let target = params.required_string("target")?;
+		let features = self.ripdoc.features(target)?;
+		Ok(json!({ "features": features }))
+	}
+
+	/// Build search options from a request, falling back to the server's search defaults.
+	fn search_options(&self, params: &Params<'_>, query: &str) -> Result<SearchOptions, RpcError> {
+		let requested = SearchConfig {
+			case_sensitive: params.bool("case_sensitive")?,
+			fuzzy: params.bool("fuzzy")?,
+			domains: params.domains("domains")?,
+			direct_match_only: params.bool("direct_match_only")?,
+		};
+		Ok(search_options(requested, &self.search_defaults, query))
+	}
+}
+
+/// Search options for `query`, taking the settings `requested` leaves out from `defaults`.
+pub(crate) fn search_options(
+	requested: SearchConfig,
+	defaults: &SearchConfig,
+	query: &str,
+) -> SearchOptions {
+	let search = requested.or(defaults.clone());
+	let mut options = SearchOptions::new(query);
+	options.case_sensitive = search.case_sensitive.unwrap_or(false);
+	if search.fuzzy.unwrap_or(false) {
+		options.match_mode = MatchMode::Fuzzy;
+	}
+	options.expand_containers = !search.direct_match_only.unwrap_or(false);
+	options.domains = search.domains.unwrap_or_default();
+	options
+}
+
+/// Items of `loaded` listed for a `list` request: all of them, or those `search` matches
+/// without `use` items.
+pub(crate) fn listed_items(loaded: &LoadedCrate, search: Option<&SearchOptions>) -> Vec<ListItem> {
+	match search {
+		Some(options) => loaded.list_matching(options),
+		None => loaded.list(),
+	}
+}
+
+/// JSON form of a search result.
+pub(crate) fn search_result_json(result: &SearchResult) -> Value {
+	json!({
+		"crate": result.crate_name,
+		"kind": result.kind.label(),
+		"path": result.path_string,
+		"definition_path": result.definition_path,
+		"source": result.source.as_ref().map(source_json),
+	})
+}
+
+/// JSON form of a listed item.
+pub(crate) fn list_item_json(item: &ListItem) -> Value {
+	json!({
+		"kind": item.kind.label(),
+		"path": item.path,
+		"source": item.source.as_ref().map(source_json),
+	})
+}
+
+/// JSON form of a source location.
+pub(crate) fn source_json(location: &SourceLocation) -> Value {
+	json!({ "path": location.path, "line": location.line, "column": location.column })
+}
+
+/// JSON-RPC error response carrying `error`.
+fn error_response(id: Value, error: &RpcError) -> Value {
+	json!({ "jsonrpc": "2.0", "id": id, "error": error.to_json() })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn server() -> Server {
+		Server::new(
+			Ripdoc::new().with_offline(true).with_silent(true),
+			LoadOptions::default(),
+			SearchConfig::default(),
+		)
+	}
+
+	fn error_code(response: &Value) -> i64 {
+		response["error"]["code"].as_i64().unwrap()
+	}
+
+	#[test]
+	fn malformed_requests_get_protocol_errors() {
+		let mut server = server();
+
+		let response = server.handle_line("{not json").unwrap();
+		assert_eq!(error_code(&response), PARSE_ERROR);
+		assert_eq!(response["id"], Value::Null);
+
+		let response = server.handle_line(r#"{"jsonrpc":"2.0","id":1}"#).unwrap();
+		assert_eq!(error_code(&response), INVALID_REQUEST);
+
+		let response = server
+			.handle_line(r#"{"jsonrpc":"2.0","id":2,"method":"explode"}"#)
+			.unwrap();
+		assert_eq!(error_code(&response), METHOD_NOT_FOUND);
+		assert_eq!(response["id"], 2);
+
+		let response = server
+			.handle_line(r#"{"jsonrpc":"2.0","id":3,"method":"render","params":{"target":7}}"#)
+			.unwrap();
+		assert_eq!(error_code(&response), INVALID_PARAMS);
+	}
+
+	#[test]
+	fn notifications_get_no_reply() {
+		assert_eq!(
+			server().handle_line(r#"{"jsonrpc":"2.0","method":"explode"}"#),
+			None
+		);
+	}
+
+	#[test]
+	fn ripdoc_errors_map_to_structured_codes() {
+		let error = RpcError::from(RipdocError::PrivateFilter("inner".to_string()));
+		assert_eq!(error.code, PRIVATE_FILTER);
+		assert_eq!(error.data, Some(json!({ "path": "inner" })));
+
+		let error = RpcError::from(RipdocError::FilterSuggestions {
+			filter: "Wdget".to_string(),
+			suggestions: vec!["Widget".to_string()],
+		});
+		assert_eq!(error.code, FILTER_NOT_MATCHED);
+		assert_eq!(error.data.unwrap()["suggestions"], json!(["Widget"]));
+
+		let error = RpcError::from(RipdocError::InvalidTarget("bad target".to_string()));
+		assert_eq!(error.code, INVALID_TARGET);
+		assert_eq!(error.message, "bad target");
+	}
+}
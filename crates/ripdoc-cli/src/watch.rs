@@ -0,0 +1,136 @@
+//! Re-running a command when a package's sources change.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+/// Quiet period after a change before refreshing, so a burst of events causes one refresh.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Paths under a package root whose changes trigger a refresh: `src/` and the manifest.
+///
+/// Build output under `target/` is deliberately excluded, as rendering writes there.
+fn watch_paths(root: &Path) -> Vec<(PathBuf, RecursiveMode)> {
+	[
+		(root.join("src"), RecursiveMode::Recursive),
+		(root.join("Cargo.toml"), RecursiveMode::NonRecursive),
+	]
+	.into_iter()
+	.filter(|(path, _)| path.exists())
+	.collect()
+}
+
+/// Block watching the package at `root`, calling `on_change` once per debounced burst of changes.
+///
+/// Returns when `on_change` returns `false` or the watcher shuts down.
+pub fn watch(root: &Path, mut on_change: impl FnMut() -> bool) -> notify::Result<()> {
+	let paths = watch_paths(root);
+	if paths.is_empty() {
+		return Err(notify::Error::generic(&format!(
+			"nothing to watch: {} has neither src/ nor Cargo.toml",
+			root.display()
+		)));
+	}
+
+	let (tx, rx) = mpsc::channel();
+	let mut watcher = RecommendedWatcher::new(
+		move |event| {
+			let _ = tx.send(event);
+		},
+		Config::default().with_poll_interval(Duration::from_millis(250)),
+	)?;
+	for (path, mode) in &paths {
+		watcher.watch(path, *mode)?;
+	}
+
+	while let Ok(event) = rx.recv() {
+		if !is_change(event) {
+			continue;
+		}
+		// Swallow the rest of the burst before refreshing.
+		loop {
+			match rx.recv_timeout(DEBOUNCE) {
+				Ok(_) => {}
+				Err(RecvTimeoutError::Timeout) => break,
+				Err(RecvTimeoutError::Disconnected) => return Ok(()),
+			}
+		}
+		if !on_change() {
+			break;
+		}
+	}
+	Ok(())
+}
+
+/// Whether a watcher event reports a modification worth refreshing for.
+fn is_change(event: notify::Result<Event>) -> bool {
+	match event {
+		Ok(event) => !event.kind.is_access(),
+		Err(err) => {
+			log::warn!("watch error: {err}");
+			false
+		}
+	}
+}
+
+/// Line printed before each refresh, stamped with the current UTC time of day.
+pub fn separator() -> String {
+	let secs = SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map_or(0, |elapsed| elapsed.as_secs());
+	let (hours, minutes, seconds) = (secs / 3600 % 24, secs / 60 % 60, secs % 60);
+	format!("──── refreshed at {hours:02}:{minutes:02}:{seconds:02} UTC ────")
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+	use std::thread;
+
+	use tempfile::TempDir;
+
+	use super::*;
+
+	#[test]
+	fn watch_paths_skip_build_output() {
+		let temp_dir = TempDir::new().unwrap();
+		let root = temp_dir.path();
+		fs::create_dir_all(root.join("src")).unwrap();
+		fs::create_dir_all(root.join("target")).unwrap();
+
+		let paths: Vec<PathBuf> = watch_paths(root).into_iter().map(|(p, _)| p).collect();
+		assert_eq!(paths, [root.join("src")]);
+	}
+
+	#[test]
+	fn touching_a_source_file_fires_callback() {
+		let temp_dir = TempDir::new().unwrap();
+		let root = temp_dir.path().to_path_buf();
+		fs::create_dir_all(root.join("src")).unwrap();
+		fs::write(root.join("src/lib.rs"), "pub fn before() {}\n").unwrap();
+
+		let (fired_tx, fired_rx) = mpsc::channel();
+		let watched = root.clone();
+		thread::spawn(move || {
+			watch(&watched, || {
+				let _ = fired_tx.send(());
+				false
+			})
+		});
+
+		// Keep touching the file until the watcher, which starts asynchronously, notices.
+		for attempt in 0..20 {
+			fs::write(
+				root.join("src/lib.rs"),
+				format!("pub fn after_{attempt}() {{}}\n"),
+			)
+			.unwrap();
+			if fired_rx.recv_timeout(Duration::from_millis(500)).is_ok() {
+				return;
+			}
+		}
+		panic!("watch callback never fired");
+	}
+}
@@ -0,0 +1,606 @@
+//! Integration tests ensuring the CLI keeps stdout reserved for generated output.
+#![allow(clippy::tests_outside_test_module)]
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Command, Stdio};
+
+use tempfile::TempDir;
+
+/// Write a minimal library crate and return its directory.
+fn create_crate() -> TempDir {
+	let temp_dir = TempDir::new().unwrap();
+	fs::create_dir(temp_dir.path().join("src")).unwrap();
+	fs::write(temp_dir.path().join("src/lib.rs"), "pub fn present() {}\n").unwrap();
+	fs::write(
+		temp_dir.path().join("Cargo.toml"),
+		"[package]\nname = \"dummy_crate\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+	)
+	.unwrap();
+	temp_dir
+}
+
+#[test]
+fn search_without_matches_keeps_stdout_empty() {
+	let temp_dir = create_crate();
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(["--offline", "search"])
+		.arg(temp_dir.path())
+		.arg("definitely_missing")
+		.output()
+		.unwrap();
+
+	assert_eq!(output.status.code(), Some(4));
+	assert!(
+		output.stdout.is_empty(),
+		"{}",
+		String::from_utf8_lossy(&output.stdout)
+	);
+	assert!(String::from_utf8_lossy(&output.stderr).contains("no matches found"));
+
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(["--offline", "list"])
+		.arg(temp_dir.path())
+		.args(["--query", "definitely_missing"])
+		.output()
+		.unwrap();
+	assert_eq!(output.status.code(), Some(4));
+	assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn malformed_targets_exit_with_the_usage_code() {
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args([
+			"--offline",
+			"--no-config",
+			"render",
+			"dummy_crate@not-a-version",
+		])
+		.output()
+		.unwrap();
+
+	assert_eq!(
+		output.status.code(),
+		Some(2),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+	assert!(String::from_utf8_lossy(&output.stderr).contains("Invalid version"));
+}
+
+#[test]
+fn missing_toolchains_exit_with_the_toolchain_code() {
+	let temp_dir = create_crate();
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args([
+			"--offline",
+			"--toolchain",
+			"nightly-ripdoc-missing",
+			"render",
+		])
+		.arg(temp_dir.path())
+		.output()
+		.unwrap();
+
+	assert_eq!(
+		output.status.code(),
+		Some(5),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+	assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn search_with_matches_prints_only_the_skeleton() {
+	let temp_dir = create_crate();
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(["--offline", "--quiet", "--format", "rust", "search"])
+		.arg(temp_dir.path())
+		.arg("present")
+		.output()
+		.unwrap();
+
+	assert!(output.status.success());
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	// Stdout is not a terminal, so matches are not highlighted with ANSI escapes.
+	assert!(stdout.contains("pub fn present()"), "{stdout}");
+	assert!(!stdout.contains('\x1b'), "{stdout:?}");
+	assert!(
+		output.stderr.is_empty(),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+}
+
+#[test]
+fn closing_stdout_early_exits_quietly() {
+	let temp_dir = create_crate();
+	// Far more output than a pipe buffers, so the write after the reader leaves must fail.
+	let functions: String = (0..5000)
+		.map(|i| format!("/// Documented function number {i}.\npub fn function_{i}() {{}}\n"))
+		.collect();
+	fs::write(temp_dir.path().join("src/lib.rs"), functions).unwrap();
+
+	let mut child = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(["--offline", "--no-config", "render"])
+		.arg(temp_dir.path())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+	// Read a single line, like `ripdoc ... | head -n 1`, then close the pipe.
+	let mut first_line = String::new();
+	BufReader::new(child.stdout.take().unwrap())
+		.read_line(&mut first_line)
+		.unwrap();
+	let status = child.wait().unwrap();
+	let mut stderr = String::new();
+	child
+		.stderr
+		.take()
+		.unwrap()
+		.read_to_string(&mut stderr)
+		.unwrap();
+
+	assert!(!first_line.is_empty());
+	assert!(status.success(), "{status}: {stderr}");
+	assert!(!stderr.contains("panicked"), "{stderr}");
+	assert!(!stderr.contains("Broken pipe"), "{stderr}");
+}
+
+#[test]
+fn completions_and_manpage_are_printed_to_stdout() {
+	let completions = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(["completions", "bash"])
+		.output()
+		.unwrap();
+	assert!(completions.status.success());
+	let script = String::from_utf8_lossy(&completions.stdout);
+	assert!(script.contains("_ripdoc"), "{script}");
+	assert!(script.contains("markdown"), "{script}");
+
+	let manpage = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.arg("manpage")
+		.output()
+		.unwrap();
+	assert!(manpage.status.success());
+	assert!(String::from_utf8_lossy(&manpage.stdout).contains(".TH ripdoc"));
+}
+
+#[test]
+fn dry_run_prints_plan_without_building() {
+	let temp_dir = create_crate();
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args([
+			"--offline",
+			"--dry-run",
+			"--format",
+			"json",
+			"-F",
+			"b,a",
+			"--target",
+			"thumbv7em-none-eabihf",
+			"render",
+		])
+		.arg(temp_dir.path())
+		.output()
+		.unwrap();
+
+	assert!(
+		output.status.success(),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+	let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	assert_eq!(plan["package"], "dummy_crate");
+	assert_eq!(plan["version"], "0.1.0");
+	assert_eq!(plan["target"]["kind"], "lib");
+	assert_eq!(plan["features"], serde_json::json!(["a", "b"]));
+	assert_eq!(plan["target_triple"], "thumbv7em-none-eabihf");
+	assert!(!temp_dir.path().join("target").exists());
+}
+
+#[test]
+fn resolve_only_prints_package_without_building() {
+	let temp_dir = create_crate();
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(["--offline", "--resolve-only", "--format", "json", "render"])
+		.arg(temp_dir.path())
+		.output()
+		.unwrap();
+
+	assert!(
+		output.status.success(),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+	let info: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	assert_eq!(info["package"], "dummy_crate");
+	assert_eq!(info["version"], "0.1.0");
+	assert_eq!(info["source"], "path");
+	assert_eq!(info["filter"], "");
+	assert!(!temp_dir.path().join("target").exists());
+}
+
+#[test]
+fn project_config_fills_unset_flags() {
+	let temp_dir = create_crate();
+	fs::write(
+		temp_dir.path().join(".ripdoc.toml"),
+		"format = \"json\"\nfeatures = [\"from-config\"]\nunknown_key = 1\n",
+	)
+	.unwrap();
+	let config_home = TempDir::new().unwrap();
+	let dry_run = |flags: &[&str]| {
+		Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+			.env("XDG_CONFIG_HOME", config_home.path())
+			.args(["--offline", "--dry-run"])
+			.args(flags)
+			.arg("render")
+			.arg(temp_dir.path())
+			.output()
+			.unwrap()
+	};
+
+	let output = dry_run(&[]);
+	assert!(output.status.success());
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(
+		stderr.contains("unknown config key `unknown_key`"),
+		"{stderr}"
+	);
+	let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	assert_eq!(plan["features"], serde_json::json!(["from-config"]));
+
+	// Flags on the command line take precedence over the project file.
+	let output = dry_run(&["-F", "from-cli"]);
+	let plan: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+	assert_eq!(plan["features"], serde_json::json!(["from-cli"]));
+
+	let output = dry_run(&["--no-config"]);
+	assert!(output.status.success());
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	assert!(stdout.starts_with("package"), "{stdout}");
+	assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn several_targets_render_under_banners_and_report_failures() {
+	let first = create_crate();
+	let second = create_crate();
+	let missing = first.path().join("missing");
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(["--offline", "--quiet", "--format", "rust"])
+		.arg(first.path())
+		.arg(&missing)
+		.arg(second.path())
+		.output()
+		.unwrap();
+
+	assert!(!output.status.success());
+	let stdout = String::from_utf8_lossy(&output.stdout);
+	for target in [first.path(), second.path()] {
+		let banner = format!("// ===== {} =====", target.display());
+		assert!(stdout.contains(&banner), "{stdout}");
+	}
+	assert_eq!(stdout.matches("pub fn present()").count(), 2, "{stdout}");
+	assert_eq!(
+		stdout.matches("// crate: dummy_crate v0.1.0").count(),
+		2,
+		"{stdout}"
+	);
+	let stderr = String::from_utf8_lossy(&output.stderr);
+	assert!(stderr.contains(&missing.display().to_string()), "{stderr}");
+	assert!(stderr.contains("1 of 3 targets failed"), "{stderr}");
+}
+
+#[test]
+fn several_targets_write_one_file_each_into_an_output_directory() {
+	let temp_dir = create_crate();
+	let out_dir = TempDir::new().unwrap();
+	let target = temp_dir.path().to_str().unwrap();
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(["--offline", "--quiet", "--format", "markdown", "--output"])
+		.arg(out_dir.path())
+		.args(["render", target, &format!("{target}::present")])
+		.output()
+		.unwrap();
+
+	assert!(
+		output.status.success(),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+	assert!(output.stdout.is_empty());
+	let mut files: Vec<_> = fs::read_dir(out_dir.path())
+		.unwrap()
+		.map(|entry| entry.unwrap().file_name().into_string().unwrap())
+		.collect();
+	files.sort();
+	assert_eq!(files.len(), 2, "{files:?}");
+	assert!(files.iter().all(|file| file.ends_with(".md")), "{files:?}");
+	assert!(files[1].ends_with("__present.md"), "{files:?}");
+}
+
+#[test]
+fn color_never_matches_piped_output_and_always_adds_ansi() {
+	let temp_dir = create_crate();
+	let render = |flags: &[&str]| {
+		let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+			.args(["--offline", "--quiet", "--format", "rust"])
+			.args(flags)
+			.arg("render")
+			.arg(temp_dir.path())
+			.output()
+			.unwrap();
+		assert!(output.status.success());
+		String::from_utf8(output.stdout).unwrap()
+	};
+
+	// Stdout is a pipe, so `auto` leaves the output uncolored.
+	let piped = render(&[]);
+	assert!(piped.contains("pub fn present()"), "{piped}");
+	assert!(!piped.contains('\x1b'), "{piped:?}");
+	assert_eq!(render(&["--color", "never"]), piped);
+
+	let colored = render(&["--color", "always"]);
+	assert!(colored.contains('\x1b'), "{colored:?}");
+	let stripped = colored
+		.split('\x1b')
+		.enumerate()
+		.map(|(idx, part)| match idx {
+			0 => part,
+			_ => part.split_once('m').map_or(part, |(_, rest)| rest),
+		})
+		.collect::<String>();
+	assert_eq!(stripped, piped);
+}
+
+#[test]
+fn todo_bodies_render_a_crate_that_type_checks() {
+	let source = r#"
+/// Configuration for a widget.
+pub struct Config {
+	/// Widget width.
+	pub width: u32,
+}
+
+impl Config {
+	/// Create a configuration.
+	pub fn new(width: u32) -> Self {
+		Self { width }
+	}
+
+	/// Double the width.
+	pub fn grow(&mut self) {
+		self.width *= 2;
+	}
+}
+
+/// Something that can be measured.
+pub trait Measure {
+	/// Size of the item.
+	fn size(&self) -> usize;
+}
+
+impl Measure for Config {
+	fn size(&self) -> usize {
+		self.width as usize
+	}
+}
+
+/// Parse a width.
+pub fn parse(input: &str) -> Result<u32, std::num::ParseIntError> {
+	input.parse()
+}
+"#;
+	let temp_dir = create_crate();
+	fs::write(temp_dir.path().join("src/lib.rs"), source).unwrap();
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(["--offline", "--quiet", "--no-header", "--format", "rust"])
+		.args(["--body-style", "todo", "render"])
+		.arg(temp_dir.path())
+		.output()
+		.unwrap();
+	assert!(output.status.success());
+	let skeleton = String::from_utf8(output.stdout).unwrap();
+	assert!(skeleton.contains("todo!()"), "{skeleton}");
+
+	let stub = create_crate();
+	fs::write(stub.path().join("src/lib.rs"), &skeleton).unwrap();
+	let check = Command::new(env!("CARGO"))
+		.args(["check", "--offline", "--quiet"])
+		.current_dir(stub.path())
+		.output()
+		.unwrap();
+	assert!(
+		check.status.success(),
+		"{skeleton}\n{}",
+		String::from_utf8_lossy(&check.stderr)
+	);
+}
+
+#[test]
+fn split_modules_write_a_crate_that_type_checks() {
+	let source = r#"
+//! Shapes and helpers.
+
+/// Geometric shapes.
+pub mod shapes {
+	/// Deeply nested items.
+	pub mod inner {
+		/// A marker.
+		pub struct Deep;
+	}
+
+	/// A circle.
+	pub struct Circle {
+		/// Radius of the circle.
+		pub radius: f64,
+	}
+
+	/// Area of `circle`.
+	pub fn area(circle: &Circle) -> f64 {
+		circle.radius * circle.radius * 3.14
+	}
+}
+
+/// Helpers built on shapes.
+pub mod util {
+	/// A unit circle.
+	pub fn unit() -> crate::shapes::Circle {
+		crate::shapes::Circle { radius: 1.0 }
+	}
+}
+
+/// Circles at the crate root.
+pub use shapes::Circle;
+"#;
+	let temp_dir = create_crate();
+	fs::write(temp_dir.path().join("src/lib.rs"), source).unwrap();
+	let out = TempDir::new().unwrap();
+	let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args([
+			"--offline",
+			"--quiet",
+			"--body-style",
+			"todo",
+			"--split-modules",
+		])
+		.arg("--output-dir")
+		.arg(out.path())
+		.arg("render")
+		.arg(temp_dir.path())
+		.output()
+		.unwrap();
+	assert!(
+		output.status.success(),
+		"{}",
+		String::from_utf8_lossy(&output.stderr)
+	);
+	assert!(output.stdout.is_empty());
+
+	let lib = fs::read_to_string(out.path().join("src/lib.rs")).unwrap();
+	assert!(lib.contains("//! Shapes and helpers."), "{lib}");
+	assert!(lib.contains("pub mod shapes;"), "{lib}");
+	assert!(lib.contains("pub mod util;"), "{lib}");
+	let shapes = fs::read_to_string(out.path().join("src/shapes/mod.rs")).unwrap();
+	// Small nested modules stay inline.
+	assert!(shapes.contains("pub mod inner {"), "{shapes}");
+	assert!(
+		shapes.contains("pub fn area(circle: &Circle) -> f64 {"),
+		"{shapes}"
+	);
+	assert!(shapes.contains("todo!()"), "{shapes}");
+	assert!(out.path().join("src/util/mod.rs").is_file());
+	let manifest = fs::read_to_string(out.path().join("Cargo.toml")).unwrap();
+	assert!(
+		manifest.contains("name = \"dummy_crate-skeleton\""),
+		"{manifest}"
+	);
+
+	let check = Command::new(env!("CARGO"))
+		.args(["check", "--offline", "--quiet"])
+		.current_dir(out.path())
+		.output()
+		.unwrap();
+	assert!(
+		check.status.success(),
+		"{}",
+		String::from_utf8_lossy(&check.stderr)
+	);
+}
+
+/// Run ripdoc with `args`, writing `stdin` to it, and return the finished output.
+fn run_with_stdin(args: &[&str], stdin: &[u8]) -> std::process::Output {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(args)
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.stderr(Stdio::piped())
+		.spawn()
+		.unwrap();
+	child.stdin.take().unwrap().write_all(stdin).unwrap();
+	child.wait_with_output().unwrap()
+}
+
+#[test]
+fn stdin_json_is_searched_and_filtered_without_a_toolchain() {
+	let temp_dir = create_crate();
+	fs::write(
+		temp_dir.path().join("src/lib.rs"),
+		"pub fn present() {}\n\npub mod net {\n\t/// An open connection.\n\t\
+		 pub struct Connection;\n}\n",
+	)
+	.unwrap();
+	let raw = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args(["--offline", "--quiet", "raw"])
+		.arg(temp_dir.path())
+		.output()
+		.unwrap();
+	assert!(
+		raw.status.success(),
+		"{}",
+		String::from_utf8_lossy(&raw.stderr)
+	);
+
+	// A missing toolchain would fail the nightly check, which stdin JSON skips.
+	let common = [
+		"--no-config",
+		"--toolchain",
+		"nightly-ripdoc-missing",
+		"--stdin-json",
+		"--format",
+		"rust",
+	];
+	let search = run_with_stdin(
+		&[&common[..], &["search", "Connection"]].concat(),
+		&raw.stdout,
+	);
+	assert!(
+		search.status.success(),
+		"{}",
+		String::from_utf8_lossy(&search.stderr)
+	);
+	let stdout = String::from_utf8_lossy(&search.stdout);
+	assert!(stdout.contains("pub struct Connection;"), "{stdout}");
+	assert!(stdout.contains("An open connection."), "{stdout}");
+	assert!(!stdout.contains("present"), "{stdout}");
+
+	let filtered = run_with_stdin(&[&common[..], &["--filter", "net"]].concat(), &raw.stdout);
+	assert!(filtered.status.success());
+	let stdout = String::from_utf8_lossy(&filtered.stdout);
+	assert!(stdout.contains("pub mod net"), "{stdout}");
+	assert!(!stdout.contains("present"), "{stdout}");
+
+	let missing = run_with_stdin(&[&common[..], &["search", "absent"]].concat(), &raw.stdout);
+	assert_eq!(missing.status.code(), Some(4));
+	assert!(missing.stdout.is_empty());
+}
+
+#[test]
+fn generated_completions_and_man_page_are_not_empty() {
+	for args in [
+		&["completions", "bash"][..],
+		&["completions", "zsh"],
+		&["manpage"],
+	] {
+		let output = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+			.args(args)
+			.output()
+			.unwrap();
+		assert!(output.status.success(), "{args:?}: {output:?}");
+		let stdout = String::from_utf8_lossy(&output.stdout);
+		assert!(stdout.contains("ripdoc"), "{args:?}: {stdout}");
+	}
+
+	let help = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.arg("--help")
+		.output()
+		.unwrap();
+	let help = String::from_utf8_lossy(&help.stdout);
+	assert!(!help.contains("completions"), "{help}");
+	assert!(!help.contains("manpage"), "{help}");
+}
@@ -0,0 +1,227 @@
+//! Integration tests for the `serve --stdio` JSON-RPC server and the `serve --http` server.
+#![allow(clippy::tests_outside_test_module)]
+
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::process::{Child, Command, Stdio};
+
+use serde_json::{Value, json};
+use tempfile::TempDir;
+
+/// Write a minimal library crate and return its directory.
+fn create_crate() -> TempDir {
+	let temp_dir = TempDir::new().unwrap();
+	fs::create_dir(temp_dir.path().join("src")).unwrap();
+	fs::write(temp_dir.path().join("src/lib.rs"), "pub fn present() {}\n").unwrap();
+	fs::write(
+		temp_dir.path().join("Cargo.toml"),
+		"[package]\nname = \"dummy_crate\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n\
+		 [features]\nextra = []\n",
+	)
+	.unwrap();
+	temp_dir
+}
+
+/// Send `requests` to a fresh server, one per line, and collect its responses.
+fn exchange(requests: &[Value]) -> Vec<Value> {
+	let mut child = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+		.args([
+			"--offline",
+			"--quiet",
+			"--format",
+			"rust",
+			"serve",
+			"--stdio",
+		])
+		.stdin(Stdio::piped())
+		.stdout(Stdio::piped())
+		.spawn()
+		.unwrap();
+	let mut stdin = child.stdin.take().unwrap();
+	for request in requests {
+		writeln!(stdin, "{request}").unwrap();
+	}
+	// Closing stdin tells the server to exit once it has answered everything.
+	drop(stdin);
+
+	let output = child.wait_with_output().unwrap();
+	assert!(output.status.success());
+	String::from_utf8(output.stdout)
+		.unwrap()
+		.lines()
+		.map(|line| serde_json::from_str(line).unwrap())
+		.collect()
+}
+
+#[test]
+fn repeated_render_is_served_from_cache() {
+	let temp_dir = create_crate();
+	let target = temp_dir.path().to_str().unwrap();
+	let render = |id: u64| {
+		json!({
+			"jsonrpc": "2.0",
+			"id": id,
+			"method": "render",
+			"params": { "target": target },
+		})
+	};
+
+	let responses = exchange(&[render(1), render(2)]);
+	assert_eq!(responses.len(), 2, "{responses:?}");
+
+	let first = &responses[0]["result"];
+	let second = &responses[1]["result"];
+	assert_eq!(responses[0]["id"], 1);
+	assert_eq!(first["cached"], false);
+	assert_eq!(second["cached"], true);
+	assert!(
+		first["rendered"]
+			.as_str()
+			.unwrap()
+			.contains("pub fn present()"),
+		"{first}"
+	);
+	assert_eq!(first["rendered"], second["rendered"]);
+}
+
+#[test]
+fn search_and_features_share_the_server() {
+	let temp_dir = create_crate();
+	let target = temp_dir.path().to_str().unwrap();
+
+	let responses = exchange(&[
+		json!({
+			"jsonrpc": "2.0",
+			"id": "search",
+			"method": "search",
+			"params": { "target": target, "query": "present" },
+		}),
+		json!({
+			"jsonrpc": "2.0",
+			"id": "features",
+			"method": "features",
+			"params": { "target": target },
+		}),
+		json!({
+			"jsonrpc": "2.0",
+			"id": "missing",
+			"method": "search",
+			"params": { "target": target },
+		}),
+	]);
+
+	let search = &responses[0]["result"];
+	assert_eq!(search["results"][0]["path"], "dummy_crate::present");
+	assert_eq!(search["results"][0]["kind"], "function");
+	assert_eq!(responses[1]["result"]["features"], json!({ "extra": [] }));
+	assert_eq!(responses[2]["error"]["code"], -32602);
+}
+
+/// A `serve --http` process, killed when dropped.
+struct HttpServer {
+	child: Child,
+	addr: String,
+}
+
+impl HttpServer {
+	/// Start a server for `target` on a free port and wait until it is listening.
+	fn start(target: &str) -> Self {
+		let mut child = Command::new(env!("CARGO_BIN_EXE_ripdoc"))
+			.args(["--offline", "--quiet", "--format", "rust", "serve"])
+			.args(["--http", "127.0.0.1:0", target])
+			.stderr(Stdio::piped())
+			.spawn()
+			.unwrap();
+		let stderr = BufReader::new(child.stderr.take().unwrap());
+		let addr = stderr
+			.lines()
+			.map(Result::unwrap)
+			.find_map(|line| {
+				line.strip_prefix("listening on http://")
+					.map(str::to_string)
+			})
+			.expect("server exited before listening");
+		Self { child, addr }
+	}
+
+	/// Send a GET request for `path`, returning the status, content type and JSON body.
+	fn get(&self, path: &str) -> (u16, String, Value) {
+		let mut stream = TcpStream::connect(&self.addr).unwrap();
+		write!(stream, "GET {path} HTTP/1.1\r\nHost: {}\r\n\r\n", self.addr).unwrap();
+		let mut response = String::new();
+		stream.read_to_string(&mut response).unwrap();
+
+		let (head, body) = response.split_once("\r\n\r\n").unwrap();
+		let mut lines = head.lines();
+		let status = lines.next().unwrap().split(' ').nth(1).unwrap();
+		let content_type = lines
+			.find_map(|line| line.strip_prefix("Content-Type: "))
+			.unwrap_or_default();
+		(
+			status.parse().unwrap(),
+			content_type.to_string(),
+			serde_json::from_str(body).unwrap(),
+		)
+	}
+}
+
+impl Drop for HttpServer {
+	fn drop(&mut self) {
+		let _ = self.child.kill();
+		let _ = self.child.wait();
+	}
+}
+
+#[test]
+fn http_server_answers_search_item_and_list() {
+	let temp_dir = create_crate();
+	fs::write(
+		temp_dir.path().join("src/lib.rs"),
+		"pub fn present() {}\n\n/// A widget.\npub struct Widget;\n\n\
+		 impl Widget {\n\t/// Draw it.\n\tpub fn render(&self) {}\n}\n",
+	)
+	.unwrap();
+	let server = HttpServer::start(temp_dir.path().to_str().unwrap());
+
+	let (status, content_type, search) = server.get("/search?q=present&domains=name");
+	assert_eq!(status, 200, "{search}");
+	assert_eq!(content_type, "application/json");
+	assert_eq!(search["results"][0]["path"], "dummy_crate::present");
+	assert!(
+		search["rendered"]
+			.as_str()
+			.unwrap()
+			.contains("pub fn present()"),
+		"{search}"
+	);
+
+	let (status, _, item) = server.get("/item/Widget::render");
+	assert_eq!(status, 200, "{item}");
+	assert_eq!(item["kind"], "method");
+	assert!(
+		item["rendered"].as_str().unwrap().contains("fn render"),
+		"{item}"
+	);
+
+	let (status, _, list) = server.get("/list");
+	assert_eq!(status, 200, "{list}");
+	let paths: Vec<&str> = list["items"]
+		.as_array()
+		.unwrap()
+		.iter()
+		.map(|item| item["path"].as_str().unwrap())
+		.collect();
+	assert!(paths.contains(&"dummy_crate::Widget"), "{paths:?}");
+
+	let (status, content_type, error) = server.get("/item/Missing");
+	assert_eq!(status, 404);
+	assert_eq!(content_type, "application/json");
+	assert!(
+		error["error"].as_str().unwrap().contains("Missing"),
+		"{error}"
+	);
+	assert_eq!(server.get("/search").0, 400);
+	assert_eq!(server.get("/search?q=x&domains=colour").0, 400);
+	assert_eq!(server.get("/nowhere").0, 404);
+}
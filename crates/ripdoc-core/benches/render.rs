@@ -0,0 +1,55 @@
+//! Benchmarks for indexing, searching and rendering the generated fixture crates.
+//!
+//! Run with `cargo bench -p ripdoc-core`; see `tests/fixtures` for how the fixtures are made.
+
+#![allow(
+	missing_docs,
+	reason = "`criterion_group!` defines a public function without a doc comment."
+)]
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use ripdoc_core::search::{SearchIndex, SearchOptions};
+use ripdoc_core::{RenderFormat, Renderer};
+
+#[path = "../tests/fixtures/mod.rs"]
+mod fixtures;
+#[path = "../tests/utils.rs"]
+mod utils;
+
+use fixtures::Fixture;
+
+fn bench_fixtures(c: &mut Criterion) {
+	let mut group = c.benchmark_group("render");
+	group.sample_size(10);
+	for fixture in Fixture::ALL {
+		let crate_data = fixture.load();
+		let name = fixture.name();
+
+		group.bench_with_input(
+			BenchmarkId::new("index_build", name),
+			&crate_data,
+			|b, data| {
+				b.iter(|| SearchIndex::build(data, false, None));
+			},
+		);
+
+		let index = SearchIndex::build(&crate_data, false, None);
+		let options = SearchOptions::new("field3");
+		group.bench_with_input(
+			BenchmarkId::new("index_search", name),
+			&index,
+			|b, index| {
+				b.iter(|| index.search(&options));
+			},
+		);
+
+		let renderer = Renderer::default().with_format(RenderFormat::Rust);
+		group.bench_with_input(BenchmarkId::new("render", name), &crate_data, |b, data| {
+			b.iter(|| renderer.render(data).unwrap());
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, bench_fixtures);
+criterion_main!(benches);
@@ -0,0 +1,63 @@
+//! Items whose source changed since a git revision.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum};
+
+/// Local items of `crate_data` defined in one of the `changed` files, sorted by id.
+///
+/// An item is matched by the file its span starts in. Span file names are relative to the
+/// directory cargo ran rustdoc from, which is the package root or one of its ancestors, so
+/// they are resolved against each of those in turn. Items without a span, such as some
+/// macro-generated ones, are matched when the module containing them is.
+pub fn changed_items(crate_data: &Crate, package_root: &Path, changed: &[PathBuf]) -> Vec<Id> {
+	let changed: HashSet<PathBuf> = changed.iter().map(|path| normalize(path)).collect();
+	let mut files: HashMap<PathBuf, bool> = HashMap::new();
+	let mut in_changed_file = |item: &Item| {
+		let Some(span) = &item.span else {
+			return false;
+		};
+		*files.entry(span.filename.clone()).or_insert_with(|| {
+			source_file(package_root, &span.filename).is_some_and(|file| changed.contains(&file))
+		})
+	};
+
+	let mut ids = Vec::new();
+	for item in crate_data.index.values().filter(|item| item.crate_id == 0) {
+		if !in_changed_file(item) {
+			continue;
+		}
+		ids.push(item.id);
+		if let ItemEnum::Module(module) = &item.inner {
+			ids.extend(module.items.iter().filter(|child| {
+				crate_data
+					.index
+					.get(child)
+					.is_some_and(|child| child.span.is_none())
+			}));
+		}
+	}
+	ids.sort_by_key(|id| id.0);
+	ids.dedup();
+	ids
+}
+
+/// The file a span's `filename` refers to, found relative to the package root or the nearest
+/// of its ancestors that contains it.
+fn source_file(package_root: &Path, filename: &Path) -> Option<PathBuf> {
+	if filename.is_absolute() {
+		return Some(normalize(filename));
+	}
+	package_root
+		.ancestors()
+		.map(|dir| dir.join(filename))
+		.find(|path| path.is_file())
+		.map(|path| normalize(&path))
+}
+
+/// `path` with symlinks and `..` resolved when it exists, so equal files compare equal.
+fn normalize(path: &Path) -> PathBuf {
+	fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
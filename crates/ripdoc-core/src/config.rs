@@ -0,0 +1,349 @@
+//! Layered configuration loaded from TOML files.
+//!
+//! Settings are resolved with the following precedence, highest first:
+//!
+//! 1. command-line flags,
+//! 2. the project file `.ripdoc.toml`, found by walking up from the target,
+//! 3. the user file `$XDG_CONFIG_HOME/ripdoc/config.toml` (`~/.config/ripdoc/config.toml` when
+//!    `XDG_CONFIG_HOME` is unset),
+//! 4. built-in defaults.
+//!
+//! Every key is optional; a layer only overrides the keys it sets. Layers are combined with
+//! [`Config::or`].
+
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+use ripdoc_render::RenderFormat;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer};
+
+use crate::error::{Result, RipdocError};
+use crate::search::SearchDomain;
+
+/// Name of the project-local config file.
+pub const PROJECT_CONFIG_FILE: &str = ".ripdoc.toml";
+
+/// Top-level keys accepted in a config file.
+const KNOWN_KEYS: &[&str] = &[
+	"format",
+	"auto_impls",
+	"private",
+	"include_hidden",
+	"include_tests",
+	"no_default_features",
+	"all_features",
+	"features",
+	"offline",
+	"registry",
+	"toolchain",
+	"target",
+	"cache",
+	"cache_dir",
+	"search",
+];
+
+/// Keys accepted in the `[search]` table.
+const KNOWN_SEARCH_KEYS: &[&str] = &["case_sensitive", "fuzzy", "domains", "direct_match_only"];
+
+/// One configuration layer. Unset fields defer to lower-precedence layers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+	/// Output format (`rust`, `markdown`, or `json`).
+	pub format: Option<RenderFormat>,
+	/// Render auto-implemented traits.
+	pub auto_impls: Option<bool>,
+	/// Render private items.
+	pub private: Option<bool>,
+	/// Render items marked `#[doc(hidden)]`.
+	pub include_hidden: Option<bool>,
+	/// Render `#[cfg(test)] mod tests` modules and items from build scripts.
+	pub include_tests: Option<bool>,
+	/// Disable default features.
+	pub no_default_features: Option<bool>,
+	/// Enable all features.
+	pub all_features: Option<bool>,
+	/// Features to enable.
+	pub features: Option<Vec<String>>,
+	/// Prevent cargo from using the network.
+	pub offline: Option<bool>,
+	/// Registry named targets are downloaded from: a name from cargo's config or an index URL.
+	pub registry: Option<String>,
+	/// Rustup toolchain used to generate rustdoc JSON.
+	pub toolchain: Option<String>,
+	/// Target triple to document instead of the host.
+	pub target: Option<String>,
+	/// Cache rustdoc JSON on disk.
+	pub cache: Option<bool>,
+	/// Directory for cached rustdoc JSON.
+	pub cache_dir: Option<PathBuf>,
+	/// Search defaults from the `[search]` table.
+	pub search: SearchConfig,
+}
+
+/// Search defaults from the `[search]` table of a config file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default)]
+pub struct SearchConfig {
+	/// Match letter case exactly.
+	pub case_sensitive: Option<bool>,
+	/// Match names fuzzily and rank results by score.
+	pub fuzzy: Option<bool>,
+	/// Domains searched by default, written as a list of `name`, `doc`, `path`, `signature`, `attrs`.
+	#[serde(deserialize_with = "deserialize_domains")]
+	pub domains: Option<SearchDomain>,
+	/// Keep matched containers collapsed.
+	pub direct_match_only: Option<bool>,
+}
+
+impl Config {
+	/// Fill every field left unset in `self` from `fallback`, so `self` takes precedence.
+	pub fn or(self, fallback: Self) -> Self {
+		Self {
+			format: self.format.or(fallback.format),
+			auto_impls: self.auto_impls.or(fallback.auto_impls),
+			private: self.private.or(fallback.private),
+			include_hidden: self.include_hidden.or(fallback.include_hidden),
+			include_tests: self.include_tests.or(fallback.include_tests),
+			no_default_features: self.no_default_features.or(fallback.no_default_features),
+			all_features: self.all_features.or(fallback.all_features),
+			features: self.features.or(fallback.features),
+			offline: self.offline.or(fallback.offline),
+			registry: self.registry.or(fallback.registry),
+			toolchain: self.toolchain.or(fallback.toolchain),
+			target: self.target.or(fallback.target),
+			cache: self.cache.or(fallback.cache),
+			cache_dir: self.cache_dir.or(fallback.cache_dir),
+			search: self.search.or(fallback.search),
+		}
+	}
+
+	/// Parse a config file, returning it along with the dotted names of any unknown keys.
+	pub fn parse(content: &str) -> Result<(Self, Vec<String>)> {
+		Self::parse_toml(content).map_err(|err| RipdocError::Config(err.to_string()))
+	}
+
+	fn parse_toml(content: &str) -> std::result::Result<(Self, Vec<String>), toml::de::Error> {
+		let table: toml::Table = content.parse()?;
+
+		let mut unknown = Vec::new();
+		for (key, value) in &table {
+			if !KNOWN_KEYS.contains(&key.as_str()) {
+				unknown.push(key.clone());
+			} else if key == "search"
+				&& let Some(search) = value.as_table()
+			{
+				unknown.extend(
+					search
+						.keys()
+						.filter(|key| !KNOWN_SEARCH_KEYS.contains(&key.as_str()))
+						.map(|key| format!("search.{key}")),
+				);
+			}
+		}
+
+		Ok((table.try_into()?, unknown))
+	}
+
+	/// Read a config file, logging a warning for each unknown key.
+	pub fn read(path: &Path) -> Result<Self> {
+		let content = fs::read_to_string(path).map_err(|err| {
+			RipdocError::Config(format!("failed to read {}: {err}", path.display()))
+		})?;
+		let (config, unknown) = Self::parse_toml(&content)
+			.map_err(|err| RipdocError::Config(format!("{}: {err}", path.display())))?;
+		for key in unknown {
+			log::warn!("{}: unknown config key `{key}`", path.display());
+		}
+		Ok(config)
+	}
+
+	/// Load the project file nearest to `target` layered over the user file.
+	///
+	/// The project file is searched for from `target` when it names an existing path, and from
+	/// the current directory otherwise. Missing files are skipped.
+	pub fn load(target: &str) -> Result<Self> {
+		let target = Path::new(target);
+		let start = if target.exists() {
+			target.to_path_buf()
+		} else {
+			env::current_dir().map_err(|err| RipdocError::Config(err.to_string()))?
+		};
+
+		let project = match find_project_file(&start) {
+			Some(path) => Self::read(&path)?,
+			None => Self::default(),
+		};
+		let user = match user_config_path().filter(|path| path.is_file()) {
+			Some(path) => Self::read(&path)?,
+			None => Self::default(),
+		};
+		Ok(project.or(user))
+	}
+}
+
+impl SearchConfig {
+	/// Fill every field left unset in `self` from `fallback`, so `self` takes precedence.
+	pub fn or(self, fallback: Self) -> Self {
+		Self {
+			case_sensitive: self.case_sensitive.or(fallback.case_sensitive),
+			fuzzy: self.fuzzy.or(fallback.fuzzy),
+			domains: self.domains.or(fallback.domains),
+			direct_match_only: self.direct_match_only.or(fallback.direct_match_only),
+		}
+	}
+}
+
+/// Location of the user config file, if a config directory can be determined.
+pub fn user_config_path() -> Option<PathBuf> {
+	let base = env::var_os("XDG_CONFIG_HOME")
+		.filter(|dir| !dir.is_empty())
+		.map(PathBuf::from)
+		.or_else(|| dirs::home_dir().map(|home| home.join(".config")))?;
+	Some(base.join("ripdoc").join("config.toml"))
+}
+
+/// Walk upwards from `start` to find the closest [`PROJECT_CONFIG_FILE`].
+pub fn find_project_file(start: &Path) -> Option<PathBuf> {
+	let start = fs::canonicalize(start).ok()?;
+	start
+		.ancestors()
+		.map(|dir| dir.join(PROJECT_CONFIG_FILE))
+		.find(|path| path.is_file())
+}
+
+/// Deserialize a list of domain names into a [`SearchDomain`] set.
+fn deserialize_domains<'de, D: Deserializer<'de>>(
+	deserializer: D,
+) -> std::result::Result<Option<SearchDomain>, D::Error> {
+	let names = Vec::<String>::deserialize(deserializer)?;
+	let names: Vec<&str> = names.iter().map(String::as_str).collect();
+	SearchDomain::from_str_list(&names)
+		.map(Some)
+		.map_err(D::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+	use tempfile::TempDir;
+
+	use super::*;
+
+	#[test]
+	fn parse_reads_nested_search_table() {
+		let (config, unknown) = Config::parse(
+			r#"
+                format = "rust"
+                private = true
+                features = ["a", "b"]
+                cache_dir = "/tmp/ripdoc"
+                registry = "corp"
+
+                [search]
+                case_sensitive = true
+                domains = ["name", "path"]
+            "#,
+		)
+		.unwrap();
+
+		assert!(unknown.is_empty(), "{unknown:?}");
+		assert_eq!(config.format, Some(RenderFormat::Rust));
+		assert_eq!(config.private, Some(true));
+		assert_eq!(
+			config.features,
+			Some(vec!["a".to_string(), "b".to_string()])
+		);
+		assert_eq!(config.cache_dir, Some(PathBuf::from("/tmp/ripdoc")));
+		assert_eq!(config.registry.as_deref(), Some("corp"));
+		assert_eq!(config.search.case_sensitive, Some(true));
+		assert_eq!(
+			config.search.domains,
+			Some(SearchDomain::NAMES | SearchDomain::PATHS)
+		);
+		assert_eq!(config.auto_impls, None);
+	}
+
+	#[test]
+	fn parse_reports_unknown_keys() {
+		let (config, unknown) = Config::parse(
+			r#"
+                offline = true
+                docs = "summary"
+
+                [search]
+                limit = 10
+            "#,
+		)
+		.unwrap();
+
+		assert_eq!(config.offline, Some(true));
+		assert_eq!(unknown, ["docs", "search.limit"]);
+	}
+
+	#[test]
+	fn parse_rejects_invalid_values() {
+		assert!(Config::parse("format = \"html\"").is_err());
+		assert!(Config::parse("[search]\ndomains = [\"body\"]").is_err());
+		assert!(Config::parse("private = \"yes\"").is_err());
+	}
+
+	#[test]
+	fn or_prefers_higher_precedence_layers() {
+		let cli = Config {
+			private: Some(false),
+			..Config::default()
+		};
+		let project = Config {
+			private: Some(true),
+			format: Some(RenderFormat::Json),
+			search: SearchConfig {
+				case_sensitive: Some(true),
+				..SearchConfig::default()
+			},
+			..Config::default()
+		};
+		let user = Config {
+			format: Some(RenderFormat::Rust),
+			offline: Some(true),
+			search: SearchConfig {
+				case_sensitive: Some(false),
+				direct_match_only: Some(true),
+				..SearchConfig::default()
+			},
+			..Config::default()
+		};
+
+		let merged = cli.or(project).or(user);
+		assert_eq!(merged.private, Some(false));
+		assert_eq!(merged.format, Some(RenderFormat::Json));
+		assert_eq!(merged.offline, Some(true));
+		assert_eq!(merged.search.case_sensitive, Some(true));
+		assert_eq!(merged.search.direct_match_only, Some(true));
+		assert_eq!(merged.auto_impls, None);
+	}
+
+	#[test]
+	fn project_file_is_found_from_nested_target() {
+		let temp_dir = TempDir::new().unwrap();
+		let nested = temp_dir.path().join("crate/src");
+		fs::create_dir_all(&nested).unwrap();
+		fs::write(
+			temp_dir.path().join(PROJECT_CONFIG_FILE),
+			"format = \"rust\"\n",
+		)
+		.unwrap();
+
+		let found = find_project_file(&nested).unwrap();
+		assert_eq!(
+			found,
+			fs::canonicalize(temp_dir.path())
+				.unwrap()
+				.join(PROJECT_CONFIG_FILE)
+		);
+		assert_eq!(
+			Config::read(&found).unwrap().format,
+			Some(RenderFormat::Rust)
+		);
+	}
+}
@@ -1,27 +1,110 @@
 use std::fmt;
 
+/// Errors from target resolution and rustdoc builds, wrapped in [`RipdocError::Cargo`].
+#[cfg(feature = "cargo-resolution")]
+pub use ripdoc_cargo::RipdocError as CargoError;
+/// Errors emitted by the renderer, wrapped in [`RipdocError::Render`].
+pub use ripdoc_render::error::RipdocError as RenderError;
 use serde_json::Error as SerdeError;
 
+/// Exit status for a failure without a more specific code.
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit status for invalid arguments or a malformed target specification.
+pub const EXIT_USAGE: i32 = 2;
+/// Exit status when the target package, module, or crate release does not exist.
+pub const EXIT_NOT_FOUND: i32 = 3;
+/// Exit status when a filter path or search query matched nothing.
+pub const EXIT_NO_MATCHES: i32 = 4;
+/// Exit status when the toolchain is missing, too old, or lacks the requested target.
+pub const EXIT_TOOLCHAIN: i32 = 5;
+/// Exit status when rustdoc failed to build the crate.
+pub const EXIT_BUILD_FAILED: i32 = 6;
+
 /// Aggregate errors produced by the ripdoc-core API.
 #[derive(Debug)]
 pub enum RipdocError {
 	/// Errors returned by cargo/target resolution helpers.
+	#[cfg(feature = "cargo-resolution")]
 	Cargo(ripdoc_cargo::RipdocError),
 	/// Errors emitted while rendering skeleton output.
-	Render(ripdoc_render::error::RipdocError),
+	Render(RenderError),
 	/// Failed to encode or decode JSON.
 	Serialization(SerdeError),
 	/// Invalid target specifications provided by the user.
 	InvalidTarget(String),
+	/// A config file could not be read or parsed.
+	Config(String),
+	/// The filter path names a private item, but private items were not rendered.
+	PrivateFilter(String),
+	/// The filter path matched nothing, with near-miss paths that do exist.
+	FilterSuggestions {
+		/// Filter path as given.
+		filter: String,
+		/// Existing paths close to the filter, closest first.
+		suggestions: Vec<String>,
+	},
 }
 
 impl fmt::Display for RipdocError {
 	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
 		match self {
+			#[cfg(feature = "cargo-resolution")]
 			Self::Cargo(err) => write!(f, "{err}"),
 			Self::Render(err) => write!(f, "{err}"),
 			Self::Serialization(err) => write!(f, "{err}"),
 			Self::InvalidTarget(message) => write!(f, "{message}"),
+			Self::Config(message) => write!(f, "invalid config: {message}"),
+			Self::PrivateFilter(path) => {
+				write!(
+					f,
+					"path '{path}' exists but is private; re-run with --private"
+				)
+			}
+			Self::FilterSuggestions {
+				filter,
+				suggestions,
+			} => {
+				let suggestions: Vec<String> =
+					suggestions.iter().map(|path| format!("'{path}'")).collect();
+				write!(
+					f,
+					"filter path '{filter}' did not match any items; did you mean {}?",
+					suggestions.join(" or ")
+				)
+			}
+		}
+	}
+}
+
+impl RipdocError {
+	/// Process exit status a command-line frontend reports for this error, one of the `EXIT_*`
+	/// constants.
+	pub fn exit_code(&self) -> i32 {
+		match self {
+			#[cfg(feature = "cargo-resolution")]
+			Self::Cargo(err) => match err {
+				ripdoc_cargo::RipdocError::InvalidTarget(_) => EXIT_USAGE,
+				ripdoc_cargo::RipdocError::ManifestNotFound
+				| ripdoc_cargo::RipdocError::ModuleNotFound(_)
+				| ripdoc_cargo::RipdocError::Yanked { .. } => EXIT_NOT_FOUND,
+				ripdoc_cargo::RipdocError::Toolchain(_)
+				| ripdoc_cargo::RipdocError::ToolchainMissing { .. }
+				| ripdoc_cargo::RipdocError::TargetMissing { .. }
+				| ripdoc_cargo::RipdocError::FormatVersionMismatch { .. } => EXIT_TOOLCHAIN,
+				ripdoc_cargo::RipdocError::BuildFailed(_) => EXIT_BUILD_FAILED,
+				ripdoc_cargo::RipdocError::Generate(_)
+				| ripdoc_cargo::RipdocError::ManifestParse(_)
+				| ripdoc_cargo::RipdocError::NotCached { .. }
+				| ripdoc_cargo::RipdocError::TimedOut { .. }
+				| ripdoc_cargo::RipdocError::Cancelled { .. } => EXIT_FAILURE,
+			},
+			Self::Render(RenderError::FilterNotMatched(_))
+			| Self::PrivateFilter(_)
+			| Self::FilterSuggestions { .. } => EXIT_NO_MATCHES,
+			Self::Render(RenderError::FilterAmbiguous { .. }) | Self::InvalidTarget(_) => {
+				EXIT_USAGE
+			}
+			Self::Render(_) | Self::Serialization(_) | Self::Config(_) => EXIT_FAILURE,
 		}
 	}
 }
@@ -29,22 +112,27 @@ impl fmt::Display for RipdocError {
 impl std::error::Error for RipdocError {
 	fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
 		match self {
+			#[cfg(feature = "cargo-resolution")]
 			Self::Cargo(err) => Some(err),
 			Self::Render(err) => Some(err),
 			Self::Serialization(err) => Some(err),
-			Self::InvalidTarget(_) => None,
+			Self::InvalidTarget(_)
+			| Self::Config(_)
+			| Self::PrivateFilter(_)
+			| Self::FilterSuggestions { .. } => None,
 		}
 	}
 }
 
+#[cfg(feature = "cargo-resolution")]
 impl From<ripdoc_cargo::RipdocError> for RipdocError {
 	fn from(err: ripdoc_cargo::RipdocError) -> Self {
 		Self::Cargo(err)
 	}
 }
 
-impl From<ripdoc_render::error::RipdocError> for RipdocError {
-	fn from(err: ripdoc_render::error::RipdocError) -> Self {
+impl From<RenderError> for RipdocError {
+	fn from(err: RenderError) -> Self {
 		Self::Render(err)
 	}
 }
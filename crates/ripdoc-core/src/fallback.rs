@@ -0,0 +1,366 @@
+//! Best-effort skeletons parsed straight from source when rustdoc cannot build a crate.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use quote::ToTokens;
+use ripdoc_render::markdown::render_markdown;
+use ripdoc_render::{RenderFormat, Renderer};
+use syn::{
+	Attribute, Block, Expr, ExprLit, Fields, FieldsNamed, ImplItem, Item, ItemMod, Lit, LitStr,
+	Meta, TraitItem, Visibility,
+};
+
+use crate::error::{Result, RipdocError};
+
+/// Note placed above skeletons parsed from source.
+pub const SOURCE_FALLBACK_NOTE: &str = "generated from source parse (rustdoc build failed)";
+
+/// Render a skeleton of the package at `package_root` by parsing its sources with `syn`.
+///
+/// Module files are followed from the library (or binary) root. Only signatures are kept:
+/// function bodies are emptied and macro invocations dropped, so macro-generated items do not
+/// appear. Doc comments and visibility are preserved. JSON output is not supported.
+pub fn render_source_skeleton(
+	package_root: &Path,
+	format: RenderFormat,
+	private_items: bool,
+) -> Result<String> {
+	let (crate_name, root_file) = crate_root(package_root)?;
+	let file = parse_file(&root_file)?;
+	let dir = root_file.parent().unwrap_or(package_root);
+	let module = ItemMod {
+		attrs: doc_attrs(file.attrs),
+		vis: Visibility::Public(Default::default()),
+		unsafety: None,
+		mod_token: Default::default(),
+		ident: syn::parse_str(&crate_name).map_err(|err| parse_error(&root_file, &err))?,
+		content: Some((
+			Default::default(),
+			skeleton_items(file.items, dir, private_items),
+		)),
+		semi: None,
+	};
+
+	let formatted = Renderer::default().format_source(module.to_token_stream().to_string())?;
+	let source = doc_comments(&formatted);
+	Ok(match format {
+		RenderFormat::Markdown => {
+			format!(
+				"<!-- {SOURCE_FALLBACK_NOTE} -->\n\n{}",
+				render_markdown(&source)
+			)
+		}
+		RenderFormat::Rust | RenderFormat::Json => format!("// {SOURCE_FALLBACK_NOTE}\n\n{source}"),
+	})
+}
+
+/// Crate name and root source file of the package, preferring the library target.
+fn crate_root(package_root: &Path) -> Result<(String, PathBuf)> {
+	let manifest_path = package_root.join("Cargo.toml");
+	let manifest: toml::Table = fs::read_to_string(&manifest_path)
+		.map_err(ripdoc_cargo::RipdocError::from)?
+		.parse()
+		.map_err(|err| ripdoc_cargo::RipdocError::ManifestParse(format!("{err}")))?;
+	let lib = manifest.get("lib").and_then(|lib| lib.as_table());
+	let package_name = manifest
+		.get("package")
+		.and_then(|package| package.get("name"))
+		.and_then(|name| name.as_str());
+	let name = lib
+		.and_then(|lib| lib.get("name"))
+		.and_then(|name| name.as_str())
+		.or(package_name)
+		.ok_or(ripdoc_cargo::RipdocError::ManifestNotFound)?
+		.replace('-', "_");
+
+	let lib_path = lib
+		.and_then(|lib| lib.get("path"))
+		.and_then(|path| path.as_str())
+		.map(|path| package_root.join(path));
+	let root = lib_path
+		.into_iter()
+		.chain(["src/lib.rs", "src/main.rs"].map(|path| package_root.join(path)))
+		.find(|path| path.is_file())
+		.ok_or_else(|| {
+			ripdoc_cargo::RipdocError::Generate(format!(
+				"no crate root found under {}",
+				package_root.display()
+			))
+		})?;
+	Ok((name, root))
+}
+
+/// Read and parse one source file.
+fn parse_file(path: &Path) -> Result<syn::File> {
+	let source = fs::read_to_string(path).map_err(ripdoc_cargo::RipdocError::from)?;
+	syn::parse_file(&source).map_err(|err| parse_error(path, &err))
+}
+
+fn parse_error(path: &Path, err: &syn::Error) -> RipdocError {
+	ripdoc_cargo::RipdocError::Generate(format!("failed to parse {}: {err}", path.display())).into()
+}
+
+/// Reduce `items` to the signatures a skeleton shows; `dir` holds the files of child modules.
+fn skeleton_items(items: Vec<Item>, dir: &Path, private_items: bool) -> Vec<Item> {
+	items
+		.into_iter()
+		.filter_map(|item| skeleton_item(item, dir, private_items))
+		.collect()
+}
+
+fn skeleton_item(item: Item, dir: &Path, private_items: bool) -> Option<Item> {
+	let keep = |attrs: &[Attribute], vis: &Visibility| {
+		!is_cfg_test(attrs) && (private_items || is_pub(vis))
+	};
+	let item = match item {
+		Item::Fn(mut item) if keep(&item.attrs, &item.vis) => {
+			item.attrs = doc_attrs(item.attrs);
+			*item.block = empty_block();
+			Item::Fn(item)
+		}
+		Item::Struct(mut item) if keep(&item.attrs, &item.vis) => {
+			item.attrs = doc_attrs(item.attrs);
+			skeleton_fields(&mut item.fields, private_items);
+			Item::Struct(item)
+		}
+		Item::Union(mut item) if keep(&item.attrs, &item.vis) => {
+			item.attrs = doc_attrs(item.attrs);
+			skeleton_named_fields(&mut item.fields, private_items);
+			Item::Union(item)
+		}
+		Item::Enum(mut item) if keep(&item.attrs, &item.vis) => {
+			item.attrs = doc_attrs(item.attrs);
+			for variant in &mut item.variants {
+				variant.attrs = doc_attrs(std::mem::take(&mut variant.attrs));
+				// Variant fields share the enum's visibility.
+				skeleton_fields(&mut variant.fields, true);
+			}
+			Item::Enum(item)
+		}
+		Item::Trait(mut item) if keep(&item.attrs, &item.vis) => {
+			item.attrs = doc_attrs(item.attrs);
+			item.items = item
+				.items
+				.into_iter()
+				.filter_map(|trait_item| match trait_item {
+					TraitItem::Fn(mut method) => {
+						method.attrs = doc_attrs(method.attrs);
+						if method.default.is_some() {
+							method.default = Some(empty_block());
+						}
+						Some(TraitItem::Fn(method))
+					}
+					TraitItem::Const(mut constant) => {
+						constant.attrs = doc_attrs(constant.attrs);
+						Some(TraitItem::Const(constant))
+					}
+					TraitItem::Type(mut ty) => {
+						ty.attrs = doc_attrs(ty.attrs);
+						Some(TraitItem::Type(ty))
+					}
+					_ => None,
+				})
+				.collect();
+			Item::Trait(item)
+		}
+		Item::Impl(mut item) if !is_cfg_test(&item.attrs) => {
+			item.attrs = doc_attrs(item.attrs);
+			// Trait impl items carry no visibility of their own.
+			let keep_all = private_items || item.trait_.is_some();
+			item.items = item
+				.items
+				.into_iter()
+				.filter_map(|impl_item| match impl_item {
+					ImplItem::Fn(mut method) if keep_all || is_pub(&method.vis) => {
+						method.attrs = doc_attrs(method.attrs);
+						method.block = empty_block();
+						Some(ImplItem::Fn(method))
+					}
+					ImplItem::Const(mut constant) if keep_all || is_pub(&constant.vis) => {
+						constant.attrs = doc_attrs(constant.attrs);
+						Some(ImplItem::Const(constant))
+					}
+					ImplItem::Type(mut ty) if keep_all || is_pub(&ty.vis) => {
+						ty.attrs = doc_attrs(ty.attrs);
+						Some(ImplItem::Type(ty))
+					}
+					_ => None,
+				})
+				.collect();
+			if item.trait_.is_none() && item.items.is_empty() {
+				return None;
+			}
+			Item::Impl(item)
+		}
+		Item::Mod(item) if keep(&item.attrs, &item.vis) => {
+			Item::Mod(skeleton_module(item, dir, private_items))
+		}
+		Item::Const(mut item) if keep(&item.attrs, &item.vis) => {
+			item.attrs = doc_attrs(item.attrs);
+			Item::Const(item)
+		}
+		Item::Static(mut item) if keep(&item.attrs, &item.vis) => {
+			item.attrs = doc_attrs(item.attrs);
+			Item::Static(item)
+		}
+		Item::Type(mut item) if keep(&item.attrs, &item.vis) => {
+			item.attrs = doc_attrs(item.attrs);
+			Item::Type(item)
+		}
+		Item::TraitAlias(mut item) if keep(&item.attrs, &item.vis) => {
+			item.attrs = doc_attrs(item.attrs);
+			Item::TraitAlias(item)
+		}
+		Item::Use(mut item) if keep(&item.attrs, &item.vis) => {
+			item.attrs = doc_attrs(item.attrs);
+			Item::Use(item)
+		}
+		_ => return None,
+	};
+	Some(item)
+}
+
+/// Inline a module's contents, reading them from its file for `mod name;` declarations.
+fn skeleton_module(mut item: ItemMod, dir: &Path, private_items: bool) -> ItemMod {
+	let child_dir = dir.join(item.ident.to_string());
+	let path_attr = item.attrs.iter().find_map(|attr| match &attr.meta {
+		Meta::NameValue(meta) if meta.path.is_ident("path") => match &meta.value {
+			Expr::Lit(ExprLit {
+				lit: Lit::Str(path),
+				..
+			}) => Some(path.value()),
+			_ => None,
+		},
+		_ => None,
+	});
+	let mut attrs = doc_attrs(std::mem::take(&mut item.attrs));
+
+	let (items, child_dir) = match item.content.take() {
+		Some((_, items)) => (items, child_dir),
+		None => {
+			let candidates = match &path_attr {
+				Some(path) => vec![dir.join(path)],
+				None => vec![
+					dir.join(format!("{}.rs", item.ident)),
+					child_dir.join("mod.rs"),
+				],
+			};
+			let Some(file) = candidates.into_iter().find(|path| path.is_file()) else {
+				log::warn!("source file for module `{}` not found", item.ident);
+				return module_with(item, attrs, Vec::new());
+			};
+			let parsed = match parse_file(&file) {
+				Ok(parsed) => parsed,
+				Err(err) => {
+					log::warn!("{err}");
+					return module_with(item, attrs, Vec::new());
+				}
+			};
+			attrs.extend(doc_attrs(parsed.attrs));
+			// Children of `mod.rs` sit beside it; children of `name.rs` sit in `name/`.
+			let file_dir = match file.file_name().and_then(|name| name.to_str()) {
+				Some("mod.rs") => file.parent().map(Path::to_path_buf),
+				_ => file
+					.parent()
+					.zip(file.file_stem())
+					.map(|(parent, stem)| parent.join(stem)),
+			};
+			(parsed.items, file_dir.unwrap_or(child_dir))
+		}
+	};
+	let items = skeleton_items(items, &child_dir, private_items);
+	module_with(item, attrs, items)
+}
+
+/// Give `item` the given attributes and an inline body holding `items`.
+fn module_with(mut item: ItemMod, attrs: Vec<Attribute>, items: Vec<Item>) -> ItemMod {
+	item.attrs = attrs;
+	item.content = Some((Default::default(), items));
+	item.semi = None;
+	item
+}
+
+/// Drop private named fields unless `private_items` is set, keeping only doc attributes.
+fn skeleton_fields(fields: &mut Fields, private_items: bool) {
+	match fields {
+		Fields::Named(named) => skeleton_named_fields(named, private_items),
+		Fields::Unnamed(_) | Fields::Unit => {
+			for field in fields.iter_mut() {
+				field.attrs = doc_attrs(std::mem::take(&mut field.attrs));
+			}
+		}
+	}
+}
+
+fn skeleton_named_fields(fields: &mut FieldsNamed, private_items: bool) {
+	if !private_items {
+		fields.named = std::mem::take(&mut fields.named)
+			.into_iter()
+			.filter(|field| is_pub(&field.vis))
+			.collect();
+	}
+	for field in &mut fields.named {
+		field.attrs = doc_attrs(std::mem::take(&mut field.attrs));
+	}
+}
+
+fn is_pub(vis: &Visibility) -> bool {
+	matches!(vis, Visibility::Public(_))
+}
+
+/// Whether `attrs` include `#[cfg(test)]`.
+fn is_cfg_test(attrs: &[Attribute]) -> bool {
+	attrs.iter().any(|attr| {
+		attr.path().is_ident("cfg")
+			&& attr
+				.parse_args::<syn::Ident>()
+				.is_ok_and(|ident| ident == "test")
+	})
+}
+
+/// Keep only `#[doc = "..."]` attributes, which is what doc comments parse into.
+fn doc_attrs(attrs: Vec<Attribute>) -> Vec<Attribute> {
+	attrs
+		.into_iter()
+		.filter(|attr| matches!(&attr.meta, Meta::NameValue(meta) if meta.path.is_ident("doc")))
+		.collect()
+}
+
+fn empty_block() -> Block {
+	Block {
+		brace_token: Default::default(),
+		stmts: Vec::new(),
+	}
+}
+
+/// Turn the `#[doc = "..."]` lines rustfmt leaves behind back into `///` and `//!` comments.
+fn doc_comments(source: &str) -> String {
+	let mut output = String::with_capacity(source.len());
+	for line in source.lines() {
+		let trimmed = line.trim_start();
+		let indent = &line[..line.len() - trimmed.len()];
+		match doc_attr_text(trimmed) {
+			Some((marker, text)) => {
+				for doc_line in text.split('\n') {
+					output.push_str(&format!("{indent}{marker}{}\n", doc_line.trim_end()));
+				}
+			}
+			None => {
+				output.push_str(line);
+				output.push('\n');
+			}
+		}
+	}
+	output
+}
+
+/// Comment marker and text of a formatted doc attribute line.
+fn doc_attr_text(line: &str) -> Option<(&'static str, String)> {
+	let (marker, rest) = match line.strip_prefix("#![doc = ") {
+		Some(rest) => ("//!", rest),
+		None => ("///", line.strip_prefix("#[doc = ")?),
+	};
+	let literal = syn::parse_str::<LitStr>(rest.strip_suffix(']')?).ok()?;
+	Some((marker, literal.value()))
+}
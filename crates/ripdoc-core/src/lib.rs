@@ -3,22 +3,79 @@
 //! This crate provides the high-level `Ripdoc` API which orchestrates target resolution,
 //! crate documentation generation, and rendering. It is designed to be UI-agnostic and
 //! can be used by any frontend (CLI, GUI, language server, etc.).
+//!
+//! Tools that already hold a parsed `rustdoc_types::Crate` can search, list, and render it with
+//! [`LoadedCrate::from_crate`] instead. `Ripdoc` and everything else that runs cargo sits behind
+//! the default `cargo-resolution` feature, so those tools can turn it off.
 
+/// Items whose source changed since a git revision.
+pub mod changed;
+/// Layered configuration files.
+pub mod config;
 /// Error helpers for the core API.
 pub mod error;
+/// Best-effort skeletons parsed from source when rustdoc fails.
+#[cfg(feature = "cargo-resolution")]
+pub mod fallback;
+/// Crates loaded once for repeated queries.
+pub mod loaded;
+/// Per-stage timings and sizes of a render.
+pub mod metrics;
+/// Rustdoc JSON reduced to selected items.
+pub mod prune;
 /// Search and indexing utilities.
 pub mod search;
-use ripdoc_cargo::resolve_target;
+/// Crate statistics for summaries.
+pub mod summary;
+/// Crate listings nested into a tree.
+pub mod tree;
+/// Remedies for common failures.
+pub mod troubleshoot;
+
+#[cfg(feature = "cargo-resolution")]
+use std::collections::BTreeMap;
+#[cfg(feature = "cargo-resolution")]
+use std::time::{Duration, Instant};
+
 /// Target parsing helpers exposed through ripdoc-cargo.
+#[cfg(feature = "cargo-resolution")]
 pub use ripdoc_cargo::target;
-pub use ripdoc_render::{RenderFormat, Renderer};
+#[cfg(feature = "cargo-resolution")]
+use ripdoc_cargo::{
+	BuildOptions, CargoEnv, Interrupt, ResolvedTarget, check_locked, resolve_target,
+};
+#[cfg(feature = "cargo-resolution")]
+pub use ripdoc_cargo::{
+	BuildPlan, CancellationToken, CrateJson, PlannedTarget, ResolvedFeatures, ResolvedInfo,
+	SourceKind, Stage, Verbosity,
+};
+pub use ripdoc_render::{
+	BodyStyle, FormatterChoice, ModuleTree, Reachability, RenderBudget, RenderFormat, RenderReport,
+	Renderer, SkeletonNode,
+};
+#[cfg(feature = "cargo-resolution")]
 use rustdoc_types::Crate;
 
+pub use crate::config::{Config, SearchConfig};
 pub use crate::error::Result;
+#[cfg(feature = "cargo-resolution")]
+use crate::error::{RenderError, RipdocError};
+#[cfg(feature = "cargo-resolution")]
+pub use crate::loaded::LoadOptions;
+pub use crate::loaded::LoadedCrate;
+pub use crate::metrics::Metrics;
+#[cfg(feature = "cargo-resolution")]
+use crate::search::SearchIndex;
 pub use crate::search::{
-	ListItem, SearchDomain, SearchItemKind, SearchOptions, SearchResponse, SourceLocation,
+	ListItem, MatchMode, SearchDomain, SearchItemKind, SearchOptions, SearchResponse,
+	SourceLocation, UnknownDomain,
 };
-use crate::search::{SearchIndex, build_render_selection};
+pub use crate::summary::{CrateSummary, ModuleSize};
+pub use crate::tree::TreeNode;
+
+/// Most near-miss paths listed when a filter matches nothing.
+#[cfg(feature = "cargo-resolution")]
+const MAX_FILTER_SUGGESTIONS: usize = 3;
 
 /// Ripdoc generates a skeletonized version of a Rust crate in a single page.
 /// It produces syntactically valid Rust code with all implementations omitted.
@@ -27,29 +84,152 @@ use crate::search::{SearchIndex, build_render_selection};
 /// then uses 'cargo doc' with the nightly toolchain to generate JSON output. This JSON
 /// is parsed and used to render the skeletonized code. Users must have the nightly
 /// Rust toolchain installed and available.
+#[cfg(feature = "cargo-resolution")]
 #[derive(Debug, Clone)]
 pub struct Ripdoc {
 	/// In offline mode Ripdoc will not attempt to fetch dependencies from the network.
 	offline: bool,
 
+	/// In locked mode any operation that would rewrite `Cargo.lock` fails instead.
+	locked: bool,
+
+	/// Registry named targets are downloaded from: a name from cargo's config or an index URL.
+	registry: Option<String>,
+
 	/// Whether to render auto-implemented traits.
 	auto_impls: bool,
 
+	/// Whether to render impl blocks at all.
+	impls: bool,
+
+	/// Whether to render items marked `#[doc(hidden)]`.
+	doc_hidden: bool,
+
+	/// Whether to render `#[cfg(test)] mod tests` modules and items from build scripts.
+	tests: bool,
+
 	/// Output format to use when rendering crates.
 	render_format: RenderFormat,
 
-	/// Whether to suppress output during processing.
-	silent: bool,
+	/// Toolchain used to generate rustdoc JSON, overriding the default `nightly`.
+	toolchain: Option<String>,
+
+	/// Target triple to document instead of the host, e.g. `thumbv7em-none-eabihf`.
+	target_triple: Option<String>,
+
+	/// Whether rendered skeletons start with a header describing the crate.
+	header: bool,
+
+	/// Whether a crate that rustdoc cannot build is rendered from a parse of its source instead.
+	source_fallback: bool,
+
+	/// Size rendered skeletons are degraded to fit.
+	budget: Option<RenderBudget>,
+
+	/// What the bodies of rendered functions contain.
+	body_style: BodyStyle,
+
+	/// Amount of diagnostic output emitted during processing.
+	verbosity: Verbosity,
 
 	/// Cache configuration for rustdoc JSON output.
 	cache_config: ripdoc_cargo::CacheConfig,
+
+	/// Timeout and cancellation token for the fetch and build steps.
+	interrupt: Interrupt,
+
+	/// Environment the cargo commands behind the fetch and build steps run in.
+	env: CargoEnv,
+}
+
+/// Warn that a render left out items whose kinds are not supported yet or did not match where
+/// they were listed, listing each item at the info level.
+#[cfg(feature = "cargo-resolution")]
+fn log_skipped(report: &RenderReport) {
+	if report.is_empty() {
+		return;
+	}
+	log::warn!("skipped {report}; pass --verbose to list them");
+	for (kind, paths) in report.skipped() {
+		for path in paths {
+			log::info!(
+				"skipped {} `{path}`: not supported by the renderer",
+				kind.name(1)
+			);
+		}
+	}
+	for mismatched in report.mismatched() {
+		log::info!("skipped {mismatched}");
+	}
+}
+
+/// Render `crate_data` with `renderer`, adding the rendering and formatting time to `metrics`.
+#[cfg(feature = "cargo-resolution")]
+fn measure_render(
+	renderer: &Renderer,
+	crate_data: &Crate,
+	metrics: &mut Metrics,
+) -> ripdoc_render::error::Result<(String, RenderReport)> {
+	let started = Instant::now();
+	let (rendered, report, format_time) = renderer.render_timed(crate_data)?;
+	metrics.format += format_time;
+	metrics.render += started.elapsed().saturating_sub(format_time);
+	Ok((rendered, report))
+}
+
+/// Explain why `filter` matched nothing in `crate_data`.
+///
+/// A path that only exists among private items is reported as private, calling
+/// `read_private` to document them if `crate_data` lacks them. Otherwise near-miss paths are
+/// suggested.
+#[cfg(feature = "cargo-resolution")]
+fn explain_filter_miss(
+	crate_data: &Crate,
+	filter: &str,
+	private_items: bool,
+	read_private: impl FnOnce() -> ripdoc_cargo::Result<Crate>,
+) -> RipdocError {
+	let private_data = if private_items || crate_data.includes_private {
+		None
+	} else {
+		read_private()
+			.inspect_err(|err| log::debug!("failed to document private items: {err}"))
+			.ok()
+	};
+	let full_index = SearchIndex::build(private_data.as_ref().unwrap_or(crate_data), true, None);
+
+	if !private_items
+		&& full_index
+			.find_relative(filter)
+			.is_some_and(|entry| entry.is_private())
+	{
+		return RipdocError::PrivateFilter(filter.to_string());
+	}
+
+	let visible_index = SearchIndex::build(crate_data, private_items, None);
+	let suggestions = visible_index.suggest_relative(filter, MAX_FILTER_SUGGESTIONS);
+	if suggestions.is_empty() {
+		return RenderError::FilterNotMatched(filter.to_string()).into();
+	}
+	RipdocError::FilterSuggestions {
+		filter: filter.to_string(),
+		suggestions,
+	}
 }
 
 /// Check if the rendered output is essentially empty (just an empty module declaration).
 /// This is used to detect binary-only crates with no public API.
+#[cfg(feature = "cargo-resolution")]
 fn is_empty_output(rendered: &str) -> bool {
+	// Markdown output wraps the skeleton in a code fence
+	let trimmed = rendered.trim();
+	let skeleton = trimmed
+		.strip_prefix("```rust")
+		.and_then(|body| body.strip_suffix("```"))
+		.unwrap_or(trimmed);
+
 	// Remove all whitespace: "pub mod name {}" becomes "pubmodname{}"
-	let normalized: String = rendered.chars().filter(|c| !c.is_whitespace()).collect();
+	let normalized: String = skeleton.chars().filter(|c| !c.is_whitespace()).collect();
 
 	// Match pattern: pubmod<identifier>{}
 	normalized.starts_with("pubmod")
@@ -57,12 +237,44 @@ fn is_empty_output(rendered: &str) -> bool {
 		&& normalized.matches('{').count() == 1
 }
 
+/// Feature set named in the output header: `all`, or `default` (unless disabled) followed by the
+/// requested features.
+#[cfg(feature = "cargo-resolution")]
+fn header_features(
+	no_default_features: bool,
+	all_features: bool,
+	features: &[String],
+) -> Vec<String> {
+	if all_features {
+		return vec!["all".to_string()];
+	}
+	let mut requested = features.to_vec();
+	requested.sort();
+	requested.dedup();
+	let default = (!no_default_features).then(|| "default".to_string());
+	default.into_iter().chain(requested).collect()
+}
+
+/// Banner placed above each crate's skeleton when a search spans several crates.
+///
+/// JSON output has no banner: every skeleton's root node already names its crate.
+#[cfg(feature = "cargo-resolution")]
+fn crate_banner(format: RenderFormat, crate_name: &str) -> String {
+	match format {
+		RenderFormat::Rust => format!("// crate: {crate_name}\n"),
+		RenderFormat::Markdown => format!("# Crate `{crate_name}`\n\n"),
+		RenderFormat::Json => String::new(),
+	}
+}
+
+#[cfg(feature = "cargo-resolution")]
 impl Default for Ripdoc {
 	fn default() -> Self {
 		Self::new()
 	}
 }
 
+#[cfg(feature = "cargo-resolution")]
 impl Ripdoc {
 	/// Creates a new Ripdoc instance with default configuration.
 	///
@@ -96,10 +308,23 @@ impl Ripdoc {
 	pub fn new() -> Self {
 		Self {
 			offline: false,
+			locked: false,
+			registry: None,
 			auto_impls: false,
-			silent: false,
+			impls: true,
+			doc_hidden: false,
+			tests: false,
+			toolchain: None,
+			target_triple: None,
+			header: false,
+			source_fallback: false,
+			budget: None,
+			body_style: BodyStyle::Empty,
+			verbosity: Verbosity::Verbose,
 			render_format: RenderFormat::Markdown,
 			cache_config: ripdoc_cargo::CacheConfig::default(),
+			interrupt: Interrupt::default(),
+			env: CargoEnv::default(),
 		}
 	}
 
@@ -110,21 +335,113 @@ impl Ripdoc {
 		self
 	}
 
+	/// Enables or disables locked mode, which fails with cargo's `--locked` error instead of
+	/// updating an out-of-date `Cargo.lock` while resolving or building the target.
+	pub fn with_locked(mut self, locked: bool) -> Self {
+		self.locked = locked;
+		self
+	}
+
 	/// Enables or disables rendering of auto-implemented traits.
 	pub fn with_auto_impls(mut self, auto_impls: bool) -> Self {
 		self.auto_impls = auto_impls;
 		self
 	}
 
+	/// Enables or disables rendering of impl blocks. Without them only declarations remain, with
+	/// derived traits still listed in `#[derive(...)]` attributes.
+	pub fn with_impls(mut self, impls: bool) -> Self {
+		self.impls = impls;
+		self
+	}
+
+	/// Enables or disables rendering of items marked `#[doc(hidden)]`.
+	pub fn with_doc_hidden(mut self, doc_hidden: bool) -> Self {
+		self.doc_hidden = doc_hidden;
+		self
+	}
+
+	/// Enables or disables rendering of `#[cfg(test)] mod tests` modules and items defined in
+	/// build scripts, which can show up when private items are documented.
+	pub fn with_tests(mut self, tests: bool) -> Self {
+		self.tests = tests;
+		self
+	}
+
 	/// Selects the output format used when rendering crate documentation.
 	pub fn with_render_format(mut self, format: RenderFormat) -> Self {
 		self.render_format = format;
 		self
 	}
 
-	/// Enables or disables silent mode, which suppresses output during processing.
+	/// Enables or disables silent mode, which suppresses cargo output during processing.
 	pub fn with_silent(mut self, silent: bool) -> Self {
-		self.silent = silent;
+		self.verbosity = if silent {
+			Verbosity::Normal
+		} else {
+			Verbosity::Verbose
+		};
+		self
+	}
+
+	/// Selects the registry named targets are looked up in and downloaded from, given as the name
+	/// of a registry in cargo's config or as an index URL.
+	///
+	/// `None` uses crates.io, or the mirror configured through cargo's source replacement.
+	pub fn with_registry(mut self, registry: Option<String>) -> Self {
+		self.registry = registry;
+		self
+	}
+
+	/// Selects the rustup toolchain used to generate rustdoc JSON, such as `nightly-2024-10-01`.
+	///
+	/// `None` keeps the default of `nightly` when rustup is available.
+	pub fn with_toolchain(mut self, toolchain: Option<String>) -> Self {
+		self.toolchain = toolchain;
+		self
+	}
+
+	/// Selects the target triple to document, for crates whose API differs per platform. The
+	/// triple's standard library must be installed with `rustup target add`.
+	pub fn with_target_triple(mut self, target_triple: Option<String>) -> Self {
+		self.target_triple = target_triple;
+		self
+	}
+
+	/// Enables or disables the header naming the crate, its version and features, and the
+	/// ripdoc and toolchain versions at the top of [`Ripdoc::render`] output.
+	pub fn with_header(mut self, header: bool) -> Self {
+		self.header = header;
+		self
+	}
+
+	/// Enables or disables rendering a best-effort skeleton parsed from source when rustdoc
+	/// fails to build the crate, e.g. because it does not compile.
+	///
+	/// See [`fallback::render_source_skeleton`] for what the degraded output contains.
+	pub fn with_source_fallback(mut self, source_fallback: bool) -> Self {
+		self.source_fallback = source_fallback;
+		self
+	}
+
+	/// Sets a size that rendered skeletons must fit, dropping doc comments, large structs' fields,
+	/// and the items of deep modules, in that order, until they do.
+	///
+	/// See [`ripdoc_render::budget`] for the exact degradation steps.
+	pub fn with_budget(mut self, budget: Option<RenderBudget>) -> Self {
+		self.budget = budget;
+		self
+	}
+
+	/// Chooses what the bodies of rendered functions contain; see [`BodyStyle`].
+	pub fn with_body_style(mut self, body_style: BodyStyle) -> Self {
+		self.body_style = body_style;
+		self
+	}
+
+	/// Sets how much diagnostic output is emitted during processing.
+	pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+		self.verbosity = verbosity;
 		self
 	}
 
@@ -140,6 +457,156 @@ impl Ripdoc {
 		self
 	}
 
+	/// Stops downloading a registry crate or building its rustdoc JSON once either runs longer
+	/// than `timeout`, failing with [`CargoError::TimedOut`](crate::error::CargoError::TimedOut)
+	/// naming the step. Each step gets the full timeout.
+	pub fn with_timeout(mut self, timeout: Duration) -> Self {
+		self.interrupt.timeout = Some(timeout);
+		self
+	}
+
+	/// Stops a running download or rustdoc JSON build when `token` is cancelled from another
+	/// thread, failing with [`CargoError::Cancelled`](crate::error::CargoError::Cancelled).
+	/// Steps started after cancelling fail at once.
+	pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+		self.interrupt.cancel = Some(token);
+		self
+	}
+
+	/// Sets environment variables for the cargo commands that fetch crates and build their
+	/// rustdoc JSON, removing a variable when its value is `None`.
+	///
+	/// Overrides are applied after the defaults described in [`CargoEnv`], so they can restore
+	/// `RUSTC_WRAPPER` or pick another `CARGO_TARGET_DIR`. Calling this again replaces earlier
+	/// overrides.
+	pub fn with_env(mut self, overrides: Vec<(String, Option<String>)>) -> Self {
+		self.env.overrides = overrides;
+		self
+	}
+
+	/// Builds rustdoc JSON in the project's own target directory, as `cargo doc` would, instead
+	/// of the scratch directory ripdoc keeps under its cache.
+	pub fn with_project_target_dir(mut self, project_target_dir: bool) -> Self {
+		self.env.project_target_dir = project_target_dir;
+		self
+	}
+
+	/// Applies the instance-wide settings from a config layer: offline mode, registry, auto
+	/// impls, hidden items, test modules, output format, toolchain, target triple, and caching.
+	/// Fields left unset keep their current values.
+	///
+	/// Per-call settings such as features and private items are passed to each method instead.
+	pub fn with_config(mut self, config: &Config) -> Self {
+		if let Some(offline) = config.offline {
+			self.offline = offline;
+		}
+		if let Some(registry) = &config.registry {
+			self.registry = Some(registry.clone());
+		}
+		if let Some(auto_impls) = config.auto_impls {
+			self.auto_impls = auto_impls;
+		}
+		if let Some(include_hidden) = config.include_hidden {
+			self.doc_hidden = include_hidden;
+		}
+		if let Some(include_tests) = config.include_tests {
+			self.tests = include_tests;
+		}
+		if let Some(format) = config.format {
+			self.render_format = format;
+		}
+		if let Some(toolchain) = &config.toolchain {
+			self.toolchain = Some(toolchain.clone());
+		}
+		if let Some(target) = &config.target {
+			self.target_triple = Some(target.clone());
+		}
+		if let Some(cache) = config.cache {
+			self.cache_config.enabled = cache;
+		}
+		if let Some(cache_dir) = &config.cache_dir {
+			self.cache_config = self.cache_config.with_cache_dir(cache_dir.clone());
+		}
+		self
+	}
+
+	/// Resolves the target and describes how its rustdoc JSON would be obtained, without building.
+	///
+	/// Takes the same build arguments as [`Self::inspect`].
+	pub fn plan(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+		private_items: bool,
+	) -> Result<BuildPlan> {
+		let rt = self.resolve_checked(target)?;
+		Ok(rt.plan(
+			&self.build_options(no_default_features, all_features, features, private_items),
+			&self.cache_config,
+		)?)
+	}
+
+	/// Resolves a target without building it, reporting the package name and version that were
+	/// picked up, where the package comes from, its directory, and the normalized module filter.
+	///
+	/// Named targets prefer a workspace member, then a dependency of the current package, then
+	/// the latest registry release, so this shows which of those a name refers to.
+	pub fn resolve(&self, target: &str) -> Result<ResolvedInfo> {
+		Ok(self.resolve_checked(target)?.info()?)
+	}
+
+	/// Build settings for documenting a target with these features, and private items when
+	/// `private_items` is set.
+	fn build_options<'a>(
+		&'a self,
+		no_default_features: bool,
+		all_features: bool,
+		features: &'a [String],
+		private_items: bool,
+	) -> BuildOptions<'a> {
+		BuildOptions {
+			no_default_features,
+			all_features,
+			features,
+			private_items,
+			toolchain: self.toolchain.as_deref(),
+			target_triple: self.target_triple.as_deref(),
+			verbosity: self.verbosity,
+			interrupt: &self.interrupt,
+			env: &self.env,
+		}
+	}
+
+	/// Resolve a target, first checking the lockfile in locked mode.
+	fn resolve_checked(&self, target: &str) -> Result<ResolvedTarget> {
+		if self.locked {
+			check_locked(target, self.offline)?;
+		}
+		Ok(resolve_target(
+			target,
+			self.offline,
+			self.registry.as_deref(),
+			&self.interrupt,
+			&self.env,
+		)?)
+	}
+
+	/// Returns the package directory backing a path-based target, for watching its sources.
+	///
+	/// Named targets are rejected because they may resolve to read-only registry downloads.
+	pub fn source_root(&self, target: &str) -> Result<std::path::PathBuf> {
+		let parsed = target::Target::parse(target)?;
+		if !matches!(parsed.entrypoint, target::Entrypoint::Path(_)) {
+			return Err(RipdocError::InvalidTarget(format!(
+				"'{target}' is not a path; only local package paths can be watched"
+			)));
+		}
+		let rt = self.resolve_checked(target)?;
+		Ok(rt.package_root().to_path_buf())
+	}
+
 	/// Returns the parsed representation of the crate's API.
 	///
 	/// # Arguments
@@ -153,16 +620,32 @@ impl Ripdoc {
 		target: &str,
 		no_default_features: bool,
 		all_features: bool,
-		features: Vec<String>,
+		features: &[String],
 		private_items: bool,
 	) -> Result<Crate> {
-		let rt = resolve_target(target, self.offline)?;
+		let rt = self.resolve_checked(target)?;
 		Ok(rt.read_crate(
-			no_default_features,
-			all_features,
-			features,
-			private_items,
-			self.silent,
+			&self.build_options(no_default_features, all_features, features, private_items),
+			&self.cache_config,
+		)?)
+	}
+
+	/// Returns the parsed representation of the crate's API like [`Self::inspect`], along with
+	/// the rustdoc JSON file it was read from.
+	///
+	/// Other tools can reuse that file instead of building the JSON again. When the crate came
+	/// from the ripdoc cache and rustdoc's output has since been removed, the path is `None`.
+	pub fn inspect_with_path(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+		private_items: bool,
+	) -> Result<CrateJson> {
+		let rt = self.resolve_checked(target)?;
+		Ok(rt.read_crate_with_path(
+			&self.build_options(no_default_features, all_features, features, private_items),
 			&self.cache_config,
 		)?)
 	}
@@ -170,104 +653,287 @@ impl Ripdoc {
 	/// Execute a search against the crate and return the matched items along with a rendered skeleton.
 	///
 	/// The search respects the same target resolution logic as [`Self::render`], but only the
-	/// matched items and their ancestors are emitted in the final skeleton.
+	/// matched items and their ancestors are emitted in the final skeleton. When
+	/// [`SearchOptions::include_dependencies`] is set this defers to [`Self::search_with_deps`].
 	pub fn search(
 		&self,
 		target: &str,
 		no_default_features: bool,
 		all_features: bool,
-		features: Vec<String>,
+		features: &[String],
 		options: &SearchOptions,
 	) -> Result<SearchResponse> {
-		let rt = resolve_target(target, self.offline)?;
+		if options.include_dependencies {
+			return self.search_with_deps(
+				target,
+				no_default_features,
+				all_features,
+				features,
+				options,
+			);
+		}
+
+		let loaded = self.load(
+			target,
+			&LoadOptions {
+				no_default_features,
+				all_features,
+				features: features.to_vec(),
+				include_private: options.include_private,
+			},
+		)?;
+		loaded.search(options)
+	}
+
+	/// List the features declared by the target's package, each with what it enables.
+	pub fn features(&self, target: &str) -> Result<BTreeMap<String, Vec<String>>> {
+		Ok(self.resolve_checked(target)?.features()?)
+	}
+
+	/// Build and index the target once so it can be searched, listed, and rendered repeatedly.
+	///
+	/// The returned [`LoadedCrate`] keeps this instance's render settings.
+	pub fn load(&self, target: &str, options: &LoadOptions) -> Result<LoadedCrate> {
+		self.load_resolved(&self.resolve_checked(target)?, options)
+	}
+
+	/// Build the target and summarize it: how many items of each kind it has, its largest
+	/// modules, and how many items are documented or deprecated. See [`CrateSummary`].
+	pub fn summarize(&self, target: &str, options: &LoadOptions) -> Result<CrateSummary> {
+		Ok(self.load(target, options)?.summary())
+	}
+
+	/// Index rustdoc JSON that is already in memory with this instance's render settings.
+	///
+	/// Nothing is resolved or built; private items are indexed when `crate_data` documents them.
+	/// See [`LoadedCrate::from_crate`] for the same without a `Ripdoc`.
+	pub fn load_crate(&self, crate_data: Crate) -> LoadedCrate {
+		let include_private = crate_data.includes_private;
+		LoadedCrate::new(self, crate_data, None, None, include_private)
+	}
+
+	/// Build and index the already resolved target `rt`.
+	fn load_resolved(&self, rt: &ResolvedTarget, options: &LoadOptions) -> Result<LoadedCrate> {
 		let crate_data = rt.read_crate(
-			no_default_features,
-			all_features,
-			features,
-			options.include_private,
-			self.silent,
+			&self.build_options(
+				options.no_default_features,
+				options.all_features,
+				&options.features,
+				options.include_private,
+			),
 			&self.cache_config,
 		)?;
+		let resolved = rt.info()?;
+		let resolved_features = self.resolved_features(
+			rt,
+			&resolved,
+			options.no_default_features,
+			options.all_features,
+			&options.features,
+		);
+		Ok(LoadedCrate::new(
+			self,
+			crate_data,
+			Some(resolved),
+			resolved_features,
+			options.include_private,
+		))
+	}
 
-		let index = SearchIndex::build(
-			&crate_data,
+	/// Features cargo enables for the package behind `rt` given the feature flags, warning when
+	/// other packages in the build turned on features that were not requested.
+	///
+	/// Only local packages are checked, since only they are built alongside other workspace
+	/// members. Failures are logged and yield `None`.
+	fn resolved_features(
+		&self,
+		rt: &ResolvedTarget,
+		resolved: &ResolvedInfo,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+	) -> Option<ResolvedFeatures> {
+		if !matches!(
+			resolved.source,
+			SourceKind::WorkspaceMember | SourceKind::Path
+		) {
+			return None;
+		}
+		let resolved_features = rt
+			.resolved_features(
+				no_default_features,
+				all_features,
+				features,
+				self.offline,
+				&self.env,
+			)
+			.inspect_err(|err| log::debug!("failed to resolve enabled features: {err}"))
+			.ok()
+			.flatten()?;
+		if !resolved_features.unified.is_empty() {
+			log::warn!(
+				"{} is built with features [{}] that were not requested, enabled by other \
+				 packages in the workspace",
+				resolved.package,
+				resolved_features.unified.join(", ")
+			);
+		}
+		Some(resolved_features)
+	}
+
+	/// Search the target and its direct dependencies, tagging each result with its crate name.
+	///
+	/// Feature flags apply to the target only; dependencies are documented with their default
+	/// features, going through the same cache as the target. Each crate with matches is rendered
+	/// on its own and the skeletons are concatenated, each preceded by a banner naming the crate.
+	/// Dependencies that fail to build are skipped with a warning.
+	pub fn search_with_deps(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+		options: &SearchOptions,
+	) -> Result<SearchResponse> {
+		let rt = self.resolve_checked(target)?;
+		let crate_data = rt.read_crate(
+			&self.build_options(
+				no_default_features,
+				all_features,
+				features,
+				options.include_private,
+			),
+			&self.cache_config,
+		)?;
+		let loaded = LoadedCrate::new(
+			self,
+			crate_data,
+			Some(rt.info()?),
+			None,
 			options.include_private,
-			Some(rt.package_root()),
 		);
-		let results = index.search(options);
+		let mut responses = vec![loaded.search(options)?];
 
-		if results.is_empty() {
-			return Ok(SearchResponse {
-				results,
-				rendered: String::new(),
+		for dependency in rt.dependencies(self.offline)? {
+			let read = dependency.info().and_then(|resolved| {
+				let crate_data = dependency.read_crate(
+					&self.build_options(false, false, &[], options.include_private),
+					&self.cache_config,
+				)?;
+				Ok((resolved, crate_data))
 			});
+			let (resolved, crate_data) = match read {
+				Ok(read) => read,
+				Err(err) => {
+					log::warn!(
+						"skipping dependency at {}: {err}",
+						dependency.package_root().display()
+					);
+					continue;
+				}
+			};
+			let loaded = LoadedCrate::new(
+				self,
+				crate_data,
+				Some(resolved),
+				None,
+				options.include_private,
+			);
+			responses.push(loaded.search(options)?);
 		}
 
-		let selection = build_render_selection(&index, &results, options.expand_containers);
-		let renderer = Renderer::default()
-			.with_filter(&rt.filter)
-			.with_auto_impls(self.auto_impls)
-			.with_private_items(options.include_private)
-			.with_format(self.render_format)
-			.with_selection(selection);
-		let rendered = renderer.render(&crate_data)?;
-
-		Ok(SearchResponse { results, rendered })
+		let mut merged = SearchResponse {
+			results: Vec::new(),
+			rendered: String::new(),
+		};
+		for response in responses {
+			let Some(crate_name) = response.results.first().map(|r| r.crate_name.clone()) else {
+				continue;
+			};
+			if !merged.rendered.is_empty() {
+				merged.rendered.push('\n');
+			}
+			merged
+				.rendered
+				.push_str(&crate_banner(self.render_format, &crate_name));
+			merged.rendered.push_str(&response.rendered);
+			merged.results.extend(response.results);
+		}
+		Ok(merged)
 	}
 
 	/// Produce a lightweight listing of crate items, optionally filtered by a search query.
+	///
+	/// Only items at or below the target's `::path` filter are listed.
 	pub fn list(
 		&self,
 		target: &str,
 		no_default_features: bool,
 		all_features: bool,
-		features: Vec<String>,
+		features: &[String],
 		include_private: bool,
 		search: Option<&SearchOptions>,
 	) -> Result<Vec<ListItem>> {
-		let include_private = include_private
-			|| search
-				.map(|options| options.include_private)
-				.unwrap_or(false);
-
-		let rt = resolve_target(target, self.offline)?;
-		let crate_data = rt.read_crate(
+		let loaded = self.load_listing(
+			target,
 			no_default_features,
 			all_features,
 			features,
 			include_private,
-			self.silent,
-			&self.cache_config,
+			search,
 		)?;
 
-		let index = SearchIndex::build(&crate_data, include_private, Some(rt.package_root()));
-
-		let mut results: Vec<ListItem> = if let Some(options) = search {
-			index
-				.search(options)
-				.into_iter()
-				.map(|result| ListItem {
-					kind: result.kind,
-					path: result.path_string,
-					source: result.source,
-				})
-				.collect()
-		} else {
-			index
-				.entries()
-				.iter()
-				.cloned()
-				.map(|entry| ListItem {
-					kind: entry.kind,
-					path: entry.path_string,
-					source: entry.source,
-				})
-				.collect()
-		};
+		Ok(match search {
+			Some(options) => loaded.list_matching(options),
+			None => loaded.list(),
+		})
+	}
 
-		results.retain(|item| item.kind != SearchItemKind::Use);
+	/// Produce the listing as a tree of modules and the items they contain, optionally limited
+	/// to what a search query matches and the items enclosing it.
+	pub fn list_tree(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+		include_private: bool,
+		search: Option<&SearchOptions>,
+	) -> Result<Vec<TreeNode>> {
+		let loaded = self.load_listing(
+			target,
+			no_default_features,
+			all_features,
+			features,
+			include_private,
+			search,
+		)?;
+		Ok(loaded.list_tree(search))
+	}
 
-		Ok(results)
+	/// Load a target for listing, indexing private items when `search` asks for them.
+	fn load_listing(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+		include_private: bool,
+		search: Option<&SearchOptions>,
+	) -> Result<LoadedCrate> {
+		let include_private = include_private
+			|| search
+				.map(|options| options.include_private)
+				.unwrap_or(false);
+		self.load(
+			target,
+			&LoadOptions {
+				no_default_features,
+				all_features,
+				features: features.to_vec(),
+				include_private,
+			},
+		)
 	}
 
 	/// Render the crate target into a Rust skeleton without filtering.
@@ -276,49 +942,313 @@ impl Ripdoc {
 		target: &str,
 		no_default_features: bool,
 		all_features: bool,
-		features: Vec<String>,
+		features: &[String],
 		private_items: bool,
 	) -> Result<String> {
-		let rt = resolve_target(target, self.offline)?;
-		let crate_data = rt.read_crate(
+		self.render_measured(
+			target,
 			no_default_features,
 			all_features,
-			features.clone(),
+			features,
 			private_items,
-			self.silent,
-			&self.cache_config,
+			&mut Metrics::default(),
+		)
+	}
+
+	/// Render the crate target like [`Self::render`], also measuring how long each stage took
+	/// and how large its output was.
+	pub fn render_with_metrics(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+		private_items: bool,
+	) -> Result<(String, Metrics)> {
+		let started = Instant::now();
+		let mut metrics = Metrics::default();
+		let rendered = self.render_measured(
+			target,
+			no_default_features,
+			all_features,
+			features,
+			private_items,
+			&mut metrics,
 		)?;
+		metrics.output_bytes = rendered.len() as u64;
+		metrics.total = started.elapsed();
+		Ok((rendered, metrics))
+	}
+
+	/// Render the crate target, recording each stage's measurements in `metrics`.
+	fn render_measured(
+		&self,
+		target: &str,
+		no_default_features: bool,
+		all_features: bool,
+		features: &[String],
+		private_items: bool,
+		metrics: &mut Metrics,
+	) -> Result<String> {
+		let started = Instant::now();
+		let rt = self.resolve_checked(target)?;
+		metrics.fetch = rt.fetch_time();
+		metrics.resolve = started.elapsed().saturating_sub(metrics.fetch);
+		let (crate_data, build) = match rt.read_crate_measured(
+			&self.build_options(no_default_features, all_features, features, private_items),
+			&self.cache_config,
+		) {
+			Err(ripdoc_cargo::RipdocError::BuildFailed(message))
+				if self.source_fallback && self.render_format != RenderFormat::Json =>
+			{
+				log::warn!("{message}");
+				log::warn!("rendering a skeleton parsed from source instead");
+				return fallback::render_source_skeleton(
+					rt.package_root(),
+					self.render_format,
+					private_items,
+				)
+				.map_err(|err| {
+					log::warn!("source fallback failed: {err}");
+					ripdoc_cargo::RipdocError::BuildFailed(message).into()
+				});
+			}
+			result => result?,
+		};
+		metrics.add_build(&build);
+		metrics.item_count = crate_data.index.len();
 
 		let renderer = Renderer::default()
 			.with_filter(&rt.filter)
 			.with_auto_impls(self.auto_impls)
+			.with_impls(self.impls)
+			.with_doc_hidden(self.doc_hidden)
+			.with_tests(self.tests)
 			.with_private_items(private_items)
-			.with_format(self.render_format);
+			.with_format(self.render_format)
+			.with_budget(self.budget)
+			.with_body_style(self.body_style);
+
+		let (rendered, report) = match measure_render(&renderer, &crate_data, metrics) {
+			Err(RenderError::FilterNotMatched(filter)) => {
+				return Err(explain_filter_miss(
+					&crate_data,
+					&filter,
+					private_items,
+					|| {
+						rt.read_crate(
+							&self.build_options(no_default_features, all_features, features, true),
+							&self.cache_config,
+						)
+					},
+				));
+			}
+			result => result?,
+		};
 
-		let rendered = renderer.render(&crate_data)?;
+		let header_features = header_features(no_default_features, all_features, features);
+		let resolved = rt.info()?;
+		let resolved_features =
+			self.resolved_features(&rt, &resolved, no_default_features, all_features, features);
 
 		// If the public API is essentially empty and we weren't already including private items,
 		// automatically retry with private items enabled (useful for binary-only crates)
 		if !private_items && is_empty_output(&rendered) {
-			let crate_data_private = rt.read_crate(
-				no_default_features,
-				all_features,
-				features,
-				true,
-				self.silent,
-				&self.cache_config,
-			)?;
-
+			log::info!("no public items found; rendering private items instead");
 			let renderer_private = Renderer::default()
 				.with_filter(&rt.filter)
 				.with_auto_impls(self.auto_impls)
+				.with_impls(self.impls)
+				.with_doc_hidden(true)
+				.with_tests(self.tests)
 				.with_private_items(true)
-				.with_format(RenderFormat::Rust);
+				.with_format(RenderFormat::Rust)
+				.with_budget(self.budget)
+				.with_body_style(self.body_style);
+
+			// Binary targets are documented with private items by default, so the JSON we already
+			// have can usually be re-rendered without another rustdoc run.
+			if crate_data.includes_private {
+				let header = self.header(
+					RenderFormat::Rust,
+					&crate_data,
+					&resolved,
+					header_features,
+					resolved_features.as_ref(),
+				);
+				let (rendered, report) = measure_render(&renderer_private, &crate_data, metrics)?;
+				log_skipped(&report);
+				return Ok(header + &rendered);
+			}
+
+			let (crate_data_private, build) = rt.read_crate_measured(
+				&self.build_options(no_default_features, all_features, features, true),
+				&self.cache_config,
+			)?;
+			metrics.add_build(&build);
+			metrics.item_count = crate_data_private.index.len();
+			let header = self.header(
+				RenderFormat::Rust,
+				&crate_data_private,
+				&resolved,
+				header_features,
+				resolved_features.as_ref(),
+			);
+			let (rendered, report) =
+				measure_render(&renderer_private, &crate_data_private, metrics)?;
+			log_skipped(&report);
+			return Ok(header + &rendered);
+		}
+
+		log_skipped(&report);
+		let header = self.header(
+			self.render_format,
+			&crate_data,
+			&resolved,
+			header_features,
+			resolved_features.as_ref(),
+		);
+		Ok(header + &rendered)
+	}
+
+	/// Render the crate target as Rust source split into one file per module, as laid out by
+	/// [`ModuleTree`]; the header, when enabled, starts the root module's source.
+	///
+	/// Returns `None` when nothing is rendered. Unlike [`Self::render`], there is no fallback to
+	/// private items or to parsing source.
+	pub fn render_tree(&self, target: &str, options: &LoadOptions) -> Result<Option<ModuleTree>> {
+		let rt = self.resolve_checked(target)?;
+		let loaded = self.load_resolved(&rt, options)?;
+		let tree = match loaded.render_tree() {
+			Err(RipdocError::Render(RenderError::FilterNotMatched(filter))) => {
+				return Err(explain_filter_miss(
+					loaded.crate_data(),
+					&filter,
+					options.include_private,
+					|| {
+						rt.read_crate(
+							&self.build_options(
+								options.no_default_features,
+								options.all_features,
+								&options.features,
+								true,
+							),
+							&self.cache_config,
+						)
+					},
+				));
+			}
+			result => result?,
+		};
+		let Some(mut tree) = tree else {
+			return Ok(None);
+		};
+
+		let features = header_features(
+			options.no_default_features,
+			options.all_features,
+			&options.features,
+		);
+		let header = self.header(
+			RenderFormat::Rust,
+			loaded.crate_data(),
+			&rt.info()?,
+			features,
+			loaded.resolved_features(),
+		);
+		tree.source.insert_str(0, &header);
+		Ok(Some(tree))
+	}
+
+	/// Header describing `crate_data`, resolved as `resolved` and built with
+	/// `resolved_features` when known, for `format` output, or nothing when headers are off.
+	fn header(
+		&self,
+		format: RenderFormat,
+		crate_data: &Crate,
+		resolved: &ResolvedInfo,
+		features: Vec<String>,
+		resolved_features: Option<&ResolvedFeatures>,
+	) -> String {
+		if !self.header {
+			return String::new();
+		}
+		Renderer::default()
+			.with_format(format)
+			.with_header_source(Some(resolved.source.to_string()))
+			.with_header_requested(resolved.requested.clone())
+			.with_header_features(features)
+			.with_header_resolved_features(
+				resolved_features.map(|resolved_features| resolved_features.enabled.clone()),
+			)
+			.with_header_toolchain(ripdoc_cargo::get_toolchain_version(
+				self.toolchain.as_deref(),
+			))
+			.with_header_target(self.target_triple.clone())
+			.header(crate_data)
+	}
 
-			return Ok(renderer_private.render(&crate_data_private)?);
+	/// Render only the items defined in files changed since the git revision `rev`, with the
+	/// modules and types enclosing them.
+	///
+	/// Only path targets inside a git worktree are supported. Changed files are those listed by
+	/// `git diff --name-only <rev>`; see [`changed::changed_items`] for how items are matched
+	/// to them. Nothing is rendered when no item changed.
+	pub fn render_changed_since(
+		&self,
+		target: &str,
+		options: &LoadOptions,
+		rev: &str,
+	) -> Result<String> {
+		let parsed = target::Target::parse(target)?;
+		if !matches!(parsed.entrypoint, target::Entrypoint::Path(_)) {
+			return Err(RipdocError::InvalidTarget(format!(
+				"'{target}' is not a path; only local packages can be compared with a git revision"
+			)));
+		}
+		let rt = self.resolve_checked(target)?;
+		let loaded = self.load_resolved(&rt, options)?;
+		let package_root = rt.package_root();
+		let changed = ripdoc_cargo::changed_files(package_root, rev)?;
+		let ids = changed::changed_items(loaded.crate_data(), package_root, &changed);
+		if ids.is_empty() {
+			log::info!("no items changed since {rev}");
+			return Ok(String::new());
 		}
+		loaded.render_items(&ids)
+	}
 
-		Ok(rendered)
+	/// Render only the target's public surface, the items reachable from its root through
+	/// public modules and `pub use` re-exports; see [`LoadedCrate::render_facade`].
+	pub fn render_facade(&self, target: &str, options: &LoadOptions) -> Result<String> {
+		self.load(target, options)?.render_facade()
+	}
+
+	/// List the items the target declares `pub` that no public path reaches; see
+	/// [`LoadedCrate::unreachable_pub`].
+	///
+	/// Private items are always documented for this, since the modules holding such items are
+	/// private themselves.
+	pub fn unreachable_pub(&self, target: &str, options: &LoadOptions) -> Result<Vec<ListItem>> {
+		let options = LoadOptions {
+			include_private: true,
+			..options.clone()
+		};
+		Ok(self.load(target, &options)?.unreachable_pub())
+	}
+
+	/// Returns pretty-printed rustdoc JSON reduced to the items a search or filter path selects.
+	///
+	/// With `search`, the matched items are kept; otherwise the target's `::path` filter picks the
+	/// item, and a target without a filter yields the whole crate like [`Self::raw_json`]. See
+	/// [`LoadedCrate::selected_json`].
+	pub fn raw_json_selected(
+		&self,
+		target: &str,
+		options: &LoadOptions,
+		search: Option<&SearchOptions>,
+	) -> Result<String> {
+		self.load(target, options)?.selected_json(search)
 	}
 
 	/// Returns a pretty-printed version of the crate's JSON representation.
@@ -334,7 +1264,7 @@ impl Ripdoc {
 		target: &str,
 		no_default_features: bool,
 		all_features: bool,
-		features: Vec<String>,
+		features: &[String],
 		private_items: bool,
 	) -> Result<String> {
 		Ok(serde_json::to_string_pretty(&self.inspect(
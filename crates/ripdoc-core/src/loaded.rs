@@ -0,0 +1,447 @@
+//! Crates loaded once and queried repeatedly.
+
+#[cfg(feature = "cargo-resolution")]
+use ripdoc_cargo::{ResolvedFeatures, ResolvedInfo};
+use ripdoc_render::{
+	BodyStyle, ModuleTree, Reachability, RenderBudget, RenderFormat, RenderSelection, Renderer,
+};
+use rustdoc_types::{Crate, Id};
+
+#[cfg(feature = "cargo-resolution")]
+use crate::Ripdoc;
+use crate::error::{RenderError, Result};
+use crate::prune;
+use crate::search::{
+	ListItem, SearchIndex, SearchItemKind, SearchOptions, SearchResponse, SearchResult,
+};
+use crate::summary::CrateSummary;
+use crate::tree::{self, TreeNode};
+
+/// Build settings for [`Ripdoc::load`].
+#[cfg(feature = "cargo-resolution")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct LoadOptions {
+	/// Build without the crate's default features.
+	pub no_default_features: bool,
+	/// Build with all of the crate's features.
+	pub all_features: bool,
+	/// Specific features to enable.
+	pub features: Vec<String>,
+	/// Document and index private items.
+	pub include_private: bool,
+}
+
+/// A parsed crate with its search index, ready for repeated queries.
+///
+/// Nothing is rebuilt or re-read between queries, so an interactive frontend can search on
+/// every keystroke. The type is `Send`, so it can be queried from a worker thread.
+///
+/// Crates are loaded from a target with [`Ripdoc::load`], or from rustdoc JSON already in
+/// memory with [`LoadedCrate::from_crate`], which needs neither cargo nor a toolchain.
+#[derive(Debug, Clone)]
+pub struct LoadedCrate {
+	/// Parsed rustdoc JSON for the crate.
+	crate_data: Crate,
+	/// Index over the crate's items.
+	index: SearchIndex,
+	/// Module filter path applied when rendering, empty for the whole crate.
+	filter: String,
+	/// What the target specification resolved to; `None` for crates loaded from memory.
+	#[cfg(feature = "cargo-resolution")]
+	resolved: Option<ResolvedInfo>,
+	/// Features cargo enabled for the package; `None` when unknown.
+	#[cfg(feature = "cargo-resolution")]
+	resolved_features: Option<ResolvedFeatures>,
+	/// Whether private items were documented and indexed.
+	include_private: bool,
+	/// Whether to render auto-implemented traits.
+	auto_impls: bool,
+	/// Whether to render impl blocks at all.
+	impls: bool,
+	/// Whether to render items marked `#[doc(hidden)]`.
+	doc_hidden: bool,
+	/// Whether to render `#[cfg(test)] mod tests` modules and items from build scripts.
+	tests: bool,
+	/// Output format used when rendering selections.
+	render_format: RenderFormat,
+	/// Size rendered output is degraded to fit.
+	budget: Option<RenderBudget>,
+	/// What the bodies of rendered functions contain.
+	body_style: BodyStyle,
+}
+
+impl LoadedCrate {
+	/// Index rustdoc JSON that is already in memory, without resolving or building anything.
+	///
+	/// Private items are indexed when `crate_data` documents them. Rendering starts out as
+	/// Markdown with impl blocks but without auto-implemented traits, hidden items, or tests;
+	/// the `with_*` methods change that.
+	pub fn from_crate(crate_data: Crate) -> Self {
+		let include_private = crate_data.includes_private;
+		let index = SearchIndex::build(&crate_data, include_private, None);
+		Self {
+			crate_data,
+			index,
+			filter: String::new(),
+			#[cfg(feature = "cargo-resolution")]
+			resolved: None,
+			#[cfg(feature = "cargo-resolution")]
+			resolved_features: None,
+			include_private,
+			auto_impls: false,
+			impls: true,
+			doc_hidden: false,
+			tests: false,
+			render_format: RenderFormat::Markdown,
+			budget: None,
+			body_style: BodyStyle::Empty,
+		}
+	}
+
+	/// Parse rustdoc JSON and index it as [`Self::from_crate`] does.
+	///
+	/// JSON written by a rustdoc whose format version this build does not understand is
+	/// reported as a version mismatch rather than as a schema error.
+	#[cfg(feature = "cargo-resolution")]
+	pub fn from_json(json: &str) -> Result<Self> {
+		Ok(Self::from_crate(ripdoc_cargo::parse_crate(json)?))
+	}
+
+	/// Index `crate_data`, taking render settings from `ripdoc` for later selections.
+	#[cfg(feature = "cargo-resolution")]
+	pub(crate) fn new(
+		ripdoc: &Ripdoc,
+		crate_data: Crate,
+		resolved: Option<ResolvedInfo>,
+		resolved_features: Option<ResolvedFeatures>,
+		include_private: bool,
+	) -> Self {
+		let source_root = resolved.as_ref().map(|resolved| resolved.path.as_path());
+		let index = SearchIndex::build(&crate_data, include_private, source_root);
+		let filter = resolved
+			.as_ref()
+			.map(|resolved| resolved.filter.clone())
+			.unwrap_or_default();
+		Self {
+			crate_data,
+			index,
+			filter,
+			resolved,
+			resolved_features,
+			include_private,
+			auto_impls: ripdoc.auto_impls,
+			impls: ripdoc.impls,
+			doc_hidden: ripdoc.doc_hidden,
+			tests: ripdoc.tests,
+			render_format: ripdoc.render_format,
+			budget: ripdoc.budget,
+			body_style: ripdoc.body_style,
+		}
+	}
+
+	/// Restrict rendering to the module or item at `filter`, a path relative to the crate root.
+	pub fn with_filter(mut self, filter: &str) -> Self {
+		self.filter = filter.to_string();
+		self
+	}
+
+	/// Set whether to render auto-implemented traits.
+	pub fn with_auto_impls(mut self, auto_impls: bool) -> Self {
+		self.auto_impls = auto_impls;
+		self
+	}
+
+	/// Set whether to render impl blocks at all.
+	pub fn with_impls(mut self, impls: bool) -> Self {
+		self.impls = impls;
+		self
+	}
+
+	/// Set whether to render items marked `#[doc(hidden)]`.
+	pub fn with_doc_hidden(mut self, doc_hidden: bool) -> Self {
+		self.doc_hidden = doc_hidden;
+		self
+	}
+
+	/// Set whether to render `#[cfg(test)] mod tests` modules and items from build scripts.
+	pub fn with_tests(mut self, tests: bool) -> Self {
+		self.tests = tests;
+		self
+	}
+
+	/// Set the output format used when rendering.
+	pub fn with_render_format(mut self, render_format: RenderFormat) -> Self {
+		self.render_format = render_format;
+		self
+	}
+
+	/// Set the size rendered output is degraded to fit.
+	pub fn with_budget(mut self, budget: Option<RenderBudget>) -> Self {
+		self.budget = budget;
+		self
+	}
+
+	/// Set what the bodies of rendered functions contain.
+	pub fn with_body_style(mut self, body_style: BodyStyle) -> Self {
+		self.body_style = body_style;
+		self
+	}
+
+	/// Parsed rustdoc JSON for the crate.
+	pub fn crate_data(&self) -> &Crate {
+		&self.crate_data
+	}
+
+	/// Index over the crate's items.
+	pub fn index(&self) -> &SearchIndex {
+		&self.index
+	}
+
+	/// Module filter path applied when rendering, empty when the whole crate is rendered.
+	pub fn filter(&self) -> &str {
+		&self.filter
+	}
+
+	/// Package, version, and source the target resolved to, or `None` for a crate loaded with
+	/// [`Self::from_crate`].
+	#[cfg(feature = "cargo-resolution")]
+	pub fn resolved(&self) -> Option<&ResolvedInfo> {
+		self.resolved.as_ref()
+	}
+
+	/// Features cargo enabled for the package once they were unified with the rest of the
+	/// workspace, or `None` when they were not checked; see [`ripdoc_cargo::resolved_features`].
+	#[cfg(feature = "cargo-resolution")]
+	pub fn resolved_features(&self) -> Option<&ResolvedFeatures> {
+		self.resolved_features.as_ref()
+	}
+
+	/// Render the whole crate, or the part selected by the target's filter path.
+	///
+	/// Unlike `Ripdoc::render`, this never falls back to private items when the public API is
+	/// empty; load with `LoadOptions::include_private` to see them.
+	pub fn render(&self) -> Result<String> {
+		let renderer = Renderer::default()
+			.with_filter(&self.filter)
+			.with_auto_impls(self.auto_impls)
+			.with_impls(self.impls)
+			.with_doc_hidden(self.doc_hidden)
+			.with_tests(self.tests)
+			.with_private_items(self.include_private)
+			.with_format(self.render_format)
+			.with_budget(self.budget)
+			.with_body_style(self.body_style);
+		Ok(renderer.render(&self.crate_data)?)
+	}
+
+	/// Render only the crate's public surface: the items reachable from the crate root through
+	/// public modules and `pub use` re-exports, shown where they are re-exported.
+	///
+	/// Private items are never rendered, and neither are `pub` items in private modules that
+	/// nothing re-exports; [`Self::unreachable_pub`] lists those. See [`Reachability`].
+	pub fn render_facade(&self) -> Result<String> {
+		let selection = Reachability::new(&self.crate_data).selection(&self.crate_data);
+		let renderer = Renderer::default()
+			.with_filter(&self.filter)
+			.with_auto_impls(self.auto_impls)
+			.with_impls(self.impls)
+			.with_doc_hidden(self.doc_hidden)
+			.with_tests(self.tests)
+			.with_private_items(false)
+			.with_format(self.render_format)
+			.with_budget(self.budget)
+			.with_body_style(self.body_style)
+			.with_selection(selection);
+		Ok(renderer.render(&self.crate_data)?)
+	}
+
+	/// List the items declared `pub` that no public path from the crate root reaches, usually
+	/// by accident, such as `pub` items in a private module the crate never re-exports.
+	///
+	/// Items are listed at the path they are declared at, in declaration order. Only a crate
+	/// loaded with its private items documents the modules these live in, so the list is
+	/// empty otherwise.
+	pub fn unreachable_pub(&self) -> Vec<ListItem> {
+		Reachability::new(&self.crate_data)
+			.unreachable_pub(&self.crate_data)
+			.into_iter()
+			.filter_map(|(id, path)| {
+				let entry = self.index.get(&id)?;
+				entry.is_under(&self.filter).then(|| ListItem {
+					kind: entry.kind,
+					path,
+					source: entry.source.clone(),
+				})
+			})
+			.collect()
+	}
+
+	/// Render the whole crate, or the part selected by the target's filter path, as Rust source
+	/// split into one file per module; see [`Renderer::render_tree`].
+	pub fn render_tree(&self) -> Result<Option<ModuleTree>> {
+		let renderer = Renderer::default()
+			.with_filter(&self.filter)
+			.with_auto_impls(self.auto_impls)
+			.with_impls(self.impls)
+			.with_doc_hidden(self.doc_hidden)
+			.with_tests(self.tests)
+			.with_private_items(self.include_private)
+			.with_format(RenderFormat::Rust)
+			.with_body_style(self.body_style);
+		Ok(renderer.render_tree(&self.crate_data)?)
+	}
+
+	/// Search the index and render the matched items.
+	///
+	/// Visibility is fixed when the crate is loaded, so [`SearchOptions::include_private`] and
+	/// [`SearchOptions::include_dependencies`] are ignored.
+	pub fn search(&self, options: &SearchOptions) -> Result<SearchResponse> {
+		let results = self.matches(options);
+		if results.is_empty() {
+			return Ok(SearchResponse {
+				results,
+				rendered: String::new(),
+			});
+		}
+		let rendered = self.render_results(&results, options.expand_containers)?;
+		Ok(SearchResponse { results, rendered })
+	}
+
+	/// Search the index, keeping only results at or below the filter path.
+	pub fn matches(&self, options: &SearchOptions) -> Vec<SearchResult> {
+		let mut results = self.index.search(options);
+		results.retain(|result| result.is_under(&self.filter));
+		results
+	}
+
+	/// List every indexed item at or below the filter path except `use` declarations.
+	pub fn list(&self) -> Vec<ListItem> {
+		self.index
+			.entries()
+			.iter()
+			.filter(|entry| entry.kind != SearchItemKind::Use && entry.is_under(&self.filter))
+			.map(ListItem::from)
+			.collect()
+	}
+
+	/// List the items `search` matches at or below the filter path, except `use` declarations.
+	pub fn list_matching(&self, search: &SearchOptions) -> Vec<ListItem> {
+		self.matches(search)
+			.iter()
+			.filter(|result| result.kind != SearchItemKind::Use)
+			.map(ListItem::from)
+			.collect()
+	}
+
+	/// Count the items at or below the filter path by kind, along with the largest modules and
+	/// how many items are documented or deprecated.
+	pub fn summary(&self) -> CrateSummary {
+		CrateSummary::from_index(&self.index, &self.filter)
+	}
+
+	/// Nest the listed items into a tree, keeping only what `search` matches, or what lies
+	/// below the filter path, and the items enclosing it.
+	pub fn list_tree(&self, search: Option<&SearchOptions>) -> Vec<TreeNode> {
+		let selected: Option<Vec<Id>> = match search {
+			Some(options) => Some(
+				self.matches(options)
+					.iter()
+					.map(|result| result.item_id)
+					.collect(),
+			),
+			None if self.filter.is_empty() => None,
+			None => Some(
+				self.index
+					.entries()
+					.iter()
+					.filter(|entry| entry.is_under(&self.filter))
+					.map(|entry| entry.item_id)
+					.collect(),
+			),
+		};
+		tree::build_tree(&self.index, selected.as_deref())
+	}
+
+	/// Render a skeleton containing only the items `ids` and what encloses them, without
+	/// expanding selected containers to their other children.
+	pub fn render_items(&self, ids: &[Id]) -> Result<String> {
+		let selection = RenderSelection::for_items(&self.crate_data, ids, false);
+		let renderer = Renderer::default()
+			.with_filter(&self.filter)
+			.with_auto_impls(self.auto_impls)
+			.with_impls(self.impls)
+			.with_doc_hidden(self.doc_hidden)
+			.with_tests(self.tests)
+			.with_private_items(self.include_private)
+			.with_format(self.render_format)
+			.with_budget(self.budget)
+			.with_body_style(self.body_style)
+			.with_selection(selection);
+		Ok(renderer.render(&self.crate_data)?)
+	}
+
+	/// Render a skeleton containing only `results` and their ancestors, expanding matched
+	/// containers.
+	pub fn render_selection(&self, results: &[SearchResult]) -> Result<String> {
+		self.render_results(results, true)
+	}
+
+	/// Pretty-printed rustdoc JSON reduced to the items a search or the filter path selects.
+	///
+	/// With `search`, the matched items are kept; otherwise the filter path picks the item, and
+	/// without a filter the whole crate is returned. See [`prune::prune_crate`] for what the
+	/// reduced crate contains.
+	pub fn selected_json(&self, search: Option<&SearchOptions>) -> Result<String> {
+		let (ids, expand): (Vec<_>, _) = match search {
+			Some(search) => (
+				self.index
+					.search(search)
+					.iter()
+					.map(|result| result.item_id)
+					.collect(),
+				search.expand_containers,
+			),
+			None if self.filter.is_empty() => {
+				return Ok(serde_json::to_string_pretty(&self.crate_data)?);
+			}
+			None => {
+				let entry = self
+					.index
+					.find_relative(&self.filter)
+					.ok_or_else(|| RenderError::FilterNotMatched(self.filter.clone()))?;
+				(vec![entry.item_id], true)
+			}
+		};
+		let selection = RenderSelection::for_items(&self.crate_data, &ids, expand);
+		Ok(serde_json::to_string_pretty(&prune::prune_crate(
+			&self.crate_data,
+			&selection,
+		))?)
+	}
+
+	fn render_results(&self, results: &[SearchResult], expand_containers: bool) -> Result<String> {
+		// A search that lands on exactly one type shows everything it implements.
+		let single_type = matches!(
+			results,
+			[result] if matches!(result.kind, SearchItemKind::Struct | SearchItemKind::Enum)
+		);
+		// A search that lands on exactly one trait lists the types implementing it.
+		let single_trait = matches!(results, [result] if result.kind == SearchItemKind::Trait);
+		let ids: Vec<_> = results.iter().map(|result| result.item_id).collect();
+		let selection = RenderSelection::for_items(&self.crate_data, &ids, expand_containers);
+		let renderer = Renderer::default()
+			.with_filter(&self.filter)
+			.with_auto_impls(self.auto_impls)
+			.with_impls(self.impls)
+			.with_blanket_impls(single_type)
+			.with_trait_implementors(single_trait)
+			.with_doc_hidden(self.doc_hidden)
+			.with_tests(self.tests)
+			.with_private_items(self.include_private)
+			.with_format(self.render_format)
+			.with_budget(self.budget)
+			.with_body_style(self.body_style)
+			.with_selection(selection);
+		Ok(renderer.render(&self.crate_data)?)
+	}
+}
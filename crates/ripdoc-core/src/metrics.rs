@@ -0,0 +1,80 @@
+//! Per-stage timings and sizes of a render.
+
+use std::fmt;
+use std::time::Duration;
+
+#[cfg(feature = "cargo-resolution")]
+use ripdoc_cargo::BuildMetrics;
+
+/// Where the time and bytes of one render went, stage by stage.
+///
+/// Stages that did not run, such as the download of a local package, stay zero. When a render
+/// reads the crate twice, as it does when retrying with private items, the build and parse
+/// measurements add up and the sizes describe the last read.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metrics {
+	/// Time spent resolving the target, excluding any registry download.
+	pub resolve: Duration,
+	/// Time spent downloading the package from a registry.
+	pub fetch: Duration,
+	/// Time spent running rustdoc.
+	pub build: Duration,
+	/// Size of the rustdoc JSON; zero when the crate came from the cache.
+	pub json_bytes: u64,
+	/// Time spent deserializing the rustdoc JSON or the cache entry.
+	pub parse: Duration,
+	/// Whether the crate was loaded from the rustdoc JSON cache.
+	pub cached: bool,
+	/// Number of items in the crate's index.
+	pub item_count: usize,
+	/// Time spent rendering, excluding formatting.
+	pub render: Duration,
+	/// Time spent formatting the rendered source.
+	pub format: Duration,
+	/// Size of the output, including its header.
+	pub output_bytes: u64,
+	/// Wall-clock time of the whole render, which is at least the sum of the stages.
+	pub total: Duration,
+}
+
+impl Metrics {
+	/// Timed stages in pipeline order, with their names.
+	pub fn stages(&self) -> [(&'static str, Duration); 6] {
+		[
+			("resolve", self.resolve),
+			("fetch", self.fetch),
+			("build", self.build),
+			("parse", self.parse),
+			("render", self.render),
+			("format", self.format),
+		]
+	}
+
+	/// Add the measurements of one rustdoc JSON read.
+	#[cfg(feature = "cargo-resolution")]
+	pub(crate) fn add_build(&mut self, build: &BuildMetrics) {
+		self.build += build.build;
+		self.parse += build.parse;
+		self.json_bytes = build.json_bytes;
+		self.cached = build.cached;
+	}
+}
+
+impl fmt::Display for Metrics {
+	/// A compact table with one stage per line, its time in milliseconds, and the sizes next
+	/// to the stages that produced them.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (name, time) in self.stages() {
+			let note = match name {
+				"build" if self.cached => "cached".to_string(),
+				"build" => format!("{} bytes of JSON", self.json_bytes),
+				"parse" => format!("{} items", self.item_count),
+				"format" => format!("{} bytes of output", self.output_bytes),
+				_ => String::new(),
+			};
+			let line = format!("{name:<8}{:>8} ms  {note}", time.as_millis());
+			writeln!(f, "{}", line.trim_end())?;
+		}
+		write!(f, "{:<8}{:>8} ms", "total", self.total.as_millis())
+	}
+}
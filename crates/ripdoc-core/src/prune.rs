@@ -0,0 +1,114 @@
+//! Rustdoc JSON reduced to the items a selection renders.
+
+use std::collections::{HashMap, HashSet};
+
+use ripdoc_render::RenderSelection;
+use rustdoc_types::{Crate, Id, ItemEnum, StructKind, VariantKind};
+use serde_json::Value;
+
+/// Reduce `crate_data` to the items in `selection`'s context, keeping the rustdoc JSON schema.
+///
+/// `index` holds the selected items with their ancestors and expanded children, and each kept
+/// item's child lists are trimmed to match. `paths` keeps the entries for those items and for
+/// every item they reference, such as the types in their signatures.
+pub fn prune_crate(crate_data: &Crate, selection: &RenderSelection) -> Crate {
+	let kept = selection.context();
+	let mut referenced = kept.clone();
+	let mut index = HashMap::new();
+	for (id, item) in &crate_data.index {
+		if !kept.contains(id) {
+			continue;
+		}
+		let mut item = item.clone();
+		retain_children(&mut item.inner, kept);
+		if let Ok(value) = serde_json::to_value(&item) {
+			collect_ids(&value, &mut referenced);
+		}
+		referenced.extend(item.links.values().copied());
+		index.insert(*id, item);
+	}
+
+	let paths = crate_data
+		.paths
+		.iter()
+		.filter(|(id, _)| referenced.contains(id))
+		.map(|(id, summary)| (*id, summary.clone()))
+		.collect();
+
+	Crate {
+		root: crate_data.root,
+		crate_version: crate_data.crate_version.clone(),
+		includes_private: crate_data.includes_private,
+		index,
+		paths,
+		external_crates: crate_data.external_crates.clone(),
+		target: crate_data.target.clone(),
+		format_version: crate_data.format_version,
+	}
+}
+
+/// Drop children of an item that are not in `kept`, so the reduced index has no dangling ids.
+fn retain_children(inner: &mut ItemEnum, kept: &HashSet<Id>) {
+	let keep = |id: &Id| kept.contains(id);
+	let retain_tuple = |fields: &mut Vec<Option<Id>>| {
+		for field in fields {
+			if field.is_some_and(|id| !kept.contains(&id)) {
+				*field = None;
+			}
+		}
+	};
+	match inner {
+		ItemEnum::Module(module) => module.items.retain(keep),
+		ItemEnum::Struct(struct_) => {
+			match &mut struct_.kind {
+				StructKind::Unit => {}
+				StructKind::Tuple(fields) => retain_tuple(fields),
+				StructKind::Plain { fields, .. } => fields.retain(keep),
+			}
+			struct_.impls.retain(keep);
+		}
+		ItemEnum::Union(union_) => {
+			union_.fields.retain(keep);
+			union_.impls.retain(keep);
+		}
+		ItemEnum::Enum(enum_) => {
+			enum_.variants.retain(keep);
+			enum_.impls.retain(keep);
+		}
+		ItemEnum::Variant(variant) => match &mut variant.kind {
+			VariantKind::Plain => {}
+			VariantKind::Tuple(fields) => retain_tuple(fields),
+			VariantKind::Struct { fields, .. } => fields.retain(keep),
+		},
+		ItemEnum::Trait(trait_) => {
+			trait_.items.retain(keep);
+			trait_.implementations.retain(keep);
+		}
+		ItemEnum::Impl(impl_) => impl_.items.retain(keep),
+		ItemEnum::Primitive(primitive) => primitive.impls.retain(keep),
+		_ => {}
+	}
+}
+
+/// Collect every `id` field in a serialized item, which covers resolved paths in types, trait
+/// references and `use` targets.
+fn collect_ids(value: &Value, ids: &mut HashSet<Id>) {
+	match value {
+		Value::Object(map) => {
+			for (key, value) in map {
+				if key == "id"
+					&& let Some(id) = value.as_u64().and_then(|id| u32::try_from(id).ok())
+				{
+					ids.insert(Id(id));
+				}
+				collect_ids(value, ids);
+			}
+		}
+		Value::Array(values) => {
+			for value in values {
+				collect_ids(value, ids);
+			}
+		}
+		_ => {}
+	}
+}
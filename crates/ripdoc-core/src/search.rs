@@ -1,14 +1,17 @@
 //! Internal search index implementation.
 #![allow(clippy::missing_docs_in_private_items)]
 
+use std::cmp::Reverse;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::path::{Path, PathBuf};
 
 use bitflags::bitflags;
+use ripdoc_render::impls::derive_attr_name;
 use ripdoc_render::{
-	RenderSelection, render_name, render_path, render_type, signatures as signature,
+	DERIVE_TRAITS, attr_text, render_name, render_path, render_type, signatures as signature,
 };
-use rustdoc_types::{Crate, Id, Item, ItemEnum, Module, Struct, StructKind, Visibility};
+use rustdoc_types::{Attribute, Crate, Id, Item, ItemEnum, Module, Struct, StructKind, Visibility};
 
 bitflags! {
 	/// Domains that a search query can operate over.
@@ -22,6 +25,14 @@ bitflags! {
 		const PATHS = 1 << 2;
 		/// Match against rendered item signatures.
 		const SIGNATURES = 1 << 3;
+		/// Match against attributes, including `#[derive(...)]` lists.
+		const ATTRS = 1 << 4;
+		/// Match against every domain.
+		const ALL = Self::NAMES.bits()
+			| Self::DOCS.bits()
+			| Self::PATHS.bits()
+			| Self::SIGNATURES.bits()
+			| Self::ATTRS.bits();
 	}
 }
 
@@ -31,6 +42,60 @@ impl Default for SearchDomain {
 	}
 }
 
+impl SearchDomain {
+	/// Parse a single domain name, accepting singular and plural forms and `all`.
+	pub fn parse_name(name: &str) -> Option<Self> {
+		let domain = match name.trim().to_ascii_lowercase().as_str() {
+			"name" | "names" => Self::NAMES,
+			"doc" | "docs" => Self::DOCS,
+			"path" | "paths" => Self::PATHS,
+			"signature" | "signatures" => Self::SIGNATURES,
+			"attr" | "attrs" => Self::ATTRS,
+			"all" => Self::ALL,
+			_ => return None,
+		};
+		Some(domain)
+	}
+
+	/// Parse a list of domain names into the union of their domains.
+	///
+	/// Fails on the first name that is not a known domain.
+	pub fn from_str_list(names: &[&str]) -> Result<Self, UnknownDomain> {
+		names.iter().try_fold(Self::empty(), |domains, name| {
+			Self::parse_name(name)
+				.map(|domain| domains | domain)
+				.ok_or_else(|| UnknownDomain(name.to_string()))
+		})
+	}
+}
+
+/// A search domain name that [`SearchDomain::from_str_list`] does not recognize.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownDomain(pub String);
+
+impl fmt::Display for UnknownDomain {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"unknown search domain `{}`, expected name, doc, path, signature, attrs, or all",
+			self.0
+		)
+	}
+}
+
+impl std::error::Error for UnknownDomain {}
+
+/// How a query is compared against item names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+	/// Names must contain the query.
+	#[default]
+	Substring,
+	/// Names must contain the query's characters in order, possibly with gaps, and results are
+	/// ranked by [`fuzzy_score`]. Other domains still match by substring.
+	Fuzzy,
+}
+
 /// Options that control how a crate search should be performed.
 #[derive(Debug, Clone)]
 pub struct SearchOptions {
@@ -40,10 +105,16 @@ pub struct SearchOptions {
 	pub domains: SearchDomain,
 	/// Whether matching should respect letter casing.
 	pub case_sensitive: bool,
+	/// How the query is compared against item names.
+	pub match_mode: MatchMode,
 	/// Whether to include private or crate-private items.
 	pub include_private: bool,
 	/// Whether matched container items should expand to include their children.
 	pub expand_containers: bool,
+	/// Whether the target's direct dependencies are searched as well.
+	///
+	/// Each dependency is built (or loaded from the cache) separately, so this is opt-in.
+	pub include_dependencies: bool,
 }
 
 impl SearchOptions {
@@ -53,8 +124,10 @@ impl SearchOptions {
 			query: query.into(),
 			domains: SearchDomain::default(),
 			case_sensitive: false,
+			match_mode: MatchMode::Substring,
 			include_private: false,
 			expand_containers: true,
+			include_dependencies: false,
 		}
 	}
 
@@ -67,7 +140,9 @@ impl SearchOptions {
 }
 
 /// Classified kind associated with a search result.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+///
+/// Kinds order as declared, which is how tree views sort the items of a level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum SearchItemKind {
 	/// Synthetic crate root module.
 	Crate,
@@ -156,6 +231,11 @@ pub struct SearchPathSegment {
 	pub kind: SearchItemKind,
 	/// Whether the segment corresponds to a publicly visible item.
 	pub is_public: bool,
+	/// Item the segment names, for navigating to it with [`SearchIndex::get`].
+	///
+	/// `None` for impl targets and traits defined outside the crate, and for impl targets that
+	/// are not plain paths.
+	pub id: Option<Id>,
 }
 
 /// Aggregated search response containing matches and rendered output.
@@ -189,37 +269,83 @@ pub struct ListItem {
 	pub source: Option<SourceLocation>,
 }
 
+impl From<&SearchResult> for ListItem {
+	fn from(result: &SearchResult) -> Self {
+		Self {
+			kind: result.kind,
+			path: result.path_string.clone(),
+			source: result.source.clone(),
+		}
+	}
+}
+
 /// Result of performing a query against a crate index.
 #[derive(Debug, Clone)]
 pub struct SearchResult {
+	/// Name of the crate the item belongs to.
+	pub crate_name: String,
 	/// Identifier of the matching item.
 	pub item_id: Id,
 	/// Kind of result item.
 	pub kind: SearchItemKind,
-	/// Canonical path segments to reach the item.
+	/// Canonical path segments to reach the item, from the crate root down to the item itself.
 	pub path: Vec<SearchPathSegment>,
-	/// Canonical path rendered as a `::` separated string.
+	/// Shortest public path to the item, following `pub use` re-exports of it or its ancestors,
+	/// rendered as a `::` separated string. Falls back to [`Self::definition_path`].
 	pub path_string: String,
+	/// Canonical path where the item is defined, rendered as a `::` separated string.
+	pub definition_path: String,
 	/// Raw identifier of the item.
 	pub raw_name: String,
 	/// Display name formatted for rendering.
 	pub display_name: String,
 	/// Documentation snippet if available.
 	pub docs: Option<String>,
+	/// Whether the item is marked `#[deprecated]`.
+	pub deprecated: bool,
 	/// Rendered signature used for matching and display.
 	pub signature: Option<String>,
+	/// Attributes rendered as source, one per line, with derives folded into `#[derive(...)]`.
+	pub attrs: Option<String>,
 	/// Source location for the item if available.
 	pub source: Option<SourceLocation>,
 	/// Ancestor chain of items that must be rendered for context.
 	pub ancestors: Vec<Id>,
 	/// Domains that produced a match (empty when stored in the index).
 	pub matched: SearchDomain,
+	/// How closely the name matched in [`MatchMode::Fuzzy`], higher is closer; `None` when the
+	/// name did not match fuzzily.
+	pub score: Option<i64>,
 }
 
 impl SearchResult {
 	/// Reset match metadata so the record can be reused for a new query.
 	fn clear_match_info(&mut self) {
 		self.matched = SearchDomain::empty();
+		self.score = None;
+	}
+
+	/// Path below the crate root, or `None` for the crate root itself.
+	fn relative_path(&self) -> Option<String> {
+		let below_root = self.path.get(1..).filter(|segments| !segments.is_empty())?;
+		Some(join_path(below_root))
+	}
+
+	/// Whether the item is the one at `filter`, a path relative to the crate root, or lies
+	/// below it. Everything is under an empty filter.
+	pub fn is_under(&self, filter: &str) -> bool {
+		if filter.is_empty() {
+			return true;
+		}
+		self.relative_path().is_some_and(|path| {
+			path.strip_prefix(filter)
+				.is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+		})
+	}
+
+	/// Whether any segment of the path, including the item itself, is private.
+	pub fn is_private(&self) -> bool {
+		self.path.iter().any(|segment| !segment.is_public)
 	}
 }
 
@@ -270,13 +396,20 @@ impl SearchIndex {
 			trimmed.to_lowercase()
 		};
 
+		let fuzzy = opts.match_mode == MatchMode::Fuzzy;
 		let mut results = Vec::new();
 		for entry in &self.entries {
 			let mut matched = SearchDomain::empty();
-			if opts.domains.contains(SearchDomain::NAMES)
-				&& contains(&entry.raw_name, &normalized_query, opts.case_sensitive)
-			{
-				matched |= SearchDomain::NAMES;
+			let mut score = None;
+			if opts.domains.contains(SearchDomain::NAMES) {
+				if fuzzy {
+					score = fuzzy_score(&entry.raw_name, trimmed, opts.case_sensitive);
+				}
+				if score.is_some()
+					|| (!fuzzy && contains(&entry.raw_name, &normalized_query, opts.case_sensitive))
+				{
+					matched |= SearchDomain::NAMES;
+				}
 			}
 			if opts.domains.contains(SearchDomain::DOCS)
 				&& entry
@@ -287,8 +420,12 @@ impl SearchIndex {
 				matched |= SearchDomain::DOCS;
 			}
 			if opts.domains.contains(SearchDomain::PATHS)
-				&& contains(&entry.path_string, &normalized_query, opts.case_sensitive)
-			{
+				&& (contains(&entry.path_string, &normalized_query, opts.case_sensitive)
+					|| contains(
+						&entry.definition_path,
+						&normalized_query,
+						opts.case_sensitive,
+					)) {
 				matched |= SearchDomain::PATHS;
 			}
 			if opts.domains.contains(SearchDomain::SIGNATURES)
@@ -299,21 +436,180 @@ impl SearchIndex {
 			{
 				matched |= SearchDomain::SIGNATURES;
 			}
+			if opts.domains.contains(SearchDomain::ATTRS)
+				&& entry
+					.attrs
+					.as_ref()
+					.is_some_and(|attrs| contains(attrs, &normalized_query, opts.case_sensitive))
+			{
+				matched |= SearchDomain::ATTRS;
+			}
 
 			if !matched.is_empty() {
 				let mut clone = entry.clone();
 				clone.matched = matched;
+				clone.score = score;
 				results.push(clone);
 			}
 		}
 
+		if fuzzy {
+			// Stable, so equal scores and results matched only in other domains keep index order.
+			results.sort_by_key(|result| Reverse(result.score));
+		}
 		results
 	}
+
+	/// Find the entry whose path below the crate root is exactly `path`, e.g. `module::Item`.
+	pub fn find_relative(&self, path: &str) -> Option<&SearchResult> {
+		self.entries
+			.iter()
+			.find(|entry| entry.relative_path().as_deref() == Some(path))
+	}
+
+	/// Paths below the crate root that nearly match `path`, closest first.
+	///
+	/// A candidate must have the same number of segments as `path` and lie within
+	/// [`MAX_SUGGESTION_DISTANCE`] edits of it, ignoring case; this catches differently-cased
+	/// paths and small typos in a single segment.
+	pub fn suggest_relative(&self, path: &str, limit: usize) -> Vec<String> {
+		let wanted = path.to_lowercase();
+		let segments = path.split("::").count();
+		let mut candidates: Vec<(usize, String)> = self
+			.entries
+			.iter()
+			.filter_map(|entry| entry.relative_path())
+			.filter(|candidate| candidate != path && candidate.split("::").count() == segments)
+			.filter_map(|candidate| {
+				let distance = edit_distance(&candidate.to_lowercase(), &wanted);
+				(distance <= MAX_SUGGESTION_DISTANCE).then_some((distance, candidate))
+			})
+			.collect();
+		candidates.sort();
+		candidates.dedup_by(|a, b| a.1 == b.1);
+		candidates
+			.into_iter()
+			.take(limit)
+			.map(|(_, candidate)| candidate)
+			.collect()
+	}
+}
+
+/// Score for each query character matched by [`fuzzy_score`].
+const FUZZY_MATCH: i64 = 16;
+/// Bonus for a match at the start of a word: the start of the name, after a separator such as
+/// `_`, or an uppercase letter after a lowercase one. Doubled for the first query character.
+const FUZZY_BOUNDARY: i64 = 8;
+/// Minimum bonus for a match directly after the previous one.
+const FUZZY_CONSECUTIVE: i64 = 8;
+/// Penalty for the first character skipped between two matches.
+const FUZZY_GAP_START: i64 = 3;
+/// Penalty for each further character skipped between two matches.
+const FUZZY_GAP_EXTEND: i64 = 1;
+/// Bonus for a name equal to the query, so exact matches outrank every partial one.
+const FUZZY_EXACT: i64 = FUZZY_MATCH;
+
+/// Score `name` against `query` as a skim-style fuzzy match, or `None` if it does not match.
+///
+/// The query's characters must appear in `name` in order. Each alignment is scored
+/// Smith-Waterman style: matched characters earn points, with bonuses at word boundaries and
+/// for runs of consecutive matches, and skipped characters between matches cost a gap penalty.
+/// The best alignment counts, and matches scoring under half the points of the matched
+/// characters alone are rejected as too scattered. Underscores in the query are ignored, so
+/// `snake_case` queries find `CamelCase` names.
+pub fn fuzzy_score(name: &str, query: &str, case_sensitive: bool) -> Option<i64> {
+	let fold = |c: char| {
+		if case_sensitive {
+			c
+		} else {
+			c.to_lowercase().next().unwrap_or(c)
+		}
+	};
+	let original: Vec<char> = name.chars().collect();
+	let candidate: Vec<char> = original.iter().copied().map(fold).collect();
+	let pattern: Vec<char> = query.chars().filter(|c| *c != '_').map(fold).collect();
+	if pattern.is_empty() || pattern.len() > candidate.len() {
+		return None;
+	}
+	let bonus: Vec<i64> = (0..original.len())
+		.map(|index| boundary_bonus(&original, index))
+		.collect();
+
+	// Best score of the pattern so far with its last character matched at each position.
+	let mut best: Vec<Option<i64>> = candidate
+		.iter()
+		.zip(&bonus)
+		.map(|(&c, &bonus)| (c == pattern[0]).then_some(FUZZY_MATCH + 2 * bonus))
+		.collect();
+	for &wanted in &pattern[1..] {
+		let mut next = vec![None; candidate.len()];
+		// Best score with the previous character matched two or more positions back, less the
+		// penalty for the gap up to the current position.
+		let mut gapped: Option<i64> = None;
+		for index in 1..candidate.len() {
+			let previous = best[index - 1];
+			if candidate[index] == wanted {
+				let consecutive =
+					previous.map(|score| score + FUZZY_MATCH + bonus[index].max(FUZZY_CONSECUTIVE));
+				let after_gap = gapped.map(|score| score + FUZZY_MATCH + bonus[index]);
+				next[index] = consecutive.max(after_gap);
+			}
+			gapped = gapped
+				.map(|score| score - FUZZY_GAP_EXTEND)
+				.max(previous.map(|score| score - FUZZY_GAP_START));
+		}
+		best = next;
+	}
+
+	let mut score = best.into_iter().flatten().max()?;
+	if score * 2 < pattern.len() as i64 * FUZZY_MATCH {
+		return None;
+	}
+	let exact = if case_sensitive {
+		name == query
+	} else {
+		name.to_lowercase() == query.to_lowercase()
+	};
+	if exact {
+		score += FUZZY_EXACT;
+	}
+	Some(score)
+}
+
+/// Word-boundary bonus for a match at `index` in `name`.
+fn boundary_bonus(name: &[char], index: usize) -> i64 {
+	let Some(&previous) = index.checked_sub(1).and_then(|previous| name.get(previous)) else {
+		return FUZZY_BOUNDARY;
+	};
+	let current = name[index];
+	if !previous.is_alphanumeric() || (previous.is_lowercase() && current.is_uppercase()) {
+		FUZZY_BOUNDARY
+	} else {
+		0
+	}
+}
+
+/// Largest edit distance at which [`SearchIndex::suggest_relative`] still suggests a path.
+pub const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut previous: Vec<usize> = (0..=b.len()).collect();
+	let mut current = vec![0; b.len() + 1];
+	for (i, a_char) in a.chars().enumerate() {
+		current[0] = i + 1;
+		for (j, b_char) in b.iter().enumerate() {
+			let substitution = previous[j] + usize::from(a_char != *b_char);
+			current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+		}
+		std::mem::swap(&mut previous, &mut current);
+	}
+	previous[b.len()]
 }
 
 #[derive(Clone)]
 struct PathStackEntry {
-	id: Option<Id>,
 	segment: SearchPathSegment,
 }
 
@@ -324,12 +620,16 @@ struct ImplContext {
 
 struct IndexBuilder<'a> {
 	crate_data: &'a Crate,
+	crate_name: String,
 	include_private: bool,
 	source_root: Option<PathBuf>,
 	source_prefix: Option<String>,
 	stack: Vec<PathStackEntry>,
 	entries: Vec<SearchResult>,
 	visited: HashSet<Id>,
+	derive_traits: Vec<String>,
+	/// Public paths that `pub use` items re-export each target under.
+	aliases: HashMap<Id, Vec<String>>,
 }
 
 impl<'a> IndexBuilder<'a> {
@@ -356,12 +656,15 @@ impl<'a> IndexBuilder<'a> {
 		};
 		Self {
 			crate_data,
+			crate_name: crate_name.unwrap_or_default(),
 			include_private,
 			source_root: source_root.map(PathBuf::from),
 			source_prefix,
 			stack: Vec::new(),
 			entries: Vec::new(),
 			visited: HashSet::new(),
+			derive_traits: DERIVE_TRAITS.iter().map(ToString::to_string).collect(),
+			aliases: HashMap::new(),
 		}
 	}
 
@@ -371,7 +674,8 @@ impl<'a> IndexBuilder<'a> {
 		}
 	}
 
-	fn finish(self) -> SearchIndex {
+	fn finish(mut self) -> SearchIndex {
+		self.apply_public_paths();
 		let entries: Vec<SearchResult> = self.entries;
 		let mut id_to_entry = HashMap::with_capacity(entries.len());
 		for (idx, entry) in entries.iter().enumerate() {
@@ -383,14 +687,72 @@ impl<'a> IndexBuilder<'a> {
 		}
 	}
 
+	/// Point each entry's `path_string` at its shortest public path.
+	///
+	/// Candidates are the definition path, when every segment is public, and the re-exports of
+	/// the item or of any ancestor followed by the public remainder of the definition path. The
+	/// shortest wins, ties broken lexicographically.
+	fn apply_public_paths(&mut self) {
+		let segment_counts: HashMap<Id, usize> = self
+			.entries
+			.iter()
+			.map(|entry| (entry.item_id, entry.path.len()))
+			.collect();
+		for entry in &mut self.entries {
+			let mut best = (!entry.is_private()).then(|| entry.definition_path.clone());
+			for id in entry.ancestors.iter().chain([&entry.item_id]) {
+				let (Some(aliases), Some(&depth)) = (self.aliases.get(id), segment_counts.get(id))
+				else {
+					continue;
+				};
+				let Some(rest) = entry.path.get(depth..) else {
+					continue;
+				};
+				if rest.iter().any(|segment| !segment.is_public) {
+					continue;
+				}
+				for alias in aliases {
+					let mut candidate = alias.clone();
+					if !rest.is_empty() {
+						candidate.push_str("::");
+						candidate.push_str(&join_path(rest));
+					}
+					if best
+						.as_ref()
+						.is_none_or(|best| (candidate.len(), &candidate) < (best.len(), best))
+					{
+						best = Some(candidate);
+					}
+				}
+			}
+			if let Some(best) = best {
+				entry.path_string = best;
+			}
+		}
+	}
+
+	/// Remember the path a public, non-glob `use` item re-exports its target under.
+	fn record_alias(&mut self, item: &Item, use_: &rustdoc_types::Use) {
+		let public = matches!(item.visibility, Visibility::Public)
+			&& self.stack.iter().all(|entry| entry.segment.is_public);
+		let Some(target) = use_.id.filter(|_| public && !use_.is_glob) else {
+			return;
+		};
+		let mut path: Vec<&str> = self
+			.stack
+			.iter()
+			.map(|entry| entry.segment.name.as_str())
+			.collect();
+		path.push(&use_.name);
+		let path = path.join("::");
+		self.aliases.entry(target).or_default().push(path);
+	}
+
 	fn visit_root(&mut self, item: &Item) {
 		if let ItemEnum::Module(module) = &item.inner {
 			let segment = self.make_segment(item, SearchItemKind::Crate, Some("crate"));
 			self.record_item(item, SearchItemKind::Crate, &segment, true, &[]);
-			self.stack.push(PathStackEntry {
-				id: Some(item.id),
-				segment,
-			});
+			self.stack.push(PathStackEntry { segment });
 			self.visit_module_items(module);
 			self.stack.pop();
 		}
@@ -416,8 +778,11 @@ impl<'a> IndexBuilder<'a> {
 			ItemEnum::Macro(_) => self.record_simple(item, SearchItemKind::Macro),
 			ItemEnum::ProcMacro(_) => self.record_simple(item, SearchItemKind::ProcMacro),
 			ItemEnum::TraitAlias(_) => self.record_simple(item, SearchItemKind::TraitAlias),
-			ItemEnum::Use(_) => self.record_simple(item, SearchItemKind::Use),
-			ItemEnum::Primitive(_) => self.record_simple(item, SearchItemKind::Primitive),
+			ItemEnum::Use(use_) => {
+				self.record_alias(item, use_);
+				self.record_simple(item, SearchItemKind::Use);
+			}
+			ItemEnum::Primitive(primitive) => self.visit_primitive(item, primitive),
 			ItemEnum::Variant(variant) => self.visit_variant(item, variant),
 			ItemEnum::StructField(_) => self.record_simple(item, SearchItemKind::Field),
 			ItemEnum::AssocConst { .. } => self.record_simple(item, SearchItemKind::AssocConst),
@@ -430,10 +795,7 @@ impl<'a> IndexBuilder<'a> {
 	fn visit_module(&mut self, item: &Item, module: &Module) {
 		let segment = self.make_segment(item, SearchItemKind::Module, None);
 		let _ = self.record_item(item, SearchItemKind::Module, &segment, module.is_crate, &[]);
-		self.stack.push(PathStackEntry {
-			id: Some(item.id),
-			segment,
-		});
+		self.stack.push(PathStackEntry { segment });
 		self.visit_module_items(module);
 		self.stack.pop();
 	}
@@ -447,10 +809,7 @@ impl<'a> IndexBuilder<'a> {
 	fn visit_struct(&mut self, item: &Item, struct_: &Struct) {
 		let segment = self.make_segment(item, SearchItemKind::Struct, None);
 		let include_children = self.record_item(item, SearchItemKind::Struct, &segment, false, &[]);
-		self.stack.push(PathStackEntry {
-			id: Some(item.id),
-			segment,
-		});
+		self.stack.push(PathStackEntry { segment });
 		if include_children {
 			match &struct_.kind {
 				StructKind::Unit => {}
@@ -475,10 +834,7 @@ impl<'a> IndexBuilder<'a> {
 	fn visit_union(&mut self, item: &Item, union_: &rustdoc_types::Union) {
 		let segment = self.make_segment(item, SearchItemKind::Union, None);
 		let include_children = self.record_item(item, SearchItemKind::Union, &segment, false, &[]);
-		self.stack.push(PathStackEntry {
-			id: Some(item.id),
-			segment,
-		});
+		self.stack.push(PathStackEntry { segment });
 		if include_children {
 			for field in &union_.fields {
 				self.visit_item(field);
@@ -490,13 +846,20 @@ impl<'a> IndexBuilder<'a> {
 		self.stack.pop();
 	}
 
+	fn visit_primitive(&mut self, item: &Item, primitive: &rustdoc_types::Primitive) {
+		let segment = self.make_segment(item, SearchItemKind::Primitive, None);
+		let _ = self.record_item(item, SearchItemKind::Primitive, &segment, false, &[]);
+		self.stack.push(PathStackEntry { segment });
+		for impl_id in &primitive.impls {
+			self.visit_item(impl_id);
+		}
+		self.stack.pop();
+	}
+
 	fn visit_enum(&mut self, item: &Item, enum_: &rustdoc_types::Enum) {
 		let segment = self.make_segment(item, SearchItemKind::Enum, None);
 		let include_children = self.record_item(item, SearchItemKind::Enum, &segment, false, &[]);
-		self.stack.push(PathStackEntry {
-			id: Some(item.id),
-			segment,
-		});
+		self.stack.push(PathStackEntry { segment });
 		if include_children {
 			for variant_id in &enum_.variants {
 				self.visit_item(variant_id);
@@ -512,10 +875,7 @@ impl<'a> IndexBuilder<'a> {
 		let segment = self.make_segment(item, SearchItemKind::EnumVariant, None);
 		let include_children =
 			self.record_item(item, SearchItemKind::EnumVariant, &segment, false, &[]);
-		self.stack.push(PathStackEntry {
-			id: Some(item.id),
-			segment,
-		});
+		self.stack.push(PathStackEntry { segment });
 		if include_children {
 			match &variant.kind {
 				rustdoc_types::VariantKind::Plain => {}
@@ -537,10 +897,7 @@ impl<'a> IndexBuilder<'a> {
 	fn visit_trait(&mut self, item: &Item, trait_: &rustdoc_types::Trait) {
 		let segment = self.make_segment(item, SearchItemKind::Trait, None);
 		let include_children = self.record_item(item, SearchItemKind::Trait, &segment, false, &[]);
-		self.stack.push(PathStackEntry {
-			id: Some(item.id),
-			segment,
-		});
+		self.stack.push(PathStackEntry { segment });
 		if include_children {
 			for assoc_id in &trait_.items {
 				if let Some(assoc) = self.crate_data.index.get(assoc_id) {
@@ -619,15 +976,21 @@ impl<'a> IndexBuilder<'a> {
 
 		if let Some(target_entry) = self.impl_target_entry(&impl_.for_) {
 			let has_target = target_entry
+				.segment
 				.id
 				.and_then(|id| {
 					self.stack
 						.iter()
-						.find(|entry| entry.id == Some(id))
+						.find(|entry| entry.segment.id == Some(id))
 						.map(|_| ())
 				})
 				.is_some();
-			if !has_target {
+			// A primitive's impls are visited from the primitive, already on the stack.
+			let under_primitive = self.stack.last().is_some_and(|entry| {
+				entry.segment.kind == SearchItemKind::Primitive
+					&& entry.segment.name == target_entry.segment.name
+			});
+			if !has_target && !under_primitive {
 				self.stack.push(target_entry.clone());
 				ctx.pushed.push(target_entry);
 			}
@@ -664,12 +1027,12 @@ impl<'a> IndexBuilder<'a> {
 					(None, SearchItemKind::Trait, true)
 				};
 			PathStackEntry {
-				id,
 				segment: SearchPathSegment {
 					name: display.clone(),
 					display_name: display,
 					kind,
 					is_public,
+					id,
 				},
 			}
 		})
@@ -685,22 +1048,22 @@ impl<'a> IndexBuilder<'a> {
 						.unwrap_or(SearchItemKind::ImplTarget);
 					let segment = self.make_segment(item, kind, None);
 					Some(PathStackEntry {
-						id: Some(item.id),
 						segment: SearchPathSegment {
 							name: name.clone(),
 							display_name: name,
 							kind: SearchItemKind::ImplTarget,
 							is_public: segment.is_public,
+							id: Some(item.id),
 						},
 					})
 				} else {
 					Some(PathStackEntry {
-						id: None,
 						segment: SearchPathSegment {
 							name: name.clone(),
 							display_name: name,
 							kind: SearchItemKind::ImplTarget,
 							is_public: true,
+							id: None,
 						},
 					})
 				}
@@ -708,12 +1071,12 @@ impl<'a> IndexBuilder<'a> {
 			_ => {
 				let name = render_type(ty);
 				Some(PathStackEntry {
-					id: None,
 					segment: SearchPathSegment {
 						name: name.clone(),
 						display_name: name,
 						kind: SearchItemKind::ImplTarget,
 						is_public: true,
+						id: None,
 					},
 				})
 			}
@@ -768,6 +1131,7 @@ impl<'a> IndexBuilder<'a> {
 			display_name,
 			kind,
 			is_public: matches!(item.visibility, Visibility::Public | Visibility::Default),
+			id: Some(item.id),
 		}
 	}
 
@@ -790,24 +1154,34 @@ impl<'a> IndexBuilder<'a> {
 			.collect();
 		path.push(segment.clone());
 
-		let mut ancestors: Vec<Id> = self.stack.iter().filter_map(|entry| entry.id).collect();
+		let mut ancestors: Vec<Id> = self
+			.stack
+			.iter()
+			.filter_map(|entry| entry.segment.id)
+			.collect();
 		ancestors.extend(extra_ancestors.iter().copied());
 
-		let path_string = join_path(&path);
+		let definition_path = join_path(&path);
 		let source = self.resolve_source(item);
 		let signature = self.signature_for(item, kind);
+		let attrs = self.attrs_for(item);
 		let result = SearchResult {
+			crate_name: self.crate_name.clone(),
 			item_id: item.id,
 			kind,
 			path,
-			path_string,
+			path_string: definition_path.clone(),
+			definition_path,
 			raw_name: segment.name.clone(),
 			display_name: segment.display_name.clone(),
 			docs: item.docs.clone(),
+			deprecated: item.deprecation.is_some(),
 			signature,
+			attrs,
 			source,
 			ancestors,
 			matched: SearchDomain::empty(),
+			score: None,
 		};
 
 		self.entries.push(result);
@@ -818,10 +1192,10 @@ impl<'a> IndexBuilder<'a> {
 		let span = item.span.as_ref()?;
 		let mut path = span.filename.clone();
 
-		if let Some(root) = &self.source_root {
-			if path.is_relative() {
-				path = root.join(path);
-			}
+		if let Some(root) = &self.source_root
+			&& path.is_relative()
+		{
+			path = root.join(path);
 		}
 
 		let absolute_path = match path.canonicalize() {
@@ -942,6 +1316,33 @@ impl<'a> IndexBuilder<'a> {
 		}
 	}
 
+	/// Attribute text for an item; structs, enums, and unions also list their derived traits the
+	/// way the skeleton renders them, so the match lands on the type rather than its impls.
+	fn attrs_for(&self, item: &Item) -> Option<String> {
+		let mut lines: Vec<String> = item
+			.attrs
+			.iter()
+			.filter(|attr| !matches!(attr, Attribute::AutomaticallyDerived))
+			.map(attr_text)
+			.collect();
+		let impls: &[Id] = match &item.inner {
+			ItemEnum::Struct(struct_) => &struct_.impls,
+			ItemEnum::Enum(enum_) => &enum_.impls,
+			ItemEnum::Union(union_) => &union_.impls,
+			_ => &[],
+		};
+		let derives: Vec<&str> = impls
+			.iter()
+			.filter_map(|id| self.crate_data.index.get(id))
+			.filter(|impl_item| matches!(impl_item.inner, ItemEnum::Impl(_)))
+			.filter_map(|impl_item| derive_attr_name(impl_item, &self.derive_traits))
+			.collect();
+		if !derives.is_empty() {
+			lines.push(format!("#[derive({})]", derives.join(", ")));
+		}
+		(!lines.is_empty()).then(|| lines.join("\n"))
+	}
+
 	fn should_include(&self, item: &Item) -> bool {
 		if self.include_private {
 			return true;
@@ -972,58 +1373,6 @@ fn contains(haystack: &str, needle: &str, case_sensitive: bool) -> bool {
 	}
 }
 
-/// Build a renderer selection set covering matches, their ancestors, and optionally their children.
-pub fn build_render_selection(
-	index: &SearchIndex,
-	results: &[SearchResult],
-	expand_containers: bool,
-) -> RenderSelection {
-	let mut matches = HashSet::new();
-	let mut context = HashSet::new();
-	let mut expanded = HashSet::new();
-	for result in results {
-		matches.insert(result.item_id);
-		context.insert(result.item_id);
-		context.extend(result.ancestors.iter().copied());
-	}
-	if expand_containers {
-		let containers: HashSet<Id> = results
-			.iter()
-			.filter(|result| {
-				matches!(
-					result.kind,
-					SearchItemKind::Crate
-						| SearchItemKind::Module
-						| SearchItemKind::Struct
-						| SearchItemKind::Trait
-				)
-			})
-			.map(|result| result.item_id)
-			.collect();
-
-		if !containers.is_empty() {
-			expanded.extend(containers.iter().copied());
-			let mut descendant_containers = HashSet::new();
-			for entry in index.entries() {
-				if let Some(pos) = entry
-					.ancestors
-					.iter()
-					.position(|ancestor| containers.contains(ancestor))
-				{
-					context.insert(entry.item_id);
-					for descendant in entry.ancestors.iter().skip(pos + 1) {
-						context.insert(*descendant);
-						descendant_containers.insert(*descendant);
-					}
-				}
-			}
-			expanded.extend(descendant_containers);
-		}
-	}
-
-	RenderSelection::new(matches, context, expanded)
-}
-
 /// Format the set of matched domains into human-friendly labels.
 pub fn describe_domains(domains: SearchDomain) -> Vec<&'static str> {
 	let mut labels = Vec::new();
@@ -1039,6 +1388,9 @@ pub fn describe_domains(domains: SearchDomain) -> Vec<&'static str> {
 	if domains.contains(SearchDomain::SIGNATURES) {
 		labels.push("signature");
 	}
+	if domains.contains(SearchDomain::ATTRS) {
+		labels.push("attrs");
+	}
 	labels
 }
 
@@ -0,0 +1,174 @@
+//! Crate statistics gathered from the search index.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::search::{SearchIndex, SearchItemKind, SearchResult};
+
+/// Most modules listed in [`CrateSummary::largest_modules`].
+const LARGEST_MODULES: usize = 5;
+
+/// Shape of a crate: how many items of each kind it declares, which modules hold the most, and
+/// how many items are documented or deprecated.
+///
+/// Counts cover the items the index holds, so private items only count when they were
+/// documented and indexed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CrateSummary {
+	/// Name of the crate.
+	pub crate_name: String,
+	/// Modules, not counting the crate root.
+	pub modules: usize,
+	/// Structs.
+	pub structs: usize,
+	/// Enums.
+	pub enums: usize,
+	/// Unions.
+	pub unions: usize,
+	/// Traits and trait aliases.
+	pub traits: usize,
+	/// Free functions.
+	pub functions: usize,
+	/// Methods in impl blocks and trait method declarations.
+	pub methods: usize,
+	/// `macro_rules!` and procedural macros.
+	pub macros: usize,
+	/// Type aliases, including associated types.
+	pub type_aliases: usize,
+	/// Constants and statics, including associated constants.
+	pub constants: usize,
+	/// Items that can carry docs: everything counted above along with fields and variants.
+	pub items: usize,
+	/// Items among [`Self::items`] with doc comments.
+	pub documented: usize,
+	/// Items among [`Self::items`] marked `#[deprecated]`.
+	pub deprecated: usize,
+	/// Modules declaring the most items directly, largest first.
+	pub largest_modules: Vec<ModuleSize>,
+}
+
+/// A module and the number of items declared directly in it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModuleSize {
+	/// Canonical path of the module, starting with the crate name.
+	pub path: String,
+	/// Items declared directly in the module, including its submodules but not their contents.
+	pub items: usize,
+}
+
+impl CrateSummary {
+	/// Summarize the entries of `index` at or below `filter`, a path relative to the crate root,
+	/// in one pass over them.
+	pub fn from_index(index: &SearchIndex, filter: &str) -> Self {
+		let mut summary = Self::default();
+		let mut module_sizes: HashMap<String, usize> = HashMap::new();
+		for entry in index.entries() {
+			if summary.crate_name.is_empty() {
+				summary.crate_name = entry.crate_name.clone();
+			}
+			if !entry.is_under(filter) {
+				continue;
+			}
+			let counter = match entry.kind {
+				SearchItemKind::Module => Some(&mut summary.modules),
+				SearchItemKind::Struct => Some(&mut summary.structs),
+				SearchItemKind::Enum => Some(&mut summary.enums),
+				SearchItemKind::Union => Some(&mut summary.unions),
+				SearchItemKind::Trait | SearchItemKind::TraitAlias => Some(&mut summary.traits),
+				SearchItemKind::Function => Some(&mut summary.functions),
+				SearchItemKind::Method | SearchItemKind::TraitMethod => Some(&mut summary.methods),
+				SearchItemKind::Macro | SearchItemKind::ProcMacro => Some(&mut summary.macros),
+				SearchItemKind::TypeAlias | SearchItemKind::AssocType => {
+					Some(&mut summary.type_aliases)
+				}
+				SearchItemKind::Constant | SearchItemKind::Static | SearchItemKind::AssocConst => {
+					Some(&mut summary.constants)
+				}
+				SearchItemKind::EnumVariant | SearchItemKind::Field => None,
+				// The root, imports, primitives, and impl targets are not items the crate declares
+				SearchItemKind::Crate
+				| SearchItemKind::Use
+				| SearchItemKind::Primitive
+				| SearchItemKind::ImplTarget => continue,
+			};
+			if let Some(counter) = counter {
+				*counter += 1;
+			}
+			summary.items += 1;
+			if entry
+				.docs
+				.as_deref()
+				.is_some_and(|docs| !docs.trim().is_empty())
+			{
+				summary.documented += 1;
+			}
+			if entry.deprecated {
+				summary.deprecated += 1;
+			}
+			if let Some(module) = declaring_module(entry) {
+				*module_sizes.entry(module).or_default() += 1;
+			}
+		}
+
+		let mut largest: Vec<ModuleSize> = module_sizes
+			.into_iter()
+			.map(|(path, items)| ModuleSize { path, items })
+			.collect();
+		largest.sort_by(|a, b| b.items.cmp(&a.items).then_with(|| a.path.cmp(&b.path)));
+		largest.truncate(LARGEST_MODULES);
+		summary.largest_modules = largest;
+		summary
+	}
+}
+
+/// Path of the module `entry` is declared directly in, or `None` for members of types, traits,
+/// and impl blocks.
+fn declaring_module(entry: &SearchResult) -> Option<String> {
+	let (_, parents) = entry.path.split_last()?;
+	let parent = parents.last()?;
+	if !matches!(parent.kind, SearchItemKind::Crate | SearchItemKind::Module) {
+		return None;
+	}
+	let segments: Vec<&str> = parents
+		.iter()
+		.map(|segment| segment.name.as_str())
+		.collect();
+	Some(segments.join("::"))
+}
+
+impl fmt::Display for CrateSummary {
+	/// A compact table with one count per line, followed by the largest modules.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "{:<14}{}", "crate", self.crate_name)?;
+		let counts = [
+			("modules", self.modules),
+			("structs", self.structs),
+			("enums", self.enums),
+			("unions", self.unions),
+			("traits", self.traits),
+			("functions", self.functions),
+			("methods", self.methods),
+			("macros", self.macros),
+			("type aliases", self.type_aliases),
+			("constants", self.constants),
+		];
+		for (label, count) in counts {
+			writeln!(f, "{label:<14}{count:>6}")?;
+		}
+		writeln!(
+			f,
+			"{:<14}{:>6} of {} items",
+			"documented", self.documented, self.items
+		)?;
+		write!(f, "{:<14}{:>6}", "deprecated", self.deprecated)?;
+		if !self.largest_modules.is_empty() {
+			write!(f, "\n\nlargest modules:")?;
+			for module in &self.largest_modules {
+				write!(f, "\n{:>8}  {}", module.items, module.path)?;
+			}
+		}
+		Ok(())
+	}
+}
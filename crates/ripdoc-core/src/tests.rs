@@ -1,11 +1,28 @@
 use std::collections::HashMap;
+#[cfg(feature = "cargo-resolution")]
+use std::fs;
 
+use ripdoc_render::{
+	FormatterChoice, MismatchedItem, Reachability, RenderFormat, RenderSelection, Renderer,
+	SkippedKind, is_doc_hidden,
+};
+#[cfg(feature = "cargo-resolution")]
+use rustdoc_types::ItemKind;
 use rustdoc_types::{
-	Abi, Crate, Function, FunctionHeader, FunctionSignature, Generics, Id, Impl, Item, ItemEnum,
-	Module, Path, Struct, StructKind, Target, Trait, Type, Visibility,
+	Abi, AssocItemConstraint, AssocItemConstraintKind, Attribute, Constant, Crate, Deprecation,
+	DynTrait, Function, FunctionHeader, FunctionSignature, GenericArg, GenericArgs, GenericBound,
+	GenericParamDef, GenericParamDefKind, Generics, Id, Impl, Item, ItemEnum, ItemSummary, Module,
+	Path, PolyTrait, Primitive, Span, Static, Struct, StructKind, Target, Term, Trait, Type, Use,
+	Visibility, WherePredicate,
 };
+#[cfg(feature = "cargo-resolution")]
+use tempfile::TempDir;
 
+use crate::LoadedCrate;
 use crate::search::*;
+use crate::summary::{CrateSummary, ModuleSize};
+#[cfg(feature = "cargo-resolution")]
+use crate::{LoadOptions, Ripdoc, TreeNode};
 
 /// Create an empty Generics instance for testing.
 pub fn empty_generics() -> Generics {
@@ -34,6 +51,7 @@ fn fixture_crate() -> Crate {
 	let helper_fn = Id(5);
 	let paintable_trait = Id(6);
 	let paint_method = Id(7);
+	let paintable_impl = Id(8);
 
 	let mut index = HashMap::new();
 
@@ -51,7 +69,13 @@ fn fixture_crate() -> Crate {
 			deprecation: None,
 			inner: ItemEnum::Module(Module {
 				is_crate: true,
-				items: vec![widget, helper_fn, paintable_trait, widget_impl],
+				items: vec![
+					widget,
+					helper_fn,
+					paintable_trait,
+					widget_impl,
+					paintable_impl,
+				],
 				is_stripped: false,
 			}),
 		},
@@ -75,7 +99,7 @@ fn fixture_crate() -> Crate {
 					has_stripped_fields: false,
 				},
 				generics: empty_generics(),
-				impls: vec![widget_impl],
+				impls: vec![widget_impl, paintable_impl],
 			}),
 		},
 	);
@@ -206,7 +230,41 @@ fn fixture_crate() -> Crate {
 				items: vec![paint_method],
 				generics: empty_generics(),
 				bounds: Vec::new(),
-				implementations: Vec::new(),
+				implementations: vec![paintable_impl],
+			}),
+		},
+	);
+
+	index.insert(
+		paintable_impl,
+		Item {
+			id: paintable_impl,
+			crate_id: 0,
+			name: None,
+			span: None,
+			visibility: Visibility::Default,
+			docs: None,
+			links: HashMap::new(),
+			attrs: Vec::new(),
+			deprecation: None,
+			inner: ItemEnum::Impl(Impl {
+				is_unsafe: false,
+				generics: empty_generics(),
+				provided_trait_methods: Vec::new(),
+				trait_: Some(Path {
+					path: "Paintable".into(),
+					id: paintable_trait,
+					args: None,
+				}),
+				for_: Type::ResolvedPath(Path {
+					path: "Widget".into(),
+					id: widget,
+					args: None,
+				}),
+				items: Vec::new(),
+				is_negative: false,
+				is_synthetic: false,
+				blanket_impl: None,
 			}),
 		},
 	);
@@ -277,6 +335,66 @@ fn name_domain_matches_impl_method() {
 	);
 }
 
+#[test]
+fn path_segments_carry_kinds_and_ids() {
+	let index = build_index();
+	let render = index.get(&Id(4)).expect("render entry");
+	let segments: Vec<_> = render
+		.path
+		.iter()
+		.map(|segment| (segment.name.as_str(), segment.kind, segment.id))
+		.collect();
+	assert_eq!(
+		segments,
+		[
+			("fixture", SearchItemKind::Crate, Some(Id(0))),
+			("Widget", SearchItemKind::Struct, Some(Id(1))),
+			("render", SearchItemKind::Method, Some(Id(4))),
+		]
+	);
+	let parent = render.path[1].id.and_then(|id| index.get(&id));
+	assert_eq!(parent.map(|entry| entry.raw_name.as_str()), Some("Widget"));
+}
+
+#[test]
+fn tree_nests_items_under_their_parents() {
+	let loaded = LoadedCrate::from_crate(fixture_crate());
+	let expected = [
+		"crate fixture",
+		"|-- struct Widget",
+		"|   |-- field id",
+		"|   `-- method render",
+		"|-- trait Paintable",
+		"|   `-- trait method paint",
+		"`-- function helper",
+	];
+	assert_eq!(
+		crate::tree::render_tree(&loaded.list_tree(None), false),
+		expected.map(|line| format!("{line}\n")).concat()
+	);
+}
+
+#[test]
+fn searched_tree_keeps_matches_and_their_ancestors() {
+	let loaded = LoadedCrate::from_crate(fixture_crate());
+	let mut options = SearchOptions::new("render");
+	options.domains = SearchDomain::NAMES;
+	let tree = loaded.list_tree(Some(&options));
+	let expected = [
+		"crate fixture",
+		"└── struct Widget",
+		"    └── method render",
+	];
+	assert_eq!(
+		crate::tree::render_tree(&tree, true),
+		expected.map(|line| format!("{line}\n")).concat()
+	);
+	assert_eq!(
+		tree[0].children[0].children[0].path,
+		"fixture::Widget::render"
+	);
+}
+
 #[test]
 fn multi_domain_hits_report_all_matches() {
 	let index = build_index();
@@ -336,6 +454,394 @@ fn negative_query_returns_empty() {
 	assert!(index.search(&options).is_empty());
 }
 
+#[test]
+fn relative_paths_are_found_exactly() {
+	let index = build_index();
+	let entry = index.find_relative("Widget::render").expect("render entry");
+	assert_eq!(entry.raw_name, "render");
+	assert!(!entry.is_private());
+	assert!(index.find_relative("widget::render").is_none());
+	assert!(index.find_relative("fixture").is_none());
+}
+
+#[test]
+fn summary_counts_the_fixture_crate() {
+	let mut crate_data = fixture_crate();
+	crate_data.index.get_mut(&Id(2)).unwrap().docs = None;
+	crate_data.index.get_mut(&Id(5)).unwrap().deprecation = Some(Deprecation {
+		since: Some("0.2.0".into()),
+		note: None,
+	});
+	let loaded = LoadedCrate::from_crate(crate_data);
+
+	assert_eq!(
+		loaded.summary(),
+		CrateSummary {
+			crate_name: "fixture".into(),
+			structs: 1,
+			traits: 1,
+			functions: 1,
+			methods: 2,
+			items: 6,
+			documented: 5,
+			deprecated: 1,
+			largest_modules: vec![ModuleSize {
+				path: "fixture".into(),
+				items: 3,
+			}],
+			..CrateSummary::default()
+		}
+	);
+
+	let widget = loaded.with_filter("Widget").summary();
+	assert_eq!((widget.structs, widget.methods, widget.items), (1, 1, 3));
+	assert_eq!(widget.functions, 0);
+}
+
+#[test]
+fn only_simple_constant_values_are_rendered() {
+	let mut crate_data = fixture_crate();
+	let block = format!(
+		"{{ let mut table = [0u32; 16]; let mut i = 0; while i < 16 {{ table[i] = {}; i += 1; }} \
+		 table[0] }}",
+		"(i as u32).wrapping_mul(0x9E37_79B9).rotate_left(5) ^ 0xDEAD_BEEF".repeat(2)
+	);
+	assert!(block.len() > 200);
+	let consts = [
+		(Id(20), "LIMIT", "42".to_string()),
+		(Id(21), "MAX", "u32::MAX".to_string()),
+		(Id(22), "TABLE", block.clone()),
+	];
+	for (id, name, value) in &consts {
+		crate_data.index.insert(
+			*id,
+			fixture_item(
+				*id,
+				name,
+				Vec::new(),
+				ItemEnum::AssocConst {
+					type_: Type::Primitive("u32".into()),
+					value: Some(value.clone()),
+				},
+			),
+		);
+	}
+	if let Some(Item {
+		inner: ItemEnum::Impl(Impl { items, .. }),
+		..
+	}) = crate_data.index.get_mut(&Id(3))
+	{
+		items.extend(consts.iter().map(|(id, ..)| *id));
+	}
+	let seed = Id(23);
+	crate_data.index.insert(
+		seed,
+		fixture_item(
+			seed,
+			"SEED",
+			Vec::new(),
+			ItemEnum::Constant {
+				type_: Type::Primitive("u32".into()),
+				const_: Constant {
+					expr: block,
+					value: None,
+					is_literal: false,
+				},
+			},
+		),
+	);
+	if let Some(Item {
+		inner: ItemEnum::Module(Module { items, .. }),
+		..
+	}) = crate_data.index.get_mut(&Id(0))
+	{
+		items.push(seed);
+	}
+
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.render(&crate_data)
+		.unwrap();
+	assert!(
+		rendered.contains("pub const LIMIT: u32 = 42;"),
+		"{rendered}"
+	);
+	assert!(
+		rendered.contains("pub const MAX: u32 = u32::MAX;"),
+		"{rendered}"
+	);
+	assert!(
+		rendered.contains("pub const TABLE: u32 = unimplemented!() /* value omitted */;"),
+		"{rendered}"
+	);
+	assert!(
+		rendered.contains("pub const SEED: u32; // value omitted"),
+		"{rendered}"
+	);
+	assert!(!rendered.contains("wrapping_mul"), "{rendered}");
+	syn::parse_file(&rendered).unwrap();
+}
+
+#[test]
+fn field_matches_render_inside_their_struct() {
+	let mut crate_data = fixture_crate();
+	let label = Id(20);
+	crate_data.index.insert(
+		label,
+		fixture_item(
+			label,
+			"label",
+			Vec::new(),
+			ItemEnum::StructField(Type::Primitive("str".into())),
+		),
+	);
+	if let Some(Item {
+		inner: ItemEnum::Struct(Struct {
+			kind: StructKind::Plain { fields, .. },
+			..
+		}),
+		..
+	}) = crate_data.index.get_mut(&Id(1))
+	{
+		fields.push(label);
+	}
+	let loaded = LoadedCrate::from_crate(crate_data).with_render_format(RenderFormat::Rust);
+
+	let mut options = SearchOptions::new("id");
+	options.domains = SearchDomain::NAMES;
+	let results = loaded.index().search(&options);
+	let field = results
+		.iter()
+		.find(|result| result.kind == SearchItemKind::Field)
+		.expect("field result");
+	assert_eq!(field.path_string, "fixture::Widget::id");
+
+	let rendered = loaded
+		.render_selection(std::slice::from_ref(field))
+		.unwrap();
+	assert!(rendered.contains("pub struct Widget {"), "{rendered}");
+	assert!(rendered.contains("pub id: u32,"), "{rendered}");
+	assert!(
+		rendered.contains("/* 1 other field omitted */"),
+		"{rendered}"
+	);
+	assert!(!rendered.contains("label"), "{rendered}");
+	assert!(!rendered.contains("fn render("), "{rendered}");
+}
+
+#[test]
+fn suggestions_cover_case_and_single_segment_typos() {
+	let index = build_index();
+	assert_eq!(index.suggest_relative("widget", 3), ["Widget"]);
+	assert_eq!(
+		index.suggest_relative("Widget::rendr", 3),
+		["Widget::render"]
+	);
+	assert_eq!(index.suggest_relative("helpr", 3), ["helper"]);
+	assert!(index.suggest_relative("Gadget::render::deep", 3).is_empty());
+	assert!(index.suggest_relative("completely_unrelated", 3).is_empty());
+}
+
+#[test]
+fn trait_implementors_are_listed_on_request() {
+	let crate_data = fixture_crate();
+	let renderer = Renderer::default().with_format(RenderFormat::Rust);
+
+	let plain = renderer.render(&crate_data).unwrap();
+	assert!(!plain.contains("Implemented by"), "{plain}");
+
+	let listed = renderer
+		.with_trait_implementors(true)
+		.render(&crate_data)
+		.unwrap();
+	assert!(listed.contains("// Implemented by:"), "{listed}");
+	assert!(listed.contains("// impl Paintable for Widget"), "{listed}");
+}
+
+#[test]
+fn selection_for_items_renders_picked_items_in_context() {
+	let crate_data = fixture_crate();
+	let helper_fn = Id(5);
+	let render_method = Id(4);
+	let selection = RenderSelection::for_items(&crate_data, &[helper_fn, render_method], false);
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_selection(selection)
+		.render(&crate_data)
+		.unwrap();
+
+	for expected in [
+		"pub mod fixture",
+		"pub struct Widget",
+		"fn render(",
+		"fn helper(",
+	] {
+		assert!(
+			rendered.contains(expected),
+			"missing {expected}: {rendered}"
+		);
+	}
+	for unexpected in ["Paintable", "fn paint(", "Identifier for Widget"] {
+		assert!(
+			!rendered.contains(unexpected),
+			"unexpected {unexpected}: {rendered}"
+		);
+	}
+}
+
+#[test]
+fn header_describes_crate_features_and_toolchain() {
+	let crate_data = fixture_crate();
+	let renderer = Renderer::default()
+		.with_header(true)
+		.with_header_features(vec!["default".into(), "rt".into()])
+		.with_header_toolchain(Some("rustc 1.82.0-nightly (abc123 2024-08-01)\n".into()));
+	let version = env!("CARGO_PKG_VERSION");
+
+	let rust = renderer.with_format(RenderFormat::Rust);
+	let expected = format!(
+		"// crate: fixture v0.1.0  features: [default, rt]  generated-by: ripdoc {version}  \
+		 nightly: 1.82.0-nightly (abc123 2024-08-01)\n\n"
+	);
+	assert_eq!(rust.header(&crate_data), expected);
+	let rendered = rust.render(&crate_data).unwrap();
+	assert!(rendered.starts_with(&expected), "{rendered}");
+	// Nothing in the header changes between runs.
+	assert_eq!(rust.render(&crate_data).unwrap(), rendered);
+
+	let markdown = rust
+		.with_format(RenderFormat::Markdown)
+		.render(&crate_data)
+		.unwrap();
+	assert!(
+		markdown.starts_with("---\ncrate: fixture v0.1.0\nfeatures: [default, rt]\n"),
+		"{markdown}"
+	);
+}
+
+#[test]
+fn header_notes_the_requested_version() {
+	let crate_data = fixture_crate();
+	let header = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_header_source(Some("registry".into()))
+		.with_header_requested(Some("^0.1".into()))
+		.header(&crate_data);
+	assert!(
+		header.starts_with("// crate: fixture v0.1.0 (requested ^0.1)  source: registry  "),
+		"{header}"
+	);
+}
+
+#[test]
+fn header_lists_resolved_features_after_requested_ones() {
+	let crate_data = fixture_crate();
+	let header = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_header_features(vec!["default".into()])
+		.with_header_resolved_features(Some(vec!["default".into(), "extra".into(), "std".into()]))
+		.header(&crate_data);
+	assert!(
+		header.contains("features: [default]  resolved-features: [default, extra, std]  "),
+		"{header}"
+	);
+}
+
+#[test]
+#[cfg(feature = "cargo-resolution")]
+fn header_reports_target_triple() {
+	let mut crate_data = fixture_crate();
+	crate_data.format_version = rustdoc_types::FORMAT_VERSION;
+	let (_temp_dir, target) = write_json_fixture(&crate_data);
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_header(true)
+		.with_target_triple(Some("thumbv7em-none-eabihf".into()))
+		.with_render_format(RenderFormat::Rust);
+
+	let rendered = ripdoc.render(&target, false, false, &[], false).unwrap();
+	let header = rendered.lines().next().unwrap();
+	assert!(
+		header.contains("features: [default]  target: thumbv7em-none-eabihf  generated-by:"),
+		"{header}"
+	);
+}
+
+#[test]
+#[cfg(feature = "cargo-resolution")]
+fn single_trait_search_lists_implementors() {
+	let mut crate_data = fixture_crate();
+	crate_data.format_version = rustdoc_types::FORMAT_VERSION;
+	let (_temp_dir, target) = write_json_fixture(&crate_data);
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_render_format(RenderFormat::Rust);
+
+	let mut options = SearchOptions::new("Paintable");
+	options.domains = SearchDomain::NAMES;
+	let response = ripdoc.search(&target, false, false, &[], &options).unwrap();
+	assert_eq!(response.results.len(), 1);
+	assert!(
+		response.rendered.contains("// impl Paintable for Widget"),
+		"{}",
+		response.rendered
+	);
+
+	let mut options = SearchOptions::new("Widget");
+	options.domains = SearchDomain::NAMES;
+	let response = ripdoc.search(&target, false, false, &[], &options).unwrap();
+	assert!(
+		!response.rendered.contains("Implemented by"),
+		"{}",
+		response.rendered
+	);
+}
+
+/// Count the items in `items`, including those nested in inline modules, traits and impls.
+fn count_items(items: &[syn::Item]) -> usize {
+	items
+		.iter()
+		.map(|item| {
+			1 + match item {
+				syn::Item::Mod(module) => module
+					.content
+					.as_ref()
+					.map_or(0, |(_, items)| count_items(items)),
+				syn::Item::Trait(trait_) => trait_.items.len(),
+				syn::Item::Impl(impl_) => impl_.items.len(),
+				_ => 0,
+			}
+		})
+		.sum()
+}
+
+#[test]
+fn every_formatter_renders_the_same_items() {
+	let crate_data = fixture_crate();
+	let render = |choice| {
+		Renderer::default()
+			.with_format(RenderFormat::Rust)
+			.with_formatter(choice)
+			.render(&crate_data)
+			.unwrap()
+	};
+	let rustfmt = render(FormatterChoice::RustFmt);
+	let prettyplease = render(FormatterChoice::Prettyplease);
+	let unformatted = render(FormatterChoice::None);
+
+	let items = |source: &str| count_items(&syn::parse_file(source).unwrap().items);
+	assert_eq!(
+		items(&prettyplease),
+		items(&rustfmt),
+		"{prettyplease}\n{rustfmt}"
+	);
+	assert_eq!(items(&unformatted), items(&rustfmt), "{unformatted}");
+	assert!(
+		unformatted.contains("\n    pub struct Widget"),
+		"{unformatted}"
+	);
+}
+
 #[test]
 fn describe_domains_lists_selected_flags() {
 	assert_eq!(
@@ -348,3 +854,1825 @@ fn describe_domains_lists_selected_flags() {
 		vec!["name", "doc"]
 	);
 }
+
+#[test]
+fn search_domain_parses_singular_plural_and_all() {
+	assert_eq!(
+		SearchDomain::from_str_list(&["name", "docs", "Paths"]),
+		Ok(SearchDomain::NAMES | SearchDomain::DOCS | SearchDomain::PATHS)
+	);
+	assert_eq!(
+		SearchDomain::from_str_list(&["signatures", "attr"]),
+		Ok(SearchDomain::SIGNATURES | SearchDomain::ATTRS)
+	);
+	assert_eq!(SearchDomain::from_str_list(&["all"]), Ok(SearchDomain::ALL));
+	assert_eq!(SearchDomain::from_str_list(&[]), Ok(SearchDomain::empty()));
+	assert_eq!(
+		super::describe_domains(SearchDomain::ALL),
+		vec!["name", "doc", "path", "signature", "attrs"]
+	);
+}
+
+#[test]
+fn search_domain_rejects_unknown_names_in_mixed_lists() {
+	let err = SearchDomain::from_str_list(&["name", "body", "doc"]).unwrap_err();
+	assert_eq!(err, UnknownDomain("body".into()));
+	assert_eq!(
+		err.to_string(),
+		"unknown search domain `body`, expected name, doc, path, signature, attrs, or all"
+	);
+}
+
+/// Create a public item with the given attributes for hand-built fixtures.
+fn fixture_item(id: Id, name: &str, attrs: Vec<Attribute>, inner: ItemEnum) -> Item {
+	Item {
+		id,
+		crate_id: 0,
+		name: Some(name.into()),
+		span: None,
+		visibility: Visibility::Public,
+		docs: None,
+		links: HashMap::new(),
+		attrs,
+		deprecation: None,
+		inner,
+	}
+}
+
+/// Assemble hand-built fixture items into a crate rooted at `Id(0)`.
+fn fixture_crate_of(items: impl IntoIterator<Item = Item>) -> Crate {
+	Crate {
+		root: Id(0),
+		crate_version: Some("0.1.0".into()),
+		includes_private: false,
+		index: items.into_iter().map(|item| (item.id, item)).collect(),
+		paths: HashMap::new(),
+		external_crates: HashMap::new(),
+		target: Target {
+			triple: "test-target".into(),
+			target_features: Vec::new(),
+		},
+		format_version: 0,
+	}
+}
+
+fn hidden_fixture_crate() -> Crate {
+	let root = Id(0);
+	let secret_mod = Id(1);
+	let secret_fn = Id(2);
+	let config = Id(3);
+	let visible_field = Id(4);
+	let hidden_field = Id(5);
+	let hidden = || vec![Attribute::Other("#[doc(hidden)]".into())];
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![secret_mod, config],
+				is_stripped: false,
+			}),
+		),
+		fixture_item(
+			secret_mod,
+			"secret",
+			hidden(),
+			ItemEnum::Module(Module {
+				is_crate: false,
+				items: vec![secret_fn],
+				is_stripped: false,
+			}),
+		),
+		fixture_item(
+			secret_fn,
+			"internals",
+			Vec::new(),
+			ItemEnum::Function(Function {
+				sig: FunctionSignature {
+					inputs: Vec::new(),
+					output: None,
+					is_c_variadic: false,
+				},
+				generics: empty_generics(),
+				header: default_header(),
+				has_body: true,
+			}),
+		),
+		fixture_item(
+			config,
+			"Config",
+			Vec::new(),
+			ItemEnum::Struct(Struct {
+				kind: StructKind::Plain {
+					fields: vec![visible_field, hidden_field],
+					has_stripped_fields: false,
+				},
+				generics: empty_generics(),
+				impls: Vec::new(),
+			}),
+		),
+		fixture_item(
+			visible_field,
+			"verbose",
+			Vec::new(),
+			ItemEnum::StructField(Type::Primitive("bool".into())),
+		),
+		fixture_item(
+			hidden_field,
+			"cache",
+			hidden(),
+			ItemEnum::StructField(Type::Primitive("u32".into())),
+		),
+	];
+
+	fixture_crate_of(items)
+}
+
+#[test]
+fn doc_hidden_items_are_skipped_by_default() {
+	let crate_data = hidden_fixture_crate();
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.render(&crate_data)
+		.unwrap();
+	assert!(!rendered.contains("mod secret"), "{rendered}");
+	assert!(!rendered.contains("cache"), "{rendered}");
+	assert!(rendered.contains("pub verbose: bool"), "{rendered}");
+}
+
+#[test]
+fn doc_hidden_flag_restores_hidden_items() {
+	let crate_data = hidden_fixture_crate();
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_doc_hidden(true)
+		.render(&crate_data)
+		.unwrap();
+	assert!(rendered.contains("pub mod secret"), "{rendered}");
+	assert!(rendered.contains("pub fn internals()"), "{rendered}");
+	assert!(rendered.contains("pub cache: u32"), "{rendered}");
+}
+
+#[test]
+fn doc_hidden_needs_a_standalone_hidden_entry() {
+	let with_attr = |attr: &str| {
+		fixture_item(
+			Id(0),
+			"cache",
+			vec![Attribute::Other(attr.into())],
+			ItemEnum::StructField(Type::Primitive("u32".into())),
+		)
+	};
+	for hidden in [
+		"#[doc(hidden)]",
+		"#[doc(inline, hidden)]",
+		"#[doc( hidden , alias = \"cfg\")]",
+	] {
+		assert!(is_doc_hidden(&with_attr(hidden)), "{hidden}");
+	}
+	for shown in [
+		"#[doc(alias = \"hidden\")]",
+		"#[doc(alias(\"hidden\", \"secret\"))]",
+		"#[doc(alias = \"a, hidden\")]",
+		"#[doc = \"hidden\"]",
+		"#[cfg(hidden)]",
+	] {
+		assert!(!is_doc_hidden(&with_attr(shown)), "{shown}");
+	}
+}
+
+/// Crate declaring everything in private modules: `Engine` is re-exported from the root,
+/// `Circle` through a glob in the public `prelude`, and `Orphan` is never re-exported.
+fn private_modules_fixture_crate() -> Crate {
+	let root = Id(0);
+	let internal = Id(1);
+	let engine = Id(2);
+	let orphan = Id(3);
+	let engine_use = Id(4);
+	let prelude = Id(5);
+	let shapes_glob = Id(6);
+	let shapes = Id(7);
+	let api = Id(8);
+	let circle = Id(9);
+	let run = Id(10);
+	let module = |items| {
+		ItemEnum::Module(Module {
+			is_crate: false,
+			items,
+			is_stripped: false,
+		})
+	};
+	let unit_struct = || {
+		ItemEnum::Struct(Struct {
+			kind: StructKind::Unit,
+			generics: empty_generics(),
+			impls: Vec::new(),
+		})
+	};
+	let private = |mut item: Item| {
+		item.visibility = Visibility::Default;
+		item
+	};
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![internal, engine_use, prelude, shapes, api],
+				is_stripped: false,
+			}),
+		),
+		private(fixture_item(
+			internal,
+			"internal",
+			Vec::new(),
+			module(vec![engine, orphan]),
+		)),
+		fixture_item(engine, "Engine", Vec::new(), unit_struct()),
+		fixture_item(orphan, "Orphan", Vec::new(), unit_struct()),
+		fixture_item(
+			engine_use,
+			"Engine",
+			Vec::new(),
+			ItemEnum::Use(Use {
+				source: "internal::Engine".into(),
+				name: "Engine".into(),
+				id: Some(engine),
+				is_glob: false,
+			}),
+		),
+		fixture_item(prelude, "prelude", Vec::new(), module(vec![shapes_glob])),
+		fixture_item(
+			shapes_glob,
+			"shapes",
+			Vec::new(),
+			ItemEnum::Use(Use {
+				source: "crate::shapes".into(),
+				name: "shapes".into(),
+				id: Some(shapes),
+				is_glob: true,
+			}),
+		),
+		private(fixture_item(
+			shapes,
+			"shapes",
+			Vec::new(),
+			module(vec![circle]),
+		)),
+		fixture_item(circle, "Circle", Vec::new(), unit_struct()),
+		fixture_item(api, "api", Vec::new(), module(vec![run])),
+		fixture_item(
+			run,
+			"run",
+			Vec::new(),
+			ItemEnum::Function(Function {
+				sig: FunctionSignature {
+					inputs: Vec::new(),
+					output: None,
+					is_c_variadic: false,
+				},
+				generics: empty_generics(),
+				header: default_header(),
+				has_body: true,
+			}),
+		),
+	];
+
+	Crate {
+		includes_private: true,
+		..fixture_crate_of(items)
+	}
+}
+
+#[test]
+fn reachability_follows_re_exports_and_globs_to_public_paths() {
+	let crate_data = private_modules_fixture_crate();
+	let reachability = Reachability::new(&crate_data);
+	assert_eq!(reachability.public_path(&Id(2)), Some("fixture::Engine"));
+	assert_eq!(
+		reachability.public_path(&Id(9)),
+		Some("fixture::prelude::Circle")
+	);
+	assert_eq!(reachability.public_path(&Id(10)), Some("fixture::api::run"));
+	assert!(!reachability.is_reachable(&Id(1)));
+	assert!(!reachability.is_reachable(&Id(3)));
+}
+
+#[test]
+fn facade_renders_only_the_re_exported_surface() {
+	let loaded = LoadedCrate::from_crate(private_modules_fixture_crate())
+		.with_render_format(RenderFormat::Rust);
+	let everything = loaded.render().unwrap();
+	assert!(everything.contains("pub struct Orphan;"), "{everything}");
+
+	let facade = loaded.render_facade().unwrap();
+	for expected in [
+		"pub struct Engine;",
+		"pub mod prelude",
+		"pub struct Circle;",
+		"pub mod api",
+		"pub fn run()",
+	] {
+		assert!(facade.contains(expected), "missing {expected}: {facade}");
+	}
+	for unexpected in ["Orphan", "mod internal", "mod shapes"] {
+		assert!(
+			!facade.contains(unexpected),
+			"unexpected {unexpected}: {facade}"
+		);
+	}
+}
+
+#[test]
+fn unreachable_pub_lists_items_nothing_re_exports() {
+	let loaded = LoadedCrate::from_crate(private_modules_fixture_crate());
+	let unreachable = loaded.unreachable_pub();
+	let paths: Vec<&str> = unreachable.iter().map(|item| item.path.as_str()).collect();
+	assert_eq!(paths, ["fixture::internal::Orphan"]);
+	assert_eq!(unreachable[0].kind, SearchItemKind::Struct);
+}
+
+fn test_module_fixture_crate() -> Crate {
+	let root = Id(0);
+	let cfg_tests = Id(1);
+	let helper = Id(2);
+	let api = Id(3);
+	let api_tests = Id(4);
+	let check = Id(5);
+	let generated = Id(6);
+	let module = |items| {
+		ItemEnum::Module(Module {
+			is_crate: false,
+			items,
+			is_stripped: false,
+		})
+	};
+	let function = || {
+		ItemEnum::Function(Function {
+			sig: FunctionSignature {
+				inputs: Vec::new(),
+				output: None,
+				is_c_variadic: false,
+			},
+			generics: empty_generics(),
+			header: default_header(),
+			has_body: true,
+		})
+	};
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![cfg_tests, api, generated],
+				is_stripped: false,
+			}),
+		),
+		fixture_item(
+			cfg_tests,
+			"tests",
+			vec![Attribute::Other("#[cfg(test)]".into())],
+			module(vec![helper]),
+		),
+		fixture_item(helper, "make_helper", Vec::new(), function()),
+		fixture_item(api, "api", Vec::new(), module(vec![api_tests])),
+		fixture_item(api_tests, "tests", Vec::new(), module(vec![check])),
+		fixture_item(check, "check", Vec::new(), function()),
+		Item {
+			span: Some(Span {
+				filename: "build.rs".into(),
+				begin: (1, 0),
+				end: (1, 20),
+			}),
+			..fixture_item(generated, "probe_compiler", Vec::new(), function())
+		},
+	];
+
+	Crate {
+		includes_private: true,
+		..fixture_crate_of(items)
+	}
+}
+
+#[test]
+fn cfg_test_modules_and_build_script_items_are_skipped_by_default() {
+	let crate_data = test_module_fixture_crate();
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_private_items(true)
+		.render(&crate_data)
+		.unwrap();
+	assert!(!rendered.contains("make_helper"), "{rendered}");
+	assert!(!rendered.contains("probe_compiler"), "{rendered}");
+	// A module that is merely called `tests` is regular API.
+	assert!(rendered.contains("pub mod tests"), "{rendered}");
+	assert!(rendered.contains("pub fn check()"), "{rendered}");
+}
+
+#[test]
+fn tests_flag_restores_test_modules_and_build_script_items() {
+	let crate_data = test_module_fixture_crate();
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_private_items(true)
+		.with_tests(true)
+		.render(&crate_data)
+		.unwrap();
+	assert!(rendered.contains("pub fn make_helper()"), "{rendered}");
+	assert!(rendered.contains("pub fn probe_compiler()"), "{rendered}");
+}
+
+#[test]
+#[cfg(feature = "cargo-resolution")]
+fn search_output_omits_ancestor_module_docs() {
+	let mut crate_data = test_module_fixture_crate();
+	for (id, docs) in [
+		(Id(0), "Crate overview prose"),
+		(Id(3), "API module prose"),
+		(Id(5), "Validate the API"),
+	] {
+		crate_data.index.get_mut(&id).unwrap().docs = Some(docs.into());
+	}
+	crate_data.format_version = rustdoc_types::FORMAT_VERSION;
+	let (_temp_dir, target) = write_json_fixture(&crate_data);
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_render_format(RenderFormat::Rust);
+
+	let mut options = SearchOptions::new("check");
+	options.domains = SearchDomain::NAMES;
+	let response = ripdoc.search(&target, false, false, &[], &options).unwrap();
+	let rendered = &response.rendered;
+	assert!(rendered.contains("/// Validate the API"), "{rendered}");
+	assert!(rendered.contains("pub mod api {"), "{rendered}");
+	assert!(!rendered.contains("Crate overview prose"), "{rendered}");
+	assert!(!rendered.contains("API module prose"), "{rendered}");
+}
+
+#[test]
+#[cfg(feature = "cargo-resolution")]
+fn listing_and_search_keep_to_the_target_filter() {
+	let mut crate_data = test_module_fixture_crate();
+	crate_data.format_version = rustdoc_types::FORMAT_VERSION;
+	let (_temp_dir, target) = write_json_fixture(&crate_data);
+	let ripdoc = Ripdoc::new().with_offline(true);
+	let api = format!("{target}::api");
+	let expected = [
+		"fixture::api",
+		"fixture::api::tests",
+		"fixture::api::tests::check",
+	];
+	fn collect(nodes: &[TreeNode], paths: &mut Vec<String>) {
+		for node in nodes {
+			paths.push(node.path.clone());
+			collect(&node.children, paths);
+		}
+	}
+
+	let listed = ripdoc.list(&api, false, false, &[], false, None).unwrap();
+	let mut paths: Vec<String> = listed.into_iter().map(|item| item.path).collect();
+	paths.sort();
+	assert_eq!(paths, expected);
+
+	let tree = ripdoc
+		.list_tree(&api, false, false, &[], false, None)
+		.unwrap();
+	let mut paths = Vec::new();
+	collect(&tree, &mut paths);
+	paths.retain(|path| path != "fixture");
+	paths.sort();
+	assert_eq!(paths, expected);
+
+	// `tests` at the root does not take in `api::tests`
+	let mut options = SearchOptions::new("check");
+	options.domains = SearchDomain::NAMES;
+	let found = ripdoc.search(&api, false, false, &[], &options).unwrap();
+	assert_eq!(found.results.len(), 1);
+	let tests = format!("{target}::tests");
+	let found = ripdoc.search(&tests, false, false, &[], &options).unwrap();
+	assert!(found.results.is_empty(), "{:?}", found.results);
+	let listed = ripdoc
+		.list(&tests, false, false, &[], false, Some(&options))
+		.unwrap();
+	assert!(listed.is_empty(), "{listed:?}");
+}
+
+/// Write a fixture crate to a rustdoc JSON file inside a fresh temporary directory.
+#[cfg(feature = "cargo-resolution")]
+fn write_json_fixture(crate_data: &Crate) -> (TempDir, String) {
+	let temp_dir = TempDir::new().unwrap();
+	let path = temp_dir.path().join("fixture.json");
+	fs::write(&path, serde_json::to_string(crate_data).unwrap()).unwrap();
+	let target = path.to_str().unwrap().to_string();
+	(temp_dir, target)
+}
+
+#[test]
+#[cfg(feature = "cargo-resolution")]
+fn json_file_target_renders_without_cargo() {
+	let mut crate_data = hidden_fixture_crate();
+	crate_data.format_version = rustdoc_types::FORMAT_VERSION;
+	let (_temp_dir, target) = write_json_fixture(&crate_data);
+	let ripdoc = Ripdoc::new()
+		.with_offline(true)
+		.with_render_format(RenderFormat::Rust);
+
+	let rendered = ripdoc.render(&target, false, false, &[], false).unwrap();
+	assert!(rendered.contains("pub struct Config"), "{rendered}");
+
+	let filtered = ripdoc
+		.render(&format!("{target}::Config"), false, false, &[], false)
+		.unwrap();
+	assert!(filtered.contains("pub verbose: bool"), "{filtered}");
+}
+
+#[test]
+#[cfg(feature = "cargo-resolution")]
+fn json_file_target_rejects_incompatible_format_version() {
+	let mut crate_data = hidden_fixture_crate();
+	crate_data.format_version = rustdoc_types::FORMAT_VERSION + 1;
+	let (_temp_dir, target) = write_json_fixture(&crate_data);
+
+	let err = Ripdoc::new()
+		.render(&target, false, false, &[], false)
+		.unwrap_err();
+	assert!(err.to_string().contains("format version"), "{err}");
+}
+
+#[test]
+#[cfg(feature = "cargo-resolution")]
+fn raw_json_selected_keeps_the_selected_items_and_referenced_paths() {
+	let mut crate_data = fixture_crate();
+	crate_data.format_version = rustdoc_types::FORMAT_VERSION;
+	for (id, path, kind) in [
+		(Id(1), "Widget", ItemKind::Struct),
+		(Id(6), "Paintable", ItemKind::Trait),
+	] {
+		let summary = ItemSummary {
+			crate_id: 0,
+			path: vec!["fixture".into(), path.into()],
+			kind,
+		};
+		crate_data.paths.insert(id, summary);
+	}
+	let (_temp_dir, target) = write_json_fixture(&crate_data);
+	let ripdoc = Ripdoc::new().with_offline(true);
+
+	let json = ripdoc
+		.raw_json_selected(&format!("{target}::helper"), &LoadOptions::default(), None)
+		.unwrap();
+	let reduced: Crate = serde_json::from_str(&json).unwrap();
+	let mut kept: Vec<_> = reduced.index.keys().copied().collect();
+	kept.sort_by_key(|id| id.0);
+	assert_eq!(kept, vec![Id(0), Id(5)]);
+	let ItemEnum::Module(root) = &reduced.index[&Id(0)].inner else {
+		panic!("root is not a module");
+	};
+	assert_eq!(root.items, vec![Id(5)]);
+	// `helper` returns a `Widget`, so its path entry survives; `Paintable` is unreferenced.
+	assert!(reduced.paths.contains_key(&Id(1)));
+	assert!(!reduced.paths.contains_key(&Id(6)));
+
+	let mut search = SearchOptions::new("render");
+	search.domains = SearchDomain::NAMES;
+	let json = ripdoc
+		.raw_json_selected(&target, &LoadOptions::default(), Some(&search))
+		.unwrap();
+	let reduced: Crate = serde_json::from_str(&json).unwrap();
+	assert!(reduced.index.contains_key(&Id(4)), "{json}");
+	assert!(!reduced.index.contains_key(&Id(5)), "{json}");
+}
+
+#[test]
+fn crate_loaded_from_memory_searches_lists_and_renders_without_cargo() {
+	let loaded = LoadedCrate::from_crate(fixture_crate()).with_render_format(RenderFormat::Rust);
+
+	let listed: Vec<_> = loaded.list().into_iter().map(|item| item.path).collect();
+	assert!(
+		listed.contains(&"fixture::Widget".to_string()),
+		"{listed:?}"
+	);
+	assert!(
+		listed.contains(&"fixture::helper".to_string()),
+		"{listed:?}"
+	);
+
+	let rendered = loaded.render().unwrap();
+	assert!(rendered.contains("pub struct Widget"), "{rendered}");
+	assert!(
+		rendered.contains("pub fn helper(count: i32) -> Widget"),
+		"{rendered}"
+	);
+
+	let mut options = SearchOptions::new("helper");
+	options.domains = SearchDomain::NAMES;
+	let response = loaded.search(&options).unwrap();
+	assert_eq!(response.results.len(), 1);
+	assert!(
+		response
+			.rendered
+			.contains("pub fn helper(count: i32) -> Widget"),
+		"{}",
+		response.rendered
+	);
+	assert!(
+		!response.rendered.contains("pub struct Widget"),
+		"{}",
+		response.rendered
+	);
+	assert_eq!(
+		loaded.render_selection(&response.results).unwrap(),
+		response.rendered
+	);
+
+	let filtered = loaded.clone().with_filter("Widget").render().unwrap();
+	assert!(filtered.contains("pub struct Widget"), "{filtered}");
+	assert!(
+		!filtered.contains("pub fn helper(count: i32) -> Widget"),
+		"{filtered}"
+	);
+
+	let markdown = loaded
+		.clone()
+		.with_render_format(RenderFormat::Markdown)
+		.render_items(&[Id(5)])
+		.unwrap();
+	assert!(markdown.contains("```"), "{markdown}");
+	assert!(
+		markdown.contains("pub fn helper(count: i32) -> Widget"),
+		"{markdown}"
+	);
+
+	let json = loaded
+		.clone()
+		.with_render_format(RenderFormat::Json)
+		.render()
+		.unwrap();
+	let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+	assert!(value.to_string().contains("Widget"), "{json}");
+
+	let reduced: Crate =
+		serde_json::from_str(&loaded.selected_json(Some(&options)).unwrap()).unwrap();
+	assert!(reduced.index.contains_key(&Id(5)));
+	assert!(!reduced.index.contains_key(&Id(1)));
+}
+
+fn derive_fixture_crate() -> Crate {
+	let root = Id(0);
+	let plain = Id(1);
+	let record = Id(2);
+	let serialize_impl = Id(3);
+	let unit_struct = || {
+		ItemEnum::Struct(Struct {
+			kind: StructKind::Unit,
+			generics: empty_generics(),
+			impls: Vec::new(),
+		})
+	};
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![plain, record],
+				is_stripped: false,
+			}),
+		),
+		fixture_item(
+			plain,
+			"Plain",
+			vec![Attribute::Other("#[doc(cfg(feature = \"rt\"))]".into())],
+			unit_struct(),
+		),
+		fixture_item(
+			record,
+			"Record",
+			Vec::new(),
+			ItemEnum::Struct(Struct {
+				kind: StructKind::Unit,
+				generics: empty_generics(),
+				impls: vec![serialize_impl],
+			}),
+		),
+		Item {
+			name: None,
+			..fixture_item(
+				serialize_impl,
+				"",
+				vec![Attribute::AutomaticallyDerived],
+				ItemEnum::Impl(Impl {
+					is_unsafe: false,
+					generics: empty_generics(),
+					provided_trait_methods: Vec::new(),
+					trait_: Some(Path {
+						path: "serde::Serialize".into(),
+						id: Id(100),
+						args: None,
+					}),
+					for_: Type::ResolvedPath(Path {
+						path: "Record".into(),
+						id: record,
+						args: None,
+					}),
+					items: Vec::new(),
+					is_negative: false,
+					is_synthetic: false,
+					blanket_impl: None,
+				}),
+			)
+		},
+	];
+
+	fixture_crate_of(items)
+}
+
+#[test]
+fn attrs_domain_matches_derived_traits_on_the_type() {
+	let index = SearchIndex::build(&derive_fixture_crate(), false, None);
+	let mut options = SearchOptions::new("Serialize");
+	options.domains = SearchDomain::ATTRS;
+	let results = index.search(&options);
+	let names: Vec<&str> = results.iter().map(|r| r.raw_name.as_str()).collect();
+	assert_eq!(names, ["Record"]);
+	assert_eq!(results[0].kind, SearchItemKind::Struct);
+	assert_eq!(results[0].attrs.as_deref(), Some("#[derive(Serialize)]"));
+}
+
+#[test]
+fn attrs_domain_matches_quoted_attribute_text() {
+	let index = SearchIndex::build(&derive_fixture_crate(), false, None);
+	let mut options = SearchOptions::new("feature = \"rt\"");
+	options.domains = SearchDomain::ATTRS;
+	let results = index.search(&options);
+	let names: Vec<&str> = results.iter().map(|r| r.raw_name.as_str()).collect();
+	assert_eq!(names, ["Plain"]);
+}
+
+fn fuzzy_fixture_crate() -> Crate {
+	let root = Id(0);
+	let names = [
+		"Serializer",
+		"DeepSerializer",
+		"Deserializer",
+		"DeserializeOwned",
+		"Deserialize",
+	];
+	let ids: Vec<Id> = (1..=names.len() as u32).map(Id).collect();
+	let unit_struct = || {
+		ItemEnum::Struct(Struct {
+			kind: StructKind::Unit,
+			generics: empty_generics(),
+			impls: Vec::new(),
+		})
+	};
+
+	let mut items = vec![fixture_item(
+		root,
+		"fixture",
+		Vec::new(),
+		ItemEnum::Module(Module {
+			is_crate: true,
+			items: ids.clone(),
+			is_stripped: false,
+		}),
+	)];
+	items.extend(
+		ids.iter()
+			.zip(names)
+			.map(|(id, name)| fixture_item(*id, name, Vec::new(), unit_struct())),
+	);
+
+	fixture_crate_of(items)
+}
+
+fn fuzzy_names(query: &str) -> Vec<(String, Option<i64>)> {
+	let index = SearchIndex::build(&fuzzy_fixture_crate(), false, None);
+	let mut options = SearchOptions::new(query);
+	options.domains = SearchDomain::NAMES;
+	options.match_mode = MatchMode::Fuzzy;
+	index
+		.search(&options)
+		.into_iter()
+		.map(|result| (result.raw_name, result.score))
+		.collect()
+}
+
+#[test]
+fn fuzzy_names_match_scattered_subsequences() {
+	let results = fuzzy_names("dsrlz");
+	assert!(
+		results.iter().any(|(name, _)| name == "Deserializer"),
+		"{results:?}"
+	);
+	assert!(results.iter().all(|(_, score)| score.is_some()));
+	assert!(!results.iter().any(|(name, _)| name == "Serializer"));
+
+	// Substring matching finds nothing for the same query.
+	let index = SearchIndex::build(&fuzzy_fixture_crate(), false, None);
+	let mut options = SearchOptions::new("dsrlz");
+	options.domains = SearchDomain::NAMES;
+	assert!(index.search(&options).is_empty());
+}
+
+#[test]
+fn exact_fuzzy_matches_outrank_partial_ones() {
+	let results = fuzzy_names("deserialize");
+	let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+	assert_eq!(names.first(), Some(&"Deserialize"), "{results:?}");
+	assert_eq!(names.last(), Some(&"DeepSerializer"), "{results:?}");
+	assert!(!names.contains(&"Serializer"));
+	assert!(results[0].1 > results[1].1, "{results:?}");
+	assert!(
+		results.windows(2).all(|pair| pair[0].1 >= pair[1].1),
+		"{results:?}"
+	);
+}
+
+#[test]
+fn fuzzy_queries_ignore_snake_case_underscores() {
+	let results = fuzzy_names("deserialize_owned");
+	let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+	assert_eq!(names, ["DeserializeOwned"]);
+}
+
+fn unsupported_fixture_crate() -> Crate {
+	let root = Id(0);
+	let counter = Id(1);
+	let limit = Id(2);
+	let internal = Id(3);
+	let foreign = Id(4);
+	let run = Id(5);
+	let static_item = |id, name: &str| {
+		fixture_item(
+			id,
+			name,
+			Vec::new(),
+			ItemEnum::Static(Static {
+				type_: Type::Primitive("usize".into()),
+				is_mutable: false,
+				expr: "0".into(),
+				is_unsafe: false,
+			}),
+		)
+	};
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![counter, limit, internal, foreign, run],
+				is_stripped: false,
+			}),
+		),
+		static_item(counter, "COUNTER"),
+		static_item(limit, "LIMIT"),
+		Item {
+			visibility: Visibility::Crate,
+			..static_item(internal, "INTERNAL")
+		},
+		fixture_item(foreign, "Handle", Vec::new(), ItemEnum::ExternType),
+		fixture_item(
+			run,
+			"run",
+			Vec::new(),
+			ItemEnum::Function(Function {
+				sig: FunctionSignature {
+					inputs: Vec::new(),
+					output: None,
+					is_c_variadic: false,
+				},
+				generics: empty_generics(),
+				header: default_header(),
+				has_body: true,
+			}),
+		),
+	];
+
+	fixture_crate_of(items)
+}
+
+#[test]
+fn render_report_counts_visible_unsupported_items() {
+	let crate_data = unsupported_fixture_crate();
+	let (rendered, report) = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.render_with_report(&crate_data)
+		.unwrap();
+	assert!(rendered.contains("pub fn run()"), "{rendered}");
+	assert_eq!(report.to_string(), "1 extern type, 2 statics");
+
+	let skipped: Vec<_> = report.skipped().collect();
+	assert_eq!(
+		skipped,
+		[
+			(
+				SkippedKind::ExternType,
+				&["fixture::Handle".to_string()][..]
+			),
+			(
+				SkippedKind::Static,
+				&["fixture::COUNTER".to_string(), "fixture::LIMIT".to_string()][..]
+			),
+		]
+	);
+
+	let (_, json_report) = Renderer::default()
+		.with_format(RenderFormat::Json)
+		.render_with_report(&crate_data)
+		.unwrap();
+	assert_eq!(json_report, report);
+}
+
+#[test]
+fn render_report_is_empty_when_every_item_is_supported() {
+	let (_, report) = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.render_with_report(&hidden_fixture_crate())
+		.unwrap();
+	assert!(report.is_empty());
+	assert_eq!(report.to_string(), "");
+}
+
+/// A `core`-like crate documenting the `u32` primitive with one inherent impl.
+fn primitive_fixture_crate() -> Crate {
+	let root = Id(0);
+	let primitive = Id(1);
+	let impl_id = Id(2);
+	let count_ones = Id(3);
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![primitive],
+				is_stripped: false,
+			}),
+		),
+		Item {
+			docs: Some("The 32-bit unsigned integer type.\n\nSee also `i32`.".into()),
+			..fixture_item(
+				primitive,
+				"u32",
+				Vec::new(),
+				ItemEnum::Primitive(Primitive {
+					name: "u32".into(),
+					impls: vec![impl_id],
+				}),
+			)
+		},
+		Item {
+			name: None,
+			visibility: Visibility::Default,
+			..fixture_item(
+				impl_id,
+				"",
+				Vec::new(),
+				ItemEnum::Impl(Impl {
+					is_unsafe: false,
+					generics: empty_generics(),
+					provided_trait_methods: Vec::new(),
+					trait_: None,
+					for_: Type::Primitive("u32".into()),
+					items: vec![count_ones],
+					is_negative: false,
+					is_synthetic: false,
+					blanket_impl: None,
+				}),
+			)
+		},
+		fixture_item(
+			count_ones,
+			"count_ones",
+			Vec::new(),
+			ItemEnum::Function(Function {
+				sig: FunctionSignature {
+					inputs: vec![("self".into(), Type::Generic("Self".into()))],
+					output: Some(Type::Primitive("u32".into())),
+					is_c_variadic: false,
+				},
+				generics: empty_generics(),
+				header: default_header(),
+				has_body: true,
+			}),
+		),
+	];
+
+	Crate {
+		crate_version: None,
+		..fixture_crate_of(items)
+	}
+}
+
+#[test]
+fn primitives_render_as_comments_followed_by_their_impls() {
+	let crate_data = primitive_fixture_crate();
+	let (rendered, report) = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.render_with_report(&crate_data)
+		.unwrap();
+	assert!(rendered.contains("/* primitive u32 */"), "{rendered}");
+	let doc = [
+		"// The 32-bit unsigned integer type.",
+		"//",
+		"// See also `i32`.",
+	];
+	assert!(
+		trimmed_lines(&rendered)
+			.windows(doc.len())
+			.any(|lines| lines == doc),
+		"{rendered}"
+	);
+	assert!(rendered.contains("impl u32 {"), "{rendered}");
+	assert!(
+		rendered.contains("pub fn count_ones(self) -> u32"),
+		"{rendered}"
+	);
+	assert!(!rendered.contains("///"), "{rendered}");
+	syn::parse_file(&rendered).expect("primitive skeleton parses");
+	assert!(report.is_empty(), "{report}");
+
+	let (_, json_report) = Renderer::default()
+		.with_format(RenderFormat::Json)
+		.render_with_report(&crate_data)
+		.unwrap();
+	assert!(json_report.is_empty(), "{json_report}");
+}
+
+#[test]
+fn primitives_and_their_methods_are_indexed() {
+	let index = SearchIndex::build(&primitive_fixture_crate(), false, None);
+	let primitive = index.find_relative("u32").expect("primitive entry");
+	assert_eq!(primitive.kind, SearchItemKind::Primitive);
+	assert_eq!(primitive.signature.as_deref(), Some("primitive u32"));
+
+	let method = index
+		.find_relative("u32::count_ones")
+		.expect("method entry");
+	assert_eq!(method.kind, SearchItemKind::Method);
+	assert_eq!(method.path_string, "fixture::u32::count_ones");
+}
+
+fn facade_fixture_crate() -> Crate {
+	let root = Id(0);
+	let reexport = Id(1);
+	let ready = Id(2);
+	let local = Id(3);
+
+	let items = [
+		fixture_item(
+			root,
+			"facade",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![reexport, local],
+				is_stripped: false,
+			}),
+		),
+		fixture_item(
+			reexport,
+			"Ready",
+			Vec::new(),
+			ItemEnum::Use(Use {
+				source: "futures_core::Ready".into(),
+				name: "Ready".into(),
+				id: Some(ready),
+				is_glob: false,
+			}),
+		),
+		Item {
+			crate_id: 1,
+			docs: Some("A value that is immediately ready.".into()),
+			..fixture_item(
+				ready,
+				"Ready",
+				Vec::new(),
+				ItemEnum::Struct(Struct {
+					kind: StructKind::Unit,
+					generics: empty_generics(),
+					impls: Vec::new(),
+				}),
+			)
+		},
+		fixture_item(
+			local,
+			"poll",
+			Vec::new(),
+			ItemEnum::Function(Function {
+				sig: FunctionSignature {
+					inputs: Vec::new(),
+					output: None,
+					is_c_variadic: false,
+				},
+				generics: empty_generics(),
+				header: default_header(),
+				has_body: true,
+			}),
+		),
+	];
+	let futures_core = serde_json::from_value(serde_json::json!({
+		"name": "futures_core",
+		"html_root_url": null,
+		"path": "/deps/libfutures_core.rlib",
+	}))
+	.unwrap();
+
+	Crate {
+		external_crates: HashMap::from([(1, futures_core)]),
+		..fixture_crate_of(items)
+	}
+}
+
+/// A facade re-exporting a derive, an attribute macro and a function-like macro from a
+/// proc-macro crate, whose definitions rustdoc only records in `paths`.
+fn proc_macro_facade_fixture_crate() -> Crate {
+	let uses = [
+		(Id(1), "serde_derive::Serialize", "Serialize", Id(11)),
+		(Id(2), "serde_derive::with", "with", Id(12)),
+		(Id(3), "serde_derive::fields", "field_names", Id(13)),
+	];
+	let mut items: Vec<Item> = uses
+		.iter()
+		.map(|(id, source, name, target)| {
+			fixture_item(
+				*id,
+				name,
+				Vec::new(),
+				ItemEnum::Use(Use {
+					source: (*source).into(),
+					name: (*name).into(),
+					id: Some(*target),
+					is_glob: false,
+				}),
+			)
+		})
+		.collect();
+	items.push(fixture_item(
+		Id(0),
+		"facade",
+		Vec::new(),
+		ItemEnum::Module(Module {
+			is_crate: true,
+			items: uses.iter().map(|(id, ..)| *id).collect(),
+			is_stripped: false,
+		}),
+	));
+	let paths: HashMap<Id, ItemSummary> = serde_json::from_value(serde_json::json!({
+		"11": { "crate_id": 1, "path": ["serde_derive", "Serialize"], "kind": "proc_derive" },
+		"12": { "crate_id": 1, "path": ["serde_derive", "with"], "kind": "proc_attribute" },
+		"13": { "crate_id": 1, "path": ["serde_derive", "fields"], "kind": "macro" },
+	}))
+	.unwrap();
+
+	Crate {
+		index: items.into_iter().map(|item| (item.id, item)).collect(),
+		paths,
+		..facade_fixture_crate()
+	}
+}
+
+#[test]
+fn external_macro_reexports_name_their_kind_and_origin() {
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.render(&proc_macro_facade_fixture_crate())
+		.unwrap();
+	let lines = trimmed_lines(&rendered);
+	for expected in [
+		[
+			"// derive macro serde_derive::Serialize, used as `#[derive(Serialize)]`",
+			"pub use serde_derive::Serialize;",
+		],
+		[
+			"// attribute macro serde_derive::with, used as `#[with]`",
+			"pub use serde_derive::with;",
+		],
+		[
+			"// macro serde_derive::fields, used as `field_names!(..)`",
+			"pub use serde_derive::fields as field_names;",
+		],
+	] {
+		assert!(
+			lines.windows(2).any(|pair| pair == expected),
+			"{expected:?}\n{rendered}"
+		);
+	}
+	syn::parse_file(&rendered).unwrap();
+}
+
+/// A crate whose only function returns a tuple nested `depth` levels deep.
+fn deep_type_fixture_crate(depth: usize) -> Crate {
+	let root = Id(0);
+	let deep = Id(1);
+	let mut output = Type::Primitive("u8".into());
+	for _ in 0..depth {
+		output = Type::Tuple(vec![Type::Primitive("u8".into()), output]);
+	}
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![deep],
+				is_stripped: false,
+			}),
+		),
+		fixture_item(
+			deep,
+			"deep",
+			Vec::new(),
+			ItemEnum::Function(Function {
+				sig: FunctionSignature {
+					inputs: Vec::new(),
+					output: Some(output),
+					is_c_variadic: false,
+				},
+				generics: empty_generics(),
+				header: default_header(),
+				has_body: true,
+			}),
+		),
+	];
+
+	fixture_crate_of(items)
+}
+
+#[test]
+fn deeply_nested_types_are_elided_past_the_depth_limit() {
+	let crate_data = deep_type_fixture_crate(100);
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.render(&crate_data)
+		.unwrap();
+	assert!(rendered.contains("_ /* … */"), "{rendered}");
+	// The outermost tuple sits at depth zero, so the deepest `u8` kept is one level short of
+	// the limit.
+	assert_eq!(
+		rendered.matches("u8").count(),
+		ripdoc_render::DEFAULT_MAX_TYPE_DEPTH - 1,
+		"{rendered}"
+	);
+
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_max_type_depth(3)
+		.render(&crate_data)
+		.unwrap();
+	assert_eq!(rendered.matches("u8").count(), 2, "{rendered}");
+
+	let shallow = deep_type_fixture_crate(2);
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.render(&shallow)
+		.unwrap();
+	assert!(!rendered.contains("/* … */"), "{rendered}");
+}
+
+fn reexport_fixture_crate() -> Crate {
+	let root = Id(0);
+	let inner = Id(1);
+	let thing = Id(2);
+	let reexport = Id(3);
+	let helper = Id(4);
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![inner, reexport],
+				is_stripped: false,
+			}),
+		),
+		Item {
+			visibility: Visibility::Crate,
+			..fixture_item(
+				inner,
+				"inner",
+				Vec::new(),
+				ItemEnum::Module(Module {
+					is_crate: false,
+					items: vec![thing, helper],
+					is_stripped: false,
+				}),
+			)
+		},
+		fixture_item(
+			thing,
+			"Thing",
+			Vec::new(),
+			ItemEnum::Struct(Struct {
+				kind: StructKind::Unit,
+				generics: empty_generics(),
+				impls: Vec::new(),
+			}),
+		),
+		fixture_item(
+			reexport,
+			"Thing",
+			Vec::new(),
+			ItemEnum::Use(Use {
+				source: "inner::Thing".into(),
+				name: "Thing".into(),
+				id: Some(thing),
+				is_glob: false,
+			}),
+		),
+		fixture_item(
+			helper,
+			"helper",
+			Vec::new(),
+			ItemEnum::Function(Function {
+				sig: FunctionSignature {
+					inputs: Vec::new(),
+					output: None,
+					is_c_variadic: false,
+				},
+				generics: empty_generics(),
+				header: default_header(),
+				has_body: true,
+			}),
+		),
+	];
+
+	fixture_crate_of(items)
+}
+
+#[test]
+fn index_displays_reexported_items_under_their_public_path() {
+	let index = SearchIndex::build(&reexport_fixture_crate(), true, None);
+
+	let thing = index.get(&Id(2)).expect("Thing entry");
+	assert_eq!(thing.path_string, "fixture::Thing");
+	assert_eq!(thing.definition_path, "fixture::inner::Thing");
+	// Filter paths still follow the module tree.
+	assert_eq!(
+		index
+			.find_relative("inner::Thing")
+			.map(|entry| entry.item_id),
+		Some(Id(2))
+	);
+
+	// Items without a public path keep their definition path.
+	let helper = index.get(&Id(4)).expect("helper entry");
+	assert_eq!(helper.path_string, "fixture::inner::helper");
+	assert_eq!(helper.path_string, helper.definition_path);
+}
+
+#[test]
+fn origin_comments_name_the_crate_of_inlined_items() {
+	let crate_data = facade_fixture_crate();
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_origin_comments(true)
+		.render(&crate_data)
+		.unwrap();
+	assert!(
+		rendered.contains("pub struct Ready; // from futures_core\n"),
+		"{rendered}"
+	);
+	assert!(rendered.contains("/// A value that is immediately ready."));
+	assert!(rendered.contains("pub fn poll() {}\n"), "{rendered}");
+	assert_eq!(rendered.matches("// from").count(), 1, "{rendered}");
+
+	let plain = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.render(&crate_data)
+		.unwrap();
+	assert!(plain.contains("pub struct Ready;\n"), "{plain}");
+	assert!(!plain.contains("// from"), "{plain}");
+}
+
+fn breadcrumb_fixture_crate() -> Crate {
+	let root = Id(0);
+	let outer = Id(1);
+	let first = Id(2);
+	let inner = Id(3);
+	let deep = Id(4);
+	let after = Id(5);
+	let last = Id(6);
+	let module = |items| {
+		ItemEnum::Module(Module {
+			is_crate: false,
+			items,
+			is_stripped: false,
+		})
+	};
+	let function = || {
+		ItemEnum::Function(Function {
+			sig: FunctionSignature {
+				inputs: Vec::new(),
+				output: None,
+				is_c_variadic: false,
+			},
+			generics: empty_generics(),
+			header: default_header(),
+			has_body: true,
+		})
+	};
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![outer, last],
+				is_stripped: false,
+			}),
+		),
+		fixture_item(
+			outer,
+			"outer",
+			Vec::new(),
+			module(vec![first, inner, after]),
+		),
+		fixture_item(first, "first", Vec::new(), function()),
+		fixture_item(inner, "inner", Vec::new(), module(vec![deep])),
+		fixture_item(deep, "deep", Vec::new(), function()),
+		fixture_item(after, "after", Vec::new(), function()),
+		fixture_item(last, "last", Vec::new(), function()),
+	];
+
+	fixture_crate_of(items)
+}
+
+/// Lines of `text` without indentation, so expectations don't depend on the formatter's indent.
+fn trimmed_lines(text: &str) -> Vec<&str> {
+	text.lines().map(str::trim).collect()
+}
+
+#[test]
+fn breadcrumbs_name_the_enclosing_module_in_long_bodies() {
+	let crate_data = breadcrumb_fixture_crate();
+	let render = |renderer: Renderer| renderer.render(&crate_data).unwrap();
+	let rust = || Renderer::default().with_format(RenderFormat::Rust);
+
+	let every_item = render(rust().with_breadcrumbs(true).with_breadcrumb_lines(0));
+	assert_eq!(
+		trimmed_lines(&every_item),
+		trimmed_lines(
+			"pub mod fixture {
+    // path: fixture
+    pub mod outer {
+        // path: fixture::outer
+        pub fn first() {}
+
+        // path: fixture::outer
+        pub mod inner {
+            // path: fixture::outer::inner
+            pub fn deep() {}
+        }
+
+        // path: fixture::outer
+        pub fn after() {}
+    }
+
+    // path: fixture
+    pub fn last() {}
+}
+"
+		)
+	);
+
+	let short_bodies = render(rust().with_breadcrumbs(true));
+	assert_eq!(
+		trimmed_lines(&short_bodies),
+		trimmed_lines(
+			"pub mod fixture {
+    // path: fixture
+    pub mod outer {
+        // path: fixture::outer
+        pub fn first() {}
+
+        pub mod inner {
+            // path: fixture::outer::inner
+            pub fn deep() {}
+        }
+
+        pub fn after() {}
+    }
+
+    pub fn last() {}
+}
+"
+		)
+	);
+
+	let plain = render(rust());
+	assert_eq!(
+		trimmed_lines(&plain),
+		trimmed_lines(
+			"pub mod fixture {
+    pub mod outer {
+        pub fn first() {}
+
+        pub mod inner {
+            pub fn deep() {}
+        }
+
+        pub fn after() {}
+    }
+
+    pub fn last() {}
+}
+"
+		)
+	);
+
+	let markdown = render(
+		Renderer::default()
+			.with_breadcrumbs(true)
+			.with_breadcrumb_lines(0),
+	);
+	assert!(!markdown.contains("// path:"), "{markdown}");
+}
+
+fn async_trait_fixture_crate() -> Crate {
+	let root = Id(0);
+	let store = Id(1);
+	let fetch = Id(2);
+	let poll_boxed = Id(3);
+	let path = |path: &str, args: Option<GenericArgs>| Path {
+		path: path.into(),
+		id: Id(99),
+		args: args.map(Box::new),
+	};
+	let wrap = |name: &str, ty: Type| {
+		Type::ResolvedPath(path(
+			name,
+			Some(GenericArgs::AngleBracketed {
+				args: vec![GenericArg::Type(ty)],
+				constraints: Vec::new(),
+			}),
+		))
+	};
+	let poly = |trait_| PolyTrait {
+		trait_,
+		generic_params: Vec::new(),
+	};
+	let boxed_future = |lifetime: Option<&str>| {
+		let future = path(
+			"Future",
+			Some(GenericArgs::AngleBracketed {
+				args: Vec::new(),
+				constraints: vec![AssocItemConstraint {
+					name: "Output".into(),
+					args: None,
+					binding: AssocItemConstraintKind::Equality(Term::Type(Type::Primitive(
+						"u32".into(),
+					))),
+				}],
+			}),
+		);
+		let dyn_future = Type::DynTrait(DynTrait {
+			traits: vec![poly(future), poly(path("Send", None))],
+			lifetime: lifetime.map(Into::into),
+		});
+		wrap("::core::pin::Pin", wrap("Box", dyn_future))
+	};
+	let self_ref = |lifetime: Option<&str>| {
+		(
+			"self".to_string(),
+			Type::BorrowedRef {
+				lifetime: lifetime.map(Into::into),
+				is_mutable: false,
+				type_: Box::new(Type::Generic("Self".into())),
+			},
+		)
+	};
+	let lifetime_param = |name: &str| GenericParamDef {
+		name: name.into(),
+		kind: GenericParamDefKind::Lifetime {
+			outlives: Vec::new(),
+		},
+	};
+
+	// What `#[async_trait]` expands `async fn fetch(&self, key: u32) -> u32` into
+	let desugared = Function {
+		sig: FunctionSignature {
+			inputs: vec![
+				self_ref(Some("'life0")),
+				("key".into(), Type::Primitive("u32".into())),
+			],
+			output: Some(boxed_future(Some("'async_trait"))),
+			is_c_variadic: false,
+		},
+		generics: Generics {
+			params: vec![lifetime_param("'life0"), lifetime_param("'async_trait")],
+			where_predicates: vec![
+				WherePredicate::LifetimePredicate {
+					lifetime: "'life0".into(),
+					outlives: vec!["'async_trait".into()],
+				},
+				WherePredicate::BoundPredicate {
+					type_: Type::Generic("Self".into()),
+					bounds: vec![GenericBound::Outlives("'async_trait".into())],
+					generic_params: Vec::new(),
+				},
+			],
+		},
+		header: default_header(),
+		has_body: false,
+	};
+	// A boxed future written by hand, without the macro's lifetimes
+	let hand_written = Function {
+		sig: FunctionSignature {
+			inputs: vec![self_ref(None)],
+			output: Some(boxed_future(None)),
+			is_c_variadic: false,
+		},
+		generics: empty_generics(),
+		header: default_header(),
+		has_body: false,
+	};
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![store],
+				is_stripped: false,
+			}),
+		),
+		fixture_item(
+			store,
+			"Store",
+			Vec::new(),
+			ItemEnum::Trait(Trait {
+				is_auto: false,
+				is_unsafe: false,
+				is_dyn_compatible: true,
+				items: vec![fetch, poll_boxed],
+				generics: empty_generics(),
+				bounds: Vec::new(),
+				implementations: Vec::new(),
+			}),
+		),
+		// Trait items carry no visibility of their own
+		Item {
+			visibility: Visibility::Default,
+			..fixture_item(fetch, "fetch", Vec::new(), ItemEnum::Function(desugared))
+		},
+		Item {
+			visibility: Visibility::Default,
+			..fixture_item(
+				poll_boxed,
+				"poll_boxed",
+				Vec::new(),
+				ItemEnum::Function(hand_written),
+			)
+		},
+	];
+
+	fixture_crate_of(items)
+}
+
+#[test]
+fn async_trait_methods_render_as_async_fns() {
+	let crate_data = async_trait_fixture_crate();
+	let render = |resugar: bool| {
+		Renderer::default()
+			.with_format(RenderFormat::Rust)
+			.with_resugar_async(resugar)
+			.render(&crate_data)
+			.unwrap()
+	};
+
+	let rendered = render(true);
+	let lines: Vec<&str> = rendered.lines().map(str::trim).collect();
+	assert!(
+		lines.windows(2).any(|pair| pair
+			== [
+				"// via #[async_trait]",
+				"async fn fetch(&self, key: u32) -> u32;"
+			]),
+		"{rendered}"
+	);
+	assert!(!rendered.contains("'life0"), "{rendered}");
+	// The hand-written boxed future has no `'async_trait` lifetime and keeps its signature
+	assert!(
+		rendered.contains(
+			"fn poll_boxed(&self) -> ::core::pin::Pin<Box<dyn Future<Output = u32> + Send>>;"
+		),
+		"{rendered}"
+	);
+	assert_eq!(
+		rendered.matches("// via #[async_trait]").count(),
+		1,
+		"{rendered}"
+	);
+
+	let desugared = render(false);
+	assert!(!desugared.contains("// via"), "{desugared}");
+	assert!(desugared.contains("'async_trait"), "{desugared}");
+}
+
+fn mismatched_field_fixture_crate() -> Crate {
+	let root = Id(0);
+	let config = Id(1);
+	let len = Id(2);
+
+	let items = [
+		fixture_item(
+			root,
+			"fixture",
+			Vec::new(),
+			ItemEnum::Module(Module {
+				is_crate: true,
+				items: vec![config],
+				is_stripped: false,
+			}),
+		),
+		// The struct lists a field whose id actually belongs to a function
+		fixture_item(
+			config,
+			"Config",
+			Vec::new(),
+			ItemEnum::Struct(Struct {
+				kind: StructKind::Plain {
+					fields: vec![len],
+					has_stripped_fields: false,
+				},
+				generics: empty_generics(),
+				impls: Vec::new(),
+			}),
+		),
+		fixture_item(
+			len,
+			"len",
+			Vec::new(),
+			ItemEnum::Function(Function {
+				sig: FunctionSignature {
+					inputs: Vec::new(),
+					output: None,
+					is_c_variadic: false,
+				},
+				generics: empty_generics(),
+				header: default_header(),
+				has_body: true,
+			}),
+		),
+	];
+
+	fixture_crate_of(items)
+}
+
+#[test]
+fn mismatched_child_items_are_reported_instead_of_panicking() {
+	let crate_data = mismatched_field_fixture_crate();
+	let (rendered, report) = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.render_with_report(&crate_data)
+		.unwrap();
+	assert!(rendered.contains("pub struct Config {}"), "{rendered}");
+	assert!(!rendered.contains("len"), "{rendered}");
+	assert_eq!(
+		report.mismatched(),
+		[MismatchedItem {
+			id: Id(2),
+			name: "len".to_string(),
+			expected: "struct field",
+			found: "function",
+		}]
+	);
+	assert_eq!(report.to_string(), "1 mismatched item");
+	assert_eq!(
+		report.mismatched()[0].to_string(),
+		"`len` is a function, not a struct field"
+	);
+
+	let (skeleton, json_report) = Renderer::default()
+		.with_format(RenderFormat::Json)
+		.render_with_report(&crate_data)
+		.unwrap();
+	assert!(!skeleton.contains("\"len\""), "{skeleton}");
+	assert_eq!(json_report, report);
+}
@@ -0,0 +1,179 @@
+//! Crate listings nested into a tree: modules containing their items, types their members.
+
+use std::collections::{HashMap, HashSet};
+
+use rustdoc_types::Id;
+
+use crate::search::{SearchIndex, SearchItemKind, SearchPathSegment, SearchResult, SourceLocation};
+
+/// Item in a tree view of a crate, with the items it contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+	/// Kind classification for the item.
+	pub kind: SearchItemKind,
+	/// Path relative to the parent node: usually a single name, but `Paintable::paint` for a
+	/// method of a trait impl.
+	pub name: String,
+	/// Canonical path rendered as a `::` separated string.
+	pub path: String,
+	/// Source location for the item if available.
+	pub source: Option<SourceLocation>,
+	/// Contained items, sorted by kind and then by name.
+	pub children: Vec<TreeNode>,
+}
+
+/// Nest the indexed items by their definition paths.
+///
+/// An item's parent is the closest indexed item its path passes through: the module holding a
+/// struct, or the struct whose impl defines a method. With `selected`, only those items and
+/// their ancestors appear, like the context of a render selection. `use` items are left out.
+pub fn build_tree(index: &SearchIndex, selected: Option<&[Id]>) -> Vec<TreeNode> {
+	let mut children: HashMap<Option<Id>, Vec<(&SearchResult, String)>> = HashMap::new();
+	let mut parents = HashMap::new();
+	for entry in index.entries() {
+		if entry.kind == SearchItemKind::Use {
+			continue;
+		}
+		let (parent, name) = parent_of(index, entry);
+		parents.insert(entry.item_id, parent);
+		children.entry(parent).or_default().push((entry, name));
+	}
+
+	let kept = selected.map(|selected| {
+		let mut kept = HashSet::new();
+		for id in selected {
+			let mut next = Some(*id);
+			while let Some(id) = next.filter(|id| parents.contains_key(id)) {
+				if !kept.insert(id) {
+					break;
+				}
+				next = parents[&id];
+			}
+		}
+		kept
+	});
+	nodes(&children, None, kept.as_ref())
+}
+
+/// Closest indexed ancestor of `entry` and the path from it to `entry`.
+///
+/// Segments whose item sits elsewhere, like the trait of a trait impl, are skipped: the
+/// ancestor's own path must be the start of `entry`'s.
+fn parent_of(index: &SearchIndex, entry: &SearchResult) -> (Option<Id>, String) {
+	for depth in (0..entry.path.len().saturating_sub(1)).rev() {
+		let Some(parent) = entry.path[depth].id.and_then(|id| index.get(&id)) else {
+			continue;
+		};
+		let leads_here = parent.kind != SearchItemKind::Use
+			&& parent
+				.path
+				.iter()
+				.map(|segment| segment.id)
+				.eq(entry.path[..=depth].iter().map(|segment| segment.id));
+		if leads_here {
+			return (Some(parent.item_id), join_names(&entry.path[depth + 1..]));
+		}
+	}
+	(None, join_names(&entry.path))
+}
+
+/// Display names of `segments`, joined with `::`.
+fn join_names(segments: &[SearchPathSegment]) -> String {
+	segments
+		.iter()
+		.map(|segment| segment.display_name.as_str())
+		.collect::<Vec<_>>()
+		.join("::")
+}
+
+/// Sorted nodes for the children of `parent`, limited to `kept` when given.
+fn nodes(
+	children: &HashMap<Option<Id>, Vec<(&SearchResult, String)>>,
+	parent: Option<Id>,
+	kept: Option<&HashSet<Id>>,
+) -> Vec<TreeNode> {
+	let Some(entries) = children.get(&parent) else {
+		return Vec::new();
+	};
+	let mut level: Vec<TreeNode> = entries
+		.iter()
+		.filter(|(entry, _)| kept.is_none_or(|kept| kept.contains(&entry.item_id)))
+		.map(|(entry, name)| TreeNode {
+			kind: entry.kind,
+			name: name.clone(),
+			path: entry.path_string.clone(),
+			source: entry.source.clone(),
+			children: nodes(children, Some(entry.item_id), kept),
+		})
+		.collect();
+	level.sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+	level
+}
+
+/// Lines connecting a node to its parent and siblings.
+struct Branches {
+	/// Before a node followed by a sibling.
+	middle: &'static str,
+	/// Before the last node of a level.
+	last: &'static str,
+	/// Below a node followed by a sibling, in front of its descendants.
+	through: &'static str,
+	/// Below the last node of a level.
+	blank: &'static str,
+}
+
+/// Box-drawing branches, for terminals.
+const UNICODE_BRANCHES: Branches = Branches {
+	middle: "├── ",
+	last: "└── ",
+	through: "│   ",
+	blank: "    ",
+};
+
+/// Plain ASCII branches, for pipes and files.
+const ASCII_BRANCHES: Branches = Branches {
+	middle: "|-- ",
+	last: "`-- ",
+	through: "|   ",
+	blank: "    ",
+};
+
+/// Draw `roots` as an indented hierarchy, one `kind name` line per node.
+///
+/// Branches use box-drawing characters when `unicode` is set and ASCII otherwise.
+pub fn render_tree(roots: &[TreeNode], unicode: bool) -> String {
+	let branches = if unicode {
+		&UNICODE_BRANCHES
+	} else {
+		&ASCII_BRANCHES
+	};
+	let mut output = String::new();
+	for root in roots {
+		output.push_str(&format!("{} {}\n", root.kind.label(), root.name));
+		render_children(&mut output, &root.children, "", branches);
+	}
+	output
+}
+
+/// Append the lines for `nodes`, each indented by `prefix`.
+fn render_children(output: &mut String, nodes: &[TreeNode], prefix: &str, branches: &Branches) {
+	for (i, node) in nodes.iter().enumerate() {
+		let is_last = i + 1 == nodes.len();
+		let (branch, below) = if is_last {
+			(branches.last, branches.blank)
+		} else {
+			(branches.middle, branches.through)
+		};
+		output.push_str(&format!(
+			"{prefix}{branch}{} {}\n",
+			node.kind.label(),
+			node.name
+		));
+		render_children(
+			output,
+			&node.children,
+			&format!("{prefix}{below}"),
+			branches,
+		);
+	}
+}
@@ -0,0 +1,240 @@
+use std::fmt;
+
+#[cfg(feature = "cargo-resolution")]
+use crate::error::CargoError;
+use crate::error::RipdocError;
+
+/// What to do about an error: a short instruction and the commands that carry it out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Remedy {
+	/// One-line instruction, such as `install the nightly toolchain`.
+	pub summary: String,
+	/// Commands to run, in order; may be empty when there is nothing to run.
+	pub commands: Vec<String>,
+}
+
+#[cfg(feature = "cargo-resolution")]
+impl Remedy {
+	/// A remedy carried out by `commands`.
+	fn new(summary: impl Into<String>, commands: Vec<String>) -> Self {
+		Self {
+			summary: summary.into(),
+			commands,
+		}
+	}
+}
+
+impl fmt::Display for Remedy {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "help: {}", self.summary)?;
+		for command in &self.commands {
+			write!(f, "\n    $ {command}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Advice for `err`, when it is one of the failures new users commonly run into.
+///
+/// Errors whose message already says what to do, and those with no general fix, have none.
+pub fn remedy(err: &RipdocError) -> Option<Remedy> {
+	match err {
+		#[cfg(feature = "cargo-resolution")]
+		RipdocError::Cargo(err) => cargo_remedy(err),
+		_ => None,
+	}
+}
+
+/// Advice for an error from target resolution or the rustdoc build.
+#[cfg(feature = "cargo-resolution")]
+fn cargo_remedy(err: &CargoError) -> Option<Remedy> {
+	match err {
+		CargoError::ToolchainMissing { toolchain } => Some(Remedy::new(
+			format!("install the {toolchain} toolchain, which generates rustdoc JSON"),
+			vec![format!("rustup toolchain install {toolchain}")],
+		)),
+		CargoError::TargetMissing { target } => Some(Remedy::new(
+			format!("install the standard library for {target}"),
+			vec![format!("rustup target add {target} --toolchain nightly")],
+		)),
+		CargoError::NotCached {
+			name,
+			version,
+			cached,
+		} => Some(match cached.last() {
+			Some(newest) => Remedy::new(
+				format!(
+					"use a release that is cached, or run without --offline to download '{name}'"
+				),
+				vec![format!("ripdoc {name}@{newest} --offline")],
+			),
+			None => {
+				let spec = match version {
+					Some(version) => format!("{name}@{version}"),
+					None => name.clone(),
+				};
+				Remedy::new(
+					format!(
+						"resolve '{spec}' once while online to download it; offline runs then \
+						 reuse the download"
+					),
+					vec![format!("ripdoc '{spec}' --resolve-only")],
+				)
+			}
+		}),
+		CargoError::FormatVersionMismatch { found, supported } if found > supported => {
+			Some(Remedy::new(
+				"update ripdoc to read the rustdoc JSON of newer nightlies",
+				vec!["cargo install ripdoc".into()],
+			))
+		}
+		CargoError::FormatVersionMismatch { .. } => Some(Remedy::new(
+			"update the nightly toolchain to one writing the rustdoc JSON ripdoc reads",
+			vec!["rustup update nightly".into()],
+		)),
+		CargoError::BuildFailed(message) => build_remedy(message),
+		_ => None,
+	}
+}
+
+/// Advice for a failed rustdoc build, from the message describing it.
+#[cfg(feature = "cargo-resolution")]
+fn build_remedy(message: &str) -> Option<Remedy> {
+	// Crashes and out-of-memory kills already say what to try
+	if message.contains("internal compiler error") || message.contains("killed by signal") {
+		return None;
+	}
+	if message.contains("the build script of `") {
+		return Some(build_script_remedy(message));
+	}
+	if message.contains("unstable features") {
+		return Some(Remedy::new(
+			"update the nightly toolchain, or pin one from around the crate's release with \
+			 --toolchain nightly-YYYY-MM-DD",
+			vec!["rustup update nightly".into()],
+		));
+	}
+	Some(Remedy::new(
+		"rustdoc builds the package on its own, without features other workspace members turn \
+		 on; check that it builds that way, and enable what it needs with --features",
+		vec!["cargo +nightly check --lib".into()],
+	))
+}
+
+/// Advice for a failed build script, following the reason the message gives for it.
+#[cfg(feature = "cargo-resolution")]
+fn build_script_remedy(message: &str) -> Remedy {
+	let summary = if message.contains("could not reach the network") {
+		"drop --offline so the build script can download what it needs, or turn off the feature \
+		 that runs it with --no-default-features and --features"
+	} else if message.contains("system library") {
+		"install the library's development package, or turn off the feature that needs it with \
+		 --no-default-features and --features"
+	} else {
+		"turn off the feature that runs the build script with --no-default-features and \
+		 --features; if the script downloads or generates files, drop --offline"
+	};
+	Remedy::new(summary, Vec::new())
+}
+
+#[cfg(all(test, feature = "cargo-resolution"))]
+mod tests {
+	use super::*;
+
+	fn remedy_for(err: CargoError) -> Remedy {
+		remedy(&RipdocError::Cargo(err)).expect("a remedy")
+	}
+
+	#[test]
+	fn missing_toolchain_is_installed_with_rustup() {
+		let remedy = remedy_for(CargoError::ToolchainMissing {
+			toolchain: "nightly-2024-10-01".into(),
+		});
+		assert_eq!(
+			remedy.commands,
+			["rustup toolchain install nightly-2024-10-01"]
+		);
+		assert_eq!(
+			remedy.to_string(),
+			"help: install the nightly-2024-10-01 toolchain, which generates rustdoc JSON\n    \
+			 $ rustup toolchain install nightly-2024-10-01"
+		);
+	}
+
+	#[test]
+	fn missing_target_is_added_with_rustup() {
+		let remedy = remedy_for(CargoError::TargetMissing {
+			target: "thumbv7em-none-eabihf".into(),
+		});
+		assert_eq!(
+			remedy.commands,
+			["rustup target add thumbv7em-none-eabihf --toolchain nightly"]
+		);
+	}
+
+	#[test]
+	fn offline_misses_point_at_cached_releases_or_a_download() {
+		let remedy = remedy_for(CargoError::NotCached {
+			name: "serde".into(),
+			version: Some("^2".into()),
+			cached: vec!["1.0.100".into(), "1.0.210".into()],
+		});
+		assert_eq!(remedy.commands, ["ripdoc serde@1.0.210 --offline"]);
+
+		let remedy = remedy_for(CargoError::NotCached {
+			name: "serde".into(),
+			version: Some("^2".into()),
+			cached: Vec::new(),
+		});
+		assert_eq!(remedy.commands, ["ripdoc 'serde@^2' --resolve-only"]);
+	}
+
+	#[test]
+	fn format_version_mismatches_update_the_older_side() {
+		let newer = remedy_for(CargoError::FormatVersionMismatch {
+			found: 60,
+			supported: 56,
+		});
+		assert_eq!(newer.commands, ["cargo install ripdoc"]);
+		let older = remedy_for(CargoError::FormatVersionMismatch {
+			found: 50,
+			supported: 56,
+		});
+		assert_eq!(older.commands, ["rustup update nightly"]);
+	}
+
+	#[test]
+	fn build_failures_suggest_checking_features_unless_already_explained() {
+		let advice = remedy_for(CargoError::BuildFailed(
+			"Failed to build rustdoc JSON: error[E0433]: failed to resolve: use of undeclared \
+			 crate or module `tokio`"
+				.into(),
+		));
+		assert!(advice.summary.contains("--features"), "{advice}");
+
+		let crashed = CargoError::BuildFailed(
+			"Failed to build rustdoc JSON: rustdoc crashed with an internal compiler error".into(),
+		);
+		assert_eq!(remedy(&RipdocError::Cargo(crashed)), None);
+		assert_eq!(remedy(&RipdocError::Config("bad".into())), None);
+	}
+
+	#[test]
+	fn build_script_failures_suggest_features_or_going_online() {
+		let offline = remedy_for(CargoError::BuildFailed(
+			"Failed to build rustdoc JSON: the build script of `onnxruntime-sys v0.0.14` failed \
+			 because it could not reach the network."
+				.into(),
+		));
+		assert!(offline.summary.starts_with("drop --offline"), "{offline}");
+		assert!(offline.summary.contains("--features"), "{offline}");
+		assert!(offline.commands.is_empty());
+
+		let library = remedy_for(CargoError::BuildFailed(
+			"Failed to build rustdoc JSON: the build script of `libgit2-sys v0.16.2` failed \
+			 because the system library `libgit2` was not found."
+				.into(),
+		));
+		assert!(library.summary.starts_with("install"), "{library}");
+	}
+}
@@ -0,0 +1,118 @@
+//! Integration tests for rendering within a size budget.
+
+#![cfg(feature = "cargo-resolution")]
+
+mod utils;
+
+#[cfg(test)]
+mod tests {
+	use ripdoc_core::{RenderBudget, RenderFormat, Renderer};
+	use rust_format::{Formatter, RustFmt};
+	use rustdoc_types::Crate;
+
+	use super::utils::inspect_crate;
+
+	/// Source for a crate with documented, wide structs spread over nested modules.
+	fn large_source() -> String {
+		let mut source = String::from("//! Budget fixture.\n\n");
+		for outer in 0..4 {
+			source.push_str(&format!(
+				"/// Outer module {outer}.\npub mod outer{outer} {{\n"
+			));
+			source.push_str("    /// Inner module.\n    pub mod inner {\n");
+			for index in 0..5 {
+				source.push_str(&format!(
+					"        /// Record {index}, with enough fields to be elided.\n        \
+					 pub struct Record{index} {{\n"
+				));
+				for field in 0..12 {
+					source.push_str(&format!(
+						"            /// Field {field} of the record.\n            \
+						 pub field{field}: u32,\n"
+					));
+				}
+				source.push_str("        }\n");
+			}
+			source.push_str("    }\n\n    /// A top-level function.\n    pub fn run() {}\n}\n\n");
+		}
+		source
+	}
+
+	fn render(crate_data: &Crate, budget: Option<RenderBudget>) -> String {
+		Renderer::default()
+			.with_format(RenderFormat::Rust)
+			.with_budget(budget)
+			.render(crate_data)
+			.unwrap()
+	}
+
+	#[test]
+	fn large_crate_fits_byte_budget_as_valid_rust() {
+		let crate_data = inspect_crate(&large_source(), false, false);
+		let full = render(&crate_data, None);
+		let budget = full.len() / 16;
+
+		let output = render(&crate_data, Some(RenderBudget::Bytes(budget)));
+		assert!(
+			output.len() <= budget,
+			"{} > {budget}\n{output}",
+			output.len()
+		);
+		assert!(
+			output.contains(&format!("// elided to fit {budget} bytes: doc comments;")),
+			"{output}"
+		);
+		assert!(output.contains("items omitted */"), "{output}");
+		RustFmt::default().format_str(&output).unwrap();
+
+		assert_eq!(
+			output,
+			render(&crate_data, Some(RenderBudget::Bytes(budget)))
+		);
+	}
+
+	#[test]
+	fn docs_are_dropped_before_fields() {
+		let crate_data = inspect_crate(&large_source(), false, false);
+		let full = render(&crate_data, None);
+
+		let budget = full.len() - 1;
+		let output = render(&crate_data, Some(RenderBudget::Bytes(budget)));
+		assert!(!output.contains("///"), "{output}");
+		assert!(output.contains("pub field11: u32,"), "{output}");
+		assert!(!output.contains("omitted"), "{output}");
+		assert!(
+			output
+				.trim_end()
+				.ends_with(&format!("// elided to fit {budget} bytes: doc comments")),
+			"{output}"
+		);
+	}
+
+	#[test]
+	fn large_struct_fields_are_counted() {
+		let crate_data = inspect_crate(&large_source(), false, false);
+		let full = render(&crate_data, None);
+		// Just too small for the output with only doc comments dropped.
+		let budget = full
+			.lines()
+			.filter(|line| !line.trim_start().starts_with("//"))
+			.flat_map(str::split_whitespace)
+			.count() - 1;
+
+		let output = render(&crate_data, Some(RenderBudget::Tokens(budget)));
+		assert!(output.contains("/* 12 fields omitted */"), "{output}");
+		assert!(!output.contains("items omitted"), "{output}");
+		assert!(output.split_whitespace().count() <= budget, "{output}");
+		RustFmt::default().format_str(&output).unwrap();
+	}
+
+	#[test]
+	fn output_within_budget_is_unchanged() {
+		let crate_data = inspect_crate(&large_source(), false, false);
+		let full = render(&crate_data, None);
+
+		let output = render(&crate_data, Some(RenderBudget::Bytes(full.len())));
+		assert_eq!(output, full);
+	}
+}
@@ -1,4 +1,5 @@
 //! Integration tests asserting filter behaviour for rendered output.
+#![cfg(feature = "cargo-resolution")]
 mod utils;
 use ripdoc_core::Renderer;
 use utils::*;
@@ -352,6 +353,206 @@ gen_tests! {
                 "#
 			}
 		}
+		rt_custom {
+			filter_generic_impl_fn: {
+				// Impl items hang off the type name without its generic arguments
+				renderer: Renderer::default().with_filter("my_module::Wrapper::get"),
+				input: r#"
+                    pub mod my_module {
+                        pub struct Wrapper<T> {
+                            pub inner: T,
+                        }
+
+                        impl<T> Wrapper<T> {
+                            pub fn get(&self) -> &T {
+                                &self.inner
+                            }
+
+                            pub fn excluded(&self) {}
+                        }
+                    }
+                "#,
+				output: r#"
+                    pub mod my_module {
+                        pub struct Wrapper<T> {
+                            pub inner: T,
+                        }
+
+                        impl<T> Wrapper<T> {
+                            pub fn get(&self) -> &T {}
+                        }
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			filter_prefers_generic_impl_fn: {
+				// A method in both a specialised and a generic impl is shown from the generic one
+				renderer: Renderer::default().with_filter("Widget::get"),
+				input: r#"
+                    pub struct Widget<T>(pub T);
+
+                    impl Widget<u32> {
+                        pub fn get(&self) -> &u32 {
+                            &self.0
+                        }
+
+                        pub fn bits(&self) -> u32 {
+                            self.0
+                        }
+                    }
+
+                    impl<T> Widget<T> {
+                        pub fn get(&self) -> &T {
+                            &self.0
+                        }
+                    }
+                "#,
+				output: r#"
+                    pub struct Widget<T>(pub T);
+
+                    // impl<T> Widget<T>
+                    impl<T> Widget<T> {
+                        pub fn get(&self) -> &T {}
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			filter_specialised_impl_fn: {
+				// A method only the specialised impl has is shown from it
+				renderer: Renderer::default().with_filter("Widget::bits"),
+				input: r#"
+                    pub struct Widget<T>(pub T);
+
+                    impl Widget<u32> {
+                        pub fn get(&self) -> &u32 {
+                            &self.0
+                        }
+
+                        pub fn bits(&self) -> u32 {
+                            self.0
+                        }
+                    }
+
+                    impl<T> Widget<T> {
+                        pub fn get(&self) -> &T {
+                            &self.0
+                        }
+                    }
+                "#,
+				output: r#"
+                    pub struct Widget<T>(pub T);
+
+                    // impl Widget<u32>
+                    impl Widget<u32> {
+                        pub fn bits(&self) -> u32 {}
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			filter_trait_impl_fn: {
+				// The implemented trait may be named between the type and the method
+				renderer: Renderer::default().with_filter("MyStruct::Greet::greet"),
+				input: r#"
+                    pub trait Greet {
+                        fn greet(&self);
+                    }
+
+                    pub trait Wave {
+                        fn greet(&self);
+                    }
+
+                    pub struct MyStruct;
+
+                    impl Greet for MyStruct {
+                        fn greet(&self) {}
+                    }
+
+                    impl Wave for MyStruct {
+                        fn greet(&self) {}
+                    }
+                "#,
+				output: r#"
+                    pub struct MyStruct;
+
+                    impl Greet for MyStruct {
+                        fn greet(&self) {}
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			filter_trait_impl_fn_unqualified: {
+				renderer: Renderer::default().with_filter("MyStruct::greet"),
+				input: r#"
+                    pub trait Greet {
+                        fn greet(&self);
+                    }
+
+                    pub struct MyStruct;
+
+                    impl Greet for MyStruct {
+                        fn greet(&self) {}
+                    }
+                "#,
+				output: r#"
+                    pub struct MyStruct;
+
+                    impl Greet for MyStruct {
+                        fn greet(&self) {}
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			filter_case_fallback: {
+				// No exact match, so components are retried ignoring case
+				renderer: Renderer::default().with_filter("my_module::mystruct"),
+				input: r#"
+                    pub mod my_module {
+                        pub struct MyStruct;
+                        pub fn my_function() {}
+                    }
+                "#,
+				output: r#"
+                    pub mod my_module {
+                        pub struct MyStruct;
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			filter_hyphen_fallback: {
+				// Hyphens in the filter are read as underscores on the fallback pass
+				renderer: Renderer::default().with_filter("my-module::MyStruct"),
+				input: r#"
+                    pub mod my_module {
+                        pub struct MyStruct;
+                        pub fn my_function() {}
+                    }
+                "#,
+				output: r#"
+                    pub mod my_module {
+                        pub struct MyStruct;
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			filter_exact_match_wins: {
+				// An exact match means the fallback never runs
+				renderer: Renderer::default().with_filter("foo"),
+				input: r#"
+                    pub struct Foo;
+                    pub fn foo() {}
+                "#,
+				output: r#"
+                    pub fn foo() {}
+                "#
+			}
+		}
 		rt_err {
 			filter_not_matched: {
 				renderer: Renderer::default().with_filter("non_existent_module"),
@@ -363,6 +564,16 @@ gen_tests! {
 				error: "filter path 'non_existent_module' did not match any items"
 			}
 		}
+		rt_err {
+			filter_case_ambiguous: {
+				renderer: Renderer::default().with_filter("FOO"),
+				input: r#"
+                    pub struct Foo;
+                    pub fn foo() {}
+                "#,
+				error: "filter path 'FOO' is ambiguous; candidates: 'Foo', 'foo'"
+			}
+		}
 
 	}
 }
@@ -0,0 +1,154 @@
+//! Rustdoc JSON fixtures of a fixed shape, for the render benchmarks and performance smoke tests.
+//!
+//! Each fixture is generated from synthetic source by running rustdoc on it. The JSON is read
+//! from `tests/fixtures/<name>.json` when that file exists and matches the `rustdoc_types`
+//! format version; otherwise it is generated again. Set `RIPDOC_REGENERATE_FIXTURES=1` to write
+//! the generated JSON back, e.g. after bumping `rustdoc_types`:
+//!
+//! ```sh
+//! RIPDOC_REGENERATE_FIXTURES=1 cargo test -p ripdoc-core --test perf
+//! ```
+
+#![allow(
+	dead_code,
+	reason = "The benchmarks and the smoke test use different fixtures."
+)]
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+use rustdoc_types::{Crate, FORMAT_VERSION};
+
+use crate::utils::inspect_crate;
+
+/// A generated crate of a known size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fixture {
+	/// A couple of modules of documented items: a few hundred kilobytes of JSON.
+	Small,
+	/// Nested modules with many wide structs, enums, traits and impls: a few megabytes of JSON.
+	Medium,
+}
+
+impl Fixture {
+	/// Every fixture, smallest first.
+	pub const ALL: [Self; 2] = [Self::Small, Self::Medium];
+
+	/// Name of the fixture, used for its file and benchmark ids.
+	pub fn name(self) -> &'static str {
+		match self {
+			Self::Small => "small",
+			Self::Medium => "medium",
+		}
+	}
+
+	/// Number of top-level modules, and of items of each kind in every module.
+	fn shape(self) -> (usize, usize) {
+		match self {
+			Self::Small => (2, 2),
+			Self::Medium => (8, 10),
+		}
+	}
+
+	/// Where the fixture's JSON is kept.
+	fn path(self) -> PathBuf {
+		PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+			.join("tests/fixtures")
+			.join(format!("{}.json", self.name()))
+	}
+
+	/// Parsed rustdoc JSON for the fixture, generated when the stored copy is missing or stale.
+	pub fn load(self) -> Crate {
+		let regenerate = env::var_os("RIPDOC_REGENERATE_FIXTURES").is_some();
+		if !regenerate
+			&& let Ok(json) = fs::read_to_string(self.path())
+			&& let Ok(crate_data) = serde_json::from_str::<Crate>(&json)
+			&& crate_data.format_version == FORMAT_VERSION
+		{
+			return crate_data;
+		}
+
+		let crate_data = inspect_crate(&self.source(), false, false);
+		if regenerate {
+			fs::write(self.path(), serde_json::to_string(&crate_data).unwrap()).unwrap();
+		}
+		crate_data
+	}
+
+	/// Source of the fixture crate.
+	pub fn source(self) -> String {
+		let (modules, items) = self.shape();
+		let mut source = format!("//! The {} performance fixture.\n\n", self.name());
+		for module in 0..modules {
+			writeln!(source, "/// Module {module}.\npub mod module{module} {{").unwrap();
+			write_items(&mut source, items);
+			writeln!(source, "    /// Nested module.\n    pub mod nested {{").unwrap();
+			write_items(&mut source, items / 2);
+			source.push_str("    }\n}\n\n");
+		}
+		source
+	}
+}
+
+/// Append `count` each of structs with impls, enums, traits, and functions.
+fn write_items(source: &mut String, count: usize) {
+	for index in 0..count {
+		writeln!(
+			source,
+			"/// Record {index}, holding a handful of documented fields.\n\
+			 #[derive(Debug, Clone, Default, PartialEq)]\n\
+			 pub struct Record{index} {{"
+		)
+		.unwrap();
+		for field in 0..8 {
+			writeln!(
+				source,
+				"/// Field {field}.\npub field{field}: Option<Vec<u32>>,"
+			)
+			.unwrap();
+		}
+		writeln!(
+			source,
+			"}}\n\n\
+			 impl Record{index} {{\n\
+			 /// Create an empty record.\n\
+			 pub fn new() -> Self {{ Self::default() }}\n\
+			 /// Look up a field by position.\n\
+			 pub fn get(&self, index: usize) -> Option<&[u32]> {{ let _ = index; None }}\n\
+			 /// Combine two records.\n\
+			 pub fn merge<T: Into<Self>>(self, other: T) -> Self {{ let _ = other.into(); self }}\n\
+			 }}\n\n\
+			 /// Outcome {index} of an operation.\n\
+			 pub enum Outcome{index} {{\n\
+			 /// It worked.\n\
+			 Done(u64),\n\
+			 /// It failed.\n\
+			 Failed {{ code: i32, message: String }},\n\
+			 /// It is still running.\n\
+			 Pending,\n\
+			 }}\n\n\
+			 /// Behaviour {index} shared by records.\n\
+			 pub trait Behaviour{index}: Clone {{\n\
+			 /// Associated output.\n\
+			 type Output;\n\
+			 /// Run the behaviour.\n\
+			 fn run(&self) -> Self::Output;\n\
+			 /// Describe the behaviour.\n\
+			 fn describe(&self) -> String {{ String::new() }}\n\
+			 }}\n\n\
+			 impl Behaviour{index} for Record{index} {{\n\
+			 type Output = Outcome{index};\n\
+			 fn run(&self) -> Outcome{index} {{ Outcome{index}::Pending }}\n\
+			 }}\n\n\
+			 /// Free function {index}.\n\
+			 pub fn process{index}<'a>(input: &'a str, limit: usize)\n\
+			 -> Result<&'a str, String> {{\n\
+			 let _ = limit;\n\
+			 Ok(input)\n\
+			 }}\n"
+		)
+		.unwrap();
+	}
+}
@@ -1,10 +1,29 @@
 //! Integration tests validating function signature rendering.
+#![cfg(feature = "cargo-resolution")]
 mod utils;
 use ripdoc_core::Renderer;
 use utils::*;
 
 gen_tests! {
 	functions, {
+		idemp {
+			must_use: r#"
+                #[must_use]
+                pub fn compute() -> u32 {}
+            "#
+		}
+		idemp {
+			must_use_reason: r#"
+                #[must_use = "the result is expensive"]
+                pub fn expensive() -> u32 {}
+            "#
+		}
+		idemp {
+			no_mangle: r#"
+                #[no_mangle]
+                pub fn exported() {}
+            "#
+		}
 		idemp {
 			complex: r#"
                 pub async unsafe fn complex_function<'a, T, U>(x: &'a T, y: U) -> Result<T, U>
@@ -22,6 +41,29 @@ gen_tests! {
                     for<'a> F: Fn(&'a str) -> bool,
                 {
                 }
+            "#
+		}
+		idemp {
+			receiver_lifetimes: r#"
+                pub struct Cursor<'a> {
+                    pub data: &'a [u8],
+                }
+
+                impl<'a> Cursor<'a> {
+                    pub fn advance(&'a mut self) {}
+                    pub fn peek(&'a self) -> &'a [u8] {}
+                }
+            "#
+		}
+		idemp {
+			arbitrary_receivers: r#"
+                pub struct Task;
+
+                impl Task {
+                    pub fn poll(self: std::pin::Pin<&mut Self>) {}
+                    pub fn into_boxed(self: Box<Self>) {}
+                    pub fn boxed_ref(self: &Box<Self>) {}
+                }
             "#
 		}
 		idemp {
@@ -45,6 +87,21 @@ gen_tests! {
                     T: 'static
                 {
                 }
+            "#
+		}
+		idemp {
+			impl_trait_argument_bounds: r#"
+                pub fn take(r: impl std::io::Read + Send + 'static) {}
+            "#
+		}
+		idemp {
+			impl_trait_behind_reference: r#"
+                pub fn take_ref(r: &mut (impl std::io::Read + Send + 'static)) {}
+            "#
+		}
+		idemp {
+			impl_trait_in_tuple: r#"
+                pub fn take_pair(pair: (impl std::io::Read + Send + 'static, u8)) {}
             "#
 		}
 		rt {
@@ -95,6 +152,18 @@ gen_tests! {
                 "#
 			}
 		}
+		rt_custom {
+			attrs_disabled: {
+				renderer: Renderer::default().with_attrs(false),
+				input: r#"
+                    #[must_use]
+                    pub fn compute() -> u32 { 0 }
+                "#,
+				output: r#"
+                    pub fn compute() -> u32 {}
+                "#
+			}
+		}
 	}
 
 }
@@ -1,6 +1,7 @@
 //! Integration tests for impl block rendering.
+#![cfg(feature = "cargo-resolution")]
 mod utils;
-use ripdoc_core::Renderer;
+use ripdoc_core::{RenderFormat, Renderer};
 use utils::*;
 
 gen_tests! {
@@ -115,6 +116,36 @@ gen_tests! {
                 }
             "#
 		}
+		rt {
+			implementing_type_renders_as_self: {
+				input: r#"
+                    pub struct Wrapper<T>(T);
+
+                    impl<T> Wrapper<T> {
+                        pub fn new(value: T) -> Wrapper<T> {}
+                        pub fn merge(self, other: Wrapper<T>) -> Vec<Wrapper<T>> {}
+                        pub fn bytes(&self) -> Wrapper<u8> {}
+                    }
+
+                    impl<T> From<T> for Wrapper<T> {
+                        fn from(value: T) -> Wrapper<T> {}
+                    }
+                "#,
+				output: r#"
+                    pub struct Wrapper<T>(_);
+
+                    impl<T> Wrapper<T> {
+                        pub fn new(value: T) -> Self {}
+                        pub fn merge(self, other: Self) -> Vec<Self> {}
+                        pub fn bytes(&self) -> Wrapper<u8> {}
+                    }
+
+                    impl<T> From<T> for Wrapper<T> {
+                        fn from(value: T) -> Self {}
+                    }
+                "#
+			}
+		}
 		rt {
 			deserialize: {
 				input:
@@ -150,8 +181,113 @@ gen_tests! {
                         type Error;
                     }
 
-                    #[derive(Deserialize)]
                     pub struct Message;
+
+                    impl<'de> Deserialize<'de> for Message {
+                        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                        where
+                            D: Deserializer<'de>,
+                        {
+                        }
+                    }
+                "#
+			}
+		}
+		rt {
+			manual_partial_eq_renders_impl: {
+				input: r#"
+                    pub struct Manual;
+
+                    impl PartialEq for Manual {
+                        fn eq(&self, other: &Self) -> bool {
+                            true
+                        }
+                    }
+                "#,
+				output: r#"
+                    pub struct Manual;
+
+                    impl PartialEq for Manual {
+                        fn eq(&self, other: &Self) -> bool {}
+                    }
+                "#
+			}
+		}
+		rt {
+			derived_partial_eq_collapses: {
+				input: r#"
+                    #[derive(PartialEq)]
+                    pub struct Derived;
+                "#,
+				output: r#"
+                    #[derive(PartialEq)]
+                    pub struct Derived;
+                "#
+			}
+		}
+		rt {
+			inherent_impls_before_sorted_trait_impls: {
+				input: r#"
+                    pub trait Zeta {
+                        fn zeta(&self);
+                    }
+
+                    pub trait Alpha {
+                        fn alpha(&self);
+                    }
+
+                    pub trait Mid {
+                        fn mid(&self);
+                    }
+
+                    pub struct Widget;
+
+                    impl Zeta for Widget {
+                        fn zeta(&self) {}
+                    }
+
+                    impl Mid for Widget {
+                        fn mid(&self) {}
+                    }
+
+                    impl Widget {
+                        pub fn new() -> Self {}
+                    }
+
+                    impl Alpha for Widget {
+                        fn alpha(&self) {}
+                    }
+                "#,
+				output: r#"
+                    pub trait Zeta {
+                        fn zeta(&self);
+                    }
+
+                    pub trait Alpha {
+                        fn alpha(&self);
+                    }
+
+                    pub trait Mid {
+                        fn mid(&self);
+                    }
+
+                    pub struct Widget;
+
+                    impl Widget {
+                        pub fn new() -> Self {}
+                    }
+
+                    impl Alpha for Widget {
+                        fn alpha(&self) {}
+                    }
+
+                    impl Mid for Widget {
+                        fn mid(&self) {}
+                    }
+
+                    impl Zeta for Widget {
+                        fn zeta(&self) {}
+                    }
                 "#
 			}
 		}
@@ -237,6 +373,215 @@ gen_tests! {
                 "#
 			}
 		}
+		rt {
+			private_inherent_items_hidden: {
+				input: r#"
+                    pub struct Config;
+
+                    impl Config {
+                        const SECRET: u32 = 7;
+
+                        fn validate(&self) -> bool {
+                            true
+                        }
+
+                        pub fn load() -> Self {
+                            Config
+                        }
+                    }
+                "#,
+				output: r#"
+                    pub struct Config;
+
+                    impl Config {
+                        pub fn load() -> Self {}
+                    }
+                "#
+			}
+		}
+		rt {
+			inherent_impls_for_different_instantiations_are_labelled: {
+				input: r#"
+                    pub struct Widget<T>(pub T);
+
+                    impl Widget<u32> {
+                        pub fn get(&self) -> &u32 {
+                            &self.0
+                        }
+                    }
+
+                    impl<T> Widget<T> {
+                        pub fn get(&self) -> &T {
+                            &self.0
+                        }
+                    }
+
+                    impl<T: Clone> Clone for Widget<T> {
+                        fn clone(&self) -> Self {
+                            Widget(self.0.clone())
+                        }
+                    }
+                "#,
+				output: r#"
+                    pub struct Widget<T>(pub T);
+
+                    // impl Widget<u32>
+                    impl Widget<u32> {
+                        pub fn get(&self) -> &u32 {}
+                    }
+
+                    // impl<T> Widget<T>
+                    impl<T> Widget<T> {
+                        pub fn get(&self) -> &T {}
+                    }
+
+                    impl<T: Clone> Clone for Widget<T> {
+                        fn clone(&self) -> Self {}
+                    }
+                "#
+			}
+		}
+		rt {
+			sealed_trait_bounds_get_stubs: {
+				input: r#"
+                    mod sealed {
+                        pub trait Sealed {}
+                    }
+
+                    pub trait Public: sealed::Sealed {
+                        fn name(&self) -> String;
+                    }
+
+                    pub struct Wrapper<T>(pub T);
+
+                    impl<T> Wrapper<T>
+                    where
+                        T: sealed::Sealed,
+                    {
+                        pub fn get(&self) -> &T {
+                            &self.0
+                        }
+                    }
+
+                    impl sealed::Sealed for u8 {}
+                "#,
+				output: r#"
+                    pub trait Public: sealed::Sealed {
+                        fn name(&self) -> String;
+                    }
+
+                    pub struct Wrapper<T>(pub T);
+
+                    impl<T> Wrapper<T>
+                    where
+                        T: sealed::Sealed,
+                    {
+                        pub fn get(&self) -> &T {}
+                    }
+
+                    mod sealed {
+                        pub trait Sealed {}
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			custom_derive_traits: {
+				renderer: Renderer::default().with_derive_traits(vec!["Clone".to_string()]),
+				input: r#"
+                    #[derive(Clone, PartialEq)]
+                    pub struct Custom;
+                "#,
+				output: r#"
+                    #[derive(Clone)]
+                    pub struct Custom;
+
+                    impl PartialEq for Custom {
+                        fn eq(&self, other: &Self) -> bool {}
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			no_impls_keeps_declarations_and_derives: {
+				renderer: Renderer::default().with_impls(false),
+				input: r#"
+                    #[derive(Clone, Debug)]
+                    pub struct Point {
+                        pub x: i32,
+                    }
+
+                    impl Point {
+                        pub fn new(x: i32) -> Self {
+                            Point { x }
+                        }
+                    }
+
+                    #[derive(Clone, Copy)]
+                    pub enum Shape {
+                        Circle,
+                        Square,
+                    }
+
+                    impl std::fmt::Display for Shape {
+                        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                            Ok(())
+                        }
+                    }
+
+                    pub trait Area {
+                        fn area(&self) -> f64;
+                    }
+
+                    impl Area for Shape {
+                        fn area(&self) -> f64 {
+                            0.0
+                        }
+                    }
+                "#,
+				output: r#"
+                    #[derive(Clone, Debug)]
+                    pub struct Point {
+                        pub x: i32,
+                    }
+
+                    #[derive(Clone, Copy)]
+                    pub enum Shape {
+                        Circle,
+                        Square,
+                    }
+
+                    pub trait Area {
+                        fn area(&self) -> f64;
+                    }
+                "#
+			}
+		}
+		rt_custom {
+			private_inherent_items_with_private: {
+				renderer: Renderer::default().with_private_items(true),
+				input: r#"
+                    pub struct Config;
+
+                    impl Config {
+                        const SECRET: u32 = 7;
+
+                        fn validate(&self) -> bool {
+                            true
+                        }
+                    }
+                "#,
+				output: r#"
+                    pub struct Config;
+
+                    impl Config {
+                        const SECRET: u32 = 7;
+
+                        fn validate(&self) -> bool {}
+                    }
+                "#
+			}
+		}
 		rt_custom {
 			default_impl: {
 				renderer: Renderer::default().with_private_items(true),
@@ -258,5 +603,111 @@ gen_tests! {
                 "#
 			}
 		}
+		rt_custom {
+			sealed_trait_bounds_omitted_without_stubs: {
+				renderer: Renderer::default().with_sealed_stubs(false),
+				input: r#"
+                    mod sealed {
+                        pub trait Sealed {}
+                    }
+
+                    pub trait Public: sealed::Sealed {
+                        fn name(&self) -> String;
+                    }
+
+                    pub struct Wrapper<T>(pub T);
+
+                    impl<T> Wrapper<T>
+                    where
+                        T: sealed::Sealed,
+                    {
+                        pub fn get(&self) -> &T {
+                            &self.0
+                        }
+                    }
+
+                    impl sealed::Sealed for u8 {}
+                "#,
+				output: r#"
+                    pub trait Public /* private bound omitted */ {
+                        fn name(&self) -> String;
+                    }
+
+                    pub struct Wrapper<T>(pub T);
+
+                    impl<T> Wrapper<T> /* private bound omitted */ {
+                        pub fn get(&self) -> &T {}
+                    }
+                "#
+			}
+		}
 	}
 }
+
+#[test]
+fn auto_trait_summary_follows_each_type() {
+	let crate_data = inspect_crate(
+		r#"
+            use std::rc::Rc;
+
+            pub struct Shared {
+                pub value: Rc<u32>,
+            }
+
+            pub enum Plain {
+                One,
+                Two(u32),
+            }
+        "#,
+		false,
+		false,
+	);
+	let rendered = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_auto_trait_summary(true)
+		.render(&crate_data)
+		.unwrap();
+
+	// The summary is the line after the declaration's closing brace.
+	let summary_of = |declaration: &str| {
+		let mut lines = rendered
+			.lines()
+			.skip_while(|line| !line.contains(declaration));
+		lines.find(|line| line.trim() == "}");
+		let line = lines.next().unwrap_or_default().trim();
+		line.strip_prefix("// auto traits: ").map(str::to_string)
+	};
+	let shared = summary_of("pub struct Shared").expect(&rendered);
+	let shared: Vec<&str> = shared.split(", ").collect();
+	assert!(shared.contains(&"!Send"), "{rendered}");
+	assert!(shared.contains(&"!Sync"), "{rendered}");
+	assert!(shared.contains(&"Unpin"), "{rendered}");
+	// Implemented traits come before negative ones.
+	let first_negative = shared
+		.iter()
+		.position(|name| name.starts_with('!'))
+		.unwrap();
+	assert!(
+		shared[first_negative..]
+			.iter()
+			.all(|name| name.starts_with('!'))
+	);
+
+	let plain = summary_of("pub enum Plain").expect(&rendered);
+	assert!(
+		plain.contains("RefUnwindSafe, Send, Sync, Unpin"),
+		"{rendered}"
+	);
+	assert!(!plain.contains('!'), "{rendered}");
+	assert!(!rendered.contains("impl"), "{rendered}");
+
+	// Full auto impl blocks replace the summary.
+	let blocks = Renderer::default()
+		.with_format(RenderFormat::Rust)
+		.with_auto_trait_summary(true)
+		.with_auto_impls(true)
+		.render(&crate_data)
+		.unwrap();
+	assert!(!blocks.contains("// auto traits"), "{blocks}");
+	assert!(blocks.contains("impl !Send for Shared"), "{blocks}");
+}
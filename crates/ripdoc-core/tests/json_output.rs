@@ -0,0 +1,121 @@
+//! Integration tests for the structured JSON skeleton format.
+
+#![cfg(feature = "cargo-resolution")]
+
+mod utils;
+
+#[cfg(test)]
+mod tests {
+	use pretty_assertions::assert_eq;
+	use ripdoc_core::{RenderFormat, Renderer};
+	use serde::Deserialize;
+
+	use super::utils::inspect_crate;
+
+	/// Mirror of the documented node schema; unknown fields fail deserialization.
+	#[derive(Debug, Deserialize)]
+	#[serde(deny_unknown_fields)]
+	struct Node {
+		kind: String,
+		name: String,
+		path: String,
+		signature: String,
+		docs: Option<String>,
+		children: Vec<Node>,
+	}
+
+	impl Node {
+		fn child(&self, name: &str) -> &Node {
+			self.children
+				.iter()
+				.find(|child| child.name == name)
+				.unwrap_or_else(|| panic!("missing child {name} under {}", self.path))
+		}
+	}
+
+	const FIXTURE: &str = r#"
+        //! Fixture crate.
+
+        /// A widget.
+        pub struct Widget {
+            /// Widget size.
+            pub size: u32,
+            secret: u8,
+        }
+
+        impl Widget {
+            /// Create a widget.
+            pub fn new(size: u32) -> Self { Widget { size, secret: 0 } }
+        }
+
+        pub enum Shape {
+            Circle(f32),
+            Square { side: f32 },
+        }
+
+        pub mod nested {
+            pub trait Paint {
+                fn paint(&self);
+            }
+        }
+
+        fn hidden() {}
+    "#;
+
+	fn render_json(renderer: Renderer) -> Node {
+		let crate_data = inspect_crate(FIXTURE, false, false);
+		let rendered = renderer
+			.with_format(RenderFormat::Json)
+			.render(&crate_data)
+			.unwrap();
+		serde_json::from_str(&rendered).unwrap()
+	}
+
+	#[test]
+	fn json_skeleton_matches_fixture_shape() {
+		let root = render_json(Renderer::default());
+		assert_eq!(root.kind, "module");
+		assert_eq!(root.path, "dummy_crate");
+		assert_eq!(root.docs.as_deref(), Some("Fixture crate."));
+
+		// Children follow rustdoc's item order, which puts modules first
+		let names: Vec<&str> = root.children.iter().map(|c| c.name.as_str()).collect();
+		assert_eq!(names, ["nested", "Widget", "Shape"]);
+
+		let widget = root.child("Widget");
+		assert_eq!(widget.kind, "struct");
+		assert_eq!(widget.path, "dummy_crate::Widget");
+		assert_eq!(widget.signature, "pub struct Widget");
+		assert_eq!(widget.docs.as_deref(), Some("A widget."));
+
+		let size = widget.child("size");
+		assert_eq!(size.kind, "field");
+		assert_eq!(size.signature, "pub size: u32");
+		assert!(widget.children.iter().all(|child| child.name != "secret"));
+
+		let inherent = widget.child("Widget");
+		assert_eq!(inherent.kind, "impl");
+		let new = inherent.child("new");
+		assert_eq!(new.kind, "method");
+		assert_eq!(new.signature, "pub fn new(size: u32) -> Self");
+
+		let shape = root.child("Shape");
+		assert_eq!(shape.kind, "enum");
+		assert_eq!(shape.child("Circle").signature, "Circle(f32)");
+		assert_eq!(shape.child("Square").signature, "Square { side: f32 }");
+
+		let paint = root.child("nested").child("Paint");
+		assert_eq!(paint.kind, "trait");
+		assert_eq!(paint.path, "dummy_crate::nested::Paint");
+		assert_eq!(paint.child("paint").kind, "trait method");
+	}
+
+	#[test]
+	fn json_skeleton_respects_filter() {
+		let root = render_json(Renderer::default().with_filter("nested::Paint"));
+		assert_eq!(root.children.len(), 1);
+		let nested = root.child("nested");
+		assert_eq!(nested.children.len(), 1);
+		assert_eq!(nested.child("Paint").children.len(), 1);
+	}
+}
@@ -1,4 +1,5 @@
 //! Integration tests covering the listing mode output.
+#![cfg(feature = "cargo-resolution")]
 #![allow(clippy::tests_outside_test_module)]
 
 mod utils;
@@ -23,7 +24,7 @@ fn list_respects_visibility_flags() {
 	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
 
 	let public_items = ripdoc
-		.list(&target, false, false, Vec::new(), false, None)
+		.list(&target, false, false, &[], false, None)
 		.unwrap();
 	let public_paths: Vec<String> = public_items.into_iter().map(|item| item.path).collect();
 
@@ -43,7 +44,7 @@ fn list_respects_visibility_flags() {
 	);
 
 	let items_with_private = ripdoc
-		.list(&target, false, false, Vec::new(), true, None)
+		.list(&target, false, false, &[], true, None)
 		.unwrap();
 	let private_paths: Vec<String> = items_with_private
 		.iter()
@@ -75,7 +76,7 @@ fn list_omits_nameless_use_items() {
 	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
 
 	let items = ripdoc
-		.list(&target, false, false, Vec::new(), false, None)
+		.list(&target, false, false, &[], false, None)
 		.unwrap();
 
 	assert!(items.iter().any(|item| item.path.ends_with("::exported")));
@@ -102,7 +103,7 @@ fn list_applies_search_filters() {
 	options.include_private = false;
 
 	let filtered = ripdoc
-		.list(&target, false, false, Vec::new(), false, Some(&options))
+		.list(&target, false, false, &[], false, Some(&options))
 		.unwrap();
 
 	let filtered_pairs: Vec<(String, String)> = filtered
@@ -134,7 +135,7 @@ fn list_reports_source_paths() {
 
 	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
 	let items = ripdoc
-		.list(&target, false, false, Vec::new(), false, None)
+		.list(&target, false, false, &[], false, None)
 		.unwrap();
 
 	let module_source = items
@@ -1,5 +1,7 @@
 //! Integration tests covering macro rendering quirks.
 
+#![cfg(feature = "cargo-resolution")]
+
 mod utils;
 
 #[cfg(test)]
@@ -77,4 +79,52 @@ mod tests {
 
 		rt(source, expected_output);
 	}
+
+	#[test]
+	fn test_multi_rule_macro_collapses_transcribers() {
+		let source = r#"
+            #[macro_export]
+            macro_rules! pick {
+                () => { None };
+                ($x:expr) => {{
+                    let value = $x;
+                    Some(value)
+                }};
+                ($x:expr, $($rest:tt)*) => { pick!($($rest)*) };
+            }
+        "#;
+
+		let expected_output = r#"
+            #[macro_export]
+            macro_rules! pick {
+                () => { ... };
+                ($x:expr) => { ... };
+                ($x:expr, $($rest:tt)*) => { ... };
+            }
+        "#;
+
+		rt(source, expected_output);
+	}
+
+	#[test]
+	fn test_macro_matcher_with_braces() {
+		let source = r#"
+            #[macro_export]
+            macro_rules! branch {
+                (if { $($then:tt)* } else { $($alt:tt)* }) => {
+                    println!("}} {{");
+                    let _ = '}';
+                };
+            }
+        "#;
+
+		let expected_output = r#"
+            #[macro_export]
+            macro_rules! branch {
+                (if { $($then:tt)* } else { $($alt:tt)* }) => { ... };
+            }
+        "#;
+
+		rt(source, expected_output);
+	}
 }
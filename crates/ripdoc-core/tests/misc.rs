@@ -1,9 +1,17 @@
 //! Miscellaneous integration tests covering edge rendering cases.
 
+#![cfg(feature = "cargo-resolution")]
+
 mod utils;
 
 #[cfg(test)]
 mod tests {
+	use ripdoc_core::{
+		LoadOptions, LoadedCrate, RenderFormat, Renderer, Ripdoc, SearchDomain, SearchOptions,
+		SearchResponse,
+	};
+	use rust_format::{Formatter, RustFmt};
+
 	use super::utils::*;
 	#[test]
 	fn test_render_constant() {
@@ -71,6 +79,45 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_render_external_reexports() {
+		let source = r#"
+                pub use std::string::String as Text;
+                pub use std::fmt::Display as Show;
+            "#;
+
+		rt(
+			source,
+			r#"
+                pub use std::string::String as Text;
+                pub use std::fmt::Display as Show;
+            "#,
+		);
+		render(
+			&Renderer::default().with_inline_external_reexports(true),
+			source,
+			r#"
+                pub use alloc::string::String as Text; // re-exported struct
+                pub use core::fmt::Display as Show; // re-exported trait
+            "#,
+			false,
+		);
+		render(
+			&Renderer::default()
+				.with_inline_external_reexports(true)
+				.with_private_items(true),
+			// rustdoc leaves restricted imports of external items out of its output entirely.
+			r#"
+                pub(crate) use std::string::String as Text;
+                pub use std::vec::Vec;
+            "#,
+			r#"
+                pub use alloc::vec::Vec; // re-exported struct
+            "#,
+			false,
+		);
+	}
+
 	#[test]
 	fn test_render_type_alias_with_bounds() {
 		rt_idemp(
@@ -102,6 +149,19 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn test_render_type_alias_where_clause() {
+		rt_idemp(
+			r#"
+                pub type Cloned<T> where T: Clone = Vec<T>;
+
+                pub type Keyed<K, V> where K: std::hash::Hash + Eq, V: Default = std::collections::HashMap<K, V>;
+
+                pub type Borrowed<'a, T> where T: 'a = &'a T;
+            "#,
+		);
+	}
+
 	#[test]
 	fn test_reserved_word() {
 		rt_idemp(
@@ -316,4 +376,326 @@ mod tests {
             "#,
 		);
 	}
+
+	#[test]
+	fn test_streaming_matches_buffered_render() {
+		let crate_data = inspect_crate(
+			r#"
+                //! Crate docs.
+
+                /// A documented struct.
+                pub struct First {
+                    pub field: u32,
+                }
+
+                pub mod nested {
+                    //! Nested docs.
+
+                    pub fn inner() {}
+                }
+
+                pub use nested::inner;
+
+                /// A documented function.
+                pub fn last(value: u32) -> u32 { value }
+            "#,
+			false,
+			false,
+		);
+
+		for format in [RenderFormat::Rust, RenderFormat::Markdown] {
+			let buffered = Renderer::default()
+				.with_format(format)
+				.render(&crate_data)
+				.unwrap();
+
+			// Force a separate rustfmt pass for every top-level item.
+			let mut streamed = Vec::new();
+			Renderer::default()
+				.with_format(format)
+				.with_stream_chunk_bytes(1)
+				.render_to(&crate_data, &mut streamed)
+				.unwrap();
+
+			assert_eq!(String::from_utf8(streamed).unwrap(), buffered);
+		}
+	}
+
+	#[test]
+	fn test_intra_doc_links_render_as_plain_paths() {
+		let crate_data = inspect_crate(
+			r#"
+                /// Settings read by [`render::Renderer`].
+                pub struct Config;
+
+                pub mod render {
+                    /// Draws output configured by [the config](crate::Config).
+                    pub struct Renderer;
+                }
+            "#,
+			false,
+			false,
+		);
+
+		let markdown = Renderer::default().render(&crate_data).unwrap();
+		assert!(
+			markdown.contains("Settings read by `dummy_crate::render::Renderer`."),
+			"{markdown}"
+		);
+		assert!(
+			markdown.contains("Draws output configured by the config."),
+			"{markdown}"
+		);
+
+		let written = Renderer::default()
+			.with_format(RenderFormat::Rust)
+			.render(&crate_data)
+			.unwrap();
+		assert!(
+			written.contains("/// Settings read by [`render::Renderer`]."),
+			"{written}"
+		);
+		assert!(
+			written.contains("/// Draws output configured by [the config](crate::Config)."),
+			"{written}"
+		);
+
+		let resolved = Renderer::default()
+			.with_format(RenderFormat::Rust)
+			.with_resolved_doc_links(true)
+			.render(&crate_data)
+			.unwrap();
+		assert!(
+			resolved.contains("/// Settings read by `dummy_crate::render::Renderer`."),
+			"{resolved}"
+		);
+		assert!(
+			resolved.contains("/// Draws output configured by the config."),
+			"{resolved}"
+		);
+	}
+
+	#[test]
+	fn test_single_type_search_renders_blanket_impls() {
+		let (_temp_dir, target) = create_test_crate(
+			r#"
+                pub struct Widget;
+
+                pub fn unrelated() {}
+            "#,
+			false,
+		);
+		let ripdoc = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_render_format(RenderFormat::Rust);
+
+		let response = ripdoc
+			.search(&target, false, false, &[], &SearchOptions::new("Widget"))
+			.unwrap();
+		let rendered = response.rendered;
+		assert!(
+			rendered.contains("impl From<Widget> for Widget {} // blanket impl"),
+			"{rendered}"
+		);
+		assert!(
+			rendered.contains("impl<U> Into<U> for Widget {} // blanket impl"),
+			"{rendered}"
+		);
+		assert!(
+			rendered.contains("impl<U> TryFrom<U> for Widget {} // blanket impl"),
+			"{rendered}"
+		);
+		assert!(!rendered.contains("unrelated"), "{rendered}");
+
+		// Rendering the whole crate keeps blanket impls out of the skeleton.
+		let full = ripdoc.render(&target, false, false, &[], false).unwrap();
+		assert!(!full.contains("blanket impl"), "{full}");
+	}
+
+	#[test]
+	fn test_method_search_keeps_tuple_struct_declaration() {
+		let (_temp_dir, target) = create_test_crate(
+			r#"
+                pub struct Meters(f64);
+
+                impl Meters {
+                    pub fn in_feet(&self) -> f64 { self.0 * 3.28 }
+                }
+
+                pub fn unrelated() {}
+            "#,
+			false,
+		);
+		let ripdoc = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_render_format(RenderFormat::Rust);
+
+		let mut options = SearchOptions::new("in_feet");
+		options.domains = SearchDomain::NAMES;
+		let rendered = ripdoc
+			.search(&target, false, false, &[], &options)
+			.unwrap()
+			.rendered;
+		assert!(rendered.contains("pub struct Meters(_);"), "{rendered}");
+		assert!(
+			rendered.contains("pub fn in_feet(&self) -> f64"),
+			"{rendered}"
+		);
+		assert!(!rendered.contains("unrelated"), "{rendered}");
+		RustFmt::default().format_str(&rendered).unwrap();
+	}
+
+	#[test]
+	fn test_field_search_keeps_enum_variant_fields() {
+		let (_temp_dir, target) = create_test_crate(
+			r#"
+                pub enum Event {
+                    Moved { count: u32, distance: f64 },
+                    Stopped,
+                }
+            "#,
+			false,
+		);
+		let ripdoc = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_render_format(RenderFormat::Rust);
+
+		let mut options = SearchOptions::new("count");
+		options.domains = SearchDomain::NAMES;
+		let rendered = ripdoc
+			.search(&target, false, false, &[], &options)
+			.unwrap()
+			.rendered;
+		assert!(rendered.contains("Moved { count: u32 }"), "{rendered}");
+		RustFmt::default().format_str(&rendered).unwrap();
+	}
+
+	#[test]
+	fn test_search_with_deps_tags_results_by_crate() {
+		let temp_dir = tempfile::TempDir::new().unwrap();
+		let root = temp_dir.path();
+		let write = |path: &str, contents: &str| {
+			let path = root.join(path);
+			std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+			std::fs::write(path, contents).unwrap();
+		};
+		write(
+			"workspace/Cargo.toml",
+			r#"
+                [workspace]
+                members = ["pkg2"]
+            "#,
+		);
+		write(
+			"workspace/pkg2/Cargo.toml",
+			r#"
+                [package]
+                name = "pkg2"
+                version = "0.1.0"
+                edition = "2021"
+
+                [dependencies]
+                standalone = { path = "../../standalone" }
+            "#,
+		);
+		write("workspace/pkg2/src/lib.rs", "pub fn widget_count() {}\n");
+		write(
+			"standalone/Cargo.toml",
+			r#"
+                [package]
+                name = "standalone"
+                version = "0.1.0"
+                edition = "2021"
+            "#,
+		);
+		write(
+			"standalone/src/lib.rs",
+			"pub struct WidgetBox;\npub fn other() {}\n",
+		);
+
+		let target = root.join("workspace/pkg2");
+		let target = target.to_str().unwrap();
+		let ripdoc = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_render_format(RenderFormat::Rust);
+
+		let primary_only = ripdoc
+			.search(target, false, false, &[], &SearchOptions::new("widget"))
+			.unwrap();
+		assert_eq!(primary_only.results.len(), 1);
+		assert!(!primary_only.rendered.contains("// crate:"));
+
+		let mut options = SearchOptions::new("widget");
+		options.include_dependencies = true;
+		let response = ripdoc.search(target, false, false, &[], &options).unwrap();
+		let tagged: Vec<(&str, &str)> = response
+			.results
+			.iter()
+			.map(|result| (result.crate_name.as_str(), result.raw_name.as_str()))
+			.collect();
+		assert_eq!(
+			tagged,
+			[("pkg2", "widget_count"), ("standalone", "WidgetBox")]
+		);
+
+		let rendered = response.rendered;
+		let pkg2 = rendered.find("// crate: pkg2").expect(&rendered);
+		let standalone = rendered.find("// crate: standalone").expect(&rendered);
+		assert!(pkg2 < standalone, "{rendered}");
+		assert!(rendered.contains("pub fn widget_count()"), "{rendered}");
+		assert!(rendered.contains("pub struct WidgetBox;"), "{rendered}");
+		assert!(!rendered.contains("other"), "{rendered}");
+	}
+
+	#[test]
+	fn test_loaded_crate_searches_match_independent_searches() {
+		fn assert_send<T: Send>() {}
+		assert_send::<LoadedCrate>();
+
+		let (_temp_dir, target) = create_test_crate(
+			r#"
+                pub mod shapes {
+                    /// A round shape.
+                    pub struct Circle;
+
+                    /// A shape with corners.
+                    pub struct Square;
+
+                    pub fn draw_circle(circle: Circle) {}
+                }
+            "#,
+			false,
+		);
+		let ripdoc = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_render_format(RenderFormat::Rust);
+
+		let loaded = ripdoc.load(&target, &LoadOptions::default()).unwrap();
+		for query in ["circle", "shape"] {
+			let options = SearchOptions::new(query);
+			let reused = loaded.search(&options).unwrap();
+			let fresh = ripdoc.search(&target, false, false, &[], &options).unwrap();
+
+			let paths = |response: &SearchResponse| {
+				response
+					.results
+					.iter()
+					.map(|result| result.path_string.clone())
+					.collect::<Vec<_>>()
+			};
+			assert!(!reused.results.is_empty(), "no results for {query}");
+			assert_eq!(paths(&reused), paths(&fresh), "results for {query}");
+			assert_eq!(reused.rendered, fresh.rendered, "skeleton for {query}");
+		}
+
+		let listed = ripdoc
+			.list(&target, false, false, &[], false, None)
+			.unwrap();
+		assert_eq!(loaded.list(), listed);
+	}
 }
@@ -1,4 +1,5 @@
 //! Integration tests covering module rendering scenarios.
+#![cfg(feature = "cargo-resolution")]
 mod utils;
 use utils::*;
 
@@ -84,6 +85,51 @@ gen_tests! {
                 }
             "#
 		}
+		idemp {
+			current_module_paths_stay_short: r#"
+                pub mod widgets {
+                    pub struct Button;
+
+                    pub struct Panel {
+                        pub button: Button,
+                    }
+
+                    pub fn focused(panel: &Panel) -> Button {}
+                }
+            "#
+		}
+		rt {
+			current_module_paths_are_shortened: {
+				input: r#"
+                    pub mod widgets {
+                        pub struct Button;
+
+                        pub struct Panel {
+                            pub button: crate::widgets::Button,
+                            pub parent: crate::Root,
+                        }
+
+                        pub fn focused(panel: &crate::widgets::Panel) -> crate::widgets::Button {}
+                    }
+
+                    pub struct Root;
+                "#,
+				output: r#"
+                    pub mod widgets {
+                        pub struct Button;
+
+                        pub struct Panel {
+                            pub button: Button,
+                            pub parent: crate::Root,
+                        }
+
+                        pub fn focused(panel: &Panel) -> Button {}
+                    }
+
+                    pub struct Root;
+                "#
+			}
+		}
 		rt {
 			module_with_inline_imports: {
 				input: r#"
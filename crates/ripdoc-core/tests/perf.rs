@@ -0,0 +1,52 @@
+//! Smoke guard catching catastrophic slowdowns in indexing, searching and rendering.
+//!
+//! The bounds are far above normal run times, so only a regression of an order of magnitude or
+//! more fails them; `cargo bench -p ripdoc-core` measures the same work precisely.
+
+#![cfg(feature = "cargo-resolution")]
+
+mod fixtures;
+mod utils;
+
+#[cfg(test)]
+mod tests {
+	use std::time::{Duration, Instant};
+
+	use ripdoc_core::search::{SearchIndex, SearchOptions};
+	use ripdoc_core::{RenderFormat, Renderer};
+
+	use super::fixtures::Fixture;
+
+	/// Longest any one step may take on the medium fixture, even on a slow CI machine.
+	const BOUND: Duration = Duration::from_secs(60);
+
+	/// Run `step`, failing if it takes longer than [`BOUND`].
+	fn within_bound<T>(name: &str, step: impl FnOnce() -> T) -> T {
+		let start = Instant::now();
+		let output = step();
+		let elapsed = start.elapsed();
+		assert!(elapsed < BOUND, "{name} took {elapsed:?}, over {BOUND:?}");
+		output
+	}
+
+	#[test]
+	fn medium_fixture_renders_within_bound() {
+		let crate_data = Fixture::Medium.load();
+
+		let index = within_bound("SearchIndex::build", || {
+			SearchIndex::build(&crate_data, false, None)
+		});
+		let results = within_bound("SearchIndex::search", || {
+			index.search(&SearchOptions::new("field3"))
+		});
+		assert!(!results.is_empty());
+
+		let rendered = within_bound("Renderer::render", || {
+			Renderer::default()
+				.with_format(RenderFormat::Rust)
+				.render(&crate_data)
+				.unwrap()
+		});
+		assert!(rendered.contains("pub struct Record9"), "{rendered}");
+	}
+}
@@ -1,11 +1,18 @@
 //! Regression tests for respecting the private item rendering flag.
 
+#![cfg(feature = "cargo-resolution")]
+
 mod utils;
 
 use rustdoc_types::Visibility;
 
 #[cfg(test)]
 mod tests {
+	use std::fs;
+
+	use ripdoc_core::Ripdoc;
+	use tempfile::TempDir;
+
 	use super::Visibility;
 	use super::utils::inspect_crate;
 
@@ -61,4 +68,119 @@ mod tests {
 			item.crate_id == private_root_id && item.name.as_deref() == Some("helper")
 		}));
 	}
+
+	#[test]
+	fn render_falls_back_to_private_items_for_bin_crates() {
+		let temp_dir = TempDir::new().unwrap();
+		fs::create_dir(temp_dir.path().join("src")).unwrap();
+		fs::write(
+			temp_dir.path().join("src/main.rs"),
+			r#"
+                struct Config {
+                    verbose: bool,
+                }
+
+                fn run(config: Config) {}
+
+                fn main() {}
+            "#,
+		)
+		.unwrap();
+		fs::write(
+			temp_dir.path().join("Cargo.toml"),
+			r#"
+                [package]
+                name = "dummy_bin"
+                version = "0.1.0"
+                edition = "2021"
+            "#,
+		)
+		.unwrap();
+		let target = temp_dir.path().to_str().unwrap();
+
+		let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+
+		// Binary targets are documented with private items even when they are not requested, so
+		// the fallback can reuse this JSON instead of rebuilding it.
+		let crate_data = ripdoc.inspect(target, false, false, &[], false).unwrap();
+		assert!(crate_data.includes_private);
+
+		let rendered = ripdoc.render(target, false, false, &[], false).unwrap();
+		assert!(rendered.contains("struct Config"), "{rendered}");
+		assert!(rendered.contains("fn run(config: Config)"), "{rendered}");
+	}
+
+	fn write_lib_crate(source: &str) -> TempDir {
+		let temp_dir = TempDir::new().unwrap();
+		fs::create_dir(temp_dir.path().join("src")).unwrap();
+		fs::write(temp_dir.path().join("src/lib.rs"), source).unwrap();
+		fs::write(
+			temp_dir.path().join("Cargo.toml"),
+			r#"
+                [package]
+                name = "dummy_lib"
+                version = "0.1.0"
+                edition = "2021"
+            "#,
+		)
+		.unwrap();
+		temp_dir
+	}
+
+	#[test]
+	fn private_filter_path_suggests_private_flag() {
+		let temp_dir = write_lib_crate(
+			r#"
+            pub fn exposed() {}
+
+            mod internal {
+                pub fn helper() {}
+            }
+        "#,
+		);
+		let target = format!("{}::internal", temp_dir.path().display());
+		let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+
+		let err = ripdoc
+			.render(&target, false, false, &[], false)
+			.unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"path 'internal' exists but is private; re-run with --private"
+		);
+
+		let rendered = ripdoc.render(&target, false, false, &[], true).unwrap();
+		assert!(rendered.contains("fn helper()"), "{rendered}");
+	}
+
+	#[test]
+	fn mistyped_filter_path_suggests_near_misses() {
+		let temp_dir = write_lib_crate(
+			r#"
+            pub mod widgets {
+                pub struct Button;
+            }
+        "#,
+		);
+		let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
+
+		let target = format!("{}::widgets::Buton", temp_dir.path().display());
+		let err = ripdoc
+			.render(&target, false, false, &[], false)
+			.unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"filter path 'widgets::Buton' did not match any items; did you mean \
+			 'widgets::Button'?"
+		);
+
+		let target = format!("{}::nothing::Here", temp_dir.path().display());
+		let err = ripdoc
+			.render(&target, false, false, &[], false)
+			.unwrap_err();
+		assert_eq!(
+			err.to_string(),
+			"filter path 'nothing::Here' did not match any items"
+		);
+	}
 }
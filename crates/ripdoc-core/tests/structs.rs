@@ -1,4 +1,5 @@
 //! Integration tests covering struct rendering scenarios.
+#![cfg(feature = "cargo-resolution")]
 mod utils;
 use utils::*;
 
@@ -69,6 +70,22 @@ gen_tests! {
 
 gen_tests! {
 	plain_struct, {
+		idemp {
+			repr_c: r#"
+                #[repr(C)]
+                pub struct Foo {
+                    pub a: u8,
+                    pub b: u32,
+                }
+            "#
+		}
+		idemp {
+			repr_transparent_derive: r#"
+                #[derive(Clone, Copy)]
+                #[repr(transparent)]
+                pub struct Handle(pub u64);
+            "#
+		}
 		rt {
 			with_private_fields: {
 				input: r#"
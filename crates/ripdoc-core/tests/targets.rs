@@ -1,8 +1,14 @@
 //! Integration tests for resolving filesystem targets.
 
+#![cfg(feature = "cargo-resolution")]
+
 use std::fs;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
 
-use ripdoc_core::Ripdoc;
+use ripdoc_core::error::{CargoError, RipdocError};
+use ripdoc_core::fallback::SOURCE_FALLBACK_NOTE;
+use ripdoc_core::{LoadOptions, RenderFormat, Ripdoc, Stage};
 use tempfile::tempdir;
 
 #[cfg(test)]
@@ -34,10 +40,339 @@ mod tests {
 
 		let target = format!("{}::DummyStruct", foo_path.display());
 		let ripdoc = Ripdoc::new().with_silent(true);
-		let output = ripdoc.render(&target, false, false, Vec::new(), false)?;
+		let output = ripdoc.render(&target, false, false, &[], false)?;
 
 		assert!(output.contains("pub struct DummyStruct;"));
 
 		Ok(())
 	}
+
+	#[test]
+	fn test_locked_rejects_stale_lockfile() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let root = temp_dir.path();
+		fs::create_dir_all(root.join("app/src"))?;
+		fs::create_dir_all(root.join("helper/src"))?;
+		fs::write(root.join("app/src/lib.rs"), "pub fn run() {}")?;
+		fs::write(root.join("helper/src/lib.rs"), "pub fn help() {}")?;
+		fs::write(
+			root.join("app/Cargo.toml"),
+			r#"
+            [package]
+            name = "app"
+            version = "0.1.0"
+            edition = "2021"
+
+            [dependencies]
+            helper = { path = "../helper" }
+            "#,
+		)?;
+		fs::write(
+			root.join("helper/Cargo.toml"),
+			r#"
+            [package]
+            name = "helper"
+            version = "0.1.0"
+            edition = "2021"
+            "#,
+		)?;
+		// Checked in before `helper` was added.
+		fs::write(
+			root.join("app/Cargo.lock"),
+			"version = 4\n\n[[package]]\nname = \"app\"\nversion = \"0.1.0\"\n",
+		)?;
+
+		let target = root.join("app");
+		let target = target.to_str().unwrap();
+		let ripdoc = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_cache(false);
+
+		let err = ripdoc
+			.clone()
+			.with_locked(true)
+			.render(target, false, false, &[], false)
+			.unwrap_err();
+		assert!(err.to_string().contains("--locked"), "{err}");
+
+		let output = ripdoc.render(target, false, false, &[], false)?;
+		assert!(output.contains("pub fn run()"), "{output}");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_source_fallback_renders_uncompilable_crate() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let root = temp_dir.path();
+		fs::create_dir_all(root.join("src"))?;
+		// rustdoc skips function bodies, so only the unresolved type in a signature stops it.
+		fs::write(
+			root.join("src/lib.rs"),
+			"/// Adds one.\npub fn add_one(x: u32) -> u32 {\n    let y: String = x;\n    y\n}\n\n\
+			 fn hidden() -> Missing {}\n\npub mod shapes;\n",
+		)?;
+		fs::write(
+			root.join("src/shapes.rs"),
+			"/// A circle.\npub struct Circle {\n    /// Radius.\n    pub radius: f64,\n    \
+			 secret: u8,\n}\n\nimpl Circle {\n    pub fn area(&self) -> f64 { 0 }\n}\n",
+		)?;
+		fs::write(
+			root.join("Cargo.toml"),
+			r#"
+            [package]
+            name = "broken-crate"
+            version = "0.1.0"
+            edition = "2021"
+            "#,
+		)?;
+
+		let target = root.to_str().unwrap();
+		let ripdoc = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_cache(false)
+			.with_render_format(RenderFormat::Rust);
+
+		assert!(ripdoc.render(target, false, false, &[], false).is_err());
+
+		let output = ripdoc
+			.with_source_fallback(true)
+			.render(target, false, false, &[], false)?;
+		assert!(
+			output.starts_with(&format!("// {SOURCE_FALLBACK_NOTE}")),
+			"{output}"
+		);
+		assert!(output.contains("pub mod broken_crate {"), "{output}");
+		assert!(output.contains("/// Adds one."), "{output}");
+		assert!(
+			output.contains("pub fn add_one(x: u32) -> u32 {}"),
+			"{output}"
+		);
+		assert!(output.contains("pub mod shapes {"), "{output}");
+		assert!(output.contains("/// Radius."), "{output}");
+		assert!(output.contains("pub fn area(&self) -> f64 {}"), "{output}");
+		assert!(!output.contains("hidden"), "{output}");
+		assert!(!output.contains("secret"), "{output}");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_render_with_metrics_measures_every_stage() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let root = temp_dir.path();
+		fs::create_dir_all(root.join("src"))?;
+		fs::write(
+			root.join("src/lib.rs"),
+			"/// A point.\npub struct Point { pub x: i32, pub y: i32 }\npub fn origin() -> Point { Point { x: 0, y: 0 } }",
+		)?;
+		fs::write(
+			root.join("Cargo.toml"),
+			r#"
+            [package]
+            name = "measured"
+            version = "0.1.0"
+            edition = "2021"
+            "#,
+		)?;
+
+		let ripdoc = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_cache(false)
+			.with_render_format(RenderFormat::Rust);
+		let (output, metrics) =
+			ripdoc.render_with_metrics(root.to_str().unwrap(), false, false, &[], false)?;
+
+		assert!(output.contains("pub struct Point"), "{output}");
+		assert_eq!(metrics.output_bytes, output.len() as u64);
+		assert!(metrics.json_bytes > 0, "{metrics:?}");
+		assert!(metrics.item_count > 0, "{metrics:?}");
+		assert!(!metrics.cached, "{metrics:?}");
+		assert_eq!(metrics.fetch, Duration::ZERO, "{metrics:?}");
+		for (stage, time) in metrics.stages() {
+			if stage != "fetch" {
+				assert!(
+					time > Duration::ZERO,
+					"{stage} was not measured: {metrics:?}"
+				);
+			}
+		}
+
+		// Stages run one after another, so their running total never passes the wall clock.
+		let mut elapsed = Duration::ZERO;
+		for (stage, time) in metrics.stages() {
+			elapsed += time;
+			assert!(
+				elapsed <= metrics.total,
+				"{stage} ends after the render: {metrics:?}"
+			);
+		}
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_inspect_with_path_reports_reusable_json() -> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let root = temp_dir.path();
+		fs::create_dir_all(root.join("src"))?;
+		fs::write(root.join("src/lib.rs"), "/// A widget.\npub struct Widget;")?;
+		fs::write(
+			root.join("Cargo.toml"),
+			r#"
+            [package]
+            name = "reused"
+            version = "0.1.0"
+            edition = "2021"
+            "#,
+		)?;
+
+		let ripdoc = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_cache(false);
+		let json = ripdoc.inspect_with_path(root.to_str().unwrap(), false, false, &[], false)?;
+
+		let path = json
+			.path
+			.expect("a fresh build reports where rustdoc wrote the JSON");
+		assert!(path.is_file(), "{} does not exist", path.display());
+		assert_eq!(
+			path.file_name().and_then(|name| name.to_str()),
+			Some("reused.json")
+		);
+		let reread: rustdoc_types::Crate = serde_json::from_str(&fs::read_to_string(&path)?)?;
+		assert_eq!(reread.index.len(), json.crate_data.index.len());
+		assert!(
+			reread
+				.index
+				.values()
+				.any(|item| item.name.as_deref() == Some("Widget"))
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_builds_running_past_the_timeout_are_stopped() -> Result<(), Box<dyn std::error::Error>>
+	{
+		let temp_dir = tempdir()?;
+		let root = temp_dir.path();
+		fs::create_dir_all(root.join("src"))?;
+		fs::write(root.join("src/lib.rs"), "pub struct Slow;")?;
+		fs::write(
+			root.join("build.rs"),
+			"fn main() { std::thread::sleep(std::time::Duration::from_secs(120)); }",
+		)?;
+		fs::write(
+			root.join("Cargo.toml"),
+			r#"
+            [package]
+            name = "slow"
+            version = "0.1.0"
+            edition = "2021"
+            "#,
+		)?;
+
+		let started = Instant::now();
+		let err = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_cache(false)
+			.with_timeout(Duration::from_secs(5))
+			.render(root.to_str().unwrap(), false, false, &[], false)
+			.unwrap_err();
+
+		assert!(
+			matches!(
+				err,
+				RipdocError::Cargo(CargoError::TimedOut {
+					stage: Stage::Build,
+					..
+				})
+			),
+			"{err:?}"
+		);
+		assert!(started.elapsed() < Duration::from_secs(60), "{err}");
+
+		Ok(())
+	}
+
+	/// Run git with `args` in `dir`, failing the test if it does not succeed.
+	fn git(dir: &std::path::Path, args: &[&str]) {
+		let status = Command::new("git")
+			.args([
+				"-c",
+				"user.name=ripdoc",
+				"-c",
+				"user.email=ripdoc@example.com",
+			])
+			.args(args)
+			.current_dir(dir)
+			.stdout(Stdio::null())
+			.stderr(Stdio::null())
+			.status()
+			.expect("git runs");
+		assert!(status.success(), "git {args:?} failed");
+	}
+
+	#[test]
+	fn test_changed_since_renders_only_items_in_changed_files()
+	-> Result<(), Box<dyn std::error::Error>> {
+		let temp_dir = tempdir()?;
+		let root = temp_dir.path();
+		fs::create_dir_all(root.join("src"))?;
+		fs::write(
+			root.join("src/lib.rs"),
+			"pub mod stable;\npub mod edited;\n",
+		)?;
+		fs::write(root.join("src/stable.rs"), "pub struct Untouched;\n")?;
+		fs::write(root.join("src/edited.rs"), "pub fn before() {}\n")?;
+		fs::write(
+			root.join("Cargo.toml"),
+			r#"
+            [package]
+            name = "changes"
+            version = "0.1.0"
+            edition = "2021"
+            "#,
+		)?;
+		let target = root.to_str().unwrap();
+		let ripdoc = Ripdoc::new()
+			.with_offline(true)
+			.with_silent(true)
+			.with_cache(false)
+			.with_render_format(RenderFormat::Rust);
+		let options = LoadOptions::default();
+
+		let err = ripdoc
+			.render_changed_since(target, &options, "HEAD")
+			.unwrap_err();
+		assert!(
+			err.to_string().contains("not inside a git worktree"),
+			"{err}"
+		);
+
+		fs::write(root.join(".gitignore"), "/target\n")?;
+		git(root, &["init", "--quiet"]);
+		git(root, &["add", "."]);
+		git(root, &["commit", "--quiet", "-m", "initial"]);
+		assert_eq!(ripdoc.render_changed_since(target, &options, "HEAD")?, "");
+
+		fs::write(
+			root.join("src/edited.rs"),
+			"pub fn before() {}\npub fn after() -> u8 { 1 }\n",
+		)?;
+		let output = ripdoc.render_changed_since(target, &options, "HEAD")?;
+		assert!(output.contains("pub mod edited {"), "{output}");
+		assert!(output.contains("pub fn before() {}"), "{output}");
+		assert!(output.contains("pub fn after() -> u8 {}"), "{output}");
+		assert!(!output.contains("stable"), "{output}");
+		assert!(!output.contains("Untouched"), "{output}");
+
+		Ok(())
+	}
 }
@@ -1,4 +1,5 @@
 //! Integration tests ensuring trait rendering stays stable.
+#![cfg(feature = "cargo-resolution")]
 mod utils;
 use utils::*;
 
@@ -39,6 +40,44 @@ gen_tests! {
                 "#
 			}
 		}
+		rt {
+			trait_alias: {
+				input: r#"
+                    #![feature(trait_alias)]
+
+                    pub trait Read {}
+                    pub trait Write {}
+
+                    pub trait ReadWrite = Read + Write;
+                "#,
+				output: r#"
+                    pub trait Read {}
+                    pub trait Write {}
+
+                    pub trait ReadWrite = Read + Write;
+                "#
+			}
+		}
+		rt {
+			generic_trait_alias: {
+				input: r#"
+                    #![feature(trait_alias)]
+
+                    pub trait Sink<T> {}
+
+                    pub trait CloneSink<T> = Sink<T> + Clone + Send + 'static
+                    where
+                        T: Clone;
+                "#,
+				output: r#"
+                    pub trait Sink<T> {}
+
+                    pub trait CloneSink<T> = Sink<T> + Clone + Send + 'static
+                    where
+                        T: Clone;
+                "#
+			}
+		}
 		rt {
 			private_trait: {
 				input: r#"
@@ -1,5 +1,6 @@
 //! Utility helpers shared across integration tests for exercising Ripdoc rendering.
 
+#![cfg(feature = "cargo-resolution")]
 #![allow(
 	dead_code,
 	reason = "Integration test crates all use a disjoint set of the pub items."
@@ -98,7 +99,7 @@ pub fn inspect_crate(source: &str, private_items: bool, is_proc_macro: bool) ->
 	let (_temp_dir, target) = create_test_crate(source, is_proc_macro);
 	let ripdoc = Ripdoc::new().with_offline(true).with_silent(true);
 	ripdoc
-		.inspect(&target, false, false, Vec::new(), private_items)
+		.inspect(&target, false, false, &[], private_items)
 		.unwrap()
 }
 
@@ -0,0 +1,210 @@
+//! Rendering skeletons that fit a size budget.
+//!
+//! The crate is first rendered in full. While the output is over budget, it is rendered again
+//! with one more degradation step applied, always in this order:
+//!
+//! 1. Doc comments are dropped.
+//! 2. Structs with more than [`LARGE_STRUCT_FIELDS`] fields list a count of their fields
+//!    instead of the fields themselves.
+//! 3. The most deeply nested modules list a count of their items instead of the items. The
+//!    cut-off then moves up one level at a time until every module below the crate root is
+//!    collapsed.
+//!
+//! Steps are never undone, so the same crate and budget always produce the same output. What
+//! was elided is reported in a comment at the end; when even the last step does not fit, the
+//! most degraded output is returned with a comment saying it is still over budget, rather
+//! than being cut mid-item.
+
+use std::time::Duration;
+
+use rustdoc_types::{Crate, Id, ItemEnum};
+
+use crate::core::{RenderFormat, Renderer};
+use crate::error::Result;
+use crate::report::RenderReport;
+
+/// Structs with more fields than this have their fields elided by the second degradation step.
+pub const LARGE_STRUCT_FIELDS: usize = 8;
+
+/// Upper bound on the size of rendered output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderBudget {
+	/// Maximum length of the output in bytes.
+	Bytes(usize),
+	/// Maximum number of tokens, estimated as the number of whitespace-separated words.
+	Tokens(usize),
+}
+
+impl RenderBudget {
+	/// Measure `text` in this budget's unit.
+	pub fn measure(self, text: &str) -> usize {
+		match self {
+			Self::Bytes(_) => text.len(),
+			Self::Tokens(_) => text.split_whitespace().count(),
+		}
+	}
+
+	/// Whether `text` fits within the budget.
+	pub fn fits(self, text: &str) -> bool {
+		self.measure(text) <= self.limit()
+	}
+
+	/// Largest size allowed, in this budget's unit.
+	pub fn limit(self) -> usize {
+		match self {
+			Self::Bytes(limit) | Self::Tokens(limit) => limit,
+		}
+	}
+
+	/// Name of the unit the budget is measured in.
+	fn unit(self) -> &'static str {
+		match self {
+			Self::Bytes(_) => "bytes",
+			Self::Tokens(_) => "tokens",
+		}
+	}
+}
+
+/// Details left out of a rendering to save space.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Elision {
+	/// Whether doc comments are dropped.
+	pub docs: bool,
+	/// Structs with more fields than this render a count of their fields instead.
+	pub fields_above: Option<usize>,
+	/// Modules nested more than this many levels below the crate root render a count of their
+	/// items instead.
+	pub module_depth: Option<usize>,
+}
+
+impl Elision {
+	/// Whether a struct with `fields` rendered fields has them elided.
+	pub fn elides_fields(&self, fields: usize) -> bool {
+		self.fields_above.is_some_and(|limit| fields > limit)
+	}
+
+	/// Whether a module `depth` levels below the crate root has its items elided.
+	pub fn elides_module(&self, depth: usize) -> bool {
+		self.module_depth.is_some_and(|limit| depth > limit)
+	}
+
+	/// Human-readable list of what is elided, in degradation order.
+	fn describe(&self) -> Vec<String> {
+		let mut elided = Vec::new();
+		if self.docs {
+			elided.push("doc comments".to_string());
+		}
+		if let Some(limit) = self.fields_above {
+			elided.push(format!("fields of structs with more than {limit} fields"));
+		}
+		match self.module_depth {
+			Some(0) => elided.push("items of every module below the crate root".to_string()),
+			Some(limit) => elided.push(format!("items of modules nested more than {limit} deep")),
+			None => {}
+		}
+		elided
+	}
+}
+
+/// One step of degradation, applied on top of the steps before it.
+#[derive(Debug, Clone, Copy)]
+enum Step {
+	/// Drop doc comments.
+	Docs,
+	/// Elide the fields of large structs.
+	Fields,
+	/// Elide the items of modules nested deeper than the given depth.
+	ModuleDepth(usize),
+}
+
+impl Step {
+	fn apply(self, elision: &mut Elision) {
+		match self {
+			Self::Docs => elision.docs = true,
+			Self::Fields => elision.fields_above = Some(LARGE_STRUCT_FIELDS),
+			Self::ModuleDepth(depth) => elision.module_depth = Some(depth),
+		}
+	}
+}
+
+/// Render `crate_data` with `renderer`, degrading the output until it fits `budget`, and
+/// return the time spent formatting over every attempt.
+pub(crate) fn render_within_budget(
+	renderer: &Renderer,
+	crate_data: &Crate,
+	budget: RenderBudget,
+) -> Result<(String, RenderReport, Duration)> {
+	let deepest = module_depth(crate_data, &crate_data.root);
+	let mut steps = [Step::Docs, Step::Fields]
+		.into_iter()
+		.chain((0..deepest).rev().map(Step::ModuleDepth));
+	let mut elision = Elision::default();
+	let mut undocumented = None;
+	let mut format_time = Duration::ZERO;
+
+	loop {
+		let data = if elision.docs {
+			&*undocumented.get_or_insert_with(|| without_docs(crate_data))
+		} else {
+			crate_data
+		};
+		let (rendered, skipped, attempt_time) = renderer.render_elided(data, elision)?;
+		format_time += attempt_time;
+		let fitted = rendered.clone() + &report(renderer.format, budget, &elision, true);
+		if budget.fits(&fitted) {
+			return Ok((fitted, skipped, format_time));
+		}
+		match steps.next() {
+			Some(step) => step.apply(&mut elision),
+			None => {
+				let rendered = rendered + &report(renderer.format, budget, &elision, false);
+				return Ok((rendered, skipped, format_time));
+			}
+		}
+	}
+}
+
+/// Trailing comment listing what `elision` left out, or nothing when it left nothing out and
+/// the output fits.
+fn report(format: RenderFormat, budget: RenderBudget, elision: &Elision, fits: bool) -> String {
+	let elided = elision.describe().join("; ");
+	let limit = format!("{} {}", budget.limit(), budget.unit());
+	let message = match (fits, elided.is_empty()) {
+		(true, true) => return String::new(),
+		(true, false) => format!("elided to fit {limit}: {elided}"),
+		(false, true) => format!("still over {limit}"),
+		(false, false) => format!("still over {limit} after eliding {elided}"),
+	};
+	match format {
+		RenderFormat::Markdown => format!("\n<!-- {message} -->\n"),
+		RenderFormat::Rust | RenderFormat::Json => format!("\n// {message}\n"),
+	}
+}
+
+/// Number of levels of modules nested below the module `id`.
+fn module_depth(crate_data: &Crate, id: &Id) -> usize {
+	let Some(ItemEnum::Module(module)) = crate_data.index.get(id).map(|item| &item.inner) else {
+		return 0;
+	};
+	module
+		.items
+		.iter()
+		.filter(|child| {
+			crate_data
+				.index
+				.get(child)
+				.is_some_and(|item| matches!(item.inner, ItemEnum::Module(_)))
+		})
+		.map(|child| 1 + module_depth(crate_data, child))
+		.max()
+		.unwrap_or(0)
+}
+
+/// Copy of `crate_data` with every doc comment removed.
+fn without_docs(crate_data: &Crate) -> Crate {
+	let mut crate_data = crate_data.clone();
+	for item in crate_data.index.values_mut() {
+		item.docs = None;
+	}
+	crate_data
+}
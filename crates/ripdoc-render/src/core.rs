@@ -1,18 +1,66 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::time::{Duration, Instant};
 
-use rust_format::{Config, Formatter, RustFmt};
-use rustdoc_types::{Crate, Id};
+use rust_format::{Config, RustFmt};
+use rustdoc_types::{Crate, FunctionSignature, Id, Item, ItemEnum, StructKind, VariantKind};
+use serde::Deserialize;
 
-use crate::error::Result;
-use crate::markdown;
+use crate::budget::{Elision, RenderBudget, render_within_budget};
+use crate::error::{Result, RipdocError};
+use crate::formatter::FormatterChoice;
+use crate::impls::DERIVE_TRAITS;
+use crate::markdown::MarkdownStream;
+use crate::report::RenderReport;
+use crate::split::{DEFAULT_SPLIT_MODULE_BYTES, ModuleTree};
+use crate::state::RenderChunk;
+use crate::syntax::{DEFAULT_MAX_TYPE_DEPTH, TypeDepthLimit};
+
+/// Default amount of unformatted output buffered before a chunk is formatted and written.
+pub const DEFAULT_STREAM_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Default number of lines into a module body after which items get a breadcrumb comment.
+pub const DEFAULT_BREADCRUMB_LINES: usize = 100;
 
 /// Supported high-level output formats.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum RenderFormat {
 	/// Render the crate as formatted Rust code (default).
+	#[serde(alias = "rs")]
 	Rust,
 	/// Render the crate using a Markdown-friendly layout.
+	#[serde(alias = "md")]
 	Markdown,
+	/// Render the crate as a structured JSON tree of skeleton nodes.
+	Json,
+}
+
+/// What the bodies of rendered functions contain.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BodyStyle {
+	/// Empty bodies, `{}`, which keep the skeleton compact but do not type-check for functions
+	/// returning a value.
+	#[default]
+	Empty,
+	/// `{ todo!() }` for functions returning a value, so the skeleton type-checks as a stub
+	/// crate.
+	Todo,
+	/// `{ unimplemented!() }` for functions returning a value.
+	Unimplemented,
+}
+
+impl BodyStyle {
+	/// Body, with its leading space, of a function with signature `sig`; functions returning
+	/// `()` always get `{}`.
+	pub fn body(self, sig: &FunctionSignature) -> &'static str {
+		match self {
+			_ if sig.output.is_none() => " {}",
+			Self::Empty => " {}",
+			Self::Todo => " { todo!() }",
+			Self::Unimplemented => " { unimplemented!() }",
+		}
+	}
 }
 
 /// Selection of item identifiers used when rendering subsets of a crate.
@@ -39,6 +87,60 @@ impl RenderSelection {
 		}
 	}
 
+	/// Select `ids` along with the modules, types and impl blocks enclosing them, so each is
+	/// rendered in place.
+	///
+	/// Parents are found by walking down from the crate root through modules, the fields,
+	/// variants and impls of types, and the members of traits and impls. Members of a trait impl
+	/// also keep the trait as context. With `expand`, selected modules, structs and traits keep
+	/// all of their children as well.
+	pub fn for_items(crate_data: &Crate, ids: &[Id], expand: bool) -> Self {
+		let parents = parent_links(crate_data);
+		let matches: HashSet<Id> = ids.iter().copied().collect();
+		let mut context = matches.clone();
+		for id in ids {
+			let mut current = *id;
+			while let Some(parent) = parents.get(&current) {
+				context.insert(*parent);
+				if let Some(ItemEnum::Impl(impl_)) = crate_data.index.get(parent).map(|i| &i.inner)
+					&& let Some(trait_) = &impl_.trait_
+					&& crate_data.index.contains_key(&trait_.id)
+				{
+					context.insert(trait_.id);
+				}
+				current = *parent;
+			}
+		}
+
+		let mut expanded = HashSet::new();
+		if expand {
+			let mut pending: Vec<Id> = ids
+				.iter()
+				.filter(|id| {
+					crate_data.index.get(id).is_some_and(|item| {
+						matches!(
+							item.inner,
+							ItemEnum::Module(_) | ItemEnum::Struct(_) | ItemEnum::Trait(_)
+						)
+					})
+				})
+				.copied()
+				.collect();
+			while let Some(id) = pending.pop() {
+				let Some(item) = crate_data.index.get(&id) else {
+					continue;
+				};
+				let children = child_ids(crate_data, item);
+				if !children.is_empty() && expanded.insert(id) {
+					context.extend(children.iter().copied());
+					pending.extend(children);
+				}
+			}
+		}
+
+		Self::new(matches, context, expanded)
+	}
+
 	/// Identifiers for items that should be fully rendered.
 	pub fn matches(&self) -> &HashSet<Id> {
 		&self.matches
@@ -55,20 +157,139 @@ impl RenderSelection {
 	}
 }
 
+/// Map each item reachable from the crate root to the item it is rendered inside.
+fn parent_links(crate_data: &Crate) -> HashMap<Id, Id> {
+	let mut parents = HashMap::new();
+	let mut pending = vec![crate_data.root];
+	while let Some(id) = pending.pop() {
+		let Some(item) = crate_data.index.get(&id) else {
+			continue;
+		};
+		for child in child_ids(crate_data, item) {
+			if child != crate_data.root && !parents.contains_key(&child) {
+				parents.insert(child, id);
+				pending.push(child);
+			}
+		}
+	}
+	parents
+}
+
+/// Items rendered directly inside `item`.
+///
+/// Impl blocks belong to the type they implement rather than the module listing them.
+pub(crate) fn child_ids(crate_data: &Crate, item: &Item) -> Vec<Id> {
+	match &item.inner {
+		ItemEnum::Module(module) => module
+			.items
+			.iter()
+			.filter(|id| {
+				!matches!(
+					crate_data.index.get(id).map(|child| &child.inner),
+					Some(ItemEnum::Impl(_))
+				)
+			})
+			.copied()
+			.collect(),
+		ItemEnum::Struct(struct_) => {
+			let fields: Vec<Id> = match &struct_.kind {
+				StructKind::Unit => Vec::new(),
+				StructKind::Tuple(fields) => fields.iter().flatten().copied().collect(),
+				StructKind::Plain { fields, .. } => fields.clone(),
+			};
+			fields
+				.into_iter()
+				.chain(struct_.impls.iter().copied())
+				.collect()
+		}
+		ItemEnum::Union(union_) => union_.fields.iter().chain(&union_.impls).copied().collect(),
+		ItemEnum::Enum(enum_) => enum_.variants.iter().chain(&enum_.impls).copied().collect(),
+		ItemEnum::Variant(variant) => match &variant.kind {
+			VariantKind::Plain => Vec::new(),
+			VariantKind::Tuple(fields) => fields.iter().flatten().copied().collect(),
+			VariantKind::Struct { fields, .. } => fields.clone(),
+		},
+		ItemEnum::Trait(trait_) => trait_.items.clone(),
+		ItemEnum::Impl(impl_) => impl_.items.clone(),
+		_ => Vec::new(),
+	}
+}
+
 /// Configurable renderer that turns rustdoc data into skeleton Rust source.
 pub struct Renderer {
 	/// Formatter used to produce tidy Rust output.
 	pub formatter: RustFmt,
+	/// Which formatter tidies the output.
+	pub formatter_choice: FormatterChoice,
 	/// Target output format.
 	pub format: RenderFormat,
+	/// Whether impl blocks are rendered; derived traits still appear as `#[derive(...)]`.
+	pub render_impls: bool,
 	/// Whether auto trait implementations should be included in the output.
 	pub render_auto_impls: bool,
+	/// Whether each struct and enum is followed by a comment listing its auto trait impls.
+	pub render_auto_trait_summary: bool,
+	/// Whether blanket and auto impls are rendered as stubs for types matched by the selection.
+	pub render_blanket_impls: bool,
+	/// Whether each trait is followed by a list of the crate's implementations of it.
+	pub render_trait_implementors: bool,
 	/// Whether private items should be rendered.
 	pub render_private_items: bool,
+	/// Whether private traits named in rendered bounds are declared as empty stubs, rather than
+	/// the bounds being dropped.
+	pub render_sealed_stubs: bool,
+	/// Traits whose derived impls collapse into `#[derive(...)]` annotations.
+	pub derive_traits: Vec<String>,
+	/// Whether items marked `#[doc(hidden)]` should be rendered.
+	pub render_doc_hidden: bool,
+	/// Whether `#[cfg(test)] mod tests` modules and items from build scripts should be rendered.
+	pub render_tests: bool,
+	/// Whether layout, linkage, and `#[must_use]` attributes are rendered above items.
+	pub render_attrs: bool,
+	/// Whether re-exports of external types should be resolved to their definition paths.
+	pub inline_external_reexports: bool,
+	/// Whether items inlined from other crates through a re-export end their first line with
+	/// `// from <crate>`.
+	pub render_origin_comments: bool,
+	/// Whether modules are annotated with `// path:` comments naming them, for Rust output.
+	pub render_breadcrumbs: bool,
+	/// Unformatted lines into a module body after which each further item is preceded by a
+	/// breadcrumb.
+	pub breadcrumb_lines: usize,
+	/// Whether methods desugared by `#[async_trait]` render as the `async fn` they were written as.
+	pub resugar_async: bool,
+	/// Whether intra-doc links in doc comments are rewritten to plain paths; `None` rewrites
+	/// them for Markdown output only.
+	pub resolve_doc_links: Option<bool>,
+	/// What the bodies of functions and methods contain.
+	pub body_style: BodyStyle,
 	/// Filter path relative to the crate root.
 	pub filter: String,
 	/// Optional selection restricting which items are rendered.
 	pub selection: Option<RenderSelection>,
+	/// Unformatted bytes buffered before [`Renderer::render_to`] formats and writes a chunk.
+	pub stream_chunk_bytes: usize,
+	/// Unformatted bytes of body above which [`Renderer::render_tree`] gives a nested module
+	/// its own file.
+	pub split_module_bytes: usize,
+	/// Whether output starts with the [`Renderer::header`] describing the crate.
+	pub render_header: bool,
+	/// Where the crate came from, such as `registry` or `workspace member`, for the header.
+	pub header_source: Option<String>,
+	/// Version requirement the crate's version was picked for, such as `^1`, for the header.
+	pub header_requested: Option<String>,
+	/// Features the crate was documented with, as listed in the header.
+	pub header_features: Vec<String>,
+	/// Features cargo actually enabled for the crate, listed in the header when known.
+	pub header_resolved_features: Option<Vec<String>>,
+	/// `rustc --version` of the toolchain that produced the rustdoc JSON, for the header.
+	pub header_toolchain: Option<String>,
+	/// Target triple the crate was documented for, for the header; `None` means the host.
+	pub header_target: Option<String>,
+	/// Size the output is degraded to fit, as described in [`crate::budget`]; ignored for JSON.
+	pub budget: Option<RenderBudget>,
+	/// Nesting depth after which the rest of a type renders as [`crate::syntax::ELIDED_TYPE`].
+	pub max_type_depth: usize,
 }
 
 impl Default for Renderer {
@@ -83,14 +304,49 @@ impl Renderer {
 		let config = Config::new_str().option("brace_style", "PreferSameLine");
 		Self {
 			formatter: RustFmt::from_config(config),
+			formatter_choice: FormatterChoice::default(),
 			format: RenderFormat::Markdown,
+			render_impls: true,
 			render_auto_impls: false,
+			render_auto_trait_summary: false,
+			render_blanket_impls: false,
+			render_trait_implementors: false,
 			render_private_items: false,
+			render_sealed_stubs: true,
+			derive_traits: DERIVE_TRAITS.iter().map(|name| name.to_string()).collect(),
+			render_doc_hidden: false,
+			render_tests: false,
+			render_attrs: true,
+			inline_external_reexports: false,
+			render_origin_comments: false,
+			render_breadcrumbs: false,
+			breadcrumb_lines: DEFAULT_BREADCRUMB_LINES,
+			resugar_async: true,
+			resolve_doc_links: None,
+			body_style: BodyStyle::default(),
 			filter: String::new(),
 			selection: None,
+			stream_chunk_bytes: DEFAULT_STREAM_CHUNK_BYTES,
+			split_module_bytes: DEFAULT_SPLIT_MODULE_BYTES,
+			render_header: false,
+			header_source: None,
+			header_requested: None,
+			header_features: Vec::new(),
+			header_resolved_features: None,
+			header_toolchain: None,
+			header_target: None,
+			budget: None,
+			max_type_depth: DEFAULT_MAX_TYPE_DEPTH,
 		}
 	}
 
+	/// Choose how output is formatted: with `rustfmt`, in process with `prettyplease`, or only
+	/// re-indented.
+	pub fn with_formatter(mut self, formatter_choice: FormatterChoice) -> Self {
+		self.formatter_choice = formatter_choice;
+		self
+	}
+
 	/// Apply a filter to output. The filter is a path BELOW the outermost module.
 	pub fn with_filter(mut self, filter: &str) -> Self {
 		self.filter = filter.to_string();
@@ -103,9 +359,57 @@ impl Renderer {
 		self
 	}
 
+	/// Render impl blocks? When disabled, only declarations remain, with derived traits
+	/// still summarized as `#[derive(...)]`.
+	pub fn with_impls(mut self, render_impls: bool) -> Self {
+		self.render_impls = render_impls;
+		self
+	}
+
 	/// Render auto-implemented traits like `Send` and `Sync`.
+	///
+	/// Enabling this turns off [`Self::with_auto_trait_summary`].
 	pub fn with_auto_impls(mut self, render_auto_impls: bool) -> Self {
 		self.render_auto_impls = render_auto_impls;
+		self.render_auto_trait_summary &= !render_auto_impls;
+		self
+	}
+
+	/// Follow each struct and enum declaration with a single comment listing the auto traits
+	/// it implements, such as `// auto traits: Send, Unpin, !Sync`, instead of an impl block
+	/// per trait.
+	///
+	/// Enabling this turns off [`Self::with_auto_impls`].
+	pub fn with_auto_trait_summary(mut self, render_auto_trait_summary: bool) -> Self {
+		self.render_auto_trait_summary = render_auto_trait_summary;
+		self.render_auto_impls &= !render_auto_trait_summary;
+		self
+	}
+
+	/// Render blanket and auto impls as stubs on types explicitly matched by the selection.
+	pub fn with_blanket_impls(mut self, render_blanket_impls: bool) -> Self {
+		self.render_blanket_impls = render_blanket_impls;
+		self
+	}
+
+	/// List the crate's implementations of each rendered trait below its definition.
+	pub fn with_trait_implementors(mut self, render_trait_implementors: bool) -> Self {
+		self.render_trait_implementors = render_trait_implementors;
+		self
+	}
+
+	/// Render `#[repr]`, `#[no_mangle]`, `#[export_name]`, `#[link_name]`, and `#[must_use]`?
+	pub fn with_attrs(mut self, render_attrs: bool) -> Self {
+		self.render_attrs = render_attrs;
+		self
+	}
+
+	/// Replace the traits whose derived impls collapse into `#[derive(...)]` annotations.
+	///
+	/// Defaults to [`DERIVE_TRAITS`]; extend that list to keep the built-in set. Impls of these
+	/// traits are only collapsed when rustdoc marks them `#[automatically_derived]`.
+	pub fn with_derive_traits(mut self, derive_traits: Vec<String>) -> Self {
+		self.derive_traits = derive_traits;
 		self
 	}
 
@@ -115,30 +419,520 @@ impl Renderer {
 		self
 	}
 
+	/// Declare private traits named in bounds, like the `sealed::Sealed` of a sealed trait, as
+	/// empty `pub trait` stubs at the end of the crate. When off, such bounds are dropped and
+	/// marked with a `/* private bound omitted */` comment.
+	pub fn with_sealed_stubs(mut self, render_sealed_stubs: bool) -> Self {
+		self.render_sealed_stubs = render_sealed_stubs;
+		self
+	}
+
+	/// Render `#[cfg(test)] mod tests` modules and items defined in build scripts, which are
+	/// skipped by default.
+	pub fn with_tests(mut self, render_tests: bool) -> Self {
+		self.render_tests = render_tests;
+		self
+	}
+
+	/// Render items marked `#[doc(hidden)]`?
+	pub fn with_doc_hidden(mut self, render_doc_hidden: bool) -> Self {
+		self.render_doc_hidden = render_doc_hidden;
+		self
+	}
+
+	/// Render re-exported external structs, enums, and traits as `use` lines naming their
+	/// definition path and kind.
+	pub fn with_inline_external_reexports(mut self, inline_external_reexports: bool) -> Self {
+		self.inline_external_reexports = inline_external_reexports;
+		self
+	}
+
+	/// Mark items inlined from another crate through a re-export, such as a facade crate's
+	/// `pub use`, with a `// from <crate>` comment naming the crate that defines them.
+	pub fn with_origin_comments(mut self, render_origin_comments: bool) -> Self {
+		self.render_origin_comments = render_origin_comments;
+		self
+	}
+
+	/// Follow each module's opening line with a `// path: crate::module` comment naming it, and
+	/// repeat the comment before items more than [`Renderer::breadcrumb_lines`] lines into a
+	/// module body, so readers of long output can tell where they are.
+	///
+	/// Markdown output never gets breadcrumbs, since its headings already show the location.
+	pub fn with_breadcrumbs(mut self, render_breadcrumbs: bool) -> Self {
+		self.render_breadcrumbs = render_breadcrumbs;
+		self
+	}
+
+	/// Set how many unformatted lines into a module body items start getting breadcrumbs.
+	pub fn with_breadcrumb_lines(mut self, breadcrumb_lines: usize) -> Self {
+		self.breadcrumb_lines = breadcrumb_lines;
+		self
+	}
+
+	/// Render methods expanded by `#[async_trait]`, whose signatures return
+	/// `Pin<Box<dyn Future<Output = T> + Send + 'async_trait>>`, as the `async fn ... -> T` they
+	/// were written as, marked with a `// via #[async_trait]` comment.
+	///
+	/// Only signatures with the macro's `'async_trait` lifetime are rewritten; hand-written
+	/// boxed futures render unchanged.
+	pub fn with_resugar_async(mut self, resugar_async: bool) -> Self {
+		self.resugar_async = resugar_async;
+		self
+	}
+
+	/// Rewrite intra-doc links in doc comments to plain text: ``[`Crate`]`` becomes the linked
+	/// item's canonical path in inline code, and links to `crate::` paths keep only their text.
+	///
+	/// Without this call, links are rewritten for Markdown output, where they would otherwise
+	/// render broken, and kept as written for Rust output. JSON nodes always carry the docs as
+	/// written.
+	pub fn with_resolved_doc_links(mut self, resolve_doc_links: bool) -> Self {
+		self.resolve_doc_links = Some(resolve_doc_links);
+		self
+	}
+
+	/// Whether doc comments are rendered with their intra-doc links rewritten, as set by
+	/// [`Renderer::with_resolved_doc_links`].
+	pub fn resolves_doc_links(&self) -> bool {
+		self.resolve_doc_links
+			.unwrap_or(self.format == RenderFormat::Markdown)
+	}
+
+	/// Choose what function bodies contain: `{}`, or a `todo!()` or `unimplemented!()` call in
+	/// functions returning a value so the skeleton compiles as a stub crate.
+	pub fn with_body_style(mut self, body_style: BodyStyle) -> Self {
+		self.body_style = body_style;
+		self
+	}
+
 	/// Restrict rendering to the provided selection.
 	pub fn with_selection(mut self, selection: RenderSelection) -> Self {
 		self.selection = Some(selection);
 		self
 	}
 
-	/// Render a crate into formatted Rust source text.
+	/// Start the output with a header naming the crate, its version and features, and the
+	/// versions of ripdoc and the toolchain.
+	pub fn with_header(mut self, render_header: bool) -> Self {
+		self.render_header = render_header;
+		self
+	}
+
+	/// Where the crate came from, listed in the header when set.
+	pub fn with_header_source(mut self, header_source: Option<String>) -> Self {
+		self.header_source = header_source;
+		self
+	}
+
+	/// Version requirement to note after the crate's version in the header, when it was resolved
+	/// from one rather than given exactly.
+	pub fn with_header_requested(mut self, header_requested: Option<String>) -> Self {
+		self.header_requested = header_requested;
+		self
+	}
+
+	/// Features to list in the header.
+	pub fn with_header_features(mut self, header_features: Vec<String>) -> Self {
+		self.header_features = header_features;
+		self
+	}
+
+	/// Features cargo enabled once they were unified across the build, to list in the header
+	/// after the requested ones.
+	pub fn with_header_resolved_features(
+		mut self,
+		header_resolved_features: Option<Vec<String>>,
+	) -> Self {
+		self.header_resolved_features = header_resolved_features;
+		self
+	}
+
+	/// Toolchain version, as printed by `rustc --version`, to name in the header.
+	pub fn with_header_toolchain(mut self, header_toolchain: Option<String>) -> Self {
+		self.header_toolchain = header_toolchain;
+		self
+	}
+
+	/// Target triple to name in the header, when documenting for something other than the host.
+	pub fn with_header_target(mut self, header_target: Option<String>) -> Self {
+		self.header_target = header_target;
+		self
+	}
+
+	/// Degrade the output step by step until it fits `budget`, or render everything with `None`.
+	pub fn with_budget(mut self, budget: Option<RenderBudget>) -> Self {
+		self.budget = budget;
+		self
+	}
+
+	/// Elide types nested deeper than `max_type_depth`, such as the long chains of generics
+	/// produced by type-level arithmetic.
+	pub fn with_max_type_depth(mut self, max_type_depth: usize) -> Self {
+		self.max_type_depth = max_type_depth;
+		self
+	}
+
+	/// Set how much unformatted output is buffered before a streamed chunk is written.
+	pub fn with_stream_chunk_bytes(mut self, stream_chunk_bytes: usize) -> Self {
+		self.stream_chunk_bytes = stream_chunk_bytes;
+		self
+	}
+
+	/// Set how large a nested module's unformatted body must be for [`Renderer::render_tree`]
+	/// to give it its own file.
+	pub fn with_split_module_bytes(mut self, split_module_bytes: usize) -> Self {
+		self.split_module_bytes = split_module_bytes;
+		self
+	}
+
+	/// Format rendered Rust source with the configured [`FormatterChoice`].
+	pub fn format_source(&self, source: String) -> Result<String> {
+		crate::formatter::format_source(self.formatter_choice, &self.formatter, source)
+	}
+
+	/// Render a crate into formatted text in the configured output format.
 	pub fn render(&self, crate_data: &Crate) -> Result<String> {
+		Ok(self.render_with_report(crate_data)?.0)
+	}
+
+	/// Render a crate like [`Renderer::render`], also reporting the visible items whose kinds
+	/// the renderer does not support and left out.
+	pub fn render_with_report(&self, crate_data: &Crate) -> Result<(String, RenderReport)> {
+		let (output, report, _) = self.render_timed(crate_data)?;
+		Ok((output, report))
+	}
+
+	/// Render a crate like [`Renderer::render_with_report`], also returning the time spent in
+	/// the formatter, summed over every attempt of a budgeted render.
+	pub fn render_timed(&self, crate_data: &Crate) -> Result<(String, RenderReport, Duration)> {
+		let mut output = Vec::new();
+		let (report, format_time) = self.render_reported_to(crate_data, &mut output)?;
+		let output = String::from_utf8(output)
+			.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+		Ok((output, report, format_time))
+	}
+
+	/// Render a crate as formatted Rust source split into one file per module, ready to be
+	/// laid out as `lib.rs` and `<module>/mod.rs` files.
+	///
+	/// Top-level modules always get their own file; nested ones do when their bodies are over
+	/// [`Renderer::split_module_bytes`], or when a module inside them does. The output is Rust
+	/// whatever [`Renderer::format`] says, and is not degraded to fit a budget. The header, if
+	/// enabled, starts the root's source. Returns `None` when the crate root is not rendered.
+	pub fn render_tree(&self, crate_data: &Crate) -> Result<Option<ModuleTree>> {
+		use super::state::RenderState;
+
+		let _type_depth = TypeDepthLimit::set(self.max_type_depth);
+		let mut state = RenderState::new(self, crate_data);
+		let tree = match state.render_tree(self.split_module_bytes) {
+			Err(RipdocError::FilterNotMatched(_)) => {
+				state.fold_filter()?.render_tree(self.split_module_bytes)?
+			}
+			result => result?,
+		};
+		let Some(mut tree) = tree else {
+			return Ok(None);
+		};
+
+		self.format_tree(&mut tree)?;
+		if self.render_header {
+			let header = self.header_as(crate_data, RenderFormat::Rust);
+			tree.source.insert_str(0, &header);
+		}
+		Ok(Some(tree))
+	}
+
+	/// Format the source of `tree` and of every module below it.
+	fn format_tree(&self, tree: &mut ModuleTree) -> Result<()> {
+		tree.source = self.format_source(std::mem::take(&mut tree.source))?;
+		for child in &mut tree.children {
+			self.format_tree(child)?;
+		}
+		Ok(())
+	}
+
+	/// Describe `crate_data` and how it was documented.
+	///
+	/// Rust output gets one `//` comment line and Markdown a front-matter block; JSON gets
+	/// nothing. There is no timestamp, so the same inputs always produce the same header.
+	pub fn header(&self, crate_data: &Crate) -> String {
+		self.header_as(crate_data, self.format)
+	}
+
+	/// [`Renderer::header`] for output in `format`.
+	fn header_as(&self, crate_data: &Crate, format: RenderFormat) -> String {
+		let name = crate_data
+			.index
+			.get(&crate_data.root)
+			.and_then(|root| root.name.as_deref())
+			.unwrap_or("unknown");
+		let mut krate = match &crate_data.crate_version {
+			Some(version) => format!("{name} v{version}"),
+			None => name.to_string(),
+		};
+		if let Some(requested) = &self.header_requested {
+			krate.push_str(&format!(" (requested {requested})"));
+		}
+		let mut fields = vec![("crate", krate)];
+		if let Some(source) = &self.header_source {
+			fields.push(("source", source.clone()));
+		}
+		fields.push(("features", format!("[{}]", self.header_features.join(", "))));
+		if let Some(resolved) = &self.header_resolved_features {
+			fields.push(("resolved-features", format!("[{}]", resolved.join(", "))));
+		}
+		if let Some(target) = &self.header_target {
+			fields.push(("target", target.clone()));
+		}
+		fields.push((
+			"generated-by",
+			format!("ripdoc {}", env!("CARGO_PKG_VERSION")),
+		));
+		if let Some(toolchain) = &self.header_toolchain {
+			let version = toolchain.trim().trim_start_matches("rustc ").to_string();
+			fields.push(("nightly", version));
+		}
+
+		match format {
+			RenderFormat::Rust => {
+				let fields: Vec<String> = fields
+					.iter()
+					.map(|(key, value)| format!("{key}: {value}"))
+					.collect();
+				format!("// {}\n\n", fields.join("  "))
+			}
+			RenderFormat::Markdown => {
+				let mut header = String::from("---\n");
+				for (key, value) in fields {
+					header.push_str(&format!("{key}: {value}\n"));
+				}
+				header.push_str("---\n\n");
+				header
+			}
+			RenderFormat::Json => String::new(),
+		}
+	}
+
+	/// Render a crate, streaming formatted output to `writer` one chunk of top-level items at a
+	/// time so the whole skeleton never has to be held in memory.
+	///
+	/// With a [`Renderer::budget`], the output is instead buffered and re-rendered until it fits.
+	pub fn render_to<W: io::Write>(&self, crate_data: &Crate, writer: W) -> Result<()> {
+		self.render_reported_to(crate_data, writer)?;
+		Ok(())
+	}
+
+	/// Stream a rendering of a crate to `writer` as [`Renderer::render_to`] does, returning
+	/// what was skipped and the time spent formatting.
+	fn render_reported_to<W: io::Write>(
+		&self,
+		crate_data: &Crate,
+		mut writer: W,
+	) -> Result<(RenderReport, Duration)> {
+		if let Some(budget) = self.budget
+			&& self.format != RenderFormat::Json
+		{
+			let (rendered, report, format_time) = render_within_budget(self, crate_data, budget)?;
+			writer.write_all(rendered.as_bytes())?;
+			writer.flush()?;
+			return Ok((report, format_time));
+		}
+		self.render_elided_to(crate_data, Elision::default(), writer)
+	}
+
+	/// Render a crate into a string, leaving out what `elision` describes.
+	pub(crate) fn render_elided(
+		&self,
+		crate_data: &Crate,
+		elision: Elision,
+	) -> Result<(String, RenderReport, Duration)> {
+		let mut output = Vec::new();
+		let (report, format_time) = self.render_elided_to(crate_data, elision, &mut output)?;
+		let output = String::from_utf8(output)
+			.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+		Ok((output, report, format_time))
+	}
+
+	/// Stream a rendering of a crate to `writer`, leaving out what `elision` describes, and
+	/// return what was skipped and the time spent formatting.
+	fn render_elided_to<W: io::Write>(
+		&self,
+		crate_data: &Crate,
+		elision: Elision,
+		mut writer: W,
+	) -> Result<(RenderReport, Duration)> {
 		use super::state::RenderState;
 
+		let _type_depth = TypeDepthLimit::set(self.max_type_depth);
+		if self.render_header {
+			writer.write_all(self.header(crate_data).as_bytes())?;
+		}
+
 		let mut state = RenderState::new(self, crate_data);
-		let raw_output = state.render()?;
-		match self.format {
-			RenderFormat::Rust => self.render_rust(&raw_output),
-			RenderFormat::Markdown => self.render_markdown(raw_output),
+		state.elision = elision;
+		if self.format == RenderFormat::Json {
+			let skeleton = match state.render_skeleton() {
+				Err(RipdocError::FilterNotMatched(_)) => {
+					state = state.fold_filter()?;
+					state.render_skeleton()?
+				}
+				result => result?,
+			};
+			serde_json::to_writer_pretty(&mut writer, &skeleton)?;
+			writer.write_all(b"\n")?;
+			writer.flush()?;
+			return Ok((state.report.into_inner(), Duration::ZERO));
+		}
+
+		let mut sink = ChunkSink::new(self, &mut writer);
+		match state.render_chunks(|chunk| sink.push(chunk)) {
+			// A miss filters out every item, so nothing has reached the writer yet.
+			Err(RipdocError::FilterNotMatched(_)) => {
+				let mut state = state.fold_filter()?;
+				let mut sink = ChunkSink::new(self, &mut writer);
+				state.render_chunks(|chunk| sink.push(chunk))?;
+				let format_time = sink.finish()?;
+				Ok((state.report.into_inner(), format_time))
+			}
+			result => {
+				result?;
+				let format_time = sink.finish()?;
+				Ok((state.report.into_inner(), format_time))
+			}
 		}
 	}
+}
+
+/// Buffers raw chunks, formats them in module-sized batches, and writes the result.
+///
+/// Every batch after the first is formatted inside a copy of the root module's opening line so
+/// rustfmt indents it exactly as it would when formatting the whole module at once.
+struct ChunkSink<'a, W: io::Write> {
+	/// Renderer supplying the formatter and output format.
+	renderer: &'a Renderer,
+	/// Destination for formatted output.
+	writer: W,
+	/// Incremental Markdown converter, present when rendering Markdown.
+	markdown: Option<MarkdownStream>,
+	/// First line of the root module, used to re-open the module for later batches.
+	header: String,
+	/// Raw output not yet formatted.
+	pending: String,
+	/// Whether any batch has already been written.
+	flushed: bool,
+	/// Whether any chunk has been received.
+	started: bool,
+	/// Time spent in the formatter so far.
+	format_time: Duration,
+}
 
-	fn render_rust(&self, raw_output: &str) -> Result<String> {
-		Ok(self.formatter.format_str(raw_output)?)
+impl<'a, W: io::Write> ChunkSink<'a, W> {
+	fn new(renderer: &'a Renderer, writer: W) -> Self {
+		let markdown = match renderer.format {
+			RenderFormat::Markdown => Some(MarkdownStream::new()),
+			RenderFormat::Rust | RenderFormat::Json => None,
+		};
+		Self {
+			renderer,
+			writer,
+			markdown,
+			header: String::new(),
+			pending: String::new(),
+			flushed: false,
+			started: false,
+			format_time: Duration::ZERO,
+		}
 	}
 
-	fn render_markdown(&self, raw_output: String) -> Result<String> {
-		let formatted = self.render_rust(&raw_output)?;
-		Ok(markdown::render_markdown(&formatted))
+	fn push(&mut self, chunk: RenderChunk<'_>) -> Result<()> {
+		self.started = true;
+		match chunk {
+			RenderChunk::Open(text) => {
+				self.header = text
+					.split_inclusive('\n')
+					.next()
+					.unwrap_or_default()
+					.to_string();
+				self.pending.push_str(text);
+			}
+			RenderChunk::Item(text) => {
+				self.pending.push_str(text);
+				// Only split where the module body has a blank line, which rustfmt preserves
+				// verbatim between items.
+				if self.pending.len() >= self.renderer.stream_chunk_bytes
+					&& self.pending.ends_with("\n\n")
+				{
+					self.flush_batch()?;
+				}
+			}
+			RenderChunk::Close(text) => self.pending.push_str(text),
+		}
+		Ok(())
 	}
+
+	/// Format the pending items inside a temporarily closed module and write its body.
+	fn flush_batch(&mut self) -> Result<()> {
+		let pending = std::mem::take(&mut self.pending);
+		let formatted = if self.flushed {
+			let source = format!("{}{pending}}}\n", self.header);
+			let formatted = self.format(source)?;
+			format!("\n{}", strip_last_line(strip_first_line(&formatted)))
+		} else {
+			let formatted = self.format(pending + "}\n")?;
+			strip_last_line(&formatted).to_string()
+		};
+		self.flushed = true;
+		self.write(&formatted)
+	}
+
+	/// Flush what is left and return the time spent formatting.
+	fn finish(mut self) -> Result<Duration> {
+		if !self.started {
+			return Ok(self.format_time);
+		}
+		let pending = std::mem::take(&mut self.pending);
+		let formatted = if !self.flushed {
+			self.format(pending)?
+		} else if pending.trim() == "}" {
+			"}\n".to_string()
+		} else {
+			let source = format!("{}{pending}", self.header);
+			let formatted = self.format(source)?;
+			format!("\n{}", strip_first_line(&formatted))
+		};
+		self.write(&formatted)?;
+		if let Some(markdown) = self.markdown.take() {
+			self.writer.write_all(markdown.finish().as_bytes())?;
+		}
+		self.writer.flush()?;
+		Ok(self.format_time)
+	}
+
+	/// Format `source` with the renderer's formatter, timing it.
+	fn format(&mut self, source: String) -> Result<String> {
+		let started = Instant::now();
+		let formatted = self.renderer.format_source(source);
+		self.format_time += started.elapsed();
+		formatted
+	}
+
+	fn write(&mut self, formatted: &str) -> Result<()> {
+		match &mut self.markdown {
+			Some(markdown) => self.writer.write_all(markdown.push(formatted).as_bytes())?,
+			None => self.writer.write_all(formatted.as_bytes())?,
+		}
+		Ok(())
+	}
+}
+
+/// Drop the first line of formatted output, including its newline.
+fn strip_first_line(text: &str) -> &str {
+	text.split_once('\n').map_or("", |(_, rest)| rest)
+}
+
+/// Drop the final line of formatted output, keeping the newline that precedes it.
+fn strip_last_line(text: &str) -> &str {
+	let trimmed = text.strip_suffix('\n').unwrap_or(text);
+	trimmed.rfind('\n').map_or("", |idx| &text[..=idx])
 }
@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, io};
 
 use rust_format::Error as FormatError;
 
@@ -7,8 +7,21 @@ use rust_format::Error as FormatError;
 pub enum RipdocError {
 	/// The requested filter path was not found in the crate.
 	FilterNotMatched(String),
+	/// The filter path matched several items once case and `-`/`_` were ignored.
+	FilterAmbiguous {
+		/// Filter path as given.
+		filter: String,
+		/// Paths of the items it matched.
+		candidates: Vec<String>,
+	},
 	/// Formatting failure while pretty-printing the rendered output.
 	Formatter(FormatError),
+	/// The rendered output could not be parsed for in-process pretty-printing.
+	Syntax(syn::Error),
+	/// Failure writing rendered output to its destination.
+	Io(io::Error),
+	/// Failure serializing the structured skeleton to JSON.
+	Serialization(serde_json::Error),
 }
 
 impl fmt::Display for RipdocError {
@@ -17,7 +30,19 @@ impl fmt::Display for RipdocError {
 			Self::FilterNotMatched(filter) => {
 				write!(f, "filter path '{filter}' did not match any items")
 			}
+			Self::FilterAmbiguous { filter, candidates } => {
+				let candidates: Vec<String> =
+					candidates.iter().map(|path| format!("'{path}'")).collect();
+				write!(
+					f,
+					"filter path '{filter}' is ambiguous; candidates: {}",
+					candidates.join(", ")
+				)
+			}
 			Self::Formatter(err) => write!(f, "{err}"),
+			Self::Syntax(err) => write!(f, "rendered output is not valid Rust: {err}"),
+			Self::Io(err) => write!(f, "failed to write rendered output: {err}"),
+			Self::Serialization(err) => write!(f, "failed to serialize skeleton JSON: {err}"),
 		}
 	}
 }
@@ -30,5 +55,17 @@ impl From<FormatError> for RipdocError {
 	}
 }
 
+impl From<io::Error> for RipdocError {
+	fn from(err: io::Error) -> Self {
+		Self::Io(err)
+	}
+}
+
+impl From<serde_json::Error> for RipdocError {
+	fn from(err: serde_json::Error) -> Self {
+		Self::Serialization(err)
+	}
+}
+
 /// Result type returned by renderer helpers.
 pub type Result<T> = std::result::Result<T, RipdocError>;
@@ -0,0 +1,259 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rust_format::{Formatter, RustFmt};
+use syn::visit::{self, Visit};
+
+use crate::error::{Result, RipdocError};
+
+/// Width of one indentation level in unformatted output.
+const INDENT: &str = "    ";
+
+/// Whether the rustfmt fallback warning has been logged, so large renders warn only once.
+static RUSTFMT_FALLBACK_WARNED: AtomicBool = AtomicBool::new(false);
+
+/// How rendered Rust source is formatted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FormatterChoice {
+	/// Run the external `rustfmt`, falling back with a warning when it cannot be run: to
+	/// [`FormatterChoice::Prettyplease`] for source without plain comments, which it would
+	/// drop, and to [`FormatterChoice::None`] otherwise.
+	#[default]
+	RustFmt,
+	/// Pretty-print in process with `prettyplease` after parsing with `syn`.
+	///
+	/// `syn` keeps doc comments but drops every plain `//` and `/* */` comment, so implementor
+	/// lists, breadcrumbs, origin comments, `// via #[async_trait]` notes, primitive
+	/// pseudo-items and `/* value omitted */` markers are all lost. Source `prettyplease`
+	/// cannot print, such as `pub` on trait methods, is only re-indented as with
+	/// [`FormatterChoice::None`].
+	Prettyplease,
+	/// Leave the source as rendered, only re-indenting lines by brace depth.
+	None,
+}
+
+/// Format `source` as `choice` describes, using `rustfmt` for [`FormatterChoice::RustFmt`].
+pub(crate) fn format_source(
+	choice: FormatterChoice,
+	rustfmt: &RustFmt,
+	source: String,
+) -> Result<String> {
+	match choice {
+		FormatterChoice::RustFmt => match rustfmt.format_str(&source) {
+			Ok(formatted) => Ok(formatted),
+			Err(err) => {
+				let formatted = if has_plain_comments(&source) {
+					indent_source(&source)
+				} else {
+					let Ok(formatted) = prettyplease_source(&source) else {
+						return Err(err.into());
+					};
+					formatted
+				};
+				if !RUSTFMT_FALLBACK_WARNED.swap(true, Ordering::Relaxed) {
+					log::warn!("rustfmt failed ({err}); formatting in process instead");
+				}
+				Ok(formatted)
+			}
+		},
+		FormatterChoice::Prettyplease => prettyplease_source(&source),
+		FormatterChoice::None => Ok(indent_source(&source)),
+	}
+}
+
+/// Parse `source` with `syn` and print it with `prettyplease`.
+///
+/// `prettyplease` panics on anything `syn` only keeps as verbatim tokens, such as `pub` on a
+/// trait method, so source containing any is re-indented instead.
+fn prettyplease_source(source: &str) -> Result<String> {
+	let file = syn::parse_file(source).map_err(RipdocError::Syntax)?;
+	let mut verbatim = VerbatimFinder::default();
+	verbatim.visit_file(&file);
+	if verbatim.found {
+		return Ok(indent_source(source));
+	}
+	Ok(prettyplease::unparse(&file))
+}
+
+/// Looks for syntax `syn` parsed as verbatim tokens, which `prettyplease` cannot print.
+#[derive(Default)]
+struct VerbatimFinder {
+	/// Whether any verbatim syntax was visited.
+	found: bool,
+}
+
+impl<'ast> Visit<'ast> for VerbatimFinder {
+	fn visit_item(&mut self, item: &'ast syn::Item) {
+		self.found |= matches!(item, syn::Item::Verbatim(_));
+		visit::visit_item(self, item);
+	}
+
+	fn visit_trait_item(&mut self, item: &'ast syn::TraitItem) {
+		self.found |= matches!(item, syn::TraitItem::Verbatim(_));
+		visit::visit_trait_item(self, item);
+	}
+
+	fn visit_impl_item(&mut self, item: &'ast syn::ImplItem) {
+		self.found |= matches!(item, syn::ImplItem::Verbatim(_));
+		visit::visit_impl_item(self, item);
+	}
+
+	fn visit_foreign_item(&mut self, item: &'ast syn::ForeignItem) {
+		self.found |= matches!(item, syn::ForeignItem::Verbatim(_));
+		visit::visit_foreign_item(self, item);
+	}
+
+	fn visit_expr(&mut self, expr: &'ast syn::Expr) {
+		self.found |= matches!(expr, syn::Expr::Verbatim(_));
+		visit::visit_expr(self, expr);
+	}
+
+	fn visit_type(&mut self, ty: &'ast syn::Type) {
+		self.found |= matches!(ty, syn::Type::Verbatim(_));
+		visit::visit_type(self, ty);
+	}
+
+	fn visit_pat(&mut self, pat: &'ast syn::Pat) {
+		self.found |= matches!(pat, syn::Pat::Verbatim(_));
+		visit::visit_pat(self, pat);
+	}
+
+	fn visit_type_param_bound(&mut self, bound: &'ast syn::TypeParamBound) {
+		self.found |= matches!(bound, syn::TypeParamBound::Verbatim(_));
+		visit::visit_type_param_bound(self, bound);
+	}
+}
+
+/// Whether `source` has a `//` or `/* */` comment other than a doc comment, which `syn`
+/// would drop.
+fn has_plain_comments(source: &str) -> bool {
+	source.lines().any(|line| {
+		let mut quote = None;
+		let mut escaped = false;
+		let mut chars = line.char_indices().peekable();
+		while let Some((index, ch)) = chars.next() {
+			if let Some(open) = quote {
+				if escaped {
+					escaped = false;
+				} else if ch == '\\' {
+					escaped = true;
+				} else if ch == open {
+					quote = None;
+				}
+				continue;
+			}
+			let next = chars.peek().map(|&(_, next)| next);
+			match ch {
+				'/' if next == Some('*') => return true,
+				'/' if next == Some('/') => {
+					// `///` and `//!` are doc comments, but `////` is a plain one.
+					let comment = &line[index..];
+					return !(comment.starts_with("//!")
+						|| comment.starts_with("///") && !comment.starts_with("////"));
+				}
+				'"' => quote = Some('"'),
+				'\'' if chars.clone().nth(1).map(|(_, ch)| ch) == Some('\'')
+					|| next == Some('\\') =>
+				{
+					quote = Some('\'');
+				}
+				_ => {}
+			}
+		}
+		false
+	})
+}
+
+/// Re-indent `source` line by line according to the braces, brackets and parentheses left open
+/// before each line.
+///
+/// Delimiters inside string and character literals and after `//` are ignored, which is enough
+/// for the renderer's output; nothing else about the layout changes.
+fn indent_source(source: &str) -> String {
+	let mut output = String::with_capacity(source.len());
+	let mut depth = 0usize;
+	for line in source.lines() {
+		let line = line.trim();
+		if line.is_empty() {
+			output.push('\n');
+			continue;
+		}
+		let (opened, closed_first) = delimiter_balance(line);
+		let indent = depth.saturating_sub(closed_first);
+		for _ in 0..indent {
+			output.push_str(INDENT);
+		}
+		output.push_str(line);
+		output.push('\n');
+		depth = depth.saturating_add_signed(opened);
+	}
+	output
+}
+
+/// Net change in open delimiters across `line`, and how many closing delimiters it starts
+/// with, which belong on the outer indentation level.
+fn delimiter_balance(line: &str) -> (isize, usize) {
+	let closed_first = line
+		.chars()
+		.take_while(|ch| matches!(ch, '}' | ']' | ')'))
+		.count();
+	let mut balance = 0isize;
+	let mut quote = None;
+	let mut escaped = false;
+	let mut chars = line.chars().peekable();
+	while let Some(ch) = chars.next() {
+		if let Some(open) = quote {
+			if escaped {
+				escaped = false;
+			} else if ch == '\\' {
+				escaped = true;
+			} else if ch == open {
+				quote = None;
+			}
+			continue;
+		}
+		match ch {
+			'/' if chars.peek() == Some(&'/') => break,
+			'"' => quote = Some('"'),
+			// A quote followed by an identifier and no closing quote is a lifetime.
+			'\'' if chars.clone().nth(1) == Some('\'') || chars.peek() == Some(&'\\') => {
+				quote = Some('\'');
+			}
+			'{' | '[' | '(' => balance += 1,
+			'}' | ']' | ')' => balance -= 1,
+			_ => {}
+		}
+	}
+	(balance, closed_first)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn only_plain_comments_count() {
+		assert!(has_plain_comments("pub fn f() {} // from futures_core\n"));
+		assert!(has_plain_comments(
+			"pub const X: u32 = unimplemented!() /* value omitted */;"
+		));
+		assert!(has_plain_comments("//// not a doc comment\npub struct S;"));
+		assert!(!has_plain_comments(
+			"/// Docs\n//! Module docs\npub struct S;"
+		));
+		assert!(!has_plain_comments(
+			"pub const URL: &str = \"http://example.com\";"
+		));
+		assert!(!has_plain_comments(
+			"pub fn f<'a>(x: &'a str) -> char { '/' }"
+		));
+	}
+
+	#[test]
+	fn verbatim_syntax_is_indented_instead_of_pretty_printed() {
+		let source = "pub trait Paint {\npub fn paint(&self);\n}\n";
+		assert_eq!(
+			prettyplease_source(source).unwrap(),
+			"pub trait Paint {\n    pub fn paint(&self);\n}\n"
+		);
+	}
+}
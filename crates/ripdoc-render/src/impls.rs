@@ -1,10 +1,17 @@
-use rustdoc_types::{Impl, Item, ItemEnum, Type, Visibility};
+use std::collections::{BTreeMap, BTreeSet};
 
+use rustdoc_types::{
+	Crate, GenericBound, GenericParamDefKind, Generics, Id, Impl, Item, ItemEnum, Path, Trait,
+	Type, Visibility, WherePredicate,
+};
+
+use super::core::Renderer;
+use super::resugar::resugar_async_trait;
 use super::state::RenderState;
-use super::utils::ppush;
+use super::utils::{FilterMatch, must_get, ppush};
 use crate::syntax::*;
 
-/// Traits that we render via `#[derive(...)]` annotations instead of explicit impl blocks.
+/// Traits whose derived impls are collapsed into `#[derive(...)]` annotations by default.
 pub const DERIVE_TRAITS: &[&str] = &[
 	"Clone",
 	"Copy",
@@ -24,15 +31,50 @@ pub const DERIVE_TRAITS: &[&str] = &[
 	// These are not built-in but are "well known" enough to treat specially
 	"Serialize",
 	"Deserialize",
+	// derive_more
+	"AsMut",
+	"AsRef",
+	"Deref",
+	"DerefMut",
+	"From",
+	"Into",
+	// zeroize
+	"Zeroize",
+	"ZeroizeOnDrop",
 ];
 
+/// Marker traits implemented alongside a derive that cannot be named in `#[derive(...)]`.
+const DERIVE_COMPANION_TRAITS: &[&str] = &["StructuralPartialEq"];
+
+/// Return the trait name of an impl that collapses into `#[derive(...)]`.
+///
+/// Only impls marked `#[automatically_derived]` collapse, so hand-written impls of derivable
+/// traits are still rendered as explicit blocks.
+pub fn derived_trait_name<'a>(item: &'a Item, derive_traits: &[String]) -> Option<&'a str> {
+	let impl_ = try_extract_item!(item, ItemEnum::Impl)?;
+	let name = impl_.trait_.as_ref()?.path.rsplit("::").next()?;
+	(is_automatically_derived(item) && derive_traits.iter().any(|t| t == name)).then_some(name)
+}
+
+/// Return the trait name to list in a type's `#[derive(...)]` annotation for an impl, if any.
+pub fn derive_attr_name<'a>(item: &'a Item, derive_traits: &[String]) -> Option<&'a str> {
+	let impl_ = try_extract_item!(item, ItemEnum::Impl)?;
+	if impl_.is_synthetic {
+		return None;
+	}
+	derived_trait_name(item, derive_traits).filter(|name| !DERIVE_COMPANION_TRAITS.contains(name))
+}
+
 /// Determine whether an impl block should be rendered in the output.
-pub fn should_render_impl(impl_: &Impl, render_auto_impls: bool) -> bool {
-	if impl_.is_synthetic && !render_auto_impls {
+pub fn should_render_impl(item: &Item, config: &Renderer) -> bool {
+	let Some(impl_) = try_extract_item!(item, ItemEnum::Impl) else {
+		return false;
+	};
+	if impl_.is_synthetic && !config.render_auto_impls {
 		return false;
 	}
 
-	if DERIVE_TRAITS.contains(&impl_.trait_.as_ref().map_or("", |t| t.path.as_str())) {
+	if derived_trait_name(item, &config.derive_traits).is_some() {
 		return false;
 	}
 
@@ -43,15 +85,150 @@ pub fn should_render_impl(impl_: &Impl, render_auto_impls: bool) -> bool {
 	true
 }
 
+/// Check whether an impl is a blanket or auto impl, which is only rendered as a stub.
+pub fn is_stub_impl(impl_: &Impl) -> bool {
+	impl_.blanket_impl.is_some() || impl_.is_synthetic
+}
+
+/// Describe where a stub impl comes from.
+pub fn impl_stub_kind(impl_: &Impl) -> &'static str {
+	if impl_.blanket_impl.is_some() {
+		"blanket impl"
+	} else {
+		"auto impl"
+	}
+}
+
+/// Render the header of a blanket or auto impl, split into the `impl<...>` prefix and the
+/// `Trait for Type` name. The blanket parameter is substituted with the implementing type so
+/// `impl<T> From<T> for T` reads as `impl From<Widget> for Widget`.
+pub fn impl_stub_header(impl_: &Impl) -> (String, String) {
+	let target = render_type(&impl_.for_);
+	let blanket_param = match &impl_.blanket_impl {
+		Some(Type::Generic(name)) => Some(name.as_str()),
+		_ => None,
+	};
+	let generics = Generics {
+		params: impl_
+			.generics
+			.params
+			.iter()
+			.filter(|param| Some(param.name.as_str()) != blanket_param)
+			.cloned()
+			.collect(),
+		where_predicates: Vec::new(),
+	};
+	let prefix = format!(
+		"{}impl{}",
+		if impl_.is_unsafe { "unsafe " } else { "" },
+		render_generics(&generics),
+	);
+	let name = match &impl_.trait_ {
+		Some(trait_) => {
+			let trait_path = render_path(trait_);
+			let trait_path = match blanket_param {
+				Some(param) => replace_ident(&trait_path, param, &target),
+				None => trait_path,
+			};
+			let negation = if impl_.is_negative { "!" } else { "" };
+			format!("{negation}{trait_path} for {target}")
+		}
+		None => target,
+	};
+	(prefix, name)
+}
+
+/// Render a blanket or auto impl as an empty block annotated with its origin.
+pub fn render_impl_stub(impl_: &Impl) -> String {
+	let (prefix, name) = impl_stub_header(impl_);
+	format!("{prefix} {name} {{}} // {}\n\n", impl_stub_kind(impl_))
+}
+
+/// Summarize a type's auto trait impls as a `// auto traits: Send, Sync, !Unpin` comment line,
+/// or nothing when rustdoc synthesized none.
+///
+/// Implemented traits come first, then negative impls prefixed with `!`, each group sorted by
+/// name. Impls that only hold under bounds on the type's parameters are listed like any other.
+pub fn auto_trait_summary(crate_data: &Crate, impls: &[Id]) -> String {
+	let mut implemented = BTreeSet::new();
+	let mut negative = BTreeSet::new();
+	for impl_id in impls {
+		let Some(impl_) = try_extract_item!(must_get(crate_data, impl_id), ItemEnum::Impl) else {
+			continue;
+		};
+		if !impl_.is_synthetic {
+			continue;
+		}
+		let Some(name) = impl_
+			.trait_
+			.as_ref()
+			.and_then(|t| t.path.rsplit("::").next())
+		else {
+			continue;
+		};
+		if impl_.is_negative {
+			negative.insert(format!("!{name}"));
+		} else {
+			implemented.insert(name.to_string());
+		}
+	}
+	if implemented.is_empty() && negative.is_empty() {
+		return String::new();
+	}
+	let traits: Vec<String> = implemented.into_iter().chain(negative).collect();
+	format!("// auto traits: {}\n", traits.join(", "))
+}
+
+/// Replace whole-identifier occurrences of `ident` in `text`.
+fn replace_ident(text: &str, ident: &str, replacement: &str) -> String {
+	let is_ident_char = |c: char| c.is_alphanumeric() || c == '_';
+	let mut output = String::with_capacity(text.len());
+	let mut rest = text;
+	while let Some(pos) = rest.find(ident) {
+		let before = rest[..pos].chars().next_back();
+		let after = rest[pos + ident.len()..].chars().next();
+		output.push_str(&rest[..pos]);
+		if before.is_some_and(is_ident_char) || after.is_some_and(is_ident_char) {
+			output.push_str(ident);
+		} else {
+			output.push_str(replacement);
+		}
+		rest = &rest[pos + ident.len()..];
+	}
+	output.push_str(rest);
+	output
+}
+
+/// Order a type's impl blocks for rendering: inherent impls first in their original order, then
+/// trait impls sorted by rendered trait path.
+pub fn ordered_impls<'a>(crate_data: &Crate, impls: &'a [Id]) -> Vec<&'a Id> {
+	let mut ordered: Vec<&Id> = impls.iter().collect();
+	ordered.sort_by_cached_key(|impl_id| {
+		try_extract_item!(must_get(crate_data, impl_id), ItemEnum::Impl)
+			.and_then(|impl_| impl_.trait_.as_ref())
+			.map(render_path)
+	});
+	ordered
+}
+
 /// Render an implementation block, respecting filtering rules.
 pub fn render_impl(state: &mut RenderState, path_prefix: &str, item: &Item) -> String {
-	let mut output = docs(item);
-	let impl_ = extract_item!(item, ItemEnum::Impl);
-
+	let Some(impl_) = try_extract_item!(item, ItemEnum::Impl) else {
+		state.record_mismatch(item, "impl");
+		return String::new();
+	};
 	if !state.selection_context_contains(&item.id) {
 		return String::new();
 	}
 
+	// Blocks for different instantiations of a type read alike once their items are filtered,
+	// so each is labelled with the type it is for.
+	let mut output = String::new();
+	if impl_.trait_.is_none() && has_distinct_inherent_impls(state.crate_data, impl_) {
+		output.push_str(&format!("// {}\n", inherent_impl_label(impl_)));
+	}
+	output.push_str(&state.docs(item));
+
 	let selection_active = state.selection().is_some();
 	let parent_expanded = match &impl_.for_ {
 		Type::ResolvedPath(path) => state.selection_expands(&path.id),
@@ -66,12 +243,46 @@ pub fn render_impl(state: &mut RenderState, path_prefix: &str, item: &Item) -> S
 		return String::new();
 	}
 
-	let where_clause = render_where_clause(&impl_.generics);
+	let path_prefix = ppush(path_prefix, &impl_path_segment(&impl_.for_));
+	let trait_name = impl_.trait_.as_ref().map(impl_trait_segment);
+	let mut body = String::new();
+	{
+		// Only the items name the implementing type `Self`; the header spells it out.
+		let _self_type = SelfTypeScope::enter(&impl_.for_);
+		for item_id in &impl_.items {
+			if let Some(member) = state.crate_data.index.get(item_id) {
+				if trait_name.is_none()
+					&& yields_to_generic_impl(state, &path_prefix, &item.id, impl_, member)
+				{
+					continue;
+				}
+				if !selection_active || expand_children || state.selection_context_contains(item_id)
+				{
+					body.push_str(&render_impl_item(
+						state,
+						&path_prefix,
+						trait_name.as_deref(),
+						member,
+						expand_children,
+					));
+				}
+			}
+		}
+	}
+
+	// Auto impls have no items; the block itself is what they add
+	if body.is_empty() && !impl_.is_synthetic {
+		return String::new();
+	}
+
+	let (generics, bounds_omitted) = strip_private_bounds(state, &impl_.generics);
+	let where_clause = render_where_clause(&generics);
 
 	let trait_part = if let Some(trait_) = &impl_.trait_ {
 		let trait_path = render_path(trait_);
 		if !trait_path.is_empty() {
-			format!("{trait_path} for ")
+			let negation = if impl_.is_negative { "!" } else { "" };
+			format!("{negation}{trait_path} for ")
 		} else {
 			String::new()
 		}
@@ -82,7 +293,7 @@ pub fn render_impl(state: &mut RenderState, path_prefix: &str, item: &Item) -> S
 	output.push_str(&format!(
 		"{}impl{} {}{}",
 		if impl_.is_unsafe { "unsafe " } else { "" },
-		render_generics(&impl_.generics),
+		render_generics(&generics),
 		trait_part,
 		render_type(&impl_.for_)
 	));
@@ -90,39 +301,144 @@ pub fn render_impl(state: &mut RenderState, path_prefix: &str, item: &Item) -> S
 	if !where_clause.is_empty() {
 		output.push_str(&format!("\n{where_clause}"));
 	}
+	if bounds_omitted {
+		output.push_str(PRIVATE_BOUND_OMITTED);
+	}
 
 	output.push_str(" {\n");
+	output.push_str(&body);
+	output.push_str("}\n\n");
 
-	let path_prefix = ppush(path_prefix, &render_type(&impl_.for_));
-	let mut has_content = false;
-	for item_id in &impl_.items {
-		if let Some(item) = state.crate_data.index.get(item_id) {
-			let is_trait_impl = impl_.trait_.is_some();
-			if (!selection_active || expand_children || state.selection_context_contains(item_id))
-				&& (is_trait_impl || is_visible(state, item))
-			{
-				let rendered = render_impl_item(state, &path_prefix, item, expand_children);
-				if !rendered.is_empty() {
-					output.push_str(&rendered);
-					has_content = true;
-				}
-			}
-		}
+	output
+}
+
+/// Label for an inherent impl block naming the instantiation it is for, such as
+/// `impl<T> Widget<T>` or `impl Widget<u32>`.
+pub fn inherent_impl_label(impl_: &Impl) -> String {
+	format!(
+		"impl{} {}",
+		render_generics(&impl_.generics),
+		render_type(&impl_.for_)
+	)
+}
+
+/// Whether the type `impl_` is for has rendered inherent impl blocks for more than one
+/// instantiation, such as both `impl Widget<u32>` and `impl<T> Widget<T>`.
+fn has_distinct_inherent_impls(crate_data: &Crate, impl_: &Impl) -> bool {
+	let for_ = render_type(&impl_.for_);
+	inherent_impls(crate_data, &impl_.for_)
+		.iter()
+		.any(|(_, sibling)| render_type(&sibling.for_) != for_)
+}
+
+/// Inherent impl blocks rendered for the type `for_`, with their ids, in declaration order.
+fn inherent_impls<'c>(crate_data: &'c Crate, for_: &Type) -> Vec<(&'c Id, &'c Impl)> {
+	let Type::ResolvedPath(path) = for_ else {
+		return Vec::new();
+	};
+	let impls = match crate_data.index.get(&path.id).map(|item| &item.inner) {
+		Some(ItemEnum::Struct(struct_)) => &struct_.impls,
+		Some(ItemEnum::Enum(enum_)) => &enum_.impls,
+		Some(ItemEnum::Union(union_)) => &union_.impls,
+		_ => return Vec::new(),
+	};
+	impls
+		.iter()
+		.filter_map(|id| {
+			let impl_ = try_extract_item!(crate_data.index.get(id)?, ItemEnum::Impl)?;
+			(impl_.trait_.is_none() && !is_stub_impl(impl_)).then_some((id, impl_))
+		})
+		.collect()
+}
+
+/// Whether an impl block declares type or const parameters, as `impl<T> Widget<T>` does.
+fn is_generic_impl(impl_: &Impl) -> bool {
+	impl_
+		.generics
+		.params
+		.iter()
+		.any(|param| !matches!(param.kind, GenericParamDefKind::Lifetime { .. }))
+}
+
+/// Whether `member` of the inherent impl `impl_id` is left out because the filter names it and
+/// a generic impl of the same type has a visible item of the same name, which is shown instead.
+///
+/// `path_prefix` ends with the implementing type. The generic impl logs a note listing every
+/// block holding the item.
+pub fn yields_to_generic_impl(
+	state: &RenderState,
+	path_prefix: &str,
+	impl_id: &Id,
+	impl_: &Impl,
+	member: &Item,
+) -> bool {
+	let Some(name) = &member.name else {
+		return false;
+	};
+	if state.filter_match(path_prefix, member) != FilterMatch::Hit {
+		return false;
 	}
 
-	if !has_content {
-		return String::new();
+	let holders: Vec<(&Id, &Impl)> =
+		inherent_impls(state.crate_data, &impl_.for_)
+			.into_iter()
+			.filter(|(_, sibling)| {
+				sibling.items.iter().any(|id| {
+					state.crate_data.index.get(id).is_some_and(|item| {
+						item.name.as_ref() == Some(name) && is_visible(state, item)
+					})
+				})
+			})
+			.collect();
+	let Some((preferred, _)) = holders.iter().find(|(_, sibling)| is_generic_impl(sibling)) else {
+		return false;
+	};
+	if holders.len() < 2 {
+		return false;
+	}
+	if *preferred != impl_id {
+		return true;
 	}
 
-	output.push_str("}\n\n");
+	let blocks: Vec<String> = holders
+		.iter()
+		.map(|(_, sibling)| format!("`{}`", inherent_impl_label(sibling)))
+		.collect();
+	log::warn!(
+		"`{name}` is defined in {}; showing the one in `{}`",
+		blocks.join(", "),
+		inherent_impl_label(impl_)
+	);
+	false
+}
 
-	output
+/// Path segment for the items of an impl on `for_`: the type's name without its module path or
+/// generic arguments, so `Deserializer<'de>` becomes `Deserializer`.
+pub fn impl_path_segment(for_: &Type) -> String {
+	match for_ {
+		Type::ResolvedPath(path) => impl_trait_segment(path),
+		_ => render_type(for_),
+	}
+}
+
+/// Last segment of `path`, without generic arguments.
+pub fn impl_trait_segment(path: &Path) -> String {
+	path.path
+		.rsplit("::")
+		.next()
+		.unwrap_or_default()
+		.to_string()
 }
 
 /// Render the item inside an impl block.
+///
+/// `path_prefix` ends with the implementing type; filters may also name `trait_name`, the
+/// implemented trait, between the type and the item. Items of inherent impls (`trait_name` is
+/// `None`) are skipped unless visible; items of trait impls share the trait's visibility.
 pub fn render_impl_item(
 	state: &mut RenderState,
 	path_prefix: &str,
+	trait_name: Option<&str>,
 	item: &Item,
 	include_all: bool,
 ) -> String {
@@ -130,13 +446,18 @@ pub fn render_impl_item(
 		return String::new();
 	}
 
-	if state.should_filter(path_prefix, item) {
+	if trait_name.is_none() && !is_visible(state, item) {
+		return String::new();
+	}
+
+	if state.should_filter_impl_item(path_prefix, trait_name, item) {
 		return String::new();
 	}
 
 	match &item.inner {
 		ItemEnum::Function(_) => render_function(state, item, false),
 		ItemEnum::Constant { .. } => render_constant(state, item),
+		ItemEnum::AssocConst { .. } => render_impl_const(state, item),
 		ItemEnum::AssocType { .. } => render_associated_type(item),
 		ItemEnum::TypeAlias(_) => render_type_alias(state, item),
 		_ => String::new(),
@@ -144,8 +465,9 @@ pub fn render_impl_item(
 }
 
 /// Render a trait definition.
-pub fn render_trait(state: &RenderState, item: &Item) -> String {
-	let mut output = docs(item);
+pub fn render_trait(state: &mut RenderState, item: &Item) -> String {
+	let mut output = state.docs(item);
+	output.push_str(&state.attrs(item));
 
 	let trait_ = extract_item!(item, ItemEnum::Trait);
 
@@ -153,13 +475,20 @@ pub fn render_trait(state: &RenderState, item: &Item) -> String {
 		return String::new();
 	}
 
+	let (trait_generics, generics_omitted) = strip_private_bounds(state, &trait_.generics);
+	let mut supertraits = trait_.bounds.clone();
+	let supertraits_omitted = strip_bounds(state, &mut supertraits);
+
 	let selection = super::items::SelectionView::new(state, &item.id, true);
 
-	let generics = render_generics(&trait_.generics);
-	let where_clause = render_where_clause(&trait_.generics);
+	let generics = render_generics(&trait_generics);
+	let mut where_clause = render_where_clause(&trait_generics);
+	if generics_omitted || supertraits_omitted {
+		where_clause.push_str(PRIVATE_BOUND_OMITTED);
+	}
 
-	let bounds = if !trait_.bounds.is_empty() {
-		format!(": {}", render_generic_bounds(&trait_.bounds))
+	let bounds = if !supertraits.is_empty() {
+		format!(": {}", render_generic_bounds(&supertraits))
 	} else {
 		String::new()
 	};
@@ -183,8 +512,182 @@ pub fn render_trait(state: &RenderState, item: &Item) -> String {
 		}
 	}
 
-	output.push_str("}\n\n");
+	output.push_str("}\n");
+	if state.config.render_trait_implementors && state.config.render_impls {
+		output.push_str(&render_trait_implementors(state, trait_));
+	}
+	output.push('\n');
+
+	output
+}
 
+/// List the crate's implementations of a trait as `// impl Trait for Type` comment lines.
+///
+/// Implementations for types that are not visible under the current settings are omitted.
+fn render_trait_implementors(state: &RenderState, trait_: &Trait) -> String {
+	let mut implementors: Vec<String> = trait_
+		.implementations
+		.iter()
+		.filter_map(|impl_id| state.crate_data.index.get(impl_id))
+		.filter_map(|item| {
+			let ItemEnum::Impl(impl_) = &item.inner else {
+				return None;
+			};
+			if let Type::ResolvedPath(path) = &impl_.for_
+				&& let Some(target) = state.crate_data.index.get(&path.id)
+				&& !is_visible(state, target)
+			{
+				return None;
+			}
+			let trait_path = render_path(impl_.trait_.as_ref()?);
+			Some(format!(
+				"{}impl{} {}{trait_path} for {}",
+				if impl_.is_unsafe { "unsafe " } else { "" },
+				render_generics(&impl_.generics),
+				if impl_.is_negative { "!" } else { "" },
+				render_type(&impl_.for_)
+			))
+		})
+		.collect();
+	if implementors.is_empty() {
+		return String::new();
+	}
+	implementors.sort();
+	implementors.dedup();
+
+	let mut output = String::from("// Implemented by:\n");
+	for implementor in implementors {
+		output.push_str(&format!("// {implementor}\n"));
+	}
+	output
+}
+
+/// Marker left where bounds on private traits were dropped.
+const PRIVATE_BOUND_OMITTED: &str = " /* private bound omitted */";
+
+/// Prepare `generics` for rendering when its bounds name traits the skeleton leaves out.
+///
+/// With sealed stubs enabled the traits are recorded for [`render_sealed_stubs`] and the bounds
+/// are kept; otherwise they are dropped. Returns the generics to render and whether any bound
+/// was dropped.
+fn strip_private_bounds(state: &mut RenderState, generics: &Generics) -> (Generics, bool) {
+	let mut generics = generics.clone();
+	let mut omitted = false;
+	for param in &mut generics.params {
+		if let GenericParamDefKind::Type { bounds, .. } = &mut param.kind {
+			omitted |= strip_bounds(state, bounds);
+		}
+	}
+	generics
+		.where_predicates
+		.retain_mut(|predicate| match predicate {
+			WherePredicate::BoundPredicate { bounds, .. } => {
+				omitted |= strip_bounds(state, bounds);
+				!bounds.is_empty()
+			}
+			_ => true,
+		});
+	(generics, omitted)
+}
+
+/// Record or drop the bounds in `bounds` that name private traits, as [`strip_private_bounds`]
+/// does, returning whether any were dropped.
+fn strip_bounds(state: &mut RenderState, bounds: &mut Vec<GenericBound>) -> bool {
+	let before = bounds.len();
+	bounds.retain(|bound| {
+		let GenericBound::TraitBound { trait_, .. } = bound else {
+			return true;
+		};
+		let Some(path) = private_trait_path(state, trait_) else {
+			return true;
+		};
+		if state.config.render_sealed_stubs {
+			state.sealed_stubs.insert(path);
+			true
+		} else {
+			false
+		}
+	});
+	bounds.len() != before
+}
+
+/// Path below the crate root of a trait from this crate that the skeleton does not render,
+/// either because it is private or because a module containing it is.
+fn private_trait_path(state: &RenderState, trait_: &Path) -> Option<Vec<String>> {
+	let summary = state
+		.crate_data
+		.paths
+		.get(&trait_.id)
+		.filter(|summary| summary.crate_id == 0);
+	let item = state.crate_data.index.get(&trait_.id);
+	if summary.is_none() && item.is_none_or(|item| item.crate_id != 0) {
+		return None;
+	}
+
+	let path: Vec<String> = match summary {
+		Some(summary) => summary.path.iter().skip(1).cloned().collect(),
+		None => trait_
+			.path
+			.trim_start_matches("crate::")
+			.split("::")
+			.map(str::to_string)
+			.collect(),
+	};
+	let rendered =
+		item.is_some_and(|item| is_visible(state, item)) && modules_visible(state, &path);
+	(!rendered).then_some(path)
+}
+
+/// Whether every module leading to the item at `path`, below the crate root, is rendered.
+fn modules_visible(state: &RenderState, path: &[String]) -> bool {
+	let index = &state.crate_data.index;
+	let mut module_id = &state.crate_data.root;
+	for name in &path[..path.len().saturating_sub(1)] {
+		let Some(ItemEnum::Module(module)) = index.get(module_id).map(|item| &item.inner) else {
+			return false;
+		};
+		let child = module
+			.items
+			.iter()
+			.filter_map(|id| index.get(id))
+			.find(|item| {
+				item.name.as_deref() == Some(name.as_str())
+					&& matches!(item.inner, ItemEnum::Module(_))
+			});
+		match child {
+			Some(child) if is_visible(state, child) => module_id = &child.id,
+			_ => return false,
+		}
+	}
+	true
+}
+
+/// Declare the private traits at `paths` as empty `pub trait`s inside stub modules, so bounds
+/// naming them still resolve.
+pub fn render_sealed_stubs(paths: &BTreeSet<Vec<String>>) -> String {
+	let paths: Vec<&[String]> = paths.iter().map(Vec::as_slice).collect();
+	let mut output = render_stub_level(&paths);
+	output.push('\n');
+	output
+}
+
+/// Render the traits and stub modules for one module level of [`render_sealed_stubs`].
+fn render_stub_level(paths: &[&[String]]) -> String {
+	let mut output = String::new();
+	let mut modules: BTreeMap<&str, Vec<&[String]>> = BTreeMap::new();
+	for path in paths {
+		match path {
+			[] => {}
+			[name] => output.push_str(&format!("pub trait {name} {{}}\n")),
+			[module, rest @ ..] => modules.entry(module.as_str()).or_default().push(rest),
+		}
+	}
+	for (module, children) in modules {
+		output.push_str(&format!(
+			"mod {module} {{\n{}}}\n",
+			render_stub_level(&children)
+		));
+	}
 	output
 }
 
@@ -202,7 +705,7 @@ fn render_trait_item(
 		ItemEnum::AssocConst { type_, value } => {
 			let default_str = value
 				.as_ref()
-				.map(|d| format!(" = {d}"))
+				.map(|d| format!(" = {}", render_assoc_const_value(d)))
 				.unwrap_or_default();
 			format!(
 				"const {}: {}{};\n",
@@ -244,9 +747,21 @@ fn is_visible(state: &RenderState, item: &Item) -> bool {
 }
 
 /// Render a function or method signature.
-fn render_function(_state: &RenderState, item: &Item, is_trait_method: bool) -> String {
-	let mut output = docs(item);
+fn render_function(state: &RenderState, item: &Item, is_trait_method: bool) -> String {
+	let mut output = state.docs(item);
+	output.push_str(&state.attrs(item));
 	let function = extract_item!(item, ItemEnum::Function);
+	let resugared = state
+		.config
+		.resugar_async
+		.then(|| resugar_async_trait(function))
+		.flatten();
+	if let Some(resugared) = &resugared {
+		output.push_str(resugared.comment());
+	}
+	let function = resugared
+		.as_ref()
+		.map_or(function, |resugared| &resugared.function);
 
 	// Handle const, async, and unsafe keywords in the correct order
 	let mut prefixes = Vec::new();
@@ -271,46 +786,64 @@ fn render_function(_state: &RenderState, item: &Item, is_trait_method: bool) ->
 		render_where_clause(&function.generics)
 	));
 
-	// Use semicolon for trait method declarations, empty body for implementations
+	// Use semicolon for trait method declarations, a body for implementations
 	if is_trait_method && !function.has_body {
 		output.push_str(";\n\n");
 	} else {
-		output.push_str(" {}\n\n");
+		output.push_str(state.config.body_style.body(&function.sig));
+		output.push_str("\n\n");
 	}
 
 	output
 }
 
 /// Render a constant definition.
-fn render_constant(_state: &RenderState, item: &Item) -> String {
-	let mut output = docs(item);
+fn render_constant(state: &RenderState, item: &Item) -> String {
+	let mut output = state.docs(item);
 
 	let (type_, const_) = extract_item!(item, ItemEnum::Constant { type_, const_ });
+	output.push_str(&render_const_declaration(
+		&render_vis(item),
+		&render_name(item),
+		&render_type(type_),
+		&const_.expr,
+	));
+
+	output
+}
+
+/// Render an associated constant of an impl, which always has a value.
+fn render_impl_const(state: &RenderState, item: &Item) -> String {
+	let mut output = state.docs(item);
+
+	let (type_, value) = extract_item!(item, ItemEnum::AssocConst { type_, value });
+	let value = value
+		.as_deref()
+		.map_or_else(|| OMITTED_CONST_VALUE.to_string(), render_assoc_const_value);
 	output.push_str(&format!(
 		"{}const {}: {} = {};\n\n",
 		render_vis(item),
 		render_name(item),
 		render_type(type_),
-		const_.expr
+		value
 	));
 
 	output
 }
 
 /// Render a type alias with generics, bounds, and visibility.
-fn render_type_alias(_state: &RenderState, item: &Item) -> String {
+fn render_type_alias(state: &RenderState, item: &Item) -> String {
 	let type_alias = extract_item!(item, ItemEnum::TypeAlias);
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 
 	output.push_str(&format!(
-		"{}type {}{}{}",
+		"{}type {}{}{} = {};\n\n",
 		render_vis(item),
 		render_name(item),
 		render_generics(&type_alias.generics),
 		render_where_clause(&type_alias.generics),
+		render_type(&type_alias.type_),
 	));
 
-	output.push_str(&format!("= {};\n\n", render_type(&type_alias.type_)));
-
 	output
 }
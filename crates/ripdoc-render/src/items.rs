@@ -1,7 +1,14 @@
-use rustdoc_types::{Id, Item, ItemEnum, StructKind, VariantKind, Visibility};
+use rustdoc_types::{Id, Item, ItemEnum, ItemKind, StructKind, VariantKind, Visibility};
 
-use super::impls::{DERIVE_TRAITS, render_impl, should_render_impl};
+use super::core::RenderFormat;
+use super::impls::{
+	auto_trait_summary, derive_attr_name, is_stub_impl, ordered_impls, render_impl,
+	render_impl_stub, should_render_impl,
+};
 use super::macros::{render_macro, render_proc_macro};
+use super::resugar::resugar_async_trait;
+use super::signatures::trait_alias_signature;
+use super::split::render_split_module;
 use super::state::RenderState;
 use super::utils::{escape_path, must_get, ppush};
 use crate::syntax::*;
@@ -33,15 +40,15 @@ impl SelectionView {
 		self.expands_self || state.selection_context_contains(child_id)
 	}
 
-	fn force_children(&self) -> bool {
+	pub(crate) fn force_children(&self) -> bool {
 		self.active && self.expands_self
 	}
 
-	fn is_active(&self) -> bool {
+	pub(crate) fn is_active(&self) -> bool {
 		self.active
 	}
 
-	fn expands_self(&self) -> bool {
+	pub(crate) fn expands_self(&self) -> bool {
 		self.expands_self
 	}
 }
@@ -131,19 +138,39 @@ fn collect_inline_traits<'a>(state: &'a RenderState, impls: &[Id]) -> Vec<&'a st
 	let mut inline_traits = Vec::new();
 	for impl_id in impls {
 		let impl_item = must_get(state.crate_data, impl_id);
-		let impl_ = extract_item!(impl_item, ItemEnum::Impl);
-		if impl_.is_synthetic {
-			continue;
+		if let Some(name) = derive_attr_name(impl_item, &state.config.derive_traits) {
+			inline_traits.push(name);
 		}
+	}
+	inline_traits
+}
 
-		if let Some(trait_) = &impl_.trait_
-			&& let Some(name) = trait_.path.split("::").last()
-			&& DERIVE_TRAITS.contains(&name)
+/// Render the impl blocks attached to a struct or enum, unless impls are disabled.
+fn render_type_impls(
+	state: &mut RenderState,
+	path_prefix: &str,
+	item: &Item,
+	impls: &[Id],
+) -> String {
+	let mut output = String::new();
+	if !state.config.render_impls {
+		return output;
+	}
+	for impl_id in ordered_impls(state.crate_data, impls) {
+		let impl_item = must_get(state.crate_data, impl_id);
+		let Some(impl_) = try_extract_item!(impl_item, ItemEnum::Impl) else {
+			state.record_mismatch(impl_item, "impl");
+			continue;
+		};
+		if is_stub_impl(impl_) && state.renders_blanket_impls_for(&item.id) {
+			output.push_str(&render_impl_stub(impl_));
+		} else if should_render_impl(impl_item, state.config)
+			&& state.selection_allows_child(&item.id, impl_id)
 		{
-			inline_traits.push(name);
+			output.push_str(&render_impl(state, path_prefix, impl_item));
 		}
 	}
-	inline_traits
+	output
 }
 
 /// Render an item into Rust source text.
@@ -161,55 +188,151 @@ pub fn render_item(
 		return String::new();
 	}
 
+	let visible = (force_private || is_visible(state, item)) && !state.is_hidden(item);
 	let output = match &item.inner {
 		ItemEnum::Module(_) => render_module(state, path_prefix, item),
 		ItemEnum::Struct(_) => render_struct(state, path_prefix, item),
 		ItemEnum::Enum(_) => render_enum(state, path_prefix, item),
 		ItemEnum::Trait(_) => super::impls::render_trait(state, item),
+		ItemEnum::TraitAlias(_) => render_trait_alias_item(state, item),
 		ItemEnum::Use(_) => render_use(state, path_prefix, item),
 		ItemEnum::Function(_) => render_function_item(state, item, false),
 		ItemEnum::Constant { .. } => render_constant_item(state, item),
 		ItemEnum::TypeAlias(_) => render_type_alias_item(state, item),
-		ItemEnum::Macro(_) => render_macro(item),
-		ItemEnum::ProcMacro(_) => render_proc_macro(item),
-		_ => String::new(),
+		ItemEnum::Macro(_) => render_macro(state, item),
+		ItemEnum::ProcMacro(_) => render_proc_macro(state, item),
+		ItemEnum::Primitive(_) => render_primitive(state, path_prefix, item),
+		_ => {
+			if visible {
+				state.report.get_mut().record(path_prefix, item);
+			}
+			String::new()
+		}
 	};
 
-	if !force_private && !is_visible(state, item) {
-		String::new()
-	} else {
-		output
-	}
+	if visible { output } else { String::new() }
 }
 
 /// Render a module and its children.
 pub fn render_module(state: &mut RenderState, path_prefix: &str, item: &Item) -> String {
+	if state.split.is_some() {
+		return render_split_module(state, path_prefix, item);
+	}
+	let (path_prefix, mut output) = render_module_open(state, path_prefix, item);
+	let _module = ModuleScope::enter(&path_prefix);
+
+	let module = extract_item!(item, ItemEnum::Module);
+	let elided = state
+		.elision
+		.elides_module(path_prefix.matches("::").count());
+
+	let mut breadcrumbs = Breadcrumbs::new(state, &path_prefix);
+	let mut omitted = 0;
+	for item_id in &module.items {
+		let item = must_get(state.crate_data, item_id);
+		let rendered = render_item(state, &path_prefix, item, false);
+		if !elided {
+			output.push_str(&breadcrumbs.item(rendered));
+		} else if !rendered.is_empty() {
+			omitted += 1;
+		}
+	}
+	if omitted > 0 {
+		output.push_str(&format!("/* {omitted} items omitted */\n"));
+	}
+
+	output.push_str("}\n\n");
+	output
+}
+
+/// Render a module's opening line and inner docs, returning the path prefix for its children.
+pub fn render_module_open(state: &RenderState, path_prefix: &str, item: &Item) -> (String, String) {
 	let path_prefix = ppush(path_prefix, &render_name(item));
 	let mut output = format!("{}mod {} {{\n", render_vis(item), render_name(item));
+	output.push_str(Breadcrumbs::new(state, &path_prefix).opening());
 	// Add module doc comment if present
 	if state.should_module_doc(&path_prefix, item)
-		&& let Some(docs) = &item.docs
+		&& let Some(docs) = state.doc_text(item)
 	{
 		for line in docs.lines() {
 			output.push_str(&format!("    //! {line}\n"));
 		}
 		output.push('\n');
 	}
+	(path_prefix, output)
+}
+
+/// Places `// path:` comments naming a module inside its body, as enabled by
+/// [`Renderer::with_breadcrumbs`](crate::Renderer::with_breadcrumbs).
+pub(crate) struct Breadcrumbs {
+	/// Comment naming the module, or `None` when breadcrumbs are off.
+	comment: Option<String>,
+	/// Unformatted lines of body after which each item gets a breadcrumb.
+	threshold: usize,
+	/// Unformatted lines of body rendered so far.
+	lines: usize,
+}
+
+impl Breadcrumbs {
+	/// Breadcrumbs for the module at `path`, off unless enabled for Rust output; Markdown
+	/// headings already say where each item lives.
+	pub(crate) fn new(state: &RenderState, path: &str) -> Self {
+		let config = state.config;
+		let enabled = config.render_breadcrumbs && config.format == RenderFormat::Rust;
+		Self {
+			comment: enabled.then(|| format!("// path: {path}\n")),
+			threshold: config.breadcrumb_lines,
+			lines: 0,
+		}
+	}
 
-	let module = extract_item!(item, ItemEnum::Module);
+	/// Breadcrumb following the module's opening line.
+	pub(crate) fn opening(&self) -> &str {
+		self.comment.as_deref().unwrap_or_default()
+	}
 
-	for item_id in &module.items {
-		let item = must_get(state.crate_data, item_id);
-		output.push_str(&render_item(state, &path_prefix, item, false));
+	/// Prefix `rendered`, the module's next item, with a breadcrumb when it starts more than
+	/// the threshold's lines into the body.
+	pub(crate) fn item(&mut self, rendered: String) -> String {
+		let Some(comment) = &self.comment else {
+			return rendered;
+		};
+		if rendered.is_empty() {
+			return rendered;
+		}
+		let past_threshold = self.lines > self.threshold;
+		self.lines += rendered.lines().count();
+		if past_threshold {
+			format!("{comment}{rendered}")
+		} else {
+			rendered
+		}
 	}
+}
 
-	output.push_str("}\n\n");
+/// Render a primitive documented by the crate as a comment naming it, followed by its impls.
+///
+/// Rust has no syntax declaring a primitive, so its name and docs stay in comments and only
+/// the impl blocks are code.
+pub fn render_primitive(state: &mut RenderState, path_prefix: &str, item: &Item) -> String {
+	let primitive = extract_item!(item, ItemEnum::Primitive);
+	let mut output = format!("/* primitive {} */\n", primitive.name);
+	for line in state.doc_text(item).unwrap_or_default().lines() {
+		output.push_str(format!("// {line}").trim_end());
+		output.push('\n');
+	}
+	output.push_str(&render_type_impls(
+		state,
+		path_prefix,
+		item,
+		&primitive.impls,
+	));
 	output
 }
 
 /// Render a struct declaration and its fields.
 pub fn render_struct(state: &mut RenderState, path_prefix: &str, item: &Item) -> String {
-	let docs = docs(item);
+	let docs = state.docs(item);
 
 	let struct_ = extract_item!(item, ItemEnum::Struct);
 
@@ -224,31 +347,20 @@ pub fn render_struct(state: &mut RenderState, path_prefix: &str, item: &Item) ->
 	let inline_traits = collect_inline_traits(state, &struct_.impls);
 
 	let rendered_struct = match &struct_.kind {
-		StructKind::Unit => Some(render_struct_unit(&ctx)),
+		StructKind::Unit => render_struct_unit(&ctx),
 		StructKind::Tuple(fields) => render_struct_tuple(state, &ctx, fields),
-		StructKind::Plain { fields, .. } => Some(render_struct_plain(state, &ctx, fields)),
+		StructKind::Plain { fields, .. } => render_struct_plain(state, &ctx, fields),
 	};
 
-	let mut output = String::new();
-
-	if let Some(rendered) = rendered_struct {
-		output.push_str(&docs);
-		if !inline_traits.is_empty() {
-			output.push_str(&format!("#[derive({})]\n", inline_traits.join(", ")));
-		}
-		output.push_str(&rendered);
+	let mut output = docs;
+	if !inline_traits.is_empty() {
+		output.push_str(&format!("#[derive({})]\n", inline_traits.join(", ")));
 	}
+	output.push_str(&state.attrs(item));
+	output.push_str(&rendered_struct);
+	push_auto_trait_summary(state, &mut output, &struct_.impls);
 
-	// Render impl blocks
-	for impl_id in &struct_.impls {
-		let impl_item = must_get(state.crate_data, impl_id);
-		let impl_ = extract_item!(impl_item, ItemEnum::Impl);
-		if should_render_impl(impl_, state.config.render_auto_impls)
-			&& state.selection_allows_child(&item.id, impl_id)
-		{
-			output.push_str(&render_impl(state, path_prefix, impl_item));
-		}
-	}
+	output.push_str(&render_type_impls(state, path_prefix, item, &struct_.impls));
 
 	output
 }
@@ -263,14 +375,16 @@ fn render_struct_unit(ctx: &StructRenderContext) -> String {
 	)
 }
 
+/// Render a tuple struct, keeping one `_` per field when the selection includes none of them so
+/// the declaration still exists for impls that refer to it.
 fn render_struct_tuple(
 	state: &RenderState,
 	ctx: &StructRenderContext,
 	fields: &[Option<Id>],
-) -> Option<String> {
+) -> String {
 	let selection = ctx.selection();
 	let include_placeholders = !selection.is_active() || selection.force_children();
-	let fields_str = fields
+	let field_strs = fields
 		.iter()
 		.filter_map(|field| match field {
 			Some(id) => {
@@ -278,8 +392,11 @@ fn render_struct_tuple(
 					return None;
 				}
 				let field_item = must_get(state.crate_data, id);
-				let ty = extract_item!(field_item, ItemEnum::StructField);
-				if !is_visible(state, field_item) {
+				let Some(ty) = try_extract_item!(field_item, ItemEnum::StructField) else {
+					state.record_mismatch(field_item, "struct field");
+					return None;
+				};
+				if !is_visible(state, field_item) || state.is_hidden(field_item) {
 					Some("_".to_string())
 				} else {
 					Some(format!("{}{}", render_vis(field_item), render_type(ty)))
@@ -287,21 +404,21 @@ fn render_struct_tuple(
 			}
 			None => include_placeholders.then(|| "_".to_string()),
 		})
-		.collect::<Vec<_>>()
-		.join(", ");
-
-	if selection.expands_self() || !fields_str.is_empty() {
-		Some(format!(
-			"{}struct {}{}({}){};\n\n",
-			render_vis(ctx.item()),
-			render_name(ctx.item()),
-			ctx.generics(),
-			fields_str,
-			ctx.where_clause()
-		))
+		.collect::<Vec<_>>();
+	let fields_str = if field_strs.is_empty() {
+		vec!["_"; fields.len()].join(", ")
 	} else {
-		None
-	}
+		field_strs.join(", ")
+	};
+
+	format!(
+		"{}struct {}{}({}){};\n\n",
+		render_vis(ctx.item()),
+		render_name(ctx.item()),
+		ctx.generics(),
+		fields_str,
+		ctx.where_clause()
+	)
 }
 
 fn render_struct_plain(state: &RenderState, ctx: &StructRenderContext, fields: &[Id]) -> String {
@@ -313,11 +430,25 @@ fn render_struct_plain(state: &RenderState, ctx: &StructRenderContext, fields: &
 		ctx.where_clause()
 	);
 
-	for field in fields {
-		let rendered = render_struct_field(state, field, ctx.force_children());
-		if !rendered.is_empty() {
-			output.push_str(&rendered);
-		}
+	let rendered: Vec<String> = fields
+		.iter()
+		.map(|field| render_struct_field(state, field, ctx.force_children()))
+		.filter(|rendered| !rendered.is_empty())
+		.collect();
+	if state.elision.elides_fields(rendered.len()) {
+		output.push_str(&format!("/* {} fields omitted */\n", rendered.len()));
+	} else {
+		output.extend(rendered);
+	}
+	// A selection that picked only some fields says how many others the struct has.
+	if ctx.selection().is_active() && !ctx.force_children() {
+		let unselected = fields
+			.iter()
+			.filter(|id| !state.selection_context_contains(id))
+			.map(|id| must_get(state.crate_data, id))
+			.filter(|field| is_visible(state, field) && !state.is_hidden(field))
+			.count();
+		output.push_str(&omitted_comment(unselected, "field"));
 	}
 
 	output.push_str("}\n\n");
@@ -336,13 +467,16 @@ pub fn render_struct_field(
 		return String::new();
 	}
 
-	if !(force || is_visible(state, field_item)) {
+	if !(force || is_visible(state, field_item)) || state.is_hidden(field_item) {
 		return String::new();
 	}
 
-	let ty = extract_item!(field_item, ItemEnum::StructField);
+	let Some(ty) = try_extract_item!(field_item, ItemEnum::StructField) else {
+		state.record_mismatch(field_item, "struct field");
+		return String::new();
+	};
 	let mut out = String::new();
-	out.push_str(&docs(field_item));
+	out.push_str(&state.docs(field_item));
 	out.push_str(&format!(
 		"{}{}: {},\n",
 		render_vis(field_item),
@@ -354,7 +488,7 @@ pub fn render_struct_field(
 
 /// Render an enum definition, including variants.
 pub fn render_enum(state: &mut RenderState, path_prefix: &str, item: &Item) -> String {
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 
 	let enum_ = extract_item!(item, ItemEnum::Enum);
 
@@ -374,6 +508,7 @@ pub fn render_enum(state: &mut RenderState, path_prefix: &str, item: &Item) -> S
 	if !inline_traits.is_empty() {
 		output.push_str(&format!("#[derive({})]\n", inline_traits.join(", ")));
 	}
+	output.push_str(&state.attrs(item));
 
 	output.push_str(&format!(
 		"{}enum {}{}{} {{\n",
@@ -383,35 +518,57 @@ pub fn render_enum(state: &mut RenderState, path_prefix: &str, item: &Item) -> S
 		ctx.where_clause()
 	));
 
+	let mut unselected = 0;
 	for variant_id in &enum_.variants {
+		let variant_item = must_get(state.crate_data, variant_id);
+		if state.is_hidden(variant_item) {
+			continue;
+		}
 		if !ctx.should_render_variant(state, variant_id) {
+			unselected += 1;
 			continue;
 		}
 
-		let variant_item = must_get(state.crate_data, variant_id);
 		let include_variant_fields = ctx.include_variant_fields(state, variant_item);
 		let rendered = render_enum_variant(state, &ctx, variant_item, include_variant_fields);
 		if !rendered.is_empty() {
 			output.push_str(&rendered);
 		}
 	}
+	output.push_str(&omitted_comment(unselected, "variant"));
 
 	output.push_str("}\n\n");
+	push_auto_trait_summary(state, &mut output, &enum_.impls);
 
-	// Render impl blocks
-	for impl_id in &enum_.impls {
-		let impl_item = must_get(state.crate_data, impl_id);
-		let impl_ = extract_item!(impl_item, ItemEnum::Impl);
-		if should_render_impl(impl_, state.config.render_auto_impls)
-			&& state.selection_allows_child(&item.id, impl_id)
-		{
-			output.push_str(&render_impl(state, path_prefix, impl_item));
-		}
-	}
+	output.push_str(&render_type_impls(state, path_prefix, item, &enum_.impls));
 
 	output
 }
 
+/// Follow the type declaration ending `output` with the summary of its auto trait impls, when
+/// [`crate::Renderer::with_auto_trait_summary`] asks for it.
+fn push_auto_trait_summary(state: &RenderState, output: &mut String, impls: &[Id]) {
+	if !state.config.render_auto_trait_summary {
+		return;
+	}
+	// Declarations end with a blank line, which stays after the summary.
+	let end = output.trim_end_matches('\n').len() + 1;
+	output.insert_str(
+		end.min(output.len()),
+		&auto_trait_summary(state.crate_data, impls),
+	);
+}
+
+/// Comment noting `count` other members of kind `noun` left out by the selection, or nothing
+/// when there are none.
+fn omitted_comment(count: usize, noun: &str) -> String {
+	match count {
+		0 => String::new(),
+		1 => format!("/* 1 other {noun} omitted */\n"),
+		_ => format!("/* {count} other {noun}s omitted */\n"),
+	}
+}
+
 /// Render a single enum variant.
 fn render_enum_variant(
 	state: &RenderState,
@@ -419,8 +576,11 @@ fn render_enum_variant(
 	item: &Item,
 	include_all_fields: bool,
 ) -> String {
-	let mut output = docs(item);
-	let variant = extract_item!(item, ItemEnum::Variant);
+	let Some(variant) = try_extract_item!(item, ItemEnum::Variant) else {
+		state.record_mismatch(item, "variant");
+		return String::new();
+	};
+	let mut output = state.docs(item);
 
 	output.push_str(&format!("    {}", render_name(item)));
 
@@ -438,7 +598,10 @@ fn render_enum_variant(
 							return None;
 						}
 						let field_item = must_get(state.crate_data, id);
-						let ty = extract_item!(field_item, ItemEnum::StructField);
+						let Some(ty) = try_extract_item!(field_item, ItemEnum::StructField) else {
+							state.record_mismatch(field_item, "struct field");
+							return None;
+						};
 						Some(render_type(ty))
 					})
 				})
@@ -453,11 +616,9 @@ fn render_enum_variant(
 					|| include_all_fields
 					|| state.selection_context_contains(field)
 				{
-					let rendered = render_struct_field(
-						state,
-						field,
-						include_all_fields || !ctx.selection().is_active(),
-					);
+					// Variant fields are as visible as the variant, whatever rustdoc reports for
+					// them, so only `#[doc(hidden)]` drops them here.
+					let rendered = render_struct_field(state, field, true);
 					if !rendered.is_empty() {
 						output.push_str(&rendered);
 					}
@@ -476,9 +637,22 @@ fn render_enum_variant(
 	output
 }
 
-enum UseResolution {
-	Items(Vec<Id>),
-	Alias { source: String, alias: String },
+pub(crate) enum UseResolution {
+	/// Items to inline, each with the name of the external crate defining it, if any.
+	Items(Vec<(Id, Option<String>)>),
+	/// A re-export of an item known only from the crate's `paths` summaries.
+	External {
+		/// Keyword of the item's kind, such as `struct`.
+		keyword: &'static str,
+		/// Name the item is re-exported under.
+		name: String,
+		/// Path of the `use`: the item's definition path, renamed with `as` when needed.
+		source: String,
+	},
+	Alias {
+		source: String,
+		alias: String,
+	},
 	Simple(String),
 }
 
@@ -490,27 +664,66 @@ pub fn render_use(state: &mut RenderState, path_prefix: &str, item: &Item) -> St
 	match resolution {
 		UseResolution::Items(items) => {
 			let mut output = String::new();
-			for item_id in items {
+			for (item_id, origin) in items {
 				if let Some(item) = state.crate_data.index.get(&item_id) {
-					output.push_str(&render_item(state, path_prefix, item, true));
+					let rendered = render_item(state, path_prefix, item, true);
+					match origin.filter(|_| state.config.render_origin_comments) {
+						Some(origin) => output.push_str(&append_origin_comment(&rendered, &origin)),
+						None => output.push_str(&rendered),
+					}
 				}
 			}
 			output
 		}
+		UseResolution::External {
+			keyword, source, ..
+		} => {
+			let mut output = state.docs(item);
+			output.push_str(&format!(
+				"{}use {source}; // re-exported {keyword}\n",
+				render_declared_vis(item)
+			));
+			output
+		}
 		UseResolution::Alias { source, alias } => {
-			let mut output = docs(item);
+			let mut output = state.docs(item);
+			output.push_str(&external_macro_comment(state, import).unwrap_or_default());
 			output.push_str(&format!("pub use {source} as {alias};\n"));
 			output
 		}
 		UseResolution::Simple(source) => {
-			let mut output = docs(item);
+			let mut output = state.docs(item);
+			output.push_str(&external_macro_comment(state, import).unwrap_or_default());
 			output.push_str(&format!("pub use {source};\n"));
 			output
 		}
 	}
 }
 
-fn resolve_use(state: &RenderState, import: &rustdoc_types::Use) -> UseResolution {
+/// Comment naming the kind and origin of a macro re-exported from another crate, and how it is
+/// invoked, such as a derive a facade crate re-exports from its proc-macro crate.
+///
+/// The macro's definition is not in the crate's rustdoc JSON, only its `paths` summary, so
+/// a derive's helper attributes cannot be shown.
+fn external_macro_comment(state: &RenderState, import: &rustdoc_types::Use) -> Option<String> {
+	let summary = state.crate_data.paths.get(import.id.as_ref()?)?;
+	if summary.crate_id == 0 {
+		return None;
+	}
+	let name = &import.name;
+	let (kind, usage) = match summary.kind {
+		ItemKind::ProcDerive => ("derive macro", format!("#[derive({name})]")),
+		ItemKind::ProcAttribute => ("attribute macro", format!("#[{name}]")),
+		ItemKind::Macro => ("macro", format!("{name}!(..)")),
+		_ => return None,
+	};
+	Some(format!(
+		"// {kind} {}, used as `{usage}`\n",
+		summary.path.join("::")
+	))
+}
+
+pub(crate) fn resolve_use(state: &RenderState, import: &rustdoc_types::Use) -> UseResolution {
 	if import.is_glob {
 		return resolve_glob_use(state, import);
 	}
@@ -520,12 +733,90 @@ fn resolve_use(state: &RenderState, import: &rustdoc_types::Use) -> UseResolutio
 		.as_ref()
 		.and_then(|id| state.crate_data.index.get(id))
 	{
-		return UseResolution::Items(vec![imported_item.id]);
+		return UseResolution::Items(vec![(imported_item.id, origin_crate(state, imported_item))]);
+	}
+
+	if state.config.inline_external_reexports
+		&& let Some(resolution) = resolve_external_use(state, import)
+	{
+		return resolution;
 	}
 
 	resolve_alias_use(import)
 }
 
+/// Name of the external crate defining `item`, or `None` for items of the crate being rendered.
+fn origin_crate(state: &RenderState, item: &Item) -> Option<String> {
+	if item.crate_id == 0 {
+		return None;
+	}
+	state
+		.crate_data
+		.external_crates
+		.get(&item.crate_id)
+		.map(|krate| krate.name.clone())
+}
+
+/// Append `// from <origin>` to the first line of `rendered` below its docs and attributes.
+fn append_origin_comment(rendered: &str, origin: &str) -> String {
+	let mut output = String::with_capacity(rendered.len() + origin.len() + 9);
+	let mut tagged = false;
+	for line in rendered.split_inclusive('\n') {
+		let trimmed = line.trim_start();
+		if tagged
+			|| trimmed.trim_end().is_empty()
+			|| trimmed.starts_with("//")
+			|| trimmed.starts_with("#[")
+		{
+			output.push_str(line);
+			continue;
+		}
+		let (code, newline) = match line.strip_suffix('\n') {
+			Some(code) => (code, "\n"),
+			None => (line, ""),
+		};
+		output.push_str(&format!("{code} // from {origin}{newline}"));
+		tagged = true;
+	}
+	output
+}
+
+/// Resolve a re-export of an external type to its definition path, using the crate's `paths`
+/// summaries.
+///
+/// The summaries carry no generics, so the re-export stays a `use` rather than becoming a stub
+/// declaration that would drop the type's parameters.
+fn resolve_external_use(state: &RenderState, import: &rustdoc_types::Use) -> Option<UseResolution> {
+	use crate::syntax::is_reserved_word;
+
+	let summary = state.crate_data.paths.get(import.id.as_ref()?)?;
+	if summary.crate_id == 0 {
+		return None;
+	}
+	let keyword = match summary.kind {
+		ItemKind::Struct => "struct",
+		ItemKind::Enum => "enum",
+		ItemKind::Trait => "trait",
+		_ => return None,
+	};
+	let name = if is_reserved_word(import.name.as_str()) {
+		format!("r#{}", import.name)
+	} else {
+		import.name.clone()
+	};
+	let origin = escape_path(&summary.path.join("::"));
+	let source = if summary.path.last() == Some(&import.name) {
+		origin
+	} else {
+		format!("{origin} as {name}")
+	};
+	Some(UseResolution::External {
+		keyword,
+		name,
+		source,
+	})
+}
+
 fn resolve_glob_use(state: &RenderState, import: &rustdoc_types::Use) -> UseResolution {
 	let Some(source_id) = &import.id else {
 		return UseResolution::Simple(format!("{}::*", escape_path(&import.source)));
@@ -535,42 +826,21 @@ fn resolve_glob_use(state: &RenderState, import: &rustdoc_types::Use) -> UseReso
 	};
 
 	match &source_item.inner {
-		ItemEnum::Module(module) => {
-			let items = module
-				.items
-				.iter()
-				.filter(|item_id| {
-					state
-						.crate_data
-						.index
-						.get(item_id)
-						.map(|item| is_visible(state, item))
-						.unwrap_or(false)
-				})
-				.cloned()
-				.collect();
-			UseResolution::Items(items)
-		}
-		ItemEnum::Enum(enum_) => {
-			let items = enum_
-				.variants
-				.iter()
-				.filter(|variant_id| {
-					state
-						.crate_data
-						.index
-						.get(variant_id)
-						.map(|variant| is_visible(state, variant))
-						.unwrap_or(false)
-				})
-				.cloned()
-				.collect();
-			UseResolution::Items(items)
-		}
+		ItemEnum::Module(module) => UseResolution::Items(visible_items(state, &module.items)),
+		ItemEnum::Enum(enum_) => UseResolution::Items(visible_items(state, &enum_.variants)),
 		_ => UseResolution::Simple(format!("{}::*", escape_path(&import.source))),
 	}
 }
 
+/// The visible items among `ids`, each with the external crate defining it, if any.
+fn visible_items(state: &RenderState, ids: &[Id]) -> Vec<(Id, Option<String>)> {
+	ids.iter()
+		.filter_map(|id| state.crate_data.index.get(id))
+		.filter(|item| is_visible(state, item))
+		.map(|item| (item.id, origin_crate(state, item)))
+		.collect()
+}
+
 fn resolve_alias_use(import: &rustdoc_types::Use) -> UseResolution {
 	use crate::syntax::is_reserved_word;
 
@@ -592,14 +862,26 @@ fn resolve_alias_use(import: &rustdoc_types::Use) -> UseResolution {
 }
 
 /// Determine whether an item should be rendered based on visibility settings.
-fn is_visible(state: &RenderState, item: &Item) -> bool {
+pub(crate) fn is_visible(state: &RenderState, item: &Item) -> bool {
 	state.config.render_private_items || matches!(item.visibility, Visibility::Public)
 }
 
 /// Render a function or method signature.
-fn render_function_item(_state: &RenderState, item: &Item, is_trait_method: bool) -> String {
-	let mut output = docs(item);
+fn render_function_item(state: &RenderState, item: &Item, is_trait_method: bool) -> String {
+	let mut output = state.docs(item);
+	output.push_str(&state.attrs(item));
 	let function = extract_item!(item, ItemEnum::Function);
+	let resugared = state
+		.config
+		.resugar_async
+		.then(|| resugar_async_trait(function))
+		.flatten();
+	if let Some(resugared) = &resugared {
+		output.push_str(resugared.comment());
+	}
+	let function = resugared
+		.as_ref()
+		.map_or(function, |resugared| &resugared.function);
 
 	// Handle const, async, and unsafe keywords in the correct order
 	let mut prefixes = Vec::new();
@@ -624,46 +906,59 @@ fn render_function_item(_state: &RenderState, item: &Item, is_trait_method: bool
 		render_where_clause(&function.generics)
 	));
 
-	// Use semicolon for trait method declarations, empty body for implementations
+	// Use semicolon for trait method declarations, a body for implementations
 	if is_trait_method && !function.has_body {
 		output.push_str(";\n\n");
 	} else {
-		output.push_str(" {}\n\n");
+		output.push_str(state.config.body_style.body(&function.sig));
+		output.push_str("\n\n");
 	}
 
 	output
 }
 
 /// Render a constant definition.
-fn render_constant_item(_state: &RenderState, item: &Item) -> String {
-	let mut output = docs(item);
+fn render_constant_item(state: &RenderState, item: &Item) -> String {
+	let mut output = state.docs(item);
 
 	let (type_, const_) = extract_item!(item, ItemEnum::Constant { type_, const_ });
-	output.push_str(&format!(
-		"{}const {}: {} = {};\n\n",
-		render_vis(item),
-		render_name(item),
-		render_type(type_),
-		const_.expr
+	output.push_str(&render_const_declaration(
+		&render_vis(item),
+		&render_name(item),
+		&render_type(type_),
+		&const_.expr,
 	));
 
 	output
 }
 
+/// Render a trait alias with its generics, bounds, and where clause.
+///
+/// Declaring trait aliases needs the nightly `trait_alias` feature, but rustfmt accepts the
+/// syntax, so the alias is emitted as written.
+fn render_trait_alias_item(state: &RenderState, item: &Item) -> String {
+	let mut output = state.docs(item);
+	output.push_str(&trait_alias_signature(item));
+	output.push_str(";\n\n");
+	output
+}
+
 /// Render a type alias with generics, bounds, and visibility.
-fn render_type_alias_item(_state: &RenderState, item: &Item) -> String {
+///
+/// A where clause goes before the `=`, the only place stable Rust accepts one on a type alias
+/// and the form rustfmt keeps.
+fn render_type_alias_item(state: &RenderState, item: &Item) -> String {
 	let type_alias = extract_item!(item, ItemEnum::TypeAlias);
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 
 	output.push_str(&format!(
-		"{}type {}{}{}",
+		"{}type {}{}{} = {};\n\n",
 		render_vis(item),
 		render_name(item),
 		render_generics(&type_alias.generics),
 		render_where_clause(&type_alias.generics),
+		render_type(&type_alias.type_),
 	));
 
-	output.push_str(&format!("= {};\n\n", render_type(&type_alias.type_)));
-
 	output
 }
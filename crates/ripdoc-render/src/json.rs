@@ -0,0 +1,426 @@
+//! Structured skeleton output for tooling that wants a tree instead of Rust source.
+//!
+//! The walker mirrors the text renderer: the same selection, filter, and visibility checks decide
+//! which items appear, but each item becomes a [`SkeletonNode`] carrying its signature and docs.
+
+use rustdoc_types::{Item, ItemEnum, StructKind, Type, VariantKind};
+use serde::{Deserialize, Serialize};
+
+use super::impls::{
+	impl_path_segment, impl_stub_header, impl_stub_kind, impl_trait_segment, is_stub_impl,
+	ordered_impls, should_render_impl, yields_to_generic_impl,
+};
+use super::items::{SelectionView, UseResolution, is_visible, resolve_use};
+use super::state::RenderState;
+use super::utils::{must_get, ppush};
+use crate::signatures::*;
+use crate::syntax::*;
+
+/// A single item in the structured skeleton.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkeletonNode {
+	/// Item kind label, such as `module`, `struct`, or `method`.
+	pub kind: String,
+	/// Item name as it appears in source.
+	pub name: String,
+	/// Fully qualified path of the item.
+	pub path: String,
+	/// Declaration-only signature of the item.
+	pub signature: String,
+	/// Documentation attached to the item, if any.
+	pub docs: Option<String>,
+	/// Items nested under this one.
+	pub children: Vec<SkeletonNode>,
+}
+
+impl SkeletonNode {
+	fn new(kind: &str, item: &Item, path: String, signature: String) -> Self {
+		Self {
+			kind: kind.to_string(),
+			name: item.name.clone().unwrap_or_default(),
+			path,
+			signature,
+			docs: item.docs.clone(),
+			children: Vec::new(),
+		}
+	}
+}
+
+/// Build skeleton nodes for an item, following the same rules as `render_item`.
+pub fn item_nodes(
+	state: &mut RenderState,
+	path_prefix: &str,
+	item: &Item,
+	force_private: bool,
+) -> Vec<SkeletonNode> {
+	if !state.selection_context_contains(&item.id) {
+		return Vec::new();
+	}
+
+	if state.should_filter(path_prefix, item) {
+		return Vec::new();
+	}
+
+	let visible = (force_private || is_visible(state, item)) && !state.is_hidden(item);
+	let nodes = match &item.inner {
+		ItemEnum::Module(_) => vec![module_node(state, path_prefix, item)],
+		ItemEnum::Struct(_) => vec![struct_node(state, path_prefix, item)],
+		ItemEnum::Enum(_) => vec![enum_node(state, path_prefix, item)],
+		ItemEnum::Trait(_) => vec![trait_node(state, path_prefix, item)],
+		ItemEnum::TraitAlias(_) => vec![leaf_node("trait alias", path_prefix, item)],
+		ItemEnum::Use(_) => use_nodes(state, path_prefix, item),
+		ItemEnum::Function(_) => vec![leaf_node("function", path_prefix, item)],
+		ItemEnum::Constant { .. } => vec![leaf_node("constant", path_prefix, item)],
+		ItemEnum::TypeAlias(_) => vec![leaf_node("type alias", path_prefix, item)],
+		ItemEnum::Macro(_) => vec![leaf_node("macro", path_prefix, item)],
+		ItemEnum::ProcMacro(_) => vec![leaf_node("proc macro", path_prefix, item)],
+		ItemEnum::Primitive(_) => vec![primitive_node(state, path_prefix, item)],
+		_ => {
+			if visible {
+				state.report.get_mut().record(path_prefix, item);
+			}
+			Vec::new()
+		}
+	};
+
+	if visible { nodes } else { Vec::new() }
+}
+
+/// Build a node for a module and its children.
+fn module_node(state: &mut RenderState, path_prefix: &str, item: &Item) -> SkeletonNode {
+	let path = ppush(path_prefix, &render_name(item));
+	let mut node = SkeletonNode::new("module", item, path.clone(), module_signature(item));
+	if !state.should_module_doc(&path, item) {
+		node.docs = None;
+	}
+
+	let module = extract_item!(item, ItemEnum::Module);
+	for item_id in &module.items {
+		let child = must_get(state.crate_data, item_id);
+		node.children.extend(item_nodes(state, &path, child, false));
+	}
+	node
+}
+
+/// Build a node for a signature-only item with no children.
+fn leaf_node(kind: &str, path_prefix: &str, item: &Item) -> SkeletonNode {
+	let signature = match &item.inner {
+		ItemEnum::Function(_) => function_signature(item),
+		ItemEnum::Constant { .. } => constant_signature(item),
+		ItemEnum::TypeAlias(_) => type_alias_signature(item),
+		ItemEnum::TraitAlias(_) => trait_alias_signature(item),
+		ItemEnum::Macro(_) => macro_signature(item),
+		ItemEnum::ProcMacro(_) => proc_macro_signature(item),
+		ItemEnum::AssocConst { .. } => assoc_const_signature(item),
+		ItemEnum::AssocType { .. } => assoc_type_signature(item),
+		ItemEnum::StructField(_) => field_signature(item),
+		_ => render_name(item),
+	};
+	SkeletonNode::new(
+		kind,
+		item,
+		ppush(path_prefix, &render_name(item)),
+		signature,
+	)
+}
+
+/// Build a node for a struct, its fields, and its impl blocks.
+fn struct_node(state: &mut RenderState, path_prefix: &str, item: &Item) -> SkeletonNode {
+	let struct_ = extract_item!(item, ItemEnum::Struct);
+	let path = ppush(path_prefix, &render_name(item));
+	let mut node = SkeletonNode::new("struct", item, path.clone(), struct_signature(item));
+	let selection = SelectionView::new(state, &item.id, false);
+
+	let field_ids: Vec<_> = match &struct_.kind {
+		StructKind::Unit => Vec::new(),
+		StructKind::Tuple(fields) => fields.iter().flatten().collect(),
+		StructKind::Plain { fields, .. } => fields.iter().collect(),
+	};
+	for field_id in field_ids {
+		let field_item = must_get(state.crate_data, field_id);
+		if !matches!(field_item.inner, ItemEnum::StructField(_)) {
+			state.record_mismatch(field_item, "struct field");
+			continue;
+		}
+		if selection.includes_child(state, field_id)
+			&& (selection.force_children() || is_visible(state, field_item))
+			&& !state.is_hidden(field_item)
+		{
+			node.children.push(leaf_node("field", &path, field_item));
+		}
+	}
+
+	node.children
+		.extend(impl_nodes(state, path_prefix, item, &struct_.impls));
+	node
+}
+
+/// Build a node for a primitive and its impl blocks.
+fn primitive_node(state: &mut RenderState, path_prefix: &str, item: &Item) -> SkeletonNode {
+	let primitive = extract_item!(item, ItemEnum::Primitive);
+	let path = ppush(path_prefix, &render_name(item));
+	let mut node = SkeletonNode::new("primitive", item, path, primitive_signature(item));
+	node.children
+		.extend(impl_nodes(state, path_prefix, item, &primitive.impls));
+	node
+}
+
+/// Build a node for an enum, its variants, and its impl blocks.
+fn enum_node(state: &mut RenderState, path_prefix: &str, item: &Item) -> SkeletonNode {
+	let enum_ = extract_item!(item, ItemEnum::Enum);
+	let path = ppush(path_prefix, &render_name(item));
+	let mut node = SkeletonNode::new("enum", item, path.clone(), enum_signature(item));
+	let selection = SelectionView::new(state, &item.id, true);
+
+	for variant_id in &enum_.variants {
+		if selection.is_active()
+			&& !selection.expands_self()
+			&& !selection.includes_child(state, variant_id)
+		{
+			continue;
+		}
+		let variant_item = must_get(state.crate_data, variant_id);
+		if state.is_hidden(variant_item) {
+			continue;
+		}
+		let include_all_fields = selection.expands_self()
+			|| !selection.is_active()
+			|| state.selection_matches(&variant_item.id);
+		node.children
+			.extend(variant_node(state, &path, variant_item, include_all_fields));
+	}
+
+	node.children
+		.extend(impl_nodes(state, path_prefix, item, &enum_.impls));
+	node
+}
+
+/// Build a node for an enum variant and its fields, or `None` when the item is not a variant.
+fn variant_node(
+	state: &RenderState,
+	path_prefix: &str,
+	item: &Item,
+	include_all_fields: bool,
+) -> Option<SkeletonNode> {
+	let Some(variant) = try_extract_item!(item, ItemEnum::Variant) else {
+		state.record_mismatch(item, "variant");
+		return None;
+	};
+	let field_lookup = |field_id: &rustdoc_types::Id| {
+		let field_item = state.crate_data.index.get(field_id)?;
+		let ItemEnum::StructField(ty) = &field_item.inner else {
+			return None;
+		};
+		if matches!(variant.kind, VariantKind::Struct { .. }) {
+			Some(format!("{}: {}", render_name(field_item), render_type(ty)))
+		} else {
+			Some(render_type(ty))
+		}
+	};
+	let path = ppush(path_prefix, &render_name(item));
+	let signature = variant_signature(item, variant, field_lookup);
+	let mut node = SkeletonNode::new("enum variant", item, path.clone(), signature);
+
+	let field_ids: Vec<_> = match &variant.kind {
+		VariantKind::Plain => Vec::new(),
+		VariantKind::Tuple(fields) => fields.iter().flatten().collect(),
+		VariantKind::Struct { fields, .. } => fields.iter().collect(),
+	};
+	for field_id in field_ids {
+		let field_item = must_get(state.crate_data, field_id);
+		if !matches!(field_item.inner, ItemEnum::StructField(_)) {
+			state.record_mismatch(field_item, "struct field");
+			continue;
+		}
+		if (include_all_fields || state.selection_context_contains(field_id))
+			&& !state.is_hidden(field_item)
+		{
+			node.children.push(leaf_node("field", &path, field_item));
+		}
+	}
+	Some(node)
+}
+
+/// Build a node for a trait and its associated items.
+fn trait_node(state: &RenderState, path_prefix: &str, item: &Item) -> SkeletonNode {
+	let trait_ = extract_item!(item, ItemEnum::Trait);
+	let path = ppush(path_prefix, &render_name(item));
+	let mut node = SkeletonNode::new("trait", item, path.clone(), trait_signature(item));
+	let selection = SelectionView::new(state, &item.id, true);
+
+	for item_id in &trait_.items {
+		if !selection.includes_child(state, item_id) {
+			continue;
+		}
+		let member = must_get(state.crate_data, item_id);
+		let kind = match &member.inner {
+			ItemEnum::Function(_) => "trait method",
+			ItemEnum::AssocConst { .. } => "assoc const",
+			ItemEnum::AssocType { .. } => "assoc type",
+			_ => continue,
+		};
+		node.children.push(leaf_node(kind, &path, member));
+	}
+	node
+}
+
+/// Build nodes for the impl blocks attached to a type, following `render_impl`.
+fn impl_nodes(
+	state: &mut RenderState,
+	path_prefix: &str,
+	parent: &Item,
+	impls: &[rustdoc_types::Id],
+) -> Vec<SkeletonNode> {
+	let mut nodes = Vec::new();
+	if !state.config.render_impls {
+		return nodes;
+	}
+	for impl_id in ordered_impls(state.crate_data, impls) {
+		let impl_item = must_get(state.crate_data, impl_id);
+		let Some(impl_) = try_extract_item!(impl_item, ItemEnum::Impl) else {
+			state.record_mismatch(impl_item, "impl");
+			continue;
+		};
+		if is_stub_impl(impl_) && state.renders_blanket_impls_for(&parent.id) {
+			nodes.push(impl_stub_node(path_prefix, impl_item));
+		} else if should_render_impl(impl_item, state.config)
+			&& state.selection_allows_child(&parent.id, impl_id)
+			&& let Some(node) = impl_node(state, path_prefix, impl_item)
+		{
+			nodes.push(node);
+		}
+	}
+	nodes
+}
+
+/// Build a childless node for a blanket or auto impl on a selected type.
+fn impl_stub_node(path_prefix: &str, item: &Item) -> SkeletonNode {
+	let impl_ = extract_item!(item, ItemEnum::Impl);
+	let target = render_type(&impl_.for_);
+	let (prefix, name) = impl_stub_header(impl_);
+	let signature = format!("{prefix} {name}");
+	SkeletonNode {
+		kind: impl_stub_kind(impl_).to_string(),
+		name,
+		path: ppush(path_prefix, &target),
+		signature,
+		docs: None,
+		children: Vec::new(),
+	}
+}
+
+/// Build a node for a single impl block, or `None` when nothing inside it is rendered.
+fn impl_node(state: &mut RenderState, path_prefix: &str, item: &Item) -> Option<SkeletonNode> {
+	let impl_ = extract_item!(item, ItemEnum::Impl);
+
+	if !state.selection_context_contains(&item.id) {
+		return None;
+	}
+
+	let selection_active = state.selection().is_some();
+	let parent_expanded = match &impl_.for_ {
+		Type::ResolvedPath(path) => state.selection_expands(&path.id),
+		_ => false,
+	};
+	let expand_children = !selection_active || state.selection_expands(&item.id) || parent_expanded;
+
+	if let Some(trait_) = &impl_.trait_
+		&& let Some(trait_item) = state.crate_data.index.get(&trait_.id)
+		&& !is_visible(state, trait_item)
+	{
+		return None;
+	}
+
+	let target = render_type(&impl_.for_);
+	let name = match &impl_.trait_ {
+		Some(trait_) => format!("{} for {target}", render_path(trait_)),
+		None => target.clone(),
+	};
+	let signature = format!(
+		"{}impl{} {name}{}",
+		if impl_.is_unsafe { "unsafe " } else { "" },
+		render_generics(&impl_.generics),
+		render_where_clause(&impl_.generics)
+	);
+
+	let path = ppush(path_prefix, &impl_path_segment(&impl_.for_));
+	let trait_name = impl_.trait_.as_ref().map(impl_trait_segment);
+	let mut node = SkeletonNode {
+		kind: "impl".to_string(),
+		name,
+		path: path.clone(),
+		signature,
+		docs: item.docs.clone(),
+		children: Vec::new(),
+	};
+
+	for item_id in &impl_.items {
+		let Some(member) = state.crate_data.index.get(item_id) else {
+			continue;
+		};
+		let is_trait_impl = impl_.trait_.is_some();
+		if !((!selection_active || expand_children || state.selection_context_contains(item_id))
+			&& (is_trait_impl || is_visible(state, member)))
+		{
+			continue;
+		}
+		if !is_trait_impl && yields_to_generic_impl(state, &path, &item.id, impl_, member) {
+			continue;
+		}
+		if state.should_filter_impl_item(&path, trait_name.as_deref(), member) {
+			continue;
+		}
+		let kind = match &member.inner {
+			ItemEnum::Function(_) => "method",
+			ItemEnum::Constant { .. } => "constant",
+			ItemEnum::AssocType { .. } => "assoc type",
+			ItemEnum::TypeAlias(_) => "type alias",
+			_ => continue,
+		};
+		node.children.push(leaf_node(kind, &path, member));
+	}
+
+	(!node.children.is_empty()).then_some(node)
+}
+
+/// Build nodes for a `use` declaration, inlining re-exported items like the text renderer.
+fn use_nodes(state: &mut RenderState, path_prefix: &str, item: &Item) -> Vec<SkeletonNode> {
+	let import = extract_item!(item, ItemEnum::Use);
+	match resolve_use(state, import) {
+		UseResolution::Items(items) => {
+			let mut nodes = Vec::new();
+			for (item_id, _) in items {
+				if let Some(target) = state.crate_data.index.get(&item_id) {
+					nodes.extend(item_nodes(state, path_prefix, target, true));
+				}
+			}
+			nodes
+		}
+		UseResolution::External {
+			keyword,
+			name,
+			source,
+		} => {
+			let mut node = SkeletonNode::new(
+				keyword,
+				item,
+				ppush(path_prefix, &name),
+				format!("{}use {source}", render_declared_vis(item)),
+			);
+			node.name = name;
+			vec![node]
+		}
+		UseResolution::Alias { alias, .. } => {
+			let mut node =
+				SkeletonNode::new("use", item, ppush(path_prefix, &alias), use_signature(item));
+			node.name = alias;
+			vec![node]
+		}
+		UseResolution::Simple(_) => vec![SkeletonNode::new(
+			"use",
+			item,
+			ppush(path_prefix, &import.name),
+			use_signature(item),
+		)],
+	}
+}
@@ -1,8 +1,27 @@
 //! Rendering logic that converts rustdoc data into skeleton Rust code.
 //!
 //! This module handles the transformation of rustdoc JSON output into skeleton code representation.
+//!
+//! To render only some items, select them by id and let [`RenderSelection::for_items`] add the
+//! modules, types and impl blocks around them:
+//!
+//! ```no_run
+//! use ripdoc_render::{RenderFormat, RenderSelection, Renderer};
+//! use rustdoc_types::{Crate, Id};
+//!
+//! fn render_picked(crate_data: &Crate, ids: &[Id]) -> ripdoc_render::error::Result<String> {
+//!     let selection = RenderSelection::for_items(crate_data, ids, false);
+//!     Renderer::default()
+//!         .with_format(RenderFormat::Rust)
+//!         .with_selection(selection)
+//!         .render(crate_data)
+//! }
+//! ```
 
 /// Convenience macro to destructure `rustdoc_types::Item` variants during rendering.
+///
+/// Panics on any other variant, so it is only for items whose kind the caller has already
+/// matched; items looked up by id from a parent go through [`try_extract_item!`].
 #[macro_export]
 macro_rules! extract_item {
     ($item:expr, $variant:path) => {
@@ -19,33 +38,75 @@ macro_rules! extract_item {
     };
 }
 
+/// Like [`extract_item!`], but yields `None` instead of panicking when the item is another
+/// variant, as rustdoc JSON from unusual toolchains can deliver.
+#[macro_export]
+macro_rules! try_extract_item {
+    ($item:expr, $variant:path) => {
+        match &$item.inner {
+            $variant(inner) => Some(inner),
+            _ => None,
+        }
+    };
+    ($item:expr, $variant:path { $($field:ident),+ }) => {
+        match &$item.inner {
+            $variant { $($field,)+ .. } => Some(($($field,)+)),
+            _ => None,
+        }
+    };
+}
+
 /// Syntax utilities for rendering items, types, and paths.
 pub mod syntax;
 
+/// Degrading output to fit a size budget.
+pub mod budget;
 /// Main renderer configuration and public API.
 pub mod core;
 /// Domain-specific errors for the renderer.
 pub mod error;
+/// Pluggable formatting of rendered Rust source.
+pub mod formatter;
 /// Trait and impl rendering logic.
 pub mod impls;
 /// Item-specific rendering functions.
 pub mod items;
+/// Structured JSON skeleton output.
+pub mod json;
 /// Procedural and declarative macro rendering.
 pub mod macros;
 /// Markdown conversion helpers.
 pub mod markdown;
+/// Items reachable from the crate root through public modules and re-exports.
+pub mod reachability;
+/// Reports of items left out of rendered output.
+pub mod report;
+/// Undoing the `#[async_trait]` desugaring of method signatures.
+pub mod resugar;
 /// Signature rendering utilities for Rust items.
 pub mod signatures;
+/// Rendering crates as one source file per module.
+pub mod split;
 /// Mutable rendering state and filtering.
 pub mod state;
 /// Utility functions for rendering.
 pub mod utils;
 
 // Re-export public API
-pub use core::{RenderFormat, RenderSelection, Renderer};
+pub use budget::RenderBudget;
+pub use core::{BodyStyle, RenderFormat, RenderSelection, Renderer};
+
+pub use formatter::FormatterChoice;
+
+pub use impls::DERIVE_TRAITS;
 
+pub use json::SkeletonNode;
+pub use reachability::Reachability;
+pub use report::{MismatchedItem, RenderReport, SkippedKind};
+pub use split::ModuleTree;
 pub use syntax::{
-	is_reserved_word, render_function_args, render_generic_bounds, render_generics, render_name,
-	render_path, render_return_type, render_type, render_type_inner, render_vis,
-	render_where_clause,
+	DEFAULT_MAX_TYPE_DEPTH, ELIDED_TYPE, TypeDepthLimit, attr_text, is_automatically_derived,
+	is_build_script_item, is_cfg_test_module, is_doc_hidden, is_reserved_word, render_attrs,
+	render_function_args, render_generic_bounds, render_generics, render_name, render_path,
+	render_return_type, render_type, render_type_inner, render_vis, render_where_clause,
 };
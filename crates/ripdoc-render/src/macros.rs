@@ -2,6 +2,7 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use rustdoc_types::{Item, ItemEnum, MacroKind};
 
+use super::state::RenderState;
 use crate::syntax::*;
 
 /// Reusable pattern for removing placeholder bodies from macro output.
@@ -9,10 +10,10 @@ static MACRO_PLACEHOLDER_REGEX: Lazy<Regex> =
 	Lazy::new(|| Regex::new(r"\}\s*\{\s*\.\.\.\s*\}\s*$").expect("valid macro fallback pattern"));
 
 /// Render a macro_rules! definition.
-pub fn render_macro(item: &Item) -> String {
+pub fn render_macro(state: &RenderState, item: &Item) -> String {
 	use crate::syntax::is_reserved_word;
 
-	let mut output = docs(item);
+	let mut output = state.docs(item);
 
 	let macro_def = extract_item!(item, ItemEnum::Macro);
 	// Add #[macro_export] for public macros
@@ -38,6 +39,9 @@ pub fn render_macro(item: &Item) -> String {
 			macro_str
 		};
 
+	// Keep each rule's matcher but drop its transcriber, which a skeleton has no use for.
+	let fixed_macro_str = collapse_macro_rules(&fixed_macro_str).unwrap_or(fixed_macro_str);
+
 	if let Some(name_start) = fixed_macro_str.find("macro_rules!") {
 		let prefix = &fixed_macro_str[..name_start + 12]; // "macro_rules!"
 		let rest = &fixed_macro_str[name_start + 12..];
@@ -67,9 +71,190 @@ pub fn render_macro(item: &Item) -> String {
 	output
 }
 
+/// Rewrite a `macro_rules!` definition so every transcriber reads `{ ... }`.
+///
+/// Returns `None` when the text does not parse as a sequence of `matcher => transcriber` rules,
+/// in which case the caller keeps it unchanged.
+fn collapse_macro_rules(source: &str) -> Option<String> {
+	let name_start = source.find("macro_rules!")? + "macro_rules!".len();
+	let open = name_start + source[name_start..].find(['{', '(', '['])?;
+	let close = group_end(source, open)?;
+
+	let mut rules = Vec::new();
+	let mut pos = skip_trivia(source, open + 1);
+	while pos < close {
+		let matcher_end = group_end(source, pos)?;
+		let matcher = &source[pos..=matcher_end];
+		pos = skip_trivia(source, matcher_end + 1);
+		if !source[pos..].starts_with("=>") {
+			return None;
+		}
+		pos = skip_trivia(source, pos + 2);
+		pos = skip_trivia(source, group_end(source, pos)? + 1);
+		if source[pos..].starts_with(';') {
+			pos = skip_trivia(source, pos + 1);
+		}
+		rules.push(format!("    {matcher} => {{ ... }};\n"));
+	}
+	if pos != close {
+		return None;
+	}
+
+	Some(format!(
+		"{}\n{}{}",
+		&source[..=open],
+		rules.concat(),
+		&source[close..]
+	))
+}
+
+/// Index of the delimiter closing the group opened at `open`.
+///
+/// Delimiters inside string literals, character literals and comments are ignored.
+fn group_end(source: &str, open: usize) -> Option<usize> {
+	let bytes = source.as_bytes();
+	if !matches!(bytes.get(open), Some(b'(' | b'[' | b'{')) {
+		return None;
+	}
+	let mut depth = 0usize;
+	let mut pos = open;
+	while pos < bytes.len() {
+		match bytes[pos] {
+			b'(' | b'[' | b'{' => depth += 1,
+			b')' | b']' | b'}' => {
+				depth = depth.checked_sub(1)?;
+				if depth == 0 {
+					return Some(pos);
+				}
+			}
+			b'"' => pos = string_end(bytes, pos)?,
+			b'r' if is_raw_string_start(bytes, pos) => pos = raw_string_end(bytes, pos)?,
+			b'\'' => pos = char_end(source, pos),
+			b'/' if bytes.get(pos + 1) == Some(&b'/') || bytes.get(pos + 1) == Some(&b'*') => {
+				pos = comment_end(bytes, pos)?;
+			}
+			_ => {}
+		}
+		pos += 1;
+	}
+	None
+}
+
+/// Index just past any whitespace and comments starting at `pos`.
+fn skip_trivia(source: &str, mut pos: usize) -> usize {
+	let bytes = source.as_bytes();
+	loop {
+		while bytes.get(pos).is_some_and(u8::is_ascii_whitespace) {
+			pos += 1;
+		}
+		match (bytes.get(pos), bytes.get(pos + 1)) {
+			(Some(b'/'), Some(b'/' | b'*')) => match comment_end(bytes, pos) {
+				Some(end) => pos = end + 1,
+				None => return bytes.len(),
+			},
+			_ => return pos,
+		}
+	}
+}
+
+/// Index of the quote closing the string literal opened at `quote`.
+fn string_end(bytes: &[u8], quote: usize) -> Option<usize> {
+	let mut pos = quote + 1;
+	while pos < bytes.len() {
+		match bytes[pos] {
+			b'\\' => pos += 1,
+			b'"' => return Some(pos),
+			_ => {}
+		}
+		pos += 1;
+	}
+	None
+}
+
+/// Whether the `r` at `pos` starts a raw string literal such as `r"..."` or `r#"..."#`.
+fn is_raw_string_start(bytes: &[u8], pos: usize) -> bool {
+	let is_ident = |idx: usize| bytes[idx].is_ascii_alphanumeric() || bytes[idx] == b'_';
+	// A `b` directly before the `r` makes a raw byte string; any other identifier character
+	// means the `r` ends an identifier.
+	let prefix = match pos.checked_sub(1) {
+		Some(prev) if bytes[prev] == b'b' => prev,
+		_ => pos,
+	};
+	let standalone = prefix == 0 || !is_ident(prefix - 1);
+	let hashes = bytes[pos + 1..]
+		.iter()
+		.take_while(|&&byte| byte == b'#')
+		.count();
+	standalone && bytes.get(pos + 1 + hashes) == Some(&b'"')
+}
+
+/// Index of the last character of the raw string literal starting with the `r` at `start`.
+fn raw_string_end(bytes: &[u8], start: usize) -> Option<usize> {
+	let hashes = bytes[start + 1..]
+		.iter()
+		.take_while(|&&byte| byte == b'#')
+		.count();
+	let body = start + hashes + 2;
+	(body..bytes.len()).find_map(|pos| {
+		let closes = bytes[pos] == b'"'
+			&& bytes.len() > pos + hashes
+			&& bytes[pos + 1..=pos + hashes]
+				.iter()
+				.all(|&byte| byte == b'#');
+		closes.then_some(pos + hashes)
+	})
+}
+
+/// Index of the quote closing the character literal opened at `quote`, or `quote` itself when
+/// it starts a lifetime or label instead.
+fn char_end(source: &str, quote: usize) -> usize {
+	let rest = &source[quote + 1..];
+	if let Some(escaped) = rest.strip_prefix('\\') {
+		// `'\''`, `'\n'`, `'\u{..}'`: the quote after the escaped character closes it.
+		return escaped
+			.get(1..)
+			.and_then(|tail| tail.find('\''))
+			.map_or(quote, |end| quote + 3 + end);
+	}
+	let mut chars = rest.chars();
+	match (chars.next(), chars.next()) {
+		(Some(c), Some('\'')) => quote + 1 + c.len_utf8(),
+		_ => quote,
+	}
+}
+
+/// Index of the last character of the line or block comment starting at `start`.
+fn comment_end(bytes: &[u8], start: usize) -> Option<usize> {
+	if bytes[start + 1] == b'/' {
+		let newline = bytes[start..].iter().position(|&byte| byte == b'\n');
+		return Some(newline.map_or(bytes.len() - 1, |offset| start + offset));
+	}
+	// Block comments nest.
+	let mut depth = 0usize;
+	let mut pos = start;
+	while pos + 1 < bytes.len() {
+		match (bytes[pos], bytes[pos + 1]) {
+			(b'/', b'*') => {
+				depth += 1;
+				pos += 1;
+			}
+			(b'*', b'/') => {
+				depth -= 1;
+				pos += 1;
+				if depth == 0 {
+					return Some(pos);
+				}
+			}
+			_ => {}
+		}
+		pos += 1;
+	}
+	None
+}
+
 /// Render a procedural macro definition.
-pub fn render_proc_macro(item: &Item) -> String {
-	let mut output = docs(item);
+pub fn render_proc_macro(state: &RenderState, item: &Item) -> String {
+	let mut output = state.docs(item);
 
 	let fn_name = render_name(item);
 
@@ -105,3 +290,58 @@ pub fn render_proc_macro(item: &Item) -> String {
 
 	output
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn transcribers_collapse_in_every_rule() {
+		let source = "\
+macro_rules! pick {
+    () => { None };
+    ($x:expr) => {{
+        let v = $x;
+        Some(v)
+    }};
+    ($x:expr, $($rest:tt)*) => ( pick!($($rest)*) )
+}";
+		let expected = "\
+macro_rules! pick {
+    () => { ... };
+    ($x:expr) => { ... };
+    ($x:expr, $($rest:tt)*) => { ... };
+}";
+		assert_eq!(collapse_macro_rules(source).unwrap(), expected);
+	}
+
+	#[test]
+	fn braces_in_matchers_and_literals_are_balanced() {
+		let source = r##"macro_rules! block {
+    ({ $($body:tt)* } else { $alt:expr }) => {
+        println!("}} {{");
+        let _ = '}';
+        let _ = r#"}"#; // }
+    };
+}"##;
+		let expected = "\
+macro_rules! block {
+    ({ $($body:tt)* } else { $alt:expr }) => { ... };
+}";
+		assert_eq!(collapse_macro_rules(source).unwrap(), expected);
+	}
+
+	#[test]
+	fn lifetimes_are_not_char_literals() {
+		let source = "macro_rules! borrow {\n    ($lt:lifetime) => { &'static str };\n}";
+		assert_eq!(
+			collapse_macro_rules(source).unwrap(),
+			"macro_rules! borrow {\n    ($lt:lifetime) => { ... };\n}"
+		);
+	}
+
+	#[test]
+	fn unparseable_rules_are_left_alone() {
+		assert_eq!(collapse_macro_rules("macro_rules! odd { ($x:expr) }"), None);
+	}
+}
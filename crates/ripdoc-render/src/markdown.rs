@@ -1,5 +1,3 @@
-use std::iter::Peekable;
-
 /// Render formatted Rust source into Markdown by stripping the outer module and
 /// converting doc comments + code fences into Markdown-friendly output.
 pub fn render_markdown(source: &str) -> String {
@@ -7,56 +5,285 @@ pub fn render_markdown(source: &str) -> String {
 	rust_to_markdown(&without_outer)
 }
 
-fn rust_to_markdown(source: &str) -> String {
-	let mut markdown = String::new();
-	let mut in_code_block = false;
-	let mut need_gap_before_code = false;
-	let mut code_buffer: Vec<String> = Vec::new();
-	let mut lines = source.lines().peekable();
+/// Incremental counterpart to [`render_markdown`] that accepts formatted Rust in pieces.
+///
+/// Pushing a source through [`MarkdownStream::push`] in any number of pieces and then calling
+/// [`MarkdownStream::finish`] yields the same text as a single [`render_markdown`] call, while
+/// only buffering the code block currently being assembled.
+#[derive(Debug, Default)]
+pub struct MarkdownStream {
+	/// Trailing source text that does not yet form a complete line.
+	partial: String,
+	/// Tracks the outer module wrapper so it can be stripped without seeing the whole source.
+	outer: OuterModule,
+	/// Line-oriented Rust to Markdown conversion state.
+	converter: Converter,
+	/// Blank line normalisation applied to the converted output.
+	normalizer: SpacingNormalizer,
+}
+
+impl MarkdownStream {
+	/// Create an empty stream.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Feed the next piece of formatted Rust source, returning any Markdown that is now final.
+	pub fn push(&mut self, source: &str) -> String {
+		self.partial.push_str(source);
+		let Some(end) = self.partial.rfind('\n') else {
+			return String::new();
+		};
+		let rest = self.partial.split_off(end + 1);
+		let complete = std::mem::replace(&mut self.partial, rest);
+		for line in complete.lines() {
+			self.push_line(line);
+		}
+		let ready = self.converter.take_complete_lines();
+		self.normalize(&ready)
+	}
+
+	/// Flush all buffered state and return the remaining Markdown.
+	pub fn finish(mut self) -> String {
+		let partial = std::mem::take(&mut self.partial);
+		for line in partial.lines() {
+			self.push_line(line);
+		}
+		for line in self.outer.finish() {
+			self.converter.push_line(&line);
+		}
+		self.converter.finish();
+		let remaining = std::mem::take(&mut self.converter.markdown);
+		self.normalize(&remaining)
+	}
+
+	fn push_line(&mut self, line: &str) {
+		for line in self.outer.push_line(line) {
+			self.converter.push_line(&line);
+		}
+	}
+
+	fn normalize(&mut self, markdown: &str) -> String {
+		let mut output = String::new();
+		for line in markdown.lines() {
+			self.normalizer.push_line(line, &mut output);
+		}
+		output
+	}
+}
+
+/// Streaming equivalent of [`strip_outer_module`].
+#[derive(Debug, Default)]
+struct OuterModule {
+	/// First non-blank line, held until the second line decides whether it is stripped.
+	header: Option<String>,
+	/// Whether any non-blank line has been seen.
+	started: bool,
+	/// Whether the header was recognised as the outer module and dropped.
+	stripping: bool,
+	/// Last non-blank line and any blank lines after it, held in case it closes the module.
+	held: Vec<String>,
+}
+
+impl OuterModule {
+	/// Accept a source line, returning the lines that can be converted now.
+	fn push_line(&mut self, line: &str) -> Vec<String> {
+		if !self.started {
+			if !line.trim().is_empty() {
+				self.started = true;
+				// Matches the leading trim applied to the whole source in the batch path.
+				self.header = Some(line.trim_start().to_string());
+			}
+			return Vec::new();
+		}
+
+		let mut ready = Vec::new();
+		if let Some(header) = self.header.take() {
+			let first = header.trim();
+			if first.starts_with("pub mod ") && first.ends_with('{') {
+				self.stripping = true;
+			} else {
+				ready.push(header);
+			}
+		}
+
+		if !self.stripping {
+			ready.push(line.to_string());
+		} else if line.trim().is_empty() {
+			self.held.push(line.to_string());
+		} else {
+			ready.append(&mut self.held);
+			self.held.push(line.to_string());
+		}
+		ready
+	}
+
+	/// Release any held lines once the source is complete.
+	fn finish(&mut self) -> Vec<String> {
+		if let Some(header) = self.header.take() {
+			return vec![header];
+		}
+		let closes_module = self.held.first().is_some_and(|line| line.trim() == "}");
+		if closes_module {
+			self.held.clear();
+		}
+		std::mem::take(&mut self.held)
+	}
+}
+
+/// Line-oriented state machine turning formatted Rust into Markdown.
+#[derive(Debug, Default)]
+struct Converter {
+	/// Markdown produced so far that has not been handed out yet.
+	markdown: String,
+	in_code_block: bool,
+	need_gap_before_code: bool,
+	code_buffer: Vec<String>,
+	/// Consecutive doc comment lines awaiting conversion.
+	doc_block: Vec<(String, String)>,
+	/// Whether the pending doc block started with an outer (`///`) comment.
+	doc_block_is_outer: bool,
+}
 
-	while let Some(line) = lines.next() {
+impl Converter {
+	fn push_line(&mut self, line: &str) {
 		let trimmed = line.trim_start();
 
 		if is_doc_comment(trimmed) {
-			let doc_block = collect_doc_block(line, &mut lines);
-			let is_inner_doc = trimmed.starts_with("///");
-			let inline_doc = in_code_block
-				&& is_inner_doc
-				&& doc_block.len() == 1
-				&& !doc_block[0].1.trim().is_empty();
-
-			if inline_doc {
-				let indent = &doc_block[0].0;
-				let text = doc_block[0].1.trim();
-				code_buffer.push(format!("{indent}// {text}"));
-			} else {
-				flush_code_block(&mut markdown, &mut code_buffer, &mut need_gap_before_code);
-				in_code_block = false;
-				let doc_contains_text = render_doc_block(&doc_block, &mut markdown);
-				need_gap_before_code = doc_contains_text;
+			if self.doc_block.is_empty() {
+				self.doc_block_is_outer = trimmed.starts_with("///");
 			}
-			continue;
+			self.doc_block.push(doc_line(line));
+			return;
 		}
 
+		self.flush_doc_block();
+
 		if trimmed.is_empty() {
-			if in_code_block {
-				code_buffer.push(String::new());
-			} else if !markdown.is_empty() && !markdown.ends_with('\n') {
-				markdown.push('\n');
+			if self.in_code_block {
+				self.code_buffer.push(String::new());
+			} else if !self.markdown.is_empty() && !self.markdown.ends_with('\n') {
+				self.markdown.push('\n');
 			}
-			continue;
+			return;
 		}
 
-		if !in_code_block {
-			in_code_block = true;
+		if !self.in_code_block {
+			self.in_code_block = true;
 		}
 
-		code_buffer.push(line.to_string());
+		self.code_buffer.push(line.to_string());
 	}
 
-	flush_code_block(&mut markdown, &mut code_buffer, &mut need_gap_before_code);
+	fn flush_doc_block(&mut self) {
+		if self.doc_block.is_empty() {
+			return;
+		}
+		let doc_block = std::mem::take(&mut self.doc_block);
+		let inline_doc = self.in_code_block
+			&& self.doc_block_is_outer
+			&& doc_block.len() == 1
+			&& !doc_block[0].1.trim().is_empty();
+
+		if inline_doc {
+			let indent = &doc_block[0].0;
+			let text = doc_block[0].1.trim();
+			self.code_buffer.push(format!("{indent}// {text}"));
+		} else {
+			flush_code_block(
+				&mut self.markdown,
+				&mut self.code_buffer,
+				&mut self.need_gap_before_code,
+			);
+			self.in_code_block = false;
+			let doc_contains_text = render_doc_block(&doc_block, &mut self.markdown);
+			self.need_gap_before_code = doc_contains_text;
+		}
+	}
+
+	fn finish(&mut self) {
+		self.flush_doc_block();
+		flush_code_block(
+			&mut self.markdown,
+			&mut self.code_buffer,
+			&mut self.need_gap_before_code,
+		);
+	}
+
+	/// Hand out completed lines while keeping enough trailing output for the gap checks, which
+	/// only look at whether the buffer is empty and at its last two characters.
+	fn take_complete_lines(&mut self) -> String {
+		let keep_from = self.markdown.len().saturating_sub(2);
+		let Some(end) = self.markdown.as_bytes()[..keep_from]
+			.iter()
+			.rposition(|&b| b == b'\n')
+		else {
+			return String::new();
+		};
+		let rest = self.markdown.split_off(end + 1);
+		std::mem::replace(&mut self.markdown, rest)
+	}
+}
 
-	let normalized = normalize_spacing(&markdown);
+/// Collapses repeated blank lines and trims blank lines around fenced code.
+#[derive(Debug, Default)]
+struct SpacingNormalizer {
+	in_fence: bool,
+	/// A blank line seen but not yet emitted, since the next line decides whether it survives.
+	pending_blank: bool,
+	/// Whether any non-blank output has been emitted.
+	started: bool,
+}
+
+impl SpacingNormalizer {
+	fn push_line(&mut self, line: &str, output: &mut String) {
+		let trimmed = line.trim();
+		if trimmed.starts_with("```") {
+			if self.pending_blank && !self.in_fence {
+				self.emit("", output);
+			}
+			self.pending_blank = false;
+			self.emit(line, output);
+			self.in_fence = !self.in_fence;
+			return;
+		}
+
+		if trimmed.is_empty() {
+			self.pending_blank = true;
+			return;
+		}
+
+		if self.pending_blank {
+			self.emit("", output);
+			self.pending_blank = false;
+		}
+		self.emit(line, output);
+	}
+
+	fn emit(&mut self, line: &str, output: &mut String) {
+		if !self.started {
+			// Leading whitespace of the document is trimmed.
+			let line = line.trim_start();
+			if line.is_empty() {
+				return;
+			}
+			self.started = true;
+			output.push_str(line);
+		} else {
+			output.push('\n');
+			output.push_str(line);
+		}
+	}
+}
+
+fn rust_to_markdown(source: &str) -> String {
+	let mut converter = Converter::default();
+	for line in source.lines() {
+		converter.push_line(line);
+	}
+	converter.finish();
+
+	let normalized = normalize_spacing(&converter.markdown);
 	normalized.trim().to_string()
 }
 
@@ -75,29 +302,15 @@ fn strip_outer_module(source: &str) -> String {
 	trimmed.to_string()
 }
 
-fn collect_doc_block<'a, I>(first_line: &'a str, lines: &mut Peekable<I>) -> Vec<(String, String)>
-where
-	I: Iterator<Item = &'a str>,
-{
-	let mut block = Vec::new();
-	let mut current_line = first_line;
-	loop {
-		let trimmed = current_line.trim_start();
-		let indent = current_line
-			.chars()
-			.take_while(|c| c.is_whitespace())
-			.collect::<String>();
-		let text = strip_doc_comment(trimmed).trim_end().to_string();
-		block.push((indent, text));
-
-		match lines.peek() {
-			Some(next_line) if is_doc_comment(next_line.trim_start()) => {
-				current_line = lines.next().unwrap();
-			}
-			_ => break,
-		}
-	}
-	block
+/// Split a doc comment line into its indentation and comment text.
+fn doc_line(line: &str) -> (String, String) {
+	let trimmed = line.trim_start();
+	let indent = line
+		.chars()
+		.take_while(|c| c.is_whitespace())
+		.collect::<String>();
+	let text = strip_doc_comment(trimmed).trim_end().to_string();
+	(indent, text)
 }
 
 fn is_doc_comment(line: &str) -> bool {
@@ -154,9 +367,9 @@ fn render_doc_block(doc_block: &[(String, String)], markdown: &mut String) -> bo
 	for (_, text) in doc_block {
 		let trimmed_end = text.trim_end();
 		let trimmed_start = trimmed_end.trim_start();
-		if trimmed_start.starts_with("```") {
+		if let Some(info) = trimmed_start.strip_prefix("```") {
 			flush_paragraph(markdown, &mut paragraph, &mut contains_text);
-			let lang = trimmed_start[3..].trim();
+			let lang = info.trim();
 			if let Some(mapped) = normalize_doc_lang(lang) {
 				if fence_open {
 					markdown.push_str("```\n\n");
@@ -300,50 +513,12 @@ fn unhide_doctest_line(line: &str) -> Option<String> {
 }
 
 fn normalize_spacing(input: &str) -> String {
-	let mut result: Vec<String> = Vec::new();
-	let lines: Vec<&str> = input.lines().collect();
-	let mut in_fence = false;
-
-	for (idx, line) in lines.iter().enumerate() {
-		let trimmed = line.trim();
-		if trimmed.starts_with("```") {
-			if in_fence
-				&& result
-					.last()
-					.map(|prev| prev.trim().is_empty())
-					.unwrap_or(false)
-			{
-				result.pop();
-			}
-			result.push((*line).to_string());
-			in_fence = !in_fence;
-			continue;
-		}
-
-		let is_blank = trimmed.is_empty();
-		if is_blank {
-			if result
-				.last()
-				.map(|prev| prev.trim().is_empty())
-				.unwrap_or(false)
-			{
-				continue;
-			}
-			let next_is_closing = in_fence
-				&& lines
-					.get(idx + 1)
-					.map(|next| next.trim().starts_with("```"))
-					.unwrap_or(false);
-			if next_is_closing {
-				continue;
-			}
-			result.push(String::new());
-		} else {
-			result.push((*line).to_string());
-		}
+	let mut normalizer = SpacingNormalizer::default();
+	let mut output = String::new();
+	for line in input.lines() {
+		normalizer.push_line(line, &mut output);
 	}
-
-	result.join("\n")
+	output
 }
 
 fn normalize_doc_lang(lang: &str) -> Option<&'static str> {
@@ -522,4 +697,41 @@ pub struct Cart;
 
 		assert_eq!(rust_to_markdown(source), expected.trim());
 	}
+
+	#[test]
+	fn stream_matches_batch_conversion() {
+		let source = "\
+pub mod example {
+    //! Crate docs
+
+    /// Widget docs
+    pub struct Widget;
+
+    pub fn alpha() {}
+    pub fn beta() {}
+
+    /// ```
+    /// let value = 1;
+    /// ```
+    pub fn gamma() {}
+}
+";
+
+		let expected = render_markdown(source);
+		for split in 1..source.len() {
+			let mut stream = MarkdownStream::new();
+			let mut output = stream.push(&source[..split]);
+			output.push_str(&stream.push(&source[split..]));
+			output.push_str(&stream.finish());
+			assert_eq!(output, expected, "split at byte {split}");
+		}
+
+		let mut stream = MarkdownStream::new();
+		let mut output = String::new();
+		for line in source.lines() {
+			output.push_str(&stream.push(&format!("{line}\n")));
+		}
+		output.push_str(&stream.finish());
+		assert_eq!(output, expected);
+	}
 }
@@ -0,0 +1,195 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use rustdoc_types::{Crate, Id, Item, ItemEnum, Visibility};
+
+use crate::core::{RenderSelection, child_ids};
+
+/// The public surface of a crate: the items that can be named from the crate root by going
+/// through public modules and `pub use` re-exports, glob re-exports included.
+///
+/// Many crates declare everything in private modules and re-export what they mean to publish
+/// from the root. Items that are `pub` but sit in a private module nothing re-exports are not
+/// part of this surface; [`Reachability::unreachable_pub`] lists them.
+#[derive(Debug, Clone, Default)]
+pub struct Reachability {
+	/// Public path of each reachable item, starting with the crate name: the one through the
+	/// fewest modules, or the first found among those.
+	public_paths: HashMap<Id, String>,
+	/// Public `use` items passed through, which render as the items they re-export.
+	uses: HashSet<Id>,
+}
+
+impl Reachability {
+	/// Walk `crate_data` from its root through public modules and re-exports.
+	///
+	/// Modules are visited breadth first, so an item re-exported from several places keeps
+	/// the public path through the fewest modules.
+	pub fn new(crate_data: &Crate) -> Self {
+		let mut reachability = Self::default();
+		let Some(root) = crate_data.index.get(&crate_data.root) else {
+			return reachability;
+		};
+		let mut pending = VecDeque::new();
+		reachability.reach(root, root.name.clone().unwrap_or_default(), &mut pending);
+		while let Some((module_id, path)) = pending.pop_front() {
+			let Some(ItemEnum::Module(module)) =
+				crate_data.index.get(&module_id).map(|item| &item.inner)
+			else {
+				continue;
+			};
+			for id in &module.items {
+				reachability.visit(crate_data, id, &path, &mut pending);
+			}
+		}
+		reachability
+	}
+
+	/// Whether the item `id` can be named from the crate root.
+	///
+	/// Only modules and the items declared in them are tracked; the fields, variants and
+	/// methods of a reachable type are reachable along with it.
+	pub fn is_reachable(&self, id: &Id) -> bool {
+		self.public_paths.contains_key(id)
+	}
+
+	/// Public path of the item `id`, such as `my_crate::Engine` for an item declared in
+	/// `my_crate::internal` and re-exported from the root, or `None` when it is unreachable.
+	pub fn public_path(&self, id: &Id) -> Option<&str> {
+		self.public_paths.get(id).map(String::as_str)
+	}
+
+	/// Select the reachable items with every member of them, along with the public modules and
+	/// `use` items leading to them.
+	///
+	/// Private modules are left out, so items declared in them render in place of the
+	/// re-exports naming them, under their public paths.
+	pub fn selection(&self, crate_data: &Crate) -> RenderSelection {
+		let mut context = self.uses.clone();
+		let mut expanded = HashSet::new();
+		let mut pending: Vec<Id> = self
+			.public_paths
+			.keys()
+			.filter(|id| {
+				!matches!(
+					crate_data.index.get(id).map(|item| &item.inner),
+					Some(ItemEnum::Module(_))
+				)
+			})
+			.copied()
+			.collect();
+		while let Some(id) = pending.pop() {
+			let Some(item) = crate_data.index.get(&id) else {
+				continue;
+			};
+			let children = child_ids(crate_data, item);
+			if !children.is_empty() && expanded.insert(id) {
+				context.extend(children.iter().copied());
+				pending.extend(children);
+			}
+		}
+		let matches = self.public_paths.keys().copied().collect();
+		RenderSelection::new(matches, context, expanded)
+	}
+
+	/// Items declared `pub` that no public path reaches, each with the path it is declared at,
+	/// in declaration order.
+	///
+	/// These are usually accidental API: `pub` items in a private module that the crate never
+	/// re-exports. Rustdoc only lists the private modules they live in when it documents
+	/// private items, so `crate_data` should include them.
+	pub fn unreachable_pub(&self, crate_data: &Crate) -> Vec<(Id, String)> {
+		let mut unreachable = Vec::new();
+		let Some(root) = crate_data.index.get(&crate_data.root) else {
+			return unreachable;
+		};
+		let mut pending = vec![(root, root.name.clone().unwrap_or_default())];
+		while let Some((module, path)) = pending.pop() {
+			let ItemEnum::Module(inner) = &module.inner else {
+				continue;
+			};
+			let mut submodules = Vec::new();
+			for item in inner.items.iter().filter_map(|id| crate_data.index.get(id)) {
+				if matches!(item.inner, ItemEnum::Use(_) | ItemEnum::Impl(_)) {
+					continue;
+				}
+				let item_path = format!("{path}::{}", item.name.as_deref().unwrap_or("_"));
+				if matches!(item.visibility, Visibility::Public) && !self.is_reachable(&item.id) {
+					unreachable.push((item.id, item_path.clone()));
+				}
+				if matches!(item.inner, ItemEnum::Module(_)) {
+					submodules.push((item, item_path));
+				}
+			}
+			// Pushed in reverse so submodules are walked in declaration order
+			pending.extend(submodules.into_iter().rev());
+		}
+		unreachable
+	}
+
+	/// Follow the item `id`, found in the reachable module at `path`, when it is public.
+	fn visit(
+		&mut self,
+		crate_data: &Crate,
+		id: &Id,
+		path: &str,
+		pending: &mut VecDeque<(Id, String)>,
+	) {
+		let Some(item) = crate_data.index.get(id) else {
+			return;
+		};
+		if !matches!(item.visibility, Visibility::Public) {
+			return;
+		}
+		match &item.inner {
+			ItemEnum::Impl(_) => {}
+			ItemEnum::Use(import) => {
+				// Glob re-exports can name each other, so each is followed once
+				if !self.uses.insert(item.id) {
+					return;
+				}
+				let Some(target) = import.id.as_ref().and_then(|id| crate_data.index.get(id))
+				else {
+					return;
+				};
+				if !import.is_glob {
+					self.reach(target, format!("{path}::{}", import.name), pending);
+					return;
+				}
+				match &target.inner {
+					ItemEnum::Module(module) => {
+						for id in &module.items {
+							self.visit(crate_data, id, path, pending);
+						}
+					}
+					ItemEnum::Enum(enum_) => {
+						for variant in enum_
+							.variants
+							.iter()
+							.filter_map(|id| crate_data.index.get(id))
+						{
+							let name = variant.name.as_deref().unwrap_or("_");
+							self.reach(variant, format!("{path}::{name}"), pending);
+						}
+					}
+					_ => {}
+				}
+			}
+			_ => {
+				let name = item.name.as_deref().unwrap_or("_");
+				self.reach(item, format!("{path}::{name}"), pending);
+			}
+		}
+	}
+
+	/// Record `item` as reachable at `path` unless it already is, queueing it when it is a
+	/// module so its items are visited.
+	fn reach(&mut self, item: &Item, path: String, pending: &mut VecDeque<(Id, String)>) {
+		if self.public_paths.contains_key(&item.id) {
+			return;
+		}
+		if matches!(item.inner, ItemEnum::Module(_)) {
+			pending.push_back((item.id, path.clone()));
+		}
+		self.public_paths.insert(item.id, path);
+	}
+}
@@ -0,0 +1,175 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use rustdoc_types::{Id, Item, ItemEnum};
+
+use super::utils::ppush;
+
+/// What a render left out because the renderer does not support the items' kinds yet or
+/// rustdoc described them inconsistently.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenderReport {
+	/// Paths of the skipped items, keyed by item kind.
+	skipped: BTreeMap<SkippedKind, Vec<String>>,
+	/// Items left out because rustdoc describes them as another kind than their parent expects.
+	mismatched: Vec<MismatchedItem>,
+}
+
+/// An item left out because its parent lists it as one kind, such as a struct field, while
+/// rustdoc describes it as another, as JSON from bleeding-edge toolchains occasionally does.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MismatchedItem {
+	/// Id of the item in the crate index.
+	pub id: Id,
+	/// Name of the item, or `_` when it has none.
+	pub name: String,
+	/// Kind the parent lists the item as.
+	pub expected: &'static str,
+	/// Kind rustdoc describes the item as.
+	pub found: &'static str,
+}
+
+impl fmt::Display for MismatchedItem {
+	/// Name the item and both kinds, the one found first.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"`{}` is {}, not {}",
+			self.name,
+			with_article(self.found),
+			with_article(self.expected)
+		)
+	}
+}
+
+/// `kind` preceded by `a` or `an`.
+fn with_article(kind: &str) -> String {
+	if kind.starts_with(['a', 'e', 'i', 'o', 'u']) {
+		format!("an {kind}")
+	} else {
+		format!("a {kind}")
+	}
+}
+
+/// Item kinds the renderer cannot render yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SkippedKind {
+	/// `extern crate` declarations.
+	ExternCrate,
+	/// Types declared in `extern` blocks.
+	ExternType,
+	/// `static` items.
+	Static,
+	/// Unions.
+	Union,
+}
+
+impl SkippedKind {
+	/// The kind of `item`, if it is one the renderer skips.
+	///
+	/// Fields, variants, impls and associated items are rendered with their parents, so they
+	/// never count as skipped.
+	pub fn of(item: &Item) -> Option<Self> {
+		match item.inner {
+			ItemEnum::ExternCrate { .. } => Some(Self::ExternCrate),
+			ItemEnum::ExternType => Some(Self::ExternType),
+			ItemEnum::Static(_) => Some(Self::Static),
+			ItemEnum::Union(_) => Some(Self::Union),
+			_ => None,
+		}
+	}
+
+	/// Name of the kind, for one item or for `count` of them.
+	pub fn name(self, count: usize) -> &'static str {
+		let (singular, plural) = match self {
+			Self::ExternCrate => ("extern crate", "extern crates"),
+			Self::ExternType => ("extern type", "extern types"),
+			Self::Static => ("static", "statics"),
+			Self::Union => ("union", "unions"),
+		};
+		if count == 1 { singular } else { plural }
+	}
+}
+
+impl RenderReport {
+	/// Whether nothing was skipped.
+	pub fn is_empty(&self) -> bool {
+		self.skipped.is_empty() && self.mismatched.is_empty()
+	}
+
+	/// Skipped item kinds with the paths of their items, in a stable order.
+	pub fn skipped(&self) -> impl Iterator<Item = (SkippedKind, &[String])> {
+		self.skipped
+			.iter()
+			.map(|(kind, paths)| (*kind, paths.as_slice()))
+	}
+
+	/// Items left out because their kind did not match where they were listed, in render order.
+	pub fn mismatched(&self) -> &[MismatchedItem] {
+		&self.mismatched
+	}
+
+	/// Record `item`, found under `path_prefix`, if its kind is one the renderer skips.
+	pub(crate) fn record(&mut self, path_prefix: &str, item: &Item) {
+		if let Some(kind) = SkippedKind::of(item) {
+			let name = item.name.as_deref().unwrap_or("_");
+			self.skipped
+				.entry(kind)
+				.or_default()
+				.push(ppush(path_prefix, name));
+		}
+	}
+
+	/// Record that `item` was listed as an `expected` item but is some other kind.
+	pub(crate) fn record_mismatch(&mut self, item: &Item, expected: &'static str) {
+		self.mismatched.push(MismatchedItem {
+			id: item.id,
+			name: item.name.clone().unwrap_or_else(|| "_".to_string()),
+			expected,
+			found: kind_name(&item.inner),
+		});
+	}
+}
+
+/// Name of the kind of item `inner` describes, as used in reports.
+fn kind_name(inner: &ItemEnum) -> &'static str {
+	match inner {
+		ItemEnum::Module(_) => "module",
+		ItemEnum::ExternCrate { .. } => "extern crate",
+		ItemEnum::Use(_) => "use declaration",
+		ItemEnum::Union(_) => "union",
+		ItemEnum::Struct(_) => "struct",
+		ItemEnum::StructField(_) => "struct field",
+		ItemEnum::Enum(_) => "enum",
+		ItemEnum::Variant(_) => "variant",
+		ItemEnum::Function(_) => "function",
+		ItemEnum::Trait(_) => "trait",
+		ItemEnum::TraitAlias(_) => "trait alias",
+		ItemEnum::Impl(_) => "impl",
+		ItemEnum::TypeAlias(_) => "type alias",
+		ItemEnum::Constant { .. } => "constant",
+		ItemEnum::Static(_) => "static",
+		ItemEnum::ExternType => "extern type",
+		ItemEnum::Macro(_) => "macro",
+		ItemEnum::ProcMacro(_) => "proc macro",
+		ItemEnum::Primitive(_) => "primitive",
+		ItemEnum::AssocConst { .. } => "associated constant",
+		ItemEnum::AssocType { .. } => "associated type",
+	}
+}
+
+impl fmt::Display for RenderReport {
+	/// Summarize the report as counts per kind, such as `3 statics, 1 union`.
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		let mut counts: Vec<String> = self
+			.skipped()
+			.map(|(kind, paths)| format!("{} {}", paths.len(), kind.name(paths.len())))
+			.collect();
+		match self.mismatched.len() {
+			0 => {}
+			1 => counts.push("1 mismatched item".to_string()),
+			count => counts.push(format!("{count} mismatched items")),
+		}
+		f.write_str(&counts.join(", "))
+	}
+}
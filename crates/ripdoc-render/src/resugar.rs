@@ -0,0 +1,194 @@
+use rustdoc_types::{
+	AssocItemConstraintKind, Function, GenericArg, GenericArgs, GenericBound, GenericParamDefKind,
+	Path, Term, Type, WherePredicate,
+};
+
+/// Lifetime `#[async_trait]` bounds the returned future by.
+const ASYNC_TRAIT_LIFETIME: &str = "'async_trait";
+
+/// A method desugared by `#[async_trait]`, turned back into an `async fn`.
+pub(crate) struct AsyncTraitMethod {
+	/// The method as written: `async`, returning the future's output, without the synthetic
+	/// lifetimes.
+	pub function: Function,
+	/// Whether the boxed future was `Send`, which `#[async_trait(?Send)]` leaves out.
+	pub is_send: bool,
+}
+
+impl AsyncTraitMethod {
+	/// Comment line marking the method as rewritten from the `#[async_trait]` expansion.
+	pub fn comment(&self) -> &'static str {
+		if self.is_send {
+			"// via #[async_trait]\n"
+		} else {
+			"// via #[async_trait(?Send)]\n"
+		}
+	}
+}
+
+/// Undo the `#[async_trait]` desugaring of `function`, or return `None` when it does not match.
+///
+/// The expansion turns `async fn foo(&self) -> T` into
+/// `fn foo<'life0, 'async_trait>(&'life0 self) -> Pin<Box<dyn Future<Output = T> + Send +
+/// 'async_trait>>` with `'life0: 'async_trait` bounds. Only that exact shape matches: a
+/// hand-written boxed future without the `'async_trait` parameter is left alone.
+pub(crate) fn resugar_async_trait(function: &Function) -> Option<AsyncTraitMethod> {
+	if function.header.is_async
+		|| !function.generics.params.iter().any(|param| {
+			param.name == ASYNC_TRAIT_LIFETIME
+				&& matches!(param.kind, GenericParamDefKind::Lifetime { .. })
+		}) {
+		return None;
+	}
+	let (output, is_send) = boxed_future_output(function.sig.output.as_ref()?)?;
+
+	let mut function = function.clone();
+	function.header.is_async = true;
+	function.sig.output = output;
+	function
+		.generics
+		.params
+		.retain_mut(|param| match &mut param.kind {
+			GenericParamDefKind::Lifetime { .. } => !is_synthetic_lifetime(&param.name),
+			GenericParamDefKind::Type { bounds, .. } => {
+				bounds.retain(|bound| !outlives_async_trait(bound));
+				true
+			}
+			GenericParamDefKind::Const { .. } => true,
+		});
+	function
+		.generics
+		.where_predicates
+		.retain_mut(|predicate| match predicate {
+			WherePredicate::LifetimePredicate { lifetime, outlives } => {
+				outlives.retain(|lifetime| lifetime != ASYNC_TRAIT_LIFETIME);
+				!is_synthetic_lifetime(lifetime) && !outlives.is_empty()
+			}
+			WherePredicate::BoundPredicate { bounds, .. } => {
+				bounds.retain(|bound| !outlives_async_trait(bound));
+				!bounds.is_empty()
+			}
+			WherePredicate::EqPredicate { .. } => true,
+		});
+	for (_, ty) in &mut function.sig.inputs {
+		elide_synthetic_lifetimes(ty);
+	}
+	if let Some(output) = &mut function.sig.output {
+		elide_synthetic_lifetimes(output);
+	}
+	Some(AsyncTraitMethod { function, is_send })
+}
+
+/// The output of a `Pin<Box<dyn Future<Output = T> + 'async_trait>>` return type, `None` for
+/// `()`, and whether the future is `Send`.
+fn boxed_future_output(ty: &Type) -> Option<(Option<Type>, bool)> {
+	let boxed = single_type_arg(ty, "Pin")?;
+	let Type::DynTrait(dyn_trait) = single_type_arg(boxed, "Box")? else {
+		return None;
+	};
+	if dyn_trait.lifetime.as_deref() != Some(ASYNC_TRAIT_LIFETIME) {
+		return None;
+	}
+	let mut output = None;
+	let mut is_send = false;
+	for poly in &dyn_trait.traits {
+		match last_segment(&poly.trait_) {
+			"Future" => output = Some(future_output(&poly.trait_)?),
+			"Send" => is_send = true,
+			_ => return None,
+		}
+	}
+	let output = output?;
+	let output = match output {
+		Type::Tuple(types) if types.is_empty() => None,
+		output => Some(output),
+	};
+	Some((output, is_send))
+}
+
+/// The `T` of `Future<Output = T>`.
+fn future_output(path: &Path) -> Option<Type> {
+	let Some(GenericArgs::AngleBracketed { args, constraints }) = path.args.as_deref() else {
+		return None;
+	};
+	if !args.is_empty() {
+		return None;
+	}
+	match constraints.as_slice() {
+		[constraint] if constraint.name == "Output" => match &constraint.binding {
+			AssocItemConstraintKind::Equality(Term::Type(ty)) => Some(ty.clone()),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// The only type argument of `ty` when it is a path ending in `name`.
+fn single_type_arg<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
+	let Type::ResolvedPath(path) = ty else {
+		return None;
+	};
+	if last_segment(path) != name {
+		return None;
+	}
+	match path.args.as_deref() {
+		Some(GenericArgs::AngleBracketed { args, constraints }) if constraints.is_empty() => {
+			match args.as_slice() {
+				[GenericArg::Type(ty)] => Some(ty),
+				_ => None,
+			}
+		}
+		_ => None,
+	}
+}
+
+/// Last segment of a path such as `::core::pin::Pin`.
+fn last_segment(path: &Path) -> &str {
+	path.path.rsplit("::").next().unwrap_or(&path.path)
+}
+
+/// Whether `lifetime` is one `#[async_trait]` introduces: `'async_trait` or a `'lifeN` standing
+/// in for an elided lifetime.
+fn is_synthetic_lifetime(lifetime: &str) -> bool {
+	lifetime == ASYNC_TRAIT_LIFETIME
+		|| lifetime.strip_prefix("'life").is_some_and(|index| {
+			!index.is_empty() && index.bytes().all(|byte| byte.is_ascii_digit())
+		})
+}
+
+/// Whether `bound` is the `'async_trait` bound the expansion adds to captured types.
+fn outlives_async_trait(bound: &GenericBound) -> bool {
+	matches!(bound, GenericBound::Outlives(lifetime) if lifetime == ASYNC_TRAIT_LIFETIME)
+}
+
+/// Put back the elided lifetimes `#[async_trait]` named in `ty`.
+fn elide_synthetic_lifetimes(ty: &mut Type) {
+	match ty {
+		Type::BorrowedRef {
+			lifetime, type_, ..
+		} => {
+			if lifetime.as_deref().is_some_and(is_synthetic_lifetime) {
+				*lifetime = None;
+			}
+			elide_synthetic_lifetimes(type_);
+		}
+		Type::ResolvedPath(path) => {
+			if let Some(GenericArgs::AngleBracketed { args, .. }) = path.args.as_deref_mut() {
+				for arg in args {
+					match arg {
+						GenericArg::Lifetime(lifetime) if is_synthetic_lifetime(lifetime) => {
+							*lifetime = "'_".to_string();
+						}
+						GenericArg::Type(ty) => elide_synthetic_lifetimes(ty),
+						_ => {}
+					}
+				}
+			}
+		}
+		Type::Tuple(types) => types.iter_mut().for_each(elide_synthetic_lifetimes),
+		Type::Slice(type_) | Type::Array { type_, .. } | Type::RawPointer { type_, .. } => {
+			elide_synthetic_lifetimes(type_)
+		}
+		_ => {}
+	}
+}
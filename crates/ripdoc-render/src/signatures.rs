@@ -43,7 +43,10 @@ pub fn function_signature(item: &Item) -> String {
 	signature.push('(');
 	signature.push_str(&render_function_args(&function.sig));
 	signature.push(')');
-	signature.push_str(&render_return_type(&function.sig));
+	if function.sig.output.is_some() {
+		signature.push(' ');
+		signature.push_str(&render_return_type(&function.sig));
+	}
 	signature.push_str(&render_where_clause(&function.generics));
 	signature
 }
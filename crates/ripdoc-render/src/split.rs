@@ -0,0 +1,150 @@
+use rustdoc_types::{Item, ItemEnum};
+
+use super::items::{Breadcrumbs, render_item, render_module_open};
+use super::state::RenderState;
+use super::utils::must_get;
+use crate::syntax::{ModuleScope, render_name, render_vis};
+
+/// Default size of a nested module's unformatted body above which it gets its own file.
+pub const DEFAULT_SPLIT_MODULE_BYTES: usize = 16 * 1024;
+
+/// Rendered Rust source of a module, with the modules split out of it into their own files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModuleTree {
+	/// Module name; the crate name for the root.
+	pub name: String,
+	/// Source of the module's file: inner docs, items, and a `mod name;` declaration for each of
+	/// `children`.
+	pub source: String,
+	/// Modules declared in `source` without a body, in declaration order.
+	pub children: Vec<ModuleTree>,
+}
+
+impl ModuleTree {
+	/// Visit this module and every module below it, with their paths from the crate root
+	/// (empty for the root itself).
+	pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&[&'a str], &'a Self)) {
+		fn walk_from<'a>(
+			node: &'a ModuleTree,
+			path: &mut Vec<&'a str>,
+			visit: &mut impl FnMut(&[&'a str], &'a ModuleTree),
+		) {
+			visit(path, node);
+			for child in &node.children {
+				path.push(&child.name);
+				walk_from(child, path, visit);
+				path.pop();
+			}
+		}
+		walk_from(self, &mut Vec::new(), visit);
+	}
+}
+
+/// Modules split out while rendering a [`ModuleTree`].
+#[derive(Debug)]
+pub(crate) struct ModuleSplit {
+	/// Nested modules with larger unformatted bodies get their own file; top-level modules
+	/// always do.
+	threshold: usize,
+	/// Modules split out of each module being rendered, innermost last.
+	pending: Vec<Vec<ModuleTree>>,
+}
+
+impl ModuleSplit {
+	/// Split top-level modules, and nested ones whose bodies exceed `threshold` bytes.
+	pub(crate) fn new(threshold: usize) -> Self {
+		Self {
+			threshold,
+			pending: Vec::new(),
+		}
+	}
+}
+
+/// Render `item`, a module, either inline or as a `mod name;` declaration whose body is
+/// recorded as a child of the enclosing module.
+///
+/// A module containing split modules is split as well, so every file sits at the path its
+/// module declares.
+pub(crate) fn render_split_module(
+	state: &mut RenderState,
+	path_prefix: &str,
+	item: &Item,
+) -> String {
+	let (path_prefix, open) = render_module_open(state, path_prefix, item);
+	let (body, children) = render_module_body(state, &path_prefix, item, &open);
+
+	let split = state
+		.split
+		.as_mut()
+		.expect("modules are split only while a split is in progress");
+	let top_level = path_prefix.matches("::").count() <= 1;
+	if top_level || !children.is_empty() || body.len() > split.threshold {
+		if let Some(siblings) = split.pending.last_mut() {
+			siblings.push(ModuleTree {
+				name: render_name(item),
+				source: body,
+				children,
+			});
+		}
+		return format!("{}mod {};\n\n", render_vis(item), render_name(item));
+	}
+
+	let mut output = open
+		.split_inclusive('\n')
+		.next()
+		.unwrap_or_default()
+		.to_string();
+	output.push_str(&body);
+	output.push_str("}\n\n");
+	output
+}
+
+/// Render the crate root as a [`ModuleTree`] with `split` in effect, or `None` when it is not
+/// visible.
+pub(crate) fn render_root_tree(state: &mut RenderState) -> Option<ModuleTree> {
+	use super::items::is_visible;
+
+	let root = must_get(state.crate_data, &state.crate_data.root);
+	if !state.selection_context_contains(&root.id) || !is_visible(state, root) {
+		return None;
+	}
+	let (path_prefix, open) = render_module_open(state, "", root);
+	let (source, children) = render_module_body(state, &path_prefix, root, &open);
+	Some(ModuleTree {
+		name: render_name(root),
+		source,
+		children,
+	})
+}
+
+/// Render the body of the module `item` below its opening line `open`, returning it with the
+/// modules split out of it.
+fn render_module_body(
+	state: &mut RenderState,
+	path_prefix: &str,
+	item: &Item,
+	open: &str,
+) -> (String, Vec<ModuleTree>) {
+	let _module = ModuleScope::enter(path_prefix);
+	if let Some(split) = state.split.as_mut() {
+		split.pending.push(Vec::new());
+	}
+
+	let mut body = open
+		.split_once('\n')
+		.map(|(_, rest)| rest.to_string())
+		.unwrap_or_default();
+	let module = extract_item!(item, ItemEnum::Module);
+	let mut breadcrumbs = Breadcrumbs::new(state, path_prefix);
+	for item_id in &module.items {
+		let item = must_get(state.crate_data, item_id);
+		body.push_str(&breadcrumbs.item(render_item(state, path_prefix, item, false)));
+	}
+
+	let children = state
+		.split
+		.as_mut()
+		.and_then(|split| split.pending.pop())
+		.unwrap_or_default();
+	(body, children)
+}
@@ -1,8 +1,40 @@
-use rustdoc_types::{Crate, Id, Item};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::BTreeSet;
 
+use rustdoc_types::{Crate, Id, Item, ItemEnum};
+
+use super::budget::Elision;
 use super::core::{RenderSelection, Renderer};
+use super::json::SkeletonNode;
+use super::report::RenderReport;
+use super::split::{ModuleSplit, ModuleTree};
 use super::utils::{FilterMatch, must_get, ppush};
 use crate::error::{Result, RipdocError};
+use crate::syntax::{
+	ModuleScope, doc_comment, is_build_script_item, is_cfg_test_module, is_doc_hidden,
+	render_attrs, resolve_doc_links,
+};
+
+/// A piece of unformatted output produced while rendering the crate root.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderChunk<'a> {
+	/// Opening line of the root module, followed by its inner docs.
+	Open(&'a str),
+	/// A fully rendered top-level item.
+	Item(&'a str),
+	/// Closing brace of the root module.
+	Close(&'a str),
+}
+
+impl<'a> RenderChunk<'a> {
+	/// Raw source text carried by this chunk.
+	pub fn text(&self) -> &'a str {
+		match self {
+			Self::Open(text) | Self::Item(text) | Self::Close(text) => text,
+		}
+	}
+}
 
 /// Mutable rendering context shared across helper functions.
 pub struct RenderState<'a, 'b> {
@@ -12,6 +44,21 @@ pub struct RenderState<'a, 'b> {
 	pub crate_data: &'b Crate,
 	/// Tracks whether any item matched the configured filter.
 	pub filter_matched: bool,
+	/// Filter path in effect, initially the configured one.
+	filter: String,
+	/// Whether filter components are compared ignoring case, with `-` read as `_`.
+	folding: bool,
+	/// Item paths that matched the filter while folding.
+	folded_matches: BTreeSet<String>,
+	/// Paths below the crate root of private traits to declare as stubs.
+	pub(crate) sealed_stubs: BTreeSet<Vec<String>>,
+	/// Details left out to fit a size budget.
+	pub(crate) elision: Elision,
+	/// Visible items left out because their kind is not supported or did not match where they
+	/// were listed; behind a `RefCell` so helpers holding a shared state can record mismatches.
+	pub(crate) report: RefCell<RenderReport>,
+	/// Modules split into their own files, while rendering a [`ModuleTree`].
+	pub(crate) split: Option<ModuleSplit>,
 }
 
 impl<'a, 'b> RenderState<'a, 'b> {
@@ -21,26 +68,145 @@ impl<'a, 'b> RenderState<'a, 'b> {
 			config,
 			crate_data,
 			filter_matched: false,
+			filter: config.filter.clone(),
+			folding: false,
+			folded_matches: BTreeSet::new(),
+			sealed_stubs: BTreeSet::new(),
+			elision: Elision::default(),
+			report: RefCell::default(),
+			split: None,
+		}
+	}
+
+	/// Retry a filter that matched nothing, comparing path components case-insensitively and
+	/// reading `-` as `_`.
+	///
+	/// Returns a fresh state filtering on the single path that matched, after logging which
+	/// path was used. Fails when nothing matches, or when several paths do.
+	pub fn fold_filter(&self) -> Result<Self> {
+		let mut probe = Self::new(self.config, self.crate_data);
+		probe.filter = self.filter.clone();
+		probe.folding = true;
+		probe.elision = self.elision;
+		match probe.render_chunks(|_| Ok(())) {
+			Ok(()) | Err(RipdocError::FilterNotMatched(_)) => {}
+			Err(err) => return Err(err),
+		}
+
+		let mut matches = probe.folded_matches.into_iter();
+		match (matches.next(), matches.next()) {
+			(None, _) => Err(RipdocError::FilterNotMatched(self.filter.clone())),
+			(Some(path), None) => {
+				log::warn!("matched `{path}` for filter `{}`", self.filter);
+				let mut state = Self::new(self.config, self.crate_data);
+				state.filter = path;
+				state.elision = self.elision;
+				Ok(state)
+			}
+			(Some(first), Some(second)) => Err(RipdocError::FilterAmbiguous {
+				filter: self.filter.clone(),
+				candidates: [first, second].into_iter().chain(matches).collect(),
+			}),
 		}
 	}
 
 	/// Render the crate, applying filters and formatting output.
 	pub fn render(&mut self) -> Result<String> {
-		use super::items::render_item;
+		let mut output = String::new();
+		self.render_chunks(|chunk| {
+			output.push_str(chunk.text());
+			Ok(())
+		})?;
+		Ok(output)
+	}
+
+	/// Render the crate one top-level item at a time, handing each chunk to `emit`.
+	///
+	/// Concatenating the emitted chunks yields exactly the output of [`RenderState::render`].
+	pub fn render_chunks<F>(&mut self, mut emit: F) -> Result<()>
+	where
+		F: FnMut(RenderChunk<'_>) -> Result<()>,
+	{
+		use super::impls::render_sealed_stubs;
+		use super::items::{Breadcrumbs, is_visible, render_item, render_module_open};
 
 		// The root item is always a module
-		let output = render_item(
-			self,
-			"",
-			must_get(self.crate_data, &self.crate_data.root),
-			false,
-		);
+		let root = must_get(self.crate_data, &self.crate_data.root);
+		let opened = self.selection_context_contains(&root.id) && is_visible(self, root);
+		if opened {
+			let (path_prefix, header) = render_module_open(self, "", root);
+			emit(RenderChunk::Open(&header))?;
+			let _module = ModuleScope::enter(&path_prefix);
 
-		if !self.config.filter.is_empty() && !self.filter_matched {
-			return Err(RipdocError::FilterNotMatched(self.config.filter.clone()));
+			let module = extract_item!(root, ItemEnum::Module);
+			let mut breadcrumbs = Breadcrumbs::new(self, &path_prefix);
+			for item_id in &module.items {
+				let item = must_get(self.crate_data, item_id);
+				let output = breadcrumbs.item(render_item(self, &path_prefix, item, false));
+				if !output.is_empty() {
+					emit(RenderChunk::Item(&output))?;
+				}
+			}
 		}
 
-		Ok(output)
+		if !self.filter.is_empty() && !self.filter_matched {
+			return Err(RipdocError::FilterNotMatched(self.filter.clone()));
+		}
+
+		if opened {
+			if !self.sealed_stubs.is_empty() {
+				emit(RenderChunk::Item(&render_sealed_stubs(&self.sealed_stubs)))?;
+			}
+			emit(RenderChunk::Close("}\n\n"))?;
+		}
+		Ok(())
+	}
+
+	/// Render the crate as Rust source split into module files, applying the same filters as
+	/// [`Self::render`].
+	///
+	/// Top-level modules get their own file, as do nested modules whose unformatted bodies are
+	/// over `threshold` bytes. Returns `None` when the crate root is not rendered.
+	pub fn render_tree(&mut self, threshold: usize) -> Result<Option<ModuleTree>> {
+		use super::impls::render_sealed_stubs;
+		use super::split::render_root_tree;
+
+		self.split = Some(ModuleSplit::new(threshold));
+		let tree = render_root_tree(self);
+		self.split = None;
+
+		if !self.filter.is_empty() && !self.filter_matched {
+			return Err(RipdocError::FilterNotMatched(self.filter.clone()));
+		}
+
+		Ok(tree.map(|mut tree| {
+			if !self.sealed_stubs.is_empty() {
+				tree.source
+					.push_str(&render_sealed_stubs(&self.sealed_stubs));
+			}
+			tree
+		}))
+	}
+
+	/// Build the structured skeleton of the crate, applying the same filters as [`Self::render`].
+	pub fn render_skeleton(&mut self) -> Result<Option<SkeletonNode>> {
+		use super::json::item_nodes;
+
+		// The root item is always a module
+		let root = must_get(self.crate_data, &self.crate_data.root);
+		let root_node = item_nodes(self, "", root, false).pop();
+
+		if !self.filter.is_empty() && !self.filter_matched {
+			return Err(RipdocError::FilterNotMatched(self.filter.clone()));
+		}
+
+		Ok(root_node)
+	}
+
+	/// Record that `item` was listed as an `expected` item but is another kind, so it is left out
+	/// instead of aborting the render.
+	pub(crate) fn record_mismatch(&self, item: &Item, expected: &'static str) {
+		self.report.borrow_mut().record_mismatch(item, expected);
 	}
 
 	/// Return the active render selection, if any.
@@ -80,24 +246,109 @@ impl<'a, 'b> RenderState<'a, 'b> {
 		self.selection_expands(parent_id) || self.selection_context_contains(child_id)
 	}
 
-	/// Determine whether an item is filtered out by the configured path filter.
+	/// Determine whether blanket and auto impls should be stubbed in for a selected type.
+	pub fn renders_blanket_impls_for(&self, type_id: &Id) -> bool {
+		self.config.render_blanket_impls && self.selection_matches(type_id)
+	}
+
+	/// Render the whitelisted attributes for an item, if attribute rendering is enabled.
+	pub fn attrs(&self, item: &Item) -> String {
+		if self.config.render_attrs {
+			render_attrs(item)
+		} else {
+			String::new()
+		}
+	}
+
+	/// Doc comment of an item, with its intra-doc links rewritten when the renderer resolves
+	/// them.
+	pub fn doc_text<'i>(&self, item: &'i Item) -> Option<Cow<'i, str>> {
+		let docs = item.docs.as_deref()?;
+		// Destinations of `crate::` links are dropped even when rustdoc did not resolve them
+		let has_links = !item.links.is_empty() || docs.contains("crate::");
+		if has_links && self.config.resolves_doc_links() {
+			Some(Cow::Owned(resolve_doc_links(
+				docs,
+				&item.links,
+				&self.crate_data.paths,
+			)))
+		} else {
+			Some(Cow::Borrowed(docs))
+		}
+	}
+
+	/// Format an item's doc comment as triple-slash lines, resolving links as
+	/// [`Self::doc_text`] does.
+	pub fn docs(&self, item: &Item) -> String {
+		self.doc_text(item)
+			.map(|docs| doc_comment(&docs))
+			.unwrap_or_default()
+	}
+
+	/// Determine whether an item is suppressed by `#[doc(hidden)]`.
+	pub fn is_hidden(&self, item: &Item) -> bool {
+		!self.config.render_doc_hidden && is_doc_hidden(item)
+	}
+
+	/// Determine whether an item is test or build-script scaffolding that should be skipped.
+	pub fn is_test_only(&self, item: &Item) -> bool {
+		!self.config.render_tests && (is_cfg_test_module(item) || is_build_script_item(item))
+	}
+
+	/// Determine whether an item is filtered out by the configured path filter, or skipped as
+	/// test or build-script scaffolding.
 	pub fn should_filter(&mut self, path_prefix: &str, item: &Item) -> bool {
+		self.is_test_only(item) || self.should_filter_any(&[path_prefix], item)
+	}
+
+	/// Determine whether an item inside an impl is filtered out, accepting filters that name
+	/// the implemented trait between the type and the item (`Type::Trait::method`).
+	pub fn should_filter_impl_item(
+		&mut self,
+		path_prefix: &str,
+		trait_name: Option<&str>,
+		item: &Item,
+	) -> bool {
+		match trait_name {
+			Some(trait_name) => {
+				let qualified = ppush(path_prefix, trait_name);
+				self.should_filter_any(&[path_prefix, qualified.as_str()], item)
+			}
+			None => self.should_filter(path_prefix, item),
+		}
+	}
+
+	/// Determine whether an item is filtered out, keeping it if the filter matches it under any
+	/// of `path_prefixes`.
+	fn should_filter_any(&mut self, path_prefixes: &[&str], item: &Item) -> bool {
 		// We never filter the root module - filters operate under the root.
 		if item.id == self.crate_data.root {
 			return false;
 		}
 
-		if self.config.filter.is_empty() {
+		if self.filter.is_empty() {
 			return false;
 		}
-		match self.filter_match(path_prefix, item) {
-			FilterMatch::Hit => {
-				self.filter_matched = true;
-				false
+
+		let mut partial = false;
+		for path_prefix in path_prefixes {
+			match self.filter_match(path_prefix, item) {
+				FilterMatch::Hit => {
+					self.filter_matched = true;
+					if self.folding
+						&& let Some(name) = &item.name
+					{
+						let item_path = ppush(path_prefix, name);
+						let below_root = item_path.split_once("::").map_or("", |(_, rest)| rest);
+						self.folded_matches.insert(below_root.to_string());
+					}
+					return false;
+				}
+				FilterMatch::Prefix | FilterMatch::Suffix => partial = true,
+				FilterMatch::Miss => {}
 			}
-			FilterMatch::Prefix | FilterMatch::Suffix => false,
-			FilterMatch::Miss => true,
 		}
+		!partial
 	}
 
 	/// Evaluate how the current filter matches a candidate path.
@@ -108,8 +359,15 @@ impl<'a, 'b> RenderState<'a, 'b> {
 			return FilterMatch::Prefix;
 		};
 
-		let filter_components: Vec<&str> = self.config.filter.split("::").collect();
-		let item_components: Vec<&str> = item_path.split("::").skip(1).collect();
+		let normalize = |component: &str| {
+			if self.folding {
+				component.to_lowercase().replace('-', "_")
+			} else {
+				component.to_string()
+			}
+		};
+		let filter_components: Vec<String> = self.filter.split("::").map(normalize).collect();
+		let item_components: Vec<String> = item_path.split("::").skip(1).map(normalize).collect();
 
 		if filter_components == item_components {
 			FilterMatch::Hit
@@ -123,8 +381,17 @@ impl<'a, 'b> RenderState<'a, 'b> {
 	}
 
 	/// Determine whether a module should emit a `//!` doc comment header.
+	///
+	/// With a selection active, only matched or expanded modules keep their docs; ancestors kept
+	/// for context render as bare wrappers.
 	pub fn should_module_doc(&self, path_prefix: &str, item: &Item) -> bool {
-		if self.config.filter.is_empty() {
+		if self.selection().is_some()
+			&& !self.selection_matches(&item.id)
+			&& !self.selection_expands(&item.id)
+		{
+			return false;
+		}
+		if self.filter.is_empty() {
 			return true;
 		}
 		matches!(
@@ -1,4 +1,7 @@
-use rustdoc_types::{GenericBound, PolyTrait, TraitBoundModifier};
+use rustdoc_types::{
+	AssocItemConstraintKind, GenericArg, GenericArgs, GenericBound, Path, PolyTrait, Term,
+	TraitBoundModifier, Type,
+};
 
 use super::path::render_path;
 
@@ -62,13 +65,117 @@ fn render_path_as_poly_trait(poly_trait: &PolyTrait) -> String {
 }
 
 /// Render a comma-separated list of generic bounds.
+///
+/// Bounds using return type notation, such as `Trait<method(..): Send>`, are unstable syntax
+/// the formatters cannot parse, so they follow the other bounds in a comment instead.
 pub fn render_generic_bounds(bounds: &[GenericBound]) -> String {
-	let parts: Vec<String> = bounds
+	let (unstable, stable): (Vec<_>, Vec<_>) = bounds
 		.iter()
+		.partition(|bound| bound_uses_return_type_notation(bound));
+	let parts: Vec<String> = stable
+		.into_iter()
 		.map(render_generic_bound)
 		.filter(|s| !s.trim().is_empty())
 		.collect();
-	parts.join(" + ")
+	let mut rendered = parts.join(" + ");
+	if !unstable.is_empty() {
+		let unstable: Vec<String> = unstable.into_iter().map(render_generic_bound).collect();
+		if !rendered.is_empty() {
+			rendered.push(' ');
+		}
+		rendered.push_str(&return_type_notation_comment(&unstable.join(" + ")));
+	}
+	rendered
+}
+
+/// Comment standing in for `code` that uses return type notation.
+pub fn return_type_notation_comment(code: &str) -> String {
+	format!("/* return type notation: {code} */")
+}
+
+/// Whether `bound` uses return type notation anywhere, as in `Trait<method(..): Send>`.
+pub fn bound_uses_return_type_notation(bound: &GenericBound) -> bool {
+	match bound {
+		GenericBound::TraitBound { trait_, .. } => path_uses_return_type_notation(trait_),
+		GenericBound::Outlives(_) | GenericBound::Use(_) => false,
+	}
+}
+
+/// Whether `ty` uses return type notation anywhere, as in `T::method(..)`.
+pub fn type_uses_return_type_notation(ty: &Type) -> bool {
+	match ty {
+		Type::ResolvedPath(path) => path_uses_return_type_notation(path),
+		Type::DynTrait(dyn_trait) => dyn_trait
+			.traits
+			.iter()
+			.any(|poly| path_uses_return_type_notation(&poly.trait_)),
+		Type::ImplTrait(bounds) => bounds.iter().any(bound_uses_return_type_notation),
+		Type::FunctionPointer(f) => {
+			f.sig
+				.inputs
+				.iter()
+				.any(|(_, ty)| type_uses_return_type_notation(ty))
+				|| f.sig
+					.output
+					.as_ref()
+					.is_some_and(type_uses_return_type_notation)
+		}
+		Type::Tuple(types) => types.iter().any(type_uses_return_type_notation),
+		Type::Slice(type_)
+		| Type::Array { type_, .. }
+		| Type::RawPointer { type_, .. }
+		| Type::BorrowedRef { type_, .. } => type_uses_return_type_notation(type_),
+		Type::QualifiedPath {
+			args,
+			self_type,
+			trait_,
+			..
+		} => {
+			args.as_deref().is_some_and(args_use_return_type_notation)
+				|| type_uses_return_type_notation(self_type)
+				|| trait_.as_ref().is_some_and(path_uses_return_type_notation)
+		}
+		// Pattern types render as a comment already.
+		Type::Generic(_) | Type::Primitive(_) | Type::Infer | Type::Pat { .. } => false,
+	}
+}
+
+/// Whether the generic arguments of `path` use return type notation.
+fn path_uses_return_type_notation(path: &Path) -> bool {
+	path.args
+		.as_deref()
+		.is_some_and(args_use_return_type_notation)
+}
+
+/// Whether `args` are, or contain, return type notation.
+fn args_use_return_type_notation(args: &GenericArgs) -> bool {
+	match args {
+		GenericArgs::ReturnTypeNotation => true,
+		GenericArgs::AngleBracketed { args, constraints } => {
+			args.iter().any(|arg| match arg {
+				GenericArg::Type(ty) => type_uses_return_type_notation(ty),
+				GenericArg::Lifetime(_) | GenericArg::Const(_) | GenericArg::Infer => false,
+			}) || constraints.iter().any(|constraint| {
+				constraint
+					.args
+					.as_deref()
+					.is_some_and(args_use_return_type_notation)
+					|| match &constraint.binding {
+						AssocItemConstraintKind::Equality(Term::Type(ty)) => {
+							type_uses_return_type_notation(ty)
+						}
+						AssocItemConstraintKind::Equality(Term::Constant(_)) => false,
+						AssocItemConstraintKind::Constraint(bounds) => {
+							bounds.iter().any(bound_uses_return_type_notation)
+						}
+					}
+			})
+		}
+		GenericArgs::Parenthesized { inputs, output } => {
+			inputs.iter().any(type_uses_return_type_notation)
+				|| output.as_ref().is_some_and(type_uses_return_type_notation)
+		}
+	}
 }
 
 #[cfg(test)]
@@ -0,0 +1,141 @@
+use rustdoc_types::Type;
+
+/// Stands in for a const generic argument or array length that cannot be written as Rust, such
+/// as an expression still containing macro variables. `_` parses wherever a const may be
+/// inferred.
+pub const UNREPRESENTABLE_CONST: &str = "_ /* unrepresentable expression */";
+
+/// Comment following the placeholder value of a const parameter default that cannot be
+/// written as Rust.
+const UNREPRESENTABLE_DEFAULT: &str = "/* unrepresentable expression */";
+
+/// Render a const expression in generic argument position, such as `N` in `Foo<N>`.
+///
+/// Anything other than a literal, a path or a block is wrapped in braces, as Rust requires
+/// there; unrepresentable expressions become [`UNREPRESENTABLE_CONST`].
+pub fn render_const_arg(expr: &str) -> String {
+	sanitize_const_expr(expr, true).unwrap_or_else(|| UNREPRESENTABLE_CONST.to_string())
+}
+
+/// Render the default of a const generic parameter of type `ty`.
+///
+/// Defaults are braced like [`render_const_arg`]. An unrepresentable default becomes a
+/// literal of the parameter's type when it is a primitive, and a block that never returns
+/// otherwise, so the parameter list still parses.
+pub fn render_const_default(expr: &str, ty: &Type) -> String {
+	if let Some(expr) = sanitize_const_expr(expr, true) {
+		return expr;
+	}
+	let placeholder = match ty {
+		Type::Primitive(name) if name == "bool" => "false",
+		Type::Primitive(name) if name == "char" => "'\\0'",
+		Type::Primitive(name) if is_integer(name) => "0",
+		_ => "{ unimplemented!() }",
+	};
+	format!("{placeholder} {UNREPRESENTABLE_DEFAULT}")
+}
+
+/// Stands in for the value of an associated constant that is not shown. A panic has every
+/// type, and associated constants are only evaluated where they are used, so the impl or trait
+/// still type-checks.
+pub const OMITTED_CONST_VALUE: &str = "unimplemented!() /* value omitted */";
+
+/// Follows a free constant rendered without its value, as `const X: u32; // value omitted`.
+///
+/// A free constant is evaluated even when unused, so no placeholder value would type-check;
+/// the declaration is left without one, which rustfmt still accepts.
+pub const OMITTED_CONST_COMMENT: &str = "// value omitted";
+
+/// Render the value of a constant, or of an associated constant, from its expression.
+///
+/// Only literals, negated literals and plain paths such as `u32::MAX` are shown. Blocks, calls,
+/// macro expansions and anything else that may be long or name private items give `None`;
+/// associated constants then take [`OMITTED_CONST_VALUE`] and free constants
+/// [`OMITTED_CONST_COMMENT`].
+pub fn render_const_value(expr: &str) -> Option<String> {
+	let expr = expr.replace("$crate::", "");
+	let expr = expr.trim();
+	if expr.contains('$') {
+		return None;
+	}
+	match syn::parse_str::<syn::Expr>(expr) {
+		Ok(parsed) if is_simple_const_value(&parsed) => Some(expr.to_string()),
+		_ => None,
+	}
+}
+
+/// Render the value of an associated constant, or [`OMITTED_CONST_VALUE`] when it is left out.
+pub fn render_assoc_const_value(expr: &str) -> String {
+	render_const_value(expr).unwrap_or_else(|| OMITTED_CONST_VALUE.to_string())
+}
+
+/// Render a free constant declaration, without a value when [`render_const_value`] leaves it
+/// out.
+pub fn render_const_declaration(vis: &str, name: &str, ty: &str, expr: &str) -> String {
+	match render_const_value(expr) {
+		Some(value) => format!("{vis}const {name}: {ty} = {value};\n\n"),
+		None => format!("{vis}const {name}: {ty}; {OMITTED_CONST_COMMENT}\n\n"),
+	}
+}
+
+/// Whether `expr` is a literal, a negated literal or a plain path.
+fn is_simple_const_value(expr: &syn::Expr) -> bool {
+	match expr {
+		syn::Expr::Lit(_) => true,
+		syn::Expr::Path(path) => path.qself.is_none(),
+		syn::Expr::Unary(unary) => {
+			matches!(unary.op, syn::UnOp::Neg(_)) && matches!(*unary.expr, syn::Expr::Lit(_))
+		}
+		_ => false,
+	}
+}
+
+/// Render the length of an array type, which may be any expression and is never braced.
+pub fn render_array_len(expr: &str) -> String {
+	sanitize_const_expr(expr, false).unwrap_or_else(|| UNREPRESENTABLE_CONST.to_string())
+}
+
+/// Clean up a const expression from rustdoc, or return `None` when it cannot be written as
+/// Rust.
+///
+/// `$crate::` prefixes left by macro expansion are dropped; any other `$` marks an unexpanded
+/// macro variable. With `brace`, expressions that are not a literal, a path or a block are
+/// wrapped in braces.
+fn sanitize_const_expr(expr: &str, brace: bool) -> Option<String> {
+	let expr = expr.replace("$crate::", "");
+	let expr = expr.trim();
+	if expr.is_empty() || expr.contains('$') {
+		return None;
+	}
+	let parsed = syn::parse_str::<syn::Expr>(expr).ok()?;
+	if !brace || is_bare_const_arg(&parsed) {
+		Some(expr.to_string())
+	} else {
+		Some(format!("{{ {expr} }}"))
+	}
+}
+
+/// Whether `expr` may appear as a const generic argument without braces.
+fn is_bare_const_arg(expr: &syn::Expr) -> bool {
+	match expr {
+		syn::Expr::Lit(_) | syn::Expr::Block(_) | syn::Expr::Infer(_) => true,
+		syn::Expr::Path(path) => path.qself.is_none(),
+		syn::Expr::Unary(unary) => {
+			matches!(unary.op, syn::UnOp::Neg(_)) && matches!(*unary.expr, syn::Expr::Lit(_))
+		}
+		_ => false,
+	}
+}
+
+/// Whether `name` is one of Rust's integer types.
+fn is_integer(name: &str) -> bool {
+	matches!(
+		name,
+		"u8" | "u16"
+			| "u32" | "u64"
+			| "u128" | "usize"
+			| "i8" | "i16"
+			| "i32" | "i64"
+			| "i128" | "isize"
+	)
+}
@@ -8,30 +8,7 @@ pub fn render_function_args(decl: &FunctionSignature) -> String {
 		.iter()
 		.map(|(name, ty)| {
 			if name == "self" {
-				match ty {
-					Type::BorrowedRef { is_mutable, .. } => {
-						if *is_mutable {
-							"&mut self".to_string()
-						} else {
-							"&self".to_string()
-						}
-					}
-					Type::ResolvedPath(path) => {
-						if path.path == "Self" && path.args.is_none() {
-							"self".to_string()
-						} else {
-							format!("self: {}", render_type(ty))
-						}
-					}
-					Type::Generic(name) => {
-						if name == "Self" {
-							"self".to_string()
-						} else {
-							format!("self: {}", render_type(ty))
-						}
-					}
-					_ => format!("self: {}", render_type(ty)),
-				}
+				render_receiver(ty)
 			} else {
 				format!("{name}: {}", render_type(ty))
 			}
@@ -40,6 +17,39 @@ pub fn render_function_args(decl: &FunctionSignature) -> String {
 		.join(", ")
 }
 
+/// Render a `self` parameter of type `ty`.
+///
+/// `Self` by value and references to it take the shorthand forms `self`, `&'a self` and
+/// `&'a mut self`, keeping any lifetime; every other receiver, such as `Pin<&mut Self>` or
+/// `Box<Self>`, is spelled out as `self: <type>`.
+fn render_receiver(ty: &Type) -> String {
+	match ty {
+		Type::BorrowedRef {
+			lifetime,
+			is_mutable,
+			type_,
+		} if is_self_type(type_) => {
+			let lifetime = lifetime
+				.as_ref()
+				.map(|lifetime| format!("{lifetime} "))
+				.unwrap_or_default();
+			let mutability = if *is_mutable { "mut " } else { "" };
+			format!("&{lifetime}{mutability}self")
+		}
+		_ if is_self_type(ty) => "self".to_string(),
+		_ => format!("self: {}", render_type(ty)),
+	}
+}
+
+/// Whether `ty` is the bare `Self` type.
+fn is_self_type(ty: &Type) -> bool {
+	match ty {
+		Type::Generic(name) => name == "Self",
+		Type::ResolvedPath(path) => path.path == "Self" && path.args.is_none(),
+		_ => false,
+	}
+}
+
 /// Render a function's return type, including the `->` separator when needed.
 pub fn render_return_type(decl: &FunctionSignature) -> String {
 	match &decl.output {
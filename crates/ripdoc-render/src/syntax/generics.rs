@@ -1,6 +1,12 @@
-use rustdoc_types::{GenericArgs, GenericParamDef, GenericParamDefKind, Generics, WherePredicate};
+use rustdoc_types::{
+	GenericArgs, GenericBound, GenericParamDef, GenericParamDefKind, Generics, Term, WherePredicate,
+};
 
-use super::bounds::render_generic_bounds;
+use super::bounds::{
+	bound_uses_return_type_notation, render_generic_bound, render_generic_bounds,
+	return_type_notation_comment, type_uses_return_type_notation,
+};
+use super::consts::{render_const_arg, render_const_default};
 use super::types::render_type;
 
 /// Render the generic parameter list for an item.
@@ -57,7 +63,7 @@ pub fn render_generic_param_def(param: &GenericParamDef) -> Option<String> {
 		GenericParamDefKind::Const { type_, default } => {
 			let default = default
 				.as_ref()
-				.map(|expr| format!(" = {expr}"))
+				.map(|expr| format!(" = {}", render_const_default(expr, type_)))
 				.unwrap_or_default();
 			Some(format!(
 				"const {}: {}{default}",
@@ -69,6 +75,9 @@ pub fn render_generic_param_def(param: &GenericParamDef) -> Option<String> {
 }
 
 /// Render concrete generic arguments used in a path.
+///
+/// Return type notation renders as the `(..)` following the method name in
+/// `Trait<method(..): Send>`.
 pub fn render_generic_args(args: &GenericArgs) -> String {
 	match args {
 		GenericArgs::AngleBracketed { args, constraints } => {
@@ -107,7 +116,7 @@ pub fn render_generic_args(args: &GenericArgs) -> String {
 				.unwrap_or_default();
 			format!("({inputs}){output}")
 		}
-		GenericArgs::ReturnTypeNotation => String::new(),
+		GenericArgs::ReturnTypeNotation => "(..)".to_string(),
 	}
 }
 
@@ -118,35 +127,69 @@ fn render_generic_arg(arg: &rustdoc_types::GenericArg) -> String {
 	match arg {
 		GenericArg::Lifetime(lt) => lt.clone(),
 		GenericArg::Type(ty) => render_type(ty),
-		GenericArg::Const(c) => {
-			// Check if the expression contains macro variables ($ signs)
-			// These come from unexpanded macros and would create invalid syntax
-			if c.expr.contains('$') {
-				"/* macro expression */".to_string()
-			} else {
-				c.expr.clone()
-			}
-		}
+		GenericArg::Const(c) => render_const_arg(&c.expr),
 		GenericArg::Infer => "_".to_string(),
 	}
 }
 
 /// Render a `where` clause for a generics block.
+///
+/// Predicates using return type notation cannot be formatted, so they are left out of the
+/// clause and follow it in comments; rendering a weaker clause as code would misstate the
+/// item's requirements.
 pub fn render_where_clause(generics: &Generics) -> String {
-	let predicates: Vec<String> = generics
+	let (unstable, stable): (Vec<_>, Vec<_>) = generics
 		.where_predicates
 		.iter()
+		.partition(|pred| predicate_uses_return_type_notation(pred));
+	let predicates: Vec<String> = stable
+		.into_iter()
 		.filter_map(render_where_predicate)
 		.collect();
-	if predicates.is_empty() {
+	let mut clause = if predicates.is_empty() {
 		String::new()
 	} else {
 		format!(" where {}", predicates.join(", "))
+	};
+	// The comment already marks these bounds, so they are not commented out a second time.
+	let verbatim = |bounds: &[GenericBound]| {
+		let bounds: Vec<String> = bounds.iter().map(render_generic_bound).collect();
+		bounds.join(" + ")
+	};
+	for pred in unstable {
+		if let Some(pred) = render_where_predicate_with(pred, verbatim) {
+			clause.push(' ');
+			clause.push_str(&return_type_notation_comment(&pred));
+		}
+	}
+	clause
+}
+
+/// Whether `pred` uses return type notation in its bounded type or any of its bounds.
+fn predicate_uses_return_type_notation(pred: &WherePredicate) -> bool {
+	match pred {
+		WherePredicate::BoundPredicate { type_, bounds, .. } => {
+			type_uses_return_type_notation(type_)
+				|| bounds.iter().any(bound_uses_return_type_notation)
+		}
+		WherePredicate::LifetimePredicate { .. } => false,
+		WherePredicate::EqPredicate { lhs, rhs } => {
+			type_uses_return_type_notation(lhs)
+				|| matches!(rhs, Term::Type(ty) if type_uses_return_type_notation(ty))
+		}
 	}
 }
 
 /// Render a single predicate within a `where` clause.
 pub fn render_where_predicate(pred: &WherePredicate) -> Option<String> {
+	render_where_predicate_with(pred, render_generic_bounds)
+}
+
+/// Render a single predicate, with its bounds rendered by `render_bounds`.
+fn render_where_predicate_with(
+	pred: &WherePredicate,
+	render_bounds: impl Fn(&[GenericBound]) -> String,
+) -> Option<String> {
 	use rustdoc_types::Type;
 
 	match pred {
@@ -178,7 +221,7 @@ pub fn render_where_predicate(pred: &WherePredicate) -> Option<String> {
 				String::new()
 			};
 
-			let bounds_str = render_generic_bounds(bounds);
+			let bounds_str = render_bounds(bounds);
 			if bounds_str.is_empty() {
 				None
 			} else {
@@ -213,15 +256,218 @@ fn render_type_constraint(constraint: &rustdoc_types::AssocItemConstraint) -> St
 			}
 		}
 	};
-	format!("{}{binding_kind}", constraint.name)
+	let args = constraint
+		.args
+		.as_deref()
+		.map(render_generic_args)
+		.unwrap_or_default();
+	format!("{}{args}{binding_kind}", constraint.name)
 }
 
 /// Render a `Term` appearing in associated type constraints.
 fn render_term(term: &rustdoc_types::Term) -> String {
-	use rustdoc_types::Term;
-
 	match term {
 		Term::Type(ty) => render_type(ty),
-		Term::Constant(c) => c.expr.clone(),
+		Term::Constant(c) => render_const_arg(&c.expr),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rustdoc_types::{
+		AssocItemConstraint, AssocItemConstraintKind, Constant, GenericArg, Id, Path,
+		TraitBoundModifier, Type,
+	};
+
+	use super::*;
+	use crate::syntax::UNREPRESENTABLE_CONST;
+
+	fn const_param(type_: &str, default: &str) -> GenericParamDef {
+		GenericParamDef {
+			name: "N".to_string(),
+			kind: GenericParamDefKind::Const {
+				type_: Type::Primitive(type_.to_string()),
+				default: Some(default.to_string()),
+			},
+		}
+	}
+
+	fn const_arg(expr: &str) -> GenericArg {
+		GenericArg::Const(Constant {
+			expr: expr.to_string(),
+			value: None,
+			is_literal: false,
+		})
+	}
+
+	fn trait_bound(path: &str, args: Option<GenericArgs>) -> GenericBound {
+		GenericBound::TraitBound {
+			trait_: Path {
+				id: Id(0),
+				path: path.to_string(),
+				args: args.map(Box::new),
+			},
+			generic_params: Vec::new(),
+			modifier: TraitBoundModifier::None,
+		}
+	}
+
+	/// `<method(..): Send>`, as in `T: Trait<method(..): Send>`.
+	fn send_method_args() -> GenericArgs {
+		GenericArgs::AngleBracketed {
+			args: Vec::new(),
+			constraints: vec![AssocItemConstraint {
+				name: "method".to_string(),
+				args: Some(Box::new(GenericArgs::ReturnTypeNotation)),
+				binding: AssocItemConstraintKind::Constraint(vec![trait_bound("Send", None)]),
+			}],
+		}
+	}
+
+	fn bound_predicate(type_: Type, bounds: Vec<GenericBound>) -> WherePredicate {
+		WherePredicate::BoundPredicate {
+			type_,
+			bounds,
+			generic_params: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn test_render_generic_args_renders_return_type_notation() {
+		assert_eq!(
+			render_generic_args(&send_method_args()),
+			"<method(..): Send>"
+		);
+		assert_eq!(
+			render_generic_args(&GenericArgs::ReturnTypeNotation),
+			"(..)"
+		);
+	}
+
+	#[test]
+	fn test_where_clause_comments_out_return_type_notation_predicates() {
+		let generic = || Type::Generic("T".to_string());
+		let generics = Generics {
+			params: Vec::new(),
+			where_predicates: vec![
+				bound_predicate(generic(), vec![trait_bound("Clone", None)]),
+				bound_predicate(
+					generic(),
+					vec![trait_bound("Trait", Some(send_method_args()))],
+				),
+				bound_predicate(
+					Type::QualifiedPath {
+						name: "method".to_string(),
+						args: Some(Box::new(GenericArgs::ReturnTypeNotation)),
+						self_type: Box::new(generic()),
+						trait_: None,
+					},
+					vec![trait_bound("Send", None)],
+				),
+			],
+		};
+		assert_eq!(
+			render_where_clause(&generics),
+			" where T: Clone /* return type notation: T: Trait<method(..): Send> */ \
+			 /* return type notation: T::method(..): Send */"
+		);
+
+		let only_unstable = Generics {
+			params: Vec::new(),
+			where_predicates: generics.where_predicates[1..2].to_vec(),
+		};
+		assert_eq!(
+			render_where_clause(&only_unstable),
+			" /* return type notation: T: Trait<method(..): Send> */"
+		);
+	}
+
+	#[test]
+	fn test_return_type_notation_bounds_follow_the_others_in_a_comment() {
+		let bounds = [
+			trait_bound("Trait", Some(send_method_args())),
+			trait_bound("Clone", None),
+		];
+		assert_eq!(
+			render_generic_bounds(&bounds),
+			"Clone /* return type notation: Trait<method(..): Send> */"
+		);
+	}
+
+	#[test]
+	fn test_const_param_defaults_keep_blocks_and_strip_crate_prefixes() {
+		let block = const_param("usize", "{ mem::size_of::<T>() }");
+		assert_eq!(
+			render_generic_param_def(&block).as_deref(),
+			Some("const N: usize = { mem::size_of::<T>() }")
+		);
+
+		let prefixed = const_param("usize", "{ $crate::mem::size_of::<T>() }");
+		assert_eq!(
+			render_generic_param_def(&prefixed).as_deref(),
+			Some("const N: usize = { mem::size_of::<T>() }")
+		);
+
+		let literal = const_param("usize", "4");
+		assert_eq!(
+			render_generic_param_def(&literal).as_deref(),
+			Some("const N: usize = 4")
+		);
+	}
+
+	#[test]
+	fn test_const_param_defaults_brace_compound_expressions() {
+		let param = const_param("usize", "$crate::LEN * 2");
+		assert_eq!(
+			render_generic_param_def(&param).as_deref(),
+			Some("const N: usize = { LEN * 2 }")
+		);
+	}
+
+	#[test]
+	fn test_unrepresentable_const_defaults_use_a_placeholder_of_the_param_type() {
+		let rendered = |ty: &str| render_generic_param_def(&const_param(ty, "{ $len * 2 }"));
+		assert_eq!(
+			rendered("usize").as_deref(),
+			Some("const N: usize = 0 /* unrepresentable expression */")
+		);
+		assert_eq!(
+			rendered("bool").as_deref(),
+			Some("const N: bool = false /* unrepresentable expression */")
+		);
+		assert_eq!(
+			rendered("char").as_deref(),
+			Some("const N: char = '\\0' /* unrepresentable expression */")
+		);
+	}
+
+	#[test]
+	fn test_render_generic_arg_sanitizes_const_expressions() {
+		assert_eq!(render_generic_arg(&const_arg("N")), "N");
+		assert_eq!(render_generic_arg(&const_arg("-1")), "-1");
+		assert_eq!(render_generic_arg(&const_arg("$crate::LEN")), "LEN");
+		assert_eq!(render_generic_arg(&const_arg("N + 1")), "{ N + 1 }");
+		assert_eq!(render_generic_arg(&const_arg("{ N + 1 }")), "{ N + 1 }");
+		assert_eq!(render_generic_arg(&const_arg("$n")), UNREPRESENTABLE_CONST);
+		assert_eq!(render_generic_arg(&const_arg("N +")), UNREPRESENTABLE_CONST);
+	}
+
+	#[test]
+	fn test_render_generic_arg_sanitizes_array_lengths() {
+		let array = |len: &str| {
+			GenericArg::Type(Type::Array {
+				type_: Box::new(Type::Primitive("u8".to_string())),
+				len: len.to_string(),
+			})
+		};
+		assert_eq!(render_generic_arg(&array("N * 2")), "[u8; N * 2]");
+		assert_eq!(
+			render_generic_arg(&array("$crate::mem::size_of::<T>()")),
+			"[u8; mem::size_of::<T>()]"
+		);
+		assert_eq!(
+			render_generic_arg(&array("$len")),
+			format!("[u8; {UNREPRESENTABLE_CONST}]")
+		);
 	}
 }
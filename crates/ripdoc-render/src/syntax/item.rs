@@ -1,16 +1,176 @@
-use rustdoc_types::{Item, ItemEnum, Visibility};
+use rustdoc_types::{Attribute, AttributeRepr, Item, ItemEnum, ReprKind, Visibility};
 
 /// Format documentation comments as triple-slash lines.
 pub fn docs(item: &Item) -> String {
+	item.docs.as_deref().map(doc_comment).unwrap_or_default()
+}
+
+/// Format `docs` as triple-slash lines.
+pub fn doc_comment(docs: &str) -> String {
 	let mut output = String::new();
-	if let Some(docs) = &item.docs {
-		for line in docs.lines() {
-			output.push_str(&format!("/// {line}\n"));
+	for line in docs.lines() {
+		output.push_str(&format!("/// {line}\n"));
+	}
+	output
+}
+
+/// Check whether an item carries `#[doc(hidden)]`, alone or among other `doc` entries.
+pub fn is_doc_hidden(item: &Item) -> bool {
+	item.attrs.iter().any(|attr| match attr {
+		Attribute::Other(text) => {
+			let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+			compact
+				.strip_prefix("#[doc(")
+				.and_then(|rest| rest.strip_suffix(")]"))
+				.is_some_and(|entries| split_entries(entries).any(|entry| entry == "hidden"))
+		}
+		_ => false,
+	})
+}
+
+/// Split the contents of an attribute's list at its top-level commas, leaving commas inside
+/// nested lists and string literals alone.
+fn split_entries(list: &str) -> impl Iterator<Item = &str> {
+	let mut depth = 0usize;
+	let mut in_string = false;
+	let mut escaped = false;
+	let mut start = 0;
+	let mut entries = Vec::new();
+	for (index, c) in list.char_indices() {
+		match c {
+			_ if escaped => escaped = false,
+			'\\' if in_string => escaped = true,
+			'"' => in_string = !in_string,
+			'(' | '[' if !in_string => depth += 1,
+			')' | ']' if !in_string => depth = depth.saturating_sub(1),
+			',' if !in_string && depth == 0 => {
+				entries.push(&list[start..index]);
+				start = index + 1;
+			}
+			_ => {}
+		}
+	}
+	entries.push(&list[start..]);
+	entries.into_iter()
+}
+
+/// Check whether an item is a `mod tests` compiled only under `#[cfg(test)]`.
+///
+/// A module named `tests` without the attribute is ordinary public API and does not count.
+pub fn is_cfg_test_module(item: &Item) -> bool {
+	matches!(item.inner, ItemEnum::Module(_))
+		&& item.name.as_deref() == Some("tests")
+		&& item.attrs.iter().any(|attr| match attr {
+			Attribute::Other(text) => {
+				let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+				compact == "#[cfg(test)]"
+			}
+			_ => false,
+		})
+}
+
+/// Check whether an item was defined in a package's `build.rs` rather than its sources.
+pub fn is_build_script_item(item: &Item) -> bool {
+	item.span.as_ref().is_some_and(|span| {
+		span.filename.file_name() == Some("build.rs".as_ref())
+			&& !span
+				.filename
+				.components()
+				.any(|part| part.as_os_str() == "src")
+	})
+}
+
+/// Check whether an item carries `#[automatically_derived]`, as impls generated by derive macros do.
+pub fn is_automatically_derived(item: &Item) -> bool {
+	item.attrs.iter().any(|attr| match attr {
+		Attribute::AutomaticallyDerived => true,
+		Attribute::Other(text) => text.contains("automatically_derived"),
+		_ => false,
+	})
+}
+
+/// Render the attributes that affect layout, linking, or usage, one per line.
+///
+/// Only `#[repr]`, `#[no_mangle]`, `#[export_name]`, `#[link_name]`, and `#[must_use]` are
+/// emitted; everything else is noise in a skeleton.
+pub fn render_attrs(item: &Item) -> String {
+	let mut output = String::new();
+	for attr in &item.attrs {
+		let rendered = match attr {
+			Attribute::Repr(repr) => render_repr(repr),
+			Attribute::NoMangle => Some("#[no_mangle]".to_string()),
+			Attribute::ExportName(name) => Some(format!("#[export_name = {name:?}]")),
+			Attribute::MustUse { reason: None } => Some("#[must_use]".to_string()),
+			Attribute::MustUse {
+				reason: Some(reason),
+			} => Some(format!("#[must_use = {reason:?}]")),
+			Attribute::Other(text) => render_link_name(text),
+			_ => None,
+		};
+		if let Some(rendered) = rendered {
+			output.push_str(&rendered);
+			output.push('\n');
 		}
 	}
 	output
 }
 
+/// Render any attribute as source text, for matching rather than display.
+pub fn attr_text(attr: &Attribute) -> String {
+	match attr {
+		Attribute::NonExhaustive => "#[non_exhaustive]".to_string(),
+		Attribute::MustUse { reason: None } => "#[must_use]".to_string(),
+		Attribute::MustUse {
+			reason: Some(reason),
+		} => format!("#[must_use = {reason:?}]"),
+		Attribute::MacroExport => "#[macro_export]".to_string(),
+		Attribute::ExportName(name) => format!("#[export_name = {name:?}]"),
+		Attribute::LinkSection(section) => format!("#[link_section = {section:?}]"),
+		Attribute::AutomaticallyDerived => "#[automatically_derived]".to_string(),
+		Attribute::Repr(repr) => render_repr(repr).unwrap_or_else(|| "#[repr(Rust)]".to_string()),
+		Attribute::NoMangle => "#[no_mangle]".to_string(),
+		Attribute::TargetFeature { enable } => {
+			format!("#[target_feature(enable = \"{}\")]", enable.join(","))
+		}
+		Attribute::Other(text) => text.clone(),
+	}
+}
+
+/// Render a `#[repr(...)]` attribute, or `None` for the default representation.
+fn render_repr(repr: &AttributeRepr) -> Option<String> {
+	let mut parts = Vec::new();
+	match repr.kind {
+		ReprKind::Rust => {}
+		ReprKind::C => parts.push("C".to_string()),
+		ReprKind::Transparent => parts.push("transparent".to_string()),
+		ReprKind::Simd => parts.push("simd".to_string()),
+	}
+	if let Some(int) = &repr.int {
+		parts.push(int.clone());
+	}
+	match repr.packed {
+		Some(1) => parts.push("packed".to_string()),
+		Some(packed) => parts.push(format!("packed({packed})")),
+		None => {}
+	}
+	if let Some(align) = repr.align {
+		parts.push(format!("align({align})"));
+	}
+	(!parts.is_empty()).then(|| format!("#[repr({})]", parts.join(", ")))
+}
+
+/// Render a `#[link_name]` attribute from its source form or rustdoc's internal
+/// `#[attr = LinkName {name: "..."}]` form.
+fn render_link_name(text: &str) -> Option<String> {
+	if text.starts_with("#[link_name") {
+		return Some(text.to_string());
+	}
+	let rest = text.strip_prefix("#[attr = LinkName")?;
+	let start = rest.find('"')? + 1;
+	let len = rest[start..].find('"')?;
+	Some(format!("#[link_name = \"{}\"]", &rest[start..start + len]))
+}
+
 /// Render the visibility modifier for an item if it is public.
 pub fn render_vis(item: &Item) -> String {
 	match &item.visibility {
@@ -19,6 +179,19 @@ pub fn render_vis(item: &Item) -> String {
 	}
 }
 
+/// Render an item's visibility as declared, keeping restrictions such as `pub(crate) `.
+pub fn render_declared_vis(item: &Item) -> String {
+	match &item.visibility {
+		Visibility::Public => "pub ".to_string(),
+		Visibility::Crate => "pub(crate) ".to_string(),
+		Visibility::Restricted { path, .. } => match path.as_str() {
+			"self" | "super" | "crate" => format!("pub({path}) "),
+			path => format!("pub(in {path}) "),
+		},
+		Visibility::Default => String::new(),
+	}
+}
+
 /// Render an item name, escaping Rust keywords when necessary.
 pub fn render_name(item: &Item) -> String {
 	use super::keywords::is_reserved_word;
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use rustdoc_types::{Id, ItemSummary};
+
+/// Rewrite the intra-doc links in `docs` to plain text.
+///
+/// `links` maps link text and destinations to the items they resolve to, as in
+/// [`rustdoc_types::Item::links`]; `paths` supplies canonical paths for those items.
+///
+/// - ``[`Crate`]`` becomes `` `rustdoc_types::Crate` ``, the linked item's canonical path as
+///   inline code, or the link text without its backticks when the item has no known path.
+/// - `[Renderer](crate::render::Renderer)` becomes `Renderer`: the link text is kept and the
+///   destination dropped, for destinations that are intra-doc links or `crate::` paths.
+/// - Reference definitions such as `` [`Crate`]: crate::Crate `` are dropped along with the
+///   links using them.
+///
+/// Links to URLs, text that only looks like a link, and code blocks are left alone.
+pub fn resolve_doc_links(
+	docs: &str,
+	links: &HashMap<String, Id>,
+	paths: &HashMap<Id, ItemSummary>,
+) -> String {
+	let resolver = LinkResolver {
+		links,
+		paths,
+		references: reference_definitions(docs),
+	};
+	let mut output = String::with_capacity(docs.len());
+	let mut fence: Option<&str> = None;
+	for line in docs.lines() {
+		let trimmed = line.trim_start();
+		if let Some(marker) = fence {
+			if trimmed.starts_with(marker) {
+				fence = None;
+			}
+			push_line(&mut output, line);
+			continue;
+		}
+		if let Some(marker) = ["```", "~~~"].into_iter().find(|m| trimmed.starts_with(m)) {
+			fence = Some(marker);
+			push_line(&mut output, line);
+			continue;
+		}
+		if let Some((_, destination)) = reference_definition(line)
+			&& resolver.is_intra_doc(destination)
+		{
+			continue;
+		}
+		let rewritten = resolver.rewrite_line(line);
+		push_line(&mut output, &rewritten);
+	}
+	output
+}
+
+/// Resolves the links found in one item's docs.
+struct LinkResolver<'a> {
+	/// Link text and destinations mapped to the items they resolve to.
+	links: &'a HashMap<String, Id>,
+	/// Canonical paths of items by id.
+	paths: &'a HashMap<Id, ItemSummary>,
+	/// Destinations of the reference definitions in the docs, by label.
+	references: HashMap<String, String>,
+}
+
+impl LinkResolver<'_> {
+	/// Whether `destination` names an item rather than a URL.
+	fn is_intra_doc(&self, destination: &str) -> bool {
+		self.links.contains_key(destination) || destination.starts_with("crate::")
+	}
+
+	/// Rewrite the links in a line of prose.
+	fn rewrite_line(&self, line: &str) -> String {
+		let bytes = line.as_bytes();
+		let mut output = String::with_capacity(line.len());
+		let mut copied = 0;
+		let mut index = 0;
+		while index < bytes.len() {
+			match bytes[index] {
+				b'`' => index = code_span_end(line, index).unwrap_or(index + 1),
+				b'\\' => index += 2,
+				b'[' if index == 0 || bytes[index - 1] != b'!' => {
+					let Some((end, replacement)) = self.rewrite_link(line, index) else {
+						index += 1;
+						continue;
+					};
+					output.push_str(&line[copied..index]);
+					output.push_str(&replacement);
+					copied = end;
+					index = end;
+				}
+				_ => index += 1,
+			}
+		}
+		output.push_str(&line[copied..]);
+		output
+	}
+
+	/// Text replacing the link opening at `start`, and the end of the link, when it is an
+	/// intra-doc link.
+	fn rewrite_link(&self, line: &str, start: usize) -> Option<(usize, String)> {
+		let text_end = closing_bracket(line, start, b'[', b']')?;
+		let text = &line[start + 1..text_end];
+		let rest = &line[text_end + 1..];
+		if rest.starts_with('(') {
+			let end = closing_bracket(line, text_end + 1, b'(', b')')?;
+			let destination = line[text_end + 2..end].trim();
+			return self
+				.is_intra_doc(destination)
+				.then(|| (end + 1, text.to_string()));
+		}
+		if rest.starts_with('[') {
+			let end = closing_bracket(line, text_end + 1, b'[', b']')?;
+			let label = match &line[text_end + 2..end] {
+				"" => text,
+				label => label,
+			};
+			let destination = self.references.get(label)?;
+			return self
+				.is_intra_doc(destination)
+				.then(|| (end + 1, text.to_string()));
+		}
+		let id = match self.references.get(text) {
+			Some(destination) if self.is_intra_doc(destination) => self.links.get(destination),
+			Some(_) => return None,
+			None => Some(
+				self.links
+					.get(text)
+					.or_else(|| self.links.get(text.trim_matches('`')))?,
+			),
+		};
+		let path = id
+			.and_then(|id| self.paths.get(id))
+			.map(|summary| summary.path.join("::"))
+			.unwrap_or_else(|| plain_link_text(text).to_string());
+		Some((text_end + 1, format!("`{path}`")))
+	}
+}
+
+/// Append `line` and a newline to `output`.
+fn push_line(output: &mut String, line: &str) {
+	output.push_str(line);
+	output.push('\n');
+}
+
+/// Destinations of the reference definitions in `docs`, by label.
+fn reference_definitions(docs: &str) -> HashMap<String, String> {
+	docs.lines()
+		.filter_map(reference_definition)
+		.map(|(label, destination)| (label.to_string(), destination.to_string()))
+		.collect()
+}
+
+/// Label and destination of a reference definition such as `[label]: destination`.
+fn reference_definition(line: &str) -> Option<(&str, &str)> {
+	let line = line.trim_start();
+	let end = closing_bracket(line, 0, b'[', b']')?;
+	let destination = line[end + 1..].strip_prefix(':')?.trim();
+	let destination = destination.split_whitespace().next()?;
+	Some((&line[1..end], destination))
+}
+
+/// Index of the bracket closing the one `open` at `start`, skipping code spans and nested pairs.
+fn closing_bracket(line: &str, start: usize, open: u8, close: u8) -> Option<usize> {
+	let bytes = line.as_bytes();
+	if bytes.get(start) != Some(&open) {
+		return None;
+	}
+	let mut depth = 0;
+	let mut index = start;
+	while index < bytes.len() {
+		match bytes[index] {
+			b'`' => {
+				index = code_span_end(line, index).unwrap_or(index + 1);
+				continue;
+			}
+			b'\\' => index += 1,
+			byte if byte == open => depth += 1,
+			byte if byte == close => {
+				depth -= 1;
+				if depth == 0 {
+					return Some(index);
+				}
+			}
+			_ => {}
+		}
+		index += 1;
+	}
+	None
+}
+
+/// End of the code span whose opening backticks start at `start`, or `None` when they are
+/// never closed and so stand for themselves.
+fn code_span_end(line: &str, start: usize) -> Option<usize> {
+	let ticks = line[start..]
+		.bytes()
+		.take_while(|&byte| byte == b'`')
+		.count();
+	let body = start + ticks;
+	let mut search = body;
+	while let Some(offset) = line[search..].find('`') {
+		let run_start = search + offset;
+		let run = line[run_start..]
+			.bytes()
+			.take_while(|&byte| byte == b'`')
+			.count();
+		if run == ticks {
+			return Some(run_start + run);
+		}
+		search = run_start + run;
+	}
+	None
+}
+
+/// Link text without its backticks or a leading disambiguator such as `struct@`.
+fn plain_link_text(text: &str) -> &str {
+	let text = text.trim_matches('`');
+	match text.split_once('@') {
+		Some((kind, rest)) if kind.chars().all(|c| c.is_ascii_lowercase()) => rest,
+		_ => text,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use rustdoc_types::ItemKind;
+
+	use super::*;
+
+	fn summary(path: &[&str]) -> ItemSummary {
+		ItemSummary {
+			crate_id: 0,
+			path: path.iter().map(|segment| segment.to_string()).collect(),
+			kind: ItemKind::Struct,
+		}
+	}
+
+	fn resolve(docs: &str) -> String {
+		let links = HashMap::from([
+			("`Crate`".to_string(), Id(1)),
+			("crate::render::Renderer".to_string(), Id(2)),
+			("`Widget::new`".to_string(), Id(3)),
+		]);
+		let paths = HashMap::from([
+			(Id(1), summary(&["rustdoc_types", "Crate"])),
+			(Id(2), summary(&["demo", "render", "Renderer"])),
+		]);
+		resolve_doc_links(docs, &links, &paths)
+	}
+
+	#[test]
+	fn shortcut_links_become_canonical_paths() {
+		assert_eq!(
+			resolve("Reads a [`Crate`] with [`Widget::new`]."),
+			"Reads a `rustdoc_types::Crate` with `Widget::new`.\n"
+		);
+	}
+
+	#[test]
+	fn crate_destinations_are_dropped() {
+		assert_eq!(
+			resolve("See [Renderer](crate::render::Renderer) and [`Other`](crate::Other)."),
+			"See Renderer and `Other`.\n"
+		);
+	}
+
+	#[test]
+	fn urls_unresolved_text_and_code_are_kept() {
+		let docs = "\
+Index with `a[0]` or [unknown], see [site](https://example.com).
+```
+let crate_ = [`Crate`];
+```";
+		assert_eq!(resolve(docs), format!("{docs}\n"));
+	}
+
+	#[test]
+	fn reference_links_and_their_definitions_go_together() {
+		assert_eq!(
+			resolve("Uses [the renderer][r] and [`Crate`].\n\n[r]: crate::render::Renderer"),
+			"Uses the renderer and `rustdoc_types::Crate`.\n\n"
+		);
+	}
+}
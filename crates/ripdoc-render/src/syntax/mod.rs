@@ -1,17 +1,31 @@
 //! Utilities for rendering items and types in skeleton code.
 
 pub use self::bounds::{render_generic_bound, render_generic_bounds, render_poly_trait};
+pub use self::consts::{
+	OMITTED_CONST_COMMENT, OMITTED_CONST_VALUE, UNREPRESENTABLE_CONST, render_array_len,
+	render_assoc_const_value, render_const_arg, render_const_declaration, render_const_default,
+	render_const_value,
+};
 pub use self::function::{render_function_args, render_return_type};
 pub use self::generics::{
 	render_generic_args, render_generic_param_def, render_generics, render_where_clause,
 };
-pub use self::item::{docs, render_associated_type, render_name, render_vis};
+pub use self::item::{
+	attr_text, doc_comment, docs, is_automatically_derived, is_build_script_item,
+	is_cfg_test_module, is_doc_hidden, render_associated_type, render_attrs, render_declared_vis,
+	render_name, render_vis,
+};
 pub use self::keywords::is_reserved_word;
-pub use self::path::render_path;
-pub use self::types::{render_type, render_type_inner};
+pub use self::links::resolve_doc_links;
+pub use self::path::{ModuleScope, SelfTypeScope, render_path, render_type_path, shorten_path};
+pub use self::types::{
+	DEFAULT_MAX_TYPE_DEPTH, ELIDED_TYPE, TypeDepthLimit, render_type, render_type_inner,
+};
 
 /// Generic parameter and bounds rendering utilities.
 pub mod bounds;
+/// Const expression sanitizing for generic arguments, defaults, array lengths, and values.
+pub mod consts;
 /// Function signature rendering utilities.
 pub mod function;
 /// Generic argument and where clause rendering.
@@ -20,6 +34,8 @@ pub mod generics;
 pub mod item;
 /// Keyword detection and escaping utilities.
 pub mod keywords;
+/// Intra-doc link rewriting for doc comments.
+pub mod links;
 /// Path and trait rendering utilities.
 pub mod path;
 /// Type rendering including primitives, compound types, and qualified paths.
@@ -1,4 +1,70 @@
-use rustdoc_types::Path;
+use std::cell::RefCell;
+
+use rustdoc_types::{GenericArgs, Path, Type};
+
+thread_local! {
+	/// Prefixes naming the module whose items are being rendered on this thread, once from
+	/// `crate` and once from the crate's name, each ending in `::`.
+	static MODULE_PREFIXES: RefCell<Option<[String; 2]>> = const { RefCell::new(None) };
+	/// Implementing type of the impl block whose items are being rendered on this thread.
+	static SELF_TYPE: RefCell<Option<Path>> = const { RefCell::new(None) };
+}
+
+/// Shortens paths rendered on this thread that point into a module until dropped, then
+/// restores the previous module.
+pub struct ModuleScope {
+	/// Prefixes in effect before this scope.
+	previous: Option<[String; 2]>,
+}
+
+impl ModuleScope {
+	/// Render `crate::widgets::Button` as `Button` while the returned guard is alive, given the
+	/// `module_path` `my_crate::widgets`; the first segment names the crate.
+	pub fn enter(module_path: &str) -> Self {
+		let relative = match module_path.split_once("::") {
+			Some((_, rest)) => format!("crate::{rest}::"),
+			None => "crate::".to_string(),
+		};
+		let prefixes = [relative, format!("{module_path}::")];
+		Self {
+			previous: MODULE_PREFIXES.replace(Some(prefixes)),
+		}
+	}
+}
+
+impl Drop for ModuleScope {
+	fn drop(&mut self) {
+		MODULE_PREFIXES.set(self.previous.take());
+	}
+}
+
+/// Renders the implementing type of an impl block as `Self` until dropped, then restores the
+/// previous one.
+pub struct SelfTypeScope {
+	/// Implementing type in effect before this scope.
+	previous: Option<Path>,
+}
+
+impl SelfTypeScope {
+	/// Render `for_` as `Self` while the returned guard is alive.
+	///
+	/// Only named types are replaced: a blanket impl's type parameter is already short.
+	pub fn enter(for_: &Type) -> Self {
+		let self_type = match for_ {
+			Type::ResolvedPath(path) => Some(path.clone()),
+			_ => None,
+		};
+		Self {
+			previous: SELF_TYPE.replace(self_type),
+		}
+	}
+}
+
+impl Drop for SelfTypeScope {
+	fn drop(&mut self) {
+		SELF_TYPE.set(self.previous.take());
+	}
+}
 
 /// Render a type or module path into Rust source form.
 pub fn render_path(path: &Path) -> String {
@@ -7,5 +73,126 @@ pub fn render_path(path: &Path) -> String {
 		.as_ref()
 		.map(|args| super::generics::render_generic_args(args))
 		.unwrap_or_default();
-	format!("{}{}", path.path.replace("$crate::", ""), args)
+	format!("{}{}", shorten_path(&path.path), args)
+}
+
+/// Render a path in type position, as `Self` when it names the implementing type set with
+/// [`SelfTypeScope`], generic arguments included.
+pub fn render_type_path(path: &Path) -> String {
+	let is_self = SELF_TYPE.with_borrow(|self_type| {
+		self_type.as_ref().is_some_and(|self_type| {
+			self_type.id == path.id && explicit_args(self_type) == explicit_args(path)
+		})
+	});
+	if is_self {
+		"Self".to_string()
+	} else {
+		render_path(path)
+	}
+}
+
+/// `path` without `$crate::` markers, and as a bare name when it names an item of the module
+/// set with [`ModuleScope`].
+///
+/// Paths into submodules keep their prefix: `inner::Item` could be ambiguous with a crate
+/// named `inner`.
+pub fn shorten_path(path: &str) -> String {
+	let path = path.replace("$crate::", "");
+	let short = MODULE_PREFIXES.with_borrow(|prefixes| {
+		prefixes
+			.iter()
+			.flatten()
+			.find_map(|prefix| path.strip_prefix(prefix.as_str()))
+			.filter(|name| !name.is_empty() && !name.contains("::"))
+			.map(ToOwned::to_owned)
+	});
+	short.unwrap_or(path)
+}
+
+/// Generic arguments of `path`, treating an empty `<>` list like none at all.
+fn explicit_args(path: &Path) -> Option<&GenericArgs> {
+	path.args.as_deref().filter(|args| {
+		!matches!(args, GenericArgs::AngleBracketed { args, constraints }
+			if args.is_empty() && constraints.is_empty())
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use rustdoc_types::{GenericArg, Id};
+
+	use super::*;
+
+	fn path(id: u32, name: &str, args: Option<GenericArgs>) -> Path {
+		Path {
+			path: name.to_string(),
+			id: Id(id),
+			args: args.map(Box::new),
+		}
+	}
+
+	fn type_args(types: Vec<Type>) -> GenericArgs {
+		GenericArgs::AngleBracketed {
+			args: types.into_iter().map(GenericArg::Type).collect(),
+			constraints: Vec::new(),
+		}
+	}
+
+	#[test]
+	fn paths_to_items_of_the_current_module_render_bare() {
+		let _scope = ModuleScope::enter("my_crate::widgets");
+		assert_eq!(shorten_path("crate::widgets::Button"), "Button");
+		assert_eq!(shorten_path("my_crate::widgets::Button"), "Button");
+		assert_eq!(
+			shorten_path("crate::widgets::inner::Knob"),
+			"crate::widgets::inner::Knob"
+		);
+		assert_eq!(shorten_path("crate::other::Button"), "crate::other::Button");
+		assert_eq!(
+			shorten_path("alloc::string::String"),
+			"alloc::string::String"
+		);
+	}
+
+	#[test]
+	fn module_scopes_nest_and_restore() {
+		{
+			let _root = ModuleScope::enter("my_crate");
+			assert_eq!(shorten_path("crate::Widget"), "Widget");
+			{
+				let _inner = ModuleScope::enter("my_crate::inner");
+				assert_eq!(shorten_path("crate::Widget"), "crate::Widget");
+			}
+			assert_eq!(shorten_path("crate::Widget"), "Widget");
+		}
+		assert_eq!(shorten_path("crate::Widget"), "crate::Widget");
+	}
+
+	#[test]
+	fn the_implementing_type_renders_as_self() {
+		let generic = type_args(vec![Type::Generic("T".into())]);
+		let for_ = Type::ResolvedPath(path(1, "Wrapper", Some(generic.clone())));
+		let _scope = SelfTypeScope::enter(&for_);
+
+		assert_eq!(render_type_path(&path(1, "Wrapper", Some(generic))), "Self");
+		assert_eq!(
+			render_type_path(&path(
+				1,
+				"Wrapper",
+				Some(type_args(vec![Type::Primitive("u8".into())]))
+			)),
+			"Wrapper<u8>"
+		);
+		assert_eq!(render_type_path(&path(2, "Other", None)), "Other");
+	}
+
+	#[test]
+	fn empty_argument_lists_match_missing_ones() {
+		let for_ = Type::ResolvedPath(path(1, "Unit", None));
+		let _scope = SelfTypeScope::enter(&for_);
+		assert_eq!(
+			render_type_path(&path(1, "crate::Unit", Some(type_args(Vec::new())))),
+			"Self"
+		);
+	}
 }
@@ -1,19 +1,79 @@
+use std::cell::Cell;
+
 use rustdoc_types::Type;
 
 use super::bounds::render_generic_bounds;
-use super::path::render_path;
+use super::consts::render_array_len;
+use super::path::{render_path, render_type_path};
+
+/// Default nesting depth after which the rest of a type is elided.
+pub const DEFAULT_MAX_TYPE_DEPTH: usize = 32;
+
+/// Placeholder rendered in place of a type nested deeper than the limit. `_` parses wherever a
+/// type can appear, so the skeleton stays valid Rust.
+pub const ELIDED_TYPE: &str = "_ /* … */";
+
+thread_local! {
+	/// Nesting depth of the type being rendered on this thread.
+	static TYPE_DEPTH: Cell<usize> = const { Cell::new(0) };
+	/// Depth past which types are elided on this thread.
+	static MAX_TYPE_DEPTH: Cell<usize> = const { Cell::new(DEFAULT_MAX_TYPE_DEPTH) };
+}
+
+/// Limits how deeply types rendered on this thread nest until dropped, then restores the
+/// previous limit.
+pub struct TypeDepthLimit {
+	/// Limit in effect before this one.
+	previous: usize,
+}
+
+impl TypeDepthLimit {
+	/// Elide types nested deeper than `max_depth` while the returned guard is alive.
+	pub fn set(max_depth: usize) -> Self {
+		Self {
+			previous: MAX_TYPE_DEPTH.replace(max_depth),
+		}
+	}
+}
+
+impl Drop for TypeDepthLimit {
+	fn drop(&mut self) {
+		MAX_TYPE_DEPTH.set(self.previous);
+	}
+}
+
+/// Restores the type nesting depth when a level of rendering finishes, even on unwind.
+struct DepthGuard(usize);
+
+impl Drop for DepthGuard {
+	fn drop(&mut self) {
+		TYPE_DEPTH.set(self.0);
+	}
+}
 
-/// Render a type, tracking whether it is nested for parentheses handling.
+/// Render a type, tracking whether it sits behind a reference or pointer.
+///
+/// Only there do `impl` and `dyn` types with several bounds need parentheses; in argument,
+/// tuple, slice, and array position rustfmt strips them, so none are added.
+///
+/// Generic arguments, bounds and function pointers render their types through here too, so
+/// every level of nesting counts towards the limit set with [`TypeDepthLimit`]; past it the
+/// type renders as [`ELIDED_TYPE`]. This keeps pathological types, like the deeply nested
+/// generics of type-level arithmetic, from overflowing the stack.
 pub fn render_type_inner(ty: &Type, nested: bool) -> String {
+	let depth = TYPE_DEPTH.get();
+	if depth >= MAX_TYPE_DEPTH.get() {
+		return ELIDED_TYPE.to_string();
+	}
+	TYPE_DEPTH.set(depth + 1);
+	let _guard = DepthGuard(depth);
+	render_type_level(ty, nested)
+}
+
+/// Render one level of a type, recursing through [`render_type_inner`].
+fn render_type_level(ty: &Type, nested: bool) -> String {
 	match ty {
-		Type::ResolvedPath(path) => {
-			let args = path
-				.args
-				.as_ref()
-				.map(|args| super::generics::render_generic_args(args))
-				.unwrap_or_default();
-			format!("{}{}", path.path.replace("$crate::", ""), args)
-		}
+		Type::ResolvedPath(path) => render_type_path(path),
 		Type::DynTrait(dyn_trait) => {
 			let traits = dyn_trait
 				.traits
@@ -44,19 +104,23 @@ pub fn render_type_inner(ty: &Type, nested: bool) -> String {
 		Type::Tuple(types) => {
 			let inner = types
 				.iter()
-				.map(|ty| render_type_inner(ty, true))
+				.map(|ty| render_type_inner(ty, false))
 				.collect::<Vec<_>>()
 				.join(", ");
 			format!("({inner})")
 		}
-		Type::Slice(ty) => format!("[{}]", render_type_inner(ty, true)),
+		Type::Slice(ty) => format!("[{}]", render_type_inner(ty, false)),
 		Type::Array { type_, len } => {
-			format!("[{}; {len}]", render_type_inner(type_, true))
+			format!(
+				"[{}; {}]",
+				render_type_inner(type_, false),
+				render_array_len(len)
+			)
 		}
 		Type::ImplTrait(bounds) => {
 			let bounds_str = render_generic_bounds(bounds);
-			// If we're nested (e.g., inside a reference or function parameter) and have multiple bounds
-			// (indicated by presence of '+' in the bounds string), we need parentheses to avoid ambiguity
+			// Behind a reference or pointer, multiple bounds need parentheses to bind to the
+			// `impl` rather than to the reference
 			if nested && bounds_str.contains(" + ") {
 				format!("(impl {bounds_str})")
 			} else {